@@ -115,7 +115,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 if let SetExpr::Select(select) = &*query.body {
                     if let Some(expr) = &select.selection {
                         // Try to match key pattern
-                        if let Ok(key) = key_equals().match_pattern(expr) {
+                        if let Ok(key) = key_equals().match_pattern(&expr) {
                             println!("    - Key equals: {}", key);
                         }
                         
@@ -180,24 +180,24 @@ fn extract_table_types_from_select(select: &sqlparser::ast::Select) -> Vec<(Stri
         // Apply the patterns on the table factor
         let table_factor = &table_with_joins.relation;
         
-        if let Ok(table) = string_table().match_pattern(table_factor) {
-            results.push(("String".to_string(), table));
+        if let Ok(table) = string_table().match_pattern(&table_factor) {
+            results.push(("String".to_string(), table.to_string()));
         }
         
-        if let Ok(table) = table_with_suffix("__hash").match_pattern(table_factor) {
-            results.push(("Hash".to_string(), table));
+        if let Ok(table) = table_with_suffix("__hash").match_pattern(&table_factor) {
+            results.push(("Hash".to_string(), table.to_string()));
         }
         
-        if let Ok(table) = table_with_suffix("__list").match_pattern(table_factor) {
-            results.push(("List".to_string(), table));
+        if let Ok(table) = table_with_suffix("__list").match_pattern(&table_factor) {
+            results.push(("List".to_string(), table.to_string()));
         }
         
-        if let Ok(table) = table_with_suffix("__set").match_pattern(table_factor) {
-            results.push(("Set".to_string(), table));
+        if let Ok(table) = table_with_suffix("__set").match_pattern(&table_factor) {
+            results.push(("Set".to_string(), table.to_string()));
         }
         
-        if let Ok(table) = table_with_suffix("__zset").match_pattern(table_factor) {
-            results.push(("Sorted Set".to_string(), table));
+        if let Ok(table) = table_with_suffix("__zset").match_pattern(&table_factor) {
+            results.push(("Sorted Set".to_string(), table.to_string()));
         }
     }
     
@@ -0,0 +1,352 @@
+// acl.rs - Check generated commands against a Redis ACL user
+// Evaluates ACL rules (as in ACL SETUSER or ACL GETUSER) for command, key, and channel access
+
+use std::fmt;
+use thiserror::Error;
+
+use crate::commands::RedisCommand;
+use crate::reply::Reply;
+
+/// A rule string or ACL GETUSER reply that could not be interpreted
+#[derive(Debug, Error)]
+#[error("Invalid ACL rule: {0}")]
+pub struct AclParseError(pub String);
+
+/// One `+`/`-` command rule; rules apply in order, so later rules override earlier ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CommandRule {
+    Category(String, bool),
+    Command(String, bool),
+}
+
+/// A key pattern with the access it grants
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeyPattern {
+    pattern: String,
+    read: bool,
+    write: bool,
+}
+
+/// The permissions of one ACL user (root permissions; selectors are not evaluated)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acl {
+    enabled: bool,
+    commands: Vec<CommandRule>,
+    keys: Vec<KeyPattern>,
+    channels: Vec<String>,
+}
+
+/// A generated command the ACL user would be refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AclViolation {
+    pub command: RedisCommand,
+    /// What the user lacks
+    pub reason: String,
+}
+
+impl fmt::Display for AclViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not permitted: {}", self.command.command, self.reason)
+    }
+}
+
+impl Acl {
+    /// Parse ACL SETUSER rules, e.g. `on >secret ~app:* +@read +@write -@dangerous`.
+    /// A new user starts disabled with no commands, keys, or channels, as in Redis 7.
+    pub fn parse(rules: &str) -> Result<Self, AclParseError> {
+        let mut acl = Self::none();
+        for rule in rules.split_whitespace() {
+            acl.apply(rule)?;
+        }
+        Ok(acl)
+    }
+
+    /// Build from an ACL GETUSER reply (Redis 6 and 7 layouts)
+    pub fn from_getuser(reply: &Reply) -> Result<Self, AclParseError> {
+        let items = match reply {
+            Reply::Array(items) => items,
+            Reply::Nil => return Err(AclParseError("no such user".to_string())),
+            other => return Err(AclParseError(format!("unexpected ACL GETUSER reply: {}", other))),
+        };
+        let mut acl = Self::none();
+        for pair in items.chunks_exact(2) {
+            let field = pair[0].as_text().unwrap_or_default();
+            match (field.as_str(), &pair[1]) {
+                // Flags (on/off, allkeys, allcommands, ...) are rules themselves
+                ("flags", Reply::Array(flags)) => {
+                    for flag in flags.iter().filter_map(Reply::as_text) {
+                        if matches!(flag.as_str(), "on" | "off" | "allkeys" | "allcommands" | "allchannels") {
+                            acl.apply(&flag)?;
+                        }
+                    }
+                }
+                ("commands", value) => {
+                    for rule in value.as_text().unwrap_or_default().split_whitespace() {
+                        acl.apply(rule)?;
+                    }
+                }
+                // Redis 7: "~* %R~logs:*"; Redis 6: an array of bare patterns
+                ("keys", Reply::Array(patterns)) => {
+                    for pattern in patterns.iter().filter_map(Reply::as_text) {
+                        acl.apply(&format!("~{}", pattern))?;
+                    }
+                }
+                ("keys", value) => {
+                    for rule in value.as_text().unwrap_or_default().split_whitespace() {
+                        acl.apply(rule)?;
+                    }
+                }
+                ("channels", Reply::Array(patterns)) => {
+                    for pattern in patterns.iter().filter_map(Reply::as_text) {
+                        acl.apply(&format!("&{}", pattern))?;
+                    }
+                }
+                ("channels", value) => {
+                    for rule in value.as_text().unwrap_or_default().split_whitespace() {
+                        acl.apply(rule)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(acl)
+    }
+
+    fn none() -> Self {
+        Self { enabled: false, commands: Vec::new(), keys: Vec::new(), channels: Vec::new() }
+    }
+
+    fn apply(&mut self, rule: &str) -> Result<(), AclParseError> {
+        let lower = rule.to_lowercase();
+        match lower.as_str() {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "allcommands" => self.commands.push(CommandRule::Category("all".to_string(), true)),
+            "nocommands" => self.commands.push(CommandRule::Category("all".to_string(), false)),
+            "allkeys" => self.keys.push(KeyPattern { pattern: "*".to_string(), read: true, write: true }),
+            "resetkeys" => self.keys.clear(),
+            "allchannels" => self.channels.push("*".to_string()),
+            "resetchannels" => self.channels.clear(),
+            "reset" => *self = Self::none(),
+            // Authentication and payload settings don't affect what a command may touch
+            "nopass" | "resetpass" | "sanitize-payload" | "skip-sanitize-payload" => {}
+            _ if rule.starts_with(['>', '<', '#', '!']) => {}
+            _ if lower.starts_with("+@") || lower.starts_with("-@") => {
+                self.commands.push(CommandRule::Category(lower[2..].to_string(), lower.starts_with('+')));
+            }
+            _ if lower.starts_with('+') || lower.starts_with('-') => {
+                self.commands.push(CommandRule::Command(lower[1..].to_string(), lower.starts_with('+')));
+            }
+            _ if rule.starts_with('~') => {
+                self.keys.push(KeyPattern { pattern: rule[1..].to_string(), read: true, write: true });
+            }
+            _ if rule.starts_with('%') => {
+                let (flags, pattern) = rule[1..].split_once('~')
+                    .ok_or_else(|| AclParseError(rule.to_string()))?;
+                let flags = flags.to_uppercase();
+                if flags.is_empty() || !flags.chars().all(|c| c == 'R' || c == 'W') {
+                    return Err(AclParseError(rule.to_string()));
+                }
+                self.keys.push(KeyPattern { pattern: pattern.to_string(), read: flags.contains('R'), write: flags.contains('W') });
+            }
+            _ if rule.starts_with('&') => self.channels.push(rule[1..].to_string()),
+            _ => return Err(AclParseError(rule.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Whether the user may run the command (with its subcommand, e.g. `config|set`)
+    fn permits_command(&self, command: &RedisCommand) -> bool {
+        let name = command.command.to_lowercase();
+        let full = command.args.first()
+            .filter(|_| has_subcommands(&name))
+            .map(|sub| format!("{}|{}", name, sub.to_lowercase()));
+        let categories = categories(&name);
+        self.commands.iter().fold(false, |allowed, rule| match rule {
+            CommandRule::Category(category, allow) if category == "all" || categories.contains(&category.as_str()) => *allow,
+            CommandRule::Command(rule, allow) if *rule == name || Some(rule) == full.as_ref() => *allow,
+            _ => allowed,
+        })
+    }
+
+    /// Whether a single key pattern grants all the access the command needs on `key`
+    fn permits_key(&self, key: &str, read: bool, write: bool) -> bool {
+        self.keys.iter().any(|p| (p.read || !read) && (p.write || !write) && glob_match(&p.pattern, key))
+    }
+
+    /// PSUBSCRIBE patterns must equal a permitted pattern; other channels are glob-matched
+    fn permits_channel(&self, channel: &str, is_pattern: bool) -> bool {
+        self.channels.iter().any(|p| p == "*" || if is_pattern { p == channel } else { glob_match(p, channel) })
+    }
+
+    /// Check one command; None if the user may run it on its keys and channels
+    pub fn check(&self, command: &RedisCommand) -> Option<AclViolation> {
+        let violation = |reason: String| Some(AclViolation { command: command.clone(), reason });
+        let name = command.command.to_lowercase();
+
+        if !self.enabled {
+            return violation("the user is disabled (off)".to_string());
+        }
+        if !self.permits_command(command) {
+            return violation(format!("the user can't run '{}'", name));
+        }
+        match name.as_str() {
+            "subscribe" | "psubscribe" | "publish" => {
+                let channels = if name == "publish" { &command.args[..command.args.len().min(1)] } else { &command.args[..] };
+                for channel in channels {
+                    if !self.permits_channel(channel, name == "psubscribe") {
+                        return violation(format!("channel '{}' is not permitted", channel));
+                    }
+                }
+            }
+            _ => {
+                let (read, write) = key_access(&name);
+                for key in command.key_indices().into_iter().map(|i| &command.args[i]) {
+                    if !self.permits_key(key, read, write) {
+                        let access = match (read, write) {
+                            (true, true) => "read and write",
+                            (false, true) => "write",
+                            _ => "read",
+                        };
+                        return violation(format!("key '{}' doesn't match a pattern allowing {}", key, access));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Check every command, in order
+pub fn check_commands(acl: &Acl, commands: &[RedisCommand]) -> Vec<AclViolation> {
+    commands.iter().filter_map(|command| acl.check(command)).collect()
+}
+
+fn has_subcommands(name: &str) -> bool {
+    matches!(name, "config" | "client" | "slowlog" | "object" | "script" | "function" | "memory" | "acl")
+}
+
+/// Key permissions a command needs: writes that also return key data (pops, increments,
+/// scripts) need read too, like the ACCESS flag in Redis key specs
+fn key_access(name: &str) -> (bool, bool) {
+    match name {
+        "eval" | "evalsha" | "fcall" | "lpop" | "rpop" | "spop" | "zpopmin" | "zpopmax" | "incr" | "incrby"
+        | "incrbyfloat" | "decr" | "decrby" | "hincrby" | "hincrbyfloat" | "zincrby" => (true, true),
+        _ => (!categories(name).contains(&"write"), categories(name).contains(&"write")),
+    }
+}
+
+/// Check whether a command is in the @write category
+pub(crate) fn is_write(name: &str) -> bool {
+    categories(&name.to_lowercase()).contains(&"write")
+}
+
+/// Whether a command only reads: @read commands, connection and diagnostic queries, and
+/// EVAL scripts whose every `redis.call` is such a read (a command name taken from ARGV is
+/// resolved from the EVAL arguments). EVALSHA and FCALL run scripts that can't be inspected,
+/// so they count as writes, as does any command this crate doesn't know.
+pub fn is_read_only(command: &RedisCommand) -> bool {
+    let name = command.command.to_lowercase();
+    let subcommand = command.args.first().map(|sub| sub.to_lowercase()).unwrap_or_default();
+    match name.as_str() {
+        "eval" => command.args.first().is_some_and(|script| {
+            script_calls(script, command).all(|call| call.is_some_and(|call| is_read_only(&RedisCommand::new(call, Vec::<String>::new()))))
+        }),
+        "select" | "ping" | "echo" | "dbsize" | "info" | "subscribe" | "psubscribe" => true,
+        "client" => matches!(subcommand.as_str(), "list" | "info"),
+        "slowlog" => matches!(subcommand.as_str(), "get" | "len"),
+        "config" => subcommand == "get",
+        _ => categories(&name).contains(&"read"),
+    }
+}
+
+/// The command names an EVAL script passes to `redis.call`/`redis.pcall`, in order;
+/// None for a name that is neither a string literal nor an `ARGV[n]` of the command
+fn script_calls<'a>(script: &'a str, command: &'a RedisCommand) -> impl Iterator<Item = Option<&'a str>> + 'a {
+    let argv_start = 2 + command.args.get(1).and_then(|n| n.parse::<usize>().ok()).unwrap_or(0);
+    script.match_indices("redis.").filter_map(move |(at, prefix)| {
+        let rest = &script[at + prefix.len()..];
+        let name = rest.strip_prefix("call(").or_else(|| rest.strip_prefix("pcall("))?.trim_start();
+        Some(script_call_name(name, command, argv_start))
+    })
+}
+
+/// The command name at the start of a `redis.call` argument list
+fn script_call_name<'a>(name: &'a str, command: &'a RedisCommand, argv_start: usize) -> Option<&'a str> {
+    if let Some(quote) = name.chars().next().filter(|c| *c == '\'' || *c == '"') {
+        return name[1..].split(quote).next();
+    }
+    let n: usize = name.strip_prefix("ARGV[")?.split(']').next()?.trim().parse().ok()?;
+    command.args.get(argv_start + n.checked_sub(1)?).map(String::as_str)
+}
+
+/// ACL categories of the commands this crate generates (plus a few neighbours)
+fn categories(name: &str) -> &'static [&'static str] {
+    match name {
+        "get" | "mget" => &["read", "string", "fast"],
+        "set" | "append" | "incr" | "incrby" | "decr" | "decrby" => &["write", "string"],
+        "incrbyfloat" => &["write", "string", "fast"],
+        "hget" | "hmget" | "hlen" | "hexists" => &["read", "hash", "fast"],
+        "hgetall" | "hkeys" | "hvals" | "hscan" => &["read", "hash", "slow"],
+        "hset" | "hmset" | "hdel" | "hincrby" | "hincrbyfloat" => &["write", "hash", "fast"],
+        "lindex" | "lrange" | "llen" | "lpos" => &["read", "list", "slow"],
+        "rpush" | "lpush" | "rpushx" | "lpushx" | "lpop" | "rpop" => &["write", "list", "fast"],
+        "lset" | "lrem" | "ltrim" | "linsert" => &["write", "list", "slow"],
+        "smembers" | "sismember" | "scard" | "smismember" | "sscan" => &["read", "set"],
+        "sadd" | "srem" | "spop" => &["write", "set", "fast"],
+        "sunionstore" | "sinterstore" | "sdiffstore" => &["write", "set", "slow"],
+        "zrange" | "zrevrange" | "zrangebyscore" | "zrevrangebyscore" | "zcard" | "zcount" | "zscore" | "zscan" => &["read", "sortedset"],
+        "zadd" | "zrem" | "zincrby" | "zpopmin" | "zpopmax" | "zremrangebyscore" | "zrangestore" => &["write", "sortedset"],
+        "json.get" => &["read", "json"],
+        "json.set" | "json.del" => &["write", "json"],
+        "del" | "unlink" => &["keyspace", "write", "slow"],
+        "exists" | "type" | "object" | "ttl" | "touch" => &["keyspace", "read", "fast"],
+        "expire" | "pexpire" | "persist" => &["keyspace", "write", "fast"],
+        "scan" | "keys" => &["keyspace", "read", "slow", "dangerous"],
+        "eval" | "evalsha" | "fcall" | "script" | "function" => &["scripting", "slow"],
+        "eval_ro" | "evalsha_ro" | "fcall_ro" => &["scripting", "slow", "read"],
+        "subscribe" | "psubscribe" | "publish" => &["pubsub", "slow"],
+        "info" | "slowlog" | "config" | "client" => &["admin", "slow", "dangerous"],
+        "ping" | "dbsize" => &["connection", "fast"],
+        _ => &[],
+    }
+}
+
+/// Redis glob matching: `*`, `?`, `[abc]`, `[^a-z]`, and `\` escapes
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_chars(&pattern, &text)
+}
+
+fn glob_match_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| glob_match_chars(&pattern[1..], &text[skip..])),
+        Some('?') => !text.is_empty() && glob_match_chars(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(&c) = text.first() else { return false };
+            let Some(end) = pattern.iter().skip(2).position(|&p| p == ']').map(|i| i + 2) else {
+                return c == '[' && glob_match_chars(&pattern[1..], &text[1..]);
+            };
+            let (negate, class) = match pattern[1] {
+                '^' => (true, &pattern[2..end]),
+                _ => (false, &pattern[1..end]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            matched != negate && glob_match_chars(&pattern[end + 1..], &text[1..])
+        }
+        Some('\\') if pattern.len() > 1 => text.first() == Some(&pattern[1]) && glob_match_chars(&pattern[2..], &text[1..]),
+        Some(&p) => text.first() == Some(&p) && glob_match_chars(&pattern[1..], &text[1..]),
+    }
+}
@@ -0,0 +1,306 @@
+// analysis/capacity.rs - Key counts and memory estimates for INSERT workloads
+// Replays the writes a workload generates into a model of the keyspace, then sizes each key
+// with Redis 7's encodings (listpack, intset, hashtable, skiplist, quicklist) on a 64-bit build
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::commands::RedisCommand;
+
+/// Per key in the main dictionary: dictEntry (24), redisObject (16), and a bucket pointer (8)
+pub const KEY_OVERHEAD: usize = 48;
+/// A hashtable-encoded collection: dict header plus its two tables
+pub const DICT_OVERHEAD: usize = 56;
+/// Per hashtable element: dictEntry (24) and a bucket pointer (8)
+pub const DICT_ENTRY_OVERHEAD: usize = 32;
+/// Per skiplist node (score, backward pointer, ~1.33 levels on average), on top of its dict entry
+pub const SKIPLIST_NODE_OVERHEAD: usize = 40;
+/// listpack header (total bytes, element count) and end marker
+pub const LISTPACK_OVERHEAD: usize = 7;
+/// A quicklist header; each 8 KiB node adds `QUICKLIST_NODE_OVERHEAD` plus its listpack
+pub const QUICKLIST_OVERHEAD: usize = 40;
+pub const QUICKLIST_NODE_OVERHEAD: usize = 32;
+const QUICKLIST_NODE_BYTES: usize = 8192;
+
+/// Default `*-max-listpack-entries` / `-value` and `set-max-intset-entries`
+const LISTPACK_MAX_ENTRIES: usize = 128;
+const LISTPACK_MAX_VALUE: usize = 64;
+const INTSET_MAX_ENTRIES: usize = 512;
+
+/// What a key holds, as far as its size goes
+#[derive(Debug, Clone)]
+enum Stored {
+    String(String),
+    Hash(BTreeMap<String, String>),
+    List(Vec<String>),
+    Set(BTreeSet<String>),
+    /// Member -> score
+    ZSet(BTreeMap<String, String>),
+}
+
+impl Stored {
+    fn kind(&self) -> &'static str {
+        match self {
+            Stored::String(_) => "string",
+            Stored::Hash(_) => "hash",
+            Stored::List(_) => "list",
+            Stored::Set(_) => "set",
+            Stored::ZSet(_) => "zset",
+        }
+    }
+
+    /// Elements and bytes of their values (hash values, list elements, set/zset members)
+    fn values(&self) -> (usize, usize) {
+        match self {
+            Stored::String(value) => (1, value.len()),
+            Stored::Hash(fields) => (fields.len(), fields.values().map(String::len).sum()),
+            Stored::List(items) => (items.len(), items.iter().map(String::len).sum()),
+            Stored::Set(members) => (members.len(), members.iter().map(String::len).sum()),
+            Stored::ZSet(members) => (members.len(), members.keys().map(String::len).sum()),
+        }
+    }
+
+    /// Estimated bytes of the value object, excluding the key
+    fn memory(&self) -> usize {
+        match self {
+            Stored::String(value) if value.parse::<i64>().is_ok() => 0,
+            Stored::String(value) => sds(value),
+            Stored::Hash(fields) if fits_listpack(fields.len(), fields.iter().flat_map(|(f, v)| [f, v])) => {
+                LISTPACK_OVERHEAD + fields.iter().map(|(field, value)| listpack_entry(field) + listpack_entry(value)).sum::<usize>()
+            }
+            Stored::Hash(fields) => {
+                DICT_OVERHEAD + fields.iter().map(|(field, value)| DICT_ENTRY_OVERHEAD + sds(field) + sds(value)).sum::<usize>()
+            }
+            Stored::List(items) => {
+                let bytes: usize = items.iter().map(|item| listpack_entry(item)).sum();
+                let nodes = bytes.div_ceil(QUICKLIST_NODE_BYTES).max(1);
+                QUICKLIST_OVERHEAD + nodes * (QUICKLIST_NODE_OVERHEAD + LISTPACK_OVERHEAD) + bytes
+            }
+            Stored::Set(members) if members.len() <= INTSET_MAX_ENTRIES && members.iter().all(|m| m.parse::<i64>().is_ok()) => {
+                8 + 8 * members.len()
+            }
+            Stored::Set(members) if fits_listpack(members.len(), members.iter()) => {
+                LISTPACK_OVERHEAD + members.iter().map(|member| listpack_entry(member)).sum::<usize>()
+            }
+            Stored::Set(members) => {
+                DICT_OVERHEAD + members.iter().map(|member| DICT_ENTRY_OVERHEAD + sds(member)).sum::<usize>()
+            }
+            Stored::ZSet(members) if fits_listpack(members.len(), members.keys()) => {
+                LISTPACK_OVERHEAD + members.iter().map(|(member, score)| listpack_entry(member) + listpack_entry(score)).sum::<usize>()
+            }
+            Stored::ZSet(members) => {
+                DICT_OVERHEAD + members.keys()
+                    .map(|member| DICT_ENTRY_OVERHEAD + SKIPLIST_NODE_OVERHEAD + sds(member))
+                    .sum::<usize>()
+            }
+        }
+    }
+}
+
+/// An sds string: header, bytes, and terminator
+fn sds(value: &str) -> usize {
+    value.len() + 4
+}
+
+/// A listpack entry: encoding byte(s), bytes, and back-length
+fn listpack_entry(value: &str) -> usize {
+    value.len() + if value.len() < 64 { 2 } else { 4 }
+}
+
+fn fits_listpack<'a>(len: usize, mut values: impl Iterator<Item = &'a String>) -> bool {
+    len <= LISTPACK_MAX_ENTRIES && values.all(|value| value.len() <= LISTPACK_MAX_VALUE)
+}
+
+/// Rows inserted into a table and the keys its writes left behind
+#[derive(Debug, Clone, Default)]
+struct TableUsage {
+    rows: usize,
+    row_bytes: usize,
+    keys: BTreeMap<String, Stored>,
+}
+
+/// A table's estimated footprint, as reported by `CapacityReport::tables`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCapacity {
+    pub table: String,
+    /// Rows inserted, and bytes of their literal values
+    pub rows: usize,
+    pub row_bytes: usize,
+    pub keys: usize,
+    /// Key types, e.g. ["hash"]
+    pub kinds: Vec<&'static str>,
+    /// Values stored (string values, hash values, list elements, set/zset members) and their bytes
+    pub values: usize,
+    pub value_bytes: usize,
+    /// Estimated bytes of keys and values
+    pub memory: usize,
+}
+
+impl TableCapacity {
+    /// Mean size of a stored value, in bytes
+    pub fn avg_value_bytes(&self) -> f64 {
+        if self.values == 0 { 0.0 } else { self.value_bytes as f64 / self.values as f64 }
+    }
+
+    /// Mean size of an inserted row's literal values, in bytes
+    pub fn avg_row_bytes(&self) -> f64 {
+        if self.rows == 0 { 0.0 } else { self.row_bytes as f64 / self.rows as f64 }
+    }
+}
+
+/// The keyspace a workload's writes build, per table
+#[derive(Debug, Clone, Default)]
+pub struct CapacityReport {
+    tables: BTreeMap<String, TableUsage>,
+}
+
+impl CapacityReport {
+    /// Count inserted rows of `table` (the byte size of each row's values)
+    pub fn record_rows(&mut self, table: &str, row_sizes: &[usize]) {
+        let usage = self.tables.entry(table.to_string()).or_default();
+        usage.rows += row_sizes.len();
+        usage.row_bytes += row_sizes.iter().sum::<usize>();
+    }
+
+    /// Apply a write `command` of `table` to the modelled keyspace; reads and writes whose
+    /// result depends on server state (SINTERSTORE, INCR, ...) are ignored
+    pub fn record(&mut self, table: &str, command: &RedisCommand) {
+        let keys = &mut self.tables.entry(table.to_string()).or_default().keys;
+        let args = &command.args;
+        let Some(key) = args.first() else { return };
+        match command.command.to_uppercase().as_str() {
+            "SET" | "GETSET" if args.len() > 1 => {
+                keys.insert(key.clone(), Stored::String(args[1].clone()));
+            }
+            "SETNX" if args.len() > 1 => {
+                keys.entry(key.clone()).or_insert_with(|| Stored::String(args[1].clone()));
+            }
+            "SETEX" | "PSETEX" if args.len() > 2 => {
+                keys.insert(key.clone(), Stored::String(args[2].clone()));
+            }
+            "MSET" | "MSETNX" => {
+                for pair in args.chunks_exact(2) {
+                    keys.insert(pair[0].clone(), Stored::String(pair[1].clone()));
+                }
+            }
+            "HSET" | "HMSET" | "HSETNX" => {
+                let only_new = command.command.eq_ignore_ascii_case("HSETNX");
+                if let Stored::Hash(fields) = entry(keys, key, || Stored::Hash(BTreeMap::new())) {
+                    for pair in args[1..].chunks_exact(2) {
+                        if !(only_new && fields.contains_key(&pair[0])) {
+                            fields.insert(pair[0].clone(), pair[1].clone());
+                        }
+                    }
+                }
+            }
+            "RPUSH" | "LPUSH" | "RPUSHX" | "LPUSHX" => {
+                if let Stored::List(items) = entry(keys, key, || Stored::List(Vec::new())) {
+                    items.extend(args[1..].iter().cloned());
+                }
+            }
+            "SADD" => {
+                if let Stored::Set(members) = entry(keys, key, || Stored::Set(BTreeSet::new())) {
+                    members.extend(args[1..].iter().cloned());
+                }
+            }
+            "ZADD" => {
+                let pairs = args[1..].iter()
+                    .position(|arg| !["NX", "XX", "GT", "LT", "CH", "INCR"].iter().any(|flag| arg.eq_ignore_ascii_case(flag)))
+                    .map_or(&args[args.len()..], |start| &args[1 + start..]);
+                if let Stored::ZSet(members) = entry(keys, key, || Stored::ZSet(BTreeMap::new())) {
+                    for pair in pairs.chunks_exact(2) {
+                        members.insert(pair[1].clone(), pair[0].clone());
+                    }
+                }
+            }
+            "DEL" | "UNLINK" => {
+                for key in args {
+                    keys.remove(key);
+                }
+            }
+            "HDEL" | "SREM" | "ZREM" => {
+                match keys.get_mut(key) {
+                    Some(Stored::Hash(fields)) => args[1..].iter().for_each(|field| { fields.remove(field); }),
+                    Some(Stored::Set(members)) => args[1..].iter().for_each(|member| { members.remove(member); }),
+                    Some(Stored::ZSet(members)) => args[1..].iter().for_each(|member| { members.remove(member); }),
+                    _ => {}
+                }
+                // Redis deletes a collection with its last element
+                if keys.get(key).is_some_and(|stored| stored.values().0 == 0) {
+                    keys.remove(key);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Per-table key counts, value sizes, and memory estimates, by table name
+    pub fn tables(&self) -> Vec<TableCapacity> {
+        self.tables.iter()
+            .map(|(table, usage)| {
+                let kinds: BTreeSet<_> = usage.keys.values().map(Stored::kind).collect();
+                let (values, value_bytes) = usage.keys.values()
+                    .map(Stored::values)
+                    .fold((0, 0), |(n, bytes), (v, b)| (n + v, bytes + b));
+                let memory = usage.keys.iter()
+                    .map(|(key, stored)| KEY_OVERHEAD + sds(key) + stored.memory())
+                    .sum();
+                TableCapacity {
+                    table: table.clone(),
+                    rows: usage.rows,
+                    row_bytes: usage.row_bytes,
+                    keys: usage.keys.len(),
+                    kinds: kinds.into_iter().collect(),
+                    values,
+                    value_bytes,
+                    memory,
+                }
+            })
+            .collect()
+    }
+
+    /// Distinct keys across all tables
+    pub fn keys(&self) -> usize {
+        self.tables.values().map(|usage| usage.keys.len()).sum()
+    }
+
+    /// Estimated bytes of all keys and values
+    pub fn memory(&self) -> usize {
+        self.tables().iter().map(|table| table.memory).sum()
+    }
+}
+
+/// The key's value, replaced by an empty one of the written type if it held another type
+fn entry<'a>(keys: &'a mut BTreeMap<String, Stored>, key: &str, empty: impl Fn() -> Stored) -> &'a mut Stored {
+    let stored = keys.entry(key.to_string()).or_insert_with(&empty);
+    if stored.kind() != empty().kind() {
+        *stored = empty();
+    }
+    stored
+}
+
+/// `bytes` in B, KiB, MiB, or GiB
+fn human(bytes: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", size, units[unit]) }
+}
+
+impl fmt::Display for CapacityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for table in self.tables() {
+            writeln!(
+                f,
+                "{}: {} rows (avg {:.1} bytes), {} {} keys, {} values (avg {:.1} bytes), ~{}",
+                table.table, table.rows, table.avg_row_bytes(), table.keys, table.kinds.join("/"),
+                table.values, table.avg_value_bytes(), human(table.memory),
+            )?;
+        }
+        write!(f, "total: {} keys, ~{} (estimate; excludes allocator overhead and expiry metadata)", self.keys(), human(self.memory()))
+    }
+}
@@ -0,0 +1,152 @@
+// analysis/equivalence.rs - Cross-checks generated commands against their statement
+// Re-derives what the commands do (read or write, which key, which hash fields and values)
+// and compares it with the SQL, catching templates that drift from their matchers
+
+use std::fmt;
+
+use sqlparser::ast::Statement;
+
+use crate::acl;
+use crate::ast;
+use crate::commands::RedisCommand;
+use crate::types::{redis_type_of, RedisDataType, TypeMapping};
+
+/// A difference between what a statement asks for and what its commands do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquivalenceIssue {
+    /// The command at fault, or None when the commands as a whole miss an effect
+    pub command: Option<RedisCommand>,
+    pub reason: String,
+}
+
+impl EquivalenceIssue {
+    fn new(command: Option<&RedisCommand>, reason: String) -> Self {
+        Self { command: command.cloned(), reason }
+    }
+}
+
+impl fmt::Display for EquivalenceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.command {
+            Some(command) => write!(f, "{}: {}", command.to_quoted_string(), self.reason),
+            None => f.write_str(&self.reason),
+        }
+    }
+}
+
+/// What a statement should do, as read from its AST
+struct Effect {
+    writes: bool,
+    key: Option<String>,
+    hash: bool,
+    /// Fields read (no value) or written (with the value), `key` excluded
+    fields: Vec<(String, Option<String>)>,
+}
+
+/// Check that `commands`, as rendered for `stmt` (before key prefixes, hash tags, or
+/// database selection are applied), have the statement's effect: SELECTs only read and
+/// writes write, a `key = ...` predicate or inserted key is among the keys addressed, and
+/// HGET/HMGET read and HSET/SET write the columns the SQL names, with their values.
+/// Statements other than SELECT, INSERT, UPDATE, and DELETE pass unchecked.
+pub fn check_equivalence(stmt: &Statement, commands: &[RedisCommand]) -> Vec<EquivalenceIssue> {
+    let Some(effect) = statement_effect(stmt) else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    // A SELECT preamble chooses the database; it is neither the read nor the write
+    let commands: Vec<&RedisCommand> = commands.iter().filter(|command| !command.command.eq_ignore_ascii_case("SELECT")).collect();
+
+    match effect.writes {
+        true if !commands.is_empty() && commands.iter().all(|command| acl::is_read_only(command)) => {
+            issues.push(EquivalenceIssue::new(None, "the statement writes, but every command only reads".to_string()));
+        }
+        false => issues.extend(commands.iter()
+            .filter(|command| !acl::is_read_only(command))
+            .map(|command| EquivalenceIssue::new(Some(command), "a SELECT runs a write".to_string()))),
+        true => {}
+    }
+
+    let addressed: Vec<&str> = commands.iter()
+        .flat_map(|command| command.key_indices().into_iter().map(|i| command.args[i].as_str()))
+        .collect();
+    if let Some(key) = effect.key.as_deref().filter(|key| !addressed.is_empty() && !addressed.contains(key)) {
+        issues.push(EquivalenceIssue::new(None, format!("the commands address {}, not the key '{}'", addressed.join(", "), key)));
+    }
+
+    let on_key = |command: &&&RedisCommand| match &effect.key {
+        Some(key) => command.args.first() == Some(key),
+        None => true,
+    };
+    for command in commands.iter().filter(on_key) {
+        match command.command.to_uppercase().as_str() {
+            "HGET" | "HMGET" if effect.hash && !effect.writes && !effect.fields.is_empty() => {
+                let read = &command.args[1..];
+                let missing: Vec<&str> = effect.fields.iter().map(|(field, _)| field.as_str()).filter(|field| !read.iter().any(|arg| arg == field)).collect();
+                if !missing.is_empty() {
+                    issues.push(EquivalenceIssue::new(Some(command), format!("doesn't read {}", missing.join(", "))));
+                }
+            }
+            "HSET" | "HMSET" if effect.hash && effect.writes => {
+                let pairs: Vec<&[String]> = command.args[1..].chunks(2).collect();
+                for (field, value) in &effect.fields {
+                    let written = pairs.iter().find(|pair| pair[0] == *field).and_then(|pair| pair.get(1));
+                    match (written, value) {
+                        (None, _) => issues.push(EquivalenceIssue::new(Some(command), format!("doesn't write the field {}", field))),
+                        (Some(written), Some(value)) if written != value => issues.push(EquivalenceIssue::new(
+                            Some(command),
+                            format!("writes {} = '{}', not '{}'", field, written, value),
+                        )),
+                        _ => {}
+                    }
+                }
+            }
+            "SET" if !effect.hash && effect.writes => {
+                let expected = effect.fields.iter().find(|(field, _)| field == "value").and_then(|(_, value)| value.as_deref());
+                if let Some(value) = expected.filter(|value| command.args.get(1).map(String::as_str) != Some(value)) {
+                    issues.push(EquivalenceIssue::new(Some(command), format!("writes '{}', not '{}'", command.args.get(1).map_or("", String::as_str), value)));
+                }
+            }
+            _ => {}
+        }
+    }
+    issues
+}
+
+fn statement_effect(stmt: &Statement) -> Option<Effect> {
+    let hash = ast::stmt_get_table_name(stmt).is_some_and(|table| redis_type_of(&table, TypeMapping::standard()) == RedisDataType::Hash);
+    let without_key = |fields: Vec<(String, Option<String>)>| fields.into_iter().filter(|(field, _)| field != "key").collect();
+    let effect = match stmt {
+        Statement::Query(query) => {
+            let select = ast::sel_get_select(query)?;
+            let fields = match select.projection.iter().any(ast::sel_is_wildcard) {
+                true => Vec::new(),
+                false => ast::sel_get_projected_columns(&select.projection)
+                    .map(|columns| columns.into_iter().map(|(field, _)| field).collect())
+                    .unwrap_or_else(|| ast::sel_get_field_names(&select.projection))
+                    .into_iter()
+                    .map(|field| (field, None))
+                    .collect(),
+            };
+            Effect { writes: false, key: ast::sel_get_key_value(&select.selection), hash, fields: without_key(fields) }
+        }
+        Statement::Insert(_) => {
+            let rows = ast::ins_get_values_as_maps(stmt)?;
+            // Multi-row inserts spread over several keys and commands
+            let row = match <[_; 1]>::try_from(rows) {
+                Ok([row]) => row,
+                Err(_) => Default::default(),
+            };
+            let mut fields: Vec<(String, Option<String>)> = row.iter().map(|(field, value)| (field.clone(), Some(value.clone()))).collect();
+            fields.sort();
+            Effect { writes: true, key: row.get("key").cloned(), hash, fields: without_key(fields) }
+        }
+        Statement::Update { .. } => {
+            let assignments = ast::upd_get_assignments(stmt).unwrap_or_default();
+            let fields = assignments.into_iter().map(|(field, value)| (field, Some(value))).collect();
+            Effect { writes: true, key: ast::upd_get_key_value(stmt), hash, fields: without_key(fields) }
+        }
+        Statement::Delete(_) => Effect { writes: true, key: ast::get_key_value(stmt), hash, fields: Vec::new() },
+        _ => return None,
+    };
+    Some(effect)
+}
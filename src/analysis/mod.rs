@@ -0,0 +1,97 @@
+// analysis/mod.rs - Static checks over generated commands
+// Flags commands whose effect compounds when a script is re-run after a partial failure;
+// `capacity` estimates the keys and memory a workload's writes leave behind, and `equivalence`
+// checks commands do what their statement asks
+
+use std::fmt;
+
+use crate::commands::RedisCommand;
+
+pub mod capacity;
+pub mod equivalence;
+
+pub use capacity::{CapacityReport, TableCapacity};
+pub use equivalence::{check_equivalence, EquivalenceIssue};
+
+/// A generated command that changes data again each time it is re-applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyIssue {
+    pub command: RedisCommand,
+    /// Why re-running the command is unsafe
+    pub reason: String,
+    /// How to make the write safe to repeat
+    pub suggestion: String,
+}
+
+impl IdempotencyIssue {
+    fn new(command: &RedisCommand, reason: &str, suggestion: &str) -> Self {
+        Self {
+            command: command.clone(),
+            reason: reason.to_string(),
+            suggestion: suggestion.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for IdempotencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not idempotent: {}\n  suggestion: {}", self.command.command, self.reason, self.suggestion)
+    }
+}
+
+/// Check whether re-running `command` would change data again.
+/// SET, HSET, SADD, ZADD (without INCR), DEL, and the like converge and pass.
+pub fn check_idempotency(command: &RedisCommand) -> Option<IdempotencyIssue> {
+    let has_flag = |flag: &str| command.args.iter().any(|arg| arg.eq_ignore_ascii_case(flag));
+
+    match command.command.to_uppercase().as_str() {
+        "RPUSH" | "LPUSH" | "RPUSHX" | "LPUSHX" | "LINSERT" => Some(IdempotencyIssue::new(
+            command,
+            "each run adds the elements again",
+            "guard the push with a Lua script that checks the list (LPOS) first, or rebuild the list with DEL + RPUSH in one MULTI",
+        )),
+        "INCR" | "INCRBY" | "INCRBYFLOAT" | "DECR" | "DECRBY" | "HINCRBY" | "HINCRBYFLOAT" | "ZINCRBY" => Some(IdempotencyIssue::new(
+            command,
+            "each run adds the increment again",
+            "write the computed value with SET/HSET/ZADD, or record completion with SETNX on a marker key",
+        )),
+        "ZADD" if has_flag("INCR") => Some(IdempotencyIssue::new(
+            command,
+            "ZADD INCR adds to the score on each run",
+            "ZADD the final score without INCR",
+        )),
+        "APPEND" => Some(IdempotencyIssue::new(
+            command,
+            "each run appends the value again",
+            "SET the complete value instead",
+        )),
+        "LPOP" | "RPOP" | "SPOP" | "ZPOPMIN" | "ZPOPMAX" => Some(IdempotencyIssue::new(
+            command,
+            "each run removes different elements",
+            "read the elements first and remove those exact members (LREM/SREM/ZREM)",
+        )),
+        "LTRIM" if trims_head(command) => Some(IdempotencyIssue::new(
+            command,
+            "trimming from a positive start drops more elements on each run",
+            "trim to bounds anchored at the head or tail, e.g. LTRIM key 0 N or LTRIM key -N -1",
+        )),
+        "EVAL" | "EVALSHA" | "FCALL" => Some(IdempotencyIssue::new(
+            command,
+            "script effects cannot be checked statically",
+            "review the script, or guard it with SETNX on a marker key",
+        )),
+        _ => None,
+    }
+}
+
+/// Check every command, in order
+pub fn check_commands(commands: &[RedisCommand]) -> Vec<IdempotencyIssue> {
+    commands.iter().filter_map(check_idempotency).collect()
+}
+
+/// LTRIM with a positive start drops the head again on every run (the survivors shift down)
+fn trims_head(command: &RedisCommand) -> bool {
+    command.args.get(1)
+        .and_then(|start| start.parse::<i64>().ok())
+        .is_some_and(|start| start > 0)
+}
@@ -0,0 +1,58 @@
+// annotations.rs - SQL comments carried through to generated output
+// `-- owner: billing-team` lines directly above a statement (and a trailing `-- note` on it)
+// stay with its commands: `#` lines in text output, an `annotations` field in JSON records
+
+/// A statement with the comments that annotate it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotated {
+    /// 1-based line number of the statement in the input
+    pub line: usize,
+    /// The statement line as written (a trailing comment is left in place)
+    pub sql: String,
+    /// Comment texts without the `--`, in source order
+    pub notes: Vec<String>,
+}
+
+impl Annotated {
+    /// The notes as `# note` lines, each ending in a newline (empty without notes)
+    pub fn comment_lines(&self) -> String {
+        self.notes.iter().map(|note| format!("# {}\n", note)).collect()
+    }
+}
+
+/// Pair each statement line with the comment lines right above it and its trailing comment.
+/// A blank line detaches the comments above it; comments after the last statement are dropped.
+pub fn annotate<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<Annotated> {
+    let mut statements = Vec::new();
+    let mut notes = Vec::new();
+    for (number, line) in lines.into_iter().map(str::trim).enumerate() {
+        if line.is_empty() {
+            notes.clear();
+        } else if let Some(note) = line.strip_prefix("--") {
+            notes.push(note.trim().to_string());
+        } else {
+            notes.extend(trailing_comment(line).map(str::to_string));
+            statements.push(Annotated { line: number + 1, sql: line.to_string(), notes: std::mem::take(&mut notes) });
+        }
+    }
+    statements
+}
+
+/// The text of a `-- comment` ending the line, outside string literals and quoted identifiers
+pub fn trailing_comment(line: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '-') if chars.peek().is_some_and(|&(_, next)| next == '-') => {
+                let note = line[i + 2..].trim();
+                return (!note.is_empty()).then_some(note);
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,141 @@
+// ast/alias.rs - Table alias resolution for single-table statements
+// Strips `u.` / `users__hash.` qualifiers from column references, so
+// `SELECT * FROM users__hash u WHERE u.key = 'user:1'` reaches the matchers as `key = 'user:1'`
+
+use sqlparser::ast::{
+    AssignmentTarget, Expr, FromTable, FunctionArguments, FunctionArg, FunctionArgExpr, Ident, ObjectName,
+    ObjectNamePart, OrderByExpr, OrderByKind, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins,
+};
+
+/// Resolve qualified column references (`alias.column`, `table.column`, `alias.*`) of a
+/// SELECT, UPDATE, or DELETE on a single table to plain ones. Joins are left alone.
+pub fn resolve_aliases(stmt: &mut Statement) {
+    match stmt {
+        Statement::Query(query) => {
+            let SetExpr::Select(select) = query.body.as_mut() else { return };
+            let [from] = select.from.as_slice() else { return };
+            let Some(qualifiers) = qualifiers(from) else { return };
+            for item in &mut select.projection {
+                match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => resolve_expr(expr, &qualifiers),
+                    SelectItem::QualifiedWildcard(_, options) => *item = SelectItem::Wildcard(options.clone()),
+                    SelectItem::Wildcard(_) => {}
+                }
+            }
+            resolve_opt(&mut select.selection, &qualifiers);
+            if let Some(order_by) = &mut query.order_by {
+                if let OrderByKind::Expressions(exprs) = &mut order_by.kind {
+                    resolve_order_by(exprs, &qualifiers);
+                }
+            }
+        }
+        Statement::Update { table, assignments, selection, .. } => {
+            let Some(qualifiers) = qualifiers(table) else { return };
+            for assignment in assignments {
+                match &mut assignment.target {
+                    AssignmentTarget::ColumnName(name) => resolve_name(name, &qualifiers),
+                    AssignmentTarget::Tuple(names) => names.iter_mut().for_each(|name| resolve_name(name, &qualifiers)),
+                }
+                resolve_expr(&mut assignment.value, &qualifiers);
+            }
+            resolve_opt(selection, &qualifiers);
+        }
+        Statement::Delete(delete) => {
+            let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) = &delete.from;
+            let [from] = tables.as_slice() else { return };
+            let Some(qualifiers) = qualifiers(from) else { return };
+            resolve_opt(&mut delete.selection, &qualifiers);
+            resolve_order_by(&mut delete.order_by, &qualifiers);
+        }
+        _ => {}
+    }
+}
+
+/// Names a column of the table may be qualified with: its alias and its (unqualified) name
+fn qualifiers(from: &TableWithJoins) -> Option<Vec<String>> {
+    if !from.joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, alias, .. } = &from.relation else { return None };
+    let mut qualifiers: Vec<String> = name.0.last().and_then(ObjectNamePart::as_ident).map(|ident| ident.value.to_lowercase()).into_iter().collect();
+    qualifiers.extend(alias.as_ref().map(|alias| alias.name.value.to_lowercase()));
+    Some(qualifiers)
+}
+
+fn qualifies(ident: &Ident, qualifiers: &[String]) -> bool {
+    qualifiers.contains(&ident.value.to_lowercase())
+}
+
+fn resolve_opt(expr: &mut Option<Expr>, qualifiers: &[String]) {
+    if let Some(expr) = expr {
+        resolve_expr(expr, qualifiers);
+    }
+}
+
+fn resolve_order_by(exprs: &mut [OrderByExpr], qualifiers: &[String]) {
+    for order_by in exprs {
+        resolve_expr(&mut order_by.expr, qualifiers);
+    }
+}
+
+/// `u.name` as an assignment target becomes `name`
+fn resolve_name(name: &mut ObjectName, qualifiers: &[String]) {
+    if let [ObjectNamePart::Identifier(qualifier), _] = name.0.as_slice() {
+        if qualifies(qualifier, qualifiers) {
+            name.0.remove(0);
+        }
+    }
+}
+
+fn resolve_expr(expr: &mut Expr, qualifiers: &[String]) {
+    match expr {
+        Expr::CompoundIdentifier(idents) => {
+            if let [qualifier, column] = idents.as_slice() {
+                if qualifies(qualifier, qualifiers) {
+                    *expr = Expr::Identifier(column.clone());
+                }
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            resolve_expr(left, qualifiers);
+            resolve_expr(right, qualifiers);
+        }
+        Expr::Between { expr: inner, low, high, .. } => {
+            resolve_expr(inner, qualifiers);
+            resolve_expr(low, qualifiers);
+            resolve_expr(high, qualifiers);
+        }
+        Expr::InList { expr: inner, list, .. } => {
+            resolve_expr(inner, qualifiers);
+            list.iter_mut().for_each(|item| resolve_expr(item, qualifiers));
+        }
+        Expr::Like { expr: inner, pattern, .. } | Expr::ILike { expr: inner, pattern, .. } => {
+            resolve_expr(inner, qualifiers);
+            resolve_expr(pattern, qualifiers);
+        }
+        Expr::Nested(inner) | Expr::UnaryOp { expr: inner, .. } | Expr::IsNull(inner) | Expr::IsNotNull(inner)
+        | Expr::Cast { expr: inner, .. } => resolve_expr(inner, qualifiers),
+        Expr::Case { operand, conditions, else_result } => {
+            if let Some(operand) = operand {
+                resolve_expr(operand, qualifiers);
+            }
+            for when in conditions {
+                resolve_expr(&mut when.condition, qualifiers);
+                resolve_expr(&mut when.result, qualifiers);
+            }
+            if let Some(else_result) = else_result {
+                resolve_expr(else_result, qualifiers);
+            }
+        }
+        Expr::Function(function) => {
+            if let FunctionArguments::List(list) = &mut function.args {
+                for arg in &mut list.args {
+                    if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) | FunctionArg::Named { arg: FunctionArgExpr::Expr(arg), .. } = arg {
+                        resolve_expr(arg, qualifiers);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
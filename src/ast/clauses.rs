@@ -0,0 +1,230 @@
+// ast/clauses.rs - Pure functions enumerating the clauses a statement uses
+// Used to detect SQL semantics a matched rule would silently drop
+
+use sqlparser::ast::{BinaryOperator, Expr, GroupByExpr, Query, SelectItem, SetExpr, Spanned, Statement, UnaryOperator};
+use sqlparser::tokenizer::Span;
+use std::fmt;
+
+/// A kind of SQL clause a rule may or may not honor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+    /// A top-level WHERE conjunct on the named column ("" if not a single column), by how
+    /// it constrains the column
+    Predicate(String, PredicateKind),
+    /// A top-level WHERE `column IN (SELECT ...)` conjunct on the named column
+    SubqueryPredicate(String),
+    /// An explicit (non-wildcard) projection list
+    Projection,
+    Distinct,
+    GroupBy,
+    Having,
+    OrderBy,
+    Limit,
+    Offset,
+    Returning,
+}
+
+/// How a WHERE conjunct constrains its column. Rules honor a column's predicates of the
+/// kinds they declare, so a score range rule doesn't also claim `score IN (...)` or `score <> n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredicateKind {
+    /// `column = v`
+    Equals,
+    /// `column IN (v1, v2, ...)`
+    In,
+    /// `column <, <=, >, >= n` (the value on either side) or `column BETWEEN a AND b`
+    Range,
+    /// `column LIKE p`
+    Like,
+    /// Anything else: `<>`, `NOT IN`, `NOT LIKE`, `IS NULL`, ...
+    Other,
+}
+
+impl PredicateKind {
+    /// Classify a WHERE conjunct
+    pub fn of(expr: &Expr) -> Self {
+        match expr {
+            Expr::Nested(inner) => Self::of(inner),
+            Expr::BinaryOp { op: BinaryOperator::Eq, .. } => PredicateKind::Equals,
+            Expr::BinaryOp { op: BinaryOperator::Lt | BinaryOperator::LtEq | BinaryOperator::Gt | BinaryOperator::GtEq, .. }
+            | Expr::Between { negated: false, .. } => PredicateKind::Range,
+            Expr::InList { negated: false, .. } => PredicateKind::In,
+            Expr::Like { negated: false, .. } => PredicateKind::Like,
+            _ => PredicateKind::Other,
+        }
+    }
+}
+
+/// A clause as it occurs in a statement, with its SQL text and source span
+/// (`Span::empty()` when the parser records no location for it)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClauseUse {
+    pub clause: Clause,
+    pub sql: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ClauseUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sql)
+    }
+}
+
+fn clause_use(clause: Clause, sql: String, span: Span) -> ClauseUse {
+    ClauseUse { clause, sql, span }
+}
+
+/// Split a WHERE expression into its top-level AND conjuncts
+pub fn split_conjuncts(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp { left, op: BinaryOperator::And, right } => {
+            let mut conjuncts = split_conjuncts(left);
+            conjuncts.extend(split_conjuncts(right));
+            conjuncts
+        },
+        Expr::Nested(inner) if matches!(**inner, Expr::BinaryOp { op: BinaryOperator::And, .. }) => {
+            split_conjuncts(inner)
+        },
+        _ => vec![expr],
+    }
+}
+
+/// Get the single column a predicate constrains, lowercased
+pub fn predicate_column(expr: &Expr) -> Option<String> {
+    let column = match expr {
+        Expr::BinaryOp { left, right, .. } => match (&**left, &**right) {
+            (Expr::Identifier(ident), _) | (_, Expr::Identifier(ident)) => ident,
+            _ => return None,
+        },
+        Expr::Between { expr, .. }
+        | Expr::InList { expr, .. }
+        | Expr::Like { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => match &**expr {
+            Expr::Identifier(ident) => ident,
+            _ => return None,
+        },
+        Expr::Nested(inner) => return predicate_column(inner),
+        _ => return None,
+    };
+    Some(column.value.to_lowercase())
+}
+
+fn predicates(selection: &Option<Expr>) -> Vec<ClauseUse> {
+    selection.iter()
+        .flat_map(split_conjuncts)
+        .map(|expr| {
+            let clause = match expr {
+                Expr::InSubquery { expr: column, negated: false, .. } => match &**column {
+                    Expr::Identifier(ident) => Clause::SubqueryPredicate(ident.value.to_lowercase()),
+                    _ => Clause::Predicate(String::new(), PredicateKind::Other),
+                },
+                _ => Clause::Predicate(predicate_column(expr).unwrap_or_default(), PredicateKind::of(expr)),
+            };
+            clause_use(clause, format!("WHERE {}", expr), expr.span())
+        })
+        .collect()
+}
+
+/// Find a top-level WHERE conjunct excluding keys (`key != v`, `key <> v`, `key NOT IN (...)`,
+/// `key NOT LIKE p`, `NOT (key = v)`) in a SELECT, UPDATE, or DELETE
+pub fn negative_key_predicate(stmt: &Statement) -> Option<ClauseUse> {
+    let selection = match stmt {
+        Statement::Query(query) => match query.body.as_ref() {
+            SetExpr::Select(select) => select.selection.as_ref(),
+            _ => None,
+        },
+        Statement::Update { selection, .. } => selection.as_ref(),
+        Statement::Delete(delete) => delete.selection.as_ref(),
+        _ => None,
+    }?;
+    split_conjuncts(selection)
+        .into_iter()
+        .find(|expr| is_negative(expr) && predicate_column(strip_not(expr)).as_deref() == Some("key"))
+        .map(|expr| clause_use(Clause::Predicate("key".to_string(), PredicateKind::of(expr)), format!("WHERE {}", expr), expr.span()))
+}
+
+fn is_negative(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryOp { op: BinaryOperator::NotEq, .. } => true,
+        Expr::InList { negated, .. } | Expr::Like { negated, .. } => *negated,
+        Expr::UnaryOp { op: UnaryOperator::Not, .. } => true,
+        Expr::Nested(inner) => is_negative(inner),
+        _ => false,
+    }
+}
+
+fn strip_not(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::UnaryOp { op: UnaryOperator::Not, expr } => expr,
+        _ => expr,
+    }
+}
+
+/// List the clauses used by a query (a SELECT, or the source of an INSERT ... SELECT)
+pub fn query_get_clauses(query: &Query) -> Vec<ClauseUse> {
+    let mut clauses = Vec::new();
+    if let Some(select) = crate::ast::sel_get_select(query) {
+        let is_wildcard = matches!(select.projection.as_slice(), [SelectItem::Wildcard(_)]);
+        if !is_wildcard {
+            let items: Vec<String> = select.projection.iter().map(|item| item.to_string()).collect();
+            let span = Span::union_iter(select.projection.iter().map(|item| item.span()));
+            clauses.push(clause_use(Clause::Projection, format!("SELECT {}", items.join(", ")), span));
+        }
+        if let Some(distinct) = &select.distinct {
+            clauses.push(clause_use(Clause::Distinct, distinct.to_string(), Span::empty()));
+        }
+        clauses.extend(predicates(&select.selection));
+        let has_group_by = match &select.group_by {
+            GroupByExpr::All(_) => true,
+            GroupByExpr::Expressions(exprs, _) => !exprs.is_empty(),
+        };
+        if has_group_by {
+            clauses.push(clause_use(Clause::GroupBy, select.group_by.to_string(), select.group_by.span()));
+        }
+        if let Some(having) = &select.having {
+            clauses.push(clause_use(Clause::Having, format!("HAVING {}", having), having.span()));
+        }
+    }
+    if let Some(order_by) = &query.order_by {
+        clauses.push(clause_use(Clause::OrderBy, order_by.to_string(), order_by.span()));
+    }
+    if let Some(limit) = &query.limit {
+        clauses.push(clause_use(Clause::Limit, format!("LIMIT {}", limit), limit.span()));
+    }
+    if let Some(offset) = &query.offset {
+        clauses.push(clause_use(Clause::Offset, offset.to_string(), offset.span()));
+    }
+    clauses
+}
+
+/// List the clauses used by a SELECT, UPDATE, or DELETE statement.
+/// Table names, SET assignments, and INSERT values are not listed.
+pub fn stmt_get_clauses(stmt: &Statement) -> Vec<ClauseUse> {
+    let mut clauses = Vec::new();
+    match stmt {
+        Statement::Query(query) => clauses = query_get_clauses(query),
+        Statement::Update { selection, returning, .. } => {
+            clauses.extend(predicates(selection));
+            if returning.is_some() {
+                clauses.push(clause_use(Clause::Returning, "RETURNING".to_string(), Span::empty()));
+            }
+        },
+        Statement::Delete(delete) => {
+            clauses.extend(predicates(&delete.selection));
+            if !delete.order_by.is_empty() {
+                let items: Vec<String> = delete.order_by.iter().map(|item| item.to_string()).collect();
+                let span = Span::union_iter(delete.order_by.iter().map(|item| item.span()));
+                clauses.push(clause_use(Clause::OrderBy, format!("ORDER BY {}", items.join(", ")), span));
+            }
+            if let Some(limit) = &delete.limit {
+                clauses.push(clause_use(Clause::Limit, format!("LIMIT {}", limit), limit.span()));
+            }
+            if delete.returning.is_some() {
+                clauses.push(clause_use(Clause::Returning, "RETURNING".to_string(), Span::empty()));
+            }
+        },
+        _ => {},
+    }
+    clauses
+}
@@ -1,30 +1,24 @@
 // ast/delete.rs - Pure functions for DELETE AST node extraction
 // These functions don't modify state, just extract information from DELETE statements
 
-use sqlparser::ast::{Expr, FromTable, ObjectNamePart, Statement, TableFactor, Value};
+use sqlparser::ast::{BinaryOperator, Expr, FromTable, Statement, Value};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Location, Token, Tokenizer};
 use std::collections::HashMap;
 
 /// Get the table name from a DELETE statement
 pub fn get_table_name(stmt: &Statement) -> Option<String> {
+    get_table_name_ref(stmt).map(str::to_string)
+}
+
+/// Borrowing form of `get_table_name`, for matchers that only inspect the name
+pub fn get_table_name_ref(stmt: &Statement) -> Option<&str> {
     match stmt {
         Statement::Delete(delete) => {
             match &delete.from {
                 FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => {
-                    if tables.is_empty() {
-                        return None;
-                    }
-                    match &tables[0].relation {
-                        TableFactor::Table { name, .. } => {
-                            if !name.0.is_empty() {
-                                match &name.0[0] {
-                                    ObjectNamePart::Identifier(ident) => Some(ident.value.clone())
-                                }
-                            } else {
-                                None
-                            }
-                        },
-                        _ => None
-                    }
+                    super::sel_get_relation_name(&tables.first()?.relation)
                 }
             }
         },
@@ -33,26 +27,40 @@ pub fn get_table_name(stmt: &Statement) -> Option<String> {
 }
 
 pub fn get_key_value(stmt: &Statement) -> Option<String> {
+    get_key_value_ref(stmt).map(str::to_string)
+}
+
+/// Borrowing form of `get_key_value`
+pub fn get_key_value_ref(stmt: &Statement) -> Option<&str> {
     match stmt {
         Statement::Delete(delete) => {
-            delete.selection.as_ref().and_then(|expr| extract_key_value(expr))
+            delete.selection.as_ref().and_then(extract_key_value)
         }
         _ => None,
     }
 }
 
+/// Check if the WHERE clause is `key = <value>` alone, with no other conjunct
+pub fn has_only_key_equals(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Delete(delete) => delete.selection.as_ref().is_some_and(|selection| {
+            matches!(super::split_conjuncts(selection).as_slice(), [conjunct] if extract_key_value(conjunct).is_some())
+        }),
+        _ => false,
+    }
+}
 
 /// Recursively search for `key = <value>` in the WHERE clause
-fn extract_key_value(expr: &Expr) -> Option<String> {
+fn extract_key_value(expr: &Expr) -> Option<&str> {
     match expr {
         Expr::BinaryOp { left, op, right } => {
             if *op == sqlparser::ast::BinaryOperator::Eq {
                 match &**left {
-                    Expr::Identifier(ident) if ident.value.to_lowercase() == "key" => {
+                    Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key") => {
                         match &**right {
                             Expr::Value(value_with_span) => match &value_with_span.value {
-                                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s.clone()),
-                                Value::Number(n, _) => Some(n.clone()),
+                                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s),
+                                Value::Number(n, _) => Some(n),
                                 _ => None,
                             },
                             _ => None,
@@ -72,6 +80,110 @@ fn extract_key_value(expr: &Expr) -> Option<String> {
 }
 
 
+/// Get the sort direction of `ORDER BY score [ASC|DESC]`: Some(true) for descending.
+/// Only a single score ordering is recognized.
+pub fn get_order_by_score(stmt: &Statement) -> Option<bool> {
+    match stmt {
+        Statement::Delete(delete) => match delete.order_by.as_slice() {
+            [order_expr] => match &order_expr.expr {
+                Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("score") => {
+                    Some(order_expr.options.asc == Some(false))
+                },
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the numeric LIMIT of a DELETE statement
+pub fn get_limit(stmt: &Statement) -> Option<u64> {
+    match stmt {
+        Statement::Delete(delete) => match &delete.limit {
+            Some(Expr::Value(value_with_span)) => match &value_with_span.value {
+                Value::Number(n, _) => n.parse::<u64>().ok(),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Rewrite `DELETE ... ORDER BY ... LIMIT n RETURNING cols` (MariaDB order) into
+/// `DELETE ... RETURNING cols ORDER BY ... LIMIT n`, the clause order the parser accepts.
+/// Returns None if the statement is not a DELETE with a trailing RETURNING.
+pub fn move_returning_before_order(sql: &str) -> Option<String> {
+    let tokens = Tokenizer::new(&GenericDialect {}, sql).tokenize_with_location().ok()?;
+    let keyword_at = |keyword: Keyword| {
+        let mut depth = 0i32;
+        tokens.iter().find(|t| {
+            match &t.token {
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                Token::Word(word) => return depth == 0 && word.keyword == keyword,
+                _ => {}
+            }
+            false
+        })
+    };
+    
+    let first_word = tokens.iter().find_map(|t| match &t.token {
+        Token::Word(word) => Some(word.keyword),
+        _ => None,
+    })?;
+    if first_word != Keyword::DELETE {
+        return None;
+    }
+    
+    let returning = keyword_at(Keyword::RETURNING)?;
+    let tail_start = [keyword_at(Keyword::ORDER), keyword_at(Keyword::LIMIT)].into_iter()
+        .flatten()
+        .map(|t| t.span.start)
+        .min()?;
+    if returning.span.start < tail_start {
+        return None;
+    }
+    
+    let tail = byte_offset(sql, tail_start)?;
+    let returning = byte_offset(sql, returning.span.start)?;
+    let body = sql[..tail].trim_end();
+    let order_limit = sql[tail..returning].trim_end();
+    let returning_clause = sql[returning..].trim_end().trim_end_matches(';').trim_end();
+    Some(format!("{} {} {}", body, returning_clause, order_limit))
+}
+
+/// Convert a 1-based line/column location into a byte offset
+fn byte_offset(sql: &str, location: Location) -> Option<usize> {
+    let mut offset = 0;
+    for (index, line) in sql.split_inclusive('\n').enumerate() {
+        if index + 1 == location.line as usize {
+            let column = (location.column as usize).checked_sub(1)?;
+            return line.char_indices().nth(column).map(|(i, _)| offset + i);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Get the score bounds of `score <op> n` or `score BETWEEN a AND b` as ZRANGEBYSCORE-style
+/// (min, max) strings, e.g. `score < 100` gives ("-inf", "(100")
+pub fn get_score_range(stmt: &Statement) -> Option<(String, String)> {
+    match stmt {
+        Statement::Delete(delete) => super::sel_get_score_range(&delete.selection),
+        _ => None,
+    }
+}
+
+/// Check if a DELETE statement has ORDER BY or LIMIT (i.e. removes only some rows)
+pub fn has_order_or_limit(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Delete(delete) => !delete.order_by.is_empty() || delete.limit.is_some(),
+        _ => false,
+    }
+}
+
 /// Get field condition from WHERE clause
 pub fn get_field_filter(stmt: &Statement, field_name: &str) -> Option<String> {
     match stmt {
@@ -117,23 +229,28 @@ pub fn extract_field_condition(expr: &Expr, field_name: &str) -> Option<String>
 
 /// Get member values from an IN expression: member IN (...)
 pub fn get_member_in_values(stmt: &Statement) -> Option<Vec<String>> {
+    get_column_in_values(stmt, "member")
+}
+
+/// Get values from an IN expression on the given column: <column> IN (...)
+pub fn get_column_in_values(stmt: &Statement, column: &str) -> Option<Vec<String>> {
     match stmt {
         Statement::Delete(delete) => {
-            delete.selection.as_ref().and_then(extract_member_in)
+            delete.selection.as_ref().and_then(|expr| extract_column_in(expr, column))
         },
         _ => None,
     }
 }
 
-/// Recursively extract member IN (...) from an expression (handles AND)
-fn extract_member_in(expr: &Expr) -> Option<Vec<String>> {
+/// Recursively extract <column> IN (...) from an expression (handles AND)
+fn extract_column_in(expr: &Expr, column: &str) -> Option<Vec<String>> {
     match expr {
         Expr::InList { expr: inner, list, negated, .. } => {
             if *negated {
                 return None;
             }
             match &**inner {
-                Expr::Identifier(ident) if ident.value.to_lowercase() == "member" => {
+                Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case(column) => {
                     let values: Vec<String> = list.iter()
                         .filter_map(extract_value_from_expr)
                         .collect();
@@ -144,7 +261,7 @@ fn extract_member_in(expr: &Expr) -> Option<Vec<String>> {
         }
         Expr::BinaryOp { left, op, right } => {
             if *op == sqlparser::ast::BinaryOperator::And {
-                extract_member_in(left).or_else(|| extract_member_in(right))
+                extract_column_in(left, column).or_else(|| extract_column_in(right, column))
             } else {
                 None
             }
@@ -153,6 +270,85 @@ fn extract_member_in(expr: &Expr) -> Option<Vec<String>> {
     }
 }
 
+/// Get the first comparison on the given column as (operator, value), normalized so the
+/// column is on the left: `5 < index` is returned as (Gt, "5")
+pub fn get_column_comparison(stmt: &Statement, column: &str) -> Option<(BinaryOperator, String)> {
+    match stmt {
+        Statement::Delete(delete) => {
+            delete.selection.as_ref().and_then(|expr| extract_column_comparison(expr, column))
+        },
+        _ => None,
+    }
+}
+
+fn extract_column_comparison(expr: &Expr, column: &str) -> Option<(BinaryOperator, String)> {
+    let mut comparisons = Vec::new();
+    collect_column_comparisons(expr, column, &mut comparisons);
+    comparisons.into_iter().next()
+}
+
+/// Get every comparison on the given column that is ANDed into the WHERE clause, in
+/// source order and normalized like `get_column_comparison`
+pub fn get_column_comparisons(stmt: &Statement, column: &str) -> Vec<(BinaryOperator, String)> {
+    let mut comparisons = Vec::new();
+    if let Statement::Delete(delete) = stmt {
+        if let Some(expr) = delete.selection.as_ref() {
+            collect_column_comparisons(expr, column, &mut comparisons);
+        }
+    }
+    comparisons
+}
+
+fn collect_column_comparisons(expr: &Expr, column: &str, comparisons: &mut Vec<(BinaryOperator, String)>) {
+    let is_column = |expr: &Expr| matches!(expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case(column));
+    match expr {
+        Expr::BinaryOp { left, op: BinaryOperator::And, right } => {
+            collect_column_comparisons(left, column, comparisons);
+            collect_column_comparisons(right, column, comparisons);
+        },
+        Expr::BinaryOp { left, op, right } if is_column(left) => {
+            if let Some(value) = extract_value_from_expr(right) {
+                comparisons.push((op.clone(), value));
+            }
+        },
+        Expr::BinaryOp { left, op, right } if is_column(right) => {
+            let flipped = match op {
+                BinaryOperator::Lt => BinaryOperator::Gt,
+                BinaryOperator::LtEq => BinaryOperator::GtEq,
+                BinaryOperator::Gt => BinaryOperator::Lt,
+                BinaryOperator::GtEq => BinaryOperator::LtEq,
+                other => other.clone(),
+            };
+            if let Some(value) = extract_value_from_expr(left) {
+                comparisons.push((flipped, value));
+            }
+        },
+        Expr::Nested(inner) => collect_column_comparisons(inner, column, comparisons),
+        _ => {}
+    }
+}
+
+/// Get the range of list indexes kept by `DELETE ... WHERE index <op> n`, as LTRIM
+/// start/stop. Removing a tail keeps the head (`index > 999` keeps 0..999) and removing
+/// a head keeps the tail (`index < 10` keeps 10..-1). Equality and middle ranges have no
+/// single-LTRIM equivalent, so more than one `index` comparison never matches.
+pub fn get_index_trim_range(stmt: &Statement) -> Option<(i64, i64)> {
+    let (op, value) = match get_column_comparisons(stmt, "index").as_slice() {
+        [comparison] => comparison.clone(),
+        _ => return None,
+    };
+    let n = value.parse::<i64>().ok().filter(|n| *n >= 0)?;
+    match op {
+        // Removing every index from 0 keeps nothing: LTRIM with start > stop empties the list
+        BinaryOperator::GtEq if n == 0 => Some((1, 0)),
+        BinaryOperator::Gt => Some((0, n)),
+        BinaryOperator::GtEq => Some((0, n - 1)),
+        BinaryOperator::Lt => Some((n, -1)),
+        BinaryOperator::LtEq => Some((n + 1, -1)),
+        _ => None,
+    }
+}
+
 /// Extract a literal value from an expression
 fn extract_value_from_expr(expr: &Expr) -> Option<String> {
     match expr {
@@ -0,0 +1,152 @@
+// ast/fold.rs - Constant folding of WHERE predicates and written values
+// Replaces NOW() (and friends) with the current Unix time and evaluates numeric arithmetic,
+// string functions, and COALESCE of literals, so `score < NOW() - 86400` reaches the matchers
+// as a plain number literal
+
+use sqlparser::ast::{BinaryOperator, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, SetExpr, Statement, UnaryOperator, Value};
+
+use crate::expr;
+
+/// Functions folded to the current Unix time in seconds
+const NOW_FUNCTIONS: [&str; 3] = ["now", "current_timestamp", "unix_timestamp"];
+
+/// Fold constant expressions in the WHERE clause of a SELECT, UPDATE, or DELETE, the SET
+/// values of an UPDATE, and the VALUES rows of an INSERT, using `now` (Unix seconds) for
+/// NOW(), CURRENT_TIMESTAMP, and UNIX_TIMESTAMP()
+pub fn fold_constants(stmt: &mut Statement, now: i64) {
+    let mut exprs: Vec<&mut Expr> = Vec::new();
+    match stmt {
+        Statement::Query(query) => {
+            if let SetExpr::Select(select) = query.body.as_mut() {
+                exprs.extend(select.selection.as_mut());
+            }
+        }
+        Statement::Update { assignments, selection, .. } => {
+            exprs.extend(assignments.iter_mut().map(|assignment| &mut assignment.value));
+            exprs.extend(selection.as_mut());
+        }
+        Statement::Delete(delete) => exprs.extend(delete.selection.as_mut()),
+        Statement::Insert(insert) => {
+            if let Some(SetExpr::Values(values)) = insert.source.as_mut().map(|source| source.body.as_mut()) {
+                exprs.extend(values.rows.iter_mut().flatten());
+            }
+        }
+        _ => {}
+    }
+    for expr in exprs {
+        fold_expr(expr, now);
+    }
+}
+
+/// Fold an expression in place; comparisons and boolean operators keep their shape,
+/// only their constant operands are replaced
+fn fold_expr(expr: &mut Expr, now: i64) {
+    match expr {
+        Expr::BinaryOp { left, right, .. } => {
+            fold_expr(left, now);
+            fold_expr(right, now);
+        },
+        Expr::Between { expr: inner, low, high, .. } => {
+            fold_expr(inner, now);
+            fold_expr(low, now);
+            fold_expr(high, now);
+        },
+        Expr::Nested(inner) | Expr::UnaryOp { expr: inner, .. } | Expr::Trim { expr: inner, .. } => fold_expr(inner, now),
+        Expr::InList { expr: inner, list, .. } => {
+            fold_expr(inner, now);
+            list.iter_mut().for_each(|item| fold_expr(item, now));
+        },
+        Expr::Function(function) => {
+            if let FunctionArguments::List(list) = &mut function.args {
+                for arg in &mut list.args {
+                    if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) = arg {
+                        fold_expr(arg, now);
+                    }
+                }
+            }
+        },
+        _ => {},
+    }
+    if matches!(expr, Expr::Value(_)) {
+        return;
+    }
+    if let Some(value) = eval_number(expr, now) {
+        *expr = Expr::value(Value::Number(format_number(value), false));
+    } else if let Some(value) = eval_string(expr) {
+        *expr = Expr::value(Value::SingleQuotedString(value));
+    } else if let Some(value) = eval_coalesce(expr) {
+        *expr = value;
+    }
+}
+
+/// Evaluate COALESCE up to its first non-NULL literal (NULL if every argument is NULL);
+/// a column before it leaves the call to be evaluated against stored values
+fn eval_coalesce(expr: &Expr) -> Option<Expr> {
+    for arg in expr::coalesce_call(expr)? {
+        match arg {
+            Expr::Value(value_with_span) if value_with_span.value == Value::Null => continue,
+            Expr::Value(_) => return Some(arg.clone()),
+            _ => return None,
+        }
+    }
+    Some(Expr::value(Value::Null))
+}
+
+/// Evaluate a string function of (folded) literals: `UPPER('a')`, `REPLACE('a-b', '-', '_')`
+fn eval_string(expr: &Expr) -> Option<String> {
+    let (function, args) = expr::string_call(expr)?;
+    let args = args.into_iter()
+        .map(|arg| match arg {
+            Expr::Value(value_with_span) => match &value_with_span.value {
+                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) | Value::Number(s, _) => Some(s.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(function.apply(&args))
+}
+
+/// Evaluate a constant numeric expression
+fn eval_number(expr: &Expr, now: i64) -> Option<f64> {
+    match expr {
+        Expr::Value(value_with_span) => match &value_with_span.value {
+            Value::Number(n, _) => n.parse().ok(),
+            _ => None,
+        },
+        Expr::Function(function) if is_now_function(function) => Some(now as f64),
+        Expr::Nested(inner) => eval_number(inner, now),
+        Expr::UnaryOp { op: UnaryOperator::Minus, expr } => eval_number(expr, now).map(|n| -n),
+        Expr::UnaryOp { op: UnaryOperator::Plus, expr } => eval_number(expr, now),
+        Expr::BinaryOp { left, op, right } => {
+            let (left, right) = (eval_number(left, now)?, eval_number(right, now)?);
+            match op {
+                BinaryOperator::Plus => Some(left + right),
+                BinaryOperator::Minus => Some(left - right),
+                BinaryOperator::Multiply => Some(left * right),
+                BinaryOperator::Divide if right != 0.0 => Some(left / right),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+fn is_now_function(function: &sqlparser::ast::Function) -> bool {
+    let name = function.name.to_string().to_lowercase();
+    let no_args = match &function.args {
+        FunctionArguments::None => true,
+        FunctionArguments::List(list) => list.args.is_empty(),
+        FunctionArguments::Subquery(_) => false,
+    };
+    no_args && NOW_FUNCTIONS.contains(&name.as_str())
+}
+
+/// Format integral values without a fractional part, as SQL literals are usually written
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
@@ -1,9 +1,12 @@
 // ast/insert.rs - Pure functions for INSERT AST node extraction
 // These functions don't modify state, just extract information from INSERT statements
 
-use sqlparser::ast::{Expr, ObjectNamePart, SetExpr, Statement, TableObject, Value};
+use sqlparser::ast::{Expr, ObjectNamePart, Query, SelectItem, SetExpr, Statement, TableObject, UnaryOperator, Value};
 use std::collections::HashMap;
 
+use crate::codec::Scalar;
+use crate::types::{redis_type_of, RedisDataType, TypeMapping};
+
 /// Get the table name from an INSERT statement
 /// Get the table name from an INSERT statement
 pub fn ins_get_table_name(stmt: &Statement) -> Option<String> {
@@ -74,6 +77,42 @@ pub fn ins_extract_value(expr: &Expr) -> Option<String> {
     }
 }
 
+/// Extract a literal as a typed value: strings, numbers (negative ones too), booleans, and NULL
+pub fn ins_extract_scalar(expr: &Expr) -> Option<Scalar> {
+    match expr {
+        Expr::Value(value_with_span) => match &value_with_span.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(Scalar::Text(s.clone())),
+            Value::Number(n, _) => Some(Scalar::Number(n.clone())),
+            Value::Boolean(b) => Some(Scalar::Boolean(*b)),
+            Value::Null => Some(Scalar::Null),
+            _ => None,
+        },
+        Expr::UnaryOp { op: UnaryOperator::Minus, expr } => match ins_extract_scalar(expr)? {
+            Scalar::Number(n) => Some(Scalar::Number(format!("-{}", n))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Extract the rows of an INSERT VALUES clause as (column, typed value) pairs in SQL column
+/// order; None unless every value is a literal and every row names each column
+pub fn ins_get_typed_rows(stmt: &Statement) -> Option<Vec<Vec<(String, Scalar)>>> {
+    let columns = ins_get_column_names(stmt)?;
+    let Statement::Insert(insert) = stmt else { return None };
+    let SetExpr::Values(values) = &*insert.source.as_ref()?.body else { return None };
+    values.rows.iter()
+        .map(|row| {
+            if row.len() != columns.len() {
+                return None;
+            }
+            columns.iter().zip(row)
+                .map(|(column, expr)| Some((column.clone(), ins_extract_scalar(expr)?)))
+                .collect()
+        })
+        .collect()
+}
+
 /// Extract all values from an INSERT VALUES clause as rows of strings
 pub fn ins_get_values_as_strings(stmt: &Statement) -> Option<Vec<Vec<String>>> {
     match stmt {
@@ -103,33 +142,43 @@ pub fn ins_get_values_as_strings(stmt: &Statement) -> Option<Vec<Vec<String>>> {
     }
 }
 
-/// Extract values from an INSERT statement as maps of column name to value
-pub fn ins_get_values_as_maps(stmt: &Statement) -> Option<Vec<HashMap<String, String>>> {
+/// Bytes of literal values in each row of an INSERT VALUES clause (NULLs and
+/// expressions count as zero), for sizing what the rows store
+pub fn ins_get_value_sizes(stmt: &Statement) -> Option<Vec<usize>> {
+    match stmt {
+        Statement::Insert(insert) => match &*insert.source.as_ref()?.body {
+            SetExpr::Values(values) => Some(values.rows.iter()
+                .map(|row| row.iter().filter_map(ins_extract_value).map(|value| value.len()).sum())
+                .collect()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Extract values from an INSERT statement as (column, value) pairs in SQL column order
+pub fn ins_get_values_as_pairs(stmt: &Statement) -> Option<Vec<Vec<(String, String)>>> {
     let columns = ins_get_column_names(stmt)?;
     let value_rows = ins_get_values_as_strings(stmt)?;
     
-    let maps = value_rows.iter()
-        .filter_map(|row| {
-            if row.len() != columns.len() {
-                return None;
-            }
-            
-            let mut map = HashMap::new();
-            for (idx, value) in row.iter().enumerate() {
-                map.insert(columns[idx].clone(), value.clone());
-            }
-            
-            Some(map)
-        })
+    let rows = value_rows.iter()
+        .filter(|row| row.len() == columns.len())
+        .map(|row| columns.iter().cloned().zip(row.iter().cloned()).collect::<Vec<_>>())
         .collect::<Vec<_>>();
     
-    if maps.is_empty() {
+    if rows.is_empty() {
         None
     } else {
-        Some(maps)
+        Some(rows)
     }
 }
 
+/// Extract values from an INSERT statement as maps of column name to value
+pub fn ins_get_values_as_maps(stmt: &Statement) -> Option<Vec<HashMap<String, String>>> {
+    let rows = ins_get_values_as_pairs(stmt)?;
+    Some(rows.into_iter().map(|row| row.into_iter().collect()).collect())
+}
+
 /// Get a specific column's value from the first row
 pub fn ins_get_column_value(stmt: &Statement, column_name: &str) -> Option<String> {
     let value_maps = ins_get_values_as_maps(stmt)?;
@@ -156,4 +205,57 @@ pub fn ins_get_all_column_values(stmt: &Statement, column_name: &str) -> Option<
     } else {
         Some(values)
     }
-}
\ No newline at end of file
+}
+/// Get the query feeding an INSERT ... SELECT statement (None for INSERT ... VALUES)
+pub fn ins_get_source_query(stmt: &Statement) -> Option<&Query> {
+    match stmt {
+        Statement::Insert(insert) => insert.source.as_deref()
+            .filter(|query| !matches!(&*query.body, SetExpr::Values(_))),
+        _ => None,
+    }
+}
+
+/// Get the literal an INSERT ... SELECT projects into `column`: the SELECT lists one item per
+/// insert column, and the item in that column's position is a constant (e.g. `SELECT 'top:1', member`)
+pub fn ins_get_projected_literal(stmt: &Statement, projection: &[SelectItem], column: &str) -> Option<String> {
+    let columns = ins_get_column_names(stmt)?;
+    if columns.len() != projection.len() {
+        return None;
+    }
+    let position = columns.iter().position(|name| name.eq_ignore_ascii_case(column))?;
+    match &projection[position] {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => ins_extract_value(expr),
+        _ => None,
+    }
+}
+
+/// Get what an INSERT ... SELECT copies from a list, set, or sorted set into one: for each
+/// column the target type stores (see `RedisDataType::columns`), the source column the
+/// SELECT projects into it (`member`), or `=literal` for a constant (`SELECT member, 0`).
+/// The SELECT lists every insert column, or all but `key` (the destination is then derived).
+pub fn ins_get_copied_columns(stmt: &Statement, projection: &[SelectItem], source_type: RedisDataType) -> Option<Vec<String>> {
+    let target_columns = redis_type_of(&ins_get_table_name(stmt)?, TypeMapping::standard()).columns()?;
+    let source_columns = source_type.columns()?;
+    let mut columns = ins_get_column_names(stmt)?;
+    if columns.len() != target_columns.len() + 1 || !columns.iter().any(|name| name.eq_ignore_ascii_case("key")) {
+        return None;
+    }
+    if projection.len() + 1 == columns.len() {
+        columns.retain(|name| !name.eq_ignore_ascii_case("key"));
+    } else if projection.len() != columns.len() {
+        return None;
+    }
+    target_columns.iter()
+        .map(|column| {
+            let position = columns.iter().position(|name| name.eq_ignore_ascii_case(column))?;
+            match &projection[position] {
+                SelectItem::UnnamedExpr(Expr::Identifier(ident)) | SelectItem::ExprWithAlias { expr: Expr::Identifier(ident), .. } => {
+                    let source = ident.value.to_lowercase();
+                    source_columns.contains(&source.as_str()).then_some(source)
+                }
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => ins_extract_value(expr).map(|value| format!("={}", value)),
+                _ => None,
+            }
+        })
+        .collect()
+}
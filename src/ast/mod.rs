@@ -5,9 +5,37 @@ pub mod select;
 pub mod insert;
 pub mod update;
 pub mod delete;
+pub mod trigger;
+pub mod clauses;
+pub mod fold;
+pub mod alias;
+pub mod schema;
+pub mod session;
+pub mod view;
 
-// Re-export AST functions for convenience
-pub use select::*;
-pub use insert::*;
-pub use update::*;
-pub use delete::*;
\ No newline at end of file
+// Re-export AST functions for the crate; the key extractors are public through `prelude`
+pub(crate) use select::*;
+pub(crate) use insert::*;
+pub(crate) use update::*;
+pub(crate) use delete::*;
+pub(crate) use trigger::*;
+pub(crate) use clauses::*;
+pub(crate) use session::*;
+pub(crate) use view::*;
+pub(crate) use fold::fold_constants;
+pub(crate) use alias::resolve_aliases;
+pub(crate) use schema::strip_schema;
+
+use sqlparser::ast::Statement;
+
+/// Table a SELECT, INSERT, UPDATE, DELETE, or CREATE TRIGGER statement addresses
+pub fn stmt_get_table_name(stmt: &Statement) -> Option<String> {
+    match stmt {
+        Statement::Query(query) => sel_get_select(query).and_then(sel_get_table_name),
+        Statement::Insert(_) => ins_get_table_name(stmt),
+        Statement::Update { .. } => upd_get_table_name(stmt),
+        Statement::Delete(_) => get_table_name(stmt),
+        Statement::CreateTrigger { .. } => trg_get_table_name(stmt),
+        _ => None,
+    }
+}
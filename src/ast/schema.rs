@@ -0,0 +1,56 @@
+// ast/schema.rs - Schema qualifiers on table names
+// Matchers read the first part of a table name, so `app.users__hash` would miss every
+// `__hash` rule; qualified names are reduced to the table here and the qualifier returned
+
+use sqlparser::ast::{FromTable, ObjectName, Query, SetExpr, Statement, TableFactor, TableObject, TableWithJoins};
+
+/// Reduce the qualified table names of a statement (`app.users__hash`, `db.app.users__hash`)
+/// to their last part, returning the qualifier of the first one (`app`, `db.app`)
+pub fn strip_schema(stmt: &mut Statement) -> Option<String> {
+    let mut names = Vec::new();
+    match stmt {
+        Statement::Query(query) => query_tables(query, &mut names),
+        Statement::Insert(insert) => {
+            if let TableObject::TableName(name) = &mut insert.table {
+                names.push(name);
+            }
+            if let Some(source) = &mut insert.source {
+                query_tables(source, &mut names);
+            }
+        }
+        Statement::Update { table, .. } => from_tables(std::slice::from_mut(table), &mut names),
+        Statement::Delete(delete) => {
+            let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) = &mut delete.from;
+            from_tables(tables, &mut names);
+        }
+        Statement::CreateTrigger { table_name, .. } => names.push(table_name),
+        _ => {}
+    }
+    let mut schema = None;
+    for name in names {
+        if name.0.len() > 1 {
+            let table = name.0.pop().expect("qualified name has a table part");
+            let qualifier = name.0.iter().map(ToString::to_string).collect::<Vec<_>>().join(".");
+            schema.get_or_insert(qualifier);
+            name.0 = vec![table];
+        }
+    }
+    schema
+}
+
+fn query_tables<'a>(query: &'a mut Query, names: &mut Vec<&'a mut ObjectName>) {
+    if let SetExpr::Select(select) = query.body.as_mut() {
+        from_tables(&mut select.from, names);
+    }
+}
+
+fn from_tables<'a>(tables: &'a mut [TableWithJoins], names: &mut Vec<&'a mut ObjectName>) {
+    for table in tables {
+        let relations = std::iter::once(&mut table.relation).chain(table.joins.iter_mut().map(|join| &mut join.relation));
+        for relation in relations {
+            if let TableFactor::Table { name, .. } = relation {
+                names.push(name);
+            }
+        }
+    }
+}
@@ -2,9 +2,13 @@
 // These functions don't modify state, just extract or test information
 
 use sqlparser::ast::{
-    BinaryOperator, Expr, ObjectNamePart, OrderByKind, Query, Select, SelectItem, SetExpr, Statement, TableFactor, Value
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, GroupByExpr, ObjectNamePart, OrderByKind, Query, Select, SelectItem, SetExpr, SetOperator, Statement, TableFactor, Value
 };
 
+use crate::ast::split_conjuncts;
+use crate::expr::Condition;
+use crate::types::{redis_type_of, RedisDataType, TypeMapping};
+
 /// Get the query from a statement, if it exists
 pub fn sel_get_query(stmt: &Statement) -> Option<&Query> {
     match stmt {
@@ -21,39 +25,116 @@ pub fn sel_get_select(query: &Query) -> Option<&Select> {
     }
 }
 
-/// Get the table name from a select statement, if it exists
-pub fn sel_get_table_name(select: &Select) -> Option<String> {
-    select.from.get(0).and_then(|table_with_joins| {
-        match &table_with_joins.relation {
-            TableFactor::Table { name, .. } => {
-                if !name.0.is_empty() {
-                    match &name.0[0] {
-                        ObjectNamePart::Identifier(ident) => Some(ident.value.clone())
-                    }
-                } else {
-                    None
+/// Get the SELECTs combined by a query's set operation (UNION, INTERSECT, or EXCEPT),
+/// left to right, and the operator; a plain SELECT has one operand and no operator.
+/// None if the query mixes operators or nests anything other than SELECTs.
+pub fn sel_get_set_operands(query: &Query) -> Option<(Option<SetOperator>, Vec<&Select>)> {
+    fn collect<'a>(expr: &'a SetExpr, op: &mut Option<SetOperator>, selects: &mut Vec<&'a Select>) -> bool {
+        match expr {
+            SetExpr::Select(select) => {
+                selects.push(select);
+                true
+            },
+            SetExpr::SetOperation { op: this, left, right, .. } => {
+                if op.get_or_insert(*this) != this {
+                    return false;
                 }
+                collect(left, op, selects) && collect(right, op, selects)
             },
-            _ => None,
+            _ => false,
         }
-    })
+    }
+    let mut op = None;
+    let mut selects = Vec::new();
+    collect(&query.body, &mut op, &mut selects).then_some((op, selects))
+}
+
+/// Get the table name from a select statement, if it exists
+pub fn sel_get_table_name(select: &Select) -> Option<String> {
+    sel_get_table_name_ref(select).map(str::to_string)
+}
+
+/// Borrowing form of `sel_get_table_name`, for matchers that only inspect the name
+pub fn sel_get_table_name_ref(select: &Select) -> Option<&str> {
+    select.from.first().and_then(|table_with_joins| sel_get_relation_name(&table_with_joins.relation))
+}
+
+/// Get the (first part of the) name of a table factor; None for derived tables and functions
+pub fn sel_get_relation_name(table: &TableFactor) -> Option<&str> {
+    match table {
+        TableFactor::Table { name, .. } => {
+            match name.0.first()? {
+                ObjectNamePart::Identifier(ident) => Some(ident.value.as_str())
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Get the name (lowercased) and literal arguments of the table function a SELECT reads,
+/// e.g. `FROM redis_get('user:1')` gives ("redis_get", ["user:1"]); None for tables, joins,
+/// and arguments other than strings and numbers
+pub fn sel_get_table_function(select: &Select) -> Option<(String, Vec<String>)> {
+    let [from] = select.from.as_slice() else { return None };
+    if !from.joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, args: Some(args), .. } = &from.relation else { return None };
+    let name = name.0.last().and_then(ObjectNamePart::as_ident)?.value.to_lowercase();
+    let args = args.args.iter()
+        .map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => sel_extract_value(expr).or_else(|| sel_get_number(expr)),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((name, args))
+}
+
+/// Compile the WHERE conjuncts besides the `key = value` lookup into one row filter, for a
+/// single-table SELECT without GROUP BY or HAVING. `Some(None)` when the key predicate is
+/// the only one; None if there is no key predicate or another conjunct can't be compiled.
+pub fn sel_get_row_filter(select: &Select) -> Option<Option<Condition>> {
+    let grouped = match &select.group_by {
+        GroupByExpr::All(_) => true,
+        GroupByExpr::Expressions(exprs, _) => !exprs.is_empty(),
+    };
+    if select.from.len() != 1 || !select.from[0].joins.is_empty() || grouped || select.having.is_some() {
+        return None;
+    }
+    let mut conjuncts = split_conjuncts(select.selection.as_ref()?);
+    let lookup = conjuncts.iter().position(|conjunct| sel_extract_key_from_expr(conjunct).is_some())?;
+    conjuncts.remove(lookup);
+    conjuncts.into_iter()
+        .map(Condition::compile)
+        .try_fold(None, |filter: Option<Condition>, condition| {
+            let condition = condition?;
+            Some(Some(match filter {
+                Some(filter) => Condition::And(Box::new(filter), Box::new(condition)),
+                None => condition,
+            }))
+        })
 }
 
 /// Get a "key" value from a binary expression where "key = value"
 /// Recursively handles AND expressions
 pub fn sel_get_key_value(expr: &Option<Expr>) -> Option<String> {
+    sel_get_key_value_ref(expr).map(str::to_string)
+}
+
+/// Borrowing form of `sel_get_key_value`
+pub fn sel_get_key_value_ref(expr: &Option<Expr>) -> Option<&str> {
     expr.as_ref().and_then(sel_extract_key_from_expr)
 }
 
 /// Recursively extract key = value from an expression (handles AND)
-fn sel_extract_key_from_expr(expr: &Expr) -> Option<String> {
+pub fn sel_extract_key_from_expr(expr: &Expr) -> Option<&str> {
     match expr {
         Expr::BinaryOp { left, op, right } => {
             match op {
                 BinaryOperator::Eq => {
                     match &**left {
-                        Expr::Identifier(ident) if ident.value.to_lowercase() == "key" => {
-                            sel_extract_value(right)
+                        Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key") => {
+                            sel_extract_value_ref(right)
                         }
                         _ => None,
                     }
@@ -69,12 +150,39 @@ fn sel_extract_key_from_expr(expr: &Expr) -> Option<String> {
     }
 }
 
+/// Get the group set of `key IN (SELECT member FROM groups__set WHERE key = 'g')`, as
+/// (set table, set key); the subquery may be the whole WHERE or one of its AND conjuncts
+pub fn sel_get_key_in_set(expr: &Option<Expr>) -> Option<(String, String)> {
+    expr.as_ref().and_then(|expr| crate::ast::split_conjuncts(expr).into_iter().find_map(sel_extract_key_in_set))
+}
+
+fn sel_extract_key_in_set(expr: &Expr) -> Option<(String, String)> {
+    let Expr::InSubquery { expr, subquery, negated: false } = expr else { return None };
+    if !matches!(&**expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key")) {
+        return None;
+    }
+    let select = sel_get_select(subquery)?;
+    let projects_member = matches!(
+        select.projection.as_slice(),
+        [SelectItem::UnnamedExpr(Expr::Identifier(ident))] if ident.value.eq_ignore_ascii_case("member")
+    );
+    let table = sel_get_table_name(select).filter(|table| redis_type_of(table, TypeMapping::standard()) == RedisDataType::Set)?;
+    if !projects_member || select.selection.as_ref().is_some_and(|expr| crate::ast::split_conjuncts(expr).len() != 1) {
+        return None;
+    }
+    Some((table, sel_get_key_value(&select.selection)?))
+}
+
 /// Extract a string value from an expression
 fn sel_extract_value(expr: &Expr) -> Option<String> {
+    sel_extract_value_ref(expr).map(str::to_string)
+}
+
+fn sel_extract_value_ref(expr: &Expr) -> Option<&str> {
     match expr {
         Expr::Value(value_with_span) => match &value_with_span.value {
-            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s.clone()),
-            Value::Number(n, _) => Some(n.clone()),
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s),
+            Value::Number(n, _) => Some(n),
             _ => None,
         },
         _ => None,
@@ -83,8 +191,13 @@ fn sel_extract_value(expr: &Expr) -> Option<String> {
 
 /// Get a field name from a SelectItem, if it exists
 pub fn sel_get_field_name(select_item: &SelectItem) -> Option<String> {
+    sel_get_field_name_ref(select_item).map(str::to_string)
+}
+
+/// Borrowing form of `sel_get_field_name`
+pub fn sel_get_field_name_ref(select_item: &SelectItem) -> Option<&str> {
     match select_item {
-        SelectItem::UnnamedExpr(Expr::Identifier(ident)) => Some(ident.value.clone()),
+        SelectItem::UnnamedExpr(Expr::Identifier(ident)) => Some(&ident.value),
         _ => None,
     }
 }
@@ -96,6 +209,18 @@ pub fn sel_get_field_names(select_items: &[SelectItem]) -> Vec<String> {
         .collect()
 }
 
+/// Get (field, column) pairs for a projection of plain or aliased identifiers,
+/// e.g. `name AS n` gives ("name", "n"); None if any item is a wildcard or expression
+pub fn sel_get_projected_columns(select_items: &[SelectItem]) -> Option<Vec<(String, String)>> {
+    select_items.iter()
+        .map(|item| match item {
+            SelectItem::UnnamedExpr(Expr::Identifier(ident)) => Some((ident.value.clone(), ident.value.clone())),
+            SelectItem::ExprWithAlias { expr: Expr::Identifier(ident), alias } => Some((ident.value.clone(), alias.value.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Check if a SelectItem is a wildcard (*) selector
 pub fn sel_is_wildcard(select_item: &SelectItem) -> bool {
     matches!(select_item, SelectItem::Wildcard(_))
@@ -188,6 +313,49 @@ pub fn sel_get_aggregate(select: &Select) -> Option<AggregateInfo> {
     }
 }
 
+/// Comparisons of the selected aggregate with numbers in the HAVING clause, as (operator,
+/// number) with the aggregate on the left: `HAVING SUM(score) > 100 AND SUM(score) <= 500`.
+/// Empty without HAVING; None for any other HAVING condition.
+pub fn sel_get_having(select: &Select) -> Option<Vec<(BinaryOperator, String)>> {
+    let Some(having) = &select.having else { return Some(Vec::new()) };
+    let aggregate = match select.projection.as_slice() {
+        [SelectItem::UnnamedExpr(expr @ Expr::Function(_))] => expr.to_string().to_uppercase(),
+        _ => return None,
+    };
+    crate::ast::split_conjuncts(having).into_iter()
+        .map(|condition| {
+            let Expr::BinaryOp { left, op, right } = condition else { return None };
+            let flipped = match op {
+                BinaryOperator::Gt => BinaryOperator::Lt,
+                BinaryOperator::GtEq => BinaryOperator::LtEq,
+                BinaryOperator::Lt => BinaryOperator::Gt,
+                BinaryOperator::LtEq => BinaryOperator::GtEq,
+                BinaryOperator::Eq | BinaryOperator::NotEq => op.clone(),
+                _ => return None,
+            };
+            let is_aggregate = |expr: &Expr| expr.to_string().to_uppercase() == aggregate;
+            match (is_aggregate(left), is_aggregate(right)) {
+                (true, false) => Some((op.clone(), sel_get_number(right)?)),
+                (false, true) => Some((flipped, sel_get_number(left)?)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// A numeric literal, negative ones included
+fn sel_get_number(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Value(value) => match &value.value {
+            Value::Number(n, _) => Some(n.clone()),
+            _ => None,
+        },
+        Expr::UnaryOp { op: sqlparser::ast::UnaryOperator::Minus, expr } => sel_get_number(expr).map(|n| format!("-{}", n)),
+        Expr::Nested(inner) => sel_get_number(inner),
+        _ => None,
+    }
+}
+
 /// Check if the WHERE clause has a BETWEEN condition for score
 pub fn sel_get_score_between(expr: &Option<Expr>) -> Option<(String, String)> {
     expr.as_ref().and_then(sel_extract_score_between_from_expr)
@@ -247,18 +415,23 @@ fn sel_extract_index_lt_from_expr(expr: &Expr) -> Option<String> {
 /// Get a field value from a binary expression with "field = value"
 /// Recursively handles AND expressions
 pub fn sel_get_field_filter(expr: &Option<Expr>, field_name: &str) -> Option<String> {
+    sel_get_field_filter_ref(expr, field_name).map(str::to_string)
+}
+
+/// Borrowing form of `sel_get_field_filter`
+pub fn sel_get_field_filter_ref<'a>(expr: &'a Option<Expr>, field_name: &str) -> Option<&'a str> {
     expr.as_ref().and_then(|e| sel_extract_field_from_expr(e, field_name))
 }
 
 /// Recursively extract field = value from an expression (handles AND)
-fn sel_extract_field_from_expr(expr: &Expr, field_name: &str) -> Option<String> {
+pub fn sel_extract_field_from_expr<'a>(expr: &'a Expr, field_name: &str) -> Option<&'a str> {
     match expr {
         Expr::BinaryOp { left, op, right } => {
             match op {
                 BinaryOperator::Eq => {
                     match &**left {
-                        Expr::Identifier(ident) if ident.value.to_lowercase() == field_name.to_lowercase() => {
-                            sel_extract_value(right)
+                        Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case(field_name) => {
+                            sel_extract_value_ref(right)
                         }
                         _ => None,
                     }
@@ -274,7 +447,28 @@ fn sel_extract_field_from_expr(expr: &Expr, field_name: &str) -> Option<String>
     }
 }
 
-/// Get the limit value from a query, if it exists
+/// Get the key glob pattern a WHERE clause selects: `key LIKE 'user:%'` becomes `user:*`,
+/// `key = 'v'` the escaped key, and no WHERE clause every key (`*`)
+pub fn sel_get_key_pattern(selection: &Option<Expr>) -> Option<String> {
+    match selection {
+        None => Some("*".to_string()),
+        Some(expr) => crate::ast::trg_extract_key_pattern(expr),
+    }
+}
+
+/// Get the member glob pattern of a `key = 'v' AND member LIKE 'p'` WHERE clause (in either
+/// order); None if it filters anything else
+pub fn sel_get_member_pattern(selection: &Option<Expr>) -> Option<String> {
+    let conjuncts = split_conjuncts(selection.as_ref()?);
+    let [first, second] = conjuncts.as_slice() else { return None };
+    let (lookup, like) = match sel_extract_key_from_expr(first) {
+        Some(_) => (first, second),
+        None => (second, first),
+    };
+    sel_extract_key_from_expr(lookup)?;
+    crate::ast::like_glob(like, "member")
+}
+
 /// Get the limit value from a query, if it exists
 pub fn sel_get_limit(query: &Query) -> Option<u64> {
     query.limit.as_ref().and_then(|limit| {
@@ -288,74 +482,97 @@ pub fn sel_get_limit(query: &Query) -> Option<u64> {
     })
 }
 
-/// Extract score comparison from a binary expression
+/// Extract the score range of comparisons and BETWEEN from a WHERE clause
 /// Recursively handles AND expressions (common with key = value pairs)
 pub fn sel_get_score_range(expr: &Option<Expr>) -> Option<(String, String)> {
     expr.as_ref().and_then(sel_extract_score_range_from_expr)
 }
 
-fn sel_extract_score_range_from_expr(expr: &Expr) -> Option<(String, String)> {
+/// Score range of one expression. ANDed comparisons intersect in any order, so
+/// `score >= 100 AND score < 200` and `score < 200 AND score >= 100` both give ("100", "(200"),
+/// and of two bounds on one side the tighter wins. `100 <= score` reads as `score >= 100`,
+/// and `score BETWEEN a AND b` bounds both ends inclusively.
+pub fn sel_extract_score_range_from_expr(expr: &Expr) -> Option<(String, String)> {
     match expr {
-        Expr::BinaryOp { left, op, right } => {
-            // Check for AND: the score condition might be on either side
-            if *op == BinaryOperator::And {
-                return sel_extract_score_range_from_expr(left)
-                    .or_else(|| sel_extract_score_range_from_expr(right));
+        Expr::Nested(inner) => sel_extract_score_range_from_expr(inner),
+        // Check for AND: the score condition might be on either side, or bound both ends
+        Expr::BinaryOp { left, op: BinaryOperator::And, right } => {
+            match (sel_extract_score_range_from_expr(left), sel_extract_score_range_from_expr(right)) {
+                (Some((min, max)), Some((other_min, other_max))) => Some((
+                    tighter_bound(min, other_min, true),
+                    tighter_bound(max, other_max, false),
+                )),
+                (range, other) => range.or(other),
             }
-
-            match &**left {
-                Expr::Identifier(ident) if ident.value.to_lowercase() == "score" => {
-                    match op {
-                        BinaryOperator::Gt => {
-                            if let Expr::Value(value_with_span) = &**right {
-                                if let Value::Number(n, _) = &value_with_span.value {
-                                    return Some((format!("({}", n), "+inf".to_string()));
-                                }
-                            }
-                            None
-                        }
-                        BinaryOperator::GtEq => {
-                            if let Expr::Value(value_with_span) = &**right {
-                                if let Value::Number(n, _) = &value_with_span.value {
-                                    return Some((n.clone(), "+inf".to_string()));
-                                }
-                            }
-                            None
-                        }
-                        BinaryOperator::Lt => {
-                            if let Expr::Value(value_with_span) = &**right {
-                                if let Value::Number(n, _) = &value_with_span.value {
-                                    return Some(("-inf".to_string(), format!("({}", n)));
-                                }
-                            }
-                            None
-                        }
-                        BinaryOperator::LtEq => {
-                            if let Expr::Value(value_with_span) = &**right {
-                                if let Value::Number(n, _) = &value_with_span.value {
-                                    return Some(("-inf".to_string(), n.clone()));
-                                }
-                            }
-                            None
-                        }
-                        _ => None,
-                    }
-                }
+        }
+        Expr::Between { expr: column, negated: false, low, high } if is_score_column(column) => {
+            Some((sel_extract_value(low)?, sel_extract_value(high)?))
+        }
+        Expr::BinaryOp { left, op, right } => {
+            // Put the column on the left: `n < score` is `score > n`
+            let (op, value) = if is_score_column(left) {
+                (op.clone(), &**right)
+            } else if is_score_column(right) {
+                let flipped = match op {
+                    BinaryOperator::Gt => BinaryOperator::Lt,
+                    BinaryOperator::GtEq => BinaryOperator::LtEq,
+                    BinaryOperator::Lt => BinaryOperator::Gt,
+                    BinaryOperator::LtEq => BinaryOperator::GtEq,
+                    _ => return None,
+                };
+                (flipped, &**left)
+            } else {
+                return None;
+            };
+            let n = match value {
+                Expr::Value(value_with_span) => match &value_with_span.value {
+                    Value::Number(n, _) => n,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            match op {
+                BinaryOperator::Gt => Some((format!("({}", n), "+inf".to_string())),
+                BinaryOperator::GtEq => Some((n.clone(), "+inf".to_string())),
+                BinaryOperator::Lt => Some(("-inf".to_string(), format!("({}", n))),
+                BinaryOperator::LtEq => Some(("-inf".to_string(), n.clone())),
                 _ => None,
             }
         }
-        // Could add BETWEEN handling here
         _ => None,
     }
 }
 
+fn is_score_column(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("score"))
+}
+
+/// The tighter of two ZRANGEBYSCORE bounds (`-inf`, `+inf`, `n`, or exclusive `(n`): the
+/// larger for a minimum, the smaller for a maximum, and the exclusive one on a tie
+fn tighter_bound(a: String, b: String, minimum: bool) -> String {
+    let value = |bound: &str| match bound.trim_start_matches('(') {
+        "-inf" => f64::NEG_INFINITY,
+        "+inf" | "inf" => f64::INFINITY,
+        n => n.parse().unwrap_or(f64::NAN),
+    };
+    let (x, y) = (value(&a), value(&b));
+    if x.is_nan() || y.is_nan() {
+        return a;
+    }
+    if x == y {
+        return if b.starts_with('(') { b } else { a };
+    }
+    if (x > y) == minimum { a } else { b }
+}
+
 /// Get key values from an IN expression: key IN ('a', 'b', 'c')
 /// Recursively handles AND expressions
 pub fn sel_get_key_in_values(expr: &Option<Expr>) -> Option<Vec<String>> {
     expr.as_ref().and_then(sel_extract_key_in_from_expr)
 }
 
-fn sel_extract_key_in_from_expr(expr: &Expr) -> Option<Vec<String>> {
+/// Recursively extract the values of `key IN (...)` from an expression (handles AND)
+pub fn sel_extract_key_in_from_expr(expr: &Expr) -> Option<Vec<String>> {
     match expr {
         Expr::InList { expr: left, list, negated, .. } => {
             if *negated {
@@ -379,7 +596,44 @@ fn sel_extract_key_in_from_expr(expr: &Expr) -> Option<Vec<String>> {
     }
 }
 
+/// Check if a query orders by score (in either direction)
+pub fn sel_is_order_by_score(query: &Query) -> bool {
+    match query.order_by.as_ref().map(|order_by| &order_by.kind) {
+        Some(OrderByKind::Expressions(exprs)) => exprs.iter().any(|order_expr| {
+            matches!(&order_expr.expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("score"))
+        }),
+        _ => false,
+    }
+}
+
 /// Check if a SQL statement represents a query ordering by score in descending order
+/// Check if the query is ordered by `score` alone: Some(true) for DESC, Some(false) for ASC
+pub fn sel_get_score_order(query: &Query) -> Option<bool> {
+    let OrderByKind::Expressions(exprs) = &query.order_by.as_ref()?.kind else { return None };
+    match exprs.as_slice() {
+        [order_expr] => match &order_expr.expr {
+            Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("score") => Some(order_expr.options.asc == Some(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The ORDER BY items as (column, descending) pairs, empty without ORDER BY; None if an
+/// item isn't a plain column or sets NULLS FIRST/LAST
+pub fn sel_get_order_columns(query: &Query) -> Option<Vec<(String, bool)>> {
+    let Some(order_by) = &query.order_by else { return Some(Vec::new()) };
+    let OrderByKind::Expressions(exprs) = &order_by.kind else { return None };
+    exprs.iter()
+        .map(|order_expr| match &order_expr.expr {
+            Expr::Identifier(ident) if order_expr.options.nulls_first.is_none() => {
+                Some((ident.value.clone(), order_expr.options.asc == Some(false)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn sel_is_order_by_score_desc(query: &Query) -> bool {
     match &query.order_by {
         Some(order_by) => {
@@ -0,0 +1,21 @@
+// ast/session.rs - Pure functions for session statements (USE)
+// USE selects a Redis logical database by index
+
+use sqlparser::ast::{ObjectNamePart, Statement, Use};
+
+/// Get the database index a USE statement selects: `USE db3`, `USE "3"`, or `USE DATABASE db3`
+pub fn use_get_database(stmt: &Statement) -> Option<u32> {
+    let name = match stmt {
+        Statement::Use(Use::Object(name) | Use::Database(name)) => name,
+        _ => return None,
+    };
+    let [ObjectNamePart::Identifier(ident)] = name.0.as_slice() else {
+        return None;
+    };
+    let value = ident.value.as_str();
+    let index = match value.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("db") => &value[2..],
+        _ => value,
+    };
+    index.parse().ok()
+}
@@ -0,0 +1,115 @@
+// ast/trigger.rs - Pure functions for CREATE TRIGGER AST node extraction
+// These functions don't modify state, just extract information from trigger definitions
+
+use sqlparser::ast::{BinaryOperator, Expr, ObjectNamePart, Statement, TriggerEvent, TriggerPeriod, Value};
+
+/// Get the table name a trigger is defined on
+pub fn trg_get_table_name(stmt: &Statement) -> Option<String> {
+    match stmt {
+        Statement::CreateTrigger { table_name, .. } => {
+            table_name.0.first().map(|ObjectNamePart::Identifier(ident)| ident.value.clone())
+        },
+        _ => None,
+    }
+}
+
+/// Check if the trigger fires AFTER the change (the only timing Redis notifications can honor)
+pub fn trg_is_after(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::CreateTrigger { period: TriggerPeriod::After, .. })
+}
+
+/// Get the events (INSERT, UPDATE, DELETE, TRUNCATE) a trigger listens for
+pub fn trg_get_events(stmt: &Statement) -> Option<&[TriggerEvent]> {
+    match stmt {
+        Statement::CreateTrigger { events, .. } => Some(events),
+        _ => None,
+    }
+}
+
+/// Get the key glob pattern from the trigger's WHEN condition.
+/// `key LIKE 'user:%'` becomes `user:*` and `key = 'user:1'` becomes `user:1`;
+/// a trigger without a condition has no pattern (see `trg_is_unfiltered`).
+pub fn trg_get_key_pattern(stmt: &Statement) -> Option<String> {
+    match stmt {
+        Statement::CreateTrigger { condition: Some(expr), .. } => trg_extract_key_pattern(expr),
+        _ => None,
+    }
+}
+
+/// Check if a trigger has no WHEN condition, and so would watch every key of the
+/// database unless its table's key prefix scopes it
+pub fn trg_is_unfiltered(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::CreateTrigger { condition: None, .. })
+}
+
+/// Get the key glob pattern a `key LIKE 'p'` or `key = 'v'` condition selects
+pub fn trg_extract_key_pattern(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Nested(inner) => trg_extract_key_pattern(inner),
+        Expr::Like { .. } => like_glob(expr, "key"),
+        Expr::BinaryOp { left, op: BinaryOperator::Eq, right } if is_key_identifier(left) => {
+            trg_extract_value(right).map(|key| escape_glob(&key))
+        }
+        _ => None,
+    }
+}
+
+/// Get the glob pattern a `column LIKE 'p'` predicate matches (`key` here, `member` in a
+/// set or sorted set scan)
+pub fn like_glob(expr: &Expr, column: &str) -> Option<String> {
+    match expr {
+        Expr::Nested(inner) => like_glob(inner, column),
+        Expr::Like { negated: false, any: false, expr, pattern, escape_char: None }
+            if matches!(&**expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case(column)) => {
+            trg_extract_value(pattern).map(|like| like_to_glob(&like))
+        }
+        _ => None,
+    }
+}
+
+fn is_key_identifier(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key"))
+}
+
+fn trg_extract_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Value(value_with_span) => match &value_with_span.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Convert a SQL LIKE pattern into a Redis glob-style pattern.
+/// `%` becomes `*`, `_` becomes `?`, and glob metacharacters are escaped.
+pub fn like_to_glob(like: &str) -> String {
+    let mut glob = String::with_capacity(like.len());
+    let mut chars = like.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => glob.push('*'),
+            '_' => glob.push('?'),
+            '\\' => {
+                // A backslash escapes the next LIKE wildcard, which is literal in the glob too
+                if let Some(next) = chars.next() {
+                    glob.push_str(&escape_glob(&next.to_string()));
+                }
+            }
+            _ => glob.push_str(&escape_glob(&c.to_string())),
+        }
+    }
+    glob
+}
+
+/// Escape Redis glob metacharacters so a literal key matches only itself
+fn escape_glob(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -1,14 +1,20 @@
 // ast/update.rs - Pure functions for UPDATE AST node extraction
 // These functions don't modify state, just extract information from UPDATE statements
 
-use sqlparser::ast::{AssignmentTarget, Expr, ObjectNamePart, Statement, TableFactor, Value};
+use sqlparser::ast::{AssignmentTarget, Expr, ObjectNamePart, Statement, Value};
+
+use crate::expr::Case;
 
 /// Extract a string value from an SQL expression
 pub fn upd_extract_value(expr: &Expr) -> Option<String> {
+    upd_extract_value_ref(expr).map(str::to_string)
+}
+
+fn upd_extract_value_ref(expr: &Expr) -> Option<&str> {
     match expr {
         Expr::Value(value_with_span) => match &value_with_span.value {
-            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s.clone()),
-            Value::Number(n, _) => Some(n.clone()),
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s),
+            Value::Number(n, _) => Some(n),
             _ => None,
         },
         _ => None,
@@ -17,6 +23,11 @@ pub fn upd_extract_value(expr: &Expr) -> Option<String> {
 
 /// Get key condition from WHERE clause (recursively searches AND expressions)
 pub fn upd_get_key_value(stmt: &Statement) -> Option<String> {
+    upd_get_key_value_ref(stmt).map(str::to_string)
+}
+
+/// Borrowing form of `upd_get_key_value`
+pub fn upd_get_key_value_ref(stmt: &Statement) -> Option<&str> {
     match stmt {
         Statement::Update { selection, .. } => {
             selection.as_ref().and_then(upd_extract_key_from_expr)
@@ -26,14 +37,14 @@ pub fn upd_get_key_value(stmt: &Statement) -> Option<String> {
 }
 
 /// Recursively extract key = value from an expression (handles AND)
-fn upd_extract_key_from_expr(expr: &Expr) -> Option<String> {
+fn upd_extract_key_from_expr(expr: &Expr) -> Option<&str> {
     match expr {
         Expr::BinaryOp { left, op, right } => {
             match op {
                 sqlparser::ast::BinaryOperator::Eq => {
                     match &**left {
-                        Expr::Identifier(ident) if ident.value.to_lowercase() == "key" => {
-                            upd_extract_value(right)
+                        Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key") => {
+                            upd_extract_value_ref(right)
                         }
                         _ => None,
                     }
@@ -111,23 +122,32 @@ pub fn upd_get_assignments(stmt: &Statement) -> Option<Vec<(String, String)>> {
     }
 }
 
+/// Compile every SET assignment, CASE expressions included, as ordered field-case pairs.
+/// None if any assigned expression can't be compiled.
+pub fn upd_get_case_assignments(stmt: &Statement) -> Option<Vec<(String, Case)>> {
+    match stmt {
+        Statement::Update { assignments, .. } => assignments.iter()
+            .map(|assignment| match &assignment.target {
+                AssignmentTarget::ColumnName(object_name) => {
+                    let ObjectNamePart::Identifier(ident) = object_name.0.first()?;
+                    Some((ident.value.clone(), Case::compile(&assignment.value)?))
+                }
+                AssignmentTarget::Tuple(_) => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
 /// Get the table name from an UPDATE statement
 pub fn upd_get_table_name(stmt: &Statement) -> Option<String> {
+    upd_get_table_name_ref(stmt).map(str::to_string)
+}
+
+/// Borrowing form of `upd_get_table_name`, for matchers that only inspect the name
+pub fn upd_get_table_name_ref(stmt: &Statement) -> Option<&str> {
     match stmt {
-        Statement::Update { table, .. } => {
-            match &table.relation {
-                TableFactor::Table { name, .. } => {
-                    if !name.0.is_empty() {
-                        match &name.0[0] {
-                            ObjectNamePart::Identifier(ident) => Some(ident.value.clone())
-                        }
-                    } else {
-                        None
-                    }
-                },
-                _ => None,
-            }
-        },
+        Statement::Update { table, .. } => super::sel_get_relation_name(&table.relation),
         _ => None,
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+// ast/view.rs - Pure functions for CREATE VIEW AST node extraction
+// Views are materialized into derived keys; these functions read their definitions
+
+use sqlparser::ast::{ObjectNamePart, Query, Statement};
+
+/// Get the name and defining query of a CREATE VIEW statement
+pub fn view_get_definition(stmt: &Statement) -> Option<(String, &Query)> {
+    match stmt {
+        Statement::CreateView { name, query, .. } => {
+            let ObjectNamePart::Identifier(ident) = name.0.last()?;
+            Some((ident.value.clone(), query))
+        },
+        _ => None,
+    }
+}
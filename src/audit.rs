@@ -0,0 +1,149 @@
+// audit.rs - Append-only audit trail of translations and executions
+// One record per generated command: when, which SQL (by hash), which rule, what ran, and how it went
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::lua::script_sha1;
+
+/// What happened to a generated command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutcome {
+    /// Generated but not sent to a server
+    Translated,
+    /// Executed; the server replied without error
+    Succeeded,
+    /// Executed; the server or connection reported this error
+    Failed(String),
+}
+
+/// One audited command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// Unix time in milliseconds
+    pub timestamp: u128,
+    /// SHA-1 of the input SQL, so the log doesn't retain literal values
+    pub sql_hash: String,
+    /// Template name of the matched rule, or "fallback" for the built-in generator
+    pub rule: String,
+    pub command: String,
+    pub outcome: AuditOutcome,
+    /// Comments annotating the statement in the source SQL (`-- owner: billing-team`)
+    pub annotations: Vec<String>,
+}
+
+impl AuditRecord {
+    /// A record stamped with the current time
+    pub fn new(sql: &str, rule: &str, command: &str, outcome: AuditOutcome) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis())
+            .unwrap_or(0);
+        Self {
+            timestamp,
+            sql_hash: script_sha1(sql),
+            rule: rule.to_string(),
+            command: command.to_string(),
+            outcome,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Attach the statement's source comments
+    pub fn with_annotations(mut self, annotations: &[String]) -> Self {
+        self.annotations = annotations.to_vec();
+        self
+    }
+
+    /// The record as one line of JSON (no trailing newline)
+    pub fn to_json(&self) -> String {
+        let (outcome, error) = match &self.outcome {
+            AuditOutcome::Translated => ("translated", None),
+            AuditOutcome::Succeeded => ("ok", None),
+            AuditOutcome::Failed(error) => ("error", Some(error)),
+        };
+        let mut json = format!(
+            "{{\"timestamp\":{},\"sql_hash\":\"{}\",\"rule\":{},\"command\":{},\"outcome\":\"{}\"",
+            self.timestamp,
+            self.sql_hash,
+            json_string(&self.rule),
+            json_string(&self.command),
+            outcome,
+        );
+        if let Some(error) = error {
+            json.push_str(&format!(",\"error\":{}", json_string(error)));
+        }
+        if !self.annotations.is_empty() {
+            let annotations: Vec<String> = self.annotations.iter().map(|note| json_string(note)).collect();
+            json.push_str(&format!(",\"annotations\":[{}]", annotations.join(",")));
+        }
+        json.push('}');
+        json
+    }
+}
+
+/// Where audit records go: an append-only JSONL file, or a caller-supplied callback
+pub enum AuditSink {
+    File(Mutex<File>),
+    Callback(Box<dyn Fn(&AuditRecord) + Send + Sync>),
+}
+
+impl AuditSink {
+    /// Append records to `path` as JSON lines, creating the file if needed
+    pub fn jsonl(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditSink::File(Mutex::new(file)))
+    }
+
+    /// Hand each record to `callback`
+    pub fn callback(callback: impl Fn(&AuditRecord) + Send + Sync + 'static) -> Self {
+        AuditSink::Callback(Box::new(callback))
+    }
+
+    /// Write a record; file records are flushed before returning
+    pub fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        match self {
+            AuditSink::File(file) => {
+                let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+                writeln!(file, "{}", record.to_json())?;
+                file.flush()
+            }
+            AuditSink::Callback(callback) => {
+                callback(record);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for AuditSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditSink::File(file) => f.debug_tuple("File").field(file).finish(),
+            AuditSink::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+/// Quote a string as a JSON string literal
+pub(crate) fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
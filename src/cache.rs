@@ -0,0 +1,131 @@
+// cache.rs - Cache-aside recipes for reads of hash and string tables
+// A CachePlan pairs the Redis read with how to spot a miss and how to populate the key from SQL
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::commands::RedisCommand;
+use crate::reply::{Reply, Row};
+
+/// How a cache miss shows up in the read's reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMiss {
+    /// GET / HGET reply nil
+    Nil,
+    /// HGETALL replies an empty array
+    EmptyHash,
+    /// HMGET replies nil for every field
+    AllNil,
+}
+
+impl CacheMiss {
+    /// Check whether a reply to the plan's read means the key isn't cached
+    pub fn is_miss(&self, reply: &Reply) -> bool {
+        match (self, reply) {
+            (CacheMiss::Nil, Reply::Nil) => true,
+            (CacheMiss::EmptyHash, Reply::Array(items)) => items.is_empty(),
+            (CacheMiss::AllNil, Reply::Array(items)) => items.iter().all(|item| *item == Reply::Nil),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for CacheMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CacheMiss::Nil => "nil reply",
+            CacheMiss::EmptyHash => "empty array reply",
+            CacheMiss::AllNil => "every field nil",
+        })
+    }
+}
+
+/// A cache-aside recipe: run `read`; on a `miss`, load the row from SQL and run `populate`.
+/// Populate arguments written `{column}` stand for that column of the SQL row, and `{*}`
+/// for every column as field/value pairs (`SELECT *` on a hash); `fill` substitutes them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePlan {
+    pub key: String,
+    pub read: RedisCommand,
+    pub miss: CacheMiss,
+    pub populate: Vec<RedisCommand>,
+}
+
+impl CachePlan {
+    /// Build the recipe for a translated read, or None if it isn't a single-key GET, HGET,
+    /// HMGET, or HGETALL. Keys expire after `ttl` (rounded up to whole seconds).
+    pub fn for_read(read: &RedisCommand, ttl: Duration) -> Option<Self> {
+        let key = read.args.first()?.clone();
+        let seconds = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+        let seconds = seconds.max(1).to_string();
+        let expire = RedisCommand::new("EXPIRE", vec![key.clone(), seconds.clone()]);
+        let field_values = |fields: &[String]| -> Vec<String> {
+            std::iter::once(key.clone())
+                .chain(fields.iter().flat_map(|field| [field.clone(), format!("{{{}}}", field)]))
+                .collect()
+        };
+
+        let (miss, populate) = match (read.command.to_uppercase().as_str(), read.args.len()) {
+            ("GET", 1) => (CacheMiss::Nil, vec![
+                RedisCommand::new("SET", vec![key.clone(), "{value}".to_string(), "EX".to_string(), seconds]),
+            ]),
+            ("HGET", 2) => (CacheMiss::Nil, vec![
+                RedisCommand::new("HSET", field_values(&read.args[1..])),
+                expire,
+            ]),
+            ("HMGET", n) if n > 1 => (CacheMiss::AllNil, vec![
+                RedisCommand::new("HSET", field_values(&read.args[1..])),
+                expire,
+            ]),
+            ("HGETALL", 1) => (CacheMiss::EmptyHash, vec![
+                RedisCommand::new("HSET", vec![key.clone(), "{*}".to_string()]),
+                expire,
+            ]),
+            _ => return None,
+        };
+        Some(Self { key, read: read.clone(), miss, populate })
+    }
+
+    /// The populate commands for a row loaded from SQL; columns the row lacks are
+    /// left out of HSET (and written as empty strings by SET)
+    pub fn fill(&self, row: &Row) -> Vec<RedisCommand> {
+        let column = |name: &str| row.iter().find(|(column, _)| column.eq_ignore_ascii_case(name)).map(|(_, value)| value);
+        self.populate.iter()
+            .map(|command| {
+                let mut args = Vec::with_capacity(command.args.len());
+                for arg in &command.args {
+                    match placeholder(arg) {
+                        Some("*") => args.extend(row.iter().flat_map(|(column, value)| [column.clone(), value.clone()])),
+                        // HSET field {field}: keep the pair only if the row has the column
+                        Some(name) if command.command == "HSET" => {
+                            match column(name) {
+                                Some(value) => args.push(value.clone()),
+                                None => { args.pop(); }
+                            }
+                        }
+                        Some(name) => args.push(column(name).cloned().unwrap_or_default()),
+                        None => args.push(arg.clone()),
+                    }
+                }
+                RedisCommand::new(&command.command, args)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for CachePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "read: {}", self.read.to_quoted_string())?;
+        writeln!(f, "miss: {}", self.miss)?;
+        write!(f, "populate:")?;
+        for command in &self.populate {
+            write!(f, "\n  {}", command.to_quoted_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// The column name of a `{column}` placeholder
+fn placeholder(arg: &str) -> Option<&str> {
+    arg.strip_prefix('{')?.strip_suffix('}')
+}
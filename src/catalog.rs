@@ -0,0 +1,137 @@
+// catalog.rs - Definitions the transformer remembers across statements
+// Views are materialized into derived keys: CREATE VIEW refreshes one, SELECT from the view reads it.
+// Tables may declare a value codec that serializes their inserted rows.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use sqlparser::ast::Query;
+
+use crate::ast;
+use crate::codec::ValueCodec;
+use crate::commands::RedisCommand;
+use crate::lua;
+use crate::types::{redis_type_of, RedisDataType, TypeMapping};
+
+/// How a view's derived key stores its rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewStorage {
+    /// A list filled by a Lua refresh (ZRANGE/ZREVRANGE, DEL, RPUSH)
+    List,
+    /// A sorted set filled by ZRANGESTORE (Redis 6.2+), keeping scores
+    SortedSet,
+}
+
+/// A view over the top (or bottom) members of a sorted set:
+/// `CREATE VIEW top10 AS SELECT * FROM scores__zset WHERE key = 'game:1' ORDER BY score DESC LIMIT 10`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct View {
+    pub name: String,
+    /// The sorted set the view reads
+    pub source: String,
+    /// The derived key holding the materialized rows, `view:{name}`
+    pub key: String,
+    pub limit: u64,
+    pub descending: bool,
+    pub storage: ViewStorage,
+}
+
+impl View {
+    /// Define a view from its query, or None if the query isn't a keyed read of a
+    /// sorted set ordered by score with a LIMIT
+    pub fn from_query(name: &str, query: &Query, storage: ViewStorage) -> Option<Self> {
+        let select = ast::sel_get_select(query)?;
+        if redis_type_of(&ast::sel_get_table_name(select)?, TypeMapping::standard()) != RedisDataType::SortedSet
+            || !ast::sel_is_order_by_score(query)
+            || ast::sel_get_score_range(&select.selection).is_some()
+        {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            source: ast::sel_get_key_value(&select.selection)?,
+            key: format!("view:{}", name),
+            limit: ast::sel_get_limit(query).filter(|limit| *limit > 0)?,
+            descending: ast::sel_is_order_by_score_desc(query),
+            storage,
+        })
+    }
+
+    /// The command text that recomputes the derived key from the source
+    pub fn refresh(&self) -> String {
+        match self.storage {
+            ViewStorage::List => lua::view_refresh(&self.key, &self.source, self.read(), self.limit - 1),
+            ViewStorage::SortedSet => self.refresh_command().to_quoted_string(),
+        }
+    }
+
+    /// The refresh as a structured command
+    pub fn refresh_command(&self) -> RedisCommand {
+        let stop = (self.limit - 1).to_string();
+        match self.storage {
+            ViewStorage::List => RedisCommand::new("EVAL", vec![
+                lua::VIEW_REFRESH_SCRIPT, "2", &self.key, &self.source, self.read(), &stop,
+            ]),
+            ViewStorage::SortedSet => {
+                let mut args = vec![self.key.as_str(), &self.source, "0", &stop];
+                if self.descending {
+                    args.push("REV");
+                }
+                RedisCommand::new("ZRANGESTORE", args)
+            }
+        }
+    }
+
+    /// The sorted set read that lists the source's members in view order
+    fn read(&self) -> &'static str {
+        if self.descending { "ZREVRANGE" } else { "ZRANGE" }
+    }
+
+    /// The command that reads the materialized rows in view order
+    pub fn read_command(&self) -> RedisCommand {
+        match self.storage {
+            ViewStorage::List => RedisCommand::new("LRANGE", vec![self.key.as_str(), "0", "-1"]),
+            ViewStorage::SortedSet if self.descending => RedisCommand::new("ZREVRANGE", vec![self.key.as_str(), "0", "-1"]),
+            ViewStorage::SortedSet => RedisCommand::new("ZRANGE", vec![self.key.as_str(), "0", "-1"]),
+        }
+    }
+}
+
+/// Views defined so far, and the value codecs of tables, by lowercase name
+#[derive(Debug, Default)]
+pub struct Catalog {
+    views: RwLock<BTreeMap<String, View>>,
+    codecs: RwLock<BTreeMap<String, Arc<dyn ValueCodec>>>,
+}
+
+impl Catalog {
+    /// Add a view, replacing any view of the same name
+    pub fn define_view(&self, view: View) {
+        let mut views = self.views.write().unwrap_or_else(|e| e.into_inner());
+        views.insert(view.name.to_lowercase(), view);
+    }
+
+    /// Look up a view by name (case-insensitive)
+    pub fn view(&self, name: &str) -> Option<View> {
+        let views = self.views.read().unwrap_or_else(|e| e.into_inner());
+        views.get(&name.to_lowercase()).cloned()
+    }
+
+    /// Every defined view, by name
+    pub fn views(&self) -> Vec<View> {
+        let views = self.views.read().unwrap_or_else(|e| e.into_inner());
+        views.values().cloned().collect()
+    }
+    
+    /// Serialize the rows inserted into `table` with `codec`, replacing any earlier choice
+    pub fn define_codec(&self, table: &str, codec: Arc<dyn ValueCodec>) {
+        let mut codecs = self.codecs.write().unwrap_or_else(|e| e.into_inner());
+        codecs.insert(table.to_lowercase(), codec);
+    }
+    
+    /// The codec declared for a table (case-insensitive)
+    pub fn codec(&self, table: &str) -> Option<Arc<dyn ValueCodec>> {
+        let codecs = self.codecs.read().unwrap_or_else(|e| e.into_inner());
+        codecs.get(&table.to_lowercase()).cloned()
+    }
+}
@@ -0,0 +1,90 @@
+// chain.rs - Fallback chain of transformers
+// Tries an ordered list of transformers (e.g. strict built-ins, then plugin rules, then a
+// permissive profile) and reports which layer produced the translation
+
+use crate::commands::RedisCommand;
+use crate::error::SqlRedisError;
+use crate::warning::Warning;
+use crate::SqlToRedisTransformer;
+
+/// A translation and the chain layer that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainResult<T> {
+    /// Position of the layer in the chain
+    pub layer: usize,
+    /// Name the layer was added under
+    pub name: String,
+    pub output: T,
+}
+
+/// Transformers tried in order until one translates the statement
+#[derive(Default)]
+pub struct TransformerChain {
+    layers: Vec<(String, SqlToRedisTransformer)>,
+}
+
+impl TransformerChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a layer, tried after the layers added before it
+    pub fn with_layer(mut self, name: &str, transformer: SqlToRedisTransformer) -> Self {
+        self.layers.push((name.to_string(), transformer));
+        self
+    }
+
+    /// Names of the layers, in the order they are tried
+    pub fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The transformer of the named layer
+    pub fn layer(&self, name: &str) -> Option<&SqlToRedisTransformer> {
+        self.layers.iter().find(|(layer, _)| layer == name).map(|(_, transformer)| transformer)
+    }
+
+    pub fn transform(&self, sql: &str) -> Result<ChainResult<String>, SqlRedisError> {
+        self.first(|transformer| transformer.transform(sql))
+    }
+
+    /// Transform with the first layer that can, along with that layer's warnings
+    pub fn transform_with_report(&self, sql: &str) -> Result<ChainResult<(String, Vec<Warning>)>, SqlRedisError> {
+        self.first(|transformer| transformer.transform_with_report(sql))
+    }
+
+    pub fn transform_to_commands(&self, sql: &str) -> Result<ChainResult<Vec<RedisCommand>>, SqlRedisError> {
+        self.first(|transformer| transformer.transform_to_commands(sql))
+    }
+
+    /// The first layer's output; a layer that can't translate the statement (see
+    /// `falls_through`) passes it on, and other errors stop the chain. When every layer
+    /// passes, the last layer's error is returned.
+    fn first<T>(&self, translate: impl Fn(&SqlToRedisTransformer) -> Result<T, SqlRedisError>) -> Result<ChainResult<T>, SqlRedisError> {
+        let mut last_error = SqlRedisError::NoMatchingPattern("no transformer in the chain".to_string());
+        for (layer, (name, transformer)) in self.layers.iter().enumerate() {
+            match translate(transformer) {
+                Ok(output) => return Ok(ChainResult { layer, name: name.clone(), output }),
+                Err(error) if falls_through(&error) => last_error = error,
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Whether an error means a layer doesn't support the statement, so a later layer may:
+/// no rule matched, strict mode rejected dropped clauses, or the schema/predicate/join is
+/// outside what the layer was configured for. Parse and rendering errors stop the chain.
+pub fn falls_through(error: &SqlRedisError) -> bool {
+    matches!(
+        error,
+        SqlRedisError::NoMatchingPattern(_)
+            | SqlRedisError::MissingKeyPredicate(_)
+            | SqlRedisError::UnsupportedClause(_)
+            | SqlRedisError::UnsupportedPredicate(_)
+            | SqlRedisError::UnsupportedJoin(_)
+            | SqlRedisError::UnknownSchema(..)
+            | SqlRedisError::CommandTooLarge(_)
+    )
+}
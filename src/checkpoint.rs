@@ -0,0 +1,66 @@
+// checkpoint.rs - Progress records for resumable batch execution
+// A sidecar file holds how many statements were applied and a hash of the last one,
+// so a resumed run can skip them and detect an input file that changed in between
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How far a batch got: `offset` statements applied, the last of which hashes to `hash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub offset: usize,
+    pub hash: u64,
+}
+
+impl Checkpoint {
+    /// Record that `offset` statements were applied, `last_statement` being the last
+    pub fn new(offset: usize, last_statement: &str) -> Self {
+        Self { offset, hash: statement_hash(last_statement) }
+    }
+
+    /// Sidecar path for an input file: `migration.sql` -> `migration.sql.checkpoint`
+    pub fn sidecar_path(input: &Path) -> PathBuf {
+        let mut path = input.as_os_str().to_owned();
+        path.push(".checkpoint");
+        PathBuf::from(path)
+    }
+
+    /// Read a checkpoint; Ok(None) if the sidecar doesn't exist
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("Malformed checkpoint: {}", path.display()));
+        let (offset, hash) = text.trim().split_once(' ').ok_or_else(invalid)?;
+        Ok(Some(Self {
+            offset: offset.parse().map_err(|_| invalid())?,
+            hash: u64::from_str_radix(hash, 16).map_err(|_| invalid())?,
+        }))
+    }
+
+    /// Write the checkpoint atomically (temp file + rename), so a crash never leaves it torn
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        fs::write(&temp, format!("{} {:016x}\n", self.offset, self.hash))?;
+        fs::rename(&temp, path)
+    }
+
+    /// Check that `statements` (the batch being resumed) still starts with the applied ones
+    pub fn matches<S: AsRef<str>>(&self, statements: &[S]) -> bool {
+        match self.offset {
+            0 => true,
+            n => statements.get(n - 1).is_some_and(|last| statement_hash(last.as_ref()) == self.hash),
+        }
+    }
+}
+
+/// FNV-1a over the statement text; stable across platforms and Rust versions
+fn statement_hash(statement: &str) -> u64 {
+    statement.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -0,0 +1,133 @@
+// cluster.rs - Redis Cluster key slots for a SQL workload
+// Maps every key the generated commands touch to its CRC16 slot and reports how the
+// workload spreads over the 16384 slots and N evenly sized nodes
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::commands::RedisCommand;
+
+/// Number of hash slots in a Redis Cluster
+pub const SLOTS: u16 = 16384;
+
+/// The slot Redis Cluster assigns `key`: CRC16 of its hash tag (the part between the
+/// first `{` and the next `}`, if non-empty) or of the whole key, modulo 16384
+pub fn key_slot(key: &str) -> u16 {
+    let hashed = key.find('{')
+        .and_then(|open| {
+            let tag = &key[open + 1..];
+            tag.find('}').filter(|&len| len > 0).map(|len| &tag[..len])
+        })
+        .unwrap_or(key);
+    crc16(hashed.as_bytes()) % SLOTS
+}
+
+/// CRC16-CCITT (XMODEM), the checksum Redis Cluster hashes keys with
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 }
+        })
+    })
+}
+
+/// Key references of a workload, grouped by slot
+#[derive(Debug, Clone, Default)]
+pub struct SlotReport {
+    /// Slot -> (references, distinct keys)
+    slots: BTreeMap<u16, (usize, BTreeSet<String>)>,
+}
+
+impl SlotReport {
+    /// Count the keys `command` addresses
+    pub fn record(&mut self, command: &RedisCommand) {
+        for i in command.key_indices() {
+            let key = &command.args[i];
+            let (references, keys) = self.slots.entry(key_slot(key)).or_default();
+            *references += 1;
+            keys.insert(key.clone());
+        }
+    }
+
+    /// Total key references recorded
+    pub fn references(&self) -> usize {
+        self.slots.values().map(|(references, _)| references).sum()
+    }
+
+    /// Distinct keys recorded
+    pub fn keys(&self) -> usize {
+        self.slots.values().map(|(_, keys)| keys.len()).sum()
+    }
+
+    /// Slots with at least one key: (slot, references, distinct keys)
+    pub fn slots(&self) -> impl Iterator<Item = (u16, usize, &BTreeSet<String>)> {
+        self.slots.iter().map(|(slot, (references, keys))| (*slot, *references, keys))
+    }
+
+    /// Slots holding at least `percent` of all references, busiest first
+    pub fn hot_slots(&self, percent: f64) -> Vec<(u16, usize, &BTreeSet<String>)> {
+        let total = self.references();
+        let mut hot: Vec<_> = self.slots()
+            .filter(|(_, references, _)| total > 0 && share(*references, total) >= percent)
+            .collect();
+        hot.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hot
+    }
+
+    /// Per node, for `nodes` nodes owning equal contiguous slot ranges:
+    /// (first slot, last slot, references, distinct keys)
+    pub fn nodes(&self, nodes: u16) -> Vec<(u16, u16, usize, usize)> {
+        let nodes = nodes.clamp(1, SLOTS);
+        (0..nodes)
+            .map(|node| {
+                let first = (u32::from(node) * u32::from(SLOTS) / u32::from(nodes)) as u16;
+                let last = (u32::from(node + 1) * u32::from(SLOTS) / u32::from(nodes) - 1) as u16;
+                let (references, keys) = self.slots.range(first..=last)
+                    .fold((0, 0), |(references, keys), (_, (r, k))| (references + r, keys + k.len()));
+                (first, last, references, keys)
+            })
+            .collect()
+    }
+
+    /// The report as printed by `sqlnosql slots`: nodes, then slots over `hot_percent`
+    pub fn display(&self, nodes: u16, hot_percent: f64) -> SlotReportDisplay<'_> {
+        SlotReportDisplay { report: self, nodes, hot_percent }
+    }
+}
+
+/// Percentage of `total` that `part` is
+fn share(part: usize, total: usize) -> f64 {
+    part as f64 * 100.0 / total as f64
+}
+
+/// Text rendering of a `SlotReport` for a node count and hot-slot threshold
+pub struct SlotReportDisplay<'a> {
+    report: &'a SlotReport,
+    nodes: u16,
+    hot_percent: f64,
+}
+
+impl fmt::Display for SlotReportDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.report.references();
+        writeln!(
+            f,
+            "{} distinct keys, {} references across {} of {} slots",
+            self.report.keys(), total, self.report.slots.len(), SLOTS,
+        )?;
+        for (node, (first, last, references, keys)) in self.report.nodes(self.nodes).into_iter().enumerate() {
+            let percent = if total > 0 { share(references, total) } else { 0.0 };
+            writeln!(f, "node {} (slots {}-{}): {} keys, {} references ({:.1}%)", node + 1, first, last, keys, references, percent)?;
+        }
+        let hot = self.report.hot_slots(self.hot_percent);
+        if hot.is_empty() {
+            return write!(f, "no hot slots (>= {:.1}% of references)", self.hot_percent);
+        }
+        write!(f, "hot slots (>= {:.1}% of references):", self.hot_percent)?;
+        for (slot, references, keys) in hot {
+            let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+            write!(f, "\n  slot {}: {} references ({:.1}%): {}", slot, references, share(references, total), keys.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+// codec.rs - Value serialization formats for inserted rows
+// A table's codec (declared in the catalog) turns the columns of an inserted row into the one
+// value its key stores: plain text, a JSON document, or a MessagePack map, and back into columns
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::audit::json_string;
+
+/// An inserted column value, typed as its SQL literal was written
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Text(String),
+    /// A numeric literal, as written
+    Number(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Scalar {
+    /// The value as plain text (NULL is empty)
+    pub fn to_text(&self) -> String {
+        match self {
+            Scalar::Text(text) | Scalar::Number(text) => text.clone(),
+            Scalar::Boolean(value) => value.to_string(),
+            Scalar::Null => String::new(),
+        }
+    }
+}
+
+/// Serializes inserted rows for storage. Implement it for a format of your own and declare
+/// it for a table with `Catalog::define_codec`.
+pub trait ValueCodec: fmt::Debug + Send + Sync {
+    /// Lowercase name (`plain`, `json`, `msgpack`)
+    fn name(&self) -> &str;
+
+    /// Serialize a row's columns, key excluded and in SQL order, into the value a string
+    /// key stores; None if the format can't hold the row
+    fn encode_row(&self, columns: &[(String, Scalar)]) -> Option<Vec<u8>>;
+
+    /// Serialize one column's value, as a hash stores it under its field
+    fn encode_value(&self, value: &Scalar) -> Vec<u8>;
+
+    /// Whether encoded values are binary rather than UTF-8 text
+    fn is_binary(&self) -> bool {
+        false
+    }
+
+    /// Read a stored row back into its columns; None if the bytes don't hold one, or the
+    /// format isn't decoded (the value is then shown as stored)
+    fn decode_row(&self, _bytes: &[u8]) -> Option<Vec<(String, Scalar)>> {
+        None
+    }
+
+    /// Read a stored hash field value back; None as for `decode_row`
+    fn decode_value(&self, _bytes: &[u8]) -> Option<Scalar> {
+        None
+    }
+}
+
+/// How binary values are spelled in text: in rendered commands and in the replies read back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BinaryEncoding {
+    /// Lowercase hex, two digits a byte
+    #[default]
+    Hex,
+    /// Standard base64 with padding
+    Base64,
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl BinaryEncoding {
+    /// Lowercase name used by the CLI
+    pub fn name(&self) -> &'static str {
+        match self {
+            BinaryEncoding::Hex => "hex",
+            BinaryEncoding::Base64 => "base64",
+        }
+    }
+
+    /// Look an encoding up by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        [BinaryEncoding::Hex, BinaryEncoding::Base64]
+            .into_iter()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            BinaryEncoding::Hex => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            BinaryEncoding::Base64 => bytes.chunks(3)
+                .flat_map(|chunk| {
+                    let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| group | (*byte as u32) << (16 - 8 * i));
+                    (0..4).map(move |i| match i <= chunk.len() {
+                        true => BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char,
+                        false => '=',
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// The bytes `encode` spelled as `text`; None if it isn't in this encoding
+    pub fn decode(&self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            BinaryEncoding::Hex => {
+                if !text.len().is_multiple_of(2) {
+                    return None;
+                }
+                (0..text.len()).step_by(2).map(|at| u8::from_str_radix(text.get(at..at + 2)?, 16).ok()).collect()
+            }
+            BinaryEncoding::Base64 => {
+                if !text.len().is_multiple_of(4) {
+                    return None;
+                }
+                let mut out = Vec::with_capacity(text.len() / 4 * 3);
+                for (at, chunk) in text.as_bytes().chunks(4).enumerate() {
+                    let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+                    if padding > 2 || (padding > 0 && (at + 1) * 4 != text.len()) {
+                        return None;
+                    }
+                    let group = chunk[..4 - padding].iter().enumerate().try_fold(0u32, |group, (i, c)| {
+                        let digit = BASE64_ALPHABET.iter().position(|a| a == c)? as u32;
+                        Some(group | digit << (18 - 6 * i))
+                    })?;
+                    out.extend(group.to_be_bytes()[1..4 - padding].iter());
+                }
+                Some(out)
+            }
+        }
+    }
+}
+
+/// Render encoded bytes as a command argument: text as is, binary in `encoding`
+pub fn to_argument(codec: &dyn ValueCodec, bytes: &[u8], encoding: BinaryEncoding) -> String {
+    match codec.is_binary() {
+        true => encoding.encode(bytes),
+        false => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// The stored bytes of a value read back: binary values spelled in `encoding` (as written
+/// by the text output) are decoded, other values (raw bytes loaded over RESP) are kept
+pub fn from_argument(codec: &dyn ValueCodec, value: &[u8], encoding: BinaryEncoding) -> Vec<u8> {
+    let spelled = std::str::from_utf8(value).ok().filter(|_| codec.is_binary());
+    spelled.and_then(|text| encoding.decode(text)).unwrap_or_else(|| value.to_vec())
+}
+
+/// Look up a built-in codec by name: `plain`, `json`, or `msgpack` (`messagepack`)
+pub fn codec_by_name(name: &str) -> Option<Arc<dyn ValueCodec>> {
+    match name.to_lowercase().as_str() {
+        "plain" => Some(Arc::new(PlainCodec)),
+        "json" => Some(Arc::new(JsonCodec)),
+        "msgpack" | "messagepack" => Some(Arc::new(MessagePackCodec)),
+        _ => None,
+    }
+}
+
+/// Values stored as written: a string key holds a single column, a hash field its text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainCodec;
+
+impl ValueCodec for PlainCodec {
+    fn name(&self) -> &str {
+        "plain"
+    }
+
+    fn encode_row(&self, columns: &[(String, Scalar)]) -> Option<Vec<u8>> {
+        match columns {
+            [(_, value)] => Some(value.to_text().into_bytes()),
+            _ => None,
+        }
+    }
+
+    fn encode_value(&self, value: &Scalar) -> Vec<u8> {
+        value.to_text().into_bytes()
+    }
+}
+
+/// Rows as JSON objects of their columns; numbers, booleans, and NULL keep their JSON types
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl ValueCodec for JsonCodec {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn encode_row(&self, columns: &[(String, Scalar)]) -> Option<Vec<u8>> {
+        let members: Vec<String> = columns.iter()
+            .map(|(name, value)| format!("{}:{}", json_string(name), json_value(value)))
+            .collect();
+        Some(format!("{{{}}}", members.join(",")).into_bytes())
+    }
+
+    fn encode_value(&self, value: &Scalar) -> Vec<u8> {
+        json_value(value).into_bytes()
+    }
+}
+
+/// A JSON literal; numbers JSON can't spell as written (`.5`, `1e400`) are normalized or quoted
+fn json_value(value: &Scalar) -> String {
+    match value {
+        Scalar::Text(text) => json_string(text),
+        Scalar::Number(number) => match (number.parse::<i64>(), number.parse::<f64>()) {
+            (Ok(integer), _) => integer.to_string(),
+            (_, Ok(float)) if float.is_finite() => float.to_string(),
+            _ => json_string(number),
+        },
+        Scalar::Boolean(value) => value.to_string(),
+        Scalar::Null => "null".to_string(),
+    }
+}
+
+/// Rows as MessagePack maps of their columns: integers in their smallest encoding, other
+/// numbers as float 64, text as str
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackCodec;
+
+impl ValueCodec for MessagePackCodec {
+    fn name(&self) -> &str {
+        "msgpack"
+    }
+
+    fn encode_row(&self, columns: &[(String, Scalar)]) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        match columns.len() {
+            len if len < 16 => out.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                out.push(0xde);
+                out.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                out.push(0xdf);
+                out.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+        }
+        for (name, value) in columns {
+            msgpack_str(&mut out, name);
+            msgpack_scalar(&mut out, value);
+        }
+        Some(out)
+    }
+
+    fn encode_value(&self, value: &Scalar) -> Vec<u8> {
+        let mut out = Vec::new();
+        msgpack_scalar(&mut out, value);
+        out
+    }
+
+    fn is_binary(&self) -> bool {
+        true
+    }
+
+    fn decode_row(&self, bytes: &[u8]) -> Option<Vec<(String, Scalar)>> {
+        let mut reader = MessagePackReader { rest: bytes };
+        let len = match reader.byte()? {
+            marker @ 0x80..=0x8f => (marker & 0x0f) as usize,
+            0xde => reader.uint(2)? as usize,
+            0xdf => reader.uint(4)? as usize,
+            _ => return None,
+        };
+        let columns = (0..len)
+            .map(|_| match reader.scalar()? {
+                Scalar::Text(name) => Some((name, reader.scalar()?)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        reader.rest.is_empty().then_some(columns)
+    }
+
+    fn decode_value(&self, bytes: &[u8]) -> Option<Scalar> {
+        let mut reader = MessagePackReader { rest: bytes };
+        let value = reader.scalar()?;
+        reader.rest.is_empty().then_some(value)
+    }
+}
+
+/// Reads MessagePack scalars (nil, booleans, integers, floats, strings, binaries) off a buffer
+struct MessagePackReader<'a> {
+    rest: &'a [u8],
+}
+
+impl MessagePackReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        let (taken, rest) = self.rest.split_at_checked(len)?;
+        self.rest = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    /// A big-endian unsigned integer of `len` bytes
+    fn uint(&mut self, len: usize) -> Option<u64> {
+        Some(self.take(len)?.iter().fold(0, |value, byte| value << 8 | *byte as u64))
+    }
+
+    /// A big-endian signed integer of `len` bytes
+    fn int(&mut self, len: usize) -> Option<i64> {
+        let shift = 64 - 8 * len as u32;
+        Some(((self.uint(len)? << shift) as i64) >> shift)
+    }
+
+    fn text(&mut self, len: usize) -> Option<Scalar> {
+        Some(Scalar::Text(String::from_utf8_lossy(self.take(len)?).into_owned()))
+    }
+
+    fn scalar(&mut self) -> Option<Scalar> {
+        let number = |value: String| Some(Scalar::Number(value));
+        match self.byte()? {
+            marker @ 0x00..=0x7f => number(marker.to_string()),
+            marker @ 0xe0..=0xff => number((marker as i8).to_string()),
+            marker @ 0xa0..=0xbf => self.text((marker & 0x1f) as usize),
+            0xc0 => Some(Scalar::Null),
+            0xc2 => Some(Scalar::Boolean(false)),
+            0xc3 => Some(Scalar::Boolean(true)),
+            0xcc => number(self.uint(1)?.to_string()),
+            0xcd => number(self.uint(2)?.to_string()),
+            0xce => number(self.uint(4)?.to_string()),
+            0xcf => number(self.uint(8)?.to_string()),
+            0xd0 => number(self.int(1)?.to_string()),
+            0xd1 => number(self.int(2)?.to_string()),
+            0xd2 => number(self.int(4)?.to_string()),
+            0xd3 => number(self.int(8)?.to_string()),
+            0xca => number(f32::from_bits(self.uint(4)? as u32).to_string()),
+            0xcb => number(f64::from_bits(self.uint(8)?).to_string()),
+            0xd9 | 0xc4 => {
+                let len = self.uint(1)? as usize;
+                self.text(len)
+            }
+            0xda | 0xc5 => {
+                let len = self.uint(2)? as usize;
+                self.text(len)
+            }
+            0xdb | 0xc6 => {
+                let len = self.uint(4)? as usize;
+                self.text(len)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn msgpack_scalar(out: &mut Vec<u8>, value: &Scalar) {
+    match value {
+        Scalar::Text(text) => msgpack_str(out, text),
+        Scalar::Number(number) => match (number.parse::<i64>(), number.parse::<f64>()) {
+            (Ok(integer), _) => msgpack_int(out, integer),
+            (_, Ok(float)) => {
+                out.push(0xcb);
+                out.extend_from_slice(&float.to_be_bytes());
+            }
+            _ => msgpack_str(out, number),
+        },
+        Scalar::Boolean(value) => out.push(if *value { 0xc3 } else { 0xc2 }),
+        Scalar::Null => out.push(0xc0),
+    }
+}
+
+fn msgpack_str(out: &mut Vec<u8>, text: &str) {
+    let len = text.len();
+    match len {
+        len if len < 32 => out.push(0xa0 | len as u8),
+        len if len <= u8::MAX as usize => out.extend_from_slice(&[0xd9, len as u8]),
+        len if len <= u16::MAX as usize => {
+            out.push(0xda);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(0xdb);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn msgpack_int(out: &mut Vec<u8>, value: i64) {
+    match value {
+        0..=0x7f => out.push(value as u8),
+        -32..=-1 => out.push(value as i8 as u8),
+        0x80..=0xff => out.extend_from_slice(&[0xcc, value as u8]),
+        0x100..=0xffff => {
+            out.push(0xcd);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0xce);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        0x1_0000_0000.. => {
+            out.push(0xcf);
+            out.extend_from_slice(&(value as u64).to_be_bytes());
+        }
+        -0x80..=-33 => out.extend_from_slice(&[0xd0, value as i8 as u8]),
+        -0x8000..=-0x81 => {
+            out.push(0xd1);
+            out.extend_from_slice(&(value as i16).to_be_bytes());
+        }
+        -0x8000_0000..=-0x8001 => {
+            out.push(0xd2);
+            out.extend_from_slice(&(value as i32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xd3);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
@@ -1,12 +1,18 @@
 // commands.rs - Redis command generation
 use sqlparser::ast::Statement;
 use crate::pattern::extractors;
+use crate::lua::script_sha1;
+use crate::codec::BinaryEncoding;
+use crate::types::{redis_type_of, TypeMapping};
 
 /// A Redis command with its arguments
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RedisCommand {
    pub command: String,
    pub args: Vec<String>,
+   /// Indices into `args` of binary values (such as MessagePack rows), which `args` holds in
+   /// their text encoding; `to_resp_bytes` writes them as raw bytes
+   pub binary: Vec<usize>,
 }
 
 impl RedisCommand {
@@ -15,9 +21,88 @@ impl RedisCommand {
        Self {
            command: command.into(),
            args: args.into_iter().map(|a| a.into()).collect(),
+           binary: Vec::new(),
        }
    }
 
+   /// Mark the arguments at `indices` as binary values
+   pub fn with_binary_args(mut self, indices: Vec<usize>) -> Self {
+       self.binary = indices;
+       self
+   }
+
+   /// Append `source`'s argument at `index`, keeping its binary mark
+   pub(crate) fn push_arg_from(&mut self, source: &RedisCommand, index: usize) {
+       if source.binary.contains(&index) {
+           self.binary.push(self.args.len());
+       }
+       self.args.push(source.args[index].clone());
+   }
+
+   /// Parse a rendered command line back into a command and its arguments.
+   /// Arguments may be single-quoted (with `''` as an escaped quote, as in EVAL
+   /// scripts) or double-quoted (with backslash escapes), redis-cli style.
+   pub fn parse(line: &str) -> Option<Self> {
+       let mut tokens = Vec::new();
+       let mut chars = line.chars().peekable();
+       
+       while let Some(&c) = chars.peek() {
+           if c.is_whitespace() {
+               chars.next();
+               continue;
+           }
+           
+           let mut token = String::new();
+           match c {
+               '\'' => {
+                   chars.next();
+                   loop {
+                       match chars.next() {
+                           Some('\'') if chars.peek() == Some(&'\'') => {
+                               chars.next();
+                               token.push('\'');
+                           }
+                           Some('\'') => break,
+                           Some(ch) => token.push(ch),
+                           None => return None, // unterminated quote
+                       }
+                   }
+               }
+               '"' => {
+                   chars.next();
+                   loop {
+                       match chars.next() {
+                           Some('\\') => match chars.next() {
+                               Some('n') => token.push('\n'),
+                               Some('r') => token.push('\r'),
+                               Some('t') => token.push('\t'),
+                               Some(ch) => token.push(ch),
+                               None => return None,
+                           },
+                           Some('"') => break,
+                           Some(ch) => token.push(ch),
+                           None => return None, // unterminated quote
+                       }
+                   }
+               }
+               _ => {
+                   while let Some(&ch) = chars.peek() {
+                       if ch.is_whitespace() {
+                           break;
+                       }
+                       token.push(ch);
+                       chars.next();
+                   }
+               }
+           }
+           tokens.push(token);
+       }
+       
+       let mut tokens = tokens.into_iter();
+       let command = tokens.next()?;
+       Some(Self::new(command, tokens.collect::<Vec<String>>()))
+   }
+
    /// Format the command as a string
    pub fn to_string(&self) -> String {
        let mut parts = Vec::with_capacity(self.args.len() + 1);
@@ -25,10 +110,160 @@ impl RedisCommand {
        parts.extend(self.args.clone());
        parts.join(" ")
    }
+
+   /// Format the command so `parse` (and redis-cli) read it back unchanged: arguments
+   /// that are empty or contain whitespace or quotes are double-quoted with backslash escapes
+   pub fn to_quoted_string(&self) -> String {
+       std::iter::once(&self.command)
+           .chain(&self.args)
+           .map(|arg| quote_arg(arg))
+           .collect::<Vec<_>>()
+           .join(" ")
+   }
+
+   /// Format the command as the templates render it: an EVAL/EVAL_RO script stays
+   /// single-quoted across its lines (with '' escaping), other arguments as in `to_quoted_string`
+   pub(crate) fn to_rendered_string(&self) -> String {
+       match self.args.split_first() {
+           Some((script, rest)) if self.command.eq_ignore_ascii_case("EVAL") || self.command.eq_ignore_ascii_case("EVAL_RO") => {
+               std::iter::once(format!("{} {}", self.command, quote_script(script)))
+                   .chain(rest.iter().map(|arg| quote_arg(arg)))
+                   .collect::<Vec<_>>()
+                   .join(" ")
+           }
+           _ => self.to_quoted_string(),
+       }
+   }
+
+   /// A stable content hash: the lowercase hex SHA-1 of the command as RESP, its name
+   /// uppercased. Equal commands hash equal across runs, platforms, and releases, so the
+   /// hash can deduplicate generated commands or key a cache of them.
+   pub fn content_hash(&self) -> String {
+       script_sha1(&self.to_resp())
+   }
+
+   /// The command as a RESP array of bulk strings, its name uppercased
+   pub(crate) fn to_resp(&self) -> String {
+       let mut resp = format!("*{}\r\n", self.args.len() + 1);
+       for arg in std::iter::once(&self.command.to_uppercase()).chain(&self.args) {
+           resp.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+       }
+       resp
+   }
+
+   /// The command as a RESP array of bulk strings, its name uppercased, ready for
+   /// `redis-cli --pipe`. Binary arguments spelled in `encoding` are written as raw bytes.
+   pub fn to_resp_bytes(&self, encoding: BinaryEncoding) -> Vec<u8> {
+       let mut resp = format!("*{}\r\n", self.args.len() + 1).into_bytes();
+       let name = self.command.to_uppercase().into_bytes();
+       let args = self.args.iter().enumerate().map(|(i, arg)| match self.binary.contains(&i) {
+           true => encoding.decode(arg).unwrap_or_else(|| arg.as_bytes().to_vec()),
+           false => arg.as_bytes().to_vec(),
+       });
+       for arg in std::iter::once(name).chain(args) {
+           resp.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+           resp.extend_from_slice(&arg);
+           resp.extend_from_slice(b"\r\n");
+       }
+       resp
+   }
+
+   /// Indices into `args` of the keys the command addresses: every argument of multi-key
+   /// commands, the KEYS of scripts, the first argument of ordinary key commands, and
+   /// none for server commands
+   pub fn key_indices(&self) -> Vec<usize> {
+       let len = self.args.len();
+       match self.command.to_uppercase().as_str() {
+           "EVAL" | "EVALSHA" | "EVAL_RO" | "EVALSHA_RO" | "FCALL" | "FCALL_RO" => {
+               let count: usize = self.args.get(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+               (2..(2 + count).min(len)).collect()
+           }
+           "DEL" | "UNLINK" | "EXISTS" | "MGET" | "TOUCH" | "WATCH" | "SUNIONSTORE" | "SINTERSTORE" | "SDIFFSTORE" => (0..len).collect(),
+           "MSET" | "MSETNX" => (0..len).step_by(2).collect(),
+           "ZRANGESTORE" => (0..len.min(2)).collect(),
+           "OBJECT" | "MEMORY" => (1..len.min(2)).collect(),
+           "INFO" | "CLIENT" | "SLOWLOG" | "CONFIG" | "SCRIPT" | "FUNCTION" | "SCAN" | "DBSIZE" | "PING"
+           | "SUBSCRIBE" | "PSUBSCRIBE" | "PUBLISH" | "SELECT" | "ACL" | "MULTI" | "EXEC" | "DISCARD" | "UNWATCH" => Vec::new(),
+           _ => (0..len.min(1)).collect(),
+       }
+   }
+
+   /// Parse rendered output holding one command per line; newlines inside quoted
+   /// arguments (such as multi-line EVAL scripts) don't end a command
+   pub fn parse_all(text: &str) -> Vec<Self> {
+       split_commands(text).into_iter().filter_map(Self::parse).collect()
+   }
+}
+
+/// Split rendered output into command lines, ignoring newlines inside quoted arguments.
+/// Quotes are recognized where `parse` recognizes them: at the start of an argument.
+pub fn split_commands(text: &str) -> Vec<&str> {
+   let mut lines = Vec::new();
+   let mut start = 0;
+   let mut chars = text.char_indices().peekable();
+   let mut at_arg_start = true;
+   
+   while let Some((i, c)) = chars.next() {
+       match c {
+           '\'' | '"' if at_arg_start => {
+               // Skip to the closing quote, honoring '' and backslash escapes
+               while let Some((_, ch)) = chars.next() {
+                   match ch {
+                       '\'' if c == '\'' && chars.peek().map(|&(_, next)| next) == Some('\'') => { chars.next(); }
+                       '\\' if c == '"' => { chars.next(); }
+                       ch if ch == c => break,
+                       _ => {}
+                   }
+               }
+               at_arg_start = false;
+           }
+           '\n' => {
+               lines.push(&text[start..i]);
+               start = i + 1;
+               at_arg_start = true;
+           }
+           c => at_arg_start = c.is_whitespace(),
+       }
+   }
+   lines.push(&text[start..]);
+   lines.into_iter().map(str::trim).filter(|line| !line.is_empty()).collect()
+}
+
+/// Single-quote a Lua script as rendered EVAL commands carry it, doubling its quotes
+pub(crate) fn quote_script(script: &str) -> String {
+   format!("'{}'", script.replace('\'', "''"))
+}
+
+pub(crate) fn quote_arg(arg: &str) -> String {
+   let needs_quotes = arg.is_empty() || arg.chars().any(|c| c.is_whitespace() || c == '"' || c == '\'');
+   if !needs_quotes {
+       return arg.to_string();
+   }
+   let mut quoted = String::with_capacity(arg.len() + 2);
+   quoted.push('"');
+   for c in arg.chars() {
+       match c {
+           '"' => quoted.push_str("\\\""),
+           '\\' => quoted.push_str("\\\\"),
+           '\n' => quoted.push_str("\\n"),
+           '\r' => quoted.push_str("\\r"),
+           '\t' => quoted.push_str("\\t"),
+           c => quoted.push(c),
+       }
+   }
+   quoted.push('"');
+   quoted
 }
 
-/// Generate a Redis command from a SQL statement
+/// Generate a Redis command from a SQL statement, bypassing the rules and templates
+#[deprecated(note = "use SqlToRedisTransformer::transform_to_commands, which applies the rules, options, and key prefixes")]
 pub fn generate_command(stmt: &Statement) -> Option<RedisCommand> {
+   fallback_command(stmt)
+}
+
+/// The transformer's last resort for statements no rule matches: a command built straight
+/// from the extractors, without templates, options, or warnings
+pub(crate) fn fallback_command(stmt: &Statement) -> Option<RedisCommand> {
    // Try to match against all patterns, in order
    
    // String operations
@@ -107,8 +342,8 @@ pub fn generate_command(stmt: &Statement) -> Option<RedisCommand> {
            "hash" => {
                // HMSET
                let mut args = vec![info.key];
-               for (field, value) in info.fields.iter() {
-                   if field != "key" {
+               for field in info.columns.iter().filter(|column| *column != "key") {
+                   if let Some(value) = info.fields.get(field) {
                        args.push(field.clone());
                        args.push(value.clone());
                    }
@@ -186,15 +421,5 @@ pub fn generate_command(stmt: &Statement) -> Option<RedisCommand> {
 
 // Helper to determine Redis data type from table name
 fn determine_table_type(table: &str) -> &'static str {
-   if table.ends_with("__hash") {
-       "hash"
-   } else if table.ends_with("__list") {
-       "list"
-   } else if table.ends_with("__set") {
-       "set"
-   } else if table.ends_with("__zset") {
-       "zset"
-   } else {
-       "string"
-   }
+   redis_type_of(table, TypeMapping::standard()).name()
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+// config.rs - CLI defaults read from a config file
+// A flat TOML subset (`key = "value"` lines and `#` comments) in the XDG config directory;
+// command-line flags and SQLNOSQL_* environment variables take precedence over it
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What the CLI prints for --query/--file/stdin statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `Redis: <command>` per statement (and decoded replies with --execute)
+    Text,
+    /// A replayable script: SCRIPT LOAD preamble, then EVALSHA commands (--bundle)
+    Bundle,
+    /// A Redis 7+ script: FUNCTION LOAD preamble, then FCALL commands (--functions)
+    Functions,
+    /// Each statement's numbered plan steps (see `Plan`)
+    Plan,
+    /// Each statement's plan as one line of JSON
+    Json,
+    /// Each statement's commands in RESP, binary values as raw bytes (for `redis-cli --pipe`)
+    Resp,
+}
+
+impl OutputFormat {
+    /// Lowercase name used by the CLI, environment and config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Bundle => "bundle",
+            OutputFormat::Functions => "functions",
+            OutputFormat::Plan => "plan",
+            OutputFormat::Json => "json",
+            OutputFormat::Resp => "resp",
+        }
+    }
+
+    /// Look a format up by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        [OutputFormat::Text, OutputFormat::Bundle, OutputFormat::Functions, OutputFormat::Plan, OutputFormat::Json, OutputFormat::Resp]
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Defaults for CLI flags that weren't given on the command line or in the environment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Default --url
+    pub url: Option<String>,
+    /// Default --output
+    pub output: Option<OutputFormat>,
+    /// Default --key-prefix
+    pub key_prefix: Option<String>,
+    /// Directory of rule/template plugins, loaded like --plugins (the CLI refuses it when built
+    /// without the `plugins` feature)
+    pub template_dir: Option<PathBuf>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/sqlnosql/config.toml`, falling back to `~/.config/sqlnosql/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("sqlnosql").join("config.toml"))
+    }
+
+    /// Read a config file; Ok(None) if it doesn't exist
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Self::parse(&text)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parse `key = "value"` lines; unknown keys are rejected so typos don't go unnoticed
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected key = \"value\""))?;
+            let value = parse_string(value.trim()).ok_or_else(|| error("expected a quoted string value"))?;
+            match key.trim() {
+                "url" => config.url = Some(value),
+                "output" => {
+                    config.output = Some(OutputFormat::from_name(&value)
+                        .ok_or_else(|| error("output must be text, bundle, functions, plan, json or resp"))?);
+                }
+                "key_prefix" => config.key_prefix = Some(value),
+                "template_dir" => config.template_dir = Some(PathBuf::from(value)),
+                other => return Err(error(&format!("unknown key {:?}", other))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// A double-quoted TOML basic string (with `\"`, `\\`, `\n` and `\t` escapes) or a
+/// single-quoted literal string, optionally followed by a `#` comment
+fn parse_string(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        let (literal, trailing) = rest.split_once('\'')?;
+        return is_comment(trailing).then(|| literal.to_string());
+    }
+    let mut chars = value.strip_prefix('"')?.chars();
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return is_comment(chars.as_str()).then_some(out),
+            '\\' => out.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                c @ ('"' | '\\') => c,
+                _ => return None,
+            }),
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+fn is_comment(trailing: &str) -> bool {
+    let trailing = trailing.trim();
+    trailing.is_empty() || trailing.starts_with('#')
+}
@@ -42,16 +42,19 @@ impl ContextBuilder for HashDeleteContextBuilder {
 }
 
 /// Builder for hash HDEL commands
-/// <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" "=" <field>
+/// <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" ("=" <field> | "IN" "(" <field1> ["," <field2>]... ")")
 pub struct HashDeleteFieldContextBuilder;
 impl ContextBuilder for HashDeleteFieldContextBuilder {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
         let key = ast::delete::get_key_value(stmt)?;
-        let field = ast::delete::get_field_filter(stmt, "field")?;
+        let fields = match ast::delete::get_field_filter(stmt, "field") {
+            Some(field) => vec![field],
+            None => ast::delete::get_column_in_values(stmt, "field")?,
+        };
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("field".to_string(), field);
+        context.insert("fields".to_string(), super::list_value(&fields));
         Some(context)
     }
 }
@@ -88,6 +91,22 @@ impl ContextBuilder for ListDeleteValueContextBuilder {
     }
 }
 
+/// Builder for list LTRIM commands
+/// <list-trim> ::= "DELETE" "FROM" <table> "__list" "WHERE" "key" "=" <key> "AND" "index" (">" | ">=" | "<" | "<=") <n>
+pub struct ListTrimContextBuilder;
+impl ContextBuilder for ListTrimContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let key = ast::delete::get_key_value(stmt)?;
+        let (start, stop) = ast::delete::get_index_trim_range(stmt)?;
+        
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        context.insert("start".to_string(), start.to_string());
+        context.insert("stop".to_string(), stop.to_string());
+        Some(context)
+    }
+}
+
 // --------------------------------
 // Set Command Context Builders
 // --------------------------------
@@ -132,7 +151,7 @@ impl ContextBuilder for SetDeleteMultiMemberContextBuilder {
         }
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("members".to_string(), members.join(" "));
+        context.insert("members".to_string(), super::list_value(&members));
         Some(context)
     }
 }
@@ -155,16 +174,52 @@ impl ContextBuilder for ZSetDeleteContextBuilder {
 }
 
 /// Builder for sorted set ZREM commands
-/// <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" "=" <member>
+/// <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" ("=" <member> | "IN" "(" <member1> ["," <member2>]... ")")
 pub struct ZSetDeleteMemberContextBuilder;
 impl ContextBuilder for ZSetDeleteMemberContextBuilder {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
         let key = ast::delete::get_key_value(stmt)?;
-        let member = ast::delete::get_field_filter(stmt, "member")?;
+        let members = match ast::delete::get_field_filter(stmt, "member") {
+            Some(member) => vec![member],
+            None => ast::delete::get_member_in_values(stmt)?,
+        };
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("member".to_string(), member);
+        context.insert("members".to_string(), super::list_value(&members));
+        Some(context)
+    }
+}
+/// Builder for sorted set ZREMRANGEBYSCORE commands
+/// <zset-delete-score-range> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "score" (<comparison> <n> | "BETWEEN" <min> "AND" <max>)
+pub struct ZSetDeleteScoreRangeContextBuilder;
+impl ContextBuilder for ZSetDeleteScoreRangeContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let key = ast::delete::get_key_value(stmt)?;
+        let (min, max) = ast::delete::get_score_range(stmt)?;
+        
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        context.insert("min".to_string(), min);
+        context.insert("max".to_string(), max);
         Some(context)
     }
-}
\ No newline at end of file
+}
+
+/// Builder for sorted set ZPOPMIN/ZPOPMAX commands
+/// <zset-pop> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "ORDER" "BY" "score" ["ASC" | "DESC"] "LIMIT" <count> ["RETURNING" <columns>]
+pub struct ZSetPopContextBuilder;
+impl ContextBuilder for ZSetPopContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let key = ast::delete::get_key_value(stmt)?;
+        let count = ast::delete::get_limit(stmt)?;
+        
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        // ZPOPMIN/ZPOPMAX pop a single member by default
+        if count != 1 {
+            context.insert("count".to_string(), count.to_string());
+        }
+        Some(context)
+    }
+}
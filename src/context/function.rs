@@ -0,0 +1,25 @@
+// context/function.rs - Context builders for table-function reads
+// Names the arguments of `redis_get('user:1')` and friends after the template variables they fill
+
+use std::collections::HashMap;
+use sqlparser::ast::Statement;
+use crate::ast;
+use crate::context::TemplateContext;
+use crate::context::ContextBuilder;
+
+/// Builder for table-function reads: the function's arguments, in order, under `params`
+/// (e.g. `["key", "field"]` for `redis_hget('user:1', 'name')`)
+pub struct TableFunctionContextBuilder(pub &'static [&'static str]);
+impl ContextBuilder for TableFunctionContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let (_, args) = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(ast::sel_get_table_function)?;
+        if args.len() != self.0.len() {
+            return None;
+        }
+        
+        let context: HashMap<String, String> = self.0.iter().map(|param| param.to_string()).zip(args).collect();
+        Some(context)
+    }
+}
@@ -2,7 +2,7 @@
 // Extracts template variables from INSERT AST nodes
 
 use std::collections::HashMap;
-use sqlparser::ast::Statement;
+use sqlparser::ast::{Select, SetOperator, Statement};
 use crate::ast;
 use crate::context::TemplateContext;
 use crate::context::ContextBuilder;
@@ -35,30 +35,23 @@ impl ContextBuilder for StringSetContextBuilder {
 pub struct HashSetContextBuilder;
 impl ContextBuilder for HashSetContextBuilder {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
-        let value_maps = ast::ins_get_values_as_maps(stmt)?;
-        if value_maps.is_empty() {
-            return None;
-        }
-        
-        let row = &value_maps[0];
-        let key = row.get("key")?.clone();
+        let rows = ast::ins_get_values_as_pairs(stmt)?;
+        let row = rows.first()?;
+        let key = row.iter().find(|(column, _)| column == "key")?.1.clone();
         
-        // Create a clone without the key
-        let mut field_values = row.clone();
-        field_values.remove("key");
+        // Format field-value pairs for template (preserves SQL column order)
+        let fields_formatted: Vec<String> = row.iter()
+            .filter(|(column, _)| column != "key")
+            .flat_map(|(field, value)| [field.clone(), value.clone()])
+            .collect();
         
-        if field_values.is_empty() {
+        if fields_formatted.is_empty() {
             return None;
         }
         
-        // Format field-value pairs for template
-        let fields_formatted: Vec<String> = field_values.iter()
-            .map(|(field, value)| format!("{} {}", field, value))
-            .collect();
-        
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("field_values".to_string(), fields_formatted.join(" "));
+        context.insert("field_values".to_string(), super::list_value(&fields_formatted));
         Some(context)
     }
 }
@@ -87,28 +80,20 @@ impl ContextBuilder for ListPushContextBuilder {
 // --------------------------------
 
 /// Builder for set SADD commands
-/// <set-add> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "VALUES" "(" <key> "," <member> ")"
+/// <set-add> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "VALUES" "(" <key> "," <member> ")" ["," "(" <key> "," <member> ")"]...
 pub struct SetAddContextBuilder;
 impl ContextBuilder for SetAddContextBuilder {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
-        let key = ast::ins_get_column_value(stmt, "key")?;
-        
-        // Get all member values that share the same key
-        let values = ast::ins_get_values_as_maps(stmt)?;
-        let first_key = values[0].get("key")?.clone();
-        
-        let members: Vec<String> = values.iter()
-            .filter(|row| row.get("key").map_or(false, |k| k == &first_key))
-            .filter_map(|row| row.get("member").cloned())
-            .collect();
+        let (key, rows) = single_key_rows(stmt)?;
         
-        if members.is_empty() {
-            return None;
-        }
+        // One SADD argument per value row
+        let members = rows.iter()
+            .map(|row| row.get("member").cloned())
+            .collect::<Option<Vec<String>>>()?;
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("members".to_string(), members.join(" "));
+        context.insert("members".to_string(), super::list_value(&members));
         Some(context)
     }
 }
@@ -118,18 +103,125 @@ impl ContextBuilder for SetAddContextBuilder {
 // --------------------------------
 
 /// Builder for sorted set ZADD commands
-/// <zset-add> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "VALUES" "(" <key> "," <member> "," <score> ")"
+/// <zset-add> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "VALUES" "(" <key> "," <member> "," <score> ")" ["," "(" <key> "," <member> "," <score> ")"]...
 pub struct ZSetAddContextBuilder;
 impl ContextBuilder for ZSetAddContextBuilder {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
-        let key = ast::ins_get_column_value(stmt, "key")?;
-        let member = ast::ins_get_column_value(stmt, "member")?;
-        let score = ast::ins_get_column_value(stmt, "score")?;
+        let (key, rows) = single_key_rows(stmt)?;
+        
+        // ZADD takes score/member pairs, one per value row
+        let score_members = rows.iter()
+            .map(|row| Some([row.get("score")?.clone(), row.get("member")?.clone()]))
+            .collect::<Option<Vec<_>>>()?
+            .concat();
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("member".to_string(), member);
-        context.insert("score".to_string(), score);
+        context.insert("score_members".to_string(), super::list_value(&score_members));
         Some(context)
     }
-}
\ No newline at end of file
+}
+
+/// Builder for SUNIONSTORE / SINTERSTORE / SDIFFSTORE from INSERT ... SELECT
+/// <set-store> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>
+///                 [("UNION" | "INTERSECT" | "EXCEPT") "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>]...
+pub struct SetStoreContextBuilder;
+impl ContextBuilder for SetStoreContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let query = ast::ins_get_source_query(stmt)?;
+        let (op, selects) = ast::sel_get_set_operands(query)?;
+        let sources = selects.iter()
+            .map(|select| ast::sel_get_key_value(&select.selection))
+            .collect::<Option<Vec<String>>>()?;
+        
+        // A lone SELECT copies its set, which SUNIONSTORE of one source does
+        let command = match op {
+            None | Some(SetOperator::Union) => "SUNIONSTORE",
+            Some(SetOperator::Intersect) => "SINTERSTORE",
+            Some(SetOperator::Except) => "SDIFFSTORE",
+            Some(_) => return None,
+        };
+        
+        let mut context = HashMap::new();
+        context.insert("command".to_string(), command.to_string());
+        context.insert("destination".to_string(), insert_select_destination(stmt, selects.first()?, &sources[0])?);
+        context.insert("sources".to_string(), super::list_value(&sources));
+        Some(context)
+    }
+}
+
+/// Builder for sorted set ZRANGESTORE from INSERT ... SELECT
+/// <zset-range-store> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "SELECT" ... "FROM" <table> "__zset"
+///                        "WHERE" "key" "=" <key> ["AND" <score-condition>] ["ORDER BY" "score" ["DESC"]] ["LIMIT" <n>]
+pub struct ZSetRangeStoreContextBuilder;
+impl ContextBuilder for ZSetRangeStoreContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let query = ast::ins_get_source_query(stmt)?;
+        let select = ast::sel_get_select(query)?;
+        let source = ast::sel_get_key_value(&select.selection)?;
+        let reversed = ast::sel_is_order_by_score_desc(query);
+        let limit = ast::sel_get_limit(query);
+        
+        // At most one score condition, which makes the range BYSCORE
+        let score_conditions = select.selection.iter()
+            .flat_map(ast::split_conjuncts)
+            .filter(|predicate| ast::predicate_column(predicate).as_deref() == Some("score"))
+            .count();
+        let range = match score_conditions {
+            0 => None,
+            1 => Some(ast::sel_get_score_range(&select.selection)?),
+            _ => return None,
+        };
+        
+        let mut context = HashMap::new();
+        context.insert("destination".to_string(), insert_select_destination(stmt, select, &source)?);
+        context.insert("source".to_string(), source);
+        match range {
+            // BYSCORE REV takes the bounds high to low
+            Some((min, max)) => {
+                let (start, stop) = if reversed { (max, min) } else { (min, max) };
+                context.insert("start".to_string(), start);
+                context.insert("stop".to_string(), stop);
+                context.insert("byscore".to_string(), "1".to_string());
+                if let Some(limit) = limit {
+                    context.insert("count".to_string(), limit.to_string());
+                }
+            }
+            None => {
+                let stop = match limit {
+                    Some(0) => return None,
+                    Some(limit) => (limit - 1).to_string(),
+                    None => "-1".to_string(),
+                };
+                context.insert("start".to_string(), "0".to_string());
+                context.insert("stop".to_string(), stop);
+            }
+        }
+        if reversed {
+            context.insert("rev".to_string(), "1".to_string());
+        }
+        Some(context)
+    }
+}
+
+/// Destination key of an INSERT ... SELECT: the literal the SELECT projects into the key
+/// column, else `{table}:{source key}` (`top__zset` filled from `game:1` stores `top:game:1`)
+pub(crate) fn insert_select_destination(stmt: &Statement, select: &Select, source: &str) -> Option<String> {
+    if let Some(key) = ast::ins_get_projected_literal(stmt, &select.projection, "key") {
+        return Some(key);
+    }
+    let table = ast::ins_get_table_name(stmt)?;
+    let base = table.rsplit_once("__").map_or(table.as_str(), |(base, _)| base);
+    Some(format!("{}:{}", base, source))
+}
+
+/// Value rows of a multi-row INSERT, provided they all target the same key.
+/// Variadic commands take a single key, so mixed-key inserts are not translated.
+fn single_key_rows(stmt: &Statement) -> Option<(String, Vec<HashMap<String, String>>)> {
+    let rows = ast::ins_get_values_as_maps(stmt)?;
+    let key = rows.first()?.get("key")?.clone();
+    if rows.iter().any(|row| row.get("key") != Some(&key)) {
+        return None;
+    }
+    Some((key, rows))
+}
@@ -8,17 +8,55 @@ pub mod select;
 pub mod insert;
 pub mod update;
 pub mod delete;
+pub mod trigger;
+pub mod session;
+pub mod function;
 
 // A Template Context is just key-value pairs
 pub type TemplateContext = HashMap<String, String>;
 
+/// Separates the items of a list-valued context entry, so structured rendering
+/// can split it into one argument per item (text rendering shows a space)
+pub const LIST_SEPARATOR: char = '\u{1f}';
+
+/// Join list items into a single context value
+pub fn list_value<I, S>(items: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let items: Vec<String> = items.into_iter().map(|s| s.as_ref().to_string()).collect();
+    items.join(&LIST_SEPARATOR.to_string())
+}
+
 // Create context for commands from AST nodes
-pub trait ContextBuilder {
+pub trait ContextBuilder: Send + Sync {
     fn build_context(&self, stmt: &Statement) -> Option<TemplateContext>;
 }
 
+// Any function from a statement to a context is a context builder
+impl<F> ContextBuilder for F
+where
+    F: Fn(&Statement) -> Option<TemplateContext> + Send + Sync,
+{
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        self(stmt)
+    }
+}
+
+/// Pin a closure's signature so it can be used as a context builder
+pub fn from_fn<F>(f: F) -> F
+where
+    F: Fn(&Statement) -> Option<TemplateContext>,
+{
+    f
+}
+
 // Re-export context builders for convenience
 pub use select::*;
 pub use insert::*;
 pub use update::*;
-pub use delete::*;
\ No newline at end of file
+pub use delete::*;
+pub use trigger::*;
+pub use session::*;
+pub use function::*;
\ No newline at end of file
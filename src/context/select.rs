@@ -7,6 +7,77 @@ use crate::ast;
 use crate::context::TemplateContext;
 use crate::context::ContextBuilder;
 
+// --------------------------------
+// Key Metadata Context Builders
+// --------------------------------
+
+/// Builder for key metadata pseudo-columns (OBJECT IDLETIME, OBJECT FREQ).
+/// Works on any table type since the metadata belongs to the key itself.
+pub struct KeyMetadataContextBuilder;
+impl ContextBuilder for KeyMetadataContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let key = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(|select| ast::sel_get_key_value(&select.selection))?;
+            
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        Some(context)
+    }
+}
+
+// --------------------------------
+// System Table Context Builders
+// --------------------------------
+
+/// Builder for admin system tables; only LIMIT carries over (as a count)
+pub struct SystemTableContextBuilder;
+impl ContextBuilder for SystemTableContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let query = ast::sel_get_query(stmt)?;
+        
+        let mut context = HashMap::new();
+        if let Some(limit) = ast::sel_get_limit(query) {
+            context.insert("count".to_string(), limit.to_string());
+        }
+        Some(context)
+    }
+}
+
+/// Builder for `__keys` browsing: the key glob pattern, and LIMIT as the SCAN count
+pub struct KeysBrowseContextBuilder;
+impl ContextBuilder for KeysBrowseContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let query = ast::sel_get_query(stmt)?;
+        let pattern = ast::sel_get_select(query).and_then(|select| ast::sel_get_key_pattern(&select.selection))?;
+        
+        let mut context = HashMap::new();
+        context.insert("pattern".to_string(), pattern);
+        if let Some(limit) = ast::sel_get_limit(query) {
+            context.insert("count".to_string(), limit.to_string());
+        }
+        Some(context)
+    }
+}
+
+/// Builder for set and sorted set member scans: the key, the member glob pattern, and LIMIT as
+/// the SSCAN/ZSCAN count
+pub struct MemberPatternContextBuilder;
+impl ContextBuilder for MemberPatternContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let query = ast::sel_get_query(stmt)?;
+        let select = ast::sel_get_select(query)?;
+        
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), ast::sel_get_key_value(&select.selection)?);
+        context.insert("pattern".to_string(), ast::sel_get_member_pattern(&select.selection)?);
+        if let Some(limit) = ast::sel_get_limit(query) {
+            context.insert("count".to_string(), limit.to_string());
+        }
+        Some(context)
+    }
+}
+
 // --------------------------------
 // String Command Context Builders
 // --------------------------------
@@ -25,6 +96,26 @@ impl ContextBuilder for StringGetContextBuilder {
     }
 }
 
+/// Builder for field reads on JSON string tables: the key plus the projected fields
+/// as JSONPath expressions (`$.name`) for JSON.GET
+pub struct JsonGetContextBuilder;
+impl ContextBuilder for JsonGetContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let select = ast::sel_get_query(stmt).and_then(ast::sel_get_select)?;
+        let key = ast::sel_get_key_value(&select.selection)?;
+        let fields = ast::sel_get_field_names(&select.projection);
+        
+        if fields.is_empty() {
+            return None;
+        }
+        
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        context.insert("paths".to_string(), super::list_value(fields.iter().map(|f| format!("$.{}", f))));
+        Some(context)
+    }
+}
+
 // String MGET context builder for key IN (...)
 pub struct StringGetMultiContextBuilder;
 impl ContextBuilder for StringGetMultiContextBuilder {
@@ -38,7 +129,7 @@ impl ContextBuilder for StringGetMultiContextBuilder {
         }
         
         let mut context = HashMap::new();
-        context.insert("keys".to_string(), keys.join(" "));
+        context.insert("keys".to_string(), super::list_value(&keys));
         Some(context)
     }
 }
@@ -83,9 +174,11 @@ impl ContextBuilder for HashGetContextBuilder {
             .and_then(ast::sel_get_select)
             .and_then(|select| ast::sel_get_key_value(&select.selection))?;
             
-        let field = ast::sel_get_query(stmt)
+        let (field, _) = ast::sel_get_query(stmt)
             .and_then(ast::sel_get_select)
-            .and_then(|select| ast::sel_get_field_name(&select.projection[0]))?;
+            .and_then(|select| ast::sel_get_projected_columns(&select.projection))?
+            .into_iter()
+            .next()?;
             
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
@@ -103,10 +196,13 @@ impl ContextBuilder for HashMultiGetContextBuilder {
             .and_then(ast::sel_get_select)
             .and_then(|select| ast::sel_get_key_value(&select.selection))?;
         
-        // Extract selected fields
-        let fields = ast::sel_get_query(stmt)
+        // Extract selected fields (aliases name the result columns, not the fields read)
+        let fields: Vec<String> = ast::sel_get_query(stmt)
             .and_then(ast::sel_get_select)
-            .map(|select| ast::sel_get_field_names(&select.projection))?;
+            .and_then(|select| ast::sel_get_projected_columns(&select.projection))?
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect();
         
         if fields.is_empty() {
             return None;
@@ -114,7 +210,7 @@ impl ContextBuilder for HashMultiGetContextBuilder {
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("fields".to_string(), fields.join(" "));
+        context.insert("fields".to_string(), super::list_value(&fields));
         
         // For Lua script option
         context.insert("fields_array".to_string(), 
@@ -185,6 +281,25 @@ impl ContextBuilder for ListGetRangeContextBuilder {
     }
 }
 
+// HSCAN / SSCAN / ZRANGEBYSCORE ... LIMIT context builder: the first page of LIMIT n
+pub struct PageContextBuilder;
+impl ContextBuilder for PageContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let key = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(|select| ast::sel_get_key_value(&select.selection))?;
+            
+        let count = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_limit)
+            .filter(|&limit| limit > 0)?;
+            
+        let mut context = HashMap::new();
+        context.insert("key".to_string(), key);
+        context.insert("count".to_string(), count.to_string());
+        Some(context)
+    }
+}
+
 // --------------------------------
 // Set Command Context Builders
 // --------------------------------
@@ -285,9 +400,10 @@ impl ContextBuilder for ZSetGetScoreBetweenContextBuilder {
         let key = ast::sel_get_query(stmt)
             .and_then(ast::sel_get_select)
             .and_then(|select| ast::sel_get_key_value(&select.selection))?;
+        // The BETWEEN intersected with any other score bounds
         let (min, max) = ast::sel_get_query(stmt)
             .and_then(ast::sel_get_select)
-            .and_then(|select| ast::sel_get_score_between(&select.selection))?;
+            .and_then(|select| ast::sel_get_score_range(&select.selection))?;
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
         context.insert("min".to_string(), min);
@@ -305,7 +421,7 @@ impl ContextBuilder for ZSetCountScoreRangeContextBuilder {
             .and_then(|select| ast::sel_get_key_value(&select.selection))?;
         let (min, max) = ast::sel_get_query(stmt)
             .and_then(ast::sel_get_select)
-            .and_then(|select| ast::sel_get_score_between(&select.selection))?;
+            .and_then(|select| ast::sel_get_score_range(&select.selection))?;
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
         context.insert("min".to_string(), min);
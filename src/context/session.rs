@@ -0,0 +1,21 @@
+// context/session.rs - Context builders for session statements
+// Extracts the database index selected by USE
+
+use std::collections::HashMap;
+use sqlparser::ast::Statement;
+use crate::ast;
+use crate::context::TemplateContext;
+use crate::context::ContextBuilder;
+
+/// Builder for database switches
+/// <use-database> ::= "USE" ["DATABASE"] ("db" <index> | <index>)
+pub struct UseDatabaseContextBuilder;
+impl ContextBuilder for UseDatabaseContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let db = ast::use_get_database(stmt)?;
+        
+        let mut context = HashMap::new();
+        context.insert("db".to_string(), db.to_string());
+        Some(context)
+    }
+}
@@ -0,0 +1,52 @@
+// context/trigger.rs - Context builders for CREATE TRIGGER transformations
+// Extracts keyspace notification settings from trigger definitions
+
+use std::collections::HashMap;
+use sqlparser::ast::{Statement, TriggerEvent};
+use crate::ast;
+use crate::context::TemplateContext;
+use crate::context::ContextBuilder;
+use crate::pattern::matchers::common::{get_redis_data_type, RedisDataType};
+
+/// Compute the `notify-keyspace-events` classes needed to observe the trigger's events.
+/// Writes are reported under the table's type class; DELETE/TRUNCATE also need the
+/// generic class because whole-key removal is reported as `del`.
+fn notification_classes(table: &str, events: &[TriggerEvent]) -> String {
+    let type_class = match get_redis_data_type(table) {
+        RedisDataType::String => '$',
+        RedisDataType::Hash => 'h',
+        RedisDataType::List => 'l',
+        RedisDataType::Set => 's',
+        RedisDataType::SortedSet => 'z',
+    };
+    
+    let mut classes = String::from("K");
+    if events.iter().any(|e| matches!(e, TriggerEvent::Delete | TriggerEvent::Truncate)) {
+        classes.push('g');
+    }
+    if events.iter().any(|e| !matches!(e, TriggerEvent::Truncate)) {
+        classes.push(type_class);
+    }
+    classes
+}
+
+/// Builder for keyspace notification subscriptions
+/// <keyspace-subscribe> ::= "CREATE" "TRIGGER" <name> "AFTER" <events> "ON" <table> ["WHEN" <key-condition>] ...
+pub struct KeyspaceSubscriptionContextBuilder;
+impl ContextBuilder for KeyspaceSubscriptionContextBuilder {
+    fn build_context(&self, stmt: &Statement) -> Option<TemplateContext> {
+        let table = ast::trg_get_table_name(stmt)?;
+        let events = ast::trg_get_events(stmt)?;
+        // Without WHEN, every key: the table's key prefix is applied to the channel later
+        let pattern = match ast::trg_is_unfiltered(stmt) {
+            true => "*".to_string(),
+            false => ast::trg_get_key_pattern(stmt)?,
+        };
+        
+        let mut context = HashMap::new();
+        context.insert("events".to_string(), notification_classes(&table, events));
+        context.insert("db".to_string(), "0".to_string());
+        context.insert("pattern".to_string(), pattern);
+        Some(context)
+    }
+}
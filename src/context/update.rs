@@ -50,12 +50,12 @@ impl ContextBuilder for HashUpdateContextBuilder {
         
         // Format field-value pairs for template (preserves SQL order)
         let fields_formatted: Vec<String> = assignments.iter()
-            .map(|(field, value)| format!("{} {}", field, value))
+            .flat_map(|(field, value)| [field.clone(), value.clone()])
             .collect();
         
         let mut context = HashMap::new();
         context.insert("key".to_string(), key);
-        context.insert("field_values".to_string(), fields_formatted.join(" "));
+        context.insert("field_values".to_string(), super::list_value(&fields_formatted));
         Some(context)
     }
 }
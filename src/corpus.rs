@@ -0,0 +1,108 @@
+// corpus.rs - Comment-based regression corpora
+// Each statement in a `.sql` file is followed by `-- expect: <redis command>` lines; checking
+// the file translates every statement and compares its commands with the expected ones
+
+use std::fmt;
+
+use crate::commands::RedisCommand;
+use crate::SqlToRedisTransformer;
+
+/// A statement and the commands it must translate to, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusCase {
+    /// 1-based line number of the statement in the file
+    pub line: usize,
+    pub sql: String,
+    /// The `-- expect:` commands as written
+    pub expected: Vec<String>,
+}
+
+/// A case whose translation differs from its expectations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusFailure {
+    pub case: CorpusCase,
+    /// The commands produced (quoted as for redis-cli), or the translation error
+    pub actual: Result<Vec<String>, String>,
+}
+
+impl fmt::Display for CorpusFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.case.line, self.case.sql)?;
+        for expected in &self.case.expected {
+            write!(f, "\n  - {}", expected)?;
+        }
+        match &self.actual {
+            Ok(actual) => actual.iter().try_for_each(|command| write!(f, "\n  + {}", command)),
+            Err(e) => write!(f, "\n  ! {}", e),
+        }
+    }
+}
+
+impl CorpusCase {
+    /// Translate the statement and compare its commands with the expected ones. Commands
+    /// compare parsed, so quoting style and the case of command names don't matter.
+    pub fn check(&self, transformer: &SqlToRedisTransformer) -> Result<(), CorpusFailure> {
+        let commands = match transformer.transform_to_commands(&self.sql) {
+            Ok(commands) => commands,
+            Err(e) => return Err(CorpusFailure { case: self.clone(), actual: Err(format!("[{}] {}", e.code(), e)) }),
+        };
+        let matches = commands.len() == self.expected.len()
+            && commands.iter().zip(&self.expected).all(|(command, expected)| same_command(command, expected));
+        if matches {
+            return Ok(());
+        }
+        let actual = commands.iter().map(RedisCommand::to_quoted_string).collect();
+        Err(CorpusFailure { case: self.clone(), actual: Ok(actual) })
+    }
+}
+
+/// Outcome of checking a corpus
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    pub passed: usize,
+    pub failures: Vec<CorpusFailure>,
+}
+
+impl CorpusReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Pair each statement line with the `-- expect:` lines following it. Other comments and
+/// blank lines are ignored, and statements without expectations aren't cases.
+pub fn parse_corpus(content: &str) -> Vec<CorpusCase> {
+    let mut cases: Vec<CorpusCase> = Vec::new();
+    let mut current: Option<CorpusCase> = None;
+    for (number, line) in content.lines().map(str::trim).enumerate() {
+        if let Some(comment) = line.strip_prefix("--") {
+            if let (Some(expected), Some(case)) = (comment.trim().strip_prefix("expect:"), current.as_mut()) {
+                case.expected.push(expected.trim().to_string());
+            }
+        } else if !line.is_empty() {
+            cases.extend(current.take().filter(|case| !case.expected.is_empty()));
+            current = Some(CorpusCase { line: number + 1, sql: line.to_string(), expected: Vec::new() });
+        }
+    }
+    cases.extend(current.filter(|case| !case.expected.is_empty()));
+    cases
+}
+
+/// Check every case of a corpus file
+pub fn check_corpus(transformer: &SqlToRedisTransformer, content: &str) -> CorpusReport {
+    let mut report = CorpusReport::default();
+    for case in parse_corpus(content) {
+        match case.check(transformer) {
+            Ok(()) => report.passed += 1,
+            Err(failure) => report.failures.push(failure),
+        }
+    }
+    report
+}
+
+fn same_command(command: &RedisCommand, expected: &str) -> bool {
+    match RedisCommand::parse(expected) {
+        Some(expected) => expected.command.eq_ignore_ascii_case(&command.command) && expected.args == command.args,
+        None => false,
+    }
+}
@@ -0,0 +1,43 @@
+// diagnostic.rs - Source snippets for errors and warnings
+// Renders a span of the SQL input with a caret underline, compiler style
+
+use sqlparser::tokenizer::{Location, Span};
+
+/// Render the source line a span starts on, underlining the span:
+///
+/// ```text
+///   --> 1:47
+///    |
+///  1 | SELECT * FROM users__hash WHERE key = 'u' AND age > 30
+///    |                                               ^^^^^^^^ WHERE age > 30 is ignored
+/// ```
+///
+/// Spans crossing lines are underlined to the end of their first line.
+pub fn render_snippet(source: &str, span: Span, label: &str) -> String {
+    let Location { line, column } = span.start;
+    let text = match source.lines().nth(line.saturating_sub(1) as usize) {
+        Some(text) if line > 0 => text,
+        _ => return format!("{}\n", label),
+    };
+
+    let line_len = text.chars().count() as u64;
+    let start = column.max(1);
+    let end = if span.end.line == line { span.end.column } else { line_len + 1 };
+    let width = end.saturating_sub(start).max(1) as usize;
+
+    let gutter = " ".repeat(line.to_string().len());
+    format!(
+        "{gutter}--> {line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {pad}{carets}{label}\n",
+        pad = " ".repeat(start as usize - 1),
+        carets = "^".repeat(width),
+        label = if label.is_empty() { String::new() } else { format!(" {}", label) },
+    )
+}
+
+/// Extract the location from a sqlparser error message ("... at Line: 1, Column: 15")
+pub fn parse_error_location(message: &str) -> Option<Location> {
+    let rest = &message[message.rfind("Line: ")? + "Line: ".len()..];
+    let (line, rest) = rest.split_once(", Column: ")?;
+    let column: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some(Location::new(line.trim().parse().ok()?, column.parse().ok()?))
+}
@@ -0,0 +1,178 @@
+// error.rs - Error type for SQL-Redis transformation
+// Every error carries a stable code so callers can branch on failures and docs can reference them
+
+use sqlparser::tokenizer::{Location, Span};
+use std::fmt;
+use thiserror::Error;
+
+use crate::ast::ClauseUse;
+use crate::diagnostic;
+use crate::templates::TemplateError;
+
+/// Stable error codes; never renumbered once released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// E0001: no rule or fallback can translate the statement
+    UnsupportedPattern,
+    /// E0002: the statement has no `key = ...` (or `key IN (...)`) predicate, or a trigger has no `WHEN (key LIKE ...)`
+    MissingKeyPredicate,
+    /// E0003: the SQL could not be parsed
+    SqlParse,
+    /// E0004: strict mode rejected a translation that drops clauses
+    UnsupportedClause,
+    /// E0005: a command template failed to render
+    Template,
+    /// E0006: the transformer could not be initialized
+    Initialization,
+    /// E0007: a single command was requested but the translation yields several (or none)
+    MultipleCommands,
+    /// E0008: a cache plan was requested for a statement that isn't a single-key hash or string read
+    NotCacheable,
+    /// E0009: a generated command exceeds the configured size limits and can't be split
+    CommandTooLarge,
+    /// E0010: a `/*+ ... */` hint is unknown, malformed, or can't be honoured
+    InvalidHint,
+    /// E0011: a table is qualified with a schema that isn't declared
+    UnknownSchema,
+    /// E0012: a predicate excludes keys (`key != ...`, `key NOT IN (...)`), which no lookup can serve
+    UnsupportedPredicate,
+    /// E0013: strict mode found a rule's commands don't do what the statement asks
+    InconsistentTranslation,
+    /// E0014: a join of a shape no rule translates
+    UnsupportedJoin,
+    /// E0015: an update plan was requested for a statement that isn't a read-dependent update of string or hash keys
+    NotPlannable,
+}
+
+impl ErrorCode {
+    /// The code as written in docs and CLI output, e.g. "E0001"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::UnsupportedPattern => "E0001",
+            ErrorCode::MissingKeyPredicate => "E0002",
+            ErrorCode::SqlParse => "E0003",
+            ErrorCode::UnsupportedClause => "E0004",
+            ErrorCode::Template => "E0005",
+            ErrorCode::Initialization => "E0006",
+            ErrorCode::MultipleCommands => "E0007",
+            ErrorCode::NotCacheable => "E0008",
+            ErrorCode::CommandTooLarge => "E0009",
+            ErrorCode::InvalidHint => "E0010",
+            ErrorCode::UnknownSchema => "E0011",
+            ErrorCode::UnsupportedPredicate => "E0012",
+            ErrorCode::InconsistentTranslation => "E0013",
+            ErrorCode::UnsupportedJoin => "E0014",
+            ErrorCode::NotPlannable => "E0015",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Core error type for SQL-Redis transformation
+#[derive(Debug, Error)]
+pub enum SqlRedisError {
+    #[error("SQL parse error: {0}")]
+    SqlParseError(String),
+    #[error("No matching pattern for: {0}")]
+    NoMatchingPattern(String),
+    /// No rule matched and the statement does not address a key, or a trigger without WHEN
+    /// on a table with no key prefix (it would watch every key)
+    #[error("Missing key predicate (WHERE key = ..., or WHEN (key LIKE ...) on a trigger) in: {0}")]
+    MissingKeyPredicate(String),
+    #[error("Template error: {0}")]
+    TemplateError(String),
+    #[error("Initialization error: {0}")]
+    InitializationError(String),
+    /// Strict mode: clauses the matched translation would silently drop
+    #[error("Unsupported clause(s) would be dropped: {}", join_clauses(.0))]
+    UnsupportedClause(Vec<ClauseUse>),
+    /// `transform_to_command` on a statement that translates to a command count other than one
+    #[error("Expected exactly one command, translation produced {0}")]
+    MultipleCommands(usize),
+    /// `cache_plan` on a statement that doesn't translate to GET, HGET, HMGET, or HGETALL
+    #[error("Not a cacheable single-key read: {0}")]
+    NotCacheable(String),
+    /// A generated command over `TransformOptions::with_max_command_bytes` / `_args` that
+    /// isn't a variadic write (or has a single item too large) and so can't be split
+    #[error("Command exceeds size limits: {0}")]
+    CommandTooLarge(String),
+    /// A `/*+ ... */` hint that is unknown or malformed, names no rule, or names a rule
+    /// that doesn't match the statement
+    #[error("Invalid hint: {0}")]
+    InvalidHint(String),
+    /// A table qualified with a schema not declared by `TransformOptions::with_schema` or
+    /// `with_schema_namespace`
+    #[error("Unknown schema {0} in: {1}")]
+    UnknownSchema(String, String),
+    /// A negative predicate on `key`: Redis reads keys by name, so excluding some means
+    /// scanning the whole keyspace
+    #[error("{0} can't map to a key lookup: Redis reads keys by name, and excluding keys would scan the whole keyspace; name the keys to read with key = ... or key IN (...)")]
+    UnsupportedPredicate(ClauseUse),
+    /// Strict mode: the matched rule's rendered commands read or write a different key,
+    /// fields, or values than the statement (see `analysis::check_equivalence`)
+    #[error("Inconsistent translation by rule {0}: {1}")]
+    InconsistentTranslation(String, String),
+    /// A join other than a sorted set INNER or LEFT joined to hashes on its members, with
+    /// the first thing about it that doesn't fit
+    #[error("Unsupported join: {0}")]
+    UnsupportedJoin(String),
+    /// `update_plan` on a statement that isn't an UPDATE of string or hash keys by
+    /// `key = ...` or `key IN (...)` with an assignment reading a column
+    #[error("Not an update computed from the current values: {0}")]
+    NotPlannable(String),
+}
+
+fn join_clauses(clauses: &[ClauseUse]) -> String {
+    clauses.iter().map(|c| c.sql.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+impl SqlRedisError {
+    /// The stable code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SqlRedisError::SqlParseError(_) => ErrorCode::SqlParse,
+            SqlRedisError::NoMatchingPattern(_) => ErrorCode::UnsupportedPattern,
+            SqlRedisError::MissingKeyPredicate(_) => ErrorCode::MissingKeyPredicate,
+            SqlRedisError::TemplateError(_) => ErrorCode::Template,
+            SqlRedisError::InitializationError(_) => ErrorCode::Initialization,
+            SqlRedisError::UnsupportedClause(_) => ErrorCode::UnsupportedClause,
+            SqlRedisError::MultipleCommands(_) => ErrorCode::MultipleCommands,
+            SqlRedisError::NotCacheable(_) => ErrorCode::NotCacheable,
+            SqlRedisError::CommandTooLarge(_) => ErrorCode::CommandTooLarge,
+            SqlRedisError::InvalidHint(_) => ErrorCode::InvalidHint,
+            SqlRedisError::UnknownSchema(..) => ErrorCode::UnknownSchema,
+            SqlRedisError::UnsupportedPredicate(_) => ErrorCode::UnsupportedPredicate,
+            SqlRedisError::InconsistentTranslation(..) => ErrorCode::InconsistentTranslation,
+            SqlRedisError::UnsupportedJoin(_) => ErrorCode::UnsupportedJoin,
+            SqlRedisError::NotPlannable(_) => ErrorCode::NotPlannable,
+        }
+    }
+
+    /// Labelled source locations the error refers to (empty if unknown)
+    pub fn labels(&self) -> Vec<(Span, &'static str)> {
+        match self {
+            SqlRedisError::UnsupportedClause(clauses) => clauses.iter()
+                .filter(|c| c.span != Span::empty())
+                .map(|c| (c.span, "not supported by the matched command"))
+                .collect(),
+            SqlRedisError::UnsupportedPredicate(predicate) if predicate.span != Span::empty() => {
+                vec![(predicate.span, "excludes keys instead of naming them")]
+            }
+            SqlRedisError::SqlParseError(msg) => diagnostic::parse_error_location(msg)
+                .map(|loc| vec![(Span::new(loc, Location::new(loc.line, loc.column + 1)), "syntax error")])
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl From<TemplateError> for SqlRedisError {
+    fn from(error: TemplateError) -> Self {
+        SqlRedisError::TemplateError(error.to_string())
+    }
+}
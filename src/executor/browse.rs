@@ -0,0 +1,113 @@
+// executor/browse.rs - Keyspace browsing for `SELECT * FROM __keys` and its COUNT(*)
+// Follows the SCAN plan through its pages, then reads each key's type and value, so keys of
+// different types come back as uniform (key, type, value) rows; counts are SCANned page by page
+
+use std::collections::HashSet;
+
+use crate::commands::RedisCommand;
+use crate::executor::{Connection, ExecutionError, Executor};
+use crate::lua;
+use crate::reply::Reply;
+
+/// Check if `command` is the SCAN plan `browse_keys` expects
+pub fn is_browse_command(command: &RedisCommand) -> bool {
+    command.command.eq_ignore_ascii_case("SCAN")
+}
+
+/// Run a `SCAN 0 MATCH pattern [COUNT n]` plan: scan until the cursor returns to 0 or `n`
+/// distinct keys are found (COUNT doubles as the statement's LIMIT), then pipeline a TYPE
+/// and a type-specific read of every key. Returns one `[key, type, value]` array per key,
+/// the value being the read's reply (nil for types without a read, such as streams); keys
+/// deleted in the meantime are left out.
+pub fn browse_keys<C: Connection>(executor: &mut Executor<C>, scan: &RedisCommand) -> Result<Reply, ExecutionError> {
+    let limit = option_value(&scan.args, "COUNT").and_then(|count| count.parse::<usize>().ok());
+    let keys = scan_keys(executor, scan.clone(), limit)?;
+
+    let types = executor.execute_batch(&keys.iter().map(|key| command("TYPE", &[key])).collect::<Vec<_>>(), |_| {})?;
+    let types: Vec<String> = types.iter().map(|reply| reply.as_text().unwrap_or_default()).collect();
+    let present: Vec<(&String, &String)> = keys.iter().zip(&types).filter(|(_, kind)| *kind != "none").collect();
+    let reads: Vec<RedisCommand> = present.iter().filter_map(|(key, kind)| read_command(key, kind)).collect();
+    let mut values = executor.execute_batch(&reads, |_| {})?.into_iter();
+
+    let rows = present.into_iter()
+        .map(|(key, kind)| {
+            let value = match read_command(key, kind) {
+                Some(_) => values.next().unwrap_or(Reply::Nil),
+                None => Reply::Nil,
+            };
+            Reply::Array(vec![Reply::bulk(key.as_str()), Reply::bulk(kind.as_str()), value])
+        })
+        .collect();
+    Ok(Reply::Array(rows))
+}
+
+/// Check if `command` is the keyspace count script (`lua::keys_count`), which `count_keys` streams
+pub fn is_count_command(command: &RedisCommand) -> bool {
+    command.command.eq_ignore_ascii_case("EVAL") && command.args.first().is_some_and(|script| lua::is_keys_count_script(script))
+}
+
+/// Count the keys matching the count script's pattern with SCAN pages from the client rather
+/// than one script call, so the server isn't blocked for the whole keyspace. Keys SCAN
+/// returns twice are counted once.
+pub fn count_keys<C: Connection>(executor: &mut Executor<C>, command: &RedisCommand) -> Result<Reply, ExecutionError> {
+    let pattern = command.args.get(2).map_or("*", String::as_str);
+    let keys = scan_keys(executor, self::command("SCAN", &["0", "MATCH", pattern, "COUNT", "1000"]), None)?;
+    Ok(Reply::Integer(keys.len() as i64))
+}
+
+/// Follow `scan` from its cursor until it returns to 0 or `limit` distinct keys are found
+fn scan_keys<C: Connection>(executor: &mut Executor<C>, mut scan: RedisCommand, limit: Option<usize>) -> Result<Vec<String>, ExecutionError> {
+    let mut seen = HashSet::new();
+    let mut keys = Vec::new();
+    loop {
+        let reply = executor.execute(&scan)?;
+        let (cursor, batch) = match &reply {
+            Reply::Array(page) if page.len() == 2 => match (page[0].as_text(), &page[1]) {
+                (Some(cursor), Reply::Array(batch)) => (cursor, batch),
+                _ => return Err(unexpected_scan_reply(&reply)),
+            },
+            _ => return Err(unexpected_scan_reply(&reply)),
+        };
+        // SCAN may return a key more than once
+        keys.extend(batch.iter().filter_map(Reply::as_text).filter(|key| seen.insert(key.clone())));
+        if cursor == "0" || limit.is_some_and(|limit| keys.len() >= limit) {
+            break;
+        }
+        match scan.args.first_mut() {
+            Some(arg) => *arg = cursor,
+            None => break,
+        }
+    }
+    keys.truncate(limit.unwrap_or(keys.len()));
+    // The shadow reply (if any) is to the last SCAN page, not to the result
+    executor.take_shadow_reply();
+    Ok(keys)
+}
+
+/// The command reading a whole value of type `kind` (as TYPE reports it)
+fn read_command(key: &str, kind: &str) -> Option<RedisCommand> {
+    match kind {
+        "string" => Some(command("GET", &[key])),
+        "hash" => Some(command("HGETALL", &[key])),
+        "list" => Some(command("LRANGE", &[key, "0", "-1"])),
+        "set" => Some(command("SMEMBERS", &[key])),
+        "zset" => Some(command("ZRANGEBYSCORE", &[key, "-inf", "+inf", "WITHSCORES"])),
+        _ => None,
+    }
+}
+
+fn command(name: &str, args: &[&str]) -> RedisCommand {
+    RedisCommand::new(name.to_string(), args.iter().map(|arg| arg.to_string()).collect())
+}
+
+/// The argument following the option `name` (e.g. COUNT), case-insensitively
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg.eq_ignore_ascii_case(name))
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn unexpected_scan_reply(reply: &Reply) -> ExecutionError {
+    ExecutionError::Protocol(format!("Expected a SCAN page (cursor and keys), got {}", reply))
+}
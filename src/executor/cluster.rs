@@ -0,0 +1,125 @@
+// executor/cluster.rs - Connection to a Redis Cluster through its seed nodes
+// Sends each command to the node that last answered and follows MOVED/ASK redirects
+
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::executor::resp::{Address, RedisUrl};
+use crate::executor::{Connection, ExecutionError, RespConnection};
+use crate::reply::Reply;
+
+/// Redirects followed for one command before giving up
+const MAX_REDIRECTS: usize = 5;
+
+/// A cluster connection opened from a `redis-cluster://` URL. Node connections are opened
+/// on first use; a `MOVED` reply makes the named node the default for later commands, and
+/// an `ASK` reply retries just that command on the named node after `ASKING`.
+pub struct ClusterConnection {
+    target: RedisUrl,
+    timeout: Option<Duration>,
+    nodes: HashMap<(String, u16), RespConnection<TcpStream>>,
+    current: (String, u16),
+}
+
+impl ClusterConnection {
+    /// Connect to the first reachable seed node of a
+    /// `redis-cluster://[:password@]host1[:port],host2[:port],...` URL
+    pub fn connect(url: &str) -> Result<Self, ExecutionError> {
+        Self::connect_with_timeout(url, None)
+    }
+
+    /// Like `connect`, with every node connection failing slow replies after `timeout`
+    pub fn connect_with_timeout(url: &str, timeout: Option<Duration>) -> Result<Self, ExecutionError> {
+        let target = RedisUrl::parse(url)?;
+        let seeds = match &target.address {
+            Address::Cluster(seeds) => seeds.clone(),
+            _ => return Err(ExecutionError::Protocol(format!("Not a redis-cluster:// URL: {}", url))),
+        };
+        let mut cluster = Self { target, timeout, nodes: HashMap::new(), current: seeds[0].clone() };
+        let mut last_error = None;
+        for seed in seeds {
+            match cluster.node(&seed) {
+                Ok(_) => {
+                    cluster.current = seed;
+                    return Ok(cluster);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ExecutionError::Protocol(format!("No seed nodes in {}", url))))
+    }
+
+    /// The node commands are sent to until the cluster redirects them
+    pub fn current_node(&self) -> (&str, u16) {
+        (self.current.0.as_str(), self.current.1)
+    }
+
+    /// The open connection to `node`, connecting first if needed
+    fn node(&mut self, node: &(String, u16)) -> Result<&mut RespConnection<TcpStream>, ExecutionError> {
+        if !self.nodes.contains_key(node) {
+            let mut connection = RespConnection::connect_tcp(&node.0, node.1, &self.target)?;
+            if let Some(timeout) = self.timeout {
+                connection = connection.with_timeout(timeout)?;
+            }
+            self.nodes.insert(node.clone(), connection);
+        }
+        Ok(self.nodes.get_mut(node).expect("node connection was just opened"))
+    }
+}
+
+impl Connection for ClusterConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        let mut node = self.current.clone();
+        let mut asking = false;
+        for _ in 0..=MAX_REDIRECTS {
+            let connection = self.node(&node)?;
+            if asking {
+                connection.send(&["ASKING".to_string()])?;
+            }
+            match connection.send(args)? {
+                // An empty host means the redirect target shares the current node's host
+                Reply::Error(msg) => match parse_redirect(&msg).map(|(redirect, (host, port))| {
+                    (redirect, (if host.is_empty() { node.0.clone() } else { host }, port))
+                }) {
+                    Some((Redirect::Moved, target)) => {
+                        self.current = target.clone();
+                        node = target;
+                        asking = false;
+                    }
+                    Some((Redirect::Ask, target)) => {
+                        node = target;
+                        asking = true;
+                    }
+                    None => return Ok(Reply::Error(msg)),
+                },
+                reply => return Ok(reply),
+            }
+        }
+        Err(ExecutionError::Protocol(format!("Too many cluster redirects for {}", args.first().map_or("", String::as_str))))
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        let current = self.current.clone();
+        self.node(&current)?.receive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Redirect {
+    Moved,
+    Ask,
+}
+
+/// Parse a `MOVED <slot> <host>:<port>` or `ASK <slot> <host>:<port>` error reply
+fn parse_redirect(msg: &str) -> Option<(Redirect, (String, u16))> {
+    let mut parts = msg.split_whitespace();
+    let redirect = match parts.next()? {
+        "MOVED" => Redirect::Moved,
+        "ASK" => Redirect::Ask,
+        _ => return None,
+    };
+    let _slot = parts.next()?;
+    let (host, port) = parts.next()?.rsplit_once(':')?;
+    Some((redirect, (host.to_string(), port.parse().ok()?)))
+}
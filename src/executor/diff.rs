@@ -0,0 +1,243 @@
+// executor/diff.rs - Dry-run previews of write commands
+// Reads the current state a write would touch and reports what would change, without writing
+
+use std::fmt;
+
+use crate::commands::RedisCommand;
+use crate::executor::{Connection, ExecutionError, Executor};
+use crate::reply::Reply;
+
+/// One effect a write command would have on the current data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A key that doesn't exist yet would be created
+    NewKey { key: String },
+    /// A string value would be replaced
+    Overwrite { key: String, old: String, new: String },
+    /// A hash field (or list index) would be added (`old` is None) or overwritten
+    SetField { key: String, field: String, old: Option<String>, new: String },
+    /// A hash field would be removed
+    DeleteField { key: String, field: String, old: String },
+    /// A set or sorted set member would be added
+    AddMember { key: String, member: String },
+    /// A sorted set member's score would change
+    SetScore { key: String, member: String, old: String, new: String },
+    /// A list element would be appended or prepended
+    Push { key: String, value: String },
+    /// A set, sorted set, or list member would be removed
+    RemoveMember { key: String, member: String },
+    /// A whole key would be deleted
+    DeleteKey { key: String },
+    /// The command writes in a way that can't be previewed (e.g. EVAL)
+    Unknown { command: String },
+}
+
+impl fmt::Display for Change {
+    /// `+` for additions, `~` for modifications, `-` for removals, `?` for unknown effects
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::NewKey { key } => write!(f, "+ {} (new key)", key),
+            Change::Overwrite { key, old, new } => write!(f, "~ {}: {:?} -> {:?}", key, old, new),
+            Change::SetField { key, field, old: None, new } => write!(f, "+ {}.{} = {:?}", key, field, new),
+            Change::SetField { key, field, old: Some(old), new } => write!(f, "~ {}.{}: {:?} -> {:?}", key, field, old, new),
+            Change::DeleteField { key, field, old } => write!(f, "- {}.{} (was {:?})", key, field, old),
+            Change::AddMember { key, member } => write!(f, "+ {} member {:?}", key, member),
+            Change::SetScore { key, member, old, new } => write!(f, "~ {} member {:?} score: {} -> {}", key, member, old, new),
+            Change::Push { key, value } => write!(f, "+ {} element {:?}", key, value),
+            Change::RemoveMember { key, member } => write!(f, "- {} member {:?}", key, member),
+            Change::DeleteKey { key } => write!(f, "- {} (deleted)", key),
+            Change::Unknown { command } => write!(f, "? {} (effect cannot be previewed)", command),
+        }
+    }
+}
+
+/// Preview a command against the server's current state, issuing only read commands.
+/// Read-only commands and writes that would change nothing produce no changes.
+pub fn diff_command<C: Connection>(executor: &mut Executor<C>, command: &RedisCommand) -> Result<Vec<Change>, ExecutionError> {
+    let args = &command.args;
+    let key = match args.first() {
+        Some(key) => key.clone(),
+        None => return Ok(Vec::new()),
+    };
+    let mut changes = Vec::new();
+
+    match command.command.to_uppercase().as_str() {
+        "SET" if args.len() >= 2 => {
+            let new = args[1].clone();
+            match read_text(executor, "GET", &[&key])? {
+                None => changes.push(Change::NewKey { key }),
+                Some(old) if old != new => changes.push(Change::Overwrite { key, old, new }),
+                Some(_) => {}
+            }
+        }
+        "HSET" | "HMSET" => {
+            if !exists(executor, &key)? {
+                changes.push(Change::NewKey { key: key.clone() });
+            }
+            for pair in args[1..].chunks_exact(2) {
+                let old = read_text(executor, "HGET", &[&key, &pair[0]])?;
+                if old.as_deref() != Some(pair[1].as_str()) {
+                    changes.push(Change::SetField { key: key.clone(), field: pair[0].clone(), old, new: pair[1].clone() });
+                }
+            }
+        }
+        "HDEL" => {
+            for field in &args[1..] {
+                if let Some(old) = read_text(executor, "HGET", &[&key, field])? {
+                    changes.push(Change::DeleteField { key: key.clone(), field: field.clone(), old });
+                }
+            }
+        }
+        "DEL" | "UNLINK" => {
+            for key in args {
+                if exists(executor, key)? {
+                    changes.push(Change::DeleteKey { key: key.clone() });
+                }
+            }
+        }
+        "SADD" => {
+            if !exists(executor, &key)? {
+                changes.push(Change::NewKey { key: key.clone() });
+            }
+            for member in &args[1..] {
+                if read_integer(executor, "SISMEMBER", &[&key, member])? == 0 {
+                    changes.push(Change::AddMember { key: key.clone(), member: member.clone() });
+                }
+            }
+        }
+        "SREM" => {
+            for member in &args[1..] {
+                if read_integer(executor, "SISMEMBER", &[&key, member])? == 1 {
+                    changes.push(Change::RemoveMember { key: key.clone(), member: member.clone() });
+                }
+            }
+        }
+        "ZREM" => {
+            for member in &args[1..] {
+                if read_text(executor, "ZSCORE", &[&key, member])?.is_some() {
+                    changes.push(Change::RemoveMember { key: key.clone(), member: member.clone() });
+                }
+            }
+        }
+        "ZADD" => {
+            if !exists(executor, &key)? {
+                changes.push(Change::NewKey { key: key.clone() });
+            }
+            for pair in args[1..].chunks_exact(2) {
+                let (score, member) = (&pair[0], &pair[1]);
+                match read_text(executor, "ZSCORE", &[&key, member])? {
+                    None => changes.push(Change::AddMember { key: key.clone(), member: member.clone() }),
+                    Some(old) if !same_score(&old, score) => changes.push(Change::SetScore {
+                        key: key.clone(), member: member.clone(), old, new: score.clone(),
+                    }),
+                    Some(_) => {}
+                }
+            }
+        }
+        "ZREMRANGEBYSCORE" if args.len() >= 3 => {
+            for member in read_list(executor, "ZRANGEBYSCORE", &[&key, &args[1], &args[2]])? {
+                changes.push(Change::RemoveMember { key: key.clone(), member });
+            }
+        }
+        "ZPOPMIN" | "ZPOPMAX" => {
+            let count: i64 = args.get(1).and_then(|n| n.parse().ok()).unwrap_or(1);
+            let range = if command.command.eq_ignore_ascii_case("ZPOPMIN") {
+                ("0".to_string(), (count - 1).to_string())
+            } else {
+                ((-count).to_string(), "-1".to_string())
+            };
+            for member in read_list(executor, "ZRANGE", &[&key, &range.0, &range.1])? {
+                changes.push(Change::RemoveMember { key: key.clone(), member });
+            }
+        }
+        "RPUSH" | "LPUSH" => {
+            if !exists(executor, &key)? {
+                changes.push(Change::NewKey { key: key.clone() });
+            }
+            for value in &args[1..] {
+                changes.push(Change::Push { key: key.clone(), value: value.clone() });
+            }
+        }
+        "LSET" if args.len() >= 3 => {
+            let old = read_text(executor, "LINDEX", &[&key, &args[1]])?;
+            if old.as_deref() != Some(args[2].as_str()) {
+                changes.push(Change::SetField { key, field: args[1].clone(), old, new: args[2].clone() });
+            }
+        }
+        "LREM" if args.len() >= 3 => {
+            let count: i64 = args[1].parse().unwrap_or(0);
+            let matches = read_list(executor, "LRANGE", &[&key, "0", "-1"])?
+                .into_iter()
+                .filter(|value| *value == args[2])
+                .count();
+            let removed = if count == 0 { matches } else { matches.min(count.unsigned_abs() as usize) };
+            for _ in 0..removed {
+                changes.push(Change::RemoveMember { key: key.clone(), member: args[2].clone() });
+            }
+        }
+        "LTRIM" if args.len() >= 3 => {
+            let values = read_list(executor, "LRANGE", &[&key, "0", "-1"])?;
+            let kept = kept_range(values.len(), &args[1], &args[2]);
+            for (i, member) in values.into_iter().enumerate() {
+                if !kept.contains(&i) {
+                    changes.push(Change::RemoveMember { key: key.clone(), member });
+                }
+            }
+        }
+        "GET" | "MGET" | "HGET" | "HMGET" | "HGETALL" | "HLEN" | "LRANGE" | "LINDEX" | "LLEN"
+        | "SMEMBERS" | "SISMEMBER" | "SCARD" | "ZRANGE" | "ZRANGEBYSCORE" | "ZREVRANGEBYSCORE"
+        | "ZCARD" | "ZCOUNT" | "ZSCORE" | "EXISTS" | "TYPE" | "OBJECT" => {}
+        _ => changes.push(Change::Unknown { command: command.to_string() }),
+    }
+    Ok(changes)
+}
+
+/// Indices LTRIM keeps for a list of `len` elements, resolving negative offsets
+fn kept_range(len: usize, start: &str, stop: &str) -> std::ops::Range<usize> {
+    let len = len as i64;
+    let resolve = |index: &str| {
+        let index: i64 = index.parse().unwrap_or(0);
+        if index < 0 { len + index } else { index }
+    };
+    let start = resolve(start).max(0);
+    let stop = resolve(stop).min(len - 1);
+    if start > stop {
+        0..0
+    } else {
+        start as usize..stop as usize + 1
+    }
+}
+
+/// Compare scores numerically, so "10" and "10.0" are the same score
+fn same_score(old: &str, new: &str) -> bool {
+    match (old.parse::<f64>(), new.parse::<f64>()) {
+        (Ok(old), Ok(new)) => old == new,
+        _ => old == new,
+    }
+}
+
+fn read<C: Connection>(executor: &mut Executor<C>, name: &str, args: &[&str]) -> Result<Reply, ExecutionError> {
+    executor.execute(&RedisCommand::new(name, args.to_vec()))
+}
+
+fn read_text<C: Connection>(executor: &mut Executor<C>, name: &str, args: &[&str]) -> Result<Option<String>, ExecutionError> {
+    Ok(read(executor, name, args)?.as_text())
+}
+
+fn read_integer<C: Connection>(executor: &mut Executor<C>, name: &str, args: &[&str]) -> Result<i64, ExecutionError> {
+    match read(executor, name, args)? {
+        Reply::Integer(n) => Ok(n),
+        other => Err(ExecutionError::Protocol(format!("Expected integer reply to {}, got {}", name, other))),
+    }
+}
+
+fn read_list<C: Connection>(executor: &mut Executor<C>, name: &str, args: &[&str]) -> Result<Vec<String>, ExecutionError> {
+    match read(executor, name, args)? {
+        Reply::Array(items) => Ok(items.iter().filter_map(Reply::as_text).collect()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn exists<C: Connection>(executor: &mut Executor<C>, key: &str) -> Result<bool, ExecutionError> {
+    Ok(read_integer(executor, "EXISTS", &[key])? > 0)
+}
@@ -0,0 +1,76 @@
+// executor/emulator.rs - In-process Redis emulation for offline runs
+// A `Connection` over in-memory keyspaces (one per logical database), so generated commands
+// can be executed in tests and demos without a server; Lua scripts run in an embedded
+// interpreter, while functions and modules are not emulated
+
+use std::collections::BTreeMap;
+
+use crate::lua::MockRedis;
+use crate::reply::Reply;
+use super::{Connection, ExecutionError};
+
+/// An emulated server: strings, hashes, lists, sets, and sorted sets in memory, with
+/// SELECT, FLUSHDB/FLUSHALL, DBSIZE, and PING. EVAL/EVALSHA run the script against the
+/// selected database (see `MockRedis`). Keys never expire; FCALL and module commands
+/// (JSON.*) reply with an error.
+#[derive(Debug, Clone, Default)]
+pub struct Emulator {
+    databases: BTreeMap<u32, MockRedis>,
+    database: u32,
+}
+
+impl Emulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The logical database commands currently run against
+    pub fn database(&self) -> u32 {
+        self.database
+    }
+
+    fn keyspace(&mut self) -> &mut MockRedis {
+        self.databases.entry(self.database).or_default()
+    }
+}
+
+impl Connection for Emulator {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        let Some(name) = args.first() else {
+            return Err(ExecutionError::Protocol("empty command".to_string()));
+        };
+        let reply = match name.to_uppercase().as_str() {
+            "PING" => Reply::Status("PONG".to_string()),
+            "SELECT" => match args.get(1).and_then(|db| db.parse().ok()) {
+                Some(db) => {
+                    self.database = db;
+                    Reply::Status("OK".to_string())
+                }
+                None => Reply::Error("ERR invalid DB index".to_string()),
+            },
+            "FLUSHDB" => {
+                self.databases.remove(&self.database);
+                Reply::Status("OK".to_string())
+            }
+            "FLUSHALL" => {
+                self.databases.clear();
+                Reply::Status("OK".to_string())
+            }
+            "DBSIZE" => match self.keyspace().call(&["KEYS", "*"]) {
+                Reply::Array(keys) => Reply::Integer(keys.len() as i64),
+                other => other,
+            },
+            _ => {
+                let keyspace = self.keyspace();
+                let reply = keyspace.call(args);
+                keyspace.clear_calls();
+                reply
+            }
+        };
+        Ok(reply)
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("the emulator has no pub/sub".to_string()))
+    }
+}
@@ -0,0 +1,124 @@
+// executor/fanout.rs - Mirror writes to several targets and compare their replies
+// E.g. the old and new cluster during a migration: reads go to the primary only, writes
+// go everywhere, and replies that differ from the primary's are reported as divergences
+
+use std::fmt;
+
+use crate::acl;
+use crate::commands::RedisCommand;
+use crate::executor::{Connection, ExecutionError};
+use crate::reply::Reply;
+
+/// A mirrored command whose reply on a target differed from the primary's
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The command, quoted as for redis-cli
+    pub command: String,
+    /// Name of the target that diverged
+    pub target: String,
+    /// The primary's reply
+    pub expected: Reply,
+    /// The target's reply, or the error that prevented one
+    pub actual: Result<Reply, String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: primary replied {}, {} ", self.command, self.expected, self.target)?;
+        match &self.actual {
+            Ok(reply) => write!(f, "replied {}", reply),
+            Err(error) => write!(f, "failed: {}", error),
+        }
+    }
+}
+
+/// A connection that sends every command to a primary and mirrors writes to other targets.
+/// The primary's replies are returned; a target's failure doesn't fail the command but is
+/// reported, like a differing reply, to the divergence callback.
+pub struct FanOutConnection {
+    primary: Box<dyn Connection>,
+    targets: Vec<(String, Box<dyn Connection>)>,
+    on_divergence: Box<dyn FnMut(&Divergence)>,
+}
+
+impl FanOutConnection {
+    /// Fan out from `primary`; divergences are discarded until `on_divergence` is set
+    pub fn new(primary: Box<dyn Connection>) -> Self {
+        Self { primary, targets: Vec::new(), on_divergence: Box::new(|_| {}) }
+    }
+
+    /// Mirror writes to `connection`, reported under `name`
+    pub fn with_target(mut self, name: &str, connection: Box<dyn Connection>) -> Self {
+        self.targets.push((name.to_string(), connection));
+        self
+    }
+
+    /// Report each divergence to `on_divergence`, e.g. to log it or collect a summary
+    pub fn on_divergence(mut self, on_divergence: impl FnMut(&Divergence) + 'static) -> Self {
+        self.on_divergence = Box::new(on_divergence);
+        self
+    }
+
+    /// Names of the mirror targets, in the order they are written
+    pub fn targets(&self) -> impl Iterator<Item = &str> {
+        self.targets.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Send the mirrored subset of `commands` to every target and compare with `expected`,
+    /// the primary's replies to all of `commands`
+    fn mirror(&mut self, commands: &[Vec<String>], expected: &[Reply]) {
+        let mirrored: Vec<usize> = (0..commands.len()).filter(|&i| is_mirrored(&commands[i])).collect();
+        if mirrored.is_empty() {
+            return;
+        }
+        let batch: Vec<Vec<String>> = mirrored.iter().map(|&i| commands[i].clone()).collect();
+        for (name, connection) in &mut self.targets {
+            let replies = connection.send_pipeline(&batch);
+            for (n, &i) in mirrored.iter().enumerate() {
+                let actual = match &replies {
+                    Ok(replies) => match replies.get(n) {
+                        Some(reply) if *reply == expected[i] => continue,
+                        Some(reply) => Ok(reply.clone()),
+                        None => Err("no reply".to_string()),
+                    },
+                    Err(e) => Err(e.to_string()),
+                };
+                (self.on_divergence)(&Divergence {
+                    command: to_command(&commands[i]).to_quoted_string(),
+                    target: name.clone(),
+                    expected: expected[i].clone(),
+                    actual,
+                });
+            }
+        }
+    }
+}
+
+impl Connection for FanOutConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        let reply = self.primary.send(args)?;
+        self.mirror(&[args.to_vec()], std::slice::from_ref(&reply));
+        Ok(reply)
+    }
+
+    fn send_pipeline(&mut self, commands: &[Vec<String>]) -> Result<Vec<Reply>, ExecutionError> {
+        let replies = self.primary.send_pipeline(commands)?;
+        self.mirror(commands, &replies);
+        Ok(replies)
+    }
+
+    /// Pub/sub messages come from the primary
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        self.primary.receive()
+    }
+}
+
+/// Writes are mirrored, and so is SELECT, so every target writes to the same database
+fn is_mirrored(args: &[String]) -> bool {
+    let command = to_command(args);
+    command.command.eq_ignore_ascii_case("SELECT") || !acl::is_read_only(&command)
+}
+
+fn to_command(args: &[String]) -> RedisCommand {
+    RedisCommand::new(args.first().cloned().unwrap_or_default(), args.iter().skip(1).cloned().collect())
+}
@@ -0,0 +1,500 @@
+// executor/mod.rs - Execute generated commands against a Redis server
+// A small, dependency-free execution layer: a Connection trait plus a RESP client
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::acl;
+use crate::commands::{split_commands, RedisCommand};
+use crate::metrics::Metrics;
+use crate::plan::{Plan, Step};
+use crate::reply::Reply;
+
+mod resp;
+mod cluster;
+mod fanout;
+pub mod diff;
+mod browse;
+mod scripts;
+#[cfg(feature = "emulator")]
+mod emulator;
+#[cfg(feature = "tls")]
+mod tls;
+
+pub use resp::{RespConnection, SocketStream};
+pub use cluster::ClusterConnection;
+pub use fanout::{Divergence, FanOutConnection};
+pub use diff::{diff_command, Change};
+pub use browse::{browse_keys, count_keys, is_browse_command, is_count_command};
+pub use scripts::ScriptCache;
+#[cfg(feature = "emulator")]
+pub use emulator::Emulator;
+#[cfg(feature = "tls")]
+pub use tls::{TlsOptions, TlsStream};
+
+/// Errors raised while executing commands
+#[derive(Debug, Error)]
+pub enum ExecutionError {
+    /// Socket or stream failure
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    /// Malformed reply or unparsable command/URL
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+    /// The server answered with an error reply
+    #[error("Server error: {0}")]
+    Server(String),
+    /// No reply arrived within the connection's timeout; the command may still run on the server
+    #[error("Timed out after {0:?} waiting for a reply")]
+    Timeout(Duration),
+    /// The executor's cancellation token was triggered before the command was sent
+    #[error("Execution cancelled")]
+    Cancelled,
+    /// The confirmation callback declined a destructive command, which was not sent
+    #[error("Declined to run {0}")]
+    Declined(String),
+    /// A command that may write was refused by a read-only executor
+    #[error("Refusing to run {0} in read-only mode")]
+    ReadOnly(String),
+}
+
+/// Which commands an executor sends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutionMode {
+    #[default]
+    ReadWrite,
+    /// Only commands classified as reads (see `acl::is_read_only`), for pointing the
+    /// executor at production replicas without risking a write
+    ReadOnly,
+}
+
+/// A cooperative cancellation flag shared between an executor and the code driving it.
+/// Cancelling stops the executor before its next command or pipelined window; a command
+/// already waiting for its reply runs to completion (or its timeout).
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every executor holding a clone of this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// A connection able to send commands and receive replies
+pub trait Connection {
+    /// Send a command (name followed by arguments) and wait for its reply
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError>;
+
+    /// Send several commands and collect their replies in order.
+    /// The default sends them one at a time; pipelining connections override it.
+    fn send_pipeline(&mut self, commands: &[Vec<String>]) -> Result<Vec<Reply>, ExecutionError> {
+        commands.iter().map(|args| self.send(args)).collect()
+    }
+
+    /// Wait for the next unsolicited reply (pub/sub messages after SUBSCRIBE)
+    fn receive(&mut self) -> Result<Reply, ExecutionError>;
+}
+
+/// Boxed connections, so one executor type can drive a server or a stand-in
+impl<C: Connection + ?Sized> Connection for Box<C> {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        (**self).send(args)
+    }
+
+    fn send_pipeline(&mut self, commands: &[Vec<String>]) -> Result<Vec<Reply>, ExecutionError> {
+        (**self).send_pipeline(commands)
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        (**self).receive()
+    }
+}
+
+/// Pacing for `Executor::execute_batch`, so large batches don't overwhelm the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionOptions {
+    /// Commands written before waiting for their replies (pipeline depth)
+    pub max_in_flight: usize,
+    /// Commands per chunk; the executor pauses between chunks
+    pub chunk_size: usize,
+    /// Pause after each chunk
+    pub delay_between_chunks: Duration,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 64,
+            chunk_size: 10_000,
+            delay_between_chunks: Duration::ZERO,
+        }
+    }
+}
+
+/// Progress of a batch, reported after each pipelined window of commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Commands whose replies have been received
+    pub completed: usize,
+    /// Commands in the batch
+    pub total: usize,
+}
+
+/// Decides whether a destructive command may run, given the keys it removes
+type Confirmation = Box<dyn FnMut(&RedisCommand, &[String]) -> bool>;
+
+/// Runs commands over a connection
+pub struct Executor<C: Connection> {
+    connection: C,
+    options: ExecutionOptions,
+    metrics: Option<Arc<dyn Metrics>>,
+    /// Logical database the connection last SELECTed, if known
+    database: Option<u32>,
+    cancellation: Option<CancellationToken>,
+    mode: ExecutionMode,
+    /// Asked before sending a command that removes whole keys (see `destroyed_keys`)
+    confirm: Option<Confirmation>,
+    /// Second server each read is repeated on, and its reply to the last one
+    shadow: Option<Box<dyn Connection>>,
+    shadow_reply: Option<Result<Reply, ExecutionError>>,
+    /// Scripts already sent, so repeated EVALs go out as EVALSHA
+    scripts: Option<ScriptCache>,
+}
+
+impl<C: Connection> Executor<C> {
+    pub fn new(connection: C) -> Self {
+        Self::with_options(connection, ExecutionOptions::default())
+    }
+
+    /// Create an executor whose batches are paced by `options`
+    pub fn with_options(connection: C, options: ExecutionOptions) -> Self {
+        Self { connection, options, metrics: None, database: None, cancellation: None, mode: ExecutionMode::ReadWrite, confirm: None, shadow: None, shadow_reply: None, scripts: None }
+    }
+
+    /// Report each command's latency and outcome to `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Stop executing once `token` is cancelled; later calls fail with `ExecutionError::Cancelled`
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Repeat each read run with `execute` (and each SELECT) on `shadow`, e.g. the new cluster
+    /// of a migration or a replica after a change; compare with `take_shadow_reply`
+    pub fn with_shadow(mut self, shadow: Box<dyn Connection>) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Refuse commands that may write with `ExecutionError::ReadOnly` in `ExecutionMode::ReadOnly`
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Ask `confirm` with each destructive command and the keys it removes before sending
+    /// it; a declined command fails with `ExecutionError::Declined` and isn't sent
+    pub fn with_confirmation(mut self, confirm: impl FnMut(&RedisCommand, &[String]) -> bool + 'static) -> Self {
+        self.confirm = Some(Box::new(confirm));
+        self
+    }
+
+    /// Send an EVAL whose script this executor already sent as EVALSHA, falling back to
+    /// EVAL when the server answers NOSCRIPT; hits and misses are counted on the cache
+    /// (`script_cache`) and reported to `Metrics::on_script_cache`
+    pub fn with_script_cache(mut self) -> Self {
+        self.scripts = Some(ScriptCache::new());
+        self
+    }
+
+    /// The script cache, if enabled with `with_script_cache`
+    pub fn script_cache(&self) -> Option<&ScriptCache> {
+        self.scripts.as_ref()
+    }
+
+    /// The logical database selected through this executor, if any
+    pub fn database(&self) -> Option<u32> {
+        self.database
+    }
+
+    /// Execute a single command; server error replies become `ExecutionError::Server`.
+    /// A `SELECT` of the database already in use is answered locally without a round trip.
+    pub fn execute(&mut self, command: &RedisCommand) -> Result<Reply, ExecutionError> {
+        self.check_cancelled()?;
+        self.check_allowed(command)?;
+        self.shadow_reply = None;
+        let selects = selected_database(command);
+        if selects.is_some() && selects == self.database {
+            return Ok(Reply::Status("OK".to_string()));
+        }
+        let script = self.scripts.as_ref().and_then(|cache| cache.lookup(command));
+        let args = scripts::wire_args(command, script.as_ref());
+
+        let started = Instant::now();
+        let reply = self.connection.send(&args).and_then(|reply| self.settle_script(command, script, reply));
+        if let Some(metrics) = &self.metrics {
+            let success = reply.as_ref().is_ok_and(|reply| !matches!(reply, Reply::Error(_)));
+            metrics.on_execute(&command.command, started.elapsed(), success);
+        }
+        match reply? {
+            Reply::Error(msg) => Err(ExecutionError::Server(msg)),
+            reply => {
+                if selects.is_some() {
+                    self.database = selects;
+                }
+                self.shadow_read(command);
+                Ok(reply)
+            }
+        }
+    }
+
+    /// Count a script command's reply as a hit or miss, retrying an EVALSHA the server
+    /// answered with NOSCRIPT as the EVAL it stands for
+    fn settle_script(&mut self, command: &RedisCommand, script: Option<(String, bool)>, reply: Reply) -> Result<Reply, ExecutionError> {
+        let Some((sha, cached)) = script else {
+            return Ok(reply);
+        };
+        let hit = cached && !scripts::is_noscript(&reply);
+        let reply = match cached && !hit {
+            true => self.connection.send(&scripts::wire_args(command, None))?,
+            false => reply,
+        };
+        if let Some(cache) = &mut self.scripts {
+            cache.record(sha, hit, &reply);
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.on_script_cache(hit);
+        }
+        Ok(reply)
+    }
+
+    /// Repeat a successful read on the shadow connection, keeping its reply; SELECTs are
+    /// repeated too, so the shadow reads from the same database. Scripts are sent in full,
+    /// as the shadow may not have them loaded.
+    fn shadow_read(&mut self, command: &RedisCommand) {
+        let Some(shadow) = &mut self.shadow else {
+            return;
+        };
+        let args = &scripts::wire_args(command, None);
+        if selected_database(command).is_some() {
+            let _ = shadow.send(args);
+        } else if acl::is_read_only(command) {
+            self.shadow_reply = Some(shadow.send(args).and_then(|reply| match reply {
+                Reply::Error(msg) => Err(ExecutionError::Server(msg)),
+                reply => Ok(reply),
+            }));
+        }
+    }
+
+    /// The shadow connection's reply if the last command run with `execute` was a read
+    pub fn take_shadow_reply(&mut self) -> Option<Result<Reply, ExecutionError>> {
+        self.shadow_reply.take()
+    }
+
+    /// Execute rendered transformer output, one command per line (quoted arguments may span lines)
+    pub fn execute_text(&mut self, text: &str) -> Result<Vec<Reply>, ExecutionError> {
+        split_commands(text)
+            .into_iter()
+            .map(|line| {
+                let command = RedisCommand::parse(line)
+                    .ok_or_else(|| ExecutionError::Protocol(format!("Cannot parse command: {}", line)))?;
+                self.execute(&command)
+            })
+            .collect()
+    }
+
+    /// Run a plan: Command and Eval steps are pipelined up to the next barrier or client-side
+    /// computation, which runs on the replies of its input steps and pipelines the commands
+    /// it computes. Returns the reply of every command run, in order, with server errors in
+    /// place as in `execute_batch`.
+    pub fn execute_plan(&mut self, plan: &Plan) -> Result<Vec<Reply>, ExecutionError> {
+        let mut replies = Vec::new();
+        let mut step_replies: Vec<Option<Reply>> = vec![None; plan.steps.len()];
+        let mut pending: Vec<(usize, RedisCommand)> = Vec::new();
+        for (i, step) in plan.steps.iter().enumerate() {
+            if let Some(command) = step.to_command() {
+                pending.push((i, command));
+                continue;
+            }
+            self.execute_pending(&mut pending, &mut step_replies, &mut replies)?;
+            if let Step::ClientCompute(compute) = step {
+                let inputs = compute.inputs.iter()
+                    .map(|&input| Some((plan.steps.get(input)?.to_command()?, step_replies[input].clone()?)))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| ExecutionError::Protocol(format!("step {} reads a step without a reply", i)))?;
+                replies.extend(self.execute_batch(&compute.commands(&inputs), |_| {})?);
+            }
+        }
+        self.execute_pending(&mut pending, &mut step_replies, &mut replies)?;
+        Ok(replies)
+    }
+
+    /// Pipeline a plan's pending steps, keeping each step's reply
+    fn execute_pending(&mut self, pending: &mut Vec<(usize, RedisCommand)>, step_replies: &mut [Option<Reply>], replies: &mut Vec<Reply>) -> Result<(), ExecutionError> {
+        let commands: Vec<RedisCommand> = pending.iter().map(|(_, command)| command.clone()).collect();
+        let batch = self.execute_batch(&commands, |_| {})?;
+        for ((step, _), reply) in pending.drain(..).zip(&batch) {
+            step_replies[step] = Some(reply.clone());
+        }
+        replies.extend(batch);
+        Ok(())
+    }
+
+    /// Execute many commands in chunks of `chunk_size`, pipelining up to `max_in_flight`
+    /// at a time and pausing `delay_between_chunks` between chunks. Server error replies
+    /// are returned in place (as `Reply::Error`) so one failing command doesn't abort
+    /// the batch; `on_progress` is called after each pipelined window. Cancellation is
+    /// checked before each window, so a cancelled batch stops at a window boundary.
+    pub fn execute_batch<F>(&mut self, commands: &[RedisCommand], mut on_progress: F) -> Result<Vec<Reply>, ExecutionError>
+    where
+        F: FnMut(BatchProgress),
+    {
+        let chunk_size = self.options.chunk_size.max(1);
+        let max_in_flight = self.options.max_in_flight.max(1);
+        let mut replies = Vec::with_capacity(commands.len());
+
+        for (i, chunk) in commands.chunks(chunk_size).enumerate() {
+            if i > 0 && !self.options.delay_between_chunks.is_zero() {
+                thread::sleep(self.options.delay_between_chunks);
+            }
+            for window in chunk.chunks(max_in_flight) {
+                self.check_cancelled()?;
+                window.iter().try_for_each(|command| self.check_allowed(command))?;
+                let window_scripts: Vec<Option<(String, bool)>> = window.iter()
+                    .map(|command| self.scripts.as_ref().and_then(|cache| cache.lookup(command)))
+                    .collect();
+                let args: Vec<Vec<String>> = window.iter()
+                    .zip(&window_scripts)
+                    .map(|(command, script)| scripts::wire_args(command, script.as_ref()))
+                    .collect();
+                let started = Instant::now();
+                let window_replies = self.connection.send_pipeline(&args).and_then(|window_replies| {
+                    window.iter().zip(window_scripts).zip(window_replies)
+                        .map(|((command, script), reply)| self.settle_script(command, script, reply))
+                        .collect::<Result<Vec<_>, _>>()
+                });
+                if let Some(metrics) = &self.metrics {
+                    let share = started.elapsed() / window.len() as u32;
+                    for (i, command) in window.iter().enumerate() {
+                        let success = window_replies.as_ref().is_ok_and(|r| !matches!(r.get(i), Some(Reply::Error(_))));
+                        metrics.on_execute(&command.command, share, success);
+                    }
+                }
+                let window_replies = window_replies?;
+                for (command, reply) in window.iter().zip(&window_replies) {
+                    if let (Some(db), false) = (selected_database(command), matches!(reply, Reply::Error(_))) {
+                        self.database = Some(db);
+                    }
+                }
+                replies.extend(window_replies);
+                on_progress(BatchProgress { completed: replies.len(), total: commands.len() });
+            }
+        }
+        Ok(replies)
+    }
+
+    fn check_cancelled(&self) -> Result<(), ExecutionError> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(ExecutionError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuse writes in read-only mode, then confirm destructive commands
+    fn check_allowed(&mut self, command: &RedisCommand) -> Result<(), ExecutionError> {
+        if self.mode == ExecutionMode::ReadOnly && !acl::is_read_only(command) {
+            return Err(ExecutionError::ReadOnly(command.command.clone()));
+        }
+        if let (Some(confirm), Some(keys)) = (&mut self.confirm, destroyed_keys(command)) {
+            if !confirm(command, &keys) {
+                return Err(ExecutionError::Declined(command.command.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Iterate over messages pushed by the server after a (P)SUBSCRIBE.
+    /// The iterator ends at the first connection error.
+    pub fn messages(&mut self) -> impl Iterator<Item = Reply> + '_ {
+        std::iter::from_fn(move || self.connection.receive().ok())
+    }
+}
+
+/// The database a `SELECT n` command switches to
+fn selected_database(command: &RedisCommand) -> Option<u32> {
+    match command.args.as_slice() {
+        [db] if command.command.eq_ignore_ascii_case("SELECT") => db.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Open a connection for any supported URL: `redis://host[:port][/db]`,
+/// `rediss://host[:port][/db]` (`tls` feature, trusting Mozilla's roots),
+/// `redis+unix:///path/to/redis.sock[?db=N]` (Unix only), or
+/// `redis-cluster://host1[:port],host2[:port]`, optionally failing slow replies after `timeout`
+pub fn connect(url: &str, timeout: Option<Duration>) -> Result<Box<dyn Connection>, ExecutionError> {
+    if url.starts_with("redis-cluster://") {
+        return Ok(Box::new(ClusterConnection::connect_with_timeout(url, timeout)?));
+    }
+    #[cfg(feature = "tls")]
+    if url.starts_with("rediss://") {
+        return connect_tls(url, timeout, &TlsOptions::default());
+    }
+    #[cfg(not(feature = "tls"))]
+    if url.starts_with("rediss://") {
+        return Err(ExecutionError::Protocol(format!("rediss:// URLs need the `tls` feature: {}", url)));
+    }
+    #[cfg(unix)]
+    if url.starts_with("redis+unix://") {
+        return with_timeout(RespConnection::connect_unix(url)?, timeout);
+    }
+    with_timeout(RespConnection::connect(url)?, timeout)
+}
+
+/// Open a `rediss://` connection verified with `options` (`tls` feature)
+#[cfg(feature = "tls")]
+pub fn connect_tls(url: &str, timeout: Option<Duration>, options: &TlsOptions) -> Result<Box<dyn Connection>, ExecutionError> {
+    with_timeout(RespConnection::connect_tls(url, options)?, timeout)
+}
+
+fn with_timeout<S: SocketStream + 'static>(connection: RespConnection<S>, timeout: Option<Duration>) -> Result<Box<dyn Connection>, ExecutionError> {
+    Ok(match timeout {
+        Some(timeout) => Box::new(connection.with_timeout(timeout)?),
+        None => Box::new(connection),
+    })
+}
+
+/// The keys a command removes wholesale: DEL/UNLINK's keys, or `*` for FLUSHDB/FLUSHALL.
+/// None for commands that leave keys in place (field, member and element removals included).
+pub fn destroyed_keys(command: &RedisCommand) -> Option<Vec<String>> {
+    match command.command.to_uppercase().as_str() {
+        "DEL" | "UNLINK" => Some(command.args.clone()),
+        "FLUSHDB" | "FLUSHALL" => Some(vec!["*".to_string()]),
+        _ => None,
+    }
+}
+
+/// Check if a command switches the connection into pub/sub mode
+pub fn is_subscribe_command(command: &RedisCommand) -> bool {
+    let name = command.command.to_uppercase();
+    name == "SUBSCRIBE" || name == "PSUBSCRIBE"
+}
@@ -0,0 +1,360 @@
+// executor/resp.rs - Minimal RESP2 client over any byte stream
+// Encodes commands as RESP arrays of bulk strings and parses server replies
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[cfg(feature = "tls")]
+use crate::executor::tls::{self, TlsOptions, TlsStream};
+use crate::executor::{Connection, ExecutionError};
+use crate::reply::Reply;
+
+/// A RESP connection over a byte stream (TCP in production, in-memory in tests)
+pub struct RespConnection<S: Read + Write> {
+    reader: BufReader<S>,
+    /// Reply timeout set on the stream, reported by `ExecutionError::Timeout`
+    timeout: Option<Duration>,
+    /// How to open the stream again, for connections opened from a URL
+    reopen: Option<Reopen<S>>,
+    /// A command failed mid-reply (e.g. timed out), so its reply may still arrive on this
+    /// stream; it is closed and reopened before the next command
+    broken: bool,
+}
+
+/// Opens a fresh stream with the given timeouts set
+type Opener<S> = Box<dyn Fn(Option<Duration>) -> Result<S, ExecutionError> + Send>;
+
+/// What a connection opened from a URL needs to reconnect
+struct Reopen<S> {
+    open: Opener<S>,
+    /// The URL's password and database; the database follows successful SELECTs
+    target: RedisUrl,
+}
+
+/// Socket streams whose reads and writes can time out
+pub trait SocketStream: Read + Write {
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl SocketStream for TcpStream {
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+}
+
+#[cfg(unix)]
+impl SocketStream for UnixStream {
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+}
+
+impl RespConnection<TcpStream> {
+    /// Connect using a `redis://[:password@]host[:port][/db]` URL
+    pub fn connect(url: &str) -> Result<Self, ExecutionError> {
+        let target = RedisUrl::parse(url)?;
+        match &target.address {
+            Address::Tcp(host, port) if !target.tls => Self::connect_tcp(host, *port, &target),
+            _ => Err(ExecutionError::Protocol(format!("Not a redis:// URL: {}", url))),
+        }
+    }
+
+    pub(crate) fn connect_tcp(host: &str, port: u16, target: &RedisUrl) -> Result<Self, ExecutionError> {
+        let host = host.to_string();
+        Self::open(target, move || Ok(TcpStream::connect((host.as_str(), port))?))
+    }
+}
+
+#[cfg(feature = "tls")]
+impl RespConnection<TlsStream> {
+    /// Connect using a `rediss://[:password@]host[:port][/db]` URL, verifying the server
+    /// with `options` (`tls` feature)
+    pub fn connect_tls(url: &str, options: &TlsOptions) -> Result<Self, ExecutionError> {
+        let target = RedisUrl::parse(url)?;
+        match &target.address {
+            Address::Tcp(host, port) if target.tls => {
+                let (host, port, options) = (host.clone(), *port, options.clone());
+                Self::open(&target, move || tls::connect(&host, port, &options))
+            }
+            _ => Err(ExecutionError::Protocol(format!("Not a rediss:// URL: {}", url))),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl RespConnection<UnixStream> {
+    /// Connect using a `redis+unix://[:password@]/path/to/redis.sock[?db=N&password=P]` URL
+    pub fn connect_unix(url: &str) -> Result<Self, ExecutionError> {
+        let target = RedisUrl::parse(url)?;
+        match &target.address {
+            Address::Unix(path) => {
+                let path = path.clone();
+                Self::open(&target, move || Ok(UnixStream::connect(&path)?))
+            }
+            _ => Err(ExecutionError::Protocol(format!("Not a redis+unix:// URL: {}", url))),
+        }
+    }
+}
+
+impl<S: SocketStream> RespConnection<S> {
+    /// Fail commands whose reply takes longer than `timeout` with `ExecutionError::Timeout`
+    /// instead of blocking. The late reply may still arrive on the stream, so the command after
+    /// a timeout replaces it with a freshly opened one (authenticated, in the same database);
+    /// a connection made with `from_stream` can't be reopened, and fails later commands.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self, ExecutionError> {
+        self.reader.get_ref().set_timeouts(Some(timeout))?;
+        self.timeout = Some(timeout);
+        Ok(self)
+    }
+
+    /// Open a stream with `open`, authenticate and select the URL's database, and keep
+    /// `open` to reconnect with
+    fn open(target: &RedisUrl, open: impl Fn() -> Result<S, ExecutionError> + Send + 'static) -> Result<Self, ExecutionError> {
+        let mut connection = Self::from_stream(open()?);
+        connection.handshake(target)?;
+        connection.reopen = Some(Reopen {
+            open: Box::new(move |timeout| {
+                let stream = open()?;
+                stream.set_timeouts(timeout)?;
+                Ok(stream)
+            }),
+            target: target.clone(),
+        });
+        Ok(connection)
+    }
+}
+
+impl<S: Read + Write> RespConnection<S> {
+    /// Wrap an already-open stream
+    pub fn from_stream(stream: S) -> Self {
+        Self { reader: BufReader::new(stream), timeout: None, reopen: None, broken: false }
+    }
+
+    fn send_checked(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        match self.send(args)? {
+            Reply::Error(msg) => Err(ExecutionError::Server(msg)),
+            reply => Ok(reply),
+        }
+    }
+
+    /// Authenticate and select `target`'s database
+    fn handshake(&mut self, target: &RedisUrl) -> Result<(), ExecutionError> {
+        if let Some(password) = &target.password {
+            self.send_checked(&["AUTH".to_string(), password.clone()])?;
+        }
+        if target.db != 0 {
+            self.send_checked(&["SELECT".to_string(), target.db.to_string()])?;
+        }
+        Ok(())
+    }
+
+    /// Replace a broken stream with a fresh one before sending anything on it
+    fn reconnect_if_broken(&mut self) -> Result<(), ExecutionError> {
+        if !self.broken {
+            return Ok(());
+        }
+        let reopen = self.reopen.as_ref()
+            .ok_or_else(|| ExecutionError::Protocol("Connection closed after a failed reply".to_string()))?;
+        let stream = (reopen.open)(self.timeout)?;
+        let target = reopen.target.clone();
+        self.reader = BufReader::new(stream);
+        self.broken = false;
+        self.handshake(&target)
+    }
+
+    /// Write `buf` and read `count` replies; any failure leaves the stream broken
+    fn round_trip(&mut self, buf: &[u8], count: usize) -> Result<Vec<Reply>, ExecutionError> {
+        self.reconnect_if_broken()?;
+        let stream = self.reader.get_mut();
+        let result = stream.write_all(buf).and_then(|_| stream.flush()).map_err(ExecutionError::from)
+            .and_then(|_| (0..count).map(|_| self.read_reply()).collect());
+        result.map_err(|e| {
+            self.broken = true;
+            self.timed_out(e)
+        })
+    }
+
+    /// Reconnect to the database a successful SELECT switched to
+    fn track_select(&mut self, args: &[String], reply: &Reply) {
+        if let (Some(reopen), [command, db], false) = (&mut self.reopen, args, matches!(reply, Reply::Error(_))) {
+            if command.eq_ignore_ascii_case("SELECT") {
+                reopen.target.db = db.parse().unwrap_or(reopen.target.db);
+            }
+        }
+    }
+
+    /// Map a read/write that hit the stream timeout to `ExecutionError::Timeout`
+    fn timed_out(&self, error: ExecutionError) -> ExecutionError {
+        match (error, self.timeout) {
+            (ExecutionError::Io(e), Some(timeout)) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                ExecutionError::Timeout(timeout)
+            }
+            (error, _) => error,
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String, ExecutionError> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(ExecutionError::Protocol("Connection closed".to_string()));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    fn read_reply(&mut self) -> Result<Reply, ExecutionError> {
+        let line = self.read_line()?;
+        let (prefix, rest) = line.split_at_checked(1)
+            .ok_or_else(|| ExecutionError::Protocol("Empty reply line".to_string()))?;
+        let parse_len = |s: &str| s.parse::<i64>()
+            .map_err(|_| ExecutionError::Protocol(format!("Invalid length: {}", s)));
+
+        match prefix {
+            "+" => Ok(Reply::Status(rest.to_string())),
+            "-" => Ok(Reply::Error(rest.to_string())),
+            ":" => Ok(Reply::Integer(parse_len(rest)?)),
+            "$" => {
+                let len = parse_len(rest)?;
+                if len < 0 {
+                    return Ok(Reply::Nil);
+                }
+                let mut buf = vec![0u8; len as usize + 2];
+                self.reader.read_exact(&mut buf)?;
+                buf.truncate(len as usize);
+                Ok(Reply::Bulk(buf))
+            }
+            "*" => {
+                let len = parse_len(rest)?;
+                if len < 0 {
+                    return Ok(Reply::Nil);
+                }
+                let items = (0..len)
+                    .map(|_| self.read_reply())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Reply::Array(items))
+            }
+            other => Err(ExecutionError::Protocol(format!("Unknown reply type: {}", other))),
+        }
+    }
+}
+
+impl<S: Read + Write> Connection for RespConnection<S> {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        let reply = self.round_trip(&encode(args), 1)?.remove(0);
+        self.track_select(args, &reply);
+        Ok(reply)
+    }
+
+    /// Write every command before reading any reply, so the batch costs one round trip
+    fn send_pipeline(&mut self, commands: &[Vec<String>]) -> Result<Vec<Reply>, ExecutionError> {
+        let buf: Vec<u8> = commands.iter().flat_map(|args| encode(args)).collect();
+        let replies = self.round_trip(&buf, commands.len())?;
+        for (args, reply) in commands.iter().zip(&replies) {
+            self.track_select(args, reply);
+        }
+        Ok(replies)
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        self.read_reply()
+    }
+}
+
+/// Encode a command as a RESP array of bulk strings
+fn encode(args: &[String]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg.as_bytes());
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+/// Where a connection URL points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Address {
+    Tcp(String, u16),
+    Unix(PathBuf),
+    /// Seed nodes of a cluster
+    Cluster(Vec<(String, u16)>),
+}
+
+/// Parsed connection URL
+#[derive(Debug, Clone)]
+pub(crate) struct RedisUrl {
+    pub(crate) address: Address,
+    pub(crate) password: Option<String>,
+    pub(crate) db: u32,
+    /// `rediss://`: TCP wrapped in TLS
+    pub(crate) tls: bool,
+}
+
+impl RedisUrl {
+    /// Parse `redis[s]://[:password@]host[:port][/db]`,
+    /// `redis+unix://[:password@]/path/to/redis.sock[?db=N&password=P]`, or
+    /// `redis-cluster://[:password@]host1[:port],host2[:port],...`
+    pub(crate) fn parse(url: &str) -> Result<Self, ExecutionError> {
+        let invalid = || ExecutionError::Protocol(format!("Invalid Redis URL: {}", url));
+        let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+
+        let (auth, rest) = match rest.rsplit_once('@') {
+            Some((auth, rest)) => (Some(auth), rest),
+            None => (None, rest),
+        };
+        let mut password = auth
+            .map(|auth| auth.split_once(':').map_or(auth, |(_, password)| password))
+            .filter(|password| !password.is_empty())
+            .map(str::to_string);
+
+        match scheme {
+            "redis" | "rediss" => {
+                let (host_port, db) = match rest.split_once('/') {
+                    Some((host_port, "")) => (host_port, 0),
+                    Some((host_port, db)) => (host_port, db.parse().map_err(|_| invalid())?),
+                    None => (rest, 0),
+                };
+                let (host, port) = parse_host_port(host_port).ok_or_else(invalid)?;
+                Ok(Self { address: Address::Tcp(host, port), password, db, tls: scheme == "rediss" })
+            }
+            "redis+unix" => {
+                let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+                if !path.starts_with('/') {
+                    return Err(invalid());
+                }
+                let mut db = 0;
+                for param in query.split('&').filter(|param| !param.is_empty()) {
+                    match param.split_once('=') {
+                        Some(("db", value)) => db = value.parse().map_err(|_| invalid())?,
+                        Some(("password", value)) if !value.is_empty() => password = Some(value.to_string()),
+                        _ => return Err(invalid()),
+                    }
+                }
+                Ok(Self { address: Address::Unix(PathBuf::from(path)), password, db, tls: false })
+            }
+            "redis-cluster" => {
+                // Cluster nodes only have database 0
+                let nodes = rest.trim_end_matches('/').split(',')
+                    .map(|node| parse_host_port(node.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid)?;
+                Ok(Self { address: Address::Cluster(nodes), password, db: 0, tls: false })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// `host[:port]`, with the default port 6379
+fn parse_host_port(host_port: &str) -> Option<(String, u16)> {
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (host_port, 6379),
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
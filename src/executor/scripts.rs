@@ -0,0 +1,99 @@
+// executor/scripts.rs - Script cache for EVAL commands
+// Remembers which scripts the server has seen, so repeats are sent as EVALSHA
+
+use std::collections::HashSet;
+
+use crate::commands::RedisCommand;
+use crate::lua::script_sha1;
+use crate::reply::Reply;
+
+/// The scripts an executor has sent, by SHA-1 of the rendered script, with hit and miss
+/// counts. A script is cached once an EVAL of it has succeeded; later EVALs of the same
+/// rendered script go out as EVALSHA (a hit), and a NOSCRIPT reply (the server restarted or
+/// ran SCRIPT FLUSH) drops it and resends the EVAL (a miss).
+#[derive(Debug, Clone, Default)]
+pub struct ScriptCache {
+    loaded: HashSet<String>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ScriptCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `script` is believed loaded on the server
+    pub fn contains(&self, script: &str) -> bool {
+        self.loaded.contains(&script_sha1(script))
+    }
+
+    /// Distinct scripts cached
+    pub fn len(&self) -> usize {
+        self.loaded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loaded.is_empty()
+    }
+
+    /// EVALs sent as EVALSHA and answered without NOSCRIPT
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// EVALs whose script had to be sent: first uses and NOSCRIPT fallbacks
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Hits over all script commands, 0 before any ran
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    /// Forget every script, e.g. after SCRIPT FLUSH or reconnecting to another server
+    pub fn clear(&mut self) {
+        self.loaded.clear();
+    }
+
+    /// The SHA-1 of an EVAL command's script and whether it is cached; None for other commands
+    pub(crate) fn lookup(&self, command: &RedisCommand) -> Option<(String, bool)> {
+        let script = command.args.first().filter(|_| command.command.eq_ignore_ascii_case("EVAL"))?;
+        let sha = script_sha1(script);
+        let cached = self.loaded.contains(&sha);
+        Some((sha, cached))
+    }
+
+    /// Record a script command answered as EVALSHA (`hit`) or after sending its script;
+    /// a script whose EVAL failed isn't cached, as it may not have compiled
+    pub(crate) fn record(&mut self, sha: String, hit: bool, reply: &Reply) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        if !matches!(reply, Reply::Error(_)) {
+            self.loaded.insert(sha);
+        }
+    }
+}
+
+/// `command` as sent: EVALSHA with `sha` in place of the script when `cached`
+pub(crate) fn wire_args(command: &RedisCommand, script: Option<&(String, bool)>) -> Vec<String> {
+    match script {
+        Some((sha, true)) => std::iter::once("EVALSHA".to_string())
+            .chain(std::iter::once(sha.clone()))
+            .chain(command.args[1..].iter().cloned())
+            .collect(),
+        _ => std::iter::once(command.command.clone()).chain(command.args.iter().cloned()).collect(),
+    }
+}
+
+/// Whether a reply says the server doesn't have the script an EVALSHA named
+pub(crate) fn is_noscript(reply: &Reply) -> bool {
+    matches!(reply, Reply::Error(msg) if msg.starts_with("NOSCRIPT"))
+}
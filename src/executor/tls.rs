@@ -0,0 +1,86 @@
+// executor/tls.rs - TLS for rediss:// connections (`tls` feature)
+// Wraps the TCP stream in a rustls client, trusting Mozilla's roots unless a CA file is given
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+
+use crate::executor::resp::SocketStream;
+use crate::executor::ExecutionError;
+
+/// A TLS session over TCP, as opened for `rediss://` URLs
+pub type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+/// Certificates for `rediss://` connections (CLI `--tls-ca/--tls-cert/--tls-key`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsOptions {
+    /// PEM file of CA certificates to trust instead of Mozilla's root store
+    pub ca_file: Option<PathBuf>,
+    /// PEM client certificate chain, for servers requiring client authentication
+    pub cert_file: Option<PathBuf>,
+    /// PEM private key of `cert_file`
+    pub key_file: Option<PathBuf>,
+}
+
+impl TlsOptions {
+    /// Build the client configuration; a client certificate needs both its chain and key
+    fn client_config(&self) -> Result<Arc<ClientConfig>, ExecutionError> {
+        let mut roots = RootCertStore::empty();
+        match &self.ca_file {
+            Some(path) => {
+                for cert in read_certs(path)? {
+                    roots.add(cert).map_err(tls_error)?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        let builder = ClientConfig::builder().with_root_certificates(roots);
+        let config = match (&self.cert_file, &self.key_file) {
+            (Some(cert), Some(key)) => {
+                let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key)?))?
+                    .ok_or_else(|| ExecutionError::Protocol(format!("No private key in {}", key.display())))?;
+                builder.with_client_auth_cert(read_certs(cert)?, key).map_err(tls_error)?
+            }
+            (None, None) => builder.with_no_client_auth(),
+            _ => return Err(ExecutionError::Protocol("A TLS client certificate needs both a certificate and a key file".to_string())),
+        };
+        Ok(Arc::new(config))
+    }
+}
+
+impl SocketStream for TlsStream {
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.sock.set_timeouts(timeout)
+    }
+}
+
+/// Open a TLS session to `host:port`, verifying the server's certificate against `host`.
+/// The handshake completes here, so certificate errors surface when connecting.
+pub(crate) fn connect(host: &str, port: u16, options: &TlsOptions) -> Result<TlsStream, ExecutionError> {
+    let name = ServerName::try_from(host.to_string())
+        .map_err(|_| ExecutionError::Protocol(format!("Invalid TLS server name: {}", host)))?;
+    let session = ClientConnection::new(options.client_config()?, name).map_err(tls_error)?;
+    let mut stream = StreamOwned::new(session, TcpStream::connect((host, port))?);
+    while stream.conn.is_handshaking() {
+        stream.conn.complete_io(&mut stream.sock)?;
+    }
+    Ok(stream)
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, ExecutionError> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(path)?)).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(ExecutionError::Protocol(format!("No certificates in {}", path.display())));
+    }
+    Ok(certs)
+}
+
+fn tls_error(error: rustls::Error) -> ExecutionError {
+    ExecutionError::Protocol(format!("TLS: {}", error))
+}
@@ -0,0 +1,473 @@
+// expr.rs - Expression compiler for CASE, COALESCE, and string functions
+// Lowers `CASE WHEN ... THEN ... ELSE ... END`, COALESCE, and UPPER/LOWER/TRIM/REPLACE over a
+// row's columns to Lua scripts, or evaluates them client-side over rows fetched in execute mode
+
+use std::cmp::Ordering;
+
+use sqlparser::ast::{BinaryOperator, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, SelectItem, UnaryOperator, Value};
+
+use crate::acl::glob_match;
+use crate::reply::Row;
+
+/// A value a CASE reads or produces
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    /// A column of the row (a hash field)
+    Column(String),
+    /// A string or number literal
+    Literal(String),
+    Null,
+    /// A string function of other operands; NULL if any argument is
+    Function(StringFunction, Vec<Operand>),
+    /// `COALESCE(a, b, ...)`: the first operand that isn't NULL
+    Coalesce(Vec<Operand>),
+}
+
+/// String functions, folded on literals and compiled to Lua `string.*` calls on stored values
+/// (Lua's UPPER and LOWER only change ASCII letters)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringFunction {
+    Upper,
+    Lower,
+    /// Strip leading and trailing spaces
+    Trim,
+    /// `REPLACE(s, from, to)`: every occurrence of `from`, matched as plain text
+    Replace,
+    /// `a || b`
+    Concat,
+}
+
+impl StringFunction {
+    /// The function a call names, if it takes `arity` arguments
+    fn named(name: &str, arity: usize) -> Option<StringFunction> {
+        match (name.to_uppercase().as_str(), arity) {
+            ("UPPER", 1) => Some(StringFunction::Upper),
+            ("LOWER", 1) => Some(StringFunction::Lower),
+            ("TRIM", 1) => Some(StringFunction::Trim),
+            ("REPLACE", 3) => Some(StringFunction::Replace),
+            _ => None,
+        }
+    }
+
+    /// Apply to argument values
+    pub fn apply(&self, args: &[String]) -> String {
+        match (self, args) {
+            (StringFunction::Upper, [s]) => s.to_uppercase(),
+            (StringFunction::Lower, [s]) => s.to_lowercase(),
+            (StringFunction::Trim, [s]) => s.trim_matches(' ').to_string(),
+            (StringFunction::Replace, [s, from, _]) if from.is_empty() => s.clone(),
+            (StringFunction::Replace, [s, from, to]) => s.replace(from.as_str(), to),
+            (StringFunction::Concat, [a, b]) => format!("{}{}", a, b),
+            _ => String::new(),
+        }
+    }
+
+    /// The column name of an unaliased projection of this function
+    fn column_name(&self) -> &'static str {
+        match self {
+            StringFunction::Upper => "upper",
+            StringFunction::Lower => "lower",
+            StringFunction::Trim => "trim",
+            StringFunction::Replace => "replace",
+            StringFunction::Concat => "concat",
+        }
+    }
+
+    /// The Lua function called through the script's nil-propagating `call`
+    fn lua_name(&self) -> &'static str {
+        match self {
+            StringFunction::Upper => "string.upper",
+            StringFunction::Lower => "string.lower",
+            StringFunction::Trim => "trim",
+            StringFunction::Replace => "replace",
+            StringFunction::Concat => "concat",
+        }
+    }
+}
+
+/// A call of a string function with its argument expressions: `UPPER(x)`, `TRIM(x)`, `REPLACE(x, a, b)`, `x || y`
+pub(crate) fn string_call(expr: &Expr) -> Option<(StringFunction, Vec<&Expr>)> {
+    match expr {
+        Expr::Trim { expr, trim_where: None, trim_what: None, trim_characters: None } => Some((StringFunction::Trim, vec![expr.as_ref()])),
+        Expr::BinaryOp { left, op: BinaryOperator::StringConcat, right } => Some((StringFunction::Concat, vec![left.as_ref(), right.as_ref()])),
+        _ => {
+            let (name, args) = function_call(expr)?;
+            Some((StringFunction::named(&name, args.len())?, args))
+        }
+    }
+}
+
+/// The arguments of `COALESCE(a, b, ...)`
+pub(crate) fn coalesce_call(expr: &Expr) -> Option<Vec<&Expr>> {
+    function_call(expr).filter(|(name, args)| name.eq_ignore_ascii_case("coalesce") && !args.is_empty()).map(|(_, args)| args)
+}
+
+/// The name and plain positional arguments of a function call
+fn function_call(expr: &Expr) -> Option<(String, Vec<&Expr>)> {
+    let Expr::Function(function) = expr else { return None };
+    let FunctionArguments::List(list) = &function.args else { return None };
+    if list.duplicate_treatment.is_some() || !list.clauses.is_empty() || function.filter.is_some() || function.over.is_some() {
+        return None;
+    }
+    let args = list.args.iter()
+        .map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => Some(arg),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((function.name.to_string(), args))
+}
+
+/// A WHEN condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `a op b` with `=`, `<>`, `<`, `<=`, `>`, `>=`; false when either side is NULL
+    Compare(Operand, BinaryOperator, Operand),
+    /// `a IS NULL` (`negated` for IS NOT NULL)
+    IsNull(Operand, bool),
+    /// `a LIKE 'p'` with the pattern as a Redis glob (`negated` for NOT LIKE); false when `a` is NULL
+    Like(Operand, String, bool),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+/// A compiled CASE: the result of the first branch whose condition holds, else `otherwise`.
+/// A plain value compiles to a CASE with no branches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub branches: Vec<(Condition, Operand)>,
+    pub otherwise: Operand,
+}
+
+/// A projected column: a plain column or a computed value (CASE, string function) over the row
+#[derive(Debug, Clone, PartialEq)]
+pub enum Projection {
+    Column(String),
+    Case(Case),
+}
+
+impl Case {
+    /// Compile a CASE expression (searched, or simple `CASE col WHEN v ...`), or a plain
+    /// column, literal, or NULL. Other expressions aren't supported.
+    pub fn compile(expr: &Expr) -> Option<Case> {
+        match expr {
+            Expr::Case { operand, conditions, else_result } => {
+                let subject = match operand {
+                    Some(operand) => Some(compile_operand(operand)?),
+                    None => None,
+                };
+                let branches = conditions.iter()
+                    .map(|when| {
+                        let condition = match &subject {
+                            Some(subject) => Condition::Compare(subject.clone(), BinaryOperator::Eq, compile_operand(&when.condition)?),
+                            None => compile_condition(&when.condition)?,
+                        };
+                        Some((condition, compile_operand(&when.result)?))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let otherwise = else_result.as_deref().map_or(Some(Operand::Null), compile_operand)?;
+                Some(Case { branches, otherwise })
+            }
+            _ => compile_operand(expr).map(|otherwise| Case { branches: Vec::new(), otherwise }),
+        }
+    }
+
+    /// Check whether this reads the row (a CASE, a column, a function) rather than being a plain literal
+    pub fn is_computed(&self) -> bool {
+        !self.branches.is_empty() || !matches!(self.otherwise, Operand::Literal(_))
+    }
+
+    /// Every operand the CASE reads or produces, nested ones included, in order
+    pub fn operands(&self) -> Vec<&Operand> {
+        let mut operands = Vec::new();
+        for (condition, result) in &self.branches {
+            condition.collect_operands(&mut operands);
+            result.collect_operands(&mut operands);
+        }
+        self.otherwise.collect_operands(&mut operands);
+        operands
+    }
+
+    /// The columns the CASE reads, once each, in order
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns: Vec<String> = Vec::new();
+        for operand in self.operands() {
+            if let Operand::Column(name) = operand {
+                if !columns.contains(name) {
+                    columns.push(name.clone());
+                }
+            }
+        }
+        columns
+    }
+
+    /// Evaluate over a row; None is SQL NULL
+    pub fn evaluate(&self, row: &Row) -> Option<String> {
+        self.branches.iter()
+            .find(|(condition, _)| condition.evaluate(row))
+            .map_or(&self.otherwise, |(_, result)| result)
+            .evaluate(row)
+    }
+
+    /// Lua statements assigning the result to `target`; literals and column names are
+    /// appended to `args` and read back as ARGV, and columns through a `field(name)` function
+    pub fn to_lua(&self, target: &str, args: &mut Vec<String>) -> String {
+        if self.branches.is_empty() {
+            return format!("{} = {}\n", target, self.otherwise.to_lua(args));
+        }
+        let mut lua = String::new();
+        for (i, (condition, result)) in self.branches.iter().enumerate() {
+            let keyword = if i == 0 { "if" } else { "elseif" };
+            lua.push_str(&format!("{} {} then\n    {} = {}\n", keyword, condition.to_lua(args), target, result.to_lua(args)));
+        }
+        if self.otherwise != Operand::Null {
+            lua.push_str(&format!("else\n    {} = {}\n", target, self.otherwise.to_lua(args)));
+        }
+        lua.push_str("end\n");
+        lua
+    }
+}
+
+impl Operand {
+    fn evaluate(&self, row: &Row) -> Option<String> {
+        match self {
+            Operand::Column(name) => row.iter().find(|(column, _)| column == name).map(|(_, value)| value.clone()),
+            Operand::Literal(value) => Some(value.clone()),
+            Operand::Null => None,
+            Operand::Function(function, args) => {
+                let args = args.iter().map(|arg| arg.evaluate(row)).collect::<Option<Vec<_>>>()?;
+                Some(function.apply(&args))
+            }
+            Operand::Coalesce(operands) => operands.iter().find_map(|operand| operand.evaluate(row)),
+        }
+    }
+
+    fn collect_operands<'a>(&'a self, operands: &mut Vec<&'a Operand>) {
+        operands.push(self);
+        if let Operand::Function(_, nested) | Operand::Coalesce(nested) = self {
+            nested.iter().for_each(|operand| operand.collect_operands(operands));
+        }
+    }
+
+    fn to_lua(&self, args: &mut Vec<String>) -> String {
+        match self {
+            Operand::Column(name) => format!("field({})", push_arg(args, name)),
+            Operand::Literal(value) => push_arg(args, value),
+            Operand::Null => "nil".to_string(),
+            Operand::Function(function, operands) => {
+                let operands: Vec<String> = operands.iter().map(|operand| operand.to_lua(args)).collect();
+                format!("call({}, {})", function.lua_name(), operands.join(", "))
+            }
+            Operand::Coalesce(operands) => {
+                let operands: Vec<String> = operands.iter().map(|operand| operand.to_lua(args)).collect();
+                format!("coalesce({})", operands.join(", "))
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// Compile a boolean expression over the row's columns: comparisons, IS [NOT] NULL,
+    /// [NOT] BETWEEN, [NOT] IN lists, [NOT] LIKE, and AND/OR/NOT of them
+    pub fn compile(expr: &Expr) -> Option<Condition> {
+        compile_condition(expr)
+    }
+
+    /// Evaluate over a row; comparisons with NULL are false
+    pub fn evaluate(&self, row: &Row) -> bool {
+        match self {
+            Condition::Compare(left, op, right) => match (left.evaluate(row), right.evaluate(row)) {
+                (Some(left), Some(right)) => compare(&left, &right).is_some_and(|ordering| holds(op, ordering)),
+                _ => false,
+            },
+            Condition::IsNull(operand, negated) => operand.evaluate(row).is_none() != *negated,
+            Condition::Like(operand, glob, negated) => operand.evaluate(row)
+                .is_some_and(|value| glob_match(glob, &value) != *negated),
+            Condition::And(left, right) => left.evaluate(row) && right.evaluate(row),
+            Condition::Or(left, right) => left.evaluate(row) || right.evaluate(row),
+            Condition::Not(inner) => !inner.evaluate(row),
+        }
+    }
+
+    fn collect_operands<'a>(&'a self, operands: &mut Vec<&'a Operand>) {
+        match self {
+            Condition::Compare(left, _, right) => {
+                left.collect_operands(operands);
+                right.collect_operands(operands);
+            }
+            Condition::IsNull(operand, _) | Condition::Like(operand, _, _) => operand.collect_operands(operands),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.collect_operands(operands);
+                right.collect_operands(operands);
+            }
+            Condition::Not(inner) => inner.collect_operands(operands),
+        }
+    }
+
+    /// A Lua boolean expression, with literals and column names read back as ARGV (see `Case::to_lua`)
+    pub fn to_lua(&self, args: &mut Vec<String>) -> String {
+        match self {
+            Condition::Compare(left, op, right) => {
+                let (left, right) = (left.to_lua(args), right.to_lua(args));
+                format!("compare({}, '{}', {})", left, op, right)
+            }
+            Condition::IsNull(operand, negated) => format!("({} {} nil)", operand.to_lua(args), if *negated { "~=" } else { "==" }),
+            Condition::Like(operand, glob, negated) => {
+                let operand = operand.to_lua(args);
+                format!("like({}, {}, {})", operand, push_arg(args, &glob_to_lua_pattern(glob)), negated)
+            }
+            Condition::And(left, right) => format!("({} and {})", left.to_lua(args), right.to_lua(args)),
+            Condition::Or(left, right) => format!("({} or {})", left.to_lua(args), right.to_lua(args)),
+            Condition::Not(inner) => format!("not {}", inner.to_lua(args)),
+        }
+    }
+}
+
+/// The columns of a SELECT projection: plain or aliased identifiers, CASE expressions, COALESCE,
+/// and string functions, under their output names (unaliased, `case` or the function's name). None
+/// if anything else is projected.
+pub fn projections(select_items: &[SelectItem]) -> Option<Vec<(Projection, String)>> {
+    select_items.iter()
+        .map(|item| {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.value.clone())),
+                _ => return None,
+            };
+            let name = match expr {
+                Expr::Identifier(ident) => return Some((Projection::Column(ident.value.clone()), alias.unwrap_or_else(|| ident.value.clone()))),
+                Expr::Case { .. } => "case",
+                _ if coalesce_call(expr).is_some() => "coalesce",
+                _ => string_call(expr)?.0.column_name(),
+            };
+            Some((Projection::Case(Case::compile(expr)?), alias.unwrap_or_else(|| name.to_string())))
+        })
+        .collect()
+}
+
+impl Projection {
+    /// The projection as a compiled expression (a plain column is a CASE with no branches)
+    pub fn to_case(&self) -> Case {
+        match self {
+            Projection::Column(name) => Case { branches: Vec::new(), otherwise: Operand::Column(name.clone()) },
+            Projection::Case(case) => case.clone(),
+        }
+    }
+
+    /// The projected value of a row; None if the column is absent or the CASE yields NULL
+    pub fn evaluate(&self, row: &Row) -> Option<String> {
+        match self {
+            Projection::Column(name) => Operand::Column(name.clone()).evaluate(row),
+            Projection::Case(case) => case.evaluate(row),
+        }
+    }
+}
+
+fn compile_operand(expr: &Expr) -> Option<Operand> {
+    match expr {
+        Expr::Identifier(ident) => Some(Operand::Column(ident.value.clone())),
+        Expr::Nested(inner) => compile_operand(inner),
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) | Value::Number(s, _) => Some(Operand::Literal(s.clone())),
+            Value::Null => Some(Operand::Null),
+            _ => None,
+        },
+        Expr::Function(_) if coalesce_call(expr).is_some() => {
+            let args = coalesce_call(expr)?;
+            Some(Operand::Coalesce(args.into_iter().map(compile_operand).collect::<Option<Vec<_>>>()?))
+        }
+        Expr::Trim { .. } | Expr::Function(_) | Expr::BinaryOp { op: BinaryOperator::StringConcat, .. } => {
+            let (function, args) = string_call(expr)?;
+            Some(Operand::Function(function, args.into_iter().map(compile_operand).collect::<Option<Vec<_>>>()?))
+        }
+        Expr::UnaryOp { op: UnaryOperator::Minus, expr } => match compile_operand(expr)? {
+            Operand::Literal(n) if n.parse::<f64>().is_ok() => Some(Operand::Literal(format!("-{}", n))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn compile_condition(expr: &Expr) -> Option<Condition> {
+    match expr {
+        Expr::Nested(inner) => compile_condition(inner),
+        Expr::BinaryOp { left, op: BinaryOperator::And, right } => Some(Condition::And(Box::new(compile_condition(left)?), Box::new(compile_condition(right)?))),
+        Expr::BinaryOp { left, op: BinaryOperator::Or, right } => Some(Condition::Or(Box::new(compile_condition(left)?), Box::new(compile_condition(right)?))),
+        Expr::BinaryOp { left, op, right } if matches!(op, BinaryOperator::Eq | BinaryOperator::NotEq
+            | BinaryOperator::Lt | BinaryOperator::LtEq | BinaryOperator::Gt | BinaryOperator::GtEq) => {
+            Some(Condition::Compare(compile_operand(left)?, op.clone(), compile_operand(right)?))
+        }
+        Expr::UnaryOp { op: UnaryOperator::Not, expr } => Some(Condition::Not(Box::new(compile_condition(expr)?))),
+        Expr::Between { expr, negated, low, high } => {
+            let operand = compile_operand(expr)?;
+            let between = Condition::And(
+                Box::new(Condition::Compare(operand.clone(), BinaryOperator::GtEq, compile_operand(low)?)),
+                Box::new(Condition::Compare(operand, BinaryOperator::LtEq, compile_operand(high)?)),
+            );
+            Some(if *negated { Condition::Not(Box::new(between)) } else { between })
+        }
+        Expr::InList { expr, list, negated } => {
+            let operand = compile_operand(expr)?;
+            let any = list.iter()
+                .map(|item| Some(Condition::Compare(operand.clone(), BinaryOperator::Eq, compile_operand(item)?)))
+                .reduce(|left, right| Some(Condition::Or(Box::new(left?), Box::new(right?))))??;
+            Some(if *negated { Condition::Not(Box::new(any)) } else { any })
+        }
+        Expr::Like { negated, any: false, expr, pattern, escape_char: None } => match compile_operand(pattern)? {
+            Operand::Literal(like) => Some(Condition::Like(compile_operand(expr)?, crate::ast::like_to_glob(&like), *negated)),
+            _ => None,
+        },
+        Expr::IsNull(inner) => Some(Condition::IsNull(compile_operand(inner)?, false)),
+        Expr::IsNotNull(inner) => Some(Condition::IsNull(compile_operand(inner)?, true)),
+        _ => None,
+    }
+}
+
+/// An anchored Lua pattern matching what a Redis glob matches: `*` is `.*`, `?` is `.`, and
+/// everything else is literal (Lua's `.` is a byte, so `?` matches one byte of a multibyte character)
+fn glob_to_lua_pattern(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => {
+                let literal = if c == '\\' { chars.next().unwrap_or(c) } else { c };
+                if "^$()%.[]*+-?".contains(literal) {
+                    pattern.push('%');
+                }
+                pattern.push(literal);
+            }
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// Compare numerically when both sides are numbers, as text otherwise (the Lua `compare` rule)
+fn compare(left: &str, right: &str) -> Option<Ordering> {
+    match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.partial_cmp(&right),
+        _ => Some(left.cmp(right)),
+    }
+}
+
+fn holds(op: &BinaryOperator, ordering: Ordering) -> bool {
+    match op {
+        BinaryOperator::Eq => ordering.is_eq(),
+        BinaryOperator::NotEq => ordering.is_ne(),
+        BinaryOperator::Lt => ordering.is_lt(),
+        BinaryOperator::LtEq => ordering.is_le(),
+        BinaryOperator::Gt => ordering.is_gt(),
+        BinaryOperator::GtEq => ordering.is_ge(),
+        _ => false,
+    }
+}
+
+/// Append a script argument, returning the Lua expression reading it
+pub(crate) fn push_arg(args: &mut Vec<String>, value: &str) -> String {
+    args.push(value.to_string());
+    format!("ARGV[{}]", args.len())
+}
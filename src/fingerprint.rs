@@ -0,0 +1,114 @@
+// fingerprint.rs - Normalized query shapes
+// Statements differing only in literal values, whitespace, comments, keyword case, or the
+// length of literal lists share a fingerprint, for aggregating workloads by query shape
+
+use sqlparser::dialect::GenericDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::lua::script_sha1;
+
+/// The statement with literals and placeholders replaced by `?`, keywords uppercased (the
+/// tokenizer's keywords, which include column names like `key` and `value`), other unquoted
+/// identifiers lowercased, comments dropped, and tokens separated by single spaces.
+/// Lists of literals collapse to one `?` and rows of them to one row, so `IN (1, 2, 3)` and
+/// `IN (4)` fingerprint alike. Input the tokenizer rejects has only its whitespace collapsed.
+///
+/// `SELECT * FROM Users__hash WHERE key = 'user:1'` -> `SELECT * FROM users__hash WHERE KEY = ?`
+pub fn fingerprint(sql: &str) -> String {
+    let Ok(tokens) = Tokenizer::new(&GenericDialect {}, sql).tokenize() else {
+        return sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    };
+    let mut parts: Vec<String> = Vec::new();
+    let mut tokens = tokens.into_iter().filter(|token| !matches!(token, Token::Whitespace(_))).peekable();
+    while let Some(token) = tokens.next() {
+        let part = match token {
+            // A sign in front of a number is part of the literal unless it follows an operand
+            Token::Minus | Token::Plus if matches!(tokens.peek(), Some(Token::Number(..))) && !follows_operand(&parts) => {
+                continue;
+            }
+            Token::Word(word) if word.quote_style.is_some() => word.to_string(),
+            Token::Word(word) if word.keyword != Keyword::NoKeyword => word.value.to_uppercase(),
+            Token::Word(word) => word.value.to_lowercase(),
+            Token::SemiColon | Token::EOF => continue,
+            token if is_literal(&token) => "?".to_string(),
+            token => token.to_string(),
+        };
+        parts.push(part);
+        collapse_lists(&mut parts);
+    }
+    join(&parts)
+}
+
+/// A stable 16-hex-digit id of the statement's fingerprint (a SHA-1 prefix), like a queryid
+pub fn fingerprint_id(sql: &str) -> String {
+    script_sha1(&fingerprint(sql))[..16].to_string()
+}
+
+fn is_literal(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Number(..)
+            | Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::TripleSingleQuotedString(_)
+            | Token::TripleDoubleQuotedString(_)
+            | Token::DollarQuotedString(_)
+            | Token::SingleQuotedByteStringLiteral(_)
+            | Token::DoubleQuotedByteStringLiteral(_)
+            | Token::TripleSingleQuotedByteStringLiteral(_)
+            | Token::TripleDoubleQuotedByteStringLiteral(_)
+            | Token::SingleQuotedRawStringLiteral(_)
+            | Token::DoubleQuotedRawStringLiteral(_)
+            | Token::TripleSingleQuotedRawStringLiteral(_)
+            | Token::TripleDoubleQuotedRawStringLiteral(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::UnicodeStringLiteral(_)
+            | Token::HexStringLiteral(_)
+            | Token::Placeholder(_)
+    )
+}
+
+/// Whether the last part ends an operand (a value, identifier, or closing parenthesis)
+fn follows_operand(parts: &[String]) -> bool {
+    parts.last().is_some_and(|last| last == "?" || last == ")" || is_identifier(last))
+}
+
+/// Fold a just-pushed `?` or `(?)` into an identical item before it in a comma-separated list
+fn collapse_lists(parts: &mut Vec<String>) {
+    let len = parts.len();
+    let tail: Vec<&str> = parts.iter().map(String::as_str).collect();
+    if tail.ends_with(&["?", ",", "?"]) {
+        parts.truncate(len - 2);
+    } else if tail.ends_with(&["(", "?", ")", ",", "(", "?", ")"]) {
+        parts.truncate(len - 4);
+    }
+}
+
+/// Tokens separated by spaces, except around `.` and inside parentheses and before commas
+fn join(parts: &[String]) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&str> = None;
+    for part in parts {
+        let tight = matches!(previous, None | Some("(") | Some("."))
+            || matches!(part.as_str(), ")" | "," | ".")
+            || (part == "(" && previous.is_some_and(|p| is_identifier(p) || is_function_keyword(p)));
+        if !tight {
+            out.push(' ');
+        }
+        out.push_str(part);
+        previous = Some(part);
+    }
+    out
+}
+
+/// Identifiers are lowercased or quoted, unlike keywords
+fn is_identifier(part: &str) -> bool {
+    part.chars().next().is_some_and(|c| c.is_lowercase() || c == '_' || c == '"' || c == '`' || c == '[')
+}
+
+/// Uppercase words that are called like functions, written `COUNT(*)` rather than `COUNT (*)`
+fn is_function_keyword(word: &str) -> bool {
+    matches!(word, "COUNT" | "SUM" | "AVG" | "MIN" | "MAX" | "COALESCE" | "UPPER" | "LOWER" | "LENGTH" | "SUBSTRING" | "TRIM" | "CAST")
+}
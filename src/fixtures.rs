@@ -0,0 +1,216 @@
+// fixtures.rs - Rule fixtures synthesized from pattern metadata
+// Every rule documents an `sql_pattern` and a `redis_pattern`; concretizing the SQL and matching
+// its translation against the Redis pattern keeps listings and docs from drifting from behavior
+
+use std::fmt;
+
+use crate::commands::RedisCommand;
+use crate::SqlToRedisTransformer;
+
+/// Values substituted for the number placeholders of patterns (`LIMIT n`, `BETWEEN n AND m`)
+const PLACEHOLDERS: [(&str, &str); 3] = [("n", "10"), ("m", "20"), ("n-1", "9")];
+
+/// Name substituted for the bare `table` of patterns, a reserved word in SQL
+const TABLE_NAME: &str = "items";
+
+/// A concrete statement for one rule and the Redis pattern its translation must match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    /// Template name of the rule
+    pub rule: String,
+    /// The rule's `sql_pattern` with placeholders filled in
+    pub sql: String,
+    /// The rule's `redis_pattern`, as documented
+    pub redis_pattern: String,
+}
+
+/// Why a fixture's translation doesn't agree with its rule's metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureFailure {
+    pub fixture: Fixture,
+    pub reason: String,
+}
+
+impl fmt::Display for FixtureFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (SQL: {}; documented: {})", self.fixture.rule, self.reason, self.fixture.sql, self.fixture.redis_pattern)
+    }
+}
+
+impl Fixture {
+    /// Translate the fixture, checking that its own rule handles it and the commands match
+    pub fn check(&self, transformer: &SqlToRedisTransformer) -> Result<(), FixtureFailure> {
+        let fail = |reason: String| FixtureFailure { fixture: self.clone(), reason };
+        let rule = transformer.matched_rule(&self.sql).map_err(|e| fail(e.to_string()))?;
+        if rule != self.rule {
+            return Err(fail(format!("translated by rule {}", rule)));
+        }
+        let commands = transformer.transform_to_commands(&self.sql).map_err(|e| fail(e.to_string()))?;
+        if !matches_pattern(&self.redis_pattern, &commands) {
+            let actual: Vec<String> = commands.iter().map(RedisCommand::to_quoted_string).collect();
+            return Err(fail(format!("produced {}", actual.join("; "))));
+        }
+        Ok(())
+    }
+}
+
+/// One fixture per rule of the transformer that documents both patterns
+pub fn fixtures(transformer: &SqlToRedisTransformer) -> Vec<Fixture> {
+    transformer.get_pattern_details().into_iter()
+        .filter(|pattern| !pattern.sql_pattern.is_empty() && !pattern.redis_pattern.is_empty())
+        .map(|pattern| Fixture {
+            rule: pattern.name,
+            sql: concretize_sql(&pattern.sql_pattern),
+            redis_pattern: pattern.redis_pattern,
+        })
+        .collect()
+}
+
+/// Check every rule's fixture, returning the failures
+pub fn check_fixtures(transformer: &SqlToRedisTransformer) -> Vec<FixtureFailure> {
+    fixtures(transformer).iter().filter_map(|fixture| fixture.check(transformer).err()).collect()
+}
+
+/// Fill in an SQL pattern's placeholders outside string literals: `n` and `m` become numbers
+/// and a bare `table` a table name
+pub fn concretize_sql(pattern: &str) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in pattern.chars().chain(std::iter::once(' ')) {
+        if !quoted && (c.is_alphanumeric() || c == '_') {
+            word.push(c);
+            continue;
+        }
+        out.push_str(match word.as_str() {
+            "table" => TABLE_NAME,
+            word => placeholder(word).unwrap_or(word),
+        });
+        word.clear();
+        if c == '\'' {
+            quoted = !quoted;
+        }
+        out.push(c);
+    }
+    out.pop();
+    out
+}
+
+/// Check commands against a documented Redis pattern. Commands are separated by `; `;
+/// `<...>` matches any text (a script, a timestamp), `[...]` parts are optional, `...`
+/// marks repetition and is ignored, and a closing parenthesized note is skipped.
+pub fn matches_pattern(pattern: &str, commands: &[RedisCommand]) -> bool {
+    let patterns: Vec<&str> = pattern.split("; ").collect();
+    patterns.len() == commands.len() && patterns.iter().zip(commands).all(|(pattern, command)| {
+        let actual: Vec<&str> = std::iter::once(command.command.as_str()).chain(command.args.iter().map(String::as_str)).collect();
+        variants(&strip_note(tokenize(pattern))).iter().any(|tokens| {
+            tokens.len() == actual.len() && tokens.iter().zip(&actual).all(|(token, actual)| glob_matches(token, actual))
+        })
+    })
+}
+
+fn placeholder(word: &str) -> Option<&'static str> {
+    PLACEHOLDERS.iter().find(|(name, _)| *name == word).map(|(_, value)| *value)
+}
+
+/// Split a command pattern on spaces, keeping single-quoted parts (`'<lua: A + B>'`) whole
+fn tokenize(pattern: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    for c in pattern.chars() {
+        match c {
+            '\'' => quoted = !quoted,
+            ' ' if !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            _ => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Drop a trailing note like `(SUNIONSTORE for UNION)`: a parenthesized run of several tokens
+fn strip_note(mut tokens: Vec<String>) -> Vec<String> {
+    if tokens.last().is_some_and(|token| token.ends_with(')')) {
+        if let Some(start) = tokens.iter().rposition(|token| token.starts_with('(')) {
+            if start + 1 < tokens.len() && tokens[start][1..].starts_with(|c: char| c.is_alphabetic()) {
+                tokens.truncate(start);
+            }
+        }
+    }
+    tokens
+}
+
+/// Every reading of a pattern with its `[...]` parts kept or left out, placeholders filled in
+fn variants(tokens: &[String]) -> Vec<Vec<String>> {
+    let mut variants: Vec<Vec<String>> = vec![Vec::new()];
+    let mut group: Option<Vec<String>> = None;
+    for token in tokens {
+        let (opens, closes) = (token.starts_with('['), token.ends_with(']'));
+        let token = concretize_token(token.trim_start_matches('[').trim_end_matches(']').trim_end_matches("..."));
+        if opens || group.is_some() {
+            let parts = group.get_or_insert_with(Vec::new);
+            if !token.is_empty() {
+                parts.push(token);
+            }
+            if closes {
+                let parts = group.take().unwrap_or_default();
+                variants = variants.into_iter()
+                    .flat_map(|variant| {
+                        let mut kept = variant.clone();
+                        kept.extend(parts.iter().cloned());
+                        [variant, kept]
+                    })
+                    .collect();
+            }
+        } else if !token.is_empty() {
+            variants.iter_mut().for_each(|variant| variant.push(token.clone()));
+        }
+    }
+    variants
+}
+
+/// Fill in a number placeholder, keeping an exclusive-bound `(`: `n-1` -> `9`, `(n` -> `(10`
+fn concretize_token(token: &str) -> String {
+    let (prefix, rest) = match token.strip_prefix('(') {
+        Some(rest) => ("(", rest),
+        None => ("", token),
+    };
+    match placeholder(rest) {
+        Some(value) => format!("{}{}", prefix, value),
+        None => token.to_string(),
+    }
+}
+
+/// Match a token whose `<...>` parts stand for any text
+fn glob_matches(pattern: &str, actual: &str) -> bool {
+    let mut literals = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else { break };
+        literals.push(&rest[..start]);
+        rest = &rest[start + end + 1..];
+    }
+    if literals.is_empty() {
+        return pattern == actual;
+    }
+    literals.push(rest);
+    let (first, last) = (literals[0], literals[literals.len() - 1]);
+    if !actual.starts_with(first) || actual.len() < first.len() + last.len() || !actual.ends_with(last) {
+        return false;
+    }
+    let mut middle = &actual[first.len()..actual.len() - last.len()];
+    for literal in &literals[1..literals.len() - 1] {
+        match middle.find(literal) {
+            Some(at) => middle = &middle[at + literal.len()..],
+            None => return false,
+        }
+    }
+    true
+}
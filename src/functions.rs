@@ -0,0 +1,125 @@
+// functions.rs - Redis 7 Function library packaging
+// Assembles the crate's Lua scripts and the lua template directory into one library for
+// FUNCTION LOAD, and rewrites generated EVAL commands to FCALL invocations of it
+
+use crate::commands::RedisCommand;
+use crate::lua;
+
+/// Library name used by `FunctionLibrary::new`
+pub const DEFAULT_LIBRARY: &str = "sql_redis";
+
+/// Standalone scripts from `src/templates/lua`; the rest are command templates, helper
+/// libraries, or (under `complex/`) rely on `load()`, which the Redis sandbox removes
+const TEMPLATE_SCRIPTS: &[(&str, &str)] = &[
+    ("string_get", include_str!("templates/lua/string/get.lua")),
+    ("string_mget", include_str!("templates/lua/string/mget.lua")),
+    ("hash_hgetall", include_str!("templates/lua/hash/hgetall.lua")),
+    ("hash_hmget", include_str!("templates/lua/hash/hmget.lua")),
+    ("list_lrange", include_str!("templates/lua/list/lrange.lua")),
+    ("set_sismember_multi", include_str!("templates/lua/set/sismember_multi.lua")),
+    ("zset_zrangebyscore", include_str!("templates/lua/zset/zrangebyscore.lua")),
+];
+
+/// Scripts the rules emit as EVAL, so their invocations can become FCALL
+const EVAL_SCRIPTS: &[(&str, &str)] = &[
+    ("zset_avg", lua::ZSET_AVG_SCRIPT),
+    ("zset_sum", lua::ZSET_SUM_SCRIPT),
+    ("zset_min", lua::ZSET_MIN_SCRIPT),
+    ("zset_max", lua::ZSET_MAX_SCRIPT),
+    ("zset_stddev_pop", lua::ZSET_STDDEV_POP_SCRIPT),
+    ("hash_field_avg", lua::HASH_FIELD_AVG_SCRIPT),
+    ("hash_field_sum", lua::HASH_FIELD_SUM_SCRIPT),
+    ("hash_field_min", lua::HASH_FIELD_MIN_SCRIPT),
+    ("hash_field_max", lua::HASH_FIELD_MAX_SCRIPT),
+    ("list_avg", lua::LIST_AVG_SCRIPT),
+    ("list_sum", lua::LIST_SUM_SCRIPT),
+    ("list_min", lua::LIST_MIN_SCRIPT),
+    ("list_max", lua::LIST_MAX_SCRIPT),
+    ("json_fields", lua::JSON_FIELDS_SCRIPT),
+    ("zset_top_per_group", lua::ZSET_TOP_PER_GROUP_SCRIPT),
+    ("zset_hash_join", lua::ZSET_HASH_JOIN_SCRIPT),
+    ("view_refresh", lua::VIEW_REFRESH_SCRIPT),
+];
+
+/// A Redis Function library holding every script the crate can emit
+#[derive(Debug, Clone)]
+pub struct FunctionLibrary {
+    name: String,
+    /// (function name, script body) in registration order
+    functions: Vec<(String, String)>,
+}
+
+impl FunctionLibrary {
+    /// The built-in library, named `sql_redis`
+    pub fn new() -> Self {
+        Self::named(DEFAULT_LIBRARY)
+    }
+
+    /// The built-in library under another name (function names are unchanged)
+    pub fn named(name: &str) -> Self {
+        let functions = EVAL_SCRIPTS.iter()
+            .chain(TEMPLATE_SCRIPTS)
+            .map(|(name, body)| (name.to_string(), body.to_string()))
+            .collect();
+        Self { name: name.to_string(), functions }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Registered function names, in registration order
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        self.functions.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Library source: the `#!lua` shebang, then one `redis.register_function` per script.
+    /// Each body runs unchanged because the callback binds its parameters to KEYS and ARGV.
+    pub fn source(&self) -> String {
+        let mut source = format!("#!lua name={}\n", self.name);
+        for (name, body) in &self.functions {
+            source.push_str(&format!(
+                "\nredis.register_function('{}', function(KEYS, ARGV)\n{}\nend)\n",
+                name,
+                body.trim()
+            ));
+        }
+        source
+    }
+
+    /// `FUNCTION LOAD [REPLACE] <source>`; REPLACE upgrades an already loaded library
+    pub fn load_command(&self, replace: bool) -> RedisCommand {
+        let mut args = vec!["LOAD".to_string()];
+        if replace {
+            args.push("REPLACE".to_string());
+        }
+        args.push(self.source());
+        RedisCommand::new("FUNCTION", args)
+    }
+
+    /// Rewrite `EVAL script numkeys ...` to `FCALL name numkeys ...` when the script is one
+    /// of the library's functions (in any layout, e.g. minified); other commands (and unknown
+    /// scripts) pass through
+    pub fn to_fcall(&self, command: RedisCommand) -> RedisCommand {
+        if !command.command.eq_ignore_ascii_case("EVAL") {
+            return command;
+        }
+        let function = command.args.first().and_then(|script| {
+            let script = lua::minify_script(script);
+            self.functions.iter().find(|(_, body)| lua::minify_script(body) == script)
+        });
+        match function {
+            Some((name, _)) => {
+                let args = std::iter::once(name.clone()).chain(command.args[1..].iter().cloned()).collect();
+                RedisCommand::new("FCALL", args)
+            }
+            None => command,
+        }
+    }
+}
+
+impl Default for FunctionLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,113 @@
+// hints.rs - Statement-level hints in `/*+ ... */` comments
+// `RULE(name)` forces a rule, `USE_SCAN` prefers paginated scans over whole-key reads, and
+// `TTL(seconds)` expires the keys a write touches; the parser drops comments, so they are read here
+
+use crate::acl;
+use crate::commands::RedisCommand;
+use crate::error::SqlRedisError;
+
+/// Hints given in a statement's `/*+ ... */` comments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hints {
+    /// Translate with the rule of this template name, or fail
+    pub rule: Option<String>,
+    /// Prefer rules issuing SCAN-family commands; whole-key reads become one page
+    pub use_scan: bool,
+    /// Follow the write with EXPIRE on every key it writes
+    pub ttl: Option<u64>,
+}
+
+impl Hints {
+    /// Read every `/*+ ... */` comment of `sql`; hints are separated by whitespace or commas
+    /// and case-insensitive. Unknown hints and malformed arguments are errors.
+    pub fn parse(sql: &str) -> Result<Self, SqlRedisError> {
+        let mut hints = Hints::default();
+        let mut rest = sql;
+        while let Some(start) = rest.find("/*+") {
+            let body = &rest[start + 3..];
+            let end = body.find("*/").ok_or_else(|| invalid("unterminated /*+ comment"))?;
+            for hint in split_hints(&body[..end])? {
+                hints.add(hint)?;
+            }
+            rest = &body[end + 2..];
+        }
+        Ok(hints)
+    }
+
+    /// Check whether no hint was given
+    pub fn is_empty(&self) -> bool {
+        *self == Hints::default()
+    }
+
+    /// Check whether a rule may translate the statement under a `RULE` hint
+    pub fn allows(&self, template_name: &str) -> bool {
+        self.rule.as_deref().is_none_or(|rule| rule == template_name)
+    }
+
+    fn add(&mut self, (name, argument): (&str, Option<&str>)) -> Result<(), SqlRedisError> {
+        match (name.to_uppercase().as_str(), argument) {
+            ("USE_SCAN", None) => self.use_scan = true,
+            ("RULE", Some(rule)) if !rule.is_empty() => self.rule = Some(rule.to_string()),
+            ("TTL", Some(seconds)) => {
+                let seconds = seconds.parse().ok().filter(|&seconds| seconds > 0)
+                    .ok_or_else(|| invalid(&format!("TTL({}) needs a positive number of seconds", seconds)))?;
+                self.ttl = Some(seconds);
+            }
+            ("USE_SCAN" | "RULE" | "TTL", _) => return Err(invalid(&format!("malformed {} hint", name))),
+            _ => return Err(invalid(&format!("unknown hint {}", name))),
+        }
+        Ok(())
+    }
+}
+
+/// Append `EXPIRE key seconds` for every key the commands write (once each, in first-write
+/// order); deletes are skipped. Fails if nothing is written, e.g. for a SELECT.
+pub(crate) fn expire_writes(commands: &mut Vec<RedisCommand>, ttl: u64) -> Result<(), SqlRedisError> {
+    let mut keys: Vec<String> = Vec::new();
+    for command in commands.iter() {
+        let name = command.command.to_uppercase();
+        if !acl::is_write(&name) || ["DEL", "UNLINK", "EXPIRE"].contains(&name.as_str()) {
+            continue;
+        }
+        // MSET writes every key it names; other writes (e.g. SINTERSTORE dest ...) only the first
+        let indices = command.key_indices();
+        let written = if name.starts_with("MSET") { &indices[..] } else { &indices[..indices.len().min(1)] };
+        for &i in written {
+            if !keys.contains(&command.args[i]) {
+                keys.push(command.args[i].clone());
+            }
+        }
+    }
+    if keys.is_empty() {
+        return Err(invalid(&format!("TTL({}) applies to writes, but the statement writes no keys", ttl)));
+    }
+    commands.extend(keys.into_iter().map(|key| RedisCommand::new("EXPIRE", vec![key, ttl.to_string()])));
+    Ok(())
+}
+
+/// Split a hint comment body into (name, argument) pairs: `RULE(x) USE_SCAN, TTL(60)`
+fn split_hints(body: &str) -> Result<Vec<(&str, Option<&str>)>, SqlRedisError> {
+    let mut hints = Vec::new();
+    let mut rest = body.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    while !rest.is_empty() {
+        let name_len = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        if name_len == 0 {
+            return Err(invalid(&format!("unexpected {:?}", rest)));
+        }
+        let (name, after) = rest.split_at(name_len);
+        let (argument, after) = match after.strip_prefix('(') {
+            Some(inner) => {
+                let close = inner.find(')').ok_or_else(|| invalid(&format!("unclosed {}(", name)))?;
+                (Some(inner[..close].trim()), &inner[close + 1..])
+            }
+            None => (None, after),
+        };
+        hints.push((name, argument));
+        rest = after.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    Ok(hints)
+}
+
+fn invalid(reason: &str) -> SqlRedisError {
+    SqlRedisError::InvalidHint(reason.to_string())
+}
@@ -1,95 +1,928 @@
 // lib.rs - Main implementation of SQL to Redis transformer
 
+use sqlparser::ast::Statement;
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
-use std::error::Error;
-use std::fmt;
 
-use crate::rules::{Rule, create_rules};
+use crate::rules::{Rule, create_rules, create_json_rules, create_lua_fallback_rules, consumed_by_default};
 use crate::templates::TemplateEngine;
-use crate::commands::generate_command;
-
-// Core error type for SQL-Redis transformation
-#[derive(Debug)]
-pub enum SqlRedisError {
-    SqlParseError(String),
-    NoMatchingPattern(String),
-    TemplateError(String),
-    InitializationError(String),
-}
+use crate::commands::{fallback_command, RedisCommand};
+use crate::context::TemplateContext;
+use crate::reply::{Reply, Row};
+use crate::ast::{Clause, ClauseUse};
+use crate::metrics::Metrics;
+use crate::catalog::{Catalog, View, ViewStorage};
+use crate::codec::{from_argument, to_argument, BinaryEncoding, JsonCodec, Scalar};
+use crate::stream::PlanStream;
+use crate::lua::{LuaFormat, DEFAULT_MAX_JOIN_MEMBERS};
+use crate::pattern::extractors;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
 
-impl fmt::Display for SqlRedisError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SqlRedisError::SqlParseError(msg) => write!(f, "SQL parse error: {}", msg),
-            SqlRedisError::NoMatchingPattern(sql) => write!(f, "No matching pattern for: {}", sql),
-            SqlRedisError::TemplateError(msg) => write!(f, "Template error: {}", msg),
-            SqlRedisError::InitializationError(msg) => write!(f, "Initialization error: {}", msg),
-        }
-    }
+pub use crate::error::{ErrorCode, SqlRedisError};
+pub use crate::options::{Profile, TransformOptions};
+pub use crate::warning::{Warning, WarningKind};
+pub use crate::analysis::{CapacityReport, IdempotencyIssue};
+pub use crate::acl::{Acl, AclViolation};
+pub use crate::cache::{CacheMiss, CachePlan};
+pub use crate::plan::{ClientCompute, Plan, Step};
+pub use crate::rewrite::{Rewrite, Suggestion};
+pub use crate::rules::RuleKind;
+pub use crate::types::{redis_type_of, RedisDataType, TypeMapping};
+pub use crate::codec::ValueCodec;
+pub use crate::hints::Hints;
+pub use crate::chain::{ChainResult, TransformerChain};
+#[cfg(feature = "plugins")]
+use crate::plugins::{Plugin, PluginDeclaration, PluginRegistry};
+
+// Matched output for a statement, before rendering
+enum Translation<'a> {
+    /// Command text produced directly by a rule (Lua EVAL scripts, etc.)
+    Direct(String),
+    /// Template name and context to render
+    Template(&'a str, TemplateContext),
+    /// Command built by the fallback generator
+    Command(RedisCommand),
 }
 
-impl Error for SqlRedisError {}
+// Where a statement's commands go: its table, and the key prefix of a namespaced schema
+struct Target {
+    table: Option<String>,
+    schema_prefix: Option<String>,
+}
 
 // Main transformer that connects SQL pattern matching with Redis command templates
 pub struct SqlToRedisTransformer {
     rules: Vec<Box<dyn Rule>>,
-    template_engine: TemplateEngine,
+    // Behind a lock so templates can be added to a shared transformer (`add_template`)
+    template_engine: RwLock<TemplateEngine>,
+    options: TransformOptions,
+    metrics: Option<Arc<dyn Metrics>>,
+    catalog: Catalog,
+    // Declared last so plugin rules are dropped before their libraries are unloaded
+    #[cfg(feature = "plugins")]
+    plugins: Vec<Plugin>,
 }
 
+// Transformers are shared across threads behind an Arc; this fails to build if a field
+// (e.g. a feature-gated one) stops being Send + Sync
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SqlToRedisTransformer>();
+};
+
 impl SqlToRedisTransformer {
     pub fn new() -> Result<Self, SqlRedisError> {
+        Self::with_options(TransformOptions::default())
+    }
+    
+    /// Create a transformer with explicit options (e.g. enabling the admin profile)
+    pub fn with_options(options: TransformOptions) -> Result<Self, SqlRedisError> {
         // Create template engine
-        let template_engine = match TemplateEngine::new() {
-            Ok(engine) => engine,
-            Err(e) => return Err(SqlRedisError::InitializationError(format!("Template engine error: {}", e))),
+        let template_engine = TemplateEngine::with_fake_data(options.fake_data())
+            .map_err(|e| SqlRedisError::InitializationError(format!("Template engine error: {}", e)))?;
+        
+        // Create rules; JSON table rules precede the string rules they refine
+        let mut rules = create_json_rules(options.json_tables());
+        rules.extend(create_rules());
+        if options.lua_fallback() {
+            rules.extend(create_lua_fallback_rules());
+        }
+        
+        // JSON tables store their inserted rows as documents
+        let catalog = Catalog::default();
+        for table in options.json_tables() {
+            catalog.define_codec(table, Arc::new(JsonCodec));
+        }
+        
+        #[allow(unused_mut)]
+        let mut transformer = Self {
+            rules,
+            template_engine: RwLock::new(template_engine),
+            options,
+            metrics: None,
+            catalog,
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
         };
         
-        // Create rules
-        let rules = create_rules();
+        #[cfg(feature = "plugins")]
+        transformer.load_plugins()?;
         
-        Ok(Self { rules, template_engine })
+        Ok(transformer)
     }
     
-    pub fn transform(&self, sql: &str) -> Result<String, SqlRedisError> {
-        // Parse SQL into AST
+    /// Load and register every plugin in the configured plugin directories
+    #[cfg(feature = "plugins")]
+    fn load_plugins(&mut self) -> Result<(), SqlRedisError> {
+        for dir in self.options.plugin_dirs().to_vec() {
+            let plugins = plugins::load_dir(&dir)
+                .map_err(|e| SqlRedisError::InitializationError(format!("Plugin error: {}", e)))?;
+            for plugin in plugins {
+                let mut registry = PluginRegistry::default();
+                plugin.register(&mut registry);
+                self.add_plugin_registry(registry)?;
+                self.plugins.push(plugin);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Register a plugin that is linked into the binary rather than loaded from a library
+    #[cfg(feature = "plugins")]
+    pub fn register_plugin(&mut self, declaration: &PluginDeclaration) -> Result<(), SqlRedisError> {
+        let mut registry = PluginRegistry::default();
+        (declaration.register)(&mut registry);
+        self.add_plugin_registry(registry)
+    }
+    
+    /// Names of the plugins loaded from plugin directories
+    #[cfg(feature = "plugins")]
+    pub fn plugins(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(Plugin::name)
+    }
+    
+    /// Add a plugin's templates, and its rules ahead of all existing rules
+    #[cfg(feature = "plugins")]
+    fn add_plugin_registry(&mut self, registry: PluginRegistry) -> Result<(), SqlRedisError> {
+        for (name, source) in &registry.templates {
+            self.template_engine.get_mut().unwrap_or_else(|e| e.into_inner()).add_template(name, source)
+                .map_err(|e| SqlRedisError::InitializationError(format!("Plugin template {}: {}", name, e)))?;
+        }
+        self.rules.splice(0..0, registry.rules);
+        Ok(())
+    }
+    
+    /// Report parse, rule-match, and render timings to `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Register a template at runtime, e.g. for a rule whose mapping is rolled out after
+    /// startup. Safe to call on a transformer shared across threads: translations in flight
+    /// finish with the templates they started with. Built-in template names are refused
+    /// (`TemplateError::Conflict`); a template added earlier under the same name is replaced.
+    pub fn add_template(&self, name: &str, source: &str) -> Result<(), SqlRedisError> {
+        let mut engine = self.template_engine.write().unwrap_or_else(|e| e.into_inner());
+        Ok(engine.add_user_template(name, source)?)
+    }
+    
+    /// Whether a template named `name` is registered, built-in or added
+    pub fn has_template(&self, name: &str) -> bool {
+        self.templates().has_template(name)
+    }
+    
+    fn templates(&self) -> RwLockReadGuard<'_, TemplateEngine> {
+        self.template_engine.read().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    /// The options this transformer was created with
+    pub fn options(&self) -> &TransformOptions {
+        &self.options
+    }
+    
+    /// Views defined by the CREATE VIEW statements transformed so far, and table codecs
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+    
+    /// The command that recomputes a defined view's derived key
+    pub fn refresh_view(&self, name: &str) -> Option<RedisCommand> {
+        self.catalog.view(name).map(|view| view.refresh_command())
+    }
+    
+    /// Check whether a rule's profile (if any) is enabled and it isn't a disabled deprecated rule
+    fn is_rule_enabled(&self, rule: &dyn Rule) -> bool {
+        if self.options.deprecated_disabled() && rule.get_deprecated().is_some() {
+            return false;
+        }
+        match rule.get_profile() {
+            Some(profile) => self.options.has_profile(profile),
+            None => true,
+        }
+    }
+    
+    /// Clauses of `stmt` the matched rule (or the fallback generator) does not honor
+    fn dropped_clauses(stmt: &Statement, rule: Option<&dyn Rule>) -> Vec<ClauseUse> {
+        ast::stmt_get_clauses(stmt)
+            .into_iter()
+            .filter(|used| match rule {
+                Some(rule) => !rule.consumes(&used.clause),
+                None => !consumed_by_default(&used.clause),
+            })
+            .collect()
+    }
+    
+    /// Warnings for the chosen rule (or the fallback generator); in strict mode
+    /// dropped clauses are an error instead
+    fn collect_warnings(&self, stmt: &Statement, rule: Option<&dyn Rule>) -> Result<Vec<Warning>, SqlRedisError> {
+        let dropped = Self::dropped_clauses(stmt, rule);
+        if self.options.is_strict() && !dropped.is_empty() {
+            return Err(SqlRedisError::UnsupportedClause(dropped));
+        }
+        
+        let mut warnings: Vec<Warning> = dropped.iter()
+            .map(|clause| Warning::new(WarningKind::DroppedClause, &format!("{} is ignored", clause.sql))
+                .with_span(clause.span))
+            .collect();
+        if let Some(rule) = rule {
+            warnings.extend(rule.get_warnings().iter().cloned());
+        }
+        Ok(warnings)
+    }
+    
+    /// A join without a LIMIT may walk the whole sorted set: warn that it fails past the
+    /// member cap, or in strict mode reject it
+    fn guard_join(&self, stmt: &Statement, rule: Option<&dyn Rule>, warnings: &mut Vec<Warning>) -> Result<(), SqlRedisError> {
+        let joins = rule.is_some_and(|rule| rule.get_template_name() == "zset_hash_join");
+        if !joins || extractors::extract_zset_hash_join(stmt).is_some_and(|join| join.limit.is_some()) {
+            return Ok(());
+        }
+        let cap = self.options.max_join_members();
+        if self.options.is_strict() {
+            return Err(SqlRedisError::UnsupportedJoin(format!(
+                "a join without a LIMIT may walk more than {} sorted set members; add a LIMIT or raise the cap with with_max_join_members",
+                cap,
+            )));
+        }
+        warnings.push(Warning::new(
+            WarningKind::FullScan,
+            &format!("the join has no LIMIT: it walks up to {} sorted set members and fails on a larger set", cap),
+        ));
+        Ok(())
+    }
+    
+    /// Strict mode: check a template translation's commands against the statement, so a
+    /// template that drifted from its matcher is an error rather than a wrong command
+    fn verify_translation(&self, stmt: &Statement, translation: &Translation) -> Result<(), SqlRedisError> {
+        if let Translation::Template(template_name, context) = translation {
+            let commands = self.templates().render_commands(template_name, context)?;
+            if let Some(issue) = analysis::check_equivalence(stmt, &commands).into_iter().next() {
+                return Err(SqlRedisError::InconsistentTranslation(template_name.to_string(), issue.to_string()));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Why no translation exists: an unsupported join, a negative key predicate, a missing
+    /// key predicate, or no matching pattern
+    fn untranslatable(stmt: &Statement, sql: &str) -> SqlRedisError {
+        if let Some(reason) = extractors::unsupported_join(stmt) {
+            return SqlRedisError::UnsupportedJoin(reason);
+        }
+        if let Some(predicate) = ast::negative_key_predicate(stmt) {
+            return SqlRedisError::UnsupportedPredicate(predicate);
+        }
+        if Self::lacks_key_predicate(stmt) {
+            return SqlRedisError::MissingKeyPredicate(sql.to_string());
+        }
+        SqlRedisError::NoMatchingPattern(sql.to_string())
+    }
+    
+    /// Check if a SELECT/UPDATE/DELETE has no top-level predicate on `key`
+    fn lacks_key_predicate(stmt: &Statement) -> bool {
+        let addresses_rows = matches!(stmt, Statement::Query(_) | Statement::Update { .. } | Statement::Delete(_));
+        addresses_rows && !ast::stmt_get_clauses(stmt)
+            .iter()
+            .any(|used| matches!(&used.clause, ast::Clause::Predicate(column, _) if column == "key"))
+    }
+    
+    fn parse(sql: &str) -> Result<Statement, SqlRedisError> {
         let dialect = GenericDialect {};
-        let ast = Parser::parse_sql(&dialect, sql)
+        let mut ast = Parser::parse_sql(&dialect, sql)
+            .or_else(|error| {
+                // Accept RETURNING after ORDER BY/LIMIT on DELETE, as MariaDB writes it
+                match ast::delete::move_returning_before_order(sql) {
+                    Some(reordered) => Parser::parse_sql(&dialect, &reordered).map_err(|_| error),
+                    None => Err(error),
+                }
+            })
             .map_err(|e| SqlRedisError::SqlParseError(e.to_string()))?;
         
         if ast.is_empty() {
             return Err(SqlRedisError::SqlParseError("Empty SQL statement".to_string()));
         }
         
-        let stmt = &ast[0];
+        Ok(ast.swap_remove(0))
+    }
+    
+    pub fn transform(&self, sql: &str) -> Result<String, SqlRedisError> {
+        self.transform_with_report(sql).map(|(command, _)| command)
+    }
+    
+    /// Transform SQL and report warnings about the translation: dropped clauses
+    /// (errors instead in strict mode), client-side evaluation, full scans, and coercions
+    pub fn transform_with_report(&self, sql: &str) -> Result<(String, Vec<Warning>), SqlRedisError> {
+        let (translation, mut warnings, target, hints) = self.translate(sql)?;
+        let command = match translation {
+            Translation::Direct(command) => command,
+            Translation::Template(template_name, context) => {
+                self.observe_render(template_name, || self.templates().render(template_name, &context))?
+            }
+            Translation::Command(command) => command.to_string(),
+        };
+        let rewrites = self.retargets(&target)
+            || self.options.command_limits().is_limited()
+            || self.options.lua_format() != LuaFormat::Preserve
+            || self.options.max_join_members() != DEFAULT_MAX_JOIN_MEMBERS
+            || hints.ttl.is_some();
+        let command = match rewrites {
+            // Re-quote rewritten commands so keys and scripts stay single arguments, with
+            // scripts quoted as the templates render them
+            true => {
+                let mut commands = self.format_scripts(self.cap_joins(RedisCommand::parse_all(&command)));
+                if let Some(ttl) = hints.ttl {
+                    hints::expire_writes(&mut commands, ttl)?;
+                }
+                let commands = self.retarget(commands, &target);
+                self.enforce_limits(commands, &mut warnings)?
+                    .iter()
+                    .map(RedisCommand::to_rendered_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            false => command,
+        };
+        Ok((command, warnings))
+    }
+    
+    /// Transform SQL into structured commands, one per Redis call. Substituted values
+    /// are kept as single arguments, so values containing spaces survive intact.
+    pub fn transform_to_commands(&self, sql: &str) -> Result<Vec<RedisCommand>, SqlRedisError> {
+        Ok(self.commands_for(sql)?.0)
+    }
+    
+    /// Transform SQL into its commands in RESP, the wire protocol, ready for `redis-cli --pipe`.
+    /// Binary values (MessagePack rows) are written as raw bytes rather than in their text encoding.
+    pub fn transform_to_resp(&self, sql: &str) -> Result<Vec<u8>, SqlRedisError> {
+        let encoding = self.options.binary_encoding();
+        Ok(self.commands_for(sql)?.0.iter().flat_map(|command| command.to_resp_bytes(encoding)).collect())
+    }
+    
+    /// The commands for SQL and the table they address
+    fn commands_for(&self, sql: &str) -> Result<(Vec<RedisCommand>, Option<String>), SqlRedisError> {
+        let (translation, _, target, hints) = self.translate(sql)?;
+        let commands = match translation {
+            Translation::Direct(command) => RedisCommand::parse_all(&command),
+            Translation::Template(template_name, context) => {
+                self.observe_render(template_name, || self.templates().render_commands(template_name, &context))?
+            }
+            Translation::Command(command) => vec![command],
+        };
+        let mut commands = self.format_scripts(self.cap_joins(commands));
+        if let Some(ttl) = hints.ttl {
+            hints::expire_writes(&mut commands, ttl)?;
+        }
+        let commands = self.retarget(commands, &target);
+        let commands = self.enforce_limits(commands, &mut Vec::new())?;
+        Ok((commands, target.table))
+    }
+    
+    /// Lay out the scripts of EVAL commands in the configured `LuaFormat`
+    fn format_scripts(&self, mut commands: Vec<RedisCommand>) -> Vec<RedisCommand> {
+        let format = self.options.lua_format();
+        if format != LuaFormat::Preserve {
+            for command in &mut commands {
+                let scripted = ["EVAL", "EVAL_RO"].iter().any(|name| command.command.eq_ignore_ascii_case(name));
+                if let Some(script) = command.args.first_mut().filter(|_| scripted) {
+                    *script = format.apply(script);
+                }
+            }
+        }
+        commands
+    }
+    
+    /// Set the member cap of join scripts to the configured `max_join_members`
+    fn cap_joins(&self, mut commands: Vec<RedisCommand>) -> Vec<RedisCommand> {
+        let cap = self.options.max_join_members();
+        if cap != DEFAULT_MAX_JOIN_MEMBERS {
+            for command in &mut commands {
+                // EVAL script numkeys key ARGV[1]..: the cap is ARGV[6]
+                let join = command.command.eq_ignore_ascii_case("EVAL")
+                    && command.args.first().is_some_and(|script| lua::is_zset_hash_join_script(script));
+                if let Some(arg) = command.args.get_mut(8).filter(|_| join) {
+                    *arg = cap.to_string();
+                }
+            }
+        }
+        commands
+    }
+    
+    /// Check whether a table's commands are rewritten by a hash tag, key prefix, or database
+    fn retargets(&self, target: &Target) -> bool {
+        let table = target.table.as_deref();
+        target.schema_prefix.is_some()
+            || self.options.hash_tags_for(table).is_some()
+            || self.options.key_prefix_for(table).is_some()
+            || self.options.database_for(table).is_some()
+    }
+    
+    /// Apply the table's hash tags, schema and key prefixes, and database (a SELECT preamble)
+    /// to its commands; keys are tagged before prefixing so the tag covers the entity, not the prefix
+    fn retarget(&self, mut commands: Vec<RedisCommand>, target: &Target) -> Vec<RedisCommand> {
+        let table = target.table.as_deref();
+        if let Some(segments) = self.options.hash_tags_for(table) {
+            commands.iter_mut().for_each(|command| namespace::tag_keys(command, segments));
+        }
+        if let Some(prefix) = &target.schema_prefix {
+            commands.iter_mut().for_each(|command| namespace::prefix_keys(command, prefix));
+        }
+        if let Some(prefix) = self.options.key_prefix_for(table) {
+            commands.iter_mut().for_each(|command| namespace::prefix_keys(command, prefix));
+        }
+        if let Some(db) = self.options.database_for(table) {
+            namespace::target_database(&mut commands, db);
+        }
+        commands
+    }
+    
+    /// Split commands over the configured size limits, warning that the parts aren't
+    /// atomic; commands that can't be split are rejected with guidance
+    fn enforce_limits(&self, commands: Vec<RedisCommand>, warnings: &mut Vec<Warning>) -> Result<Vec<RedisCommand>, SqlRedisError> {
+        let limits = self.options.command_limits();
+        if !limits.is_limited() {
+            return Ok(commands);
+        }
+        let mut limited = Vec::with_capacity(commands.len());
+        for command in commands {
+            let parts = limits.split(&command).ok_or_else(|| {
+                let limit = [
+                    limits.max_args.map(|max| format!("{} arguments", max)),
+                    limits.max_bytes.map(|max| format!("{} bytes", max)),
+                ];
+                SqlRedisError::CommandTooLarge(format!(
+                    "{} ({} arguments, {} bytes) is over the limit of {} and can't be split; \
+                     write fewer rows or smaller values per statement, or raise the limit",
+                    command.command,
+                    command.args.len(),
+                    limits::command_bytes(&command),
+                    limit.into_iter().flatten().collect::<Vec<_>>().join(" / "),
+                ))
+            })?;
+            if parts.len() > 1 {
+                warnings.push(Warning::new(WarningKind::SplitCommand, &format!(
+                    "{} is split into {} commands to fit the size limits; they are not applied atomically",
+                    command.command,
+                    parts.len(),
+                )));
+            }
+            limited.extend(parts);
+        }
+        Ok(limited)
+    }
+    
+    /// Transform SQL into exactly one structured command
+    pub fn transform_to_command(&self, sql: &str) -> Result<RedisCommand, SqlRedisError> {
+        let mut commands = self.transform_to_commands(sql)?;
+        if commands.len() != 1 {
+            return Err(SqlRedisError::MultipleCommands(commands.len()));
+        }
+        Ok(commands.remove(0))
+    }
+    
+    /// Flag generated commands that would change data again if the statement were
+    /// re-run (e.g. after a partial failure), with suggestions for safe alternatives
+    pub fn check_idempotency(&self, sql: &str) -> Result<Vec<IdempotencyIssue>, SqlRedisError> {
+        Ok(analysis::check_commands(&self.transform_to_commands(sql)?))
+    }
+    
+    /// Add a statement to a capacity report: the rows and value bytes an INSERT ... VALUES
+    /// inserts, and the keys its generated writes store
+    pub fn record_capacity(&self, sql: &str, report: &mut CapacityReport) -> Result<(), SqlRedisError> {
+        let (commands, table) = self.commands_for(sql)?;
+        let table = table.unwrap_or_default();
+        if let Some(sizes) = Self::parse(sql).ok().as_ref().and_then(ast::ins_get_value_sizes) {
+            report.record_rows(&table, &sizes);
+        }
+        commands.iter().for_each(|command| report.record(&table, command));
+        Ok(())
+    }
+    
+    /// Report generated commands the ACL user would be refused: commands or categories it
+    /// can't run, and keys or channels outside its patterns
+    pub fn validate_against_acl(&self, sql: &str, acl: &Acl) -> Result<Vec<AclViolation>, SqlRedisError> {
+        Ok(acl::check_commands(acl, &self.transform_to_commands(sql)?))
+    }
+    
+    /// A cache-aside recipe for a single-key read of a hash or string table: the read, how
+    /// a miss shows in its reply, and the SET/HSET (expiring after `ttl`) that populates it
+    pub fn cache_plan(&self, sql: &str, ttl: Duration) -> Result<CachePlan, SqlRedisError> {
+        let read = self.transform_to_command(sql)?;
+        CachePlan::for_read(&read, ttl).ok_or_else(|| SqlRedisError::NotCacheable(sql.to_string()))
+    }
+    
+    /// The steps a statement runs: its commands, with EVALs as script steps, pipelined in
+    /// order (IN lists, multi-row writes, database preambles). An UPDATE no rule translates
+    /// whose values are computed from the current ones gets its `update_plan`.
+    pub fn plan(&self, sql: &str) -> Result<Plan, SqlRedisError> {
+        match self.transform_to_commands(sql) {
+            Ok(commands) => Ok(Plan::from_commands(commands)),
+            Err(error) => self.update_plan(sql).map_err(|_| error),
+        }
+    }
+    
+    /// The plans of statements read lazily from `lines`, one statement per line (blank and
+    /// `--` comment lines are skipped). A line is read only when the next plan is asked for,
+    /// so inputs of any size translate in constant memory; `PlanStream::line` tells which
+    /// input line the last plan came from.
+    pub fn transform_stream<I>(&self, lines: I) -> PlanStream<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        PlanStream::new(self, lines.into_iter())
+    }
+    
+    /// An optimistic-locking recipe for an UPDATE of string or hash keys whose new values
+    /// are computed from their current ones (see `Plan::for_update`). Keys are prefixed,
+    /// tagged, and in the database configured for the table.
+    pub fn update_plan(&self, sql: &str) -> Result<Plan, SqlRedisError> {
+        let (stmt, _, schema) = self.prepare(sql)?;
+        let plan = Plan::for_update(&stmt).ok_or_else(|| SqlRedisError::NotPlannable(sql.to_string()))?;
+        let target = Target {
+            table: ast::stmt_get_table_name(&stmt),
+            schema_prefix: schema.and_then(|schema| self.options.schema_prefix(&schema).map(str::to_string)),
+        };
+        Ok(plan.rewrite_commands(|commands| self.retarget(commands, &target)))
+    }
+    
+    /// Suggest a bounded alternative when `sql` maps to an anti-pattern (SMEMBERS, HGETALL,
+    /// whole-key ranges): the statement rewritten per its rule's metadata, and its translation
+    pub fn suggest_rewrite(&self, sql: &str) -> Result<Option<Suggestion>, SqlRedisError> {
+        let (stmt, hints, _) = self.prepare(sql)?;
+        let rewritten = match self.select_translation(&stmt, &hints) {
+            (Some(_), Some(rule)) => rule.get_rewrite().and_then(|rewrite| rewrite.apply(&stmt)),
+            _ => None,
+        };
+        let Some(rewritten) = rewritten else { return Ok(None) };
+        let sql = rewritten.to_string();
+        let command = self.transform(&sql)?;
+        Ok(Some(Suggestion { sql, command }))
+    }
+    
+    /// Time a template render and report it to the metrics hooks
+    fn observe_render<T, E>(&self, template_name: &str, render: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let started = Instant::now();
+        let result = render();
+        if let Some(metrics) = &self.metrics {
+            metrics.on_render(template_name, started.elapsed(), result.is_ok());
+        }
+        result
+    }
+    
+    /// Template name of the rule that translates `sql`, or "fallback" for the built-in
+    /// command generator (as reported to metrics and audit records)
+    pub fn matched_rule(&self, sql: &str) -> Result<&str, SqlRedisError> {
+        let (stmt, hints, _) = self.prepare(sql)?;
+        match self.select_translation(&stmt, &hints) {
+            (Some(_), Some(rule)) => Ok(rule.get_template_name()),
+            (Some(_), None) => Ok("fallback"),
+            (None, _) if hints.rule.is_some() => Err(Self::unmatched_rule_hint(&hints)),
+            (None, _) => Err(Self::untranslatable(&stmt, sql)),
+        }
+    }
+    
+    /// The template rule that translates `sql` and the context its builder extracts, without
+    /// rendering: `SELECT name FROM users__hash WHERE key = 'user:1'` gives `hash_get` with
+    /// `key` = `user:1` and `field` = `name` (list values are joined by `context::LIST_SEPARATOR`).
+    /// None when the translation doesn't render a template: a rule's direct command (such as
+    /// a Lua script), a view, or the built-in command generator. Key prefixes, hash tags, and
+    /// databases are applied after rendering, so the context holds the keys as written.
+    pub fn build_context(&self, sql: &str) -> Result<Option<(&str, TemplateContext)>, SqlRedisError> {
+        let (stmt, hints, _) = self.prepare(sql)?;
+        match self.select_translation(&stmt, &hints) {
+            (Some(Translation::Template(name, context)), _) => Ok(Some((name, context))),
+            (Some(_), _) => Ok(None),
+            (None, _) if hints.rule.is_some() => Err(Self::unmatched_rule_hint(&hints)),
+            (None, _) => Err(Self::untranslatable(&stmt, sql)),
+        }
+    }
+    
+    /// Match a statement against the rules, then the built-in command generator.
+    /// Also returns the statement's table, which selects its key prefix and database.
+    fn translate(&self, sql: &str) -> Result<(Translation<'_>, Vec<Warning>, Target, Hints), SqlRedisError> {
+        let started = Instant::now();
+        let prepared = self.prepare(sql);
+        if let Some(metrics) = &self.metrics {
+            metrics.on_parse(started.elapsed(), prepared.is_ok());
+        }
+        let (stmt, hints, schema) = prepared?;
+        let stmt = &stmt;
+        
+        let started = Instant::now();
+        let (translation, rule) = self.select_translation(stmt, &hints);
+        if let Some(metrics) = &self.metrics {
+            let matched = translation.as_ref().map(|_| rule.map_or("fallback", |rule| rule.get_template_name()));
+            metrics.on_rule_match(matched, started.elapsed());
+        }
+        if let Some(translation) = translation {
+            let target = Target {
+                table: ast::stmt_get_table_name(stmt),
+                schema_prefix: schema.and_then(|schema| self.options.schema_prefix(&schema).map(str::to_string)),
+            };
+            // A trigger without WHEN watches the table's key prefix; with no prefix it would watch every key
+            if ast::trg_is_unfiltered(stmt) && target.schema_prefix.is_none() && self.options.key_prefix_for(target.table.as_deref()).is_none() {
+                return Err(SqlRedisError::MissingKeyPredicate(sql.to_string()));
+            }
+            let mut warnings = self.collect_warnings(stmt, rule)?;
+            self.guard_join(stmt, rule, &mut warnings)?;
+            if self.options.is_strict() {
+                self.verify_translation(stmt, &translation)?;
+            }
+            if let Some(view) = self.view_definition(stmt) {
+                self.catalog.define_view(view);
+            }
+            return Ok((translation, warnings, target, hints));
+        }
         
+        // If both strategies fail, return error
+        if hints.rule.is_some() {
+            return Err(Self::unmatched_rule_hint(&hints));
+        }
+        Err(Self::untranslatable(stmt, sql))
+    }
+
+    /// Parse SQL and its `/*+ ... */` hints, stripping a declared schema qualifier (returned),
+    /// resolving table aliases, and folding NOW() and constant arithmetic in predicates;
+    /// under `USE_SCAN`, a whole-key read becomes its paginated rewrite
+    fn prepare(&self, sql: &str) -> Result<(Statement, Hints, Option<String>), SqlRedisError> {
+        let hints = Hints::parse(sql)?;
+        if let Some(name) = &hints.rule {
+            if !self.rules.iter().any(|rule| rule.get_template_name() == name) {
+                return Err(SqlRedisError::InvalidHint(format!("RULE({}): no such rule", name)));
+            }
+        }
+        let mut stmt = Self::parse(sql)?;
+        let schema = ast::strip_schema(&mut stmt);
+        if let Some(schema) = schema.as_ref().filter(|schema| !self.options.has_schema(schema)) {
+            return Err(SqlRedisError::UnknownSchema(schema.clone(), sql.to_string()));
+        }
+        ast::resolve_aliases(&mut stmt);
+        ast::fold_constants(&mut stmt, self.options.now());
+        if hints.use_scan {
+            let paged = match self.select_translation(&stmt, &hints) {
+                (Some(_), Some(rule)) => rule.get_rewrite().and_then(|rewrite| rewrite.apply(&stmt)),
+                _ => None,
+            };
+            if let Some(paged) = paged {
+                stmt = paged;
+            }
+        }
+        Ok((stmt, hints, schema))
+    }
+    
+    fn unmatched_rule_hint(hints: &Hints) -> SqlRedisError {
+        SqlRedisError::InvalidHint(format!("RULE({}) does not match the statement", hints.rule.as_deref().unwrap_or_default()))
+    }
+    
+    /// Enabled rules in the order they are tried: as registered, but with SCAN-family rules
+    /// first under `USE_SCAN`, and only the named rule under `RULE(name)`
+    fn candidate_rules(&self, hints: &Hints) -> Vec<&dyn Rule> {
+        let scans = |rule: &&dyn Rule| {
+            hints.use_scan && rule.get_redis_pattern()
+                .and_then(|pattern| pattern.split_whitespace().next())
+                .is_some_and(|command| command.ends_with("SCAN"))
+        };
+        let (mut rules, rest): (Vec<&dyn Rule>, Vec<&dyn Rule>) = self.rules.iter()
+            .map(|rule| rule.as_ref())
+            .filter(|rule| self.is_rule_enabled(*rule) && hints.allows(rule.get_template_name()))
+            .partition(scans);
+        rules.extend(rest);
+        rules
+    }
+    
+    /// Find the translation for a statement and the rule that produced it
+    /// (None for the built-in command generator)
+    fn select_translation(&self, stmt: &Statement, hints: &Hints) -> (Option<Translation<'_>>, Option<&dyn Rule>) {
+        // Views: CREATE VIEW refreshes the derived key, SELECT from a view reads it
+        if ast::view_get_definition(stmt).is_some() {
+            return (self.view_definition(stmt).map(|view| Translation::Direct(view.refresh())), None);
+        }
+        if let Some(view) = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(ast::sel_get_table_name)
+            .and_then(|table| self.catalog.view(&table))
+        {
+            return (Some(Translation::Command(view.read_command())), None);
+        }
+        // Tables with a codec: inserted rows are serialized by it
+        if let Some(command) = self.encoded_insert(stmt) {
+            return (Some(Translation::Command(command)), None);
+        }
+        
+        let (translation, rule) = self.rule_translation(stmt, hints);
+        
+        // Last resort: a rule that honors the WHERE predicates the match would drop
+        let drops_predicates = translation.is_some() && Self::dropped_clauses(stmt, rule).iter()
+            .any(|used| matches!(used.clause, Clause::Predicate(..)));
+        if translation.is_none() || drops_predicates {
+            let last_resort = self.candidate_rules(hints).into_iter()
+                .filter(|candidate| candidate.is_last_resort() && candidate.matches(stmt))
+                .find_map(|candidate| candidate.get_direct_command(stmt).map(|command| (command, candidate)));
+            if let Some((command, last_resort)) = last_resort {
+                return (Some(Translation::Direct(command)), Some(last_resort));
+            }
+        }
+        (translation, rule)
+    }
+    
+    /// Match a statement against the rules, then the built-in command generator
+    fn rule_translation(&self, stmt: &Statement, hints: &Hints) -> (Option<Translation<'_>>, Option<&dyn Rule>) {
         // First strategy: Rule-based matching
-        for rule in &self.rules {
-            if rule.matches(stmt) {
+        for rule in self.candidate_rules(hints) {
+            if !rule.is_last_resort() && rule.matches(stmt) {
                 // Check for direct command (Lua EVAL scripts, etc.)
                 if let Some(command) = rule.get_direct_command(stmt) {
-                    return Ok(command);
+                    return (Some(Translation::Direct(command)), Some(rule));
                 }
                 
                 // Get context from the rule for the matched statement
                 if let Some(context) = rule.get_context(stmt) {
-                    // Get template name from the rule
-                    let template_name = rule.get_template_name();
-                    
-                    // Render template with context
-                    return self.template_engine.render(template_name, &context)
-                        .map_err(|e| SqlRedisError::TemplateError(e.to_string()));
+                    return (Some(Translation::Template(rule.get_template_name(), context)), Some(rule));
                 }
             }
         }
         
-        // Second strategy: Direct command generation
-        if let Some(command) = generate_command(stmt) {
-            return Ok(command.to_string());
+        // Second strategy: Direct command generation (unless a rule was demanded)
+        if hints.rule.is_some() {
+            return (None, None);
+        }
+        (fallback_command(stmt).map(Translation::Command), None)
+    }
+
+    /// An INSERT into a table with a codec in the catalog: each row's columns encoded into
+    /// one string value (SET, or MSET for several rows), or a hash row's field values encoded
+    /// (HSET). None for other statements and tables, an INSERT of just `value` (already the
+    /// stored value), or rows the codec can't hold.
+    fn encoded_insert(&self, stmt: &Statement) -> Option<RedisCommand> {
+        let table = ast::ins_get_table_name(stmt)?;
+        let codec = self.catalog.codec(&table)?;
+        let rows = ast::ins_get_typed_rows(stmt)?;
+        let encoding = self.options.binary_encoding();
+        let mut args = Vec::new();
+        let command = match redis_type_of(&table, TypeMapping::standard()) {
+            RedisDataType::String => {
+                for row in &rows {
+                    let (key, columns) = Self::split_key_column(row)?;
+                    if columns.iter().all(|(column, _)| column.eq_ignore_ascii_case("value")) {
+                        return None;
+                    }
+                    args.push(key);
+                    args.push(to_argument(codec.as_ref(), &codec.encode_row(&columns)?, encoding));
+                }
+                RedisCommand::new(if rows.len() == 1 { "SET" } else { "MSET" }, args)
+            }
+            RedisDataType::Hash => {
+                let [row] = rows.as_slice() else { return None };
+                let (key, columns) = Self::split_key_column(row)?;
+                args.push(key);
+                for (column, value) in &columns {
+                    args.push(column.clone());
+                    args.push(to_argument(codec.as_ref(), &codec.encode_value(value), encoding));
+                }
+                RedisCommand::new("HSET", args)
+            }
+            _ => return None,
+        };
+        // Values follow each key (SET/MSET) or field (HSET)
+        let first_value = if command.command == "HSET" { 2 } else { 1 };
+        let binary = match codec.is_binary() {
+            true => (first_value..command.args.len()).step_by(2).collect(),
+            false => Vec::new(),
+        };
+        Some(command.with_binary_args(binary))
+    }
+
+    /// Split an inserted row into its `key` value and its other columns; None without a key
+    fn split_key_column(row: &[(String, Scalar)]) -> Option<(String, Vec<(String, Scalar)>)> {
+        let key = row.iter().find(|(column, _)| column.eq_ignore_ascii_case("key"))?.1.to_text();
+        let columns = row.iter().filter(|(column, _)| !column.eq_ignore_ascii_case("key")).cloned().collect();
+        Some((key, columns))
+    }
+
+    /// The view a CREATE VIEW statement defines, stored as a sorted set on Redis 6.2+
+    fn view_definition(&self, stmt: &Statement) -> Option<View> {
+        let (name, query) = ast::view_get_definition(stmt)?;
+        let storage = if self.options.redis_at_least(6, 2) { ViewStorage::SortedSet } else { ViewStorage::List };
+        View::from_query(&name, query, storage)
+    }
+
+    /// Decode a raw Redis reply into rows using the result decoder of the rule
+    /// that handles `sql`. Returns `Ok(None)` if that rule has no decoder.
+    /// Rows are narrowed to the columns the SELECT projects (see `project_rows`).
+    pub fn decode_reply(&self, sql: &str, reply: &Reply) -> Result<Option<Vec<Row>>, SqlRedisError> {
+        let (stmt, hints, _) = self.prepare(sql)?;
+        let stmt = &stmt;
+        
+        let rule = match self.select_translation(stmt, &hints) {
+            (Some(_), Some(rule)) if rule.is_last_resort() => Some(rule),
+            _ => self.candidate_rules(&hints).into_iter().find(|rule| !rule.is_last_resort() && rule.matches(stmt)),
         }
+        .ok_or_else(|| SqlRedisError::NoMatchingPattern(sql.to_string()))?;
         
-        // If both strategies fail, return error
-        Err(SqlRedisError::NoMatchingPattern(sql.to_string()))
+        let table = ast::sel_get_query(stmt).and_then(ast::sel_get_select).and_then(ast::sel_get_table_name);
+        let codec = table.as_deref().and_then(|table| Some((table, self.catalog.codec(table)?)));
+        // Rows hold text: spell raw binary values the way the text output writes them
+        let spelled;
+        let reply = match &codec {
+            Some((_, codec)) if codec.is_binary() => {
+                spelled = Self::spell_binary(reply, self.options.binary_encoding());
+                &spelled
+            }
+            _ => reply,
+        };
+        if let Some((table, codec)) = &codec {
+            let reads_values = ["string_get", "string_mget"].contains(&rule.get_template_name());
+            let is_string = redis_type_of(table, TypeMapping::standard()) == RedisDataType::String;
+            if let Some(rows) = self.decode_stored_rows(codec.as_ref(), reply).filter(|_| reads_values && is_string) {
+                return Ok(Some(Self::project_rows(stmt, rows)));
+            }
+        }
+        let rows = match rule.decodes_values() {
+            true => Some(Self::value_rows(stmt, reply)),
+            false => rule.get_result_decoder().map(|decode| match rule.decodes_projection() {
+                true => decode(reply),
+                false => Self::project_rows(stmt, decode(reply)),
+            }),
+        };
+        Ok(match codec {
+            Some((_, codec)) => rows.map(|rows| self.decode_stored_values(codec.as_ref(), rows)),
+            None => rows,
+        })
+    }
+
+    /// Decode the values of a GET/MGET reply on a codec table into one row of columns each
+    /// (missing keys are skipped); None if the codec doesn't read a value back
+    fn decode_stored_rows(&self, codec: &dyn ValueCodec, reply: &Reply) -> Option<Vec<Row>> {
+        let values = match reply {
+            Reply::Array(items) => items.as_slice(),
+            value => std::slice::from_ref(value),
+        };
+        values.iter()
+            .filter_map(Reply::as_text)
+            .map(|text| {
+                let columns = codec.decode_row(&from_argument(codec, text.as_bytes(), self.options.binary_encoding()))?;
+                Some(columns.into_iter().map(|(column, value)| (column, value.to_text())).collect())
+            })
+            .collect()
+    }
+
+    /// Spell the bulk values of a reply that aren't UTF-8 (raw binary values) in `encoding`,
+    /// so decoding them into text rows keeps their bytes
+    fn spell_binary(reply: &Reply, encoding: BinaryEncoding) -> Reply {
+        match reply {
+            Reply::Bulk(bytes) if std::str::from_utf8(bytes).is_err() => Reply::bulk(encoding.encode(bytes)),
+            Reply::Array(items) => Reply::Array(items.iter().map(|item| Self::spell_binary(item, encoding)).collect()),
+            reply => reply.clone(),
+        }
+    }
+    
+    /// Decode each column value of hash rows read from a codec table
+    fn decode_stored_values(&self, codec: &dyn ValueCodec, rows: Vec<Row>) -> Vec<Row> {
+        let encoding = self.options.binary_encoding();
+        rows.into_iter()
+            .map(|row| row.into_iter()
+                .map(|(column, text)| match codec.decode_value(&from_argument(codec, text.as_bytes(), encoding)) {
+                    Some(value) => (column, value.to_text()),
+                    None => (column, text),
+                })
+                .collect())
+            .collect()
+    }
+    
+    /// Name the values of an HGET/HMGET reply after the SELECT's projected columns
+    fn value_rows(stmt: &Statement, reply: &Reply) -> Vec<Row> {
+        let columns: Vec<String> = ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(|select| ast::sel_get_projected_columns(&select.projection))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, column)| column)
+            .collect();
+        reply::decode_values(reply, &columns)
+    }
+    
+    /// Keep only the columns a SELECT projects, in projection order and under their
+    /// aliases, for rules that read more than was asked for (e.g. HGETALL); CASE
+    /// projections are evaluated over each row. Wildcard SELECTs and other statements
+    /// keep every column; fields absent from a row (and NULL CASE results) are left
+    /// out rather than invented.
+    fn project_rows(stmt: &Statement, rows: Vec<Row>) -> Vec<Row> {
+        let columns = match ast::sel_get_query(stmt)
+            .and_then(ast::sel_get_select)
+            .and_then(|select| expr::projections(&select.projection))
+        {
+            Some(columns) => columns,
+            None => return rows,
+        };
+        
+        rows.into_iter()
+            .map(|row| columns.iter()
+                .filter_map(|(projection, column)| {
+                    projection.evaluate(&row).map(|value| (column.clone(), value))
+                })
+                .collect())
+            .collect()
     }
 
     pub fn list_supported_patterns(&self) -> Vec<String> {
@@ -99,6 +932,11 @@ impl SqlToRedisTransformer {
             .collect()
     }
     
+    /// Returns which SQL constructs the rules support on each Redis data type
+    pub fn compatibility_matrix(&self) -> matrix::CompatibilityMatrix {
+        matrix::CompatibilityMatrix::from_patterns(&self.get_pattern_details())
+    }
+    
     /// Returns detailed information about all patterns
     pub fn get_pattern_details(&self) -> Vec<PatternInfo> {
         self.rules
@@ -108,6 +946,12 @@ impl SqlToRedisTransformer {
                 matcher: rule.get_matcher_name().unwrap_or("unknown").to_string(),
                 sql_pattern: rule.get_sql_pattern().unwrap_or("").to_string(),
                 redis_pattern: rule.get_redis_pattern().unwrap_or("").to_string(),
+                kind: rule.get_kind(),
+                data_type: rule.get_data_type(),
+                profile: rule.get_profile(),
+                since: rule.get_since().map(str::to_string),
+                deprecated: rule.get_deprecated().map(str::to_string),
+                replacement: rule.get_replacement().map(str::to_string),
             })
             .collect()
     }
@@ -120,9 +964,23 @@ pub struct PatternInfo {
     pub matcher: String,
     pub sql_pattern: String,
     pub redis_pattern: String,
+    /// Kind of statement the pattern translates
+    pub kind: Option<RuleKind>,
+    /// Redis data type the pattern operates on; None for type-agnostic patterns
+    pub data_type: Option<RedisDataType>,
+    /// Profile required for the pattern to apply, if any
+    pub profile: Option<Profile>,
+    /// Crate version that introduced the pattern
+    pub since: Option<String>,
+    /// Crate version that deprecated the pattern
+    pub deprecated: Option<String>,
+    /// Pattern (template name) that supersedes a deprecated one
+    pub replacement: Option<String>,
 }
 
 // Modules
+pub mod prelude;
+#[doc(hidden)]
 pub mod ast;
 pub mod pattern;
 pub mod context;
@@ -130,6 +988,43 @@ pub mod rules;
 pub mod templates;
 pub mod commands;
 pub mod lua;
+pub mod types;
+pub mod expr;
+pub mod options;
+pub mod reply;
+pub mod warning;
+pub mod error;
+pub mod diagnostic;
+pub mod analysis;
+pub mod acl;
+pub mod checkpoint;
+pub mod metrics;
+pub mod audit;
+pub mod annotations;
+pub mod catalog;
+pub mod codec;
+pub mod cache;
+pub mod plan;
+pub mod stream;
+pub mod rewrite;
+pub mod hints;
+pub mod limits;
+pub mod namespace;
+pub mod cluster;
+pub mod migrate;
+pub mod seed;
+pub mod functions;
+pub mod fixtures;
+pub mod corpus;
+pub mod fingerprint;
+pub mod matrix;
+pub mod chain;
+pub mod config;
+pub mod variables;
+pub mod recording;
+pub mod executor;
+#[cfg(feature = "plugins")]
+pub mod plugins;
 
 #[cfg(test)]
 mod tests {
@@ -167,4 +1062,37 @@ mod tests {
         let result = transformer.transform("SELECT * FROM non_redis_table");
         assert!(result.is_err(), "Should fail with unsupported pattern");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_deprecated_rules() {
+        // A legacy rule for string GETs, superseded by the built-in string_get rule
+        let legacy = crate::rule! {
+            template: "del",
+            matcher: crate::pattern::matchers::select::is_string_get,
+            context: crate::context::StringGetContextBuilder,
+            since: "0.1.0",
+            deprecated: "0.2.0",
+            replacement: "string_get",
+        };
+        let sql = "SELECT * FROM cache WHERE key = 'page:1'";
+        
+        let mut transformer = SqlToRedisTransformer::new().unwrap();
+        transformer.rules.insert(0, legacy);
+        assert_eq!(transformer.transform(sql).unwrap(), "DEL page:1");
+        let info = &transformer.get_pattern_details()[0];
+        assert_eq!(info.since.as_deref(), Some("0.1.0"));
+        assert_eq!(info.deprecated.as_deref(), Some("0.2.0"));
+        assert_eq!(info.replacement.as_deref(), Some("string_get"));
+        
+        // Disabled deprecated rules fall through to their replacement
+        let options = TransformOptions::default().without_deprecated();
+        let mut transformer = SqlToRedisTransformer::with_options(options).unwrap();
+        transformer.rules.insert(0, crate::rule! {
+            template: "del",
+            matcher: crate::pattern::matchers::select::is_string_get,
+            context: crate::context::StringGetContextBuilder,
+            deprecated: "0.2.0",
+        });
+        assert_eq!(transformer.transform(sql).unwrap(), "GET page:1");
+    }
+}
@@ -0,0 +1,91 @@
+// limits.rs - Guardrails on the size of generated commands
+// Oversized variadic writes (multi-row inserts) are split into several commands; anything
+// else over the limits is rejected, keeping commands clear of proto limits and slow MIGRATEs
+
+use crate::commands::RedisCommand;
+
+/// Maximum size of one generated command; `None` leaves that dimension unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandLimits {
+    /// Bytes of the command name plus every argument
+    pub max_bytes: Option<usize>,
+    /// Arguments after the command name
+    pub max_args: Option<usize>,
+}
+
+impl CommandLimits {
+    /// Check whether any limit is set
+    pub fn is_limited(&self) -> bool {
+        self.max_bytes.is_some() || self.max_args.is_some()
+    }
+
+    /// Check whether `command` is over a limit
+    pub fn exceeds(&self, command: &RedisCommand) -> bool {
+        self.max_args.is_some_and(|max| command.args.len() > max)
+            || self.max_bytes.is_some_and(|max| command_bytes(command) > max)
+    }
+
+    /// `command` itself if it fits, else the commands it splits into, each within the limits.
+    /// None if it can't be split (not a variadic write, or one item alone is too large).
+    pub fn split(&self, command: &RedisCommand) -> Option<Vec<RedisCommand>> {
+        if !self.exceeds(command) {
+            return Some(vec![command.clone()]);
+        }
+        let (prefix, group) = layout(command)?;
+        // A command of the given arguments of `command`, keeping their binary marks
+        let pick = |indices: &mut dyn Iterator<Item = usize>| {
+            let mut picked = RedisCommand::new(&command.command, Vec::<String>::new());
+            indices.for_each(|i| picked.push_arg_from(command, i));
+            picked
+        };
+        let mut commands = Vec::new();
+        let mut current = pick(&mut (0..prefix));
+        for item in (prefix..command.args.len()).step_by(group) {
+            let mut next = current.clone();
+            (item..item + group).for_each(|i| next.push_arg_from(command, i));
+            if !self.exceeds(&next) {
+                current = next;
+                continue;
+            }
+            if current.args.len() == prefix {
+                return None;
+            }
+            commands.push(current);
+            current = pick(&mut (0..prefix).chain(item..item + group));
+            if self.exceeds(&current) {
+                return None;
+            }
+        }
+        commands.push(current);
+        Some(commands)
+    }
+}
+
+/// Size of a command as counted by `CommandLimits::max_bytes`
+pub fn command_bytes(command: &RedisCommand) -> usize {
+    command.command.len() + command.args.iter().map(String::len).sum::<usize>()
+}
+
+/// Leading arguments every split command repeats, and the size of each item after them,
+/// for writes whose items can be applied in separate commands
+fn layout(command: &RedisCommand) -> Option<(usize, usize)> {
+    let (prefix, group) = match command.command.to_uppercase().as_str() {
+        "SADD" | "SREM" | "RPUSH" | "LPUSH" | "RPUSHX" | "LPUSHX" | "ZREM" | "HDEL" => (1, 1),
+        "HSET" => (1, 2),
+        "MSET" => (0, 2),
+        "DEL" | "UNLINK" | "TOUCH" => (0, 1),
+        // ZADD key [NX|XX] [GT|LT] [CH] score member ...; INCR takes a single pair
+        "ZADD" => {
+            let flags = command.args.iter().skip(1)
+                .take_while(|arg| ["NX", "XX", "GT", "LT", "CH", "INCR"].iter().any(|flag| arg.eq_ignore_ascii_case(flag)))
+                .collect::<Vec<_>>();
+            if flags.iter().any(|flag| flag.eq_ignore_ascii_case("INCR")) {
+                return None;
+            }
+            (1 + flags.len(), 2)
+        }
+        _ => return None,
+    };
+    let items = command.args.len().checked_sub(prefix)?;
+    (items > 0 && items % group == 0).then_some((prefix, group))
+}
@@ -0,0 +1,64 @@
+// lua/bundle.rs - Replayable command bundles with scripts loaded once
+// Rewrites EVAL to EVALSHA and collects the distinct scripts for a SCRIPT LOAD preamble
+
+use crate::commands::RedisCommand;
+use super::script_sha1;
+
+/// A batch of commands in which every `EVAL` is replaced by `EVALSHA`, plus one
+/// `SCRIPT LOAD` per distinct script, so a replayed artifact sends each script once
+#[derive(Debug, Clone, Default)]
+pub struct ScriptBundle {
+    /// Distinct scripts in first-use order
+    scripts: Vec<String>,
+    commands: Vec<RedisCommand>,
+}
+
+impl ScriptBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a command, rewriting `EVAL script numkeys ...` to `EVALSHA sha numkeys ...`
+    pub fn push(&mut self, command: RedisCommand) {
+        let script = match command.args.first() {
+            Some(script) if command.command.eq_ignore_ascii_case("EVAL") => script,
+            _ => {
+                self.commands.push(command);
+                return;
+            }
+        };
+        let sha = script_sha1(script);
+        if !self.scripts.contains(script) {
+            self.scripts.push(script.clone());
+        }
+        let args = std::iter::once(sha).chain(command.args[1..].iter().cloned()).collect();
+        self.commands.push(RedisCommand::new("EVALSHA", args));
+    }
+
+    /// `SCRIPT LOAD` commands for the distinct scripts, in first-use order
+    pub fn preamble(&self) -> Vec<RedisCommand> {
+        self.scripts.iter()
+            .map(|script| RedisCommand::new("SCRIPT", vec!["LOAD", script.as_str()]))
+            .collect()
+    }
+
+    /// The bundled commands, without the preamble
+    pub fn commands(&self) -> &[RedisCommand] {
+        &self.commands
+    }
+
+    /// The preamble followed by the bundled commands
+    pub fn into_commands(self) -> Vec<RedisCommand> {
+        let mut commands = self.preamble();
+        commands.extend(self.commands);
+        commands
+    }
+}
+
+impl Extend<RedisCommand> for ScriptBundle {
+    fn extend<I: IntoIterator<Item = RedisCommand>>(&mut self, commands: I) {
+        for command in commands {
+            self.push(command);
+        }
+    }
+}
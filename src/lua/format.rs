@@ -0,0 +1,201 @@
+// lua/format.rs - Canonical layout and minification of generated Lua
+// Rendered scripts pick up whitespace from template tags; re-indenting them (or stripping
+// comments and layout before EVAL) makes the output stable across template edits
+
+/// How scripts in generated `EVAL` commands are laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LuaFormat {
+    /// As rendered
+    #[default]
+    Preserve,
+    /// Re-indented by block depth (4 spaces), trailing whitespace and repeated blank lines removed
+    Canonical,
+    /// Comments, indentation, and blank lines removed
+    Minify,
+}
+
+impl LuaFormat {
+    /// The script laid out in this format
+    pub fn apply(&self, script: &str) -> String {
+        match self {
+            LuaFormat::Preserve => script.to_string(),
+            LuaFormat::Canonical => format_script(script),
+            LuaFormat::Minify => minify_script(script),
+        }
+    }
+}
+
+/// A run of script text: code, a string literal, or a comment (verbatim, with delimiters)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Span<'a> {
+    Code(&'a str),
+    Str(&'a str),
+    Comment(&'a str),
+}
+
+/// Split a script into code, string literals (quoted or long-bracket), and comments
+fn scan(script: &str) -> Vec<Span<'_>> {
+    let bytes = script.as_bytes();
+    let mut spans = Vec::new();
+    let (mut start, mut i) = (0, 0);
+    while i < bytes.len() {
+        let end = match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                let end = long_bracket_end(script, i + 2)
+                    .unwrap_or_else(|| script[i..].find('\n').map_or(script.len(), |n| i + n));
+                Some((end, true))
+            }
+            b'[' => long_bracket_end(script, i).map(|end| (end, false)),
+            quote @ (b'\'' | b'"') => {
+                let mut j = i + 1;
+                while j < bytes.len() && bytes[j] != quote && bytes[j] != b'\n' {
+                    j += if bytes[j] == b'\\' { 2 } else { 1 };
+                }
+                Some(((j + 1).min(bytes.len()), false))
+            }
+            _ => None,
+        };
+        match end {
+            Some((end, comment)) => {
+                if start < i {
+                    spans.push(Span::Code(&script[start..i]));
+                }
+                spans.push(if comment { Span::Comment(&script[i..end]) } else { Span::Str(&script[i..end]) });
+                start = end;
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    if start < script.len() {
+        spans.push(Span::Code(&script[start..]));
+    }
+    spans
+}
+
+/// End of a long bracket (`[[ ... ]]`, `[==[ ... ]==]`) opening at `at`, if one does
+fn long_bracket_end(script: &str, at: usize) -> Option<usize> {
+    let rest = script.get(at..)?.strip_prefix('[')?;
+    let level = rest.bytes().take_while(|&b| b == b'=').count();
+    let body = rest[level..].strip_prefix('[')?;
+    let close = format!("]{}]", "=".repeat(level));
+    let offset = at + 2 + level;
+    Some(body.find(&close).map_or(script.len(), |n| offset + n + close.len()))
+}
+
+/// Strip comments and layout: one statement line per line, single spaces between tokens
+pub fn minify_script(script: &str) -> String {
+    let mut out = String::new();
+    // Whitespace seen since the last token: None, Some(false) for spaces, Some(true) with a newline
+    let mut gap: Option<bool> = None;
+    let emit = |out: &mut String, text: &str, gap: &mut Option<bool>| {
+        if let Some(newline) = gap.take() {
+            if !out.is_empty() {
+                out.push(if newline { '\n' } else { ' ' });
+            }
+        }
+        out.push_str(text);
+    };
+    for span in scan(script) {
+        match span {
+            Span::Code(code) => {
+                for c in code.chars() {
+                    if c.is_whitespace() {
+                        gap = Some(gap.unwrap_or(false) || c == '\n');
+                    } else {
+                        emit(&mut out, c.encode_utf8(&mut [0; 4]), &mut gap);
+                    }
+                }
+            }
+            Span::Str(literal) => emit(&mut out, literal, &mut gap),
+            // A comment separates tokens like whitespace does
+            Span::Comment(_) => gap = Some(gap.unwrap_or(false)),
+        }
+    }
+    out
+}
+
+/// Re-indent a script by block depth with 4 spaces; trailing whitespace, leading and trailing
+/// blank lines, and runs of blank lines are removed. Multi-line strings are kept verbatim.
+pub fn format_script(script: &str) -> String {
+    // Lines as (text, code words and brackets outside strings and comments)
+    let mut lines: Vec<(String, Vec<String>)> = vec![(String::new(), Vec::new())];
+    for span in scan(script) {
+        match span {
+            Span::Code(code) => {
+                for (n, part) in code.split('\n').enumerate() {
+                    if n > 0 {
+                        lines.push((String::new(), Vec::new()));
+                    }
+                    let (text, words) = lines.last_mut().expect("at least one line");
+                    text.push_str(part);
+                    words.extend(block_tokens(part));
+                }
+            }
+            Span::Str(text) | Span::Comment(text) => lines.last_mut().expect("at least one line").0.push_str(text),
+        }
+    }
+
+    // Lines whose blocks are still open; a line opening several (`f(function()`) indents once
+    let mut open: Vec<usize> = Vec::new();
+    let levels = |open: &[usize]| {
+        let mut lines = open.to_vec();
+        lines.dedup();
+        lines.len()
+    };
+    let mut out: Vec<String> = Vec::new();
+    for (n, (text, words)) in lines.into_iter().enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            if out.last().is_some_and(|line| !line.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+        // Leading closers (`end`, `else`, `})`) dedent the line itself
+        let mut indent = None;
+        for word in &words {
+            let word = word.as_str();
+            if indent.is_none() && !["end", "until", "}", ")", "elseif", "else"].contains(&word) {
+                indent = Some(levels(&open));
+            }
+            match word {
+                "end" | "until" | "}" | ")" | "elseif" => {
+                    open.pop();
+                }
+                "else" => {
+                    open.pop();
+                    indent.get_or_insert(levels(&open));
+                    open.push(n);
+                }
+                "function" | "do" | "then" | "repeat" | "{" | "(" => open.push(n),
+                _ => {}
+            }
+        }
+        let indent = indent.unwrap_or_else(|| levels(&open));
+        out.push(format!("{}{}", "    ".repeat(indent), text));
+    }
+    while out.last().is_some_and(String::is_empty) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+/// Words and brackets of a code fragment, in order
+fn block_tokens(code: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in code.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+        if matches!(c, '{' | '}' | '(' | ')') {
+            tokens.push(c.to_string());
+        }
+    }
+    tokens
+}
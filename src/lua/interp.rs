@@ -0,0 +1,248 @@
+// lua/interp.rs - Embedded Lua 5.1 interpreter for running scripts against a MockRedis
+// Scripts see KEYS, ARGV, redis.call/pcall, redis.error_reply/status_reply, unpack and cjson
+// as under EVAL, and every redis.call lands in the mock's keyspace (`lua-test` feature)
+
+use std::cell::RefCell;
+
+use mlua::{Lua, MultiValue, Value};
+
+use super::MockRedis;
+use crate::reply::Reply;
+
+/// `cjson.encode`/`cjson.decode` and `cjson.null` as Redis's bundled cjson has them:
+/// objects encode with sorted keys, and an empty table encodes as `{}`
+const CJSON_PRELUDE: &str = r#"
+cjson = {null = setmetatable({}, {__tostring = function() return 'null' end})}
+local escapes = {['"'] = '\\"', ['\\'] = '\\\\', ['\b'] = '\\b', ['\f'] = '\\f', ['\n'] = '\\n', ['\r'] = '\\r', ['\t'] = '\\t'}
+local function encode(value)
+    local kind = type(value)
+    if value == nil or value == cjson.null then
+        return 'null'
+    elseif kind == 'boolean' then
+        return tostring(value)
+    elseif kind == 'number' then
+        if value == math.floor(value) and math.abs(value) < 1e15 then
+            return string.format('%d', value)
+        end
+        return string.format('%.14g', value)
+    elseif kind == 'string' then
+        local escaped = string.gsub(value, '[%c"\\]', function(c)
+            return escapes[c] or string.format('\\u%04x', string.byte(c))
+        end)
+        return '"' .. escaped .. '"'
+    elseif kind == 'table' then
+        local n, count, parts = #value, 0, {}
+        for _ in pairs(value) do
+            count = count + 1
+        end
+        if n > 0 and n == count then
+            for i = 1, n do
+                parts[i] = encode(value[i])
+            end
+            return '[' .. table.concat(parts, ',') .. ']'
+        end
+        for k, v in pairs(value) do
+            parts[#parts + 1] = encode(tostring(k)) .. ':' .. encode(v)
+        end
+        table.sort(parts)
+        return '{' .. table.concat(parts, ',') .. '}'
+    end
+    error('Cannot serialise ' .. kind .. ': type not supported')
+end
+local function utf8_char(code)
+    if code < 0x80 then
+        return string.char(code)
+    elseif code < 0x800 then
+        return string.char(0xC0 + math.floor(code / 64), 0x80 + code % 64)
+    end
+    return string.char(0xE0 + math.floor(code / 4096), 0x80 + math.floor(code / 64) % 64, 0x80 + code % 64)
+end
+local function skip(text, i)
+    local next = string.find(text, '%S', i)
+    if not next then
+        error('Expected value but found T_END')
+    end
+    return next
+end
+local decode
+local function decode_list(text, i, close, item)
+    i = skip(text, i)
+    if string.sub(text, i, i) == close then
+        return i + 1
+    end
+    while true do
+        i = skip(text, item(i))
+        local c = string.sub(text, i, i)
+        if c == close then
+            return i + 1
+        elseif c ~= ',' then
+            error('Expected comma or ' .. close .. ' at character ' .. i)
+        end
+        i = i + 1
+    end
+end
+decode = function(text, i)
+    i = skip(text, i)
+    local c = string.sub(text, i, i)
+    if c == '{' then
+        local object = {}
+        local next = decode_list(text, i + 1, '}', function(j)
+            local key
+            key, j = decode(text, j)
+            j = skip(text, j)
+            if type(key) ~= 'string' or string.sub(text, j, j) ~= ':' then
+                error('Expected object key string and colon at character ' .. j)
+            end
+            object[key], j = decode(text, j + 1)
+            return j
+        end)
+        return object, next
+    elseif c == '[' then
+        local array = {}
+        local next = decode_list(text, i + 1, ']', function(j)
+            array[#array + 1], j = decode(text, j)
+            return j
+        end)
+        return array, next
+    elseif c == '"' then
+        local parts, j = {}, i + 1
+        while true do
+            local s = string.find(text, '["\\]', j)
+            if not s then
+                error('Expected string end')
+            end
+            parts[#parts + 1] = string.sub(text, j, s - 1)
+            if string.sub(text, s, s) == '"' then
+                return table.concat(parts), s + 1
+            end
+            local escape = string.sub(text, s + 1, s + 1)
+            if escape == 'u' then
+                parts[#parts + 1] = utf8_char(tonumber(string.sub(text, s + 2, s + 5), 16))
+                j = s + 6
+            else
+                parts[#parts + 1] = ({b = '\b', f = '\f', n = '\n', r = '\r', t = '\t'})[escape] or escape
+                j = s + 2
+            end
+        end
+    elseif string.find(text, '^true', i) then
+        return true, i + 4
+    elseif string.find(text, '^false', i) then
+        return false, i + 5
+    elseif string.find(text, '^null', i) then
+        return cjson.null, i + 4
+    end
+    local s, e = string.find(text, '^-?%d+%.?%d*[eE]?[-+]?%d*', i)
+    if not s then
+        error('Expected value at character ' .. i)
+    end
+    return tonumber(string.sub(text, s, e)), e + 1
+end
+cjson.encode = encode
+cjson.decode = function(text)
+    local value, i = decode(text, 1)
+    if string.find(text, '%S', i) then
+        error('Expected the end but found more at character ' .. i)
+    end
+    return value
+end
+"#;
+
+/// Run `script` as EVAL would, with `keys` and `args` as KEYS and ARGV. Replies convert
+/// both ways as in Redis: nil is false, status and error replies are `{ok=...}` and
+/// `{err=...}` tables, and a Lua number returns as an integer (truncated). A failing
+/// `redis.call` or a script error replies with the error.
+pub(super) fn eval(redis: &mut MockRedis, script: &str, keys: &[String], args: &[String]) -> Reply {
+    let lua = Lua::new();
+    run(&lua, redis, script, keys, args).unwrap_or_else(|e| Reply::Error(error_message(&e)))
+}
+
+fn run(lua: &Lua, redis: &mut MockRedis, script: &str, keys: &[String], args: &[String]) -> mlua::Result<Reply> {
+    lua.load(CJSON_PRELUDE).set_name("cjson").exec()?;
+    let globals = lua.globals();
+    globals.set("KEYS", lua.create_sequence_from(keys.iter().map(String::as_str))?)?;
+    globals.set("ARGV", lua.create_sequence_from(args.iter().map(String::as_str))?)?;
+    let redis = RefCell::new(redis);
+    lua.scope(|scope| {
+        let api = lua.create_table()?;
+        api.set("call", scope.create_function(|lua, args: MultiValue| {
+            match call(lua, &redis, args)? {
+                Reply::Error(message) => Err(mlua::Error::runtime(message)),
+                reply => to_lua(lua, reply),
+            }
+        })?)?;
+        api.set("pcall", scope.create_function(|lua, args: MultiValue| {
+            let reply = call(lua, &redis, args)?;
+            to_lua(lua, reply)
+        })?)?;
+        api.set("error_reply", lua.create_function(|lua, message: String| to_lua(lua, Reply::Error(message)))?)?;
+        api.set("status_reply", lua.create_function(|lua, message: String| to_lua(lua, Reply::Status(message)))?)?;
+        globals.set("redis", api)?;
+        let value: Value = lua.load(script).set_name("user_script").eval()?;
+        Ok(from_lua(value))
+    })
+}
+
+/// Pass a `redis.call` to the mock; arguments must be strings or numbers
+fn call(lua: &Lua, redis: &RefCell<&mut MockRedis>, args: MultiValue) -> mlua::Result<Reply> {
+    let args = args.into_iter()
+        .map(|arg| match arg {
+            Value::String(_) | Value::Integer(_) | Value::Number(_) => Ok(lua.coerce_string(arg)?.map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()),
+            _ => Err(mlua::Error::runtime("ERR Lua redis lib command arguments must be strings or integers")),
+        })
+        .collect::<mlua::Result<Vec<String>>>()?;
+    if args.is_empty() {
+        return Err(mlua::Error::runtime("ERR Please specify at least one argument for this redis lib call"));
+    }
+    Ok(redis.borrow_mut().call(&args))
+}
+
+fn to_lua(lua: &Lua, reply: Reply) -> mlua::Result<Value<'_>> {
+    Ok(match reply {
+        Reply::Nil => Value::Boolean(false),
+        Reply::Integer(n) => Value::Integer(n),
+        Reply::Bulk(text) => Value::String(lua.create_string(&text)?),
+        Reply::Status(text) => Value::Table(lua.create_table_from([("ok", text)])?),
+        Reply::Error(text) => Value::Table(lua.create_table_from([("err", text)])?),
+        Reply::Array(items) => {
+            let table = lua.create_table()?;
+            for (i, item) in items.into_iter().enumerate() {
+                table.raw_set(i + 1, to_lua(lua, item)?)?;
+            }
+            Value::Table(table)
+        }
+    })
+}
+
+fn from_lua(value: Value) -> Reply {
+    match value {
+        Value::Boolean(true) => Reply::Integer(1),
+        Value::Integer(n) => Reply::Integer(n),
+        Value::Number(n) => Reply::Integer(n as i64),
+        Value::String(text) => Reply::Bulk(text.as_bytes().to_vec()),
+        Value::Table(table) => {
+            if let Ok(Value::String(text)) = table.raw_get("err") {
+                return Reply::Error(text.to_string_lossy().into_owned());
+            }
+            if let Ok(Value::String(text)) = table.raw_get("ok") {
+                return Reply::Status(text.to_string_lossy().into_owned());
+            }
+            // An array stops at its first nil, as Redis converts it
+            let items = (1..)
+                .map_while(|i| table.raw_get::<_, Value>(i).ok().filter(|item| !item.is_nil()))
+                .map(from_lua)
+                .collect();
+            Reply::Array(items)
+        }
+        _ => Reply::Nil,
+    }
+}
+
+/// The message of a script failure, without mlua's wrapping and traceback
+fn error_message(error: &mlua::Error) -> String {
+    match error {
+        mlua::Error::RuntimeError(message) => message.clone(),
+        mlua::Error::CallbackError { cause, .. } => error_message(cause),
+        mlua::Error::SyntaxError { message, .. } => format!("ERR Error compiling script: {}", message),
+        other => format!("ERR Error running script: {}", other),
+    }
+}
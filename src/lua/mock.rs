@@ -0,0 +1,637 @@
+// lua/mock.rs - In-memory stand-in for `redis.call` in Lua script tests
+// Holds a small keyspace and answers the commands the crate's scripts and rules issue, logging
+// each call, so script logic can be checked against known data without a Redis server
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::acl::glob_match;
+use crate::executor::{Connection, ExecutionError};
+use crate::reply::Reply;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Hash(BTreeMap<String, String>),
+    List(Vec<String>),
+    Set(BTreeSet<String>),
+    ZSet(BTreeMap<String, f64>),
+}
+
+/// A keyspace answering `redis.call` / `redis.pcall`: strings, hashes, lists, sets, and
+/// sorted sets, with the read commands scripts use and the writes that seed them.
+/// Unknown commands and type mismatches reply with the error Redis would raise. With the
+/// `lua-test` feature, EVAL/EVALSHA run scripts in an embedded Lua interpreter against it.
+#[derive(Debug, Clone, Default)]
+pub struct MockRedis {
+    keys: BTreeMap<String, Value>,
+    calls: Vec<Vec<String>>,
+    /// Scripts seen by EVAL or SCRIPT LOAD, by SHA-1, for EVALSHA
+    #[cfg(feature = "lua-test")]
+    scripts: BTreeMap<String, String>,
+}
+
+impl MockRedis {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run a command (name followed by arguments), as `redis.call(...)` would
+    pub fn call<S: AsRef<str>>(&mut self, args: &[S]) -> Reply {
+        let args: Vec<String> = args.iter().map(|arg| arg.as_ref().to_string()).collect();
+        self.calls.push(args.clone());
+        let Some((name, args)) = args.split_first() else {
+            return Reply::Error("ERR wrong number of arguments".to_string());
+        };
+        self.dispatch(&name.to_uppercase(), args)
+            .unwrap_or_else(Reply::Error)
+    }
+
+    /// Every command called so far, in order
+    pub fn calls(&self) -> &[Vec<String>] {
+        &self.calls
+    }
+
+    /// Forget the call log, keeping the data (e.g. after seeding)
+    pub fn clear_calls(&mut self) {
+        self.calls.clear();
+    }
+
+    fn dispatch(&mut self, name: &str, args: &[String]) -> Result<Reply, String> {
+        let arity = |min: usize| if args.len() < min { Err(wrong_arity(name)) } else { Ok(()) };
+        match name {
+            "GET" => {
+                arity(1)?;
+                match self.keys.get(&args[0]) {
+                    None => Ok(Reply::Nil),
+                    Some(Value::String(value)) => Ok(Reply::bulk(value.clone())),
+                    Some(_) => Err(wrong_type()),
+                }
+            }
+            "MGET" => {
+                arity(1)?;
+                Ok(Reply::Array(args.iter()
+                    .map(|key| match self.keys.get(key) {
+                        Some(Value::String(value)) => Reply::bulk(value.clone()),
+                        _ => Reply::Nil,
+                    })
+                    .collect()))
+            }
+            "SET" => {
+                arity(2)?;
+                self.keys.insert(args[0].clone(), Value::String(args[1].clone()));
+                Ok(ok())
+            }
+            "DEL" | "UNLINK" => {
+                arity(1)?;
+                Ok(Reply::Integer(args.iter().filter(|key| self.keys.remove(*key).is_some()).count() as i64))
+            }
+            "EXISTS" => {
+                arity(1)?;
+                Ok(Reply::Integer(args.iter().filter(|key| self.keys.contains_key(*key)).count() as i64))
+            }
+            "KEYS" => {
+                arity(1)?;
+                Ok(bulks(self.keys.keys().filter(|key| glob_match(&args[0], key)).cloned()))
+            }
+            // One page holds every match, whatever the cursor and COUNT
+            "SCAN" => {
+                arity(1)?;
+                let pattern = scan_match(args);
+                let keys = bulks(self.keys.keys().filter(|key| glob_match(pattern, key)).cloned());
+                Ok(Reply::Array(vec![Reply::bulk("0"), keys]))
+            }
+            "TYPE" => {
+                arity(1)?;
+                let kind = match self.keys.get(&args[0]) {
+                    None => "none",
+                    Some(Value::String(_)) => "string",
+                    Some(Value::Hash(_)) => "hash",
+                    Some(Value::List(_)) => "list",
+                    Some(Value::Set(_)) => "set",
+                    Some(Value::ZSet(_)) => "zset",
+                };
+                Ok(Reply::Status(kind.to_string()))
+            }
+            "HSET" | "HMSET" => {
+                if args.len() < 3 || args.len().is_multiple_of(2) {
+                    return Err(wrong_arity(name));
+                }
+                let Value::Hash(fields) = self.entry(&args[0], || Value::Hash(BTreeMap::new()))? else { unreachable!() };
+                let added = args[1..].chunks(2)
+                    .filter(|pair| fields.insert(pair[0].clone(), pair[1].clone()).is_none())
+                    .count();
+                Ok(if name == "HMSET" { ok() } else { Reply::Integer(added as i64) })
+            }
+            "HGET" => {
+                arity(2)?;
+                Ok(self.hash(&args[0])?.and_then(|fields| fields.get(&args[1]).cloned()).map_or(Reply::Nil, Reply::bulk))
+            }
+            "HMGET" => {
+                arity(2)?;
+                let fields = self.hash(&args[0])?;
+                Ok(Reply::Array(args[1..].iter()
+                    .map(|field| fields.and_then(|fields| fields.get(field).cloned()).map_or(Reply::Nil, Reply::bulk))
+                    .collect()))
+            }
+            "HGETALL" => {
+                arity(1)?;
+                Ok(bulks(self.hash(&args[0])?.into_iter().flatten().flat_map(|(field, value)| [field.clone(), value.clone()])))
+            }
+            "RPUSH" | "LPUSH" => {
+                arity(2)?;
+                let Value::List(items) = self.entry(&args[0], || Value::List(Vec::new()))? else { unreachable!() };
+                for item in &args[1..] {
+                    if name == "RPUSH" { items.push(item.clone()) } else { items.insert(0, item.clone()) }
+                }
+                Ok(Reply::Integer(items.len() as i64))
+            }
+            "LRANGE" => {
+                arity(3)?;
+                let items = match self.keys.get(&args[0]) {
+                    None => return Ok(Reply::Array(Vec::new())),
+                    Some(Value::List(items)) => items,
+                    Some(_) => return Err(wrong_type()),
+                };
+                let (start, stop) = (integer(&args[1])?, integer(&args[2])?);
+                let len = items.len() as i64;
+                let start = if start < 0 { (len + start).max(0) } else { start };
+                let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+                if start > stop {
+                    return Ok(Reply::Array(Vec::new()));
+                }
+                Ok(bulks(items[start as usize..=stop as usize].iter().cloned()))
+            }
+            "SADD" => {
+                arity(2)?;
+                let Value::Set(members) = self.entry(&args[0], || Value::Set(BTreeSet::new()))? else { unreachable!() };
+                Ok(Reply::Integer(args[1..].iter().filter(|member| members.insert((*member).clone())).count() as i64))
+            }
+            "SMEMBERS" => {
+                arity(1)?;
+                Ok(bulks(self.set(&args[0])?.into_iter().flatten().cloned()))
+            }
+            "SISMEMBER" => {
+                arity(2)?;
+                Ok(Reply::Integer(self.set(&args[0])?.is_some_and(|members| members.contains(&args[1])) as i64))
+            }
+            "ZADD" => {
+                if args.len() < 3 || args.len().is_multiple_of(2) {
+                    return Err(wrong_arity(name));
+                }
+                let pairs = args[1..].chunks(2)
+                    .map(|pair| Ok((pair[1].clone(), score(&pair[0])?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                let Value::ZSet(members) = self.entry(&args[0], || Value::ZSet(BTreeMap::new()))? else { unreachable!() };
+                Ok(Reply::Integer(pairs.into_iter().filter(|(member, score)| members.insert(member.clone(), *score).is_none()).count() as i64))
+            }
+            "ZSCORE" => {
+                arity(2)?;
+                Ok(self.zset(&args[0])?.and_then(|members| members.get(&args[1])).map_or(Reply::Nil, |score| Reply::bulk(format_score(*score))))
+            }
+            "ZSCAN" => {
+                arity(2)?;
+                let pattern = scan_match(args);
+                let members = sorted(self.zset(&args[0])?).into_iter().filter(|(member, _)| glob_match(pattern, member));
+                Ok(Reply::Array(vec![Reply::bulk("0"), scored(members, true)]))
+            }
+            "ZRANGEBYSCORE" | "ZREVRANGEBYSCORE" => {
+                arity(3)?;
+                let rev = name == "ZREVRANGEBYSCORE";
+                let (min, max) = if rev { (bound(&args[2])?, bound(&args[1])?) } else { (bound(&args[1])?, bound(&args[2])?) };
+                let with_scores = args[3..].iter().any(|arg| arg.eq_ignore_ascii_case("WITHSCORES"));
+                let (offset, count) = match args[3..].iter().position(|arg| arg.eq_ignore_ascii_case("LIMIT")) {
+                    Some(i) if args.len() > i + 5 => (integer(&args[i + 4])?, integer(&args[i + 5])?),
+                    Some(_) => return Err("ERR syntax error".to_string()),
+                    None => (0, -1),
+                };
+                let mut members: Vec<(&String, f64)> = sorted(self.zset(&args[0])?).into_iter()
+                    .filter(|(_, score)| min.admits(*score, false) && max.admits(*score, true))
+                    .collect();
+                if rev {
+                    members.reverse();
+                }
+                let count = if count < 0 { usize::MAX } else { count as usize };
+                Ok(scored(members.into_iter().skip(offset.max(0) as usize).take(count), with_scores))
+            }
+            "SETEX" => {
+                arity(3)?;
+                integer(&args[1])?;
+                self.keys.insert(args[0].clone(), Value::String(args[2].clone()));
+                Ok(ok())
+            }
+            "APPEND" => {
+                arity(2)?;
+                let Value::String(value) = self.entry(&args[0], || Value::String(String::new()))? else { unreachable!() };
+                value.push_str(&args[1]);
+                Ok(Reply::Integer(value.len() as i64))
+            }
+            "INCR" | "INCRBY" => {
+                arity(if name == "INCR" { 1 } else { 2 })?;
+                let by = args.get(1).map_or(Ok(1), |by| integer(by))?;
+                let Value::String(value) = self.entry(&args[0], || Value::String("0".to_string()))? else { unreachable!() };
+                let total = integer(value)? + by;
+                *value = total.to_string();
+                Ok(Reply::Integer(total))
+            }
+            "EXPIRE" => {
+                // Keys never expire in the mock; the TTL is only validated
+                arity(2)?;
+                integer(&args[1])?;
+                Ok(Reply::Integer(self.keys.contains_key(&args[0]) as i64))
+            }
+            "HDEL" => {
+                arity(2)?;
+                let removed = match self.keys.get_mut(&args[0]) {
+                    None => 0,
+                    Some(Value::Hash(fields)) => args[1..].iter().filter(|field| fields.remove(*field).is_some()).count(),
+                    Some(_) => return Err(wrong_type()),
+                };
+                self.prune(&args[0]);
+                Ok(Reply::Integer(removed as i64))
+            }
+            "HLEN" => {
+                arity(1)?;
+                Ok(Reply::Integer(self.hash(&args[0])?.map_or(0, BTreeMap::len) as i64))
+            }
+            "HINCRBY" => {
+                arity(3)?;
+                let by = integer(&args[2])?;
+                let Value::Hash(fields) = self.entry(&args[0], || Value::Hash(BTreeMap::new()))? else { unreachable!() };
+                let field = fields.entry(args[1].clone()).or_insert_with(|| "0".to_string());
+                let total = integer(field).map_err(|_| "ERR hash value is not an integer".to_string())? + by;
+                *field = total.to_string();
+                Ok(Reply::Integer(total))
+            }
+            "HSCAN" => {
+                // One page holding the whole hash, with cursor 0
+                arity(2)?;
+                let items = self.hash(&args[0])?.into_iter().flatten().flat_map(|(field, value)| [field.clone(), value.clone()]);
+                Ok(Reply::Array(vec![Reply::bulk("0"), bulks(items)]))
+            }
+            "SREM" => {
+                arity(2)?;
+                let removed = match self.keys.get_mut(&args[0]) {
+                    None => 0,
+                    Some(Value::Set(members)) => args[1..].iter().filter(|member| members.remove(*member)).count(),
+                    Some(_) => return Err(wrong_type()),
+                };
+                self.prune(&args[0]);
+                Ok(Reply::Integer(removed as i64))
+            }
+            "SCARD" => {
+                arity(1)?;
+                Ok(Reply::Integer(self.set(&args[0])?.map_or(0, BTreeSet::len) as i64))
+            }
+            "SSCAN" => {
+                arity(2)?;
+                let pattern = scan_match(args);
+                let members = self.set(&args[0])?.into_iter().flatten().filter(|member| glob_match(pattern, member)).cloned();
+                Ok(Reply::Array(vec![Reply::bulk("0"), bulks(members)]))
+            }
+            "SINTERSTORE" | "SUNIONSTORE" | "SDIFFSTORE" => {
+                arity(2)?;
+                let sets = args[1..].iter()
+                    .map(|key| Ok(self.set(key)?.cloned().unwrap_or_default()))
+                    .collect::<Result<Vec<_>, String>>()?;
+                let mut sets = sets.into_iter();
+                let first = sets.next().unwrap_or_default();
+                let result = sets.fold(first, |acc, set| match name {
+                    "SINTERSTORE" => acc.intersection(&set).cloned().collect(),
+                    "SUNIONSTORE" => acc.union(&set).cloned().collect(),
+                    _ => acc.difference(&set).cloned().collect(),
+                });
+                let len = result.len();
+                self.keys.insert(args[0].clone(), Value::Set(result));
+                self.prune(&args[0]);
+                Ok(Reply::Integer(len as i64))
+            }
+            "LLEN" => {
+                arity(1)?;
+                Ok(Reply::Integer(self.list(&args[0])?.map_or(0, Vec::len) as i64))
+            }
+            "LINDEX" => {
+                arity(2)?;
+                let items = self.list(&args[0])?.map_or(&[][..], Vec::as_slice);
+                Ok(list_index(items.len(), integer(&args[1])?).map_or(Reply::Nil, |i| Reply::bulk(items[i].clone())))
+            }
+            "LSET" => {
+                arity(3)?;
+                let index = integer(&args[1])?;
+                match self.keys.get_mut(&args[0]) {
+                    None => Err("ERR no such key".to_string()),
+                    Some(Value::List(items)) => {
+                        let i = list_index(items.len(), index).ok_or_else(|| "ERR index out of range".to_string())?;
+                        items[i] = args[2].clone();
+                        Ok(ok())
+                    }
+                    Some(_) => Err(wrong_type()),
+                }
+            }
+            "LREM" => {
+                // count > 0 removes from the head, < 0 from the tail, 0 removes all
+                arity(3)?;
+                let count = integer(&args[1])?;
+                let removed = match self.keys.get_mut(&args[0]) {
+                    None => 0,
+                    Some(Value::List(items)) => {
+                        let limit = if count == 0 { usize::MAX } else { count.unsigned_abs() as usize };
+                        let mut matches: Vec<usize> = items.iter().enumerate()
+                            .filter(|(_, item)| **item == args[2])
+                            .map(|(i, _)| i)
+                            .collect();
+                        if count < 0 {
+                            matches.reverse();
+                        }
+                        matches.truncate(limit);
+                        matches.sort_unstable();
+                        matches.iter().rev().for_each(|&i| { items.remove(i); });
+                        matches.len()
+                    }
+                    Some(_) => return Err(wrong_type()),
+                };
+                self.prune(&args[0]);
+                Ok(Reply::Integer(removed as i64))
+            }
+            "LTRIM" => {
+                arity(3)?;
+                let (start, stop) = (integer(&args[1])?, integer(&args[2])?);
+                match self.keys.get_mut(&args[0]) {
+                    None => {}
+                    Some(Value::List(items)) => {
+                        let len = items.len() as i64;
+                        let start = if start < 0 { (len + start).max(0) } else { start };
+                        let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+                        *items = if start > stop { Vec::new() } else { items[start as usize..=stop as usize].to_vec() };
+                    }
+                    Some(_) => return Err(wrong_type()),
+                }
+                self.prune(&args[0]);
+                Ok(ok())
+            }
+            "ZREM" => {
+                arity(2)?;
+                let removed = match self.keys.get_mut(&args[0]) {
+                    None => 0,
+                    Some(Value::ZSet(members)) => args[1..].iter().filter(|member| members.remove(*member).is_some()).count(),
+                    Some(_) => return Err(wrong_type()),
+                };
+                self.prune(&args[0]);
+                Ok(Reply::Integer(removed as i64))
+            }
+            "ZCARD" => {
+                arity(1)?;
+                Ok(Reply::Integer(self.zset(&args[0])?.map_or(0, BTreeMap::len) as i64))
+            }
+            "ZCOUNT" => {
+                arity(3)?;
+                let (min, max) = (bound(&args[1])?, bound(&args[2])?);
+                let count = self.zset(&args[0])?.into_iter().flatten()
+                    .filter(|(_, score)| min.admits(**score, false) && max.admits(**score, true))
+                    .count();
+                Ok(Reply::Integer(count as i64))
+            }
+            "ZINCRBY" => {
+                arity(3)?;
+                let by = score(&args[1])?;
+                let Value::ZSet(members) = self.entry(&args[0], || Value::ZSet(BTreeMap::new()))? else { unreachable!() };
+                let total = members.entry(args[2].clone()).or_insert(0.0);
+                *total += by;
+                Ok(Reply::bulk(format_score(*total)))
+            }
+            "ZREMRANGEBYSCORE" => {
+                arity(3)?;
+                let (min, max) = (bound(&args[1])?, bound(&args[2])?);
+                let removed = match self.keys.get_mut(&args[0]) {
+                    None => 0,
+                    Some(Value::ZSet(members)) => {
+                        let before = members.len();
+                        members.retain(|_, score| !(min.admits(*score, false) && max.admits(*score, true)));
+                        before - members.len()
+                    }
+                    Some(_) => return Err(wrong_type()),
+                };
+                self.prune(&args[0]);
+                Ok(Reply::Integer(removed as i64))
+            }
+            "ZPOPMIN" | "ZPOPMAX" => {
+                arity(1)?;
+                let count = args.get(1).map_or(Ok(1), |count| integer(count))?.max(0) as usize;
+                let mut popped: Vec<(String, f64)> = sorted(self.zset(&args[0])?).into_iter()
+                    .map(|(member, score)| (member.clone(), score))
+                    .collect();
+                if name == "ZPOPMAX" {
+                    popped.reverse();
+                }
+                popped.truncate(count);
+                if let Some(Value::ZSet(members)) = self.keys.get_mut(&args[0]) {
+                    popped.iter().for_each(|(member, _)| { members.remove(member); });
+                }
+                self.prune(&args[0]);
+                Ok(scored(popped.iter().map(|(member, score)| (member, *score)), true))
+            }
+            "ZRANGE" | "ZREVRANGE" => {
+                arity(3)?;
+                let mut members = sorted(self.zset(&args[0])?);
+                if name == "ZREVRANGE" {
+                    members.reverse();
+                }
+                let with_scores = args[3..].iter().any(|arg| arg.eq_ignore_ascii_case("WITHSCORES"));
+                let (start, stop) = (integer(&args[1])?, integer(&args[2])?);
+                let len = members.len() as i64;
+                let start = if start < 0 { (len + start).max(0) } else { start };
+                let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+                if start > stop {
+                    return Ok(Reply::Array(Vec::new()));
+                }
+                Ok(scored(members.into_iter().skip(start as usize).take((stop - start + 1) as usize), with_scores))
+            }
+            #[cfg(feature = "lua-test")]
+            "EVAL" | "EVALSHA" => {
+                arity(2)?;
+                let script = if name == "EVAL" {
+                    self.scripts.insert(super::script_sha1(&args[0]), args[0].clone());
+                    args[0].clone()
+                } else {
+                    self.scripts.get(&args[0].to_lowercase()).cloned()
+                        .ok_or_else(|| "NOSCRIPT No matching script. Please use EVAL.".to_string())?
+                };
+                let numkeys = usize::try_from(integer(&args[1])?)
+                    .ok()
+                    .filter(|n| *n <= args.len() - 2)
+                    .ok_or_else(|| "ERR Number of keys can't be greater than number of args".to_string())?;
+                let (keys, argv) = args[2..].split_at(numkeys);
+                Ok(super::interp::eval(self, &script, keys, argv))
+            }
+            #[cfg(feature = "lua-test")]
+            "SCRIPT" => {
+                arity(1)?;
+                match (args[0].to_uppercase().as_str(), args.get(1)) {
+                    ("LOAD", Some(script)) => {
+                        let sha = super::script_sha1(script);
+                        self.scripts.insert(sha.clone(), script.clone());
+                        Ok(Reply::bulk(sha))
+                    }
+                    ("EXISTS", _) => Ok(Reply::Array(args[1..].iter()
+                        .map(|sha| Reply::Integer(self.scripts.contains_key(&sha.to_lowercase()) as i64))
+                        .collect())),
+                    ("FLUSH", _) => {
+                        self.scripts.clear();
+                        Ok(ok())
+                    }
+                    _ => Err(format!("ERR unknown subcommand '{}'", args[0])),
+                }
+            }
+            _ => Err(format!("ERR unknown command '{}' in mock redis", name)),
+        }
+    }
+
+    /// The key's value, created empty if missing; WRONGTYPE if it holds another type
+    fn entry(&mut self, key: &str, empty: impl Fn() -> Value) -> Result<&mut Value, String> {
+        let value = self.keys.entry(key.to_string()).or_insert_with(&empty);
+        if std::mem::discriminant(value) != std::mem::discriminant(&empty()) {
+            return Err(wrong_type());
+        }
+        Ok(value)
+    }
+
+    /// Drop the key if it holds an emptied collection, as Redis does
+    fn prune(&mut self, key: &str) {
+        let empty = match self.keys.get(key) {
+            Some(Value::Hash(fields)) => fields.is_empty(),
+            Some(Value::List(items)) => items.is_empty(),
+            Some(Value::Set(members)) => members.is_empty(),
+            Some(Value::ZSet(members)) => members.is_empty(),
+            _ => false,
+        };
+        if empty {
+            self.keys.remove(key);
+        }
+    }
+
+    fn hash(&self, key: &str) -> Result<Option<&BTreeMap<String, String>>, String> {
+        match self.keys.get(key) {
+            None => Ok(None),
+            Some(Value::Hash(fields)) => Ok(Some(fields)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn set(&self, key: &str) -> Result<Option<&BTreeSet<String>>, String> {
+        match self.keys.get(key) {
+            None => Ok(None),
+            Some(Value::Set(members)) => Ok(Some(members)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn list(&self, key: &str) -> Result<Option<&Vec<String>>, String> {
+        match self.keys.get(key) {
+            None => Ok(None),
+            Some(Value::List(items)) => Ok(Some(items)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn zset(&self, key: &str) -> Result<Option<&BTreeMap<String, f64>>, String> {
+        match self.keys.get(key) {
+            None => Ok(None),
+            Some(Value::ZSet(members)) => Ok(Some(members)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+}
+
+/// Commands sent through an `Executor` land in the mock keyspace
+impl Connection for MockRedis {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        Ok(self.call(args))
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("mock redis has no pub/sub".to_string()))
+    }
+}
+
+/// The MATCH pattern of a SCAN-family call, every element (`*`) without one
+fn scan_match(args: &[String]) -> &str {
+    args.iter().position(|arg| arg.eq_ignore_ascii_case("MATCH"))
+        .and_then(|i| args.get(i + 1))
+        .map_or("*", String::as_str)
+}
+
+/// A ZRANGEBYSCORE bound: `-inf`, `+inf`, `n`, or exclusive `(n`
+struct Bound {
+    value: f64,
+    exclusive: bool,
+}
+
+impl Bound {
+    fn admits(&self, score: f64, upper: bool) -> bool {
+        match (upper, self.exclusive) {
+            (false, false) => score >= self.value,
+            (false, true) => score > self.value,
+            (true, false) => score <= self.value,
+            (true, true) => score < self.value,
+        }
+    }
+}
+
+fn bound(arg: &str) -> Result<Bound, String> {
+    let (exclusive, value) = match arg.strip_prefix('(') {
+        Some(rest) => (true, rest),
+        None => (false, arg),
+    };
+    let value = score(value).map_err(|_| "ERR min or max is not a float".to_string())?;
+    Ok(Bound { value, exclusive })
+}
+
+fn score(arg: &str) -> Result<f64, String> {
+    match arg.to_lowercase().as_str() {
+        "-inf" => Ok(f64::NEG_INFINITY),
+        "+inf" | "inf" => Ok(f64::INFINITY),
+        other => other.parse().map_err(|_| "ERR value is not a valid float".to_string()),
+    }
+}
+
+/// Scores as Redis prints them: integers without a fractional part
+fn format_score(score: f64) -> String {
+    if score.fract() == 0.0 && score.is_finite() { format!("{}", score as i64) } else { score.to_string() }
+}
+
+/// Members by ascending score, ties broken by member
+fn sorted(members: Option<&BTreeMap<String, f64>>) -> Vec<(&String, f64)> {
+    let mut members: Vec<(&String, f64)> = members.into_iter().flatten().map(|(member, score)| (member, *score)).collect();
+    members.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(b.0)));
+    members
+}
+
+/// Members, each followed by its score if `with_scores`
+fn scored<'a>(members: impl Iterator<Item = (&'a String, f64)>, with_scores: bool) -> Reply {
+    bulks(members.flat_map(|(member, score)| std::iter::once(member.clone()).chain(with_scores.then(|| format_score(score)))))
+}
+
+/// A list index (negative from the tail) as a position, if in range
+fn list_index(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+fn integer(arg: &str) -> Result<i64, String> {
+    arg.parse().map_err(|_| "ERR value is not an integer or out of range".to_string())
+}
+
+fn bulks(items: impl Iterator<Item = String>) -> Reply {
+    Reply::Array(items.map(Reply::bulk).collect())
+}
+
+fn ok() -> Reply {
+    Reply::Status("OK".to_string())
+}
+
+fn wrong_arity(name: &str) -> String {
+    format!("ERR wrong number of arguments for '{}' command", name.to_lowercase())
+}
+
+fn wrong_type() -> String {
+    "WRONGTYPE Operation against a key holding the wrong kind of value".to_string()
+}
@@ -3,6 +3,24 @@
 // Provides Lua script templates for aggregate functions, statistical operations,
 // and other features that require server-side processing in Redis.
 
+mod sha1;
+mod bundle;
+mod mock;
+mod format;
+#[cfg(feature = "lua-test")]
+mod interp;
+
+pub use sha1::script_sha1;
+pub use bundle::ScriptBundle;
+pub use mock::MockRedis;
+pub use format::{format_script, minify_script, LuaFormat};
+
+use sqlparser::ast::BinaryOperator;
+
+use crate::commands::{quote_arg, quote_script};
+use crate::expr::{push_arg, Case, Condition};
+use crate::types::RedisDataType;
+
 /// A Lua-based Redis EVAL command ready for execution
 pub struct EvalCommand {
     pub script: String,
@@ -11,12 +29,12 @@ pub struct EvalCommand {
 }
 
 impl EvalCommand {
-    /// Format as an EVAL command string
+    /// Format as an EVAL command string; keys and args are quoted as needed so a value with
+    /// spaces stays one KEYS/ARGV entry and numkeys still counts the keys
     pub fn to_redis_command(&self) -> String {
-        let script_quoted = self.script.replace('\'', "''");
-        let parts = std::iter::once(format!("EVAL '{}' {}", script_quoted, self.keys.len()))
-            .chain(self.keys.iter().map(|k| k.clone()))
-            .chain(self.args.iter().map(|a| a.clone()));
+        let parts = std::iter::once(format!("EVAL {} {}", quote_script(&self.script), self.keys.len()))
+            .chain(self.keys.iter().map(|k| quote_arg(k)))
+            .chain(self.args.iter().map(|a| quote_arg(a)));
         parts.collect::<Vec<_>>().join(" ")
     }
 }
@@ -225,36 +243,710 @@ end
 return max_val
 "#;
 
+// ============================================================
+// JSON String Scripts
+// ============================================================
+
+/// Read fields of a JSON document stored in a string key, one reply element per
+/// field (like HMGET); missing fields are nil, nested values are re-encoded as JSON
+pub const JSON_FIELDS_SCRIPT: &str = r#"
+local raw = redis.call('GET', KEYS[1])
+if not raw then
+    return nil
+end
+local doc = cjson.decode(raw)
+local values = {}
+for i = 1, #ARGV do
+    local value = doc[ARGV[i]]
+    if value == nil or value == cjson.null then
+        values[i] = false
+    elseif type(value) == 'table' then
+        values[i] = cjson.encode(value)
+    else
+        values[i] = tostring(value)
+    end
+end
+return values
+"#;
+
+// ============================================================
+// Group Scripts
+// ============================================================
+
+/// Top ARGV[2] members (with scores) of every zset named by a member of set KEYS[1],
+/// read with ARGV[1] (ZRANGEBYSCORE from ARGV[3] to ARGV[4], or ZREVRANGEBYSCORE) at
+/// ARGV[5] with the member in place of its trailing `*` (a key prefix goes before it);
+/// one {key, {member, score, ...}} pair per zset, in key order
+pub const ZSET_TOP_PER_GROUP_SCRIPT: &str = r#"
+local keys = redis.call('SMEMBERS', KEYS[1])
+table.sort(keys)
+local prefix = string.sub(ARGV[5], 1, -2)
+local groups = {}
+for i = 1, #keys do
+    local top = redis.call(ARGV[1], prefix .. keys[i], ARGV[3], ARGV[4], 'WITHSCORES', 'LIMIT', 0, ARGV[2])
+    groups[i] = {keys[i], top}
+end
+return groups
+"#;
+
+/// Check whether an EVAL script is the top-K-per-group script, however it was formatted
+pub fn is_zset_top_per_group_script(script: &str) -> bool {
+    minify_script(script) == minify_script(ZSET_TOP_PER_GROUP_SCRIPT)
+}
+
+// ============================================================
+// Join Scripts
+// ============================================================
+
+/// Members a join script walks before giving up, unless `TransformOptions::with_max_join_members`
+/// sets another cap
+pub const DEFAULT_MAX_JOIN_MEMBERS: u64 = 10_000;
+
+/// Walk sorted set KEYS[1] with ARGV[1] (ZRANGE or ZREVRANGE) a page at a time, joining each
+/// member to the hash named by ARGV[5] with the member in place of its trailing `*` (so the
+/// argument is never empty, and a key prefix goes before it); ARGV[4] is 'left' to keep members without a hash.
+/// Skips ARGV[2] joined rows and returns at most ARGV[3] (-1 for all), each a flat
+/// name/value array of the ARGV[7] columns named by the (name, source) pairs that follow:
+/// a source is the sorted set's '@member' or '@score', the hash's '#key' or '#member' (its
+/// joined column), a hash field (read with one HMGET), or '#*' for every field of the hash
+/// as its own name/value pair (read with HGETALL; none for a missing hash). Every other
+/// column is in every row: a missing field, or every hash column of a LEFT JOIN's missing
+/// hash, is padded with false, which replies as nil (SQL NULL). Walking more than ARGV[6] members is an error, bounding
+/// the script's runtime.
+pub const ZSET_HASH_JOIN_SCRIPT: &str = r#"
+local offset, limit, cap, n = tonumber(ARGV[2]), tonumber(ARGV[3]), tonumber(ARGV[6]), tonumber(ARGV[7])
+local fields = {}
+for c = 1, n do
+    local kind = string.sub(ARGV[7 + 2 * c], 1, 1)
+    if kind ~= '@' and kind ~= '#' then
+        fields[#fields + 1] = ARGV[7 + 2 * c]
+    end
+end
+local rows = {}
+local start, walked = 0, 0
+while limit < 0 or #rows < limit do
+    local page = redis.call(ARGV[1], KEYS[1], start, start + 99, 'WITHSCORES')
+    if #page == 0 then
+        break
+    end
+    start = start + 100
+    for i = 1, #page, 2 do
+        if limit >= 0 and #rows >= limit then
+            break
+        end
+        if walked == cap then
+            return redis.error_reply('ERR join walked ' .. cap .. ' members of ' .. KEYS[1] .. ' without finishing; add a LIMIT or raise the member cap')
+        end
+        walked = walked + 1
+        local member, score = page[i], page[i + 1]
+        local key = string.sub(ARGV[5], 1, -2) .. member
+        local exists = redis.call('EXISTS', key) == 1
+        if exists or ARGV[4] == 'left' then
+            if offset > 0 then
+                offset = offset - 1
+            else
+                local values = {}
+                if #fields > 0 and exists then
+                    values = redis.call('HMGET', key, unpack(fields))
+                end
+                local row, f = {}, 0
+                for c = 1, n do
+                    local source, value = ARGV[7 + 2 * c], nil
+                    if source == '#*' then
+                        local all = exists and redis.call('HGETALL', key) or {}
+                        for a = 1, #all do
+                            row[#row + 1] = all[a]
+                        end
+                    else
+                        if source == '@member' then
+                            value = member
+                        elseif source == '@score' then
+                            value = score
+                        elseif source == '#key' then
+                            value = exists and key
+                        elseif source == '#member' then
+                            value = exists and member
+                        else
+                            f = f + 1
+                            value = values[f] or false
+                        end
+                        row[#row + 1] = ARGV[6 + 2 * c]
+                        row[#row + 1] = value
+                    end
+                end
+                rows[#rows + 1] = row
+            end
+        end
+    end
+end
+return rows
+"#;
+
+/// Check whether an EVAL script is the zset-hash join script, however it was formatted
+pub fn is_zset_hash_join_script(script: &str) -> bool {
+    minify_script(script) == minify_script(ZSET_HASH_JOIN_SCRIPT)
+}
+
+// ============================================================
+// Copy Scripts (INSERT ... SELECT between collection types)
+// ============================================================
+
+/// Read set KEYS[2] into `rows` of {member}, sorted so copies into lists are stable
+const COPY_READ_SET: &str = r#"
+local rows = {}
+local members = redis.call('SMEMBERS', KEYS[2])
+table.sort(members)
+for i = 1, #members do
+    rows[i] = {member = members[i]}
+end
+"#;
+
+/// Read list KEYS[2] into `rows` of {value}
+const COPY_READ_LIST: &str = r#"
+local rows = {}
+local values = redis.call('LRANGE', KEYS[2], 0, -1)
+for i = 1, #values do
+    rows[i] = {value = values[i]}
+end
+"#;
+
+/// Read sorted set KEYS[2] into `rows` of {member, score}, lowest score first
+const COPY_READ_ZSET: &str = r#"
+local rows = {}
+local members = redis.call('ZRANGE', KEYS[2], 0, -1, 'WITHSCORES')
+for i = 1, #members, 2 do
+    rows[#rows + 1] = {member = members[i], score = members[i + 1]}
+end
+"#;
+
+/// A row's value for a written column: ARGV names the source column, or gives `=literal`
+const COPY_COLUMN: &str = r#"
+local function column(row, spec)
+    if string.sub(spec, 1, 1) == '=' then
+        return string.sub(spec, 2)
+    end
+    return row[spec]
+end
+"#;
+
+const COPY_WRITE_LIST: &str = r#"
+for i = 1, #rows do
+    redis.call('RPUSH', KEYS[1], column(rows[i], ARGV[1]))
+end
+return #rows
+"#;
+
+const COPY_WRITE_SET: &str = r#"
+for i = 1, #rows do
+    redis.call('SADD', KEYS[1], column(rows[i], ARGV[1]))
+end
+return #rows
+"#;
+
+const COPY_WRITE_ZSET: &str = r#"
+for i = 1, #rows do
+    redis.call('ZADD', KEYS[1], column(rows[i], ARGV[2]), column(rows[i], ARGV[1]))
+end
+return #rows
+"#;
+
+/// Compose a script copying the elements of KEYS[2] (a `source` list, set, or sorted set)
+/// into KEYS[1] (a `target` one), returning the count copied
+pub fn copy_script(source: RedisDataType, target: RedisDataType) -> Option<String> {
+    let read = match source {
+        RedisDataType::Set => COPY_READ_SET,
+        RedisDataType::List => COPY_READ_LIST,
+        RedisDataType::SortedSet => COPY_READ_ZSET,
+        _ => return None,
+    };
+    let write = match target {
+        RedisDataType::List => COPY_WRITE_LIST,
+        RedisDataType::Set => COPY_WRITE_SET,
+        RedisDataType::SortedSet => COPY_WRITE_ZSET,
+        _ => return None,
+    };
+    Some([read, COPY_COLUMN.trim_start_matches('\n'), write.trim_start_matches('\n')].concat())
+}
+
+// ============================================================
+// Expression Scripts (CASE, COALESCE, string functions over a hash)
+// ============================================================
+
+/// `field` for scripted writes: read a field of hash KEYS[1] as it is now (nil when absent)
+const HGET_FIELD: &str = r#"
+local function field(name)
+    local value = redis.call('HGET', KEYS[1], name)
+    if value == false then
+        return nil
+    end
+    return value
+end
+"#;
+
+/// Helpers for compiled expressions: `compare` compares numerically when both sides are
+/// numbers, as text otherwise, `coalesce` returns its first non-nil argument, and `call`
+/// applies a string function (`trim` strips spaces, `replace` matches plain text, `concat` is `||`),
+/// returning nil if any argument is nil
+const EXPR_HELPERS: &str = r#"
+local function compare(a, op, b)
+    if a == nil or b == nil then
+        return false
+    end
+    local x, y = tonumber(a), tonumber(b)
+    if x == nil or y == nil then
+        x, y = a, b
+    end
+    if op == '=' then return x == y
+    elseif op == '<>' then return x ~= y
+    elseif op == '<' then return x < y
+    elseif op == '<=' then return x <= y
+    elseif op == '>' then return x > y
+    else return x >= y
+    end
+end
+local function like(s, pattern, negated)
+    if s == nil then
+        return false
+    end
+    return (string.find(s, pattern) ~= nil) ~= negated
+end
+local function call(f, ...)
+    local args = {...}
+    for i = 1, select('#', ...) do
+        if args[i] == nil then
+            return nil
+        end
+    end
+    return (f(...))
+end
+local function coalesce(...)
+    local args = {...}
+    for i = 1, select('#', ...) do
+        if args[i] ~= nil then
+            return args[i]
+        end
+    end
+    return nil
+end
+local function trim(s)
+    return (string.gsub(s, '^ *(.-) *$', '%1'))
+end
+local function replace(s, from, to)
+    if from == '' then
+        return s
+    end
+    local parts, start = {}, 1
+    while true do
+        local i, j = string.find(s, from, start, true)
+        if i == nil then
+            break
+        end
+        parts[#parts + 1] = string.sub(s, start, i - 1) .. to
+        start = j + 1
+    end
+    parts[#parts + 1] = string.sub(s, start)
+    return table.concat(parts)
+end
+local function concat(a, b)
+    return a .. b
+end
+local values = {}
+"#;
+
+/// Compose a script setting the first `n` ARGV fields of hash KEYS[1] to the values the
+/// compiled assignments compute (all read before any is written); NULL results delete the field
+fn case_update_script(assignments: &[(String, Case)], args: &mut Vec<String>) -> String {
+    let mut script = [HGET_FIELD, EXPR_HELPERS.trim_start_matches('\n')].concat();
+    for (i, (_, case)) in assignments.iter().enumerate() {
+        script.push_str(&case.to_lua(&format!("values[{}]", i + 1), args));
+    }
+    script.push_str(&format!(
+        "for i = 1, {} do\n    if values[i] == nil then\n        redis.call('HDEL', KEYS[1], ARGV[i])\n    else\n        redis.call('HSET', KEYS[1], ARGV[i], values[i])\n    end\nend\nreturn {}\n",
+        assignments.len(),
+        assignments.len(),
+    ));
+    script
+}
+
+// ============================================================
+// Filter Scripts (Lua fallback for SELECTs with arbitrary predicates)
+// ============================================================
+
+/// Read KEYS[1] into `rows`, one flat column/value array per row: a string's `value`, a
+/// hash's fields, a list's `index` and `value`, a set's `member`, a zset's `member` and `score`
+fn filter_rows_script(data_type: RedisDataType) -> &'static str {
+    match data_type {
+        RedisDataType::String => r#"
+local rows = {}
+local value = redis.call('GET', KEYS[1])
+if value then
+    rows[1] = {'value', value}
+end
+"#,
+        RedisDataType::Hash => r#"
+local rows = {}
+local fields = redis.call('HGETALL', KEYS[1])
+if #fields > 0 then
+    rows[1] = fields
+end
+"#,
+        RedisDataType::List => r#"
+local rows = {}
+for i, value in ipairs(redis.call('LRANGE', KEYS[1], 0, -1)) do
+    rows[i] = {'index', tostring(i - 1), 'value', value}
+end
+"#,
+        RedisDataType::Set => r#"
+local rows = {}
+for i, member in ipairs(redis.call('SMEMBERS', KEYS[1])) do
+    rows[i] = {'member', member}
+end
+"#,
+        RedisDataType::SortedSet => r#"
+local rows = {}
+local read = redis.call('ZRANGE', KEYS[1], 0, -1, 'WITHSCORES')
+for i = 1, #read, 2 do
+    rows[#rows + 1] = {'member', read[i], 'score', read[i + 1]}
+end
+"#,
+    }
+}
+
+/// Compose a script returning the rows of KEYS[1] passing `filter`, each as a flat array of
+/// column/value pairs: every column, or the `projections` (names are the first ARGV, NULLs
+/// left out)
+fn filter_script(data_type: RedisDataType, filter: Option<&Condition>, projections: Option<&[(Case, String)]>, order: &[(String, bool)], limit: Option<u64>, args: &mut Vec<String>) -> String {
+    let mut script = filter_rows_script(data_type).to_string();
+    script.push_str("local row = {}\nlocal function field(name)\n    return row[name]\nend\n");
+    script.push_str(EXPR_HELPERS.trim_start_matches('\n'));
+    script.push_str("local result = {}\n");
+    if !order.is_empty() {
+        script.push_str("local sorted = {}\n");
+    }
+    script.push_str("for _, columns in ipairs(rows) do\n    row = {}\n    for i = 1, #columns, 2 do\n        row[columns[i]] = columns[i + 1]\n    end\n");
+    let condition = filter.map_or("true".to_string(), |filter| filter.to_lua(args));
+    script.push_str(&format!("    if {} then\n", condition));
+    if !order.is_empty() {
+        script.push_str("        sorted[#result + 1] = row\n");
+    }
+    match projections {
+        None => script.push_str("        result[#result + 1] = columns\n"),
+        Some(projections) => {
+            script.push_str("        values = {}\n");
+            for (i, (case, _)) in projections.iter().enumerate() {
+                for line in case.to_lua(&format!("values[{}]", i + 1), args).lines() {
+                    script.push_str(&format!("        {}\n", line));
+                }
+            }
+            script.push_str(&format!(
+                "        local out = {{}}\n        for i = 1, {} do\n            if values[i] ~= nil then\n                out[#out + 1] = ARGV[i]\n                out[#out + 1] = values[i]\n            end\n        end\n        result[#result + 1] = out\n",
+                projections.len(),
+            ));
+        }
+    }
+    script.push_str("    end\nend\n");
+    if order.is_empty() && limit.is_none() {
+        script.push_str("return result\n");
+        return script;
+    }
+    script.push_str("local order = {}\nfor i = 1, #result do\n    order[i] = i\nend\n");
+    if !order.is_empty() {
+        script.push_str(ORDER_HELPER.trim_start_matches('\n'));
+        script.push_str("table.sort(order, function(i, j)\n    local first\n");
+        for (column, descending) in order {
+            let column = push_arg(args, column);
+            script.push_str(&format!(
+                "    first = before(sorted[i][{}], sorted[j][{}])\n    if first ~= nil then\n        return {}\n    end\n",
+                column, column, if *descending { "not first" } else { "first" },
+            ));
+        }
+        script.push_str("    return i < j\nend)\n");
+    }
+    let last = match limit {
+        Some(limit) => format!("math.min(#order, tonumber({}))", push_arg(args, &limit.to_string())),
+        None => "#order".to_string(),
+    };
+    script.push_str(&format!("local window = {{}}\nfor i = 1, {} do\n    window[i] = result[order[i]]\nend\nreturn window\n", last));
+    script
+}
+
+/// Whether ORDER BY value `a` sorts before `b`: NULL (nil) first, numbers numerically,
+/// text bytewise; nil when they tie
+const ORDER_HELPER: &str = r#"
+local function before(a, b)
+    if a == b then
+        return nil
+    elseif a == nil or b == nil then
+        return a == nil
+    end
+    local x, y = tonumber(a), tonumber(b)
+    if x == nil or y == nil then
+        x, y = a, b
+    end
+    if x == y then
+        return nil
+    end
+    return x < y
+end
+"#;
+
+// ============================================================
+// View Scripts
+// ============================================================
+
+/// Materialize members 0 through ARGV[2] of zset KEYS[2], read with ARGV[1]
+/// (ZRANGE or ZREVRANGE), into list KEYS[1], replacing its contents
+pub const VIEW_REFRESH_SCRIPT: &str = r#"
+local members = redis.call(ARGV[1], KEYS[2], 0, ARGV[2])
+redis.call('DEL', KEYS[1])
+if #members > 0 then
+    redis.call('RPUSH', KEYS[1], unpack(members))
+end
+return #members
+"#;
+
+// ============================================================
+// Keyspace Scripts
+// ============================================================
+
+/// Count the keys matching glob ARGV[1], SCANning the whole keyspace in one call
+/// (a key SCAN returns twice is counted twice)
+pub const KEYS_COUNT_SCRIPT: &str = r#"
+local cursor = '0'
+local count = 0
+repeat
+    local page = redis.call('SCAN', cursor, 'MATCH', ARGV[1], 'COUNT', 1000)
+    cursor = page[1]
+    count = count + #page[2]
+until cursor == '0'
+return count
+"#;
+
+/// Check if `script` is `KEYS_COUNT_SCRIPT`, in any `LuaFormat` layout
+pub fn is_keys_count_script(script: &str) -> bool {
+    minify_script(script) == minify_script(KEYS_COUNT_SCRIPT)
+}
+
 // ============================================================
 // Convenience builders
 // ============================================================
 
-/// Build a zset aggregate EVAL command
-pub fn zset_aggregate(script: &str, key: &str, min: &str, max: &str) -> String {
+/// Wrap an aggregate script so it returns nil unless its result passes every HAVING
+/// comparison; the compared numbers are passed as the last ARGV entries, in order
+pub fn having_script(script: &str, having: &[(BinaryOperator, String)]) -> String {
+    if having.is_empty() {
+        return script.to_string();
+    }
+    let conditions: Vec<String> = having.iter().enumerate()
+        .map(|(i, (op, _))| {
+            let operator = match op {
+                BinaryOperator::Gt => ">",
+                BinaryOperator::GtEq => ">=",
+                BinaryOperator::Lt => "<",
+                BinaryOperator::LtEq => "<=",
+                BinaryOperator::NotEq => "~=",
+                _ => "==",
+            };
+            let arg = match having.len() - 1 - i {
+                0 => "ARGV[#ARGV]".to_string(),
+                back => format!("ARGV[#ARGV - {}]", back),
+            };
+            format!("n {} tonumber({})", operator, arg)
+        })
+        .collect();
+    format!(
+        "\nlocal function aggregate(){}end\nlocal result = aggregate()\nlocal n = tonumber(result)\nif n == nil or not ({}) then\n    return nil\nend\nreturn result\n",
+        script,
+        conditions.join(" and "),
+    )
+}
+
+/// The numbers compared with by `having_script`
+fn having_args(having: &[(BinaryOperator, String)]) -> impl Iterator<Item = String> + '_ {
+    having.iter().map(|(_, value)| value.clone())
+}
+
+/// Build a zset aggregate EVAL command, filtered by HAVING comparisons
+pub fn zset_aggregate(script: &str, key: &str, min: &str, max: &str, having: &[(BinaryOperator, String)]) -> String {
     EvalCommand {
-        script: script.to_string(),
+        script: having_script(script, having),
         keys: vec![key.to_string()],
-        args: vec![min.to_string(), max.to_string()],
+        args: [min.to_string(), max.to_string()].into_iter().chain(having_args(having)).collect(),
     }
     .to_redis_command()
 }
 
-/// Build a hash aggregate EVAL command (operates on a single hash key)
-pub fn hash_aggregate(script: &str, key: &str, fields: &[String]) -> String {
+/// Build a hash aggregate EVAL command (operates on a single hash key), filtered by HAVING comparisons
+pub fn hash_aggregate(script: &str, key: &str, fields: &[String], having: &[(BinaryOperator, String)]) -> String {
     EvalCommand {
-        script: script.to_string(),
+        script: having_script(script, having),
         keys: vec![key.to_string()],
-        args: fields.to_vec(),
+        args: fields.iter().cloned().chain(having_args(having)).collect(),
+    }
+    .to_redis_command()
+}
+
+/// Build a list aggregate EVAL command, filtered by HAVING comparisons
+pub fn list_aggregate(script: &str, key: &str, having: &[(BinaryOperator, String)]) -> String {
+    EvalCommand {
+        script: having_script(script, having),
+        keys: vec![key.to_string()],
+        args: having_args(having).collect(),
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command materializing the first `stop + 1` members of zset `source`,
+/// read with `read` (ZRANGE or ZREVRANGE), into list `key`
+pub fn view_refresh(key: &str, source: &str, read: &str, stop: u64) -> String {
+    EvalCommand {
+        script: VIEW_REFRESH_SCRIPT.to_string(),
+        keys: vec![key.to_string(), source.to_string()],
+        args: vec![read.to_string(), stop.to_string()],
     }
     .to_redis_command()
 }
 
-/// Build a list aggregate EVAL command
-pub fn list_aggregate(script: &str, key: &str) -> String {
+/// Build an EVAL command reading the top `limit` members of each zset named in set `groups`,
+/// highest scores first when `descending`
+pub fn zset_top_per_group(groups: &str, descending: bool, limit: u64) -> String {
+    let (read, from, to) = match descending {
+        true => ("ZREVRANGEBYSCORE", "+inf", "-inf"),
+        false => ("ZRANGEBYSCORE", "-inf", "+inf"),
+    };
     EvalCommand {
-        script: script.to_string(),
+        script: ZSET_TOP_PER_GROUP_SCRIPT.to_string(),
+        keys: vec![groups.to_string()],
+        args: vec![read.to_string(), limit.to_string(), from.to_string(), to.to_string(), "*".to_string()],
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command joining the members of sorted set `key` to the hashes at
+/// `key_prefix` + member; `columns` pair each output column with its source (see
+/// `ZSET_HASH_JOIN_SCRIPT`)
+pub fn zset_hash_join(key: &str, key_prefix: &str, left: bool, descending: bool, offset: u64, limit: Option<u64>, columns: &[(String, String)]) -> String {
+    let read = if descending { "ZREVRANGE" } else { "ZRANGE" };
+    let limit = limit.map_or("-1".to_string(), |limit| limit.to_string());
+    let mut args = vec![
+        read.to_string(),
+        offset.to_string(),
+        limit,
+        if left { "left" } else { "inner" }.to_string(),
+        format!("{}*", key_prefix),
+        DEFAULT_MAX_JOIN_MEMBERS.to_string(),
+        columns.len().to_string(),
+    ];
+    args.extend(columns.iter().flat_map(|(name, source)| [name.clone(), source.clone()]));
+    EvalCommand {
+        script: ZSET_HASH_JOIN_SCRIPT.to_string(),
         keys: vec![key.to_string()],
-        args: vec![],
+        args,
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command copying collection `source` into `destination`; `columns` are the
+/// source column (or `=literal`) for each column the target type stores
+pub fn type_copy(source_type: RedisDataType, target_type: RedisDataType, destination: &str, source: &str, columns: &[String]) -> Option<String> {
+    Some(EvalCommand {
+        script: copy_script(source_type, target_type)?,
+        keys: vec![destination.to_string(), source.to_string()],
+        args: columns.to_vec(),
+    }
+    .to_redis_command())
+}
+
+/// Compose a script reading the `columns` of hash KEYS[1] with one HMGET and returning the
+/// projected values as name/value pairs (names are the first ARGV, NULLs left out); a missing
+/// key returns no pairs
+fn computed_select_script(projections: &[(Case, String)], columns: &[String], args: &mut Vec<String>) -> String {
+    let (n, m) = (projections.len(), columns.len());
+    let mut script = String::from("\nif redis.call('EXISTS', KEYS[1]) == 0 then\n    return {}\nend\nlocal row = {}\n");
+    if m > 0 {
+        script.push_str(&format!(
+            "local read = redis.call('HMGET', KEYS[1], unpack(ARGV, {}, {}))\nfor i = 1, {} do\n    if read[i] ~= false then\n        row[ARGV[{} + i]] = read[i]\n    end\nend\n",
+            n + 1, n + m, m, n,
+        ));
+    }
+    script.push_str("local function field(name)\n    return row[name]\nend\n");
+    script.push_str(EXPR_HELPERS.trim_start_matches('\n'));
+    for (i, (case, _)) in projections.iter().enumerate() {
+        script.push_str(&case.to_lua(&format!("values[{}]", i + 1), args));
+    }
+    script.push_str(&format!(
+        "local result = {{}}\nfor i = 1, {} do\n    if values[i] ~= nil then\n        result[#result + 1] = ARGV[i]\n        result[#result + 1] = values[i]\n    end\nend\nreturn result\n",
+        n,
+    ));
+    script
+}
+
+/// Build an EVAL command computing projected expressions (e.g. `COALESCE(nickname, name)`)
+/// over hash `key`; `projections` pair each compiled expression with its output column
+pub fn hash_computed_select(key: &str, projections: &[(Case, String)]) -> String {
+    let mut columns: Vec<String> = Vec::new();
+    for column in projections.iter().flat_map(|(case, _)| case.columns()) {
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    let mut args: Vec<String> = projections.iter().map(|(_, name)| name.clone()).chain(columns.iter().cloned()).collect();
+    let script = computed_select_script(projections, &columns, &mut args);
+    EvalCommand {
+        script,
+        keys: vec![key.to_string()],
+        args,
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command applying `field = CASE ... END`, string function, and plain assignments to hash `key`
+pub fn hash_case_update(key: &str, assignments: &[(String, Case)]) -> String {
+    let mut args: Vec<String> = assignments.iter().map(|(field, _)| field.clone()).collect();
+    let script = case_update_script(assignments, &mut args);
+    EvalCommand {
+        script,
+        keys: vec![key.to_string()],
+        args,
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command reading `key` of `data_type` whole and keeping the rows that pass
+/// `filter`, projected by `projections` (every column when None)
+pub fn filtered_select(data_type: RedisDataType, key: &str, filter: Option<&Condition>, projections: Option<&[(Case, String)]>) -> String {
+    filtered_select_ordered(data_type, key, filter, projections, &[], None)
+}
+
+/// Like `filtered_select`, sorting the kept rows by the `order` columns (descending when
+/// true; NULL first, numbers numerically, text bytewise) and keeping the first `limit`
+pub fn filtered_select_ordered(data_type: RedisDataType, key: &str, filter: Option<&Condition>, projections: Option<&[(Case, String)]>, order: &[(String, bool)], limit: Option<u64>) -> String {
+    let mut args: Vec<String> = projections.unwrap_or_default().iter().map(|(_, name)| name.clone()).collect();
+    let script = filter_script(data_type, filter, projections, order, limit, &mut args);
+    EvalCommand {
+        script,
+        keys: vec![key.to_string()],
+        args,
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command reading fields of a JSON document stored in a string key
+pub fn json_fields(key: &str, fields: &[String]) -> String {
+    EvalCommand {
+        script: JSON_FIELDS_SCRIPT.to_string(),
+        keys: vec![key.to_string()],
+        args: fields.to_vec(),
+    }
+    .to_redis_command()
+}
+
+/// Build an EVAL command counting the keys matching glob `pattern`
+pub fn keys_count(pattern: &str) -> String {
+    EvalCommand {
+        script: KEYS_COUNT_SCRIPT.to_string(),
+        keys: Vec::new(),
+        args: vec![pattern.to_string()],
     }
     .to_redis_command()
 }
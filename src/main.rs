@@ -1,8 +1,39 @@
 use clap::{arg, command, Parser, Subcommand};
-use sql_redis::SqlToRedisTransformer;
+use indicatif::{ProgressBar, ProgressStyle};
+use sql_redis::commands::{split_commands, RedisCommand};
+use sql_redis::diagnostic::render_snippet;
+use sql_redis::codec::{codec_by_name, BinaryEncoding};
+use sql_redis::executor::{self, diff_command, is_browse_command, is_count_command, is_subscribe_command, Connection, ExecutionError, ExecutionMode, Executor, FanOutConnection};
+#[cfg(feature = "emulator")]
+use sql_redis::executor::Emulator;
+#[cfg(feature = "tls")]
+use sql_redis::executor::TlsOptions;
+use sql_redis::reply::{diff_rows, Reply, Row};
+use sql_redis::annotations::{annotate, trailing_comment, Annotated};
+use sql_redis::audit::{AuditOutcome, AuditRecord, AuditSink};
+use sql_redis::checkpoint::Checkpoint;
+use sql_redis::config::{Config, OutputFormat};
+use sql_redis::cluster::SlotReport;
+use sql_redis::analysis::CapacityReport;
+use sql_redis::functions::FunctionLibrary;
+use sql_redis::lua::{LuaFormat, ScriptBundle};
+use sql_redis::matrix::MatrixFormat;
+use sql_redis::migrate::{self, Migrator};
+use sql_redis::seed::SeedTemplate;
+use sql_redis::corpus::check_corpus;
+use sql_redis::recording::{self, RecordedCommand, SessionRecorder};
+use sql_redis::variables::Variables;
+use sql_redis::{Acl, Profile, RedisDataType, RuleKind, SqlRedisError, SqlToRedisTransformer, TransformOptions};
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, Read};
-use std::path::PathBuf;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type RedisExecutor = Executor<Box<dyn Connection>>;
+
+const DEFAULT_URL: &str = "redis://127.0.0.1:6379";
 
 #[derive(Parser)]
 #[command(name = "sqlnosql")]
@@ -22,6 +53,226 @@ struct Cli {
     /// List all supported patterns
     #[arg(long)]
     list_patterns: bool,
+
+    /// Enable the admin profile (server diagnostics such as INFO, CLIENT LIST, SLOWLOG)
+    #[arg(long, global = true)]
+    admin: bool,
+
+    /// Enable the redisjson profile (JSON.GET instead of Lua for JSON tables)
+    #[arg(long, global = true)]
+    redisjson: bool,
+
+    /// Declare a string table whose values are JSON documents (repeatable)
+    #[arg(long = "json-table", global = true, value_name = "TABLE")]
+    json_tables: Vec<String>,
+
+    /// Serialize a table's inserted rows with a codec: plain, json, or msgpack (repeatable)
+    #[arg(long = "codec", global = true, value_name = "TABLE=CODEC")]
+    codecs: Vec<String>,
+
+    /// Spell binary values (msgpack rows) in text output as hex or base64; --output resp
+    /// writes them as raw bytes [default: hex]
+    #[arg(long, global = true, value_name = "ENCODING", value_parser = parse_binary_encoding)]
+    binary_encoding: Option<BinaryEncoding>,
+
+    /// Prefix every generated key, e.g. "tenant42:" (script KEYS and multi-key commands included)
+    /// [default: `key_prefix` in the config file]
+    #[arg(long, global = true, value_name = "PREFIX")]
+    key_prefix: Option<String>,
+
+    /// Prefix one table's keys instead of using --key-prefix (repeatable)
+    #[arg(long = "table-prefix", global = true, value_name = "TABLE=PREFIX")]
+    table_prefixes: Vec<String>,
+
+    /// Accept tables qualified with this schema (app.users__hash), translated as the bare table (repeatable)
+    #[arg(long = "schema", global = true, value_name = "SCHEMA")]
+    schemas: Vec<String>,
+
+    /// Accept tables qualified with this schema, prefixing their keys with "SCHEMA:" (repeatable)
+    #[arg(long = "schema-namespace", global = true, value_name = "SCHEMA")]
+    schema_namespaces: Vec<String>,
+
+    /// Wrap the first N colon-separated segments of every key in a cluster hash tag,
+    /// e.g. 2: user:1001:posts -> {user:1001}:posts, so an entity's keys share a slot
+    #[arg(long, global = true, value_name = "N")]
+    hash_tags: Option<usize>,
+
+    /// Hash-tag one table's keys with its own segment count instead of --hash-tags (repeatable)
+    #[arg(long = "table-hash-tags", global = true, value_name = "TABLE=N")]
+    table_hash_tags: Vec<String>,
+
+    /// Database for tables without --table-db (default 0)
+    #[arg(long, global = true, value_name = "N")]
+    db: Option<u32>,
+
+    /// Keep one table in logical database N: its commands get a SELECT N preamble (repeatable)
+    #[arg(long = "table-db", global = true, value_name = "TABLE=N")]
+    table_dbs: Vec<String>,
+
+    /// Target server version, e.g. 7.2, enabling newer commands (ZRANGESTORE for views on 6.2+)
+    #[arg(long, global = true, value_name = "MAJOR.MINOR")]
+    redis_version: Option<String>,
+
+    /// Cap generated commands at N bytes (name plus arguments): multi-row writes over it are
+    /// split into several commands, anything else over it is an error
+    #[arg(long, global = true, value_name = "N")]
+    max_command_bytes: Option<usize>,
+
+    /// Cap generated commands at N arguments, splitting or rejecting like --max-command-bytes
+    #[arg(long, global = true, value_name = "N")]
+    max_command_args: Option<usize>,
+
+    /// Re-indent the Lua scripts of generated EVAL commands canonically (stable diffs)
+    #[arg(long, global = true, conflicts_with = "minify_lua")]
+    format_lua: bool,
+
+    /// Strip comments and layout from the Lua scripts of generated EVAL commands
+    #[arg(long, global = true)]
+    minify_lua: bool,
+
+    /// Seed the fake-data template functions (uuid(), rand_int(a, b), ...) so generated
+    /// commands and `seed` datasets are reproducible
+    #[arg(long, global = true, value_name = "N")]
+    seed: Option<u64>,
+
+    /// Reject translations that would silently drop SQL clauses
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Filter SELECT predicates no rule honors in a Lua script that reads the key whole
+    #[arg(long, global = true)]
+    lua_fallback: bool,
+
+    /// Don't print translation warnings to stderr
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Disable deprecated rules
+    #[arg(long, global = true)]
+    no_deprecated: bool,
+
+    /// Execute the generated commands against Redis and print the replies
+    #[arg(long, global = true)]
+    execute: bool,
+
+    /// With --execute, also run every write against this server (repeatable), e.g. the new
+    /// cluster during a migration; replies differing from --url's are reported on stderr
+    #[arg(long = "mirror", global = true, value_name = "URL", requires = "execute")]
+    mirrors: Vec<String>,
+
+    /// With --execute, repeat every read against this server and report where its decoded
+    /// rows differ from --url's, e.g. to validate a migrated copy of the data
+    #[arg(long, global = true, value_name = "URL", requires = "execute")]
+    shadow: Option<String>,
+
+    /// Refuse to execute anything but reads (e.g. against a production replica); statements
+    /// translating to writes fail instead of running
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    /// Run commands that remove whole keys (DEL, UNLINK, FLUSHDB, FLUSHALL) without asking
+    #[arg(long, short = 'y', global = true)]
+    yes: bool,
+
+    /// With --execute (or diff/migrate), run against an in-process emulator instead of --url;
+    /// Lua scripts (EVAL) run in an embedded interpreter, but FCALL and JSON.* commands fail
+    #[cfg(feature = "emulator")]
+    #[arg(long, global = true, requires = "execute")]
+    emulate: bool,
+
+    /// Append a JSON line per generated command to FILE: time, SQL hash, rule, command, and
+    /// outcome (executed ok/error, or translated only)
+    #[arg(long, global = true, value_name = "FILE")]
+    audit_log: Option<PathBuf>,
+
+    /// With --execute, write each statement's SQL, executed commands and replies to FILE as
+    /// JSON lines, for `sqlnosql replay FILE` later
+    #[arg(long, global = true, value_name = "FILE", requires = "execute")]
+    record: Option<PathBuf>,
+
+    /// With --execute, give up on a command whose reply takes longer than MS milliseconds;
+    /// the statement is reported as timed out and the batch moves on (an executed --file
+    /// stops there instead, so --resume retries it)
+    #[arg(long, global = true, value_name = "MS", requires = "execute")]
+    timeout: Option<u64>,
+
+    /// Redis URL used in execute and diff modes: redis://host[:port][/db],
+    /// rediss://host[:port][/db] (TLS), redis+unix:///path/to/redis.sock[?db=N], or
+    /// redis-cluster://host1[:port],host2[:port]
+    /// [default: redis://127.0.0.1:6379, or `url` in the config file]
+    #[arg(long, global = true, env = "SQLNOSQL_URL")]
+    url: Option<String>,
+
+    /// Trust the CA certificates in FILE (PEM) for rediss:// URLs instead of Mozilla's roots
+    #[cfg(feature = "tls")]
+    #[arg(long, global = true, value_name = "FILE")]
+    tls_ca: Option<PathBuf>,
+
+    /// Authenticate to rediss:// servers with the client certificate chain in FILE (PEM)
+    #[cfg(feature = "tls")]
+    #[arg(long, global = true, value_name = "FILE", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Private key (PEM) of --tls-cert
+    #[cfg(feature = "tls")]
+    #[arg(long, global = true, value_name = "FILE", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// What to print for --query/--file/stdin: text, bundle (as --bundle), functions
+    /// (as --functions), plan (each statement's steps), json (its plan as JSON) or resp
+    /// (its commands in the wire protocol, for redis-cli --pipe)
+    /// [default: text, or `output` in the config file]
+    #[arg(long, env = "SQLNOSQL_OUTPUT", value_parser = parse_output_format)]
+    output: Option<OutputFormat>,
+
+    /// Read defaults (url, output, key_prefix, template_dir) from FILE instead of
+    /// ~/.config/sqlnosql/config.toml; flags and SQLNOSQL_* variables override them
+    #[arg(long, global = true, env = "SQLNOSQL_CONFIG", value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Print a replayable script for --query/--file/stdin instead: SCRIPT LOAD for each
+    /// distinct Lua script, then every command with EVAL rewritten to EVALSHA
+    #[arg(long)]
+    bundle: bool,
+
+    /// Like --bundle, but for Redis 7+: FUNCTION LOAD REPLACE of the sql_redis library,
+    /// then every command with EVAL rewritten to FCALL
+    #[arg(long, conflicts_with = "bundle")]
+    functions: bool,
+
+    /// Check the --query/--file/stdin statements against an ACL instead of printing them:
+    /// either rules (e.g. "on ~app:* +@read -@scripting") or a username read with ACL GETUSER from --url
+    #[arg(long, value_name = "ACL", conflicts_with_all = ["bundle", "functions"])]
+    acl_check: Option<String>,
+
+    /// Print a cache-aside recipe for each --query/--file/stdin read instead of the command:
+    /// the read, how a miss shows, and the SET/HSET that populates it with a SECONDS TTL
+    #[arg(long, value_name = "SECONDS", conflicts_with_all = ["bundle", "functions", "acl_check"])]
+    cache_plan: Option<u64>,
+
+    /// Print an optimistic-locking recipe for each --query/--file/stdin UPDATE computed from
+    /// current values instead of the command: WATCH, the reads, and the MULTI/EXEC writes
+    #[arg(long, conflicts_with_all = ["bundle", "functions", "acl_check", "cache_plan"])]
+    update_plan: bool,
+
+    /// With --execute --file, skip the statements recorded in FILE.checkpoint by an earlier run
+    #[arg(long)]
+    resume: bool,
+
+    /// Write the batch statements that fail (parse, rule match, or execution) to FILE, each
+    /// under a `-- error:` comment, so the failing subset can be fixed and re-run with --file
+    #[arg(long, value_name = "FILE")]
+    dead_letter: Option<PathBuf>,
+
+    /// Re-run the --query/--file batch every SECONDS, re-evaluating NOW() each run
+    /// (e.g. retention jobs: DELETE ... WHERE score < NOW() - 86400)
+    #[arg(long, value_name = "SECONDS")]
+    cron: Option<u64>,
+
+    /// Load rule/template plugins from this directory (repeatable)
+    #[cfg(feature = "plugins")]
+    #[arg(long = "plugins", global = true, value_name = "DIR")]
+    plugin_dirs: Vec<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -31,56 +282,443 @@ enum Commands {
         /// SQL query to transform
         query: String,
     },
-    /// List all supported patterns
-    ListPatterns,
+    /// List all supported patterns, grouped by statement kind
+    ListPatterns {
+        /// Only list patterns for this statement kind (select, insert, update, delete, trigger, session)
+        #[arg(long, value_parser = parse_rule_kind)]
+        kind: Option<RuleKind>,
+        /// Only list patterns operating on this data type (string, hash, list, set, zset)
+        #[arg(long = "type", value_name = "TYPE", value_parser = parse_data_type)]
+        data_type: Option<RedisDataType>,
+    },
+    /// Export a compatibility matrix (SQL construct x Redis type) built from rule metadata
+    Patterns {
+        /// Output format: markdown or csv
+        #[arg(long, default_value = "markdown", value_parser = parse_matrix_format)]
+        format: MatrixFormat,
+    },
+    /// Show how a SQL query is translated: the rule, the command, its warnings, and a
+    /// rewrite into a paginated/scoped variant when the command reads a whole key
+    Explain {
+        /// SQL query to explain
+        query: String,
+    },
+    /// Flag statements whose commands are unsafe to re-run after a partial failure
+    Check {
+        /// Input file containing SQL statements (one per line)
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Preview what each write in a file would change on the server (--url), without applying it
+    Diff {
+        /// Input file containing SQL statements (one per line)
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Report how the keys a workload touches spread over cluster slots and nodes,
+    /// flagging hot slots (keys are hash-tagged and prefixed as configured)
+    Slots {
+        /// Input file containing SQL statements (one per line)
+        #[arg(short, long)]
+        file: PathBuf,
+        /// Number of nodes, each owning an equal contiguous range of the 16384 slots
+        #[arg(long, default_value_t = 3)]
+        nodes: u16,
+        /// Flag slots holding at least this percentage of key references
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        hot: f64,
+    },
+    /// Estimate per-table key counts, value sizes, and Redis memory for an INSERT workload
+    Capacity {
+        /// Input file containing SQL statements (one per line)
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    /// Apply or revert versioned migrations (`<version>_<name>.sql` files with
+    /// `-- up` / `-- down` sections) on the server (--url)
+    Migrate {
+        /// Directory holding the migration files
+        #[arg(short, long, default_value = "migrations")]
+        dir: PathBuf,
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// Expand a SQL template `--count` times and transform (or, with --execute, run) the
+    /// statements; `{{ i }}` and the fake-data functions (`{{ uuid() }}`, `{{ now() }}`,
+    /// `{{ rand_int(a, b) }}`, `{{ faker_name() }}`) are filled per row
+    Seed {
+        /// Template file of SQL statements (one per line)
+        #[arg(short, long)]
+        template: PathBuf,
+        /// How many rows to generate; `{{ i }}` runs from 1 to COUNT
+        #[arg(short, long, default_value_t = 1)]
+        count: u64,
+    },
+    /// Re-translate the statements of a --record session and report commands that changed;
+    /// with --execute, also re-run the recorded commands and report replies that differ
+    Replay {
+        /// Session file written by --record
+        file: PathBuf,
+    },
+    /// Check a regression corpus: each statement is followed by `-- expect: <command>` lines,
+    /// and statements translating to other commands are reported with a diff
+    Test {
+        /// Corpus file of SQL statements and their expected commands
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+    /// Apply every pending migration in version order
+    Up,
+    /// Revert the most recently applied migrations
+    Down {
+        /// How many migrations to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// List migrations and whether each is applied
+    Status,
+}
+
+impl Cli {
+    fn url(&self) -> &str {
+        self.url.as_deref().unwrap_or(DEFAULT_URL)
+    }
+
+    /// Connect to `url`, verifying rediss:// servers with the --tls-* certificates
+    fn open(&self, url: &str, timeout: Option<Duration>) -> Result<Box<dyn Connection>, ExecutionError> {
+        #[cfg(feature = "tls")]
+        if url.starts_with("rediss://") {
+            let options = TlsOptions { ca_file: self.tls_ca.clone(), cert_file: self.tls_cert.clone(), key_file: self.tls_key.clone() };
+            return executor::connect_tls(url, timeout, &options);
+        }
+        executor::connect(url, timeout)
+    }
+
+    /// --functions and --bundle win over --output, SQLNOSQL_OUTPUT and the config file
+    fn output_format(&self) -> OutputFormat {
+        match (self.functions, self.bundle) {
+            (true, _) => OutputFormat::Functions,
+            (_, true) => OutputFormat::Bundle,
+            _ => self.output.unwrap_or(OutputFormat::Text),
+        }
+    }
+
+    /// Fill the flags left unset on the command line and in the environment from the
+    /// config file: --config (which must exist), else the default path if it exists
+    fn apply_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let config = match &self.config {
+            Some(path) => Config::load(path)?.ok_or_else(|| format!("Config file not found: {}", path.display()))?,
+            None => match Config::default_path() {
+                Some(path) => Config::load(&path)?.unwrap_or_default(),
+                None => Config::default(),
+            },
+        };
+        self.url = self.url.take().or(config.url);
+        self.output = self.output.or(config.output);
+        self.key_prefix = self.key_prefix.take().or(config.key_prefix);
+        #[cfg(feature = "plugins")]
+        self.plugin_dirs.extend(config.template_dir);
+        // Without plugin loading the directory can't be honored; refuse rather than ignore it
+        #[cfg(not(feature = "plugins"))]
+        if let Some(dir) = config.template_dir {
+            return Err(format!("template_dir = {:?} in the config file needs the `plugins` feature", dir.display().to_string()).into());
+        }
+        Ok(())
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    let transformer = SqlToRedisTransformer::new()?;
+    let mut cli = Cli::parse();
+    cli.apply_config()?;
+    let mut options = TransformOptions::default();
+    if cli.admin {
+        options = options.with_profile(Profile::Admin);
+    }
+    if cli.redisjson {
+        options = options.with_profile(Profile::RedisJson);
+    }
+    for table in &cli.json_tables {
+        options = options.with_json_table(table);
+    }
+    if let Some(prefix) = &cli.key_prefix {
+        options = options.with_key_prefix(prefix);
+    }
+    for spec in &cli.table_prefixes {
+        let (table, prefix) = spec.split_once('=')
+            .ok_or_else(|| format!("--table-prefix expects TABLE=PREFIX, got {:?}", spec))?;
+        options = options.with_table_prefix(table, prefix);
+    }
+    for schema in &cli.schemas {
+        options = options.with_schema(schema);
+    }
+    for schema in &cli.schema_namespaces {
+        options = options.with_schema_namespace(schema);
+    }
+    if let Some(segments) = cli.hash_tags {
+        options = options.with_hash_tags(segments);
+    }
+    for spec in &cli.table_hash_tags {
+        let (table, segments) = spec.split_once('=')
+            .and_then(|(table, segments)| Some((table, segments.parse().ok()?)))
+            .ok_or_else(|| format!("--table-hash-tags expects TABLE=N, got {:?}", spec))?;
+        options = options.with_table_hash_tags(table, segments);
+    }
+    if let Some(db) = cli.db {
+        options = options.with_database(db);
+    }
+    for spec in &cli.table_dbs {
+        let (table, db) = spec.split_once('=')
+            .and_then(|(table, db)| Some((table, db.parse().ok()?)))
+            .ok_or_else(|| format!("--table-db expects TABLE=N, got {:?}", spec))?;
+        options = options.with_table_database(table, db);
+    }
+    if let Some(version) = &cli.redis_version {
+        let (major, minor) = version.split_once('.')
+            .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+            .ok_or_else(|| format!("--redis-version expects MAJOR.MINOR, got {:?}", version))?;
+        options = options.with_redis_version(major, minor);
+    }
+    if let Some(bytes) = cli.max_command_bytes {
+        options = options.with_max_command_bytes(bytes);
+    }
+    if let Some(args) = cli.max_command_args {
+        options = options.with_max_command_args(args);
+    }
+    if cli.format_lua {
+        options = options.with_lua_format(LuaFormat::Canonical);
+    }
+    if cli.minify_lua {
+        options = options.with_lua_format(LuaFormat::Minify);
+    }
+    if let Some(encoding) = cli.binary_encoding {
+        options = options.with_binary_encoding(encoding);
+    }
+    if let Some(seed) = cli.seed {
+        options = options.with_seed(seed);
+    }
+    if cli.strict {
+        options = options.strict();
+    }
+    if cli.lua_fallback {
+        options = options.with_lua_fallback();
+    }
+    if cli.no_deprecated {
+        options = options.without_deprecated();
+    }
+    #[cfg(feature = "plugins")]
+    for dir in &cli.plugin_dirs {
+        options = options.with_plugin_dir(dir);
+    }
+    let transformer = SqlToRedisTransformer::with_options(options)?;
+    for spec in &cli.codecs {
+        let (table, codec) = spec.split_once('=')
+            .and_then(|(table, name)| Some((table, codec_by_name(name)?)))
+            .ok_or_else(|| format!("--codec expects TABLE=plain|json|msgpack, got {:?}", spec))?;
+        transformer.catalog().define_codec(table, codec);
+    }
+    let journal = Journal {
+        audit: cli.audit_log.as_deref().map(AuditSink::jsonl).transpose()?,
+        recorder: cli.record.as_deref().map(SessionRecorder::create).transpose()?,
+    };
+    let mut executor = if cli.execute {
+        Some(connect(&cli)?)
+    } else {
+        None
+    };
 
     // Handle --list-patterns flag
     if cli.list_patterns {
-        print_patterns(&transformer);
+        print_patterns(&transformer, None, None);
         return Ok(());
     }
 
     // Handle subcommands
-    if let Some(command) = cli.command {
+    if let Some(command) = cli.command.take() {
         match command {
             Commands::Transform { query } => {
-                transform_query(&transformer, &query)?;
+                transform_query(&transformer, &query, &[], executor.as_mut(), &journal, cli.quiet)?;
+            }
+            Commands::ListPatterns { kind, data_type } => {
+                print_patterns(&transformer, kind, data_type);
+            }
+            Commands::Patterns { format } => {
+                print!("{}", transformer.compatibility_matrix().render(format));
+            }
+            Commands::Explain { query } => {
+                explain(&transformer, &query)?;
+            }
+            Commands::Check { file } => {
+                check_file(&transformer, &fs::read_to_string(file)?);
+            }
+            Commands::Diff { file } => {
+                let mut executor = match executor {
+                    Some(executor) => executor,
+                    None => connect(&cli)?,
+                };
+                diff_file(&transformer, &mut executor, &fs::read_to_string(file)?);
+            }
+            Commands::Slots { file, nodes, hot } => {
+                slot_report(&transformer, &fs::read_to_string(file)?, nodes, hot);
             }
-            Commands::ListPatterns => {
-                print_patterns(&transformer);
+            Commands::Capacity { file } => {
+                capacity_report(&transformer, &fs::read_to_string(file)?);
+            }
+            Commands::Migrate { dir, action } => {
+                let mut executor = match executor {
+                    Some(executor) => executor,
+                    None => connect(&cli)?,
+                };
+                migrate(&transformer, &mut executor, &dir, action)?;
+            }
+            Commands::Seed { template, count } => {
+                let files = BatchFiles { dead_letter: cli.dead_letter.as_deref(), ..Default::default() };
+                let template = SeedTemplate::parse(&fs::read_to_string(template)?)?;
+                let queries: Vec<String> = template.expand(count, &mut transformer.options().fake_data()).collect();
+                run_batch(&transformer, &queries, executor.as_mut(), &journal, cli.quiet, &files)?;
+            }
+            Commands::Replay { file } => {
+                if !replay(&transformer, executor.as_mut(), &recording::load(&file)?) {
+                    std::process::exit(1);
+                }
+            }
+            Commands::Test { file } => {
+                let report = check_corpus(&transformer, &fs::read_to_string(file)?);
+                for failure in &report.failures {
+                    println!("FAIL {}", failure);
+                }
+                println!("{} passed, {} failed", report.passed, report.failures.len());
+                if !report.is_success() {
+                    std::process::exit(1);
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Handle --cron: repeat the --query/--file batch forever
+    if let Some(interval) = cli.cron {
+        let queries = match (&cli.query, &cli.file) {
+            (Some(query), _) => vec![query.clone()],
+            (None, Some(file_path)) => fs::read_to_string(file_path)?.lines().map(str::to_string).collect(),
+            (None, None) => return Err("--cron requires --query or --file".into()),
+        };
+        let files = BatchFiles { dead_letter: cli.dead_letter.as_deref(), ..Default::default() };
+        loop {
+            run_batch(&transformer, &queries, executor.as_mut(), &journal, cli.quiet, &files)?;
+            thread::sleep(Duration::from_secs(interval));
+        }
+    }
+
+    // Handle --bundle/--functions/--output plan|json: connection-less output
+    let output = cli.output_format();
+    if output == OutputFormat::Plan || output == OutputFormat::Json {
+        // Plans are printed as each line is read, so dumps of any size stream through
+        let input: Box<dyn BufRead> = match (&cli.query, &cli.file) {
+            (Some(query), _) => Box::new(io::Cursor::new(query.clone())),
+            (None, Some(file_path)) => Box::new(BufReader::new(fs::File::open(file_path)?)),
+            (None, None) => Box::new(io::stdin().lock()),
+        };
+        print_plans(&transformer, input, output == OutputFormat::Json)?;
+        return Ok(());
+    }
+    if output != OutputFormat::Text {
+        let input = match (&cli.query, &cli.file) {
+            (Some(query), _) => query.clone(),
+            (None, Some(file_path)) => fs::read_to_string(file_path)?,
+            (None, None) => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
+            }
+        };
+        match output {
+            OutputFormat::Functions => functions_batch(&transformer, &input),
+            OutputFormat::Resp => resp_batch(&transformer, &input)?,
+            _ => bundle_batch(&transformer, &input),
+        }
+        return Ok(());
+    }
+
+    // Handle --acl-check: report commands the ACL user would be refused
+    if let Some(acl) = &cli.acl_check {
+        let acl = load_acl(acl, &cli)?;
+        let input = match (&cli.query, &cli.file) {
+            (Some(query), _) => query.clone(),
+            (None, Some(file_path)) => fs::read_to_string(file_path)?,
+            (None, None) => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
             }
+        };
+        if !acl_check(&transformer, &acl, &input) {
+            std::process::exit(1);
         }
         return Ok(());
     }
 
+    // Handle --cache-plan: print the cache-aside recipe of each read
+    if let Some(ttl) = cli.cache_plan {
+        let input = match (&cli.query, &cli.file) {
+            (Some(query), _) => query.clone(),
+            (None, Some(file_path)) => fs::read_to_string(file_path)?,
+            (None, None) => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
+            }
+        };
+        cache_plans(&transformer, &input, Duration::from_secs(ttl));
+        return Ok(());
+    }
+
+    // Handle --update-plan: print the WATCH/MULTI recipe of each update
+    if cli.update_plan {
+        let input = match (&cli.query, &cli.file) {
+            (Some(query), _) => query.clone(),
+            (None, Some(file_path)) => fs::read_to_string(file_path)?,
+            (None, None) => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
+            }
+        };
+        update_plans(&transformer, &input);
+        return Ok(());
+    }
+
     // Handle --query argument
     if let Some(query) = cli.query {
-        transform_query(&transformer, &query)?;
+        transform_query(&transformer, &query, &[], executor.as_mut(), &journal, cli.quiet)?;
         return Ok(());
     }
 
-    // Handle --file argument
+    if cli.resume && (cli.file.is_none() || !cli.execute) {
+        return Err("--resume requires --execute and --file".into());
+    }
+
+    // Handle --file argument; executed files record progress in a checkpoint sidecar
     if let Some(file_path) = cli.file {
-        let content = fs::read_to_string(file_path)?;
-        for line in content.lines() {
-            let line = line.trim();
-            if !line.is_empty() && !line.starts_with("--") {
-                println!("SQL: {}", line);
-                match transform_query(&transformer, line) {
-                    Ok(_) => (),
-                    Err(e) => eprintln!("Error: {}", e),
-                }
-                println!();
-            }
-        }
+        let content = fs::read_to_string(&file_path)?;
+        let queries: Vec<String> = content.lines().map(str::to_string).collect();
+        let sidecar = Checkpoint::sidecar_path(&file_path);
+        let files = BatchFiles {
+            checkpoint: executor.is_some().then_some(sidecar.as_path()),
+            resume: cli.resume,
+            dead_letter: cli.dead_letter.as_deref(),
+        };
+        run_batch(&transformer, &queries, executor.as_mut(), &journal, cli.quiet, &files)?;
         return Ok(());
     }
 
+    // Typed input is translated (or executed) a line at a time
+    if io::stdin().is_terminal() {
+        return repl(&transformer, executor.as_mut(), &journal, cli.quiet);
+    }
+
     // Check if stdin is available for reading
     let mut buffer = String::new();
     
@@ -88,17 +726,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Ok(bytes_read) = io::stdin().read_to_string(&mut buffer) {
         if bytes_read > 0 {
             // Split the input by lines and process each line as a separate query
-            for line in buffer.lines() {
-                let line = line.trim();
-                if !line.is_empty() && !line.starts_with("--") {
-                    println!("SQL: {}", line);
-                    match transform_query(&transformer, line) {
-                        Ok(_) => (),
-                        Err(e) => eprintln!("Error: {}", e),
-                    }
-                    println!();
-                }
-            }
+            let queries: Vec<String> = buffer.lines().map(str::to_string).collect();
+            let files = BatchFiles { dead_letter: cli.dead_letter.as_deref(), ..Default::default() };
+            run_batch(&transformer, &queries, executor.as_mut(), &journal, cli.quiet, &files)?;
             return Ok(());
         }
     }
@@ -108,91 +738,808 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn print_patterns(transformer: &SqlToRedisTransformer) {
-    println!("Supported SQL to Redis patterns:");
-    
-    // Get detailed pattern information
-    let details = transformer.get_pattern_details();
-    
-    // Group by SQL pattern type by inspecting the sql_pattern field
-    let mut select_patterns = Vec::new();
-    let mut insert_patterns = Vec::new();
-    let mut update_patterns = Vec::new();
-    let mut delete_patterns = Vec::new();
+/// Statement counts and timing for a batch run
+struct BatchStats {
+    statements: usize,
+    failed: usize,
+    /// Generated commands by name (GET, SET, HSET, ...)
+    commands: BTreeMap<String, usize>,
+    started: Instant,
+}
+
+impl BatchStats {
+    fn new() -> Self {
+        Self { statements: 0, failed: 0, commands: BTreeMap::new(), started: Instant::now() }
+    }
+
+    /// Count each command of a statement's translation by its name; a multi-line EVAL
+    /// script is one command
+    fn record(&mut self, output: &str) {
+        for command in RedisCommand::parse_all(output) {
+            *self.commands.entry(command.command.to_uppercase()).or_default() += 1;
+        }
+    }
+
+    fn print_summary(&self) {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { self.statements as f64 / elapsed } else { 0.0 };
+        eprintln!("Processed {} statements ({} failed) in {:.2}s, {:.1} stmts/sec",
+            self.statements, self.failed, elapsed, rate);
+        if !self.commands.is_empty() {
+            let counts: Vec<String> = self.commands.iter()
+                .map(|(name, count)| format!("{} {}", name, count))
+                .collect();
+            eprintln!("Commands: {}", counts.join(", "));
+        }
+    }
+}
+
+/// Files a batch run reads and writes besides its input
+#[derive(Default)]
+struct BatchFiles<'a> {
+    /// Sidecar recording progress after each statement
+    checkpoint: Option<&'a Path>,
+    /// Skip the statements recorded in the checkpoint
+    resume: bool,
+    /// Where the failed statements are written, with their errors
+    dead_letter: Option<&'a Path>,
+}
+
+/// Failed statements of a batch, written as SQL a later run can take as --file: each keeps
+/// its comments, gains `-- error: ...` lines, and ends with a blank line
+#[derive(Default)]
+struct DeadLetter {
+    text: String,
+    count: usize,
+}
+
+impl DeadLetter {
+    fn record(&mut self, statement: &Annotated, sql: &str, error: &dyn std::fmt::Display) {
+        // A trailing comment stays on the statement line rather than being repeated above it
+        let above = statement.notes.len() - usize::from(trailing_comment(sql).is_some());
+        for note in &statement.notes[..above] {
+            self.text.push_str(&format!("-- {}\n", note));
+        }
+        for line in error.to_string().lines() {
+            self.text.push_str(&format!("-- error: {}\n", line));
+        }
+        self.text.push_str(&format!("{}\n\n", sql));
+        self.count += 1;
+    }
+}
+
+/// Transform (and optionally execute) each non-empty, non-comment line, reporting errors per line.
+/// Shows a progress bar with throughput on stderr (hidden when stderr is not a terminal)
+/// and, unless quiet, a summary of commands by type. With a `checkpoint` sidecar, progress
+/// is recorded after each applied statement, and `resume` skips the statements recorded
+/// there; the first statement the server fails to execute then stops the batch. With
+/// a `dead_letter` file, the failed statements are written there (variables expanded).
+/// `SET @name = value` statements define variables expanded in the statements after them.
+fn run_batch(
+    transformer: &SqlToRedisTransformer,
+    queries: &[String],
+    mut executor: Option<&mut RedisExecutor>,
+    journal: &Journal,
+    quiet: bool,
+    files: &BatchFiles,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let BatchFiles { checkpoint, resume, dead_letter } = *files;
+    let annotated = annotate(queries.iter().map(String::as_str));
+    let statements: Vec<&str> = annotated.iter().map(|statement| statement.sql.as_str()).collect();
+    let skip = match checkpoint {
+        Some(sidecar) if resume => match Checkpoint::load(sidecar)? {
+            Some(saved) if saved.matches(&statements) => saved.offset,
+            Some(_) => return Err(format!("{} does not match the input; it changed since the checkpoint", sidecar.display()).into()),
+            None => 0,
+        },
+        _ => 0,
+    };
+    if skip > 0 {
+        eprintln!("Resuming after {} applied statements", skip);
+    }
     
-    for (i, pattern) in details.iter().enumerate() {
-        let entry = format!("  {}. {} (matcher: {})\n     SQL: {}\n     Redis: {}", 
-            i + 1, 
-            pattern.name, 
-            pattern.matcher,
-            pattern.sql_pattern,
-            pattern.redis_pattern);
-        
-        // Categorize by SQL statement type in the sql_pattern description
-        let sql_upper = pattern.sql_pattern.to_uppercase();
-        if sql_upper.starts_with("SELECT") {
-            select_patterns.push(entry);
-        } else if sql_upper.starts_with("INSERT") {
-            insert_patterns.push(entry);
-        } else if sql_upper.starts_with("UPDATE") {
-            update_patterns.push(entry);
-        } else if sql_upper.starts_with("DELETE") {
-            delete_patterns.push(entry);
+    let progress = ProgressBar::new(statements.len() as u64);
+    if let Ok(style) = ProgressStyle::with_template("{bar:40} {pos}/{len} statements ({per_sec}, eta {eta})") {
+        progress.set_style(style);
+    }
+    progress.set_position(skip as u64);
+    let mut stats = BatchStats::new();
+    let mut failures = DeadLetter::default();
+    // Assignments before the resume point still define their variables
+    let mut variables = Variables::new();
+    for line in &statements[..skip] {
+        let _ = variables.assign(line);
+    }
+
+    let mut stopped_at = None;
+    for (i, (line, statement)) in statements.iter().zip(&annotated).enumerate().skip(skip) {
+        let outcome = progress.suspend(|| {
+            match variables.assign(line) {
+                Ok(true) => return StatementOutcome::Applied,
+                Ok(false) => {}
+                Err(e) => {
+                    stats.failed += 1;
+                    eprintln!("Error: {}", e);
+                    failures.record(statement, line, &e);
+                    return StatementOutcome::Failed;
+                }
+            }
+            print!("{}", statement.comment_lines());
+            println!("SQL: {}", line);
+            let sql = variables.expand(line);
+            let outcome = match transform_query(transformer, &sql, &statement.notes, executor.as_deref_mut(), journal, quiet) {
+                Ok(command) => {
+                    stats.record(&command);
+                    StatementOutcome::Applied
+                }
+                Err(e) => {
+                    stats.failed += 1;
+                    eprintln!("Error: {}", e);
+                    failures.record(statement, &sql, &e);
+                    match e.downcast_ref::<ExecutionError>() {
+                        Some(ExecutionError::Declined(_) | ExecutionError::ReadOnly(_)) | None => StatementOutcome::Failed,
+                        Some(_) => StatementOutcome::Interrupted,
+                    }
+                }
+            };
+            println!();
+            outcome
+        });
+        stats.statements += 1;
+        progress.inc(1);
+        match outcome {
+            StatementOutcome::Applied => {
+                if let Some(sidecar) = checkpoint {
+                    Checkpoint::new(i + 1, line).save(sidecar)?;
+                }
+            }
+            StatementOutcome::Interrupted if checkpoint.is_some() => {
+                stopped_at = Some(i + 1);
+                break;
+            }
+            StatementOutcome::Failed | StatementOutcome::Interrupted => {}
+        }
+    }
+
+    progress.finish_and_clear();
+    if !quiet {
+        stats.print_summary();
+    }
+    if let Some(path) = dead_letter {
+        fs::write(path, &failures.text)?;
+        if !quiet {
+            eprintln!("Wrote {} failed statements to {}", failures.count, path.display());
+        }
+    }
+    match stopped_at {
+        Some(number) => Err(format!("Stopped at statement {}: the server failed to execute it", number).into()),
+        None => Ok(()),
+    }
+}
+
+/// How one statement of a batch ended
+enum StatementOutcome {
+    /// Translated, and executed when there is a server; the checkpoint moves past it
+    Applied,
+    /// Not applied (no translation, or refused before it was sent); the batch goes on
+    Failed,
+    /// The server failed to run it; a checkpointed batch stops so a resumed run starts from it
+    Interrupted,
+}
+
+/// Read statements from the terminal one line at a time until EOF (Ctrl-D) or `\q`.
+/// `SET @name = value` defines a session variable that later statements use as `@name`.
+fn repl(
+    transformer: &SqlToRedisTransformer,
+    mut executor: Option<&mut RedisExecutor>,
+    journal: &Journal,
+    quiet: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut variables = Variables::new();
+    loop {
+        eprint!("sql> ");
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            eprintln!();
+            return Ok(());
+        }
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "\\q" | "quit" | "exit" => return Ok(()),
+            _ if line.starts_with("--") => continue,
+            _ => {}
+        }
+        match variables.assign(line) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                continue;
+            }
+        }
+        let sql = variables.expand(line);
+        if let Err(e) = transform_query(transformer, &sql, &[], executor.as_deref_mut(), journal, quiet) {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Print the SCRIPT LOAD preamble and the bundled commands; statements that fail
+/// to translate are reported on stderr and left out
+fn bundle_batch(transformer: &SqlToRedisTransformer, input: &str) {
+    let mut bundle = ScriptBundle::new();
+    // Notes to print before the bundled command at each position
+    let mut notes = Vec::new();
+    for statement in annotate(input.lines()) {
+        match transformer.transform_to_commands(&statement.sql) {
+            Ok(commands) => {
+                notes.push((bundle.commands().len(), statement.comment_lines()));
+                bundle.extend(commands);
+            }
+            Err(e) => eprintln!("line {}: Error [{}]: {}", statement.line, e.code(), e),
+        }
+    }
+    let preamble = bundle.preamble().len();
+    for (i, command) in bundle.into_commands().into_iter().enumerate() {
+        for (_, lines) in notes.iter().filter(|(at, _)| *at + preamble == i) {
+            print!("{}", lines);
+        }
+        println!("{}", command.to_quoted_string());
+    }
+}
+
+/// Print FUNCTION LOAD REPLACE for the library and the commands with EVAL rewritten to
+/// FCALL; statements that fail to translate are reported and skipped
+fn functions_batch(transformer: &SqlToRedisTransformer, input: &str) {
+    let library = FunctionLibrary::new();
+    println!("{}", library.load_command(true).to_quoted_string());
+    for statement in annotate(input.lines()) {
+        match transformer.transform_to_commands(&statement.sql) {
+            Ok(commands) => {
+                print!("{}", statement.comment_lines());
+                for command in commands {
+                    println!("{}", library.to_fcall(command).to_quoted_string());
+                }
+            }
+            Err(e) => eprintln!("line {}: Error [{}]: {}", statement.line, e.code(), e),
+        }
+    }
+}
+
+/// Connect to the server at --url, or with --emulate to a fresh in-process emulator
+/// With --mirror, writes are also run on the mirrors, and with --shadow, reads on the shadow;
+/// with --read-only, writes are refused; unless --yes, commands removing whole keys are
+/// confirmed interactively first
+fn connect(cli: &Cli) -> Result<RedisExecutor, ExecutionError> {
+    let mode = if cli.read_only { ExecutionMode::ReadOnly } else { ExecutionMode::ReadWrite };
+    let timeout = cli.timeout.map(Duration::from_millis);
+    let wrap = |primary: Box<dyn Connection>| -> Result<RedisExecutor, ExecutionError> {
+        let connection = if cli.mirrors.is_empty() {
+            primary
         } else {
-            // Fallback: use matcher naming convention
-            let matcher = pattern.matcher.to_lowercase();
-            if matcher.contains("delete") || matcher.contains("_del") {
-                delete_patterns.push(entry);
-            } else if matcher.contains("update") {
-                update_patterns.push(entry);
-            } else if matcher.contains("_set") || matcher.contains("_push") || matcher.contains("_add") {
-                insert_patterns.push(entry);
-            } else if matcher.contains("_get") || matcher.contains("getall") || matcher.contains("ismember") {
-                select_patterns.push(entry);
-            } else {
-                select_patterns.push(entry);
+            let mut fan_out = FanOutConnection::new(primary)
+                .on_divergence(|divergence| eprintln!("warning[divergence]: {}", divergence));
+            for url in &cli.mirrors {
+                fan_out = fan_out.with_target(url, cli.open(url, timeout)?);
             }
+            Box::new(fan_out)
+        };
+        let mut executor = Executor::new(connection).with_mode(mode).with_script_cache();
+        if let Some(url) = &cli.shadow {
+            executor = executor.with_shadow(cli.open(url, timeout)?);
         }
+        Ok(if cli.yes { executor } else { executor.with_confirmation(confirm_destructive) })
+    };
+    #[cfg(feature = "emulator")]
+    if cli.emulate {
+        return wrap(Box::new(Emulator::new()));
     }
-    
-    // Print each category
-    if !select_patterns.is_empty() {
-        println!("\nSELECT Operations:");
-        for pattern in select_patterns {
-            println!("{}", pattern);
+    #[cfg(feature = "tls")]
+    if cli.tls_ca.is_some() || cli.tls_cert.is_some() {
+        let mut urls = std::iter::once(cli.url()).chain(cli.mirrors.iter().map(String::as_str)).chain(cli.shadow.as_deref());
+        if !urls.any(|url| url.starts_with("rediss://")) {
+            return Err(ExecutionError::Protocol("--tls-ca/--tls-cert/--tls-key need a rediss:// URL".to_string()));
         }
     }
-    
-    if !insert_patterns.is_empty() {
-        println!("\nINSERT Operations:");
-        for pattern in insert_patterns {
-            println!("{}", pattern);
+    wrap(cli.open(cli.url(), timeout)?)
+}
+
+/// Ask on the terminal before running a command that removes `keys`; without a terminal
+/// to ask on (e.g. SQL piped into stdin) the command is declined
+fn confirm_destructive(command: &RedisCommand, keys: &[String]) -> bool {
+    const SHOWN: usize = 20;
+    match keys {
+        [key] if key == "*" => eprintln!("About to run {}, removing every key", command.command),
+        [key] => eprintln!("About to run {}, removing key {}", command.command, key),
+        keys => {
+            eprintln!("About to run {}, removing {} keys:", command.command, keys.len());
+            keys.iter().take(SHOWN).for_each(|key| eprintln!("  {}", key));
+            if keys.len() > SHOWN {
+                eprintln!("  ... and {} more", keys.len() - SHOWN);
+            }
         }
     }
-    
-    if !update_patterns.is_empty() {
-        println!("\nUPDATE Operations:");
-        for pattern in update_patterns {
-            println!("{}", pattern);
+    if !io::stdin().is_terminal() {
+        eprintln!("Not confirmed: stdin is not a terminal (pass --yes to run it anyway)");
+        return false;
+    }
+    eprint!("Continue? [y/N] ");
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).is_ok() && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Parse ACL rules, or fetch a single-word username's rules with ACL GETUSER
+fn load_acl(value: &str, cli: &Cli) -> Result<Acl, Box<dyn std::error::Error>> {
+    match Acl::parse(value) {
+        Err(_) if !value.contains(char::is_whitespace) => {
+            let mut executor = Executor::new(cli.open(cli.url(), None)?);
+            let reply = executor.execute(&RedisCommand::new("ACL", vec!["GETUSER", value]))?;
+            Ok(Acl::from_getuser(&reply)?)
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Report commands the ACL user can't run, by line number; false if any were found
+fn acl_check(transformer: &SqlToRedisTransformer, acl: &Acl, input: &str) -> bool {
+    let mut denied = 0;
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match transformer.validate_against_acl(line, acl) {
+            Ok(violations) => {
+                for violation in &violations {
+                    eprintln!("line {}: warning[acl]: {}", number + 1, violation);
+                }
+                denied += usize::from(!violations.is_empty());
+            }
+            Err(e) => eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e),
         }
     }
+    eprintln!("{} statement(s) not permitted", denied);
+    denied == 0
+}
+
+/// Print the cache-aside recipe of each statement, by line number
+fn cache_plans(transformer: &SqlToRedisTransformer, input: &str, ttl: Duration) {
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match transformer.cache_plan(line, ttl) {
+            Ok(plan) => println!("{}\n", plan),
+            Err(e) => eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e),
+        }
+    }
+}
+
+/// Write the commands of each statement in RESP, binary values as raw bytes; statements
+/// that fail to translate are reported and skipped
+fn resp_batch(transformer: &SqlToRedisTransformer, input: &str) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for statement in annotate(input.lines()) {
+        match transformer.transform_to_resp(&statement.sql) {
+            Ok(resp) => stdout.write_all(&resp)?,
+            Err(e) => eprintln!("line {}: Error [{}]: {}", statement.line, e.code(), e),
+        }
+    }
+    stdout.flush()
+}
+
+/// Print the plan of each statement (as JSON lines with `json`), by line number, reading
+/// the input a line at a time
+fn print_plans(transformer: &SqlToRedisTransformer, input: impl BufRead, json: bool) -> io::Result<()> {
+    let mut read_error = None;
+    let lines = input.lines().map_while(|line| line.map_err(|e| read_error = Some(e)).ok());
+    let mut plans = transformer.transform_stream(lines);
+    while let Some(plan) = plans.next() {
+        match plan {
+            Ok(plan) if json => println!("{}", plan.to_json()),
+            Ok(plan) => println!("{}\n", plan),
+            Err(e) => eprintln!("line {}: Error [{}]: {}", plans.line(), e.code(), e),
+        }
+    }
+    read_error.map_or(Ok(()), Err)
+}
+
+/// Print the optimistic-locking recipe of each statement, by line number
+fn update_plans(transformer: &SqlToRedisTransformer, input: &str) {
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match transformer.update_plan(line) {
+            Ok(plan) => println!("{}\n", plan),
+            Err(e) => eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e),
+        }
+    }
+}
+
+/// Report non-idempotent commands by line number, then a count
+fn explain(transformer: &SqlToRedisTransformer, query: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, warnings) = transformer.transform_with_report(query)
+        .map_err(|e| format!("Transformation failed [{}]: {}", e.code(), e))?;
+    println!("SQL:   {}", query);
+    println!("Rule:  {}", transformer.matched_rule(query)?);
+    println!("Redis: {}", command.replace('\n', "\n       "));
+    for warning in &warnings {
+        println!("{}", warning);
+    }
+    if let Some(suggestion) = transformer.suggest_rewrite(query)? {
+        println!("{}", suggestion);
+    }
+    Ok(())
+}
+
+fn check_file(transformer: &SqlToRedisTransformer, content: &str) {
+    let mut flagged = 0;
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match transformer.check_idempotency(line) {
+            Ok(issues) => {
+                for issue in &issues {
+                    println!("line {}: {}", number + 1, issue);
+                }
+                flagged += usize::from(!issues.is_empty());
+            }
+            Err(e) => eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e),
+        }
+    }
+    println!("{} statement(s) not idempotent", flagged);
+}
+
+/// Print the slot distribution of the keys the statements' commands touch
+fn slot_report(transformer: &SqlToRedisTransformer, content: &str, nodes: u16, hot: f64) {
+    let mut report = SlotReport::default();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match transformer.transform_to_commands(line) {
+            Ok(commands) => commands.iter().for_each(|command| report.record(command)),
+            Err(e) => eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e),
+        }
+    }
+    println!("{}", report.display(nodes, hot));
+}
+
+/// Print the keys and estimated memory the workload's writes leave behind
+fn capacity_report(transformer: &SqlToRedisTransformer, content: &str) {
+    let mut report = CapacityReport::default();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        if let Err(e) = transformer.record_capacity(line, &mut report) {
+            eprintln!("line {}: Error [{}]: {}", number + 1, e.code(), e);
+        }
+    }
+    println!("{}", report);
+}
+
+/// Print the changes each statement's commands would make, reading current state only
+fn diff_file(transformer: &SqlToRedisTransformer, executor: &mut RedisExecutor, content: &str) {
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("--")) {
+        println!("SQL: {}", line);
+        let result = transformer.transform_to_commands(line)
+            .map_err(|e| format!("Transformation failed [{}]: {}", e.code(), e))
+            .and_then(|commands| {
+                commands.iter()
+                    .map(|command| diff_command(executor, command).map_err(|e| e.to_string()))
+                    .collect::<Result<Vec<_>, _>>()
+            });
+        match result {
+            Ok(changes) if changes.iter().all(Vec::is_empty) => println!("  (no changes)"),
+            Ok(changes) => changes.iter().flatten().for_each(|change| println!("  {}", change)),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        println!();
+    }
+}
+
+/// Run a `migrate` action and report each migration it applied, reverted, or found
+fn migrate(
+    transformer: &SqlToRedisTransformer,
+    executor: &mut RedisExecutor,
+    dir: &Path,
+    action: MigrateAction,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let migrations = migrate::load_dir(dir)?;
+    let mut migrator = Migrator::new(transformer, executor);
+    match action {
+        MigrateAction::Up => {
+            let applied = migrator.up(&migrations)?;
+            applied.iter().for_each(|migration| println!("applied  {}", migration.id()));
+            println!("{} migration(s) applied", applied.len());
+        }
+        MigrateAction::Down { steps } => {
+            let reverted = migrator.down(&migrations, steps)?;
+            reverted.iter().for_each(|migration| println!("reverted {}", migration.id()));
+            println!("{} migration(s) reverted", reverted.len());
+        }
+        MigrateAction::Status => {
+            for (migration, applied) in migrator.status(&migrations)? {
+                println!("{} {}", if applied { "applied " } else { "pending " }, migration.id());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Re-translate each recorded statement and, with an executor, re-run each recorded command,
+/// reporting translations and replies that differ from the recording. Returns whether none did.
+fn replay(transformer: &SqlToRedisTransformer, mut executor: Option<&mut RedisExecutor>, records: &[RecordedCommand]) -> bool {
+    let mut changed = 0;
+    let mut differing = 0;
+    for record in records {
+        match transformer.transform(&record.sql) {
+            Ok(output) if split_commands(&output).contains(&record.command.as_str()) => {}
+            Ok(output) => {
+                changed += 1;
+                println!("SQL: {}
+  recorded:   {}
+  translated: {}", record.sql, record.command, output);
+            }
+            Err(e) => {
+                changed += 1;
+                println!("SQL: {}
+  recorded:   {}
+  translation failed [{}]: {}", record.sql, record.command, e.code(), e);
+            }
+        }
+        let Some(executor) = executor.as_deref_mut() else {
+            continue;
+        };
+        let reply = match RedisCommand::parse(&record.command) {
+            Some(command) => execute_command(executor, &command).map_err(|e| e.to_string()),
+            None => Err(format!("Cannot parse recorded command: {}", record.command)),
+        };
+        if reply != record.reply {
+            differing += 1;
+            let show = |reply: &Result<Reply, String>| match reply {
+                Ok(reply) => reply.to_string(),
+                Err(e) => format!("error: {}", e),
+            };
+            println!("{}
+  recorded: {}
+  replied:  {}", record.command, show(&record.reply), show(&reply));
+        }
+    }
+    print!("Replayed {} commands: {} translations changed", records.len(), changed);
+    if executor.is_some() {
+        print!(", {} replies differ", differing);
+    }
+    println!();
+    changed == 0 && differing == 0
+}
+
+fn parse_rule_kind(name: &str) -> Result<RuleKind, String> {
+    RuleKind::from_name(name).ok_or_else(|| format!("unknown statement kind '{}'", name))
+}
+
+fn parse_data_type(name: &str) -> Result<RedisDataType, String> {
+    RedisDataType::from_name(name).ok_or_else(|| format!("unknown data type '{}'", name))
+}
+
+fn parse_binary_encoding(name: &str) -> Result<BinaryEncoding, String> {
+    BinaryEncoding::from_name(name).ok_or_else(|| format!("unknown binary encoding '{}' (expected hex or base64)", name))
+}
+
+fn parse_output_format(name: &str) -> Result<OutputFormat, String> {
+    OutputFormat::from_name(name).ok_or_else(|| format!("unknown output format '{}' (expected text, bundle, functions, plan, json or resp)", name))
+}
+
+fn parse_matrix_format(name: &str) -> Result<MatrixFormat, String> {
+    MatrixFormat::from_name(name).ok_or_else(|| format!("unknown format '{}' (expected markdown or csv)", name))
+}
+
+fn print_patterns(transformer: &SqlToRedisTransformer, kind: Option<RuleKind>, data_type: Option<RedisDataType>) {
+    println!("Supported SQL to Redis patterns:");
     
-    if !delete_patterns.is_empty() {
-        println!("\nDELETE Operations:");
-        for pattern in delete_patterns {
-            println!("{}", pattern);
+    // Keep the patterns matching the filters, numbered by their position among all rules
+    let details: Vec<_> = transformer.get_pattern_details().into_iter()
+        .enumerate()
+        .filter(|(_, pattern)| kind.is_none() || pattern.kind == kind)
+        .filter(|(_, pattern)| data_type.is_none() || pattern.data_type == data_type)
+        .collect();
+    
+    // Group by the kind recorded on each rule; rules without one (plugins) are listed last
+    let groups = RuleKind::ALL.into_iter().map(Some).chain(std::iter::once(None));
+    for group in groups {
+        let patterns: Vec<_> = details.iter().filter(|(_, pattern)| pattern.kind == group).collect();
+        if patterns.is_empty() {
+            continue;
+        }
+        match group {
+            Some(kind) => println!("\n{} Operations:", kind.name().to_uppercase()),
+            None => println!("\nOther Operations:"),
+        }
+        for (i, pattern) in patterns {
+            let data_type = pattern.data_type
+                .map(|t| format!(", type: {}", t.name()))
+                .unwrap_or_default();
+            let profile = pattern.profile
+                .map(|p| format!(", profile: {}", p.name()))
+                .unwrap_or_default();
+            let since = pattern.since.as_ref()
+                .map(|v| format!(", since: {}", v))
+                .unwrap_or_default();
+            let deprecated = match (&pattern.deprecated, &pattern.replacement) {
+                (Some(v), Some(r)) => format!(", DEPRECATED since {} (use {})", v, r),
+                (Some(v), None) => format!(", DEPRECATED since {}", v),
+                _ => String::new(),
+            };
+            println!("  {}. {} (matcher: {}{}{}{}{})\n     SQL: {}\n     Redis: {}", 
+                i + 1, 
+                pattern.name, 
+                pattern.matcher,
+                data_type,
+                profile,
+                since,
+                deprecated,
+                pattern.sql_pattern,
+                pattern.redis_pattern);
         }
     }
 }
 
-fn transform_query(transformer: &SqlToRedisTransformer, query: &str) -> Result<(), Box<dyn std::error::Error>> {
-    match transformer.transform(query) {
-        Ok(command) => {
+fn transform_query(
+    transformer: &SqlToRedisTransformer,
+    query: &str,
+    notes: &[String],
+    executor: Option<&mut RedisExecutor>,
+    journal: &Journal,
+    quiet: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match transformer.transform_with_report(query) {
+        Ok((command, warnings)) => {
+            if !quiet {
+                for warning in &warnings {
+                    eprintln!("{}", warning);
+                    if let Some(span) = warning.span {
+                        eprint!("{}", render_snippet(query, span, ""));
+                    }
+                }
+            }
             println!("Redis: {}", command);
-            Ok(())
+            match executor {
+                Some(executor) => execute_commands(transformer, executor, query, notes, &command, journal)?,
+                None => audit_command(transformer, journal, query, notes, &command, AuditOutcome::Translated)?,
+            }
+            Ok(command)
         }
         Err(e) => {
-            Err(format!("Transformation failed: {}", e).into())
+            for (span, label) in e.labels() {
+                eprint!("{}", render_snippet(query, span, label));
+            }
+            Err(format!("Transformation failed [{}]: {}", e.code(), e).into())
+        }
+    }
+}
+
+fn execute_commands(
+    transformer: &SqlToRedisTransformer,
+    executor: &mut RedisExecutor,
+    query: &str,
+    notes: &[String],
+    output: &str,
+    journal: &Journal,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for line in split_commands(output) {
+        let command = RedisCommand::parse(line)
+            .ok_or_else(|| format!("Cannot parse generated command: {}", line))?;
+        let reply = execute_command(executor, &command);
+        let outcome = match &reply {
+            Ok(_) => AuditOutcome::Succeeded,
+            Err(e) => AuditOutcome::Failed(e.to_string()),
+        };
+        audit_command(transformer, journal, query, notes, line, outcome)?;
+        if let Some(recorder) = &journal.recorder {
+            let recorded = reply.as_ref().map(Reply::clone).map_err(ExecutionError::to_string);
+            recorder.record(&RecordedCommand::new(query, line, recorded))?;
+        }
+        let reply = reply?;
+        
+        if is_subscribe_command(&command) {
+            // Stream pushed messages as rows until the connection closes
+            for message in executor.messages() {
+                let rows = transformer.decode_reply(query, &message)?.unwrap_or_default();
+                rows.iter().for_each(print_row);
+            }
+            return Ok(());
+        }
+        
+        let rows = transformer.decode_reply(query, &reply)?;
+        match &rows {
+            Some(rows) if !rows.is_empty() => rows.iter().for_each(print_row),
+            _ => println!("{}", reply),
+        }
+        if let Some(shadow) = executor.take_shadow_reply() {
+            report_shadow(transformer, query, &reply, rows.as_deref(), shadow)?;
         }
     }
+    Ok(())
+}
+
+/// Execute one command; a `__keys` SCAN plan is followed through to each key's type and
+/// value, and a `__keys` count script is streamed as SCAN pages instead
+fn execute_command(executor: &mut RedisExecutor, command: &RedisCommand) -> Result<Reply, ExecutionError> {
+    if is_browse_command(command) {
+        executor::browse_keys(executor, command)
+    } else if is_count_command(command) {
+        executor::count_keys(executor, command)
+    } else {
+        executor.execute(command)
+    }
+}
+
+/// Report where the --shadow server's reply to a read differs from the primary's: row by
+/// row when the statement's replies decode into rows, else as whole replies
+fn report_shadow(
+    transformer: &SqlToRedisTransformer,
+    query: &str,
+    reply: &Reply,
+    rows: Option<&[Row]>,
+    shadow: Result<Reply, ExecutionError>,
+) -> Result<(), SqlRedisError> {
+    let shadow = match shadow {
+        Ok(shadow) => shadow,
+        Err(e) => {
+            eprintln!("warning[shadow]: the shadow read failed: {}", e);
+            return Ok(());
+        }
+    };
+    match (rows, transformer.decode_reply(query, &shadow)?) {
+        (Some(expected), Some(actual)) => {
+            let diffs = diff_rows(expected, &actual);
+            if !diffs.is_empty() {
+                eprintln!("warning[shadow]: {} differences from the shadow's rows:", diffs.len());
+                diffs.iter().for_each(|diff| eprintln!("  {}", diff));
+            }
+        }
+        _ if *reply != shadow => eprintln!("warning[shadow]: the shadow replied {} instead", shadow),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Where executed (or translated) commands are logged: the --audit-log and --record files
+struct Journal {
+    audit: Option<AuditSink>,
+    recorder: Option<SessionRecorder>,
+}
+
+/// Append an audit record for one generated command, if auditing is enabled
+fn audit_command(
+    transformer: &SqlToRedisTransformer,
+    journal: &Journal,
+    query: &str,
+    notes: &[String],
+    command: &str,
+    outcome: AuditOutcome,
+) -> io::Result<()> {
+    match &journal.audit {
+        Some(audit) => {
+            let rule = transformer.matched_rule(query).unwrap_or("unknown");
+            audit.record(&AuditRecord::new(query, rule, command, outcome).with_annotations(notes))
+        }
+        None => Ok(()),
+    }
+}
+
+fn print_row(row: &Row) {
+    let columns: Vec<String> = row.iter()
+        .map(|(column, value)| format!("{}={}", column, value))
+        .collect();
+    println!("{}", columns.join(" "));
 }
\ No newline at end of file
@@ -0,0 +1,165 @@
+// matrix.rs - Compatibility matrix of SQL constructs by Redis data type
+// Derived from rule metadata (kind, data type, SQL pattern) so exported docs and dashboards
+// follow the registered rules instead of a hand-maintained table
+
+use std::fmt::Write;
+
+use crate::pattern::matchers::common::RedisDataType;
+use crate::rules::RuleKind;
+use crate::PatternInfo;
+
+/// Output format of an exported matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    /// A table with one row per construct and one column per data type
+    Markdown,
+    /// One `construct,kind,type,supported,rules` line per construct and data type
+    Csv,
+}
+
+impl MatrixFormat {
+    /// Lowercase name used by the CLI
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatrixFormat::Markdown => "markdown",
+            MatrixFormat::Csv => "csv",
+        }
+    }
+
+    /// Look a format up by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        [MatrixFormat::Markdown, MatrixFormat::Csv].into_iter().find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// An SQL construct recognized in rule SQL patterns
+#[derive(Debug)]
+pub struct Construct {
+    pub name: &'static str,
+    pub kind: RuleKind,
+    /// Whether an (uppercased) SQL pattern uses the construct
+    uses: fn(&str) -> bool,
+}
+
+impl Construct {
+    /// Whether a pattern of this construct's kind uses it
+    pub fn matches(&self, pattern: &PatternInfo) -> bool {
+        pattern.kind == Some(self.kind) && (self.uses)(&pattern.sql_pattern.to_uppercase())
+    }
+}
+
+/// Constructs listed by the matrix, in row order
+pub const CONSTRUCTS: &[Construct] = &[
+    Construct { name: "SELECT *", kind: RuleKind::Select, uses: |sql| sql.starts_with("SELECT *") },
+    Construct { name: "SELECT columns", kind: RuleKind::Select, uses: |sql| {
+        let projection = select_projection(sql);
+        projection != "*" && !projection.contains('(')
+    } },
+    Construct { name: "SELECT expressions (CASE, COALESCE, ...)", kind: RuleKind::Select, uses: |sql| {
+        let projection = select_projection(sql);
+        projection.contains('(') && !is_aggregate(projection)
+    } },
+    Construct { name: "COUNT(*)", kind: RuleKind::Select, uses: |sql| select_projection(sql).starts_with("COUNT(") },
+    Construct { name: "AVG / SUM / MIN / MAX", kind: RuleKind::Select, uses: |sql| {
+        let projection = select_projection(sql);
+        is_aggregate(projection) && !projection.starts_with("COUNT(")
+    } },
+    Construct { name: "WHERE key IN (...)", kind: RuleKind::Select, uses: |sql| sql.contains("KEY IN (") },
+    Construct { name: "WHERE ... BETWEEN", kind: RuleKind::Select, uses: |sql| sql.contains(" BETWEEN ") },
+    Construct { name: "WHERE ... < / >", kind: RuleKind::Select, uses: |sql| sql.contains(" < ") || sql.contains(" > ") },
+    Construct { name: "ORDER BY", kind: RuleKind::Select, uses: |sql| sql.contains(" ORDER BY ") },
+    Construct { name: "LIMIT", kind: RuleKind::Select, uses: |sql| sql.contains(" LIMIT ") },
+    Construct { name: "INSERT ... VALUES", kind: RuleKind::Insert, uses: |sql| sql.contains(" VALUES ") },
+    Construct { name: "INSERT ... SELECT", kind: RuleKind::Insert, uses: |sql| sql.contains(" SELECT ") },
+    Construct { name: "UPDATE ... SET", kind: RuleKind::Update, uses: |sql| sql.contains(" SET ") },
+    Construct { name: "UPDATE ... SET CASE", kind: RuleKind::Update, uses: |sql| sql.contains(" CASE ") },
+    Construct { name: "DELETE key", kind: RuleKind::Delete, uses: |sql| !sql.contains(" AND ") },
+    Construct { name: "DELETE ... WHERE member/field", kind: RuleKind::Delete, uses: |sql| sql.contains(" AND ") && !sql.contains(" RETURNING ") },
+    Construct { name: "DELETE ... RETURNING", kind: RuleKind::Delete, uses: |sql| sql.contains(" RETURNING ") },
+];
+
+/// The projection of an uppercased SELECT pattern: the text between SELECT and FROM
+fn select_projection(sql: &str) -> &str {
+    let rest = sql.strip_prefix("SELECT ").unwrap_or("");
+    rest.find(" FROM ").map_or(rest, |end| &rest[..end]).trim()
+}
+
+fn is_aggregate(projection: &str) -> bool {
+    ["COUNT(", "AVG(", "SUM(", "MIN(", "MAX(", "STDDEV_POP("].iter().any(|function| projection.starts_with(function))
+}
+
+/// Which rules support each construct on each data type. Type-agnostic rules (key
+/// metadata, admin tables, sessions) aren't tied to a column and are left out.
+#[derive(Debug, Clone, Default)]
+pub struct CompatibilityMatrix {
+    /// Construct -> rules per data type, in `RedisDataType::ALL` order
+    rows: Vec<(&'static Construct, Vec<Vec<String>>)>,
+}
+
+impl CompatibilityMatrix {
+    /// Build the matrix from the patterns of a transformer's rules
+    pub fn from_patterns(patterns: &[PatternInfo]) -> Self {
+        let rows = CONSTRUCTS.iter()
+            .map(|construct| {
+                let cells = RedisDataType::ALL.iter()
+                    .map(|data_type| {
+                        let mut rules: Vec<String> = patterns.iter()
+                            .filter(|pattern| pattern.data_type == Some(*data_type) && construct.matches(pattern))
+                            .map(|pattern| pattern.name.clone())
+                            .collect();
+                        rules.dedup();
+                        rules
+                    })
+                    .collect();
+                (construct, cells)
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Rules translating `construct` (by name) on `data_type`
+    pub fn rules(&self, construct: &str, data_type: RedisDataType) -> &[String] {
+        let column = RedisDataType::ALL.iter().position(|t| *t == data_type).unwrap_or_default();
+        self.rows.iter()
+            .find(|(c, _)| c.name == construct)
+            .map_or(&[], |(_, cells)| cells[column].as_slice())
+    }
+
+    /// Whether some rule translates `construct` (by name) on `data_type`
+    pub fn is_supported(&self, construct: &str, data_type: RedisDataType) -> bool {
+        !self.rules(construct, data_type).is_empty()
+    }
+
+    /// Render the matrix in the given format
+    pub fn render(&self, format: MatrixFormat) -> String {
+        match format {
+            MatrixFormat::Markdown => self.to_markdown(),
+            MatrixFormat::Csv => self.to_csv(),
+        }
+    }
+
+    /// A markdown table: constructs down, data types across, `yes`/`no` cells
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| Construct | Kind |");
+        RedisDataType::ALL.iter().for_each(|t| { let _ = write!(out, " {} |", t.name()); });
+        out.push_str("\n|---|---|");
+        RedisDataType::ALL.iter().for_each(|_| out.push_str("---|"));
+        for (construct, cells) in &self.rows {
+            let _ = write!(out, "\n| {} | {} |", construct.name.replace('|', "\\|"), construct.kind.name());
+            cells.iter().for_each(|rules| { let _ = write!(out, " {} |", if rules.is_empty() { "no" } else { "yes" }); });
+        }
+        out.push('\n');
+        out
+    }
+
+    /// CSV with a header and one line per construct and data type; rules are `;`-separated
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("construct,kind,type,supported,rules\n");
+        for (construct, cells) in &self.rows {
+            for (data_type, rules) in RedisDataType::ALL.iter().zip(cells) {
+                let _ = writeln!(out, "\"{}\",{},{},{},{}", construct.name, construct.kind.name(), data_type.name(), !rules.is_empty(), rules.join(";"));
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,32 @@
+// metrics/mod.rs - Telemetry hooks for embedding services
+// The transformer and executor report each stage to a `Metrics` implementation, if one is attached
+
+use std::time::Duration;
+
+#[cfg(feature = "prometheus")]
+mod prometheus;
+
+#[cfg(feature = "prometheus")]
+pub use prometheus::PrometheusMetrics;
+
+/// Callbacks for each stage of a translation and execution.
+/// Every method has a no-op default, so implementations override only what they track.
+pub trait Metrics: Send + Sync {
+    /// SQL was parsed (`success`) or rejected by the parser, taking `elapsed`
+    fn on_parse(&self, _elapsed: Duration, _success: bool) {}
+
+    /// Rule selection finished: the matched rule's template name, `"fallback"` for the
+    /// built-in command generator, or None when nothing matched (NoMatchingPattern)
+    fn on_rule_match(&self, _rule: Option<&str>, _elapsed: Duration) {}
+
+    /// A template was rendered (`success`) or failed to render
+    fn on_render(&self, _template: &str, _elapsed: Duration, _success: bool) {}
+
+    /// A command ran on the server; `success` is false for error replies and connection
+    /// failures. Pipelined commands each report an even share of their window's time.
+    fn on_execute(&self, _command: &str, _elapsed: Duration, _success: bool) {}
+
+    /// An EVAL ran through an executor's script cache: sent as EVALSHA (`hit`), or with its
+    /// script because it was new or the server answered NOSCRIPT
+    fn on_script_cache(&self, _hit: bool) {}
+}
@@ -0,0 +1,173 @@
+// metrics/prometheus.rs - Prometheus text exposition of transformer and executor metrics
+// Counters and duration summaries kept in memory; `render` produces the scrape body
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::Metrics;
+
+/// Running count and total seconds of an observed duration
+#[derive(Debug, Default, Clone, Copy)]
+struct Summary {
+    count: u64,
+    seconds: f64,
+}
+
+impl Summary {
+    fn observe(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.seconds += elapsed.as_secs_f64();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    /// Parses by result ("ok"/"error")
+    parses: BTreeMap<&'static str, u64>,
+    parse_duration: Summary,
+    /// Matches by rule name
+    rule_matches: BTreeMap<String, u64>,
+    no_match: u64,
+    match_duration: Summary,
+    /// Renders by (template, result)
+    renders: BTreeMap<(String, &'static str), u64>,
+    render_duration: BTreeMap<String, Summary>,
+    /// Executions by (command, result)
+    executions: BTreeMap<(String, &'static str), u64>,
+    execute_duration: BTreeMap<String, Summary>,
+    /// Script cache lookups by result ("hit"/"miss")
+    script_cache: BTreeMap<&'static str, u64>,
+}
+
+/// A `Metrics` implementation exposing rule hit rates, NoMatchingPattern counts, and
+/// stage latencies in the Prometheus text format; serve `render()` from a /metrics endpoint
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    counters: Mutex<Counters>,
+}
+
+fn result(success: bool) -> &'static str {
+    if success { "ok" } else { "error" }
+}
+
+/// Escape a label value: backslash, double quote, and newline
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current values in the Prometheus text exposition format (version 0.0.4)
+    pub fn render(&self) -> String {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP sql_redis_parse_total SQL statements parsed, by result\n");
+        out.push_str("# TYPE sql_redis_parse_total counter\n");
+        for (result, count) in &counters.parses {
+            let _ = writeln!(out, "sql_redis_parse_total{{result=\"{}\"}} {}", result, count);
+        }
+        write_summary(&mut out, "sql_redis_parse_duration_seconds", "Time spent parsing SQL",
+            [("", counters.parse_duration)]);
+
+        out.push_str("# HELP sql_redis_rule_matches_total Statements translated, by matched rule\n");
+        out.push_str("# TYPE sql_redis_rule_matches_total counter\n");
+        for (rule, count) in &counters.rule_matches {
+            let _ = writeln!(out, "sql_redis_rule_matches_total{{rule=\"{}\"}} {}", escape(rule), count);
+        }
+        out.push_str("# HELP sql_redis_no_matching_pattern_total Statements no rule or fallback could translate\n");
+        out.push_str("# TYPE sql_redis_no_matching_pattern_total counter\n");
+        let _ = writeln!(out, "sql_redis_no_matching_pattern_total {}", counters.no_match);
+        write_summary(&mut out, "sql_redis_rule_match_duration_seconds", "Time spent selecting a rule",
+            [("", counters.match_duration)]);
+
+        out.push_str("# HELP sql_redis_render_total Template renders, by template and result\n");
+        out.push_str("# TYPE sql_redis_render_total counter\n");
+        for ((template, result), count) in &counters.renders {
+            let _ = writeln!(out, "sql_redis_render_total{{template=\"{}\",result=\"{}\"}} {}", escape(template), result, count);
+        }
+        let labels: Vec<(String, Summary)> = counters.render_duration.iter()
+            .map(|(template, summary)| (format!("template=\"{}\"", escape(template)), *summary))
+            .collect();
+        write_summary(&mut out, "sql_redis_render_duration_seconds", "Time spent rendering templates",
+            labels.iter().map(|(labels, summary)| (labels.as_str(), *summary)));
+
+        out.push_str("# HELP sql_redis_execute_total Commands executed, by command and result\n");
+        out.push_str("# TYPE sql_redis_execute_total counter\n");
+        for ((command, result), count) in &counters.executions {
+            let _ = writeln!(out, "sql_redis_execute_total{{command=\"{}\",result=\"{}\"}} {}", escape(command), result, count);
+        }
+        let labels: Vec<(String, Summary)> = counters.execute_duration.iter()
+            .map(|(command, summary)| (format!("command=\"{}\"", escape(command)), *summary))
+            .collect();
+        write_summary(&mut out, "sql_redis_execute_duration_seconds", "Time spent executing commands",
+            labels.iter().map(|(labels, summary)| (labels.as_str(), *summary)));
+
+        out.push_str("# HELP sql_redis_script_cache_total EVALs sent through the executor's script cache, by result\n");
+        out.push_str("# TYPE sql_redis_script_cache_total counter\n");
+        for (result, count) in &counters.script_cache {
+            let _ = writeln!(out, "sql_redis_script_cache_total{{result=\"{}\"}} {}", result, count);
+        }
+
+        out
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Counters)) {
+        f(&mut self.counters.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+}
+
+/// Write a summary's `_sum` and `_count` series, one pair per label set
+fn write_summary<'a>(out: &mut String, name: &str, help: &str, series: impl IntoIterator<Item = (&'a str, Summary)>) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} summary", name);
+    for (labels, summary) in series {
+        let labels = if labels.is_empty() { String::new() } else { format!("{{{}}}", labels) };
+        let _ = writeln!(out, "{}_sum{} {}", name, labels, summary.seconds);
+        let _ = writeln!(out, "{}_count{} {}", name, labels, summary.count);
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn on_parse(&self, elapsed: Duration, success: bool) {
+        self.update(|c| {
+            *c.parses.entry(result(success)).or_default() += 1;
+            c.parse_duration.observe(elapsed);
+        });
+    }
+
+    fn on_rule_match(&self, rule: Option<&str>, elapsed: Duration) {
+        self.update(|c| {
+            match rule {
+                Some(rule) => *c.rule_matches.entry(rule.to_string()).or_default() += 1,
+                None => c.no_match += 1,
+            }
+            c.match_duration.observe(elapsed);
+        });
+    }
+
+    fn on_render(&self, template: &str, elapsed: Duration, success: bool) {
+        self.update(|c| {
+            *c.renders.entry((template.to_string(), result(success))).or_default() += 1;
+            c.render_duration.entry(template.to_string()).or_default().observe(elapsed);
+        });
+    }
+
+    fn on_execute(&self, command: &str, elapsed: Duration, success: bool) {
+        let command = command.to_uppercase();
+        self.update(|c| {
+            *c.executions.entry((command.clone(), result(success))).or_default() += 1;
+            c.execute_duration.entry(command).or_default().observe(elapsed);
+        });
+    }
+
+    fn on_script_cache(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.update(|c| *c.script_cache.entry(result).or_default() += 1);
+    }
+}
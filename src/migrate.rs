@@ -0,0 +1,177 @@
+// migrate.rs - Versioned SQL migrations with up/down sections
+// Each migration file holds `-- up` and `-- down` statements; applied versions are
+// tracked in a Redis hash so `up` runs only pending migrations and `down` reverts the latest
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::commands::RedisCommand;
+use crate::error::SqlRedisError;
+use crate::executor::{Connection, ExecutionError, Executor};
+use crate::reply::Reply;
+use crate::SqlToRedisTransformer;
+
+/// Hash tracking applied migrations: version -> Unix time (seconds) it was applied
+pub const MIGRATIONS_KEY: &str = "sql_redis:migrations";
+
+/// Errors raised while loading or running migrations
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    /// A migration file that isn't `<version>_<name>.sql` with `-- up` / `-- down` sections
+    #[error("Invalid migration {0}: {1}")]
+    Invalid(String, String),
+    /// A statement of the migration could not be translated; nothing of it was applied
+    #[error("Migration {0}: {1}")]
+    Transform(String, SqlRedisError),
+    #[error("Migration {0}: {1}")]
+    Execution(String, ExecutionError),
+    /// An applied version has no migration file, so it can't be reverted
+    #[error("Applied migration {0} has no migration file")]
+    Missing(String),
+}
+
+/// One migration: `20240101_create_users.sql` has version `20240101`, name `create_users`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: String,
+    pub name: String,
+    pub up: Vec<String>,
+    pub down: Vec<String>,
+}
+
+impl Migration {
+    /// Parse migration text: statements (one per line) follow a `-- up` or `-- down` marker;
+    /// blank lines and other comments are skipped
+    pub fn parse(version: &str, name: &str, text: &str) -> Result<Self, MigrationError> {
+        let mut migration = Self { version: version.to_string(), name: name.to_string(), up: Vec::new(), down: Vec::new() };
+        let mut section: Option<&mut Vec<String>> = None;
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(comment) = line.strip_prefix("--") {
+                match comment.trim().to_lowercase().as_str() {
+                    "up" => section = Some(&mut migration.up),
+                    "down" => section = Some(&mut migration.down),
+                    _ => {}
+                }
+                continue;
+            }
+            match section.as_mut() {
+                Some(statements) => statements.push(line.to_string()),
+                None => return Err(MigrationError::Invalid(
+                    format!("{}_{}", version, name),
+                    format!("statement before any -- up / -- down marker: {}", line),
+                )),
+            }
+        }
+        Ok(migration)
+    }
+
+    /// Load a migration file named `<version>_<name>.sql`
+    pub fn load(path: &Path) -> Result<Self, MigrationError> {
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let (version, name) = stem.split_once('_')
+            .ok_or_else(|| MigrationError::Invalid(path.display().to_string(), "expected <version>_<name>.sql".to_string()))?;
+        Self::parse(version, name, &fs::read_to_string(path)?)
+    }
+
+    /// `<version>_<name>`, as shown by `migrate status`
+    pub fn id(&self) -> String {
+        format!("{}_{}", self.version, self.name)
+    }
+}
+
+/// Load every `.sql` migration in `dir`, ordered by version
+pub fn load_dir(dir: &Path) -> Result<Vec<Migration>, MigrationError> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "sql"));
+    let mut migrations = paths.iter().map(|path| Migration::load(path)).collect::<Result<Vec<_>, _>>()?;
+    migrations.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(migrations)
+}
+
+/// Runs migrations through a transformer and executor, tracking versions in `MIGRATIONS_KEY`
+pub struct Migrator<'a, C: Connection> {
+    transformer: &'a SqlToRedisTransformer,
+    executor: &'a mut Executor<C>,
+}
+
+impl<'a, C: Connection> Migrator<'a, C> {
+    pub fn new(transformer: &'a SqlToRedisTransformer, executor: &'a mut Executor<C>) -> Self {
+        Self { transformer, executor }
+    }
+
+    /// Applied versions and the Unix time each was applied
+    pub fn applied(&mut self) -> Result<BTreeMap<String, u64>, MigrationError> {
+        let reply = self.run("status", &RedisCommand::new("HGETALL", vec![MIGRATIONS_KEY]))?;
+        let mut applied = BTreeMap::new();
+        if let Reply::Array(items) = reply {
+            for pair in items.chunks(2) {
+                if let [version, at] = pair {
+                    let version = version.as_text().unwrap_or_default();
+                    applied.insert(version, at.as_text().and_then(|at| at.parse().ok()).unwrap_or(0));
+                }
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Each migration with whether it has been applied
+    pub fn status<'m>(&mut self, migrations: &'m [Migration]) -> Result<Vec<(&'m Migration, bool)>, MigrationError> {
+        let applied = self.applied()?;
+        Ok(migrations.iter().map(|migration| (migration, applied.contains_key(&migration.version))).collect())
+    }
+
+    /// Apply pending migrations in version order; returns the ones applied
+    pub fn up<'m>(&mut self, migrations: &'m [Migration]) -> Result<Vec<&'m Migration>, MigrationError> {
+        let applied = self.applied()?;
+        let mut done = Vec::new();
+        for migration in migrations.iter().filter(|migration| !applied.contains_key(&migration.version)) {
+            self.apply(migration, &migration.up)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+            let record = RedisCommand::new("HSET", vec![MIGRATIONS_KEY.to_string(), migration.version.clone(), now.to_string()]);
+            self.run(&migration.id(), &record)?;
+            done.push(migration);
+        }
+        Ok(done)
+    }
+
+    /// Revert the `steps` most recently applied migrations (by version); returns the ones reverted
+    pub fn down<'m>(&mut self, migrations: &'m [Migration], steps: usize) -> Result<Vec<&'m Migration>, MigrationError> {
+        let applied = self.applied()?;
+        let mut done = Vec::new();
+        for version in applied.keys().rev().take(steps) {
+            let migration = migrations.iter()
+                .find(|migration| &migration.version == version)
+                .ok_or_else(|| MigrationError::Missing(version.clone()))?;
+            self.apply(migration, &migration.down)?;
+            self.run(&migration.id(), &RedisCommand::new("HDEL", vec![MIGRATIONS_KEY, version]))?;
+            done.push(migration);
+        }
+        Ok(done)
+    }
+
+    /// Translate every statement first, so a bad statement applies nothing, then execute
+    fn apply(&mut self, migration: &Migration, statements: &[String]) -> Result<(), MigrationError> {
+        let mut commands = Vec::new();
+        for statement in statements {
+            let translated = self.transformer.transform_to_commands(statement)
+                .map_err(|e| MigrationError::Transform(migration.id(), e))?;
+            commands.extend(translated);
+        }
+        for command in &commands {
+            self.run(&migration.id(), command)?;
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, id: &str, command: &RedisCommand) -> Result<Reply, MigrationError> {
+        self.executor.execute(command).map_err(|e| MigrationError::Execution(id.to_string(), e))
+    }
+}
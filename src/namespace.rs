@@ -0,0 +1,94 @@
+// namespace.rs - Keyspace targeting: key prefixes, cluster hash tags, and logical databases
+// Rewrites generated commands so one SQL workload can target per-tenant keyspaces and databases
+
+use crate::commands::RedisCommand;
+use crate::lua;
+
+/// Prefix every key `command` addresses: key arguments, script KEYS (which is how the
+/// crate's Lua scripts receive theirs), SCAN MATCH and key count script patterns, the
+/// key pattern of scripts reading the keys a set names, and keyspace channels. DBSIZE, which would count every prefix's keys, becomes a count of the
+/// prefixed keys.
+pub fn prefix_keys(command: &mut RedisCommand, prefix: &str) {
+    if command.command.eq_ignore_ascii_case("DBSIZE") {
+        *command = RedisCommand::new("EVAL", vec![lua::KEYS_COUNT_SCRIPT, "0", "*"]);
+    }
+    for i in command.key_indices() {
+        command.args[i].insert_str(0, prefix);
+    }
+    match command.command.to_uppercase().as_str() {
+        "SCAN" => {
+            let pattern = command.args.iter().position(|arg| arg.eq_ignore_ascii_case("MATCH")).map(|i| i + 1);
+            if let Some(pattern) = pattern.and_then(|i| command.args.get_mut(i)) {
+                pattern.insert_str(0, prefix);
+            }
+        }
+        "EVAL" if command.args.first().is_some_and(|script| lua::is_keys_count_script(script)) => {
+            if let Some(pattern) = command.args.get_mut(2) {
+                pattern.insert_str(0, prefix);
+            }
+        }
+        // EVAL script 1 key ARGV[1..4] pattern: the zsets or hashes are read at the pattern's `*`
+        "EVAL" if command.args.first().is_some_and(|script| {
+            lua::is_zset_top_per_group_script(script) || lua::is_zset_hash_join_script(script)
+        }) => {
+            if let Some(pattern) = command.args.get_mut(7) {
+                pattern.insert_str(0, prefix);
+            }
+        }
+        // __keyspace@0__:users:* -> __keyspace@0__:tenant42:users:*
+        "SUBSCRIBE" | "PSUBSCRIBE" => {
+            for channel in &mut command.args {
+                if channel.starts_with("__keyspace@") {
+                    if let Some(end) = channel.find("__:") {
+                        channel.insert_str(end + 3, prefix);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Wrap the entity part of every key `command` addresses, its first `segments`
+/// colon-separated parts, in a cluster hash tag: `user:1001:followers` becomes
+/// `{user:1001}:followers`, landing on the same slot as `{user:1001}`. Keys that
+/// already carry a hash tag are left alone.
+pub fn tag_keys(command: &mut RedisCommand, segments: usize) {
+    for i in command.key_indices() {
+        let key = &mut command.args[i];
+        if key.is_empty() || segments == 0 || has_hash_tag(key) {
+            continue;
+        }
+        let end = key.match_indices(':').nth(segments - 1).map_or(key.len(), |(i, _)| i);
+        key.insert(end, '}');
+        key.insert(0, '{');
+    }
+}
+
+/// Check whether Redis Cluster would hash only part of `key`: a `{` followed later by a `}`
+/// with at least one character between them
+fn has_hash_tag(key: &str) -> bool {
+    key.find('{')
+        .and_then(|open| key[open + 1..].find('}'))
+        .is_some_and(|len| len > 0)
+}
+
+/// Run `commands` in logical database `db`: prepend `SELECT db` to commands that address
+/// keys, and point keyspace notification channels (which aren't database-scoped) at `db`
+pub fn target_database(commands: &mut Vec<RedisCommand>, db: u32) {
+    let mut addresses_keys = false;
+    for command in commands.iter_mut() {
+        if command.command.eq_ignore_ascii_case("PSUBSCRIBE") || command.command.eq_ignore_ascii_case("SUBSCRIBE") {
+            for channel in &mut command.args {
+                if let Some(end) = channel.strip_prefix("__keyspace@").and_then(|rest| rest.find("__:")) {
+                    channel.replace_range("__keyspace@".len().."__keyspace@".len() + end, &db.to_string());
+                }
+            }
+        } else {
+            addresses_keys |= !command.key_indices().is_empty();
+        }
+    }
+    if addresses_keys {
+        commands.insert(0, RedisCommand::new("SELECT", vec![db.to_string()]));
+    }
+}
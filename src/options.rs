@@ -0,0 +1,334 @@
+// options.rs - Transformer configuration
+// Controls which optional rule families are active for a transformer instance
+
+use crate::codec::BinaryEncoding;
+use crate::limits::CommandLimits;
+use crate::lua::{LuaFormat, DEFAULT_MAX_JOIN_MEMBERS};
+use crate::templates::FakeData;
+
+/// An opt-in family of rules that is disabled unless explicitly requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// Server diagnostics via system tables (`__server_info`, `__clients`, `__slowlog`)
+    Admin,
+    /// Read fields of JSON string tables with `JSON.GET` (RedisJSON module) instead of Lua
+    RedisJson,
+}
+
+impl Profile {
+    /// Lowercase name used by the CLI and pattern listings
+    pub fn name(&self) -> &'static str {
+        match self {
+            Profile::Admin => "admin",
+            Profile::RedisJson => "redisjson",
+        }
+    }
+}
+
+/// Options controlling how SQL is transformed
+#[derive(Debug, Clone, Default)]
+pub struct TransformOptions {
+    profiles: Vec<Profile>,
+    disable_deprecated: bool,
+    strict: bool,
+    lua_fallback: bool,
+    now: Option<i64>,
+    seed: Option<u64>,
+    json_tables: Vec<String>,
+    key_prefix: Option<String>,
+    table_prefixes: Vec<(String, String)>,
+    schemas: Vec<(String, Option<String>)>,
+    hash_tags: Option<usize>,
+    table_hash_tags: Vec<(String, usize)>,
+    database: u32,
+    table_databases: Vec<(String, u32)>,
+    redis_version: Option<(u32, u32)>,
+    limits: CommandLimits,
+    max_join_members: Option<u64>,
+    lua_format: LuaFormat,
+    binary_encoding: BinaryEncoding,
+    #[cfg(feature = "plugins")]
+    plugin_dirs: Vec<std::path::PathBuf>,
+}
+
+impl TransformOptions {
+    /// Enable an optional rule profile
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        if !self.profiles.contains(&profile) {
+            self.profiles.push(profile);
+        }
+        self
+    }
+
+    /// Check whether a profile is enabled
+    pub fn has_profile(&self, profile: Profile) -> bool {
+        self.profiles.contains(&profile)
+    }
+
+    /// Skip rules marked deprecated (statements fall through to their replacements)
+    pub fn without_deprecated(mut self) -> Self {
+        self.disable_deprecated = true;
+        self
+    }
+
+    /// Check whether deprecated rules are disabled
+    pub fn deprecated_disabled(&self) -> bool {
+        self.disable_deprecated
+    }
+
+    /// Reject statements whose translation would drop a clause (see `SqlRedisError::UnsupportedClause`)
+    /// or whose rule renders commands inconsistent with the SQL (`SqlRedisError::InconsistentTranslation`)
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Check whether strict mode is enabled
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Compile SELECTs with key equality and predicates no rule honors into a Lua script that
+    /// reads the key whole and filters its rows (instead of dropping the predicates)
+    pub fn with_lua_fallback(mut self) -> Self {
+        self.lua_fallback = true;
+        self
+    }
+
+    /// Check whether the Lua fallback is enabled
+    pub fn lua_fallback(&self) -> bool {
+        self.lua_fallback
+    }
+
+    /// Pin the time NOW() folds to (Unix seconds) instead of reading the system clock
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// The Unix time NOW() folds to: the pinned time, or the current system time
+    pub fn now(&self) -> i64 {
+        self.now.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs() as i64)
+        })
+    }
+
+    /// Seed the fake-data template functions (`uuid()`, `rand_int(a, b)`, ...) so
+    /// generated commands are reproducible; pin `with_now` too for a stable `now()`
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The fake-data source for template functions: seeded, or seeded from the clock
+    pub fn fake_data(&self) -> FakeData {
+        let fake = self.seed.map_or_else(FakeData::unseeded, FakeData::new);
+        fake.with_now(self.now())
+    }
+
+    /// Declare a string table whose values are JSON documents, so projected
+    /// columns read fields of the document
+    pub fn with_json_table(mut self, table: &str) -> Self {
+        if !self.is_json_table(table) {
+            self.json_tables.push(table.to_string());
+        }
+        self
+    }
+
+    /// String tables declared to hold JSON documents
+    pub fn json_tables(&self) -> &[String] {
+        &self.json_tables
+    }
+
+    /// Check whether a table was declared to hold JSON documents
+    pub fn is_json_table(&self, table: &str) -> bool {
+        self.json_tables.iter().any(|name| name.eq_ignore_ascii_case(table))
+    }
+
+    /// Prefix every generated key (e.g. "tenant42:"), including script KEYS and every
+    /// key of multi-key commands, so one workload can run against per-tenant keyspaces
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// The prefix applied to generated keys, if any
+    pub fn key_prefix(&self) -> Option<&str> {
+        self.key_prefix.as_deref()
+    }
+
+    /// Prefix the keys of one table's commands, overriding the global key prefix
+    pub fn with_table_prefix(mut self, table: &str, prefix: &str) -> Self {
+        self.table_prefixes.retain(|(name, _)| !name.eq_ignore_ascii_case(table));
+        self.table_prefixes.push((table.to_string(), prefix.to_string()));
+        self
+    }
+
+    /// The prefix for keys of `table`: its own prefix, else the global key prefix
+    pub fn key_prefix_for(&self, table: Option<&str>) -> Option<&str> {
+        table
+            .and_then(|table| self.table_prefixes.iter().find(|(name, _)| name.eq_ignore_ascii_case(table)))
+            .map(|(_, prefix)| prefix.as_str())
+            .or(self.key_prefix())
+    }
+
+    /// Accept tables qualified with `schema` (`app.users__hash`), translating them as the bare table
+    pub fn with_schema(mut self, schema: &str) -> Self {
+        self.schemas.retain(|(name, _)| !name.eq_ignore_ascii_case(schema));
+        self.schemas.push((schema.to_string(), None));
+        self
+    }
+
+    /// Accept tables qualified with `schema`, keeping their keys apart under a `schema:`
+    /// prefix (`db.app` gives `db:app:`), applied inside any key prefix
+    pub fn with_schema_namespace(mut self, schema: &str) -> Self {
+        self.schemas.retain(|(name, _)| !name.eq_ignore_ascii_case(schema));
+        self.schemas.push((schema.to_string(), Some(format!("{}:", schema.replace('.', ":")))));
+        self
+    }
+
+    /// Check whether tables may be qualified with `schema`
+    pub fn has_schema(&self, schema: &str) -> bool {
+        self.schemas.iter().any(|(name, _)| name.eq_ignore_ascii_case(schema))
+    }
+
+    /// The key prefix of a namespaced schema
+    pub fn schema_prefix(&self, schema: &str) -> Option<&str> {
+        self.schemas.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(schema))
+            .and_then(|(_, prefix)| prefix.as_deref())
+    }
+
+    /// Wrap the first `segments` colon-separated parts of every key in a cluster hash tag
+    /// (`user:1001:posts` -> `{user:1001}:posts` for 2), so an entity's keys share a slot
+    /// and multi-key commands and scripts over them work under Redis Cluster
+    pub fn with_hash_tags(mut self, segments: usize) -> Self {
+        self.hash_tags = Some(segments);
+        self
+    }
+
+    /// Hash-tag one table's keys with its own segment count, overriding `with_hash_tags`
+    pub fn with_table_hash_tags(mut self, table: &str, segments: usize) -> Self {
+        self.table_hash_tags.retain(|(name, _)| !name.eq_ignore_ascii_case(table));
+        self.table_hash_tags.push((table.to_string(), segments));
+        self
+    }
+
+    /// How many key segments form the hash tag for `table`'s keys, if any
+    pub fn hash_tags_for(&self, table: Option<&str>) -> Option<usize> {
+        table
+            .and_then(|table| self.table_hash_tags.iter().find(|(name, _)| name.eq_ignore_ascii_case(table)))
+            .map(|(_, segments)| *segments)
+            .or(self.hash_tags)
+            .filter(|&segments| segments > 0)
+    }
+
+    /// Logical database for tables without their own (default 0); only used once a
+    /// table database is declared, so statements switch back from other tables' databases
+    pub fn with_database(mut self, db: u32) -> Self {
+        self.database = db;
+        self
+    }
+
+    /// Keep a table's keys in logical database `db`: its commands get a `SELECT db` preamble
+    pub fn with_table_database(mut self, table: &str, db: u32) -> Self {
+        self.table_databases.retain(|(name, _)| !name.eq_ignore_ascii_case(table));
+        self.table_databases.push((table.to_string(), db));
+        self
+    }
+
+    /// The database a table's commands select: its own, or the default database when
+    /// other tables declare one; None when no table databases are declared
+    pub fn database_for(&self, table: Option<&str>) -> Option<u32> {
+        let table = table?;
+        if self.table_databases.is_empty() {
+            return None;
+        }
+        self.table_databases.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(table))
+            .map_or(Some(self.database), |(_, db)| Some(*db))
+    }
+
+    /// Target server version (major, minor), enabling commands newer servers add
+    /// (e.g. ZRANGESTORE on 6.2+); unset targets the oldest supported server
+    pub fn with_redis_version(mut self, major: u32, minor: u32) -> Self {
+        self.redis_version = Some((major, minor));
+        self
+    }
+
+    /// Check whether the target server is at least `major.minor`
+    pub fn redis_at_least(&self, major: u32, minor: u32) -> bool {
+        self.redis_version.is_some_and(|version| version >= (major, minor))
+    }
+
+    /// Cap each generated command at `bytes` (name plus arguments): oversized multi-row
+    /// writes are split into several commands, other oversized commands are rejected
+    pub fn with_max_command_bytes(mut self, bytes: usize) -> Self {
+        self.limits.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Cap each generated command at `args` arguments, splitting or rejecting like
+    /// `with_max_command_bytes`
+    pub fn with_max_command_args(mut self, args: usize) -> Self {
+        self.limits.max_args = Some(args);
+        self
+    }
+
+    /// The size limits generated commands must fit
+    pub fn command_limits(&self) -> CommandLimits {
+        self.limits
+    }
+
+    /// Cap the sorted set members a join script walks at `members` (default
+    /// `lua::DEFAULT_MAX_JOIN_MEMBERS`); a join that walks past the cap fails rather than
+    /// running unbounded
+    pub fn with_max_join_members(mut self, members: u64) -> Self {
+        self.max_join_members = Some(members);
+        self
+    }
+
+    /// The most sorted set members a join script walks
+    pub fn max_join_members(&self) -> u64 {
+        self.max_join_members.unwrap_or(DEFAULT_MAX_JOIN_MEMBERS)
+    }
+
+    /// Lay out the scripts of generated EVAL commands: canonical indentation for stable
+    /// diffs, or minified to shrink the payload
+    pub fn with_lua_format(mut self, format: LuaFormat) -> Self {
+        self.lua_format = format;
+        self
+    }
+
+    /// How generated scripts are laid out
+    pub fn lua_format(&self) -> LuaFormat {
+        self.lua_format
+    }
+
+    /// Spell binary values (MessagePack rows) in text output as hex (the default) or base64;
+    /// RESP output writes them as raw bytes either way
+    pub fn with_binary_encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = encoding;
+        self
+    }
+
+    /// How binary values are spelled in text output
+    pub fn binary_encoding(&self) -> BinaryEncoding {
+        self.binary_encoding
+    }
+
+    /// Load plugin libraries from a directory at startup
+    #[cfg(feature = "plugins")]
+    pub fn with_plugin_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.plugin_dirs.push(dir.into());
+        self
+    }
+
+    /// Directories plugins are loaded from
+    #[cfg(feature = "plugins")]
+    pub fn plugin_dirs(&self) -> &[std::path::PathBuf] {
+        &self.plugin_dirs
+    }
+}
@@ -1,6 +1,7 @@
 // pattern/extractors/common.rs
 use sqlparser::ast::{BinaryOperator, Expr, Value, ObjectNamePart, TableFactor};
 use std::collections::HashMap;
+use crate::types::{redis_type_of, TypeMapping};
 
 // Condition value enum
 pub enum ConditionValue {
@@ -119,17 +120,7 @@ pub fn extract_complex_conditions(expr: &Expr) -> HashMap<String, ConditionValue
 
 // Helper to determine Redis data type from table name
 pub fn determine_table_type(table: &str) -> &'static str {
-    if table.ends_with("__hash") {
-        "hash"
-    } else if table.ends_with("__list") {
-        "list"
-    } else if table.ends_with("__set") {
-        "set"
-    } else if table.ends_with("__zset") {
-        "zset"
-    } else {
-        "string"
-    }
+    redis_type_of(table, TypeMapping::standard()).name()
 }
 
 // Helper to check if a table is of specific type
@@ -13,7 +13,7 @@ pub struct HashGetAllInfo {
 pub fn extract_hash_getall(stmt: &Statement) -> Option<HashGetAllInfo> {
     matchers::common::hash_getall()
         .match_pattern(stmt)
-        .map(|key| HashGetAllInfo { key })
+        .map(|key| HashGetAllInfo { key: key.to_string() })
         .ok()
 }
 
@@ -27,7 +27,7 @@ pub struct HashGetInfo {
 /// Extract data for a Redis HGET command
 pub fn extract_hash_get(stmt: &Statement) -> Option<HashGetInfo> {
     // Match a single field select
-    let (select, field) = match matchers::common::single_field_select().match_pattern(stmt) {
+    let (select, field) = match matchers::common::single_field_select().match_pattern(&stmt) {
         Ok(result) => result,
         Err(_) => return None,
     };
@@ -38,7 +38,7 @@ pub fn extract_hash_get(stmt: &Statement) -> Option<HashGetInfo> {
     }
     
     let is_hash_table = match &select.from[0].relation {
-        table => matchers::common::hash_table().match_pattern(table).is_ok(),
+        table => matchers::common::hash_table().match_pattern(&table).is_ok(),
     };
     
     if !is_hash_table {
@@ -47,8 +47,8 @@ pub fn extract_hash_get(stmt: &Statement) -> Option<HashGetInfo> {
     
     // Check for a key equals condition
     if let Some(where_clause) = &select.selection {
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(HashGetInfo { key, field }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(HashGetInfo { key: key.to_string(), field: field.to_string() }),
             Err(_) => None,
         }
     } else {
@@ -66,7 +66,7 @@ pub struct HashMultiGetInfo {
 /// Extract data for a Redis HMGET command
 pub fn extract_hash_multi_get(stmt: &Statement) -> Option<HashMultiGetInfo> {
     // Match a multi-field select
-    let (select, fields) = match matchers::common::multi_field_select().match_pattern(stmt) {
+    let (select, fields) = match matchers::common::multi_field_select().match_pattern(&stmt) {
         Ok(result) => result,
         Err(_) => return None,
     };
@@ -77,7 +77,7 @@ pub fn extract_hash_multi_get(stmt: &Statement) -> Option<HashMultiGetInfo> {
     }
     
     let is_hash_table = match &select.from[0].relation {
-        table => matchers::common::hash_table().match_pattern(table).is_ok(),
+        table => matchers::common::hash_table().match_pattern(&table).is_ok(),
     };
     
     if !is_hash_table {
@@ -86,8 +86,8 @@ pub fn extract_hash_multi_get(stmt: &Statement) -> Option<HashMultiGetInfo> {
     
     // Check for a key equals condition
     if let Some(where_clause) = &select.selection {
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(HashMultiGetInfo { key, fields }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(HashMultiGetInfo { key: key.to_string(), fields: fields.into_iter().map(str::to_string).collect() }),
             Err(_) => None,
         }
     } else {
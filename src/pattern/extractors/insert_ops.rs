@@ -8,6 +8,8 @@ pub struct InsertCommandInfo {
     pub table: String,
     pub key: String,
     pub fields: HashMap<String, String>,
+    /// Column names in SQL order
+    pub columns: Vec<String>,
 }
 
 /// Extract insert command information
@@ -46,6 +48,7 @@ pub fn extract_insert_command(stmt: &Statement) -> Option<InsertCommandInfo> {
                     table,
                     key: key.clone(),
                     fields: field_map,
+                    columns: insert.columns.iter().map(|col| col.value.clone()).collect(),
                 });
             }
         }
@@ -1,6 +1,219 @@
 // pattern/extractors/join_ops.rs
-use sqlparser::ast::{JoinConstraint, JoinOperator, ObjectName, ObjectNamePart, SetExpr, Statement, TableFactor};
+use sqlparser::ast::{BinaryOperator, Expr, GroupByExpr, JoinConstraint, JoinOperator, ObjectName, ObjectNamePart, OrderByKind, SelectItem, SelectItemQualifiedWildcardKind, SetExpr, Statement, TableFactor, Value};
 use crate::pattern::join::{JoinInfo, TableInfo, JoinType, JoinCondition};
+use crate::pattern::matchers::common::{is_hash_table_name, is_zset_table_name};
+use crate::pattern::matchers::select::query_has_limit;
+
+/// Information extracted for a sorted set joined to the hashes its members name:
+/// `SELECT l.member, l.score, u.name FROM lb__zset l JOIN users__hash u ON u.key = l.member
+/// WHERE l.key = 'game:1'`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZSetHashJoinInfo {
+    /// The sorted set's key
+    pub key: String,
+    /// Prepended to a member to form its hash's key (`ON u.key = 'user:' || l.member`)
+    pub key_prefix: String,
+    /// LEFT JOIN: keep members whose hash doesn't exist
+    pub left: bool,
+    pub descending: bool,
+    pub offset: u64,
+    pub limit: Option<u64>,
+    /// (output column, source) in projection order; the source is the sorted set's `@member`
+    /// or `@score`, the hash's `#key` or `#member` (the joined column), a hash field, or `#*`
+    /// for every field of the hash (`u.*`). `l.*` expands to the sorted set's two columns.
+    pub columns: Vec<(String, String)>,
+}
+
+/// A column reference: its qualifier (if any) and name, lowercased
+fn column_ref(expr: &Expr) -> Option<(Option<String>, String)> {
+    match expr {
+        Expr::Identifier(ident) => Some((None, ident.value.to_lowercase())),
+        Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+            [table, column] => Some((Some(table.value.to_lowercase()), column.value.to_lowercase())),
+            _ => None,
+        },
+        Expr::Nested(inner) => column_ref(inner),
+        _ => None,
+    }
+}
+
+/// Which side of a zset-hash join a column belongs to: true for the sorted set. Unqualified
+/// `member` and `score` are the sorted set's, other unqualified columns the hash's.
+fn on_zset(qualifier: &Option<String>, column: &str, zset: &TableInfo, hash: &TableInfo) -> Option<bool> {
+    let names = |table: &TableInfo| [Some(table.name.to_lowercase()), table.alias.as_ref().map(|alias| alias.to_lowercase())];
+    match qualifier {
+        Some(qualifier) if names(zset).contains(&Some(qualifier.clone())) => Some(true),
+        Some(qualifier) if names(hash).contains(&Some(qualifier.clone())) => Some(false),
+        Some(_) => None,
+        None if column == "key" => None,
+        None => Some(column == "member" || column == "score"),
+    }
+}
+
+/// Extract a sorted set joined to hashes on `hash_column = member` (or `= 'prefix' || member`),
+/// filtered only by the sorted set's key, optionally ordered by score and limited
+pub fn extract_zset_hash_join(stmt: &Statement) -> Option<ZSetHashJoinInfo> {
+    zset_hash_join(stmt).ok()
+}
+
+/// Why a SELECT with joins can't be translated (None for statements without joins, and for
+/// the sorted set to hash joins `extract_zset_hash_join` accepts)
+pub fn unsupported_join(stmt: &Statement) -> Option<String> {
+    let Statement::Query(query) = stmt else { return None };
+    let SetExpr::Select(select) = &*query.body else { return None };
+    let joined = select.from.len() > 1 || select.from.iter().any(|from| !from.joins.is_empty());
+    joined.then(|| zset_hash_join(stmt).err()).flatten()
+}
+
+/// The join as written, e.g. "LEFT OUTER JOIN"
+fn join_name(operator: &JoinOperator) -> &'static str {
+    match operator {
+        JoinOperator::Join(_) => "JOIN",
+        JoinOperator::Inner(_) => "INNER JOIN",
+        JoinOperator::Left(_) => "LEFT JOIN",
+        JoinOperator::LeftOuter(_) => "LEFT OUTER JOIN",
+        JoinOperator::Right(_) => "RIGHT JOIN",
+        JoinOperator::RightOuter(_) => "RIGHT OUTER JOIN",
+        JoinOperator::FullOuter(_) => "FULL OUTER JOIN",
+        JoinOperator::CrossJoin => "CROSS JOIN",
+        JoinOperator::Semi(_) | JoinOperator::LeftSemi(_) | JoinOperator::RightSemi(_) => "SEMI JOIN",
+        JoinOperator::Anti(_) | JoinOperator::LeftAnti(_) | JoinOperator::RightAnti(_) => "ANTI JOIN",
+        JoinOperator::CrossApply | JoinOperator::OuterApply => "APPLY",
+        JoinOperator::AsOf { .. } => "ASOF JOIN",
+    }
+}
+
+/// `extract_zset_hash_join`, with the reason a statement doesn't qualify
+fn zset_hash_join(stmt: &Statement) -> Result<ZSetHashJoinInfo, String> {
+    let not_a_join = || "not a join".to_string();
+    let Statement::Query(query) = stmt else { return Err(not_a_join()) };
+    let SetExpr::Select(select) = &*query.body else { return Err(not_a_join()) };
+    let joins = match select.from.as_slice() {
+        [from] => &from.joins,
+        _ => return Err("comma-separated tables aren't supported; join them with JOIN ... ON".to_string()),
+    };
+    let operator = match joins.as_slice() {
+        [join] => &join.join_operator,
+        [] => return Err(not_a_join()),
+        _ => return Err("only one join per statement is supported".to_string()),
+    };
+    let left = match operator {
+        JoinOperator::Join(_) | JoinOperator::Inner(_) => false,
+        JoinOperator::Left(_) | JoinOperator::LeftOuter(_) => true,
+        other => return Err(format!("{} isn't supported; only [INNER] JOIN and LEFT [OUTER] JOIN are", join_name(other))),
+    };
+    let [join] = extract_join_info(stmt).ok_or_else(not_a_join)?.try_into().map_err(|_| not_a_join())?;
+    let (zset, hash) = (&join.left, &join.right);
+    if !is_zset_table_name(&zset.name) || !is_hash_table_name(&hash.name) {
+        return Err(format!("{} {} {}: only a sorted set (__zset) joined to hashes (__hash) is supported", zset.name, join_name(operator), hash.name));
+    }
+    let grouped = !matches!(&select.group_by, GroupByExpr::Expressions(exprs, _) if exprs.is_empty());
+    if select.distinct.is_some() || grouped || select.having.is_some() {
+        return Err("DISTINCT, GROUP BY, and HAVING aren't supported on joins".to_string());
+    }
+    let z = zset.alias.as_deref().unwrap_or(&zset.name);
+    let is_member = |expr: &Expr| column_ref(expr)
+        .is_some_and(|(qualifier, column)| column == "member" && on_zset(&qualifier, &column, zset, hash) == Some(true));
+
+    // ON hash_column = [prefix ||] member, either way round
+    let on_error = || format!("the ON condition must equate a hash column with {}.member (or 'prefix' || {}.member)", z, z);
+    let JoinCondition::On(condition) = &join.condition else { return Err(on_error()) };
+    let Expr::BinaryOp { left: a, op: BinaryOperator::Eq, right: b } = &**condition else { return Err(on_error()) };
+    let member_key = |expr: &Expr| match expr {
+        member if is_member(member) => Some(String::new()),
+        Expr::BinaryOp { left, op: BinaryOperator::StringConcat, right } if is_member(right) => match &**left {
+            Expr::Value(value) => match &value.value {
+                Value::SingleQuotedString(prefix) => Some(prefix.clone()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    };
+    let hash_column = |expr: &Expr| column_ref(expr)
+        .filter(|(qualifier, column)| on_zset(qualifier, column, zset, hash) == Some(false))
+        .map(|(_, column)| column);
+    let (joined, key_prefix) = match (hash_column(a), member_key(b), hash_column(b), member_key(a)) {
+        (Some(column), Some(prefix), _, _) | (_, _, Some(column), Some(prefix)) => (column, prefix),
+        _ => return Err(on_error()),
+    };
+
+    // WHERE zset.key = 'value', and nothing else
+    let where_error = || format!("a join's WHERE clause must be exactly {}.key = 'value'", z);
+    let Some(Expr::BinaryOp { left: column, op: BinaryOperator::Eq, right: value }) = &select.selection else { return Err(where_error()) };
+    let key = match (column_ref(column).ok_or_else(where_error)?, &**value) {
+        ((qualifier @ Some(_), column), Expr::Value(value)) if column == "key" && on_zset(&qualifier, &column, zset, hash) == Some(true) => {
+            match &value.value {
+                Value::SingleQuotedString(key) => key.clone(),
+                _ => return Err(where_error()),
+            }
+        }
+        _ => return Err(where_error()),
+    };
+
+    let order_error = || format!("a join can only be ordered by {}.score", z);
+    let descending = match query.order_by.as_ref().map(|order_by| &order_by.kind) {
+        None => false,
+        Some(OrderByKind::Expressions(exprs)) => match exprs.as_slice() {
+            [order_by] => match column_ref(&order_by.expr).ok_or_else(order_error)? {
+                (qualifier, column) if column == "score" && on_zset(&qualifier, &column, zset, hash) == Some(true) => order_by.options.asc == Some(false),
+                _ => return Err(order_error()),
+            },
+            _ => return Err(order_error()),
+        },
+        Some(_) => return Err(order_error()),
+    };
+    let bounds_error = || "a join's LIMIT and OFFSET must be numbers".to_string();
+    let limit = match &query.limit {
+        Some(_) => Some(query_has_limit(query).ok_or_else(bounds_error)?),
+        None => None,
+    };
+    let offset = match &query.offset {
+        Some(offset) => match &offset.value {
+            Expr::Value(value) => match &value.value {
+                Value::Number(n, _) => n.parse().map_err(|_| bounds_error())?,
+                _ => return Err(bounds_error()),
+            },
+            _ => return Err(bounds_error()),
+        },
+        None => 0,
+    };
+
+    let zset_columns = || ["member", "score"].map(|column| (column.to_string(), format!("@{}", column)));
+    let hash_columns = || [("*".to_string(), "#*".to_string())];
+    let mut columns = Vec::new();
+    for item in &select.projection {
+        let projection_error = || format!("{} can't be projected from a join; name {}.member, {}.score, {}.*, or hash fields", item, z, z, z);
+        let (expr, alias) = match item {
+            SelectItem::UnnamedExpr(expr) => (expr, None),
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.value.clone())),
+            SelectItem::Wildcard(_) => {
+                columns.extend(zset_columns().into_iter().chain(hash_columns()));
+                continue;
+            }
+            SelectItem::QualifiedWildcard(SelectItemQualifiedWildcardKind::ObjectName(name), _) => {
+                let qualifier = name.0.last().and_then(ObjectNamePart::as_ident).map(|ident| ident.value.to_lowercase());
+                match on_zset(&qualifier, "*", zset, hash).filter(|_| qualifier.is_some()).ok_or_else(projection_error)? {
+                    true => columns.extend(zset_columns()),
+                    false => columns.extend(hash_columns()),
+                }
+                continue;
+            }
+            _ => return Err(projection_error()),
+        };
+        let (qualifier, column) = column_ref(expr).ok_or_else(projection_error)?;
+        let source = match on_zset(&qualifier, &column, zset, hash).ok_or_else(projection_error)? {
+            true if column == "member" || column == "score" => format!("@{}", column),
+            true => return Err(projection_error()),
+            false if column == joined && key_prefix.is_empty() => "#member".to_string(),
+            false if column == "key" => "#key".to_string(),
+            false => column.clone(),
+        };
+        columns.push((alias.unwrap_or(column), source));
+    }
+
+    Ok(ZSetHashJoinInfo { key, key_prefix, left, descending, offset, limit, columns })
+}
 
 /// Extract join information from a query
 pub fn extract_join_info(stmt: &Statement) -> Option<Vec<JoinInfo>> {
@@ -17,9 +230,9 @@ pub fn extract_join_info(stmt: &Statement) -> Option<Vec<JoinInfo>> {
             for table_with_joins in &select.from {
                 for join in &table_with_joins.joins {
                     let join_type = match &join.join_operator {
-                        JoinOperator::Inner(_) => JoinType::Inner,
-                        JoinOperator::Left(_) => JoinType::LeftOuter,
-                        JoinOperator::Right(_) => JoinType::RightOuter,
+                        JoinOperator::Join(_) | JoinOperator::Inner(_) => JoinType::Inner,
+                        JoinOperator::Left(_) | JoinOperator::LeftOuter(_) => JoinType::LeftOuter,
+                        JoinOperator::Right(_) | JoinOperator::RightOuter(_) => JoinType::RightOuter,
                         JoinOperator::FullOuter(_) => JoinType::FullOuter,
                         _ => JoinType::Inner,
                     };
@@ -28,9 +241,12 @@ pub fn extract_join_info(stmt: &Statement) -> Option<Vec<JoinInfo>> {
                     
                     // Get join condition
                     let condition = match &join.join_operator {
+                        JoinOperator::Join(constraint) |
                         JoinOperator::Inner(constraint) | 
                         JoinOperator::Left(constraint) |
+                        JoinOperator::LeftOuter(constraint) |
                         JoinOperator::Right(constraint) |
+                        JoinOperator::RightOuter(constraint) |
                         JoinOperator::FullOuter(constraint) => {
                             extract_join_condition(constraint)
                         },
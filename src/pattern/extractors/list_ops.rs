@@ -54,7 +54,7 @@ pub fn extract_list_index(expr: &Expr) -> Option<String> {
 /// Extract data for a Redis LRANGE command (all elements)
 pub fn extract_list_getall(stmt: &Statement) -> Option<ListGetAllInfo> {
     // First match a wildcard select
-    let select = match matchers::common::wildcard_select().match_pattern(stmt) {
+    let select = match matchers::common::wildcard_select().match_pattern(&stmt) {
         Ok(select) => select,
         Err(_) => return None,
     };
@@ -65,7 +65,7 @@ pub fn extract_list_getall(stmt: &Statement) -> Option<ListGetAllInfo> {
     }
     
     let is_list_table = match &select.from[0].relation {
-        table => matchers::common::list_table().match_pattern(table).is_ok(),
+        table => matchers::common::list_table().match_pattern(&table).is_ok(),
     };
     
     if !is_list_table {
@@ -75,12 +75,12 @@ pub fn extract_list_getall(stmt: &Statement) -> Option<ListGetAllInfo> {
     // Check for a key equals condition
     if let Some(where_clause) = &select.selection {
         // Make sure there's no index condition
-        if let Ok(_) = matchers::common::field_equals("index").match_pattern(where_clause) {
+        if matchers::common::field_equals("index").match_pattern(&where_clause).is_ok() {
             return None;
         }
         
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(ListGetAllInfo { key }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(ListGetAllInfo { key: key.to_string() }),
             Err(_) => None,
         }
     } else {
@@ -91,7 +91,7 @@ pub fn extract_list_getall(stmt: &Statement) -> Option<ListGetAllInfo> {
 /// Extract data for a Redis LRANGE command with limit
 pub fn extract_list_get_range(stmt: &Statement) -> Option<ListGetRangeInfo> {
     // Match a wildcard select
-    let select = match matchers::common::wildcard_select().match_pattern(stmt) {
+    let select = match matchers::common::wildcard_select().match_pattern(&stmt) {
         Ok(select) => select,
         Err(_) => return None,
     };
@@ -102,7 +102,7 @@ pub fn extract_list_get_range(stmt: &Statement) -> Option<ListGetRangeInfo> {
     }
     
     let is_list_table = match &select.from[0].relation {
-        table => matchers::common::list_table().match_pattern(table).is_ok(),
+        table => matchers::common::list_table().match_pattern(&table).is_ok(),
     };
     
     if !is_list_table {
@@ -111,7 +111,7 @@ pub fn extract_list_get_range(stmt: &Statement) -> Option<ListGetRangeInfo> {
     
     // Extract key from WHERE clause
     let key = if let Some(where_clause) = &select.selection {
-        match matchers::common::key_equals().match_pattern(where_clause) {
+        match matchers::common::key_equals().match_pattern(&where_clause) {
             Ok(key) => key,
             Err(_) => return None,
         }
@@ -122,7 +122,7 @@ pub fn extract_list_get_range(stmt: &Statement) -> Option<ListGetRangeInfo> {
     // Extract limit from LIMIT clause
     if let Statement::Query(query) = stmt {
         if let Some(limit_value) = crate::pattern::matchers::select::query_has_limit(query) {
-            return Some(ListGetRangeInfo { key, limit: limit_value });
+            return Some(ListGetRangeInfo { key: key.to_string(), limit: limit_value });
         }
         None
     } else {
@@ -141,7 +141,7 @@ pub fn extract_list_get_index(stmt: &Statement) -> Option<ListIndexInfo> {
                         match &name.0[0] {
                             ObjectNamePart::Identifier(ident) => {
                                 let table_name = &ident.value;
-                                if table_name.ends_with("__list") {
+                                if matchers::common::is_list_table_name(table_name) {
                                     // Get key from WHERE clause
                                     if let Some(expr) = &select.selection {
                                         if let Some(key) = extract_key_from_condition(expr) {
@@ -18,5 +18,5 @@ pub use zset_ops::{ZSetGetAllInfo, ZSetScoreRangeInfo, ZSetGetReversedInfo, extr
 pub use common::{ConditionValue, extract_key_from_condition, extract_complex_conditions, determine_table_type};
 pub use insert_ops::{InsertCommandInfo, extract_insert_command};
 pub use delete_ops::{DeleteCommandInfo, extract_delete_command};
-pub use join_ops::{extract_join_info};
+pub use join_ops::{ZSetHashJoinInfo, extract_join_info, extract_zset_hash_join, unsupported_join};
 pub use query_extractors::*;
\ No newline at end of file
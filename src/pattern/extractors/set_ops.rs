@@ -47,7 +47,7 @@ pub fn extract_set_member(expr: &Expr) -> Option<String> {
 /// Extract data for a Redis SMEMBERS command
 pub fn extract_set_getall(stmt: &Statement) -> Option<SetGetAllInfo> {
     // First match a wildcard select
-    let select = match matchers::common::wildcard_select().match_pattern(stmt) {
+    let select = match matchers::common::wildcard_select().match_pattern(&stmt) {
         Ok(select) => select,
         Err(_) => return None,
     };
@@ -58,7 +58,7 @@ pub fn extract_set_getall(stmt: &Statement) -> Option<SetGetAllInfo> {
     }
     
     let is_set_table = match &select.from[0].relation {
-        table => matchers::common::set_table().match_pattern(table).is_ok(),
+        table => matchers::common::set_table().match_pattern(&table).is_ok(),
     };
     
     if !is_set_table {
@@ -68,12 +68,12 @@ pub fn extract_set_getall(stmt: &Statement) -> Option<SetGetAllInfo> {
     // Check for a key equals condition
     if let Some(where_clause) = &select.selection {
         // Make sure there's no member condition
-        if let Ok(_) = matchers::common::field_equals("member").match_pattern(where_clause) {
+        if matchers::common::field_equals("member").match_pattern(&where_clause).is_ok() {
             return None;
         }
         
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(SetGetAllInfo { key }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(SetGetAllInfo { key: key.to_string() }),
             Err(_) => None,
         }
     } else {
@@ -118,7 +118,7 @@ pub fn extract_set_ismember(stmt: &Statement) -> Option<SetMemberInfo> {
                         match &name.0[0] {
                             ObjectNamePart::Identifier(ident) => {
                                 let table_name = &ident.value;
-                                if table_name.ends_with("__set") {
+                                if matchers::common::is_set_table_name(table_name) {
                                     // Get key and member from WHERE clause
                                     if let Some(expr) = &select.selection {
                                         if let Some(key) = extract_key_from_condition(expr) {
@@ -13,7 +13,7 @@ pub struct StringGetInfo {
 pub fn extract_string_get(stmt: &Statement) -> Option<StringGetInfo> {
     matchers::common::string_get()
         .match_pattern(stmt)
-        .map(|key| StringGetInfo { key })
+        .map(|key| StringGetInfo { key: key.to_string() })
         .ok()
 }
 
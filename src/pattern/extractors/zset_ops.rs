@@ -1,5 +1,5 @@
 // pattern/extractors/zset_ops.rs
-use sqlparser::ast::{BinaryOperator, Expr, ObjectNamePart, SelectItem, SetExpr, Statement, TableFactor, Value};
+use sqlparser::ast::{Expr, ObjectNamePart, SelectItem, SetExpr, Statement, TableFactor};
 use crate::pattern::combinators::Pattern;
 use crate::pattern::matchers;
 use super::common::extract_key_from_condition;
@@ -24,81 +24,15 @@ pub struct ZSetGetReversedInfo {
     pub key: String,
 }
 
-/// Extract score ranges from complex expressions
+/// Extract score ranges from complex expressions, ANDed bounds combined (see `sel_extract_score_range_from_expr`)
 pub fn extract_score_range(expr: &Expr) -> Option<(String, String)> {
-    match expr {
-        // Simple comparisons
-        Expr::BinaryOp { left, op, right } => {
-            if let Expr::Identifier(ident) = &**left {
-                if ident.value.to_lowercase() == "score" {
-                    match op {
-                        BinaryOperator::Gt => {
-                            if let Expr::Value(val) = &**right {
-                                if let Value::Number(n, _) = &val.value {
-                                    return Some((format!("({}",n), "+inf".to_string()));
-                                }
-                            }
-                        }
-                        BinaryOperator::GtEq => {
-                            if let Expr::Value(val) = &**right {
-                                if let Value::Number(n, _) = &val.value {
-                                    return Some((n.clone(), "+inf".to_string()));
-                                }
-                            }
-                        }
-                        BinaryOperator::Lt => {
-                            if let Expr::Value(val) = &**right {
-                                if let Value::Number(n, _) = &val.value {
-                                    return Some(("-inf".to_string(), format!("({}",n)));
-                                }
-                            }
-                        }
-                        BinaryOperator::LtEq => {
-                            if let Expr::Value(val) = &**right {
-                                if let Value::Number(n, _) = &val.value {
-                                    return Some(("-inf".to_string(), n.clone()));
-                                }
-                            }
-                        }
-                        // BinaryOperator::Between => {
-                        //     // Handle BETWEEN syntax
-                        //     if let Expr::Between { expr, low, high } = &**right {
-                        //         if let (Expr::Value(low_val), Expr::Value(high_val)) = (&**low, &**high) {
-                        //             if let (Value::Number(min, _), Value::Number(max, _)) = 
-                        //                 (&low_val.value, &high_val.value) {
-                        //                 return Some((min.clone(), max.clone()));
-                        //             }
-                        //         }
-                        //     }
-                        // }
-                        _ => {}
-                    }
-                }
-            }
-            
-            // Check for AND conditions combining score ranges
-            if *op == BinaryOperator::And {
-                let left_range = extract_score_range(left);
-                let right_range = extract_score_range(right);
-                
-                match (left_range, right_range) {
-                    (Some((min1, _)), Some((_, max2))) => Some((min1, max2)),
-                    (Some(range), None) => Some(range),
-                    (None, Some(range)) => Some(range),
-                    _ => None
-                }
-            } else {
-                None
-            }
-        },
-        _ => None
-    }
+    crate::ast::sel_extract_score_range_from_expr(expr)
 }
 
 /// Extract data for a Redis ZRANGEBYSCORE command (all elements)
 pub fn extract_zset_getall(stmt: &Statement) -> Option<ZSetGetAllInfo> {
     // First match a wildcard select
-    let select = match matchers::common::wildcard_select().match_pattern(stmt) {
+    let select = match matchers::common::wildcard_select().match_pattern(&stmt) {
         Ok(select) => select,
         Err(_) => return None,
     };
@@ -109,7 +43,7 @@ pub fn extract_zset_getall(stmt: &Statement) -> Option<ZSetGetAllInfo> {
     }
     
     let is_zset_table = match &select.from[0].relation {
-        table => matchers::common::zset_table().match_pattern(table).is_ok(),
+        table => matchers::common::zset_table().match_pattern(&table).is_ok(),
     };
     
     if !is_zset_table {
@@ -123,8 +57,8 @@ pub fn extract_zset_getall(stmt: &Statement) -> Option<ZSetGetAllInfo> {
             return None;
         }
         
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(ZSetGetAllInfo { key }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(ZSetGetAllInfo { key: key.to_string() }),
             Err(_) => None,
         }
     } else {
@@ -148,7 +82,7 @@ pub fn extract_zset_get_score_range(stmt: &Statement) -> Option<ZSetScoreRangeIn
                         match &name.0[0] {
                             ObjectNamePart::Identifier(ident) => {
                                 let table_name = &ident.value;
-                                if table_name.ends_with("__zset") {
+                                if matchers::common::is_zset_table_name(table_name) {
                                     // Get key from WHERE clause
                                     if let Some(expr) = &select.selection {
                                         if let Some(key) = extract_key_from_condition(expr) {
@@ -205,7 +139,7 @@ pub fn extract_zset_get_reversed(stmt: &Statement) -> Option<ZSetGetReversedInfo
     }
     
     let is_zset_table = match &select.from[0].relation {
-        table => matchers::common::zset_table().match_pattern(table).is_ok(),
+        table => matchers::common::zset_table().match_pattern(&table).is_ok(),
     };
     
     if !is_zset_table {
@@ -214,8 +148,8 @@ pub fn extract_zset_get_reversed(stmt: &Statement) -> Option<ZSetGetReversedInfo
     
     // Check for a key equals condition
     if let Some(where_clause) = &select.selection {
-        match matchers::common::key_equals().match_pattern(where_clause) {
-            Ok(key) => Some(ZSetGetReversedInfo { key }),
+        match matchers::common::key_equals().match_pattern(&where_clause) {
+            Ok(key) => Some(ZSetGetReversedInfo { key: key.to_string() }),
             Err(_) => None,
         }
     } else {
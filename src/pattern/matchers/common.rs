@@ -1,136 +1,89 @@
 // matchers/common.rs - Common predicate functions for SQL pattern matching
 // Shared utility functions for pattern matching across statement types
 
-use sqlparser::ast::{
-    Expr, ObjectNamePart, OrderByKind, Query, Select, SelectItem, SetExpr, Statement, TableFactor
-};
+use sqlparser::ast::{Expr, Query, Select, Statement, TableFactor};
+use crate::ast;
 use crate::pattern::combinators::{Pattern, and_then, extract};
 
+pub use crate::types::RedisDataType;
+use crate::types::{redis_type_of, TypeMapping};
+
 /// Check if a table name represents a Redis Hash table
 pub fn is_hash_table_name(name: &str) -> bool {
-    name.ends_with("__hash")
+    get_redis_data_type(name) == RedisDataType::Hash
 }
 
 /// Check if a table name represents a Redis List table
 pub fn is_list_table_name(name: &str) -> bool {
-    name.ends_with("__list")
+    get_redis_data_type(name) == RedisDataType::List
 }
 
 /// Check if a table name represents a Redis Set table
 pub fn is_set_table_name(name: &str) -> bool {
-    name.ends_with("__set")
+    get_redis_data_type(name) == RedisDataType::Set
 }
 
 /// Check if a table name represents a Redis Sorted Set table
 pub fn is_zset_table_name(name: &str) -> bool {
-    name.ends_with("__zset")
+    get_redis_data_type(name) == RedisDataType::SortedSet
 }
 
 /// Check if a table name represents a Redis String table (default)
 pub fn is_string_table_name(name: &str) -> bool {
-    !is_hash_table_name(name) && 
-    !is_list_table_name(name) && 
-    !is_set_table_name(name) && 
-    !is_zset_table_name(name)
-}
-
-/// Determine the Redis data type from a table name
-#[derive(Debug, Clone, PartialEq)]
-pub enum RedisDataType {
-    String,
-    Hash,
-    List,
-    Set,
-    SortedSet,
+    get_redis_data_type(name) == RedisDataType::String
 }
 
+/// Determine the Redis data type from a table name, under the standard suffixes
 pub fn get_redis_data_type(table_name: &str) -> RedisDataType {
-    if is_hash_table_name(table_name) {
-        RedisDataType::Hash
-    } else if is_list_table_name(table_name) {
-        RedisDataType::List
-    } else if is_set_table_name(table_name) {
-        RedisDataType::Set
-    } else if is_zset_table_name(table_name) {
-        RedisDataType::SortedSet
-    } else {
-        RedisDataType::String
-    }
+    redis_type_of(table_name, TypeMapping::standard())
 }
 
-/// Pattern that matches a SELECT statement
-pub fn select_statement() -> impl Pattern<Statement, Box<Select>> {
-    extract(|stmt: &Statement| {
-        match stmt {
-            Statement::Query(query) => {
-                match &*query.body {
-                    SetExpr::Select(select) => Some(select.clone()),
-                    _ => None,
-                }
-            },
-            _ => None,
-        }
-    })
+// --------------------------------
+// Combinator adapters - thin wrappers over the `ast` extraction functions, which are the one
+// implementation of each predicate; add new predicates there and wrap them here if needed
+// --------------------------------
+
+/// Pattern that matches a SELECT statement, borrowing it from the statement
+pub fn select_statement<'a>() -> impl Pattern<&'a Statement, &'a Select> {
+    extract(|stmt: &&'a Statement| ast::sel_get_query(stmt).and_then(ast::sel_get_select))
 }
 
 /// Pattern that matches a query with a wildcard projection
-pub fn wildcard_select() -> impl Pattern<Statement, Box<Select>> {
+pub fn wildcard_select<'a>() -> impl Pattern<&'a Statement, &'a Select> {
     and_then(
         select_statement(),
-        extract(|select: &Box<Select>| {
-            if select.projection.len() == 1 {
-                match &select.projection[0] {
-                    SelectItem::Wildcard(_) => Some(select.clone()),
-                    _ => None,
-                }
-            } else {
-                None
+        extract(|select: &&'a Select| {
+            match select.projection.as_slice() {
+                [item] if ast::sel_is_wildcard(item) => Some(*select),
+                _ => None,
             }
         })
     )
 }
 
 /// Pattern that matches a single field select
-pub fn single_field_select() -> impl Pattern<Statement, (Box<Select>, String)> {
+pub fn single_field_select<'a>() -> impl Pattern<&'a Statement, (&'a Select, &'a str)> {
     and_then(
         select_statement(),
-        extract(|select: &Box<Select>| {
-            if select.projection.len() == 1 {
-                match &select.projection[0] {
-                    SelectItem::UnnamedExpr(Expr::Identifier(ident)) => {
-                        Some((select.clone(), ident.value.clone()))
-                    },
-                    _ => None,
-                }
-            } else {
-                None
+        extract(|select: &&'a Select| {
+            match select.projection.as_slice() {
+                [item] => Some((*select, ast::sel_get_field_name_ref(item)?)),
+                _ => None,
             }
         })
     )
 }
 
 /// Pattern that matches a multi-field select
-pub fn multi_field_select() -> impl Pattern<Statement, (Box<Select>, Vec<String>)> {
+pub fn multi_field_select<'a>() -> impl Pattern<&'a Statement, (&'a Select, Vec<&'a str>)> {
     and_then(
         select_statement(),
-        extract(|select: &Box<Select>| {
+        extract(|select: &&'a Select| {
             if select.projection.len() > 1 {
-                let field_names: Vec<String> = select.projection.iter()
-                    .filter_map(|item| {
-                        match item {
-                            SelectItem::UnnamedExpr(Expr::Identifier(ident)) => {
-                                Some(ident.value.clone())
-                            },
-                            _ => None,
-                        }
-                    })
-                    .collect();
-                
-                if field_names.len() == select.projection.len() {
-                    Some((select.clone(), field_names))
-                } else {
-                    None
-                }
+                let field_names = select.projection.iter()
+                    .map(ast::sel_get_field_name_ref)
+                    .collect::<Option<Vec<_>>>()?;
+                Some((*select, field_names))
             } else {
                 None
             }
@@ -139,265 +92,67 @@ pub fn multi_field_select() -> impl Pattern<Statement, (Box<Select>, Vec<String>
 }
 
 /// Pattern that matches a table with a specific suffix
-pub fn table_with_suffix(suffix: &'static str) -> impl Pattern<TableFactor, String> {
-    extract(move |table: &TableFactor| {
-        match table {
-            TableFactor::Table { name, .. } => {
-                if !name.0.is_empty() {
-                    match &name.0[0] {
-                        ObjectNamePart::Identifier(ident) => {
-                            let table_name = &ident.value;
-                            if table_name.ends_with(suffix) {
-                                Some(table_name.clone())
-                            } else {
-                                None
-                            }
-                        }
-                    }
-                } else {
-                    None
-                }
-            },
-            _ => None,
-        }
-    })
+pub fn table_with_suffix<'a>(suffix: &'static str) -> impl Pattern<&'a TableFactor, &'a str> {
+    extract(move |table: &&'a TableFactor| ast::sel_get_relation_name(table).filter(|name| name.ends_with(suffix)))
 }
 
 /// Pattern that matches a hash table
-pub fn hash_table() -> impl Pattern<TableFactor, String> {
-    table_with_suffix("__hash")
+pub fn hash_table<'a>() -> impl Pattern<&'a TableFactor, &'a str> {
+    table_of_type(RedisDataType::Hash)
 }
 
 /// Pattern that matches a list table
-pub fn list_table() -> impl Pattern<TableFactor, String> {
-    table_with_suffix("__list")
+pub fn list_table<'a>() -> impl Pattern<&'a TableFactor, &'a str> {
+    table_of_type(RedisDataType::List)
 }
 
 /// Pattern that matches a set table
-pub fn set_table() -> impl Pattern<TableFactor, String> {
-    table_with_suffix("__set")
+pub fn set_table<'a>() -> impl Pattern<&'a TableFactor, &'a str> {
+    table_of_type(RedisDataType::Set)
 }
 
 /// Pattern that matches a sorted set table
-pub fn zset_table() -> impl Pattern<TableFactor, String> {
-    table_with_suffix("__zset")
+pub fn zset_table<'a>() -> impl Pattern<&'a TableFactor, &'a str> {
+    table_of_type(RedisDataType::SortedSet)
 }
 
-pub fn string_table() -> impl Pattern<TableFactor, String> {
-    extract(|table: &TableFactor| {
-        match table {
-            TableFactor::Table { name, .. } => {
-                if !name.0.is_empty() {
-                    match &name.0[0] {
-                        ObjectNamePart::Identifier(ident) => {
-                            let table_name = &ident.value;
-                            if !table_name.ends_with("__hash") &&
-                               !table_name.ends_with("__list") &&
-                               !table_name.ends_with("__set") &&
-                               !table_name.ends_with("__zset") {
-                                Some(table_name.clone())
-                            } else {
-                                None
-                            }
-                        }
-                    }
-                } else {
-                    None
-                }
-            },
-            _ => None,
-        }
-    })
- }
+pub fn string_table<'a>() -> impl Pattern<&'a TableFactor, &'a str> {
+    table_of_type(RedisDataType::String)
+}
 
-pub fn key_equals() -> impl Pattern<Expr, String> {
-    extract(|expr: &Expr| {
-        match expr {
-            Expr::BinaryOp { left, op, right } => {
-                if *op == sqlparser::ast::BinaryOperator::Eq {
-                    match &**left {
-                        Expr::Identifier(ident) if ident.value.to_lowercase() == "key" => {
-                            match &**right {
-                                Expr::Value(value_with_span) => match &value_with_span.value {
-                                    sqlparser::ast::Value::SingleQuotedString(s) |
-                                    sqlparser::ast::Value::DoubleQuotedString(s) => {
-                                        Some(s.clone())
-                                    },
-                                    sqlparser::ast::Value::Number(n, _) => {
-                                        Some(n.clone())
-                                    },
-                                    _ => None,
-                                },
-                                _ => None,
-                            }
-                        },
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            },
-            _ => None,
-        }
-    })
- }
+/// Pattern that matches a table whose name selects `data_type`
+pub fn table_of_type<'a>(data_type: RedisDataType) -> impl Pattern<&'a TableFactor, &'a str> {
+    extract(move |table: &&'a TableFactor| ast::sel_get_relation_name(table).filter(|name| get_redis_data_type(name) == data_type))
+}
 
+/// Pattern that matches a `key = value` condition (alone or ANDed), borrowing the value
+pub fn key_equals<'a>() -> impl Pattern<&'a Expr, &'a str> {
+    extract(|expr: &&'a Expr| ast::sel_extract_key_from_expr(expr))
+}
 
-/// Pattern that matches a field equality condition
-pub fn field_equals(field_name: &'static str) -> impl Pattern<Expr, String> {
-    extract(move |expr: &Expr| {
-        match expr {
-            Expr::BinaryOp { left, op, right } => {
-                if *op == sqlparser::ast::BinaryOperator::Eq {
-                    match &**left {
-                        Expr::Identifier(ident) if ident.value.to_lowercase() == field_name.to_lowercase() => {
-                            match &**right {
-                                Expr::Value(value_with_span) => match &value_with_span.value {
-                                    sqlparser::ast::Value::SingleQuotedString(s) |
-                                    sqlparser::ast::Value::DoubleQuotedString(s) => {
-                                        Some(s.clone())
-                                    },
-                                    sqlparser::ast::Value::Number(n, _) => {
-                                        Some(n.clone())
-                                    },
-                                    _ => None,
-                                },
-                                _ => None,
-                            }
-                        },
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            },
-            _ => None,
-        }
-    })
+/// Pattern that matches a field equality condition (alone or ANDed), borrowing the value
+pub fn field_equals<'a>(field_name: &'static str) -> impl Pattern<&'a Expr, &'a str> {
+    extract(move |expr: &&'a Expr| ast::sel_extract_field_from_expr(expr, field_name))
+}
+
+/// Pattern that matches a `key IN (...)` condition (alone or ANDed)
+pub fn key_in() -> impl Pattern<Expr, Vec<String>> {
+    extract(ast::sel_extract_key_in_from_expr)
 }
 
+/// Pattern that matches score comparisons, as a ZRANGEBYSCORE (min, max)
 pub fn score_range() -> impl Pattern<Expr, (String, String)> {
-    extract(|expr: &Expr| {
-        match expr {
-            Expr::BinaryOp { left, op, right } => {
-                // Direct score comparison
-                if let Expr::Identifier(ident) = &**left {
-                    if ident.value.to_lowercase() == "score" {
-                        match op {
-                            sqlparser::ast::BinaryOperator::Gt => {
-                                if let Expr::Value(value_with_span) = &**right {
-                                    if let sqlparser::ast::Value::Number(n, _) = &value_with_span.value {
-                                        return Some((format!("({}",n), "+inf".to_string()));
-                                    }
-                                }
-                            },
-                            sqlparser::ast::BinaryOperator::GtEq => {
-                                if let Expr::Value(value_with_span) = &**right {
-                                    if let sqlparser::ast::Value::Number(n, _) = &value_with_span.value {
-                                        return Some((n.clone(), "+inf".to_string()));
-                                    }
-                                }
-                            },
-                            sqlparser::ast::BinaryOperator::Lt => {
-                                if let Expr::Value(value_with_span) = &**right {
-                                    if let sqlparser::ast::Value::Number(n, _) = &value_with_span.value {
-                                        return Some(("-inf".to_string(), format!("({}",n)));
-                                    }
-                                }
-                            },
-                            sqlparser::ast::BinaryOperator::LtEq => {
-                                if let Expr::Value(value_with_span) = &**right {
-                                    if let sqlparser::ast::Value::Number(n, _) = &value_with_span.value {
-                                        return Some(("-inf".to_string(), n.clone()));
-                                    }
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
-                }
-                
-                // AND conditions - properly unwrap Results to Options
-                if *op == sqlparser::ast::BinaryOperator::And {
-                    // First check if one side has a key condition and the other has a score condition
-                    let left_has_key = key_equals().match_pattern(left).is_ok();
-                    let right_has_key = key_equals().match_pattern(right).is_ok();
-                    
-                    let left_score_result = score_range().match_pattern(left);
-                    let right_score_result = score_range().match_pattern(right);
-                    
-                    if left_has_key && right_score_result.is_ok() {
-                        return Some(right_score_result.unwrap());
-                    }
-                    
-                    if right_has_key && left_score_result.is_ok() {
-                        return Some(left_score_result.unwrap());
-                    }
-                    
-                    // Also check for compound score conditions (though not common in this use case)
-                    if left_score_result.is_ok() && right_score_result.is_ok() {
-                        let (min1, _) = left_score_result.unwrap();
-                        let (_, max2) = right_score_result.unwrap();
-                        return Some((min1, max2));
-                    }
-                }
-            },
-            _ => {}
-        }
-        None
-    })
+    extract(ast::sel_extract_score_range_from_expr)
 }
 
 /// Pattern that matches an ORDER BY score DESC clause
 pub fn order_by_score_desc() -> impl Pattern<Query, ()> {
-    extract(|query: &Query| {
-        if query.order_by.is_none() {
-            return None;
-        }
-        
-        let order_by = query.order_by.as_ref().unwrap();
-        
-        match &order_by.kind {
-            OrderByKind::Expressions(exprs) => {
-                if exprs.is_empty() {
-                    return None;
-                }
-                
-                let order_expr = &exprs[0];
-                match &order_expr.expr {
-                    Expr::Identifier(ident) => {
-                        if ident.value.to_lowercase() == "score" && 
-                           order_expr.options.asc.is_some() && 
-                           !order_expr.options.asc.unwrap() {
-                            Some(())
-                        } else {
-                            None
-                        }
-                    },
-                    _ => None,
-                }
-            },
-            _ => None,
-        }
-    })
+    extract(|query: &Query| ast::sel_is_order_by_score_desc(query).then_some(()))
 }
 
 /// Pattern that matches a LIMIT clause
 pub fn has_limit() -> impl Pattern<Query, u64> {
-    extract(|query: &Query| {
-        query.limit.as_ref().and_then(|limit| {
-            match limit {
-                Expr::Value(value_with_span) => {
-                    if let sqlparser::ast::Value::Number(n, _) = &value_with_span.value {
-                        n.parse::<u64>().ok()
-                    } else {
-                        None
-                    }
-                },
-                _ => None,
-            }
-        })
-    })
+    extract(ast::sel_get_limit)
 }
 
 // --------------------------------
@@ -408,7 +163,7 @@ pub fn has_limit() -> impl Pattern<Query, u64> {
 pub fn string_get() -> impl Pattern<Statement, String> {
     extract(|stmt: &Statement| {
         // First match a wildcard select
-        let select = match wildcard_select().match_pattern(stmt) {
+        let select = match wildcard_select().match_pattern(&stmt) {
             Ok(select) => select,
             Err(_) => return None,
         };
@@ -419,7 +174,7 @@ pub fn string_get() -> impl Pattern<Statement, String> {
         }
         
         let is_string_table = match &select.from[0].relation {
-            table => string_table().match_pattern(table).is_ok(),
+            table => string_table().match_pattern(&table).is_ok(),
         };
         
         if !is_string_table {
@@ -428,8 +183,8 @@ pub fn string_get() -> impl Pattern<Statement, String> {
         
         // Check for a key equals condition
         if let Some(where_clause) = &select.selection {
-            match key_equals().match_pattern(where_clause) {
-                Ok(key) => Some(key),
+            match key_equals().match_pattern(&where_clause) {
+                Ok(key) => Some(key.to_string()),
                 Err(_) => None,
             }
         } else {
@@ -442,7 +197,7 @@ pub fn string_get() -> impl Pattern<Statement, String> {
 pub fn hash_getall() -> impl Pattern<Statement, String> {
     extract(|stmt: &Statement| {
         // First match a wildcard select
-        let select = match wildcard_select().match_pattern(stmt) {
+        let select = match wildcard_select().match_pattern(&stmt) {
             Ok(select) => select,
             Err(_) => return None,
         };
@@ -453,7 +208,7 @@ pub fn hash_getall() -> impl Pattern<Statement, String> {
         }
         
         let is_hash_table = match &select.from[0].relation {
-            table => hash_table().match_pattern(table).is_ok(),
+            table => hash_table().match_pattern(&table).is_ok(),
         };
         
         if !is_hash_table {
@@ -462,8 +217,8 @@ pub fn hash_getall() -> impl Pattern<Statement, String> {
         
         // Check for a key equals condition
         if let Some(where_clause) = &select.selection {
-            match key_equals().match_pattern(where_clause) {
-                Ok(key) => Some(key),
+            match key_equals().match_pattern(&where_clause) {
+                Ok(key) => Some(key.to_string()),
                 Err(_) => None,
             }
         } else {
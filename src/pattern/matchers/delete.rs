@@ -2,6 +2,7 @@
 // Each function tests if a statement matches a particular DELETE pattern from the BNF grammar
 use sqlparser::ast::Statement;
 use crate::ast;
+use crate::pattern::matchers::common::{is_hash_table_name, is_list_table_name, is_set_table_name, is_string_table_name, is_zset_table_name};
 
 // --------------------------------
 // Basic Pattern Matchers - Pure Functions
@@ -14,48 +15,47 @@ pub fn is_delete(stmt: &Statement) -> bool {
 
 /// Check if DELETE statement target is a hash table
 pub fn is_hash_table(stmt: &Statement) -> bool {
-    match ast::delete::get_table_name(stmt) {
-        Some(name) => name.ends_with("__hash"),
+    match ast::delete::get_table_name_ref(stmt) {
+        Some(name) => is_hash_table_name(name),
         None => false,
     }
 }
 
 /// Check if DELETE statement target is a list table
 pub fn is_list_table(stmt: &Statement) -> bool {
-    match ast::delete::get_table_name(stmt) {
-        Some(name) => name.ends_with("__list"),
+    match ast::delete::get_table_name_ref(stmt) {
+        Some(name) => is_list_table_name(name),
         None => false,
     }
 }
 
 /// Check if DELETE statement target is a set table
 pub fn is_set_table(stmt: &Statement) -> bool {
-    match ast::delete::get_table_name(stmt) {
-        Some(name) => name.ends_with("__set"),
+    match ast::delete::get_table_name_ref(stmt) {
+        Some(name) => is_set_table_name(name),
         None => false,
     }
 }
 
 /// Check if DELETE statement target is a sorted set table
 pub fn is_zset_table(stmt: &Statement) -> bool {
-    match ast::delete::get_table_name(stmt) {
-        Some(name) => name.ends_with("__zset"),
+    match ast::delete::get_table_name_ref(stmt) {
+        Some(name) => is_zset_table_name(name),
         None => false,
     }
 }
 
 /// Check if DELETE statement target is a string table (default)
 pub fn is_string_table(stmt: &Statement) -> bool {
-    match ast::delete::get_table_name(stmt) {
-        Some(name) => !name.ends_with("__hash") && !name.ends_with("__list") && 
-                     !name.ends_with("__set") && !name.ends_with("__zset"),
+    match ast::delete::get_table_name_ref(stmt) {
+        Some(name) => is_string_table_name(name),
         None => false,
     }
 }
 
 /// Check if the DELETE statement has a key condition
 pub fn has_key_equals(stmt: &Statement) -> bool {
-    ast::delete::get_key_value(stmt).is_some()
+    ast::delete::get_key_value_ref(stmt).is_some()
 }
 
 /// Check if the DELETE statement has a field condition
@@ -74,17 +74,31 @@ pub fn is_string_delete(stmt: &Statement) -> bool {
 
 /// <hash-delete> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key>
 pub fn is_hash_delete(stmt: &Statement) -> bool {
-    is_delete(stmt) && is_hash_table(stmt) && has_key_equals(stmt) && !has_field_equals(stmt, "field")
+    is_delete(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
+        && !has_field_equals(stmt, "field") && !has_field_in(stmt)
 }
 
-/// <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" "=" <field>
+/// <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" ("=" <field> | "IN" "(" <field1> ["," <field2>]... ")")
 pub fn is_hash_delete_field(stmt: &Statement) -> bool {
-    is_delete(stmt) && is_hash_table(stmt) && has_key_equals(stmt) && has_field_equals(stmt, "field")
+    is_delete(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
+        && (has_field_equals(stmt, "field") || has_field_in(stmt))
+}
+
+/// Check if DELETE has a field IN (...) condition
+pub fn has_field_in(stmt: &Statement) -> bool {
+    ast::delete::get_column_in_values(stmt, "field").is_some()
 }
 
 /// <list-delete> ::= "DELETE" "FROM" <table> "__list" "WHERE" "key" "=" <key>
 pub fn is_list_delete(stmt: &Statement) -> bool {
     is_delete(stmt) && is_list_table(stmt) && has_key_equals(stmt) && !has_field_equals(stmt, "value")
+        && ast::delete::get_column_comparison(stmt, "index").is_none()
+}
+
+/// <list-trim> ::= "DELETE" "FROM" <table> "__list" "WHERE" "key" "=" <key> "AND" "index" (">" | ">=" | "<" | "<=") <n>
+pub fn is_list_trim(stmt: &Statement) -> bool {
+    is_delete(stmt) && is_list_table(stmt) && has_key_equals(stmt)
+        && ast::delete::get_index_trim_range(stmt).is_some()
 }
 
 /// <list-delete-value> ::= "DELETE" "FROM" <table> "__list" "WHERE" "key" "=" <key> "AND" "value" "=" <value>
@@ -115,10 +129,42 @@ pub fn has_member_in(stmt: &Statement) -> bool {
 
 /// <zset-delete> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key>
 pub fn is_zset_delete(stmt: &Statement) -> bool {
-    is_delete(stmt) && is_zset_table(stmt) && has_key_equals(stmt) && !has_field_equals(stmt, "member")
+    is_delete(stmt) && is_zset_table(stmt) && has_key_equals(stmt)
+        && !has_field_equals(stmt, "member") && !has_member_in(stmt)
+        && !ast::delete::has_order_or_limit(stmt) && !has_score_range(stmt)
+}
+
+/// <zset-delete-score-range> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "score" (<comparison> <n> | "BETWEEN" <min> "AND" <max>)
+pub fn is_zset_delete_score_range(stmt: &Statement) -> bool {
+    is_delete(stmt) && is_zset_table(stmt) && has_key_equals(stmt)
+        && !has_field_equals(stmt, "member") && !has_member_in(stmt)
+        && !ast::delete::has_order_or_limit(stmt) && has_score_range(stmt)
+}
+
+/// Check if DELETE has a score comparison or BETWEEN condition
+pub fn has_score_range(stmt: &Statement) -> bool {
+    ast::delete::get_score_range(stmt).is_some()
+}
+
+/// <zset-pop-min> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "ORDER" "BY" "score" ["ASC"] "LIMIT" <count> ["RETURNING" <columns>]
+pub fn is_zset_pop_min(stmt: &Statement) -> bool {
+    is_zset_pop(stmt) && ast::delete::get_order_by_score(stmt) == Some(false)
+}
+
+/// <zset-pop-max> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "ORDER" "BY" "score" "DESC" "LIMIT" <count> ["RETURNING" <columns>]
+pub fn is_zset_pop_max(stmt: &Statement) -> bool {
+    is_zset_pop(stmt) && ast::delete::get_order_by_score(stmt) == Some(true)
+}
+
+/// Key-only DELETE on a sorted set with a positive LIMIT; any other predicate (a score
+/// range, a member filter) must be applied before popping, which ZPOPMIN/ZPOPMAX can't do
+fn is_zset_pop(stmt: &Statement) -> bool {
+    is_delete(stmt) && is_zset_table(stmt) && ast::delete::has_only_key_equals(stmt)
+        && matches!(ast::delete::get_limit(stmt), Some(count) if count > 0)
 }
 
-/// <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" "=" <member>
+/// <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" ("=" <member> | "IN" "(" <member1> ["," <member2>]... ")")
 pub fn is_zset_delete_member(stmt: &Statement) -> bool {
-    is_delete(stmt) && is_zset_table(stmt) && has_key_equals(stmt) && has_field_equals(stmt, "member")
+    is_delete(stmt) && is_zset_table(stmt) && has_key_equals(stmt)
+        && (has_field_equals(stmt, "member") || has_member_in(stmt))
 }
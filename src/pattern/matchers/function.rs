@@ -0,0 +1,46 @@
+// matchers/function.rs - Predicate functions for table-function reads
+// `SELECT * FROM redis_get('user:1')` names the command and its key, without the table-suffix convention
+
+use sqlparser::ast::Statement;
+use crate::ast;
+
+/// Check if the statement is `SELECT * FROM <name>(<args>)` with `arity` literal arguments
+/// and no WHERE clause
+pub fn is_table_function(stmt: &Statement, name: &str, arity: usize) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .filter(|select| select.selection.is_none())
+        .filter(|select| select.projection.len() == 1 && ast::sel_is_wildcard(&select.projection[0]))
+        .and_then(ast::sel_get_table_function)
+        .is_some_and(|(function, args)| function == name && args.len() == arity)
+}
+
+/// <redis-get> ::= "SELECT" "*" "FROM" "redis_get" "(" <key> ")"
+pub fn is_redis_get(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_get", 1)
+}
+
+/// <redis-hgetall> ::= "SELECT" "*" "FROM" "redis_hgetall" "(" <key> ")"
+pub fn is_redis_hgetall(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_hgetall", 1)
+}
+
+/// <redis-hget> ::= "SELECT" "*" "FROM" "redis_hget" "(" <key> "," <field> ")"
+pub fn is_redis_hget(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_hget", 2)
+}
+
+/// <redis-lrange> ::= "SELECT" "*" "FROM" "redis_lrange" "(" <key> "," <start> "," <stop> ")"
+pub fn is_redis_lrange(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_lrange", 3)
+}
+
+/// <redis-smembers> ::= "SELECT" "*" "FROM" "redis_smembers" "(" <key> ")"
+pub fn is_redis_smembers(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_smembers", 1)
+}
+
+/// <redis-zrangebyscore> ::= "SELECT" "*" "FROM" "redis_zrangebyscore" "(" <key> "," <min> "," <max> ")"
+pub fn is_redis_zrangebyscore(stmt: &Statement) -> bool {
+    is_table_function(stmt, "redis_zrangebyscore", 3)
+}
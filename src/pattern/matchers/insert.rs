@@ -1,7 +1,10 @@
 // matchers/insert.rs - Predicate functions for INSERT statement pattern matching
 // Each function tests if a statement matches a particular INSERT pattern from the BNF grammar
 
-use sqlparser::ast::{Insert, ObjectNamePart, SetExpr, Statement, TableObject};
+use sqlparser::ast::{Insert, ObjectNamePart, Select, SetExpr, Statement, TableObject};
+
+use crate::ast::{self, Clause};
+use crate::pattern::matchers::common::{get_redis_data_type, is_hash_table_name, is_list_table_name, is_set_table_name, is_string_table_name, is_zset_table_name, RedisDataType};
 
 // --------------------------------
 // INSERT Pattern Analysis Functions - Pure
@@ -14,24 +17,15 @@ pub fn is_insert(stmt: &Statement) -> bool {
 
 /// Get the table name from an INSERT statement
 pub fn get_table_name(stmt: &Statement) -> Option<String> {
+    get_table_name_ref(stmt).map(str::to_string)
+}
+
+/// Borrowing form of `get_table_name`, for matchers that only inspect the name
+pub fn get_table_name_ref(stmt: &Statement) -> Option<&str> {
     match stmt {
-        Statement::Insert(Insert { table, .. }) => {
-            match table {
-                TableObject::TableName(name) => {
-                    if !name.0.is_empty() {
-                        match &name.0[0] {
-                            ObjectNamePart::Identifier(ident) => {
-                                // In a real implementation, we would check if this table name
-                                // references a CTE defined in a WITH clause. Here we're just
-                                // extracting the name for demonstration purposes.
-                                Some(ident.value.clone())
-                            }
-                        }
-                    } else {
-                        None
-                    }    
-                },
-                _ => None,
+        Statement::Insert(Insert { table: TableObject::TableName(name), .. }) => {
+            match name.0.first()? {
+                ObjectNamePart::Identifier(ident) => Some(ident.value.as_str())
             }
         },
         _ => None,
@@ -40,37 +34,36 @@ pub fn get_table_name(stmt: &Statement) -> Option<String> {
 
 /// Check if INSERT statement target is a hash table
 pub fn is_hash_table(stmt: &Statement) -> bool {
-    get_table_name(stmt)
-        .map(|name| name.ends_with("__hash"))
+    get_table_name_ref(stmt)
+        .map(is_hash_table_name)
         .unwrap_or(false)
 }
 
 /// Check if INSERT statement target is a list table
 pub fn is_list_table(stmt: &Statement) -> bool {
-    get_table_name(stmt)
-        .map(|name| name.ends_with("__list"))
+    get_table_name_ref(stmt)
+        .map(is_list_table_name)
         .unwrap_or(false)
 }
 
 /// Check if INSERT statement target is a set table
 pub fn is_set_table(stmt: &Statement) -> bool {
-    get_table_name(stmt)
-        .map(|name| name.ends_with("__set"))
+    get_table_name_ref(stmt)
+        .map(is_set_table_name)
         .unwrap_or(false)
 }
 
 /// Check if INSERT statement target is a sorted set table
 pub fn is_zset_table(stmt: &Statement) -> bool {
-    get_table_name(stmt)
-        .map(|name| name.ends_with("__zset"))
+    get_table_name_ref(stmt)
+        .map(is_zset_table_name)
         .unwrap_or(false)
 }
 
 /// Check if INSERT statement target is a string table (default)
 pub fn is_string_table(stmt: &Statement) -> bool {
-    get_table_name(stmt)
-        .map(|name| !name.ends_with("__hash") && !name.ends_with("__list") && 
-                     !name.ends_with("__set") && !name.ends_with("__zset"))
+    get_table_name_ref(stmt)
+        .map(is_string_table_name)
         .unwrap_or(false)
 }
 
@@ -126,6 +119,32 @@ pub fn has_values(stmt: &Statement) -> bool {
     }
 }
 
+/// Check if every SELECT feeding an INSERT ... SELECT reads a keyed table of `data_type`,
+/// using no clauses beyond a projection and predicates on `columns` (plus `query_clauses`
+/// such as ORDER BY and LIMIT, which a server-side store command can honor)
+fn has_select_source(stmt: &Statement, data_type: RedisDataType, columns: &[&str], query_clauses: &[Clause]) -> bool {
+    let Some(query) = ast::ins_get_source_query(stmt) else {
+        return false;
+    };
+    let Some((_, selects)) = ast::sel_get_set_operands(query) else {
+        return false;
+    };
+    let reads_table = |select: &Select| {
+        ast::sel_get_table_name_ref(select).is_some_and(|table| get_redis_data_type(table) == data_type)
+            && ast::sel_get_key_value(&select.selection).is_some()
+            && select.selection.iter().flat_map(ast::split_conjuncts).all(|predicate| {
+                ast::predicate_column(predicate).is_some_and(|column| columns.contains(&column.as_str()))
+            })
+    };
+    let honored = |clause: &Clause| match clause {
+        Clause::Projection => true,
+        Clause::Predicate(column, _) => columns.contains(&column.as_str()),
+        clause => query_clauses.contains(clause),
+    };
+    selects.iter().all(|select| reads_table(select))
+        && ast::query_get_clauses(query).iter().all(|clause| honored(&clause.clause))
+}
+
 // --------------------------------
 // BNF INSERT Rule Matchers - Direct mapping to BNF rules
 // --------------------------------
@@ -153,4 +172,33 @@ pub fn is_set_add(stmt: &Statement) -> bool {
 /// <zset-add> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "VALUES" "(" <key> "," <member> "," <score> ")"
 pub fn is_zset_add(stmt: &Statement) -> bool {
     is_insert(stmt) && is_zset_table(stmt) && has_exact_columns(stmt, &["key", "member", "score"]) && has_values(stmt)
-}
\ No newline at end of file
+}
+/// <zset-range-store> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "SELECT" ... "FROM" <table> "__zset"
+///                        "WHERE" "key" "=" <key> ["AND" <score-condition>] ["ORDER BY" "score" ["DESC"]] ["LIMIT" <n>]
+pub fn is_zset_range_store(stmt: &Statement) -> bool {
+    is_insert(stmt) && is_zset_table(stmt) && has_exact_columns(stmt, &["key", "member", "score"])
+        && ast::ins_get_source_query(stmt)
+            .is_some_and(|query| ast::sel_get_select(query).is_some() && (query.order_by.is_none() || ast::sel_is_order_by_score(query)))
+        && has_select_source(stmt, RedisDataType::SortedSet, &["key", "score"], &[Clause::OrderBy, Clause::Limit])
+}
+
+/// <set-store> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>
+///                 [("UNION" | "INTERSECT" | "EXCEPT") "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>]...
+pub fn is_set_store(stmt: &Statement) -> bool {
+    is_insert(stmt) && is_set_table(stmt) && has_exact_columns(stmt, &["key", "member"])
+        && has_select_source(stmt, RedisDataType::Set, &["key"], &[])
+}
+
+/// <type-copy> ::= "INSERT" "INTO" <table> ("__list" | "__set" | "__zset") "(key, " <column>... ")"
+///                 "SELECT" (<column> | <literal>)... "FROM" <table> ("__list" | "__set" | "__zset") "WHERE" "key" "=" <key>
+pub fn is_type_copy(stmt: &Statement) -> bool {
+    let Some(select) = ast::ins_get_source_query(stmt).and_then(ast::sel_get_select) else {
+        return false;
+    };
+    let Some(source) = ast::sel_get_table_name_ref(select).map(get_redis_data_type) else {
+        return false;
+    };
+    is_insert(stmt)
+        && ast::ins_get_copied_columns(stmt, &select.projection, source).is_some()
+        && has_select_source(stmt, source, &["key"], &[])
+}
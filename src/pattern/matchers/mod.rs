@@ -6,3 +6,6 @@ pub mod select;
 pub mod insert;
 pub mod update;
 pub mod delete;
+pub mod trigger;
+pub mod session;
+pub mod function;
@@ -3,6 +3,9 @@
 
 use sqlparser::ast::{Expr, SelectItem, Statement};
 use crate::ast;
+use crate::pattern::matchers::common::{is_hash_table_name, is_list_table_name, is_set_table_name, is_string_table_name, is_zset_table_name};
+use crate::expr::{self, Operand, Projection};
+use crate::pattern::extractors::extract_zset_hash_join;
 
 // --------------------------------
 // Basic Pattern Matchers - Pure Functions
@@ -20,7 +23,19 @@ pub fn is_wildcard_select(stmt: &Statement) -> bool {
 pub fn is_single_field_select(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .map(|select| select.projection.len() == 1 && ast::sel_get_field_name(&select.projection[0]).is_some())
+        .map(|select| select.projection.len() == 1 && ast::sel_get_field_name_ref(&select.projection[0]).is_some())
+        .unwrap_or(false)
+}
+
+/// Check if the SELECT statement projects a single metadata pseudo-column (e.g. `__idletime`)
+pub fn is_pseudo_column_select(stmt: &Statement, column: &str) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .map(|select| {
+            select.projection.len() == 1 &&
+            ast::sel_get_field_name_ref(&select.projection[0])
+                .is_some_and(|name| name.eq_ignore_ascii_case(column))
+        })
         .unwrap_or(false)
 }
 
@@ -30,21 +45,83 @@ pub fn is_multi_field_select(stmt: &Statement) -> bool {
         .and_then(ast::sel_get_select)
         .map(|select| {
             select.projection.len() > 1 && 
-            select.projection.iter().all(|item| ast::sel_get_field_name(item).is_some())
+            select.projection.iter().all(|item| ast::sel_get_field_name_ref(item).is_some())
         })
         .unwrap_or(false)
 }
 
+/// The number of plain or aliased identifiers the SELECT projects; None for wildcards
+/// and expressions
+fn projected_field_count(stmt: &Statement) -> Option<usize> {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .filter(|select| select.projection.iter().all(|item| matches!(
+            item,
+            SelectItem::UnnamedExpr(Expr::Identifier(_)) | SelectItem::ExprWithAlias { expr: Expr::Identifier(_), .. }
+        )))
+        .map(|select| select.projection.len())
+}
+
+/// Check if the SELECT projects identifiers and computed columns (CASE or a string function), at least one computed
+pub fn is_case_select(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .and_then(|select| expr::projections(&select.projection))
+        .is_some_and(|columns| columns.iter().any(|(projection, _)| matches!(projection, Projection::Case(_))))
+}
+
+/// Check if the SELECT projects identifiers and computed columns, at least one using COALESCE
+pub fn is_coalesce_select(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .and_then(|select| expr::projections(&select.projection))
+        .is_some_and(|columns| columns.iter().any(|(projection, _)| {
+            matches!(projection, Projection::Case(case) if case.operands().iter().any(|operand| matches!(operand, Operand::Coalesce(_))))
+        }))
+}
+
+/// <lua-filter-select> ::= "SELECT" ("*" | <projection> ["," ...]) "FROM" <table> "WHERE" "key" "=" <value> ["AND" <predicate>]...
+/// (any data type but system tables; every predicate besides the key lookup must compile to Lua)
+pub fn is_lua_filter_select(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .is_some_and(|select| {
+            (is_wildcard_select(stmt) || expr::projections(&select.projection).is_some())
+                && ast::sel_get_table_name_ref(select).is_some_and(|table| !table.starts_with("__"))
+                && ast::sel_get_row_filter(select).is_some()
+        })
+}
+
 // --------------------------------
 // Table Type Matchers - Pure Functions
 // --------------------------------
 
+/// <member-like-filter> ::= "SELECT" ("*" | <projection> ["," ...]) "FROM" <table> ("__set" | "__zset") "WHERE" "key" "=" <value>
+///     "AND" "member" ["NOT"] "LIKE" <pattern> ["AND" <predicate>]... ["ORDER" "BY" <column> ["ASC" | "DESC"] ["," ...]] ["LIMIT" <n>]
+/// (every predicate besides the key lookup must compile to Lua; a lone member pattern is an SSCAN/ZSCAN MATCH instead)
+pub fn is_member_like_filter(stmt: &Statement) -> bool {
+    (is_set_table(stmt) || is_zset_table(stmt)) && has_member_like(stmt) && is_lua_filter_select(stmt)
+        && has_sortable_rows(stmt)
+        && !is_set_scan_match(stmt) && !is_zset_scan_match(stmt)
+}
+
+/// Check if the SELECT reads a system table (e.g. "__clients") with no WHERE clause
+pub fn is_system_table(stmt: &Statement, table_name: &str) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .map(|select| {
+            select.selection.is_none() &&
+            ast::sel_get_table_name_ref(select).is_some_and(|name| name.eq_ignore_ascii_case(table_name))
+        })
+        .unwrap_or(false)
+}
+
 /// Check if the table name has a "__hash" suffix, indicating a Redis Hash
 pub fn is_hash_table(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .and_then(ast::sel_get_table_name)
-        .map(|name| name.ends_with("__hash"))
+        .and_then(ast::sel_get_table_name_ref)
+        .map(is_hash_table_name)
         .unwrap_or(false)
 }
 
@@ -52,8 +129,8 @@ pub fn is_hash_table(stmt: &Statement) -> bool {
 pub fn is_list_table(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .and_then(ast::sel_get_table_name)
-        .map(|name| name.ends_with("__list"))
+        .and_then(ast::sel_get_table_name_ref)
+        .map(is_list_table_name)
         .unwrap_or(false)
 }
 
@@ -61,8 +138,8 @@ pub fn is_list_table(stmt: &Statement) -> bool {
 pub fn is_set_table(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .and_then(ast::sel_get_table_name)
-        .map(|name| name.ends_with("__set"))
+        .and_then(ast::sel_get_table_name_ref)
+        .map(is_set_table_name)
         .unwrap_or(false)
 }
 
@@ -70,8 +147,8 @@ pub fn is_set_table(stmt: &Statement) -> bool {
 pub fn is_zset_table(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .and_then(ast::sel_get_table_name)
-        .map(|name| name.ends_with("__zset"))
+        .and_then(ast::sel_get_table_name_ref)
+        .map(is_zset_table_name)
         .unwrap_or(false)
 }
 
@@ -79,9 +156,17 @@ pub fn is_zset_table(stmt: &Statement) -> bool {
 pub fn is_string_table(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .and_then(ast::sel_get_table_name)
-        .map(|name| !name.ends_with("__hash") && !name.ends_with("__list") && 
-                     !name.ends_with("__set") && !name.ends_with("__zset"))
+        .and_then(ast::sel_get_table_name_ref)
+        .map(is_string_table_name)
+        .unwrap_or(false)
+}
+
+/// Check if the table is a string table declared (via `TransformOptions::with_json_table`) to hold JSON
+pub fn is_json_table(stmt: &Statement, json_tables: &[String]) -> bool {
+    is_string_table(stmt) && ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .and_then(ast::sel_get_table_name_ref)
+        .map(|name| json_tables.iter().any(|table| table.eq_ignore_ascii_case(name)))
         .unwrap_or(false)
 }
 
@@ -93,7 +178,7 @@ pub fn is_string_table(stmt: &Statement) -> bool {
 pub fn has_key_equals(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .map(|select| ast::sel_get_key_value(&select.selection).is_some())
+        .map(|select| ast::sel_get_key_value_ref(&select.selection).is_some())
         .unwrap_or(false)
 }
 
@@ -101,7 +186,7 @@ pub fn has_key_equals(stmt: &Statement) -> bool {
 pub fn has_field_equals(stmt: &Statement, field_name: &str) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
-        .map(|select| ast::sel_get_field_filter(&select.selection, field_name).is_some())
+        .map(|select| ast::sel_get_field_filter_ref(&select.selection, field_name).is_some())
         .unwrap_or(false)
 }
 
@@ -113,6 +198,25 @@ pub fn has_score_range(stmt: &Statement) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if the WHERE clause is exactly "key = value AND member LIKE pattern"
+pub fn has_member_pattern(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .map(|select| ast::sel_get_member_pattern(&select.selection).is_some())
+        .unwrap_or(false)
+}
+
+/// Check if the WHERE clause has a "member [NOT] LIKE pattern" condition among its conjuncts
+pub fn has_member_like(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .and_then(|select| select.selection.as_ref())
+        .map(|selection| ast::split_conjuncts(selection).into_iter().any(|conjunct| {
+            matches!(conjunct, Expr::Like { .. }) && ast::predicate_column(conjunct).as_deref() == Some("member")
+        }))
+        .unwrap_or(false)
+}
+
 /// Check if the query has ORDER BY score DESC
 pub fn has_order_by_score_desc(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
@@ -120,6 +224,13 @@ pub fn has_order_by_score_desc(stmt: &Statement) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if the query has any ORDER BY clause
+pub fn has_order_by(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .map(|query| query.order_by.is_some())
+        .unwrap_or(false)
+}
+
 /// Check if the WHERE clause has a "key IN (...)" condition
 pub fn has_key_in(stmt: &Statement) -> bool {
     ast::sel_get_query(stmt)
@@ -136,10 +247,12 @@ pub fn is_count_star(stmt: &Statement) -> bool {
         .unwrap_or(false)
 }
 
-/// Check if the SELECT has a specific aggregate function (e.g. AVG, SUM, MIN, MAX)
+/// Check if the SELECT has a specific aggregate function (e.g. AVG, SUM, MIN, MAX), with
+/// no HAVING or one comparing the aggregate with numbers (evaluated in the Lua script)
 pub fn has_aggregate(stmt: &Statement, func_name: &str) -> bool {
     ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
+        .filter(|select| ast::sel_get_having(select).is_some())
         .and_then(|select| ast::sel_get_aggregate(select))
         .map(|agg| agg.function == func_name.to_uppercase())
         .unwrap_or(false)
@@ -183,10 +296,69 @@ pub fn has_limit(stmt: &Statement) -> bool {
         .unwrap_or(false)
 }
 
+/// Check if ORDER BY (if any) lists only plain columns and LIMIT (if any) is a number,
+/// so a Lua filter can sort and cut its rows
+pub fn has_sortable_rows(stmt: &Statement) -> bool {
+    ast::sel_get_query(stmt)
+        .map(|query| ast::sel_get_order_columns(query).is_some() && (query.limit.is_none() || ast::sel_get_limit(query).is_some()))
+        .unwrap_or(false)
+}
+
 // --------------------------------
 // BNF Rule Matchers - Direct mapping to BNF rules
 // --------------------------------
 
+/// <object-idletime> ::= "SELECT" "__idletime" "FROM" <table> "WHERE" "key" "=" <value>
+pub fn is_object_idletime(stmt: &Statement) -> bool {
+    is_pseudo_column_select(stmt, "__idletime") && has_key_equals(stmt)
+}
+
+/// <object-freq> ::= "SELECT" "__freq" "FROM" <table> "WHERE" "key" "=" <value>
+pub fn is_object_freq(stmt: &Statement) -> bool {
+    is_pseudo_column_select(stmt, "__freq") && has_key_equals(stmt)
+}
+
+/// <server-info> ::= "SELECT" "*" "FROM" "__server_info"
+pub fn is_server_info(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_system_table(stmt, "__server_info")
+}
+
+/// <client-list> ::= "SELECT" "*" "FROM" "__clients"
+pub fn is_client_list(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_system_table(stmt, "__clients")
+}
+
+/// <slowlog-get> ::= "SELECT" "*" "FROM" "__slowlog" ["LIMIT" <count>]
+pub fn is_slowlog_get(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_system_table(stmt, "__slowlog")
+}
+
+/// <keys-browse> ::= "SELECT" "*" "FROM" "__keys" ["WHERE" "key" ("LIKE" | "=") <pattern>] ["LIMIT" <count>]
+pub fn is_keys_browse(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .is_some_and(|select| {
+            ast::sel_get_table_name_ref(select).is_some_and(|name| name.eq_ignore_ascii_case("__keys"))
+                && ast::sel_get_key_pattern(&select.selection).is_some()
+        })
+}
+
+/// <keys-count> ::= "SELECT" "COUNT(*)" "FROM" "__keys"
+pub fn is_keys_count(stmt: &Statement) -> bool {
+    is_count_star(stmt) && is_system_table(stmt, "__keys")
+}
+
+/// <keys-count-pattern> ::= "SELECT" "COUNT(*)" "FROM" "__keys" "WHERE" "key" ("LIKE" | "=") <pattern>
+pub fn is_keys_count_pattern(stmt: &Statement) -> bool {
+    is_count_star(stmt) && ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .is_some_and(|select| {
+            ast::sel_get_table_name_ref(select).is_some_and(|name| name.eq_ignore_ascii_case("__keys"))
+                && select.selection.is_some()
+                && ast::sel_get_key_pattern(&select.selection).is_some()
+        })
+}
+
 /// <string-get-multi> ::= "SELECT" "*" "FROM" <table> "WHERE" "key" "IN" "(" <value1> "," <value2> ... ")"
 pub fn is_string_get_multi(stmt: &Statement) -> bool {
     is_wildcard_select(stmt) && is_string_table(stmt) && has_key_in(stmt)
@@ -197,19 +369,43 @@ pub fn is_string_get(stmt: &Statement) -> bool {
     is_wildcard_select(stmt) && is_string_table(stmt) && has_key_equals(stmt)
 }
 
+/// <json-get-fields> ::= "SELECT" <field1> ["," <field2>]... "FROM" <json-table> "WHERE" "key" "=" <value>
+/// (a bare `value` projection still reads the whole document with GET)
+pub fn is_json_get_fields(stmt: &Statement, json_tables: &[String]) -> bool {
+    (is_single_field_select(stmt) || is_multi_field_select(stmt)) &&
+    !is_string_get_value(stmt) &&
+    is_json_table(stmt, json_tables) && has_key_equals(stmt)
+}
+
+/// <hash-scan> ::= "SELECT" "*" "FROM" <table> "__hash" "WHERE" "key" "=" <value> "LIMIT" <n>
+pub fn is_hash_scan(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt) && has_limit(stmt)
+}
+
 /// <hash-getall> ::= "SELECT" "*" "FROM" <table> "__hash" "WHERE" "key" "=" <value>
 pub fn is_hash_getall(stmt: &Statement) -> bool {
     is_wildcard_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
 }
 
-/// <hash-get> ::= "SELECT" <field> "FROM" <table> "__hash" "WHERE" "key" "=" <value>
+/// <hash-get> ::= "SELECT" <field> ["AS" <alias>] "FROM" <table> "__hash" "WHERE" "key" "=" <value>
 pub fn is_hash_get(stmt: &Statement) -> bool {
-    is_single_field_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
+    projected_field_count(stmt) == Some(1) && is_hash_table(stmt) && has_key_equals(stmt)
 }
 
-/// <hash-hmget> ::= "SELECT" <field1> ["," <field2>]... "FROM" <table> "__hash" "WHERE" "key" "=" <value>
+/// <hash-hmget> ::= "SELECT" <field1> ["AS" <alias1>] ["," <field2> ["AS" <alias2>]]... "FROM" <table> "__hash" "WHERE" "key" "=" <value>
 pub fn is_hash_hmget(stmt: &Statement) -> bool {
-    is_multi_field_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
+    projected_field_count(stmt).is_some_and(|n| n > 1) && is_hash_table(stmt) && has_key_equals(stmt)
+}
+
+/// <hash-coalesce> ::= "SELECT" ("COALESCE" "(" <field> "," ... ")" | <field> | ...) ["AS" <alias>] ["," ...] "FROM" <table> "__hash" "WHERE" "key" "=" <value>
+pub fn is_hash_coalesce(stmt: &Statement) -> bool {
+    is_coalesce_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
+}
+
+/// <hash-getall-projected> ::= "SELECT" (<field> | "CASE" ... "END" | <function> "(" ... ")") ["AS" <alias>] ["," ...] "FROM" <table> "__hash" "WHERE" "key" "=" <value>
+/// (computed projections - CASE, UPPER, LOWER, TRIM, REPLACE - are read with HGETALL and evaluated when the reply is decoded)
+pub fn is_hash_getall_projected(stmt: &Statement) -> bool {
+    is_case_select(stmt) && is_hash_table(stmt) && has_key_equals(stmt)
 }
 
 /// <hash-count> ::= SELECT COUNT(*) FROM table__hash WHERE key = value
@@ -254,6 +450,16 @@ pub fn is_set_count(stmt: &Statement) -> bool {
     is_count_star(stmt) && is_set_table(stmt) && has_key_equals(stmt)
 }
 
+/// <set-scan> ::= "SELECT" "*" "FROM" <table> "__set" "WHERE" "key" "=" <value> "LIMIT" <n>
+pub fn is_set_scan(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_set_table(stmt) && has_key_equals(stmt) && has_limit(stmt)
+}
+
+/// <set-scan-match> ::= "SELECT" "*" "FROM" <table> "__set" "WHERE" "key" "=" <value> "AND" "member" "LIKE" <pattern> ["LIMIT" <n>]
+pub fn is_set_scan_match(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_set_table(stmt) && has_member_pattern(stmt) && !has_order_by(stmt)
+}
+
 /// <set-getall> ::= "SELECT" "*" "FROM" <table> "__set" "WHERE" "key" "=" <value>
 pub fn is_set_getall(stmt: &Statement) -> bool {
     is_wildcard_select(stmt) && is_set_table(stmt) && has_key_equals(stmt)
@@ -265,6 +471,34 @@ pub fn is_set_ismember(stmt: &Statement) -> bool {
     has_key_equals(stmt) && has_field_equals(stmt, "member")
 }
 
+/// <zset-get-limit> ::= "SELECT" "*" "FROM" <table> "__zset" "WHERE" "key" "=" <value> "LIMIT" <n>
+pub fn is_zset_get_limit(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_zset_table(stmt) && 
+    has_key_equals(stmt) && has_limit(stmt) && !has_order_by(stmt)
+}
+
+/// <zset-top-per-group> ::= "SELECT" "*" "FROM" <table> "__zset" "WHERE" "key" "IN" "(" "SELECT" "member"
+///     "FROM" <table> "__set" "WHERE" "key" "=" <value> ")" "ORDER" "BY" "score" ["ASC" | "DESC"] "LIMIT" <n>
+pub fn is_zset_top_per_group(stmt: &Statement) -> bool {
+    let Some(query) = ast::sel_get_query(stmt) else { return false };
+    is_wildcard_select(stmt) && is_zset_table(stmt)
+        && ast::sel_get_select(query).is_some_and(|select| ast::sel_get_key_in_set(&select.selection).is_some())
+        && ast::sel_get_score_order(query).is_some()
+        && query_has_limit(query).is_some()
+}
+
+/// <zset-hash-join> ::= "SELECT" <columns> "FROM" <table> "__zset" <alias> ["LEFT"] "JOIN" <table> "__hash" <alias>
+///     "ON" <alias> "." <column> "=" [<prefix> "||"] <alias> ".member" "WHERE" <alias> ".key" "=" <value>
+///     ["ORDER" "BY" <alias> ".score" ["ASC" | "DESC"]] ["LIMIT" <n>] ["OFFSET" <m>]
+pub fn is_zset_hash_join(stmt: &Statement) -> bool {
+    extract_zset_hash_join(stmt).is_some()
+}
+
+/// <zset-scan-match> ::= "SELECT" "*" "FROM" <table> "__zset" "WHERE" "key" "=" <value> "AND" "member" "LIKE" <pattern> ["LIMIT" <n>]
+pub fn is_zset_scan_match(stmt: &Statement) -> bool {
+    is_wildcard_select(stmt) && is_zset_table(stmt) && has_member_pattern(stmt) && !has_order_by(stmt)
+}
+
 /// <zset-get> ::= "SELECT" "*" "FROM" <table> "__zset" "WHERE" "key" "=" <value>
 pub fn is_zset_getall(stmt: &Statement) -> bool {
     is_wildcard_select(stmt) && is_zset_table(stmt) && has_key_equals(stmt)
@@ -288,10 +522,10 @@ pub fn is_zset_get_reversed(stmt: &Statement) -> bool {
     has_key_equals(stmt) && has_order_by_score_desc(stmt)
 }
 
-/// <zset-count-score-range> ::= SELECT COUNT(*) FROM table__zset WHERE key = value AND score BETWEEN min AND max
+/// <zset-count-score-range> ::= SELECT COUNT(*) FROM table__zset WHERE key = value AND (score BETWEEN min AND max | score <comparison> <score> ...)
 pub fn is_zset_count_score_range(stmt: &Statement) -> bool {
     is_count_star(stmt) && is_zset_table(stmt) && 
-    has_key_equals(stmt) && has_score_between(stmt)
+    has_key_equals(stmt) && (has_score_between(stmt) || has_score_range(stmt))
 }
 
 /// <zset-count> ::= SELECT COUNT(*) FROM table__zset WHERE key = value
@@ -339,7 +573,7 @@ pub fn is_string_get_value(stmt: &Statement) -> bool {
     
     let is_value_field = match &select.projection[0] {
         SelectItem::UnnamedExpr(Expr::Identifier(ident)) => 
-            ident.value.to_lowercase() == "value",
+            ident.value.eq_ignore_ascii_case("value"),
         _ => false,
     };
     
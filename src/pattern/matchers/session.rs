@@ -0,0 +1,10 @@
+// matchers/session.rs - Predicate functions for session statements
+// USE maps onto SELECT, which switches the connection's logical database
+
+use sqlparser::ast::Statement;
+use crate::ast::use_get_database;
+
+/// <use-database> ::= "USE" ["DATABASE"] ("db" <index> | <index>)
+pub fn is_use_database(stmt: &Statement) -> bool {
+    use_get_database(stmt).is_some()
+}
@@ -0,0 +1,20 @@
+// matchers/trigger.rs - Predicate functions for CREATE TRIGGER pattern matching
+// Triggers map onto Redis keyspace notifications rather than stored procedures
+
+use sqlparser::ast::Statement;
+use crate::ast::{trg_get_key_pattern, trg_get_table_name, trg_is_after, trg_is_unfiltered};
+
+/// Check if statement is a CREATE TRIGGER statement
+pub fn is_create_trigger(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::CreateTrigger { .. })
+}
+
+/// <keyspace-subscribe> ::= "CREATE" "TRIGGER" <name> "AFTER" <events> "ON" <table>
+///                          ["WHEN" "(" "key" ("LIKE" | "=") <pattern> ")"] ...
+/// (without WHEN, the table needs a key prefix to scope the subscription; the transformer checks that)
+pub fn is_keyspace_subscription(stmt: &Statement) -> bool {
+    is_create_trigger(stmt) &&
+    trg_is_after(stmt) &&
+    trg_get_table_name(stmt).is_some() &&
+    (trg_get_key_pattern(stmt).is_some() || trg_is_unfiltered(stmt))
+}
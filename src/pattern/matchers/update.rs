@@ -3,11 +3,13 @@
 
 use sqlparser::ast::Statement;
 use crate::ast::{
-    upd_get_table_name,
-    upd_get_key_value,
+    upd_get_table_name_ref,
+    upd_get_key_value_ref,
     upd_get_field_filter,
     upd_get_assignments,
+    upd_get_case_assignments,
 };
+use crate::pattern::matchers::common::{is_hash_table_name, is_list_table_name, is_set_table_name, is_string_table_name, is_zset_table_name};
 
 
 // --------------------------------
@@ -21,48 +23,47 @@ pub fn is_update(stmt: &Statement) -> bool {
 
 /// Check if UPDATE statement target is a hash table
 pub fn is_hash_table(stmt: &Statement) -> bool {
-    match upd_get_table_name(stmt) {
-        Some(name) => name.ends_with("__hash"),
+    match upd_get_table_name_ref(stmt) {
+        Some(name) => is_hash_table_name(name),
         None => false,
     }
 }
 
 /// Check if UPDATE statement target is a list table
 pub fn is_list_table(stmt: &Statement) -> bool {
-    match upd_get_table_name(stmt) {
-        Some(name) => name.ends_with("__list"),
+    match upd_get_table_name_ref(stmt) {
+        Some(name) => is_list_table_name(name),
         None => false,
     }
 }
 
 /// Check if UPDATE statement target is a set table
 pub fn is_set_table(stmt: &Statement) -> bool {
-    match upd_get_table_name(stmt) {
-        Some(name) => name.ends_with("__set"),
+    match upd_get_table_name_ref(stmt) {
+        Some(name) => is_set_table_name(name),
         None => false,
     }
 }
 
 /// Check if UPDATE statement target is a sorted set table
 pub fn is_zset_table(stmt: &Statement) -> bool {
-    match upd_get_table_name(stmt) {
-        Some(name) => name.ends_with("__zset"),
+    match upd_get_table_name_ref(stmt) {
+        Some(name) => is_zset_table_name(name),
         None => false,
     }
 }
 
 /// Check if UPDATE statement target is a string table (default)
 pub fn is_string_table(stmt: &Statement) -> bool {
-    match upd_get_table_name(stmt) {
-        Some(name) => !name.ends_with("__hash") && !name.ends_with("__list") && 
-                     !name.ends_with("__set") && !name.ends_with("__zset"),
+    match upd_get_table_name_ref(stmt) {
+        Some(name) => is_string_table_name(name),
         None => false,
     }
 }
 
 /// Check if the UPDATE statement has a key condition
 pub fn has_key_equals(stmt: &Statement) -> bool {
-    upd_get_key_value(stmt).is_some()
+    upd_get_key_value_ref(stmt).is_some()
 }
 
 /// Check if the UPDATE statement has a field condition
@@ -93,6 +94,13 @@ pub fn is_hash_update(stmt: &Statement) -> bool {
     upd_get_assignments(stmt).map_or(false, |a| !a.is_empty())
 }
 
+/// <hash-case-update> ::= "UPDATE" <table> "__hash" "SET" <field> "=" ("CASE" ... "END" | <function> "(" ... ")" | <field> | <value>) ["," ...]... "WHERE" "key" "=" <key>
+/// (at least one assignment reads the hash: a CASE, a string function of a field, or a field)
+pub fn is_hash_case_update(stmt: &Statement) -> bool {
+    is_update(stmt) && is_hash_table(stmt) && has_key_equals(stmt) &&
+    upd_get_case_assignments(stmt).is_some_and(|a| a.iter().any(|(_, case)| case.is_computed()))
+}
+
 /// <list-update> ::= "UPDATE" <table> "__list" "SET" "value" "=" <new-value> "WHERE" "key" "=" <key> "AND" "index" "=" <index>
 pub fn is_list_update(stmt: &Statement) -> bool {
     is_update(stmt) && is_list_table(stmt) && has_key_equals(stmt) && 
@@ -0,0 +1,314 @@
+// plan.rs - Multi-step outputs: commands, scripts, client-side computation, and barriers
+// A Plan is what a statement runs when one command isn't enough; it renders as text or JSON
+// and runs with `Executor::execute_plan`. `Plan::for_update` builds WATCH/MULTI recipes.
+
+use std::fmt;
+
+use sqlparser::ast::{BinaryOperator, Expr, Statement};
+
+use crate::ast;
+use crate::audit::json_string;
+use crate::commands::RedisCommand;
+use crate::expr::Case;
+use crate::lua::script_sha1;
+use crate::pattern::matchers::common::{is_hash_table_name, is_string_table_name};
+use crate::reply::{Reply, Row};
+
+/// One step of a plan
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Run a command
+    Command(RedisCommand),
+    /// Run a Lua script with its keys and arguments (`EVAL script numkeys key... arg...`)
+    Eval { script: String, keys: Vec<String>, args: Vec<String> },
+    /// Compute values client-side from the replies of earlier steps, then run the commands
+    /// writing them
+    ClientCompute(ClientCompute),
+    /// Wait for the replies of every earlier step before running later ones: pipelines
+    /// stop here
+    Barrier,
+}
+
+impl Step {
+    /// The command a Command or Eval step runs
+    pub fn to_command(&self) -> Option<RedisCommand> {
+        match self {
+            Step::Command(command) => Some(command.clone()),
+            Step::Eval { script, keys, args } => {
+                let args = [script.clone(), keys.len().to_string()].into_iter().chain(keys.iter().cloned()).chain(args.iter().cloned());
+                Some(RedisCommand::new("EVAL", args.collect()))
+            }
+            Step::ClientCompute(_) | Step::Barrier => None,
+        }
+    }
+}
+
+impl From<RedisCommand> for Step {
+    /// An EVAL becomes an Eval step, anything else a Command step
+    fn from(command: RedisCommand) -> Self {
+        let count = command.args.get(1).and_then(|n| n.parse::<usize>().ok()).filter(|n| command.args.len() >= n + 2);
+        match count {
+            Some(count) if command.command.eq_ignore_ascii_case("EVAL") => Step::Eval {
+                script: command.args[0].clone(),
+                keys: command.args[2..2 + count].to_vec(),
+                args: command.args[2 + count..].to_vec(),
+            },
+            _ => Step::Command(command),
+        }
+    }
+}
+
+/// Values computed from earlier replies: the assignments of an UPDATE, evaluated over the
+/// rows its reads return, and written back with SET/DEL or HSET/HDEL
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientCompute {
+    /// The steps whose replies are read (GET or HMGET), one per key written
+    pub inputs: Vec<usize>,
+    /// Each computed column with its SQL expression
+    pub assignments: Vec<(String, String)>,
+    hash: bool,
+    compiled: Vec<(String, Case)>,
+}
+
+impl ClientCompute {
+    /// The writes of the values computed from each input's read command and reply: SET of
+    /// the value (DEL if NULL), or HSET of the computed columns and HDEL of those computed NULL
+    pub fn commands(&self, inputs: &[(RedisCommand, Reply)]) -> Vec<RedisCommand> {
+        inputs.iter()
+            .flat_map(|(read, reply)| {
+                let row = read_row(read, reply, self.hash);
+                let values: Vec<(&str, Option<String>)> = self.compiled.iter()
+                    .map(|(column, case)| (column.as_str(), case.evaluate(&row)))
+                    .collect();
+                self.writes(&read.args[0], &values)
+            })
+            .collect()
+    }
+
+    /// The writes of one key's computed values
+    fn writes(&self, key: &str, values: &[(&str, Option<String>)]) -> Vec<RedisCommand> {
+        if !self.hash {
+            return match values.first() {
+                Some((_, Some(value))) => vec![RedisCommand::new("SET", vec![key.to_string(), value.clone()])],
+                _ => vec![RedisCommand::new("DEL", vec![key.to_string()])],
+            };
+        }
+        let set: Vec<String> = values.iter()
+            .filter_map(|(column, value)| Some([column.to_string(), value.clone()?]))
+            .flatten()
+            .collect();
+        let deleted: Vec<String> = values.iter().filter(|(_, value)| value.is_none()).map(|(column, _)| column.to_string()).collect();
+        let mut writes = Vec::new();
+        if !set.is_empty() {
+            writes.push(RedisCommand::new("HSET", std::iter::once(key.to_string()).chain(set).collect()));
+        }
+        if !deleted.is_empty() {
+            writes.push(RedisCommand::new("HDEL", std::iter::once(key.to_string()).chain(deleted).collect()));
+        }
+        writes
+    }
+
+    /// The writes for `key` with `{column}` standing for each computed value
+    fn template(&self, key: &str) -> Vec<RedisCommand> {
+        let values: Vec<(&str, Option<String>)> = self.compiled.iter()
+            .map(|(column, _)| (column.as_str(), Some(format!("{{{}}}", column))))
+            .collect();
+        self.writes(key, &values)
+    }
+}
+
+/// The steps a statement runs, in order. Command and Eval steps between barriers may be
+/// pipelined.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Plan {
+    pub steps: Vec<Step>,
+}
+
+impl Plan {
+    pub fn new(steps: Vec<Step>) -> Self {
+        Self { steps }
+    }
+
+    /// A plan running `commands` in order, EVALs as Eval steps
+    pub fn from_commands(commands: Vec<RedisCommand>) -> Self {
+        Self { steps: commands.into_iter().map(Step::from).collect() }
+    }
+
+    /// An optimistic-locking recipe for an UPDATE of a string or hash table by `key = ...`
+    /// or `key IN (...)` (and nothing else) whose assignments read a column (`SET value =
+    /// '>' || value`, `SET name = UPPER(name)`); None otherwise. A string table can only
+    /// read and assign `value`. The steps WATCH the keys, read them (GET, or HMGET of the
+    /// columns read), then compute the values inside MULTI ... EXEC; a nil EXEC reply means a
+    /// watched key changed, and the plan should be run again.
+    pub fn for_update(stmt: &Statement) -> Option<Self> {
+        let Statement::Update { assignments: exprs, selection, .. } = stmt else {
+            return None;
+        };
+        let table = ast::upd_get_table_name(stmt)?;
+        let hash = is_hash_table_name(&table);
+        let keys = update_keys(selection.as_ref()?)?;
+        let compiled = ast::upd_get_case_assignments(stmt)?;
+        if !compiled.iter().any(|(_, case)| !case.columns().is_empty()) {
+            return None;
+        }
+        let mut columns: Vec<String> = Vec::new();
+        for (column, case) in &compiled {
+            for read in case.columns() {
+                if !columns.contains(&read) {
+                    columns.push(read);
+                }
+            }
+            if !hash && column != "value" {
+                return None;
+            }
+        }
+        if !hash && (!is_string_table_name(&table) || columns.iter().any(|column| column != "value")) {
+            return None;
+        }
+
+        let reads: Vec<Step> = keys.iter()
+            .map(|key| Step::Command(match hash {
+                true => RedisCommand::new("HMGET", std::iter::once(key).chain(&columns).cloned().collect()),
+                false => RedisCommand::new("GET", vec![key.clone()]),
+            }))
+            .collect();
+        let assignments = compiled.iter().zip(exprs)
+            .map(|((column, _), assignment)| (column.clone(), assignment.value.to_string()))
+            .collect();
+        let compute = ClientCompute { inputs: (1..=keys.len()).collect(), assignments, hash, compiled };
+        let mut steps = vec![Step::Command(RedisCommand::new("WATCH", keys))];
+        steps.extend(reads);
+        steps.push(Step::Barrier);
+        steps.push(Step::Command(RedisCommand::new("MULTI", Vec::<String>::new())));
+        steps.push(Step::ClientCompute(compute));
+        steps.push(Step::Command(RedisCommand::new("EXEC", Vec::<String>::new())));
+        Some(Self { steps })
+    }
+
+    /// The commands of the Command and Eval steps, in order (not those computed client-side)
+    pub fn commands(&self) -> Vec<RedisCommand> {
+        self.steps.iter().filter_map(Step::to_command).collect()
+    }
+
+    /// Rewrite the commands of the Command and Eval steps together with `rewrite`, which may
+    /// prepend commands (such as a database's SELECT); input indices follow the shift
+    pub fn rewrite_commands(self, rewrite: impl FnOnce(Vec<RedisCommand>) -> Vec<RedisCommand>) -> Self {
+        let commands = self.commands();
+        let count = commands.len();
+        let rewritten = rewrite(commands);
+        let shift = rewritten.len().saturating_sub(count);
+        let mut rewritten = rewritten.into_iter().map(Step::from);
+        let mut steps: Vec<Step> = rewritten.by_ref().take(shift).collect();
+        for step in self.steps {
+            steps.push(match step {
+                Step::Command(_) | Step::Eval { .. } => rewritten.next().unwrap_or(step),
+                Step::ClientCompute(mut compute) => {
+                    compute.inputs.iter_mut().for_each(|input| *input += shift);
+                    Step::ClientCompute(compute)
+                }
+                Step::Barrier => Step::Barrier,
+            });
+        }
+        Self { steps }
+    }
+
+    /// A stable content hash: the lowercase hex SHA-1 of the steps, each encoded as RESP
+    /// (Eval steps as their EVAL command, so `from_commands` plans of equal commands hash
+    /// equal). Equal plans hash equal across runs and releases, so the hash can deduplicate
+    /// plans or serve as a cache key (an ETag) for repeated transforms.
+    pub fn content_hash(&self) -> String {
+        let encoded: String = self.steps.iter()
+            .map(|step| match step {
+                Step::Command(_) | Step::Eval { .. } => step.to_command().map(|command| command.to_resp()).unwrap_or_default(),
+                Step::ClientCompute(compute) => {
+                    let inputs = std::iter::once(compute.inputs.len()).chain(compute.inputs.iter().copied()).map(|n| n.to_string());
+                    let assignments = compute.assignments.iter().flat_map(|(column, expr)| [column.clone(), expr.clone()]);
+                    RedisCommand::new("~COMPUTE", inputs.chain(assignments).collect()).to_resp()
+                }
+                Step::Barrier => RedisCommand::new("~BARRIER", Vec::<String>::new()).to_resp(),
+            })
+            .collect();
+        script_sha1(&encoded)
+    }
+
+    /// The plan as one line of JSON: `{"steps": [...]}`, each step an object with a `type`
+    /// of `command` (with `command` and `args`), `eval` (`script`, `keys`, `args`),
+    /// `client_compute` (`inputs` and `assignments`, column to SQL expression), or `barrier`
+    pub fn to_json(&self) -> String {
+        let strings = |values: &[String]| values.iter().map(|value| json_string(value)).collect::<Vec<_>>().join(",");
+        let steps: Vec<String> = self.steps.iter()
+            .map(|step| match step {
+                Step::Command(command) => format!(
+                    "{{\"type\":\"command\",\"command\":{},\"args\":[{}]}}",
+                    json_string(&command.command),
+                    strings(&command.args),
+                ),
+                Step::Eval { script, keys, args } => format!(
+                    "{{\"type\":\"eval\",\"script\":{},\"keys\":[{}],\"args\":[{}]}}",
+                    json_string(script),
+                    strings(keys),
+                    strings(args),
+                ),
+                Step::ClientCompute(compute) => format!(
+                    "{{\"type\":\"client_compute\",\"inputs\":[{}],\"assignments\":{{{}}}}}",
+                    compute.inputs.iter().map(usize::to_string).collect::<Vec<_>>().join(","),
+                    compute.assignments.iter().map(|(column, expr)| format!("{}:{}", json_string(column), json_string(expr))).collect::<Vec<_>>().join(","),
+                ),
+                Step::Barrier => "{\"type\":\"barrier\"}".to_string(),
+            })
+            .collect();
+        format!("{{\"steps\":[{}]}}", steps.join(","))
+    }
+}
+
+/// One numbered line per step; a client-side computation lists its assignments and the
+/// writes it runs, with `{column}` standing for each computed value
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match step {
+                Step::Command(_) | Step::Eval { .. } => {
+                    let command = step.to_command().map(|command| command.to_quoted_string());
+                    write!(f, "{}. {}", i, command.unwrap_or_default())?;
+                }
+                Step::ClientCompute(compute) => {
+                    let inputs: Vec<String> = compute.inputs.iter().map(usize::to_string).collect();
+                    let assignments: Vec<String> = compute.assignments.iter().map(|(column, expr)| format!("{} = {}", column, expr)).collect();
+                    write!(f, "{}. compute from {}: {}", i, inputs.join(", "), assignments.join(", "))?;
+                    let keys = compute.inputs.iter().filter_map(|&input| self.steps.get(input)?.to_command()?.args.first().cloned());
+                    for write in keys.flat_map(|key| compute.template(&key)) {
+                        write!(f, "\n   {}", write.to_quoted_string())?;
+                    }
+                }
+                Step::Barrier => write!(f, "{}. barrier", i)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The keys an UPDATE's WHERE clause names, when it is exactly `key = ...` or `key IN (...)`
+fn update_keys(selection: &Expr) -> Option<Vec<String>> {
+    match selection {
+        Expr::BinaryOp { left, op: BinaryOperator::Eq, right } => match left.as_ref() {
+            Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("key") => Some(vec![ast::upd_extract_value(right)?]),
+            _ => None,
+        },
+        Expr::InList { negated: false, .. } => ast::sel_get_key_in_values(&Some(selection.clone())),
+        _ => None,
+    }
+}
+
+/// The row a read's reply holds: `value` for GET, the read fields for HMGET (nil omitted)
+fn read_row(read: &RedisCommand, reply: &Reply, hash: bool) -> Row {
+    match (hash, reply) {
+        (true, Reply::Array(values)) => read.args[1..].iter().zip(values)
+            .filter_map(|(column, value)| Some((column.clone(), value.as_text()?)))
+            .collect(),
+        (false, reply) => reply.as_text().map(|value| vec![("value".to_string(), value)]).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
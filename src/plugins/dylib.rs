@@ -0,0 +1,96 @@
+// plugins/dylib.rs - Minimal dynamic library loading (dlopen/dlsym)
+
+use std::path::Path;
+
+#[cfg(unix)]
+mod sys {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    pub const RTLD_NOW: c_int = 2;
+
+    #[cfg_attr(any(target_os = "linux", target_os = "android"), link(name = "dl"))]
+    extern "C" {
+        pub fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+        pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+        pub fn dlclose(handle: *mut c_void) -> c_int;
+        pub fn dlerror() -> *mut c_char;
+    }
+}
+
+/// An open shared library, closed on drop
+pub struct Library {
+    #[cfg(unix)]
+    handle: *mut std::os::raw::c_void,
+}
+
+// SAFETY: the handle is only passed to dlsym and dlclose, which are thread-safe; the
+// library stays loaded until the single owner drops it
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+#[cfg(unix)]
+impl Library {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let filename = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| "path contains a NUL byte".to_string())?;
+        // SAFETY: filename is a valid NUL-terminated string
+        let handle = unsafe { sys::dlopen(filename.as_ptr(), sys::RTLD_NOW) };
+        if handle.is_null() {
+            return Err(last_error());
+        }
+        Ok(Self { handle })
+    }
+
+    /// Look up a symbol's address.
+    ///
+    /// # Safety
+    /// The caller must ensure the symbol really has type `T`.
+    pub unsafe fn symbol<T>(&self, name: &str) -> Result<*const T, String> {
+        let symbol = std::ffi::CString::new(name)
+            .map_err(|_| "symbol contains a NUL byte".to_string())?;
+        let ptr = sys::dlsym(self.handle, symbol.as_ptr());
+        if ptr.is_null() {
+            return Err(format!("missing symbol {}", name));
+        }
+        Ok(ptr as *const T)
+    }
+}
+
+#[cfg(unix)]
+fn last_error() -> String {
+    // SAFETY: dlerror returns NULL or a NUL-terminated string owned by libdl
+    unsafe {
+        let msg = sys::dlerror();
+        if msg.is_null() {
+            "unknown dlopen error".to_string()
+        } else {
+            std::ffi::CStr::from_ptr(msg).to_string_lossy().into_owned()
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Library {
+    fn drop(&mut self) {
+        // SAFETY: handle came from a successful dlopen and is closed once
+        unsafe {
+            sys::dlclose(self.handle);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+impl Library {
+    pub fn open(_path: &Path) -> Result<Self, String> {
+        Err("dynamic plugins are only supported on Unix platforms".to_string())
+    }
+
+    /// # Safety
+    /// Never called: libraries cannot be opened on this platform.
+    pub unsafe fn symbol<T>(&self, _name: &str) -> Result<*const T, String> {
+        unreachable!()
+    }
+}
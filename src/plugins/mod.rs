@@ -0,0 +1,158 @@
+// plugins/mod.rs - Rule/template bundles loaded from shared libraries
+// Plugins are discovered in configured directories and registered at startup
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::rules::Rule;
+
+mod dylib;
+
+use dylib::Library;
+
+/// Bumped whenever `PluginDeclaration` or `PluginRegistry` change layout
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Version of this crate; plugins must be built against the same version
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the static every plugin library exports (see `export_plugin!`)
+pub const PLUGIN_SYMBOL: &str = "SQL_REDIS_PLUGIN";
+
+/// Entry point exported by a plugin library.
+///
+/// Rules cross the library boundary as Rust trait objects, so plugins must be
+/// compiled with the same compiler and crate version as the host.
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub crate_version: &'static str,
+    pub name: &'static str,
+    pub register: fn(&mut PluginRegistry),
+}
+
+/// Collects the rules and templates a plugin contributes
+#[derive(Default)]
+pub struct PluginRegistry {
+    pub(crate) rules: Vec<Box<dyn Rule>>,
+    pub(crate) templates: Vec<(String, String)>,
+}
+
+impl PluginRegistry {
+    /// Add a rule; plugin rules are tried before the built-in rules
+    pub fn register_rule(&mut self, rule: Box<dyn Rule>) {
+        self.rules.push(rule);
+    }
+
+    /// Add a Tera template referenced by the plugin's rules
+    pub fn register_template(&mut self, name: &str, source: &str) {
+        self.templates.push((name.to_string(), source.to_string()));
+    }
+}
+
+/// Declare the plugin entry point in a `cdylib` crate
+///
+/// ```ignore
+/// fn register(registry: &mut sql_redis::plugins::PluginRegistry) {
+///     registry.register_template("acme_get", "ACME.GET {{ key }}");
+///     registry.register_rule(sql_redis::rule! { /* ... */ });
+/// }
+/// sql_redis::export_plugin!("acme", register);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($name:expr, $register:expr) => {
+        #[no_mangle]
+        pub static SQL_REDIS_PLUGIN: $crate::plugins::PluginDeclaration = $crate::plugins::PluginDeclaration {
+            abi_version: $crate::plugins::PLUGIN_ABI_VERSION,
+            crate_version: $crate::plugins::CRATE_VERSION,
+            name: $name,
+            register: $register,
+        };
+    };
+}
+
+/// Errors raised while discovering or loading plugins
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("{path}: {1}", path = .0.display())]
+    Io(PathBuf, std::io::Error),
+    #[error("failed to load {path}: {1}", path = .0.display())]
+    Load(PathBuf, String),
+    #[error("incompatible plugin {path}: {1}", path = .0.display())]
+    Incompatible(PathBuf, String),
+}
+
+/// A loaded plugin library. Its rules must be dropped before the plugin.
+pub struct Plugin {
+    name: String,
+    path: PathBuf,
+    declaration: &'static PluginDeclaration,
+    _library: Library,
+}
+
+impl Plugin {
+    /// Load a single plugin library and validate its declaration
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        let library = Library::open(path)
+            .map_err(|msg| PluginError::Load(path.to_path_buf(), msg))?;
+
+        // SAFETY: the symbol is a `PluginDeclaration` static emitted by
+        // `export_plugin!`; the library stays loaded for the Plugin's lifetime.
+        let declaration: &'static PluginDeclaration = unsafe {
+            let ptr = library.symbol::<PluginDeclaration>(PLUGIN_SYMBOL)
+                .map_err(|msg| PluginError::Load(path.to_path_buf(), msg))?;
+            &*ptr
+        };
+
+        if declaration.abi_version != PLUGIN_ABI_VERSION {
+            return Err(PluginError::Incompatible(path.to_path_buf(), format!(
+                "plugin ABI version {} (expected {})", declaration.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+        if declaration.crate_version != CRATE_VERSION {
+            return Err(PluginError::Incompatible(path.to_path_buf(), format!(
+                "built against sql_redis {} (host is {})", declaration.crate_version, CRATE_VERSION
+            )));
+        }
+
+        Ok(Self {
+            name: declaration.name.to_string(),
+            path: path.to_path_buf(),
+            declaration,
+            _library: library,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Run the plugin's registration function
+    pub fn register(&self, registry: &mut PluginRegistry) {
+        (self.declaration.register)(registry)
+    }
+}
+
+/// Load every shared library (`.so`, `.dylib`, `.dll`) in a directory, in file name order
+pub fn load_dir(dir: &Path) -> Result<Vec<Plugin>, PluginError> {
+    let entries = fs::read_dir(dir).map_err(|e| PluginError::Io(dir.to_path_buf(), e))?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| PluginError::Io(dir.to_path_buf(), e))?.path();
+        let is_library = path.extension()
+            .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION);
+        if path.is_file() && is_library {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths.iter().map(|path| Plugin::load(path)).collect()
+}
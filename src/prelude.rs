@@ -0,0 +1,19 @@
+// prelude.rs - The supported public API in one import
+// `use sql_redis::prelude::*` brings in the transformer, its options and errors, the pieces
+// for writing custom rules, and the AST helpers that read keys out of statements
+
+pub use crate::{SqlToRedisTransformer, TransformOptions, Profile};
+pub use crate::{ErrorCode, SqlRedisError, Warning, WarningKind};
+pub use crate::commands::RedisCommand;
+pub use crate::reply::{Reply, Row};
+pub use crate::rules::{GenericRule, Rule, RuleKind};
+pub use crate::ast::clauses::{Clause, PredicateKind};
+pub use crate::context::{ContextBuilder, TemplateContext};
+pub use crate::rule;
+
+// Key extractors, one per statement kind
+pub use crate::ast::select::{sel_get_query, sel_get_select, sel_get_table_name, sel_get_key_value, sel_get_key_pattern, sel_get_key_in_set, sel_get_key_in_values};
+pub use crate::ast::insert::{ins_get_table_name, ins_get_column_value};
+pub use crate::ast::update::{upd_get_table_name, upd_get_key_value};
+pub use crate::ast::delete::{get_table_name as del_get_table_name, get_key_value as del_get_key_value};
+pub use crate::ast::trigger::trg_get_key_pattern;
@@ -0,0 +1,221 @@
+// recording.rs - Recorded execution sessions for replay
+// One JSON line per executed command: the SQL, the command that ran, and its reply (or error),
+// so a session can be re-translated to spot transformer changes or re-run to re-verify replies
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::audit::json_string;
+use crate::codec::BinaryEncoding;
+use crate::reply::Reply;
+
+/// One executed command and what it returned
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCommand {
+    pub sql: String,
+    /// The command as run, quoted as for redis-cli
+    pub command: String,
+    /// The reply, or the error the server or connection reported
+    pub reply: Result<Reply, String>,
+}
+
+impl RecordedCommand {
+    pub fn new(sql: &str, command: &str, reply: Result<Reply, String>) -> Self {
+        Self { sql: sql.to_string(), command: command.to_string(), reply }
+    }
+
+    /// The record as one line of JSON (no trailing newline). Replies are JSON values: nil is
+    /// `null`, integers numbers, bulk strings strings, arrays arrays, and status and error
+    /// replies `{"status": ...}` and `{"error": ...}` objects. Bulk strings that aren't UTF-8
+    /// (binary values) are `{"bytes": "<hex>"}` objects.
+    pub fn to_json(&self) -> String {
+        let outcome = match &self.reply {
+            Ok(reply) => format!("\"reply\":{}", reply_json(reply)),
+            Err(error) => format!("\"error\":{}", json_string(error)),
+        };
+        format!("{{\"sql\":{},\"command\":{},{}}}", json_string(&self.sql), json_string(&self.command), outcome)
+    }
+
+    /// Parse a line written by `to_json`
+    pub fn from_json(line: &str) -> Option<Self> {
+        let Json::Object(fields) = Parser::parse(line)? else {
+            return None;
+        };
+        let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|(_, value)| value);
+        let text = |name: &str| match field(name) {
+            Some(Json::String(text)) => Some(text.clone()),
+            _ => None,
+        };
+        let reply = match (field("reply"), text("error")) {
+            (Some(reply), _) => Ok(json_reply(reply)?),
+            (None, Some(error)) => Err(error),
+            (None, None) => return None,
+        };
+        Some(Self { sql: text("sql")?, command: text("command")?, reply })
+    }
+}
+
+/// Writes a session's records to a JSONL file, replacing an earlier recording
+#[derive(Debug)]
+pub struct SessionRecorder {
+    file: Mutex<File>,
+}
+
+impl SessionRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self { file: Mutex::new(File::create(path)?) })
+    }
+
+    /// Write a record, flushed before returning so a crash keeps what ran
+    pub fn record(&self, record: &RecordedCommand) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(file, "{}", record.to_json())?;
+        file.flush()
+    }
+}
+
+/// Read every record of a session file; blank lines are skipped
+pub fn load(path: &Path) -> io::Result<Vec<RecordedCommand>> {
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            RecordedCommand::from_json(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{}: line {}: malformed record", path.display(), number + 1))
+            })
+        })
+        .collect()
+}
+
+fn reply_json(reply: &Reply) -> String {
+    match reply {
+        Reply::Nil => "null".to_string(),
+        Reply::Integer(n) => n.to_string(),
+        Reply::Bulk(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => json_string(text),
+            Err(_) => format!("{{\"bytes\":{}}}", json_string(&BinaryEncoding::Hex.encode(bytes))),
+        },
+        Reply::Status(text) => format!("{{\"status\":{}}}", json_string(text)),
+        Reply::Error(text) => format!("{{\"error\":{}}}", json_string(text)),
+        Reply::Array(items) => format!("[{}]", items.iter().map(reply_json).collect::<Vec<_>>().join(",")),
+    }
+}
+
+fn json_reply(json: &Json) -> Option<Reply> {
+    Some(match json {
+        Json::Null => Reply::Nil,
+        Json::Number(n) => Reply::Integer(*n),
+        Json::String(text) => Reply::bulk(text.as_str()),
+        Json::Array(items) => Reply::Array(items.iter().map(json_reply).collect::<Option<_>>()?),
+        Json::Object(fields) => match fields.as_slice() {
+            [(key, Json::String(text))] if key == "status" => Reply::Status(text.clone()),
+            [(key, Json::String(text))] if key == "error" => Reply::Error(text.clone()),
+            [(key, Json::String(hex))] if key == "bytes" => Reply::Bulk(BinaryEncoding::Hex.decode(hex)?),
+            _ => return None,
+        },
+    })
+}
+
+/// The JSON values session records use
+enum Json {
+    Null,
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// A recursive-descent parser for one JSON value
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(text: &'a str) -> Option<Json> {
+        let mut parser = Parser { rest: text };
+        let value = parser.value()?;
+        parser.rest.trim().is_empty().then_some(value)
+    }
+
+    /// Consume `token` (after whitespace) if it comes next
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        if self.eat("null") {
+            return Some(Json::Null);
+        }
+        if self.eat("[") {
+            let mut items = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    items.push(self.value()?);
+                    if self.eat("]") {
+                        break;
+                    }
+                    self.eat(",").then_some(())?;
+                }
+            }
+            return Some(Json::Array(items));
+        }
+        if self.eat("{") {
+            let mut fields = Vec::new();
+            if !self.eat("}") {
+                loop {
+                    let key = self.string()?;
+                    self.eat(":").then_some(())?;
+                    fields.push((key, self.value()?));
+                    if self.eat("}") {
+                        break;
+                    }
+                    self.eat(",").then_some(())?;
+                }
+            }
+            return Some(Json::Object(fields));
+        }
+        if self.rest.starts_with('"') {
+            return self.string().map(Json::String);
+        }
+        let end = self.rest.find(|c: char| !(c.is_ascii_digit() || c == '-')).unwrap_or(self.rest.len());
+        let number = self.rest[..end].parse().ok()?;
+        self.rest = &self.rest[end..];
+        Some(Json::Number(number))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.eat("\"").then_some(())?;
+        let mut out = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((at, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[at + 1..];
+                    return Some(out);
+                }
+                '\\' => out.push(match chars.next()?.1 {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let hex: String = (0..4).map(|_| chars.next().map(|(_, c)| c)).collect::<Option<_>>()?;
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                    }
+                    c => c,
+                }),
+                c => out.push(c),
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,371 @@
+// reply.rs - Redis replies and result decoding
+// Maps raw Redis replies onto SQL-style rows of named columns
+
+use std::fmt;
+
+use crate::audit::json_string;
+
+/// A Redis reply, independent of any particular client library
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Nil,
+    Integer(i64),
+    Status(String),
+    /// A bulk string, as the bytes the server sent (binary values aren't valid UTF-8)
+    Bulk(Vec<u8>),
+    Array(Vec<Reply>),
+    Error(String),
+}
+
+impl Reply {
+    /// A bulk reply holding `text`
+    pub fn bulk(text: impl Into<Vec<u8>>) -> Self {
+        Reply::Bulk(text.into())
+    }
+    
+    /// Render a scalar reply as text (None for nil, arrays, and errors); bulk bytes that
+    /// aren't UTF-8 are rendered lossily
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            Reply::Integer(n) => Some(n.to_string()),
+            Reply::Status(s) => Some(s.clone()),
+            Reply::Bulk(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Reply {
+    /// Format the reply the way redis-cli does
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Nil => write!(f, "(nil)"),
+            Reply::Integer(n) => write!(f, "(integer) {}", n),
+            Reply::Status(s) => write!(f, "{}", s),
+            Reply::Bulk(bytes) => write!(f, "\"{}\"", String::from_utf8_lossy(bytes)),
+            Reply::Error(msg) => write!(f, "(error) {}", msg),
+            Reply::Array(items) if items.is_empty() => write!(f, "(empty array)"),
+            Reply::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}) {}", i + 1, item)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A decoded result row: column name/value pairs in column order
+pub type Row = Vec<(String, String)>;
+
+/// Decodes a reply into rows for a particular command
+pub type ResultDecoder = fn(&Reply) -> Vec<Row>;
+
+/// Decode an `INFO` reply into (section, field, value) rows
+pub fn decode_info(reply: &Reply) -> Vec<Row> {
+    let text = match reply.as_text() {
+        Some(text) => text,
+        None => return Vec::new(),
+    };
+
+    let mut rows = Vec::new();
+    let mut section = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('#') {
+            section = name.trim().to_lowercase();
+            continue;
+        }
+        if let Some((field, value)) = line.split_once(':') {
+            rows.push(vec![
+                ("section".to_string(), section.clone()),
+                ("field".to_string(), field.to_string()),
+                ("value".to_string(), value.to_string()),
+            ]);
+        }
+    }
+    rows
+}
+
+/// Decode a `CLIENT LIST` reply into one row per client, one column per attribute
+pub fn decode_client_list(reply: &Reply) -> Vec<Row> {
+    let text = match reply.as_text() {
+        Some(text) => text,
+        None => return Vec::new(),
+    };
+
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split_whitespace()
+                .filter_map(|attr| attr.split_once('='))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Decode a `SLOWLOG GET` reply into one row per logged command
+pub fn decode_slowlog(reply: &Reply) -> Vec<Row> {
+    let entries = match reply {
+        Reply::Array(entries) => entries,
+        _ => return Vec::new(),
+    };
+
+    entries.iter()
+        .filter_map(|entry| match entry {
+            Reply::Array(fields) if fields.len() >= 4 => {
+                let command = match &fields[3] {
+                    Reply::Array(args) => args.iter()
+                        .filter_map(Reply::as_text)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    other => other.as_text().unwrap_or_default(),
+                };
+                let text_at = |i: usize| fields.get(i).and_then(Reply::as_text).unwrap_or_default();
+                Some(vec![
+                    ("id".to_string(), text_at(0)),
+                    ("timestamp".to_string(), text_at(1)),
+                    ("duration_us".to_string(), text_at(2)),
+                    ("command".to_string(), command),
+                    ("client_addr".to_string(), text_at(4)),
+                    ("client_name".to_string(), text_at(5)),
+                ])
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Decode browsed keys (`executor::browse_keys`: an array of `[key, type, value]` arrays)
+/// into (key, type, value) rows. Strings keep their text; hashes and sorted sets render as
+/// JSON objects (field to value, member to score), lists and sets as JSON arrays. A plain
+/// SCAN page (cursor, then keys) decodes into (key) rows.
+pub fn decode_browsed_keys(reply: &Reply) -> Vec<Row> {
+    let entries = match reply {
+        Reply::Array(page) if page.len() == 2 && matches!(page[0], Reply::Bulk(_)) => {
+            let keys = match &page[1] {
+                Reply::Array(keys) => keys,
+                _ => return Vec::new(),
+            };
+            return keys.iter()
+                .filter_map(Reply::as_text)
+                .map(|key| vec![("key".to_string(), key)])
+                .collect();
+        }
+        Reply::Array(entries) => entries,
+        _ => return Vec::new(),
+    };
+
+    entries.iter()
+        .filter_map(|entry| match entry {
+            Reply::Array(fields) if fields.len() == 3 => {
+                let kind = fields[1].as_text().unwrap_or_default();
+                let value = render_value(&kind, &fields[2]);
+                Some(vec![
+                    ("key".to_string(), fields[0].as_text().unwrap_or_default()),
+                    ("type".to_string(), kind),
+                    ("value".to_string(), value),
+                ])
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Render a whole value read for `browse_keys` as one column
+fn render_value(kind: &str, value: &Reply) -> String {
+    let items: Vec<String> = match value {
+        Reply::Array(items) => items.iter().map(|item| json_string(&item.as_text().unwrap_or_default())).collect(),
+        Reply::Error(msg) => return format!("(error) {}", msg),
+        other => return other.as_text().unwrap_or_default(),
+    };
+    match kind {
+        "hash" | "zset" => {
+            let pairs: Vec<String> = items.chunks_exact(2).map(|pair| format!("{}:{}", pair[0], pair[1])).collect();
+            format!("{{{}}}", pairs.join(","))
+        }
+        _ => format!("[{}]", items.join(",")),
+    }
+}
+
+/// Decode an `HGETALL` reply (flat field/value pairs) into a single row with one column
+/// per field. An empty reply (missing key) produces no rows.
+pub fn decode_hash_fields(reply: &Reply) -> Vec<Row> {
+    let items = match reply {
+        Reply::Array(items) if !items.is_empty() => items,
+        _ => return Vec::new(),
+    };
+
+    vec![items.chunks_exact(2)
+        .map(|pair| (pair[0].as_text().unwrap_or_default(), pair[1].as_text().unwrap_or_default()))
+        .collect()]
+}
+
+/// Decode an `HGET` or `HMGET` reply (the values of `columns`, in order) into a single row.
+/// NULL (nil) values are left out, and a reply of only NULLs (e.g. a missing key) produces
+/// no rows.
+pub fn decode_values(reply: &Reply, columns: &[String]) -> Vec<Row> {
+    let values = match reply {
+        Reply::Array(items) => items.as_slice(),
+        value => std::slice::from_ref(value),
+    };
+    let row: Row = columns.iter()
+        .zip(values)
+        .filter_map(|(column, value)| Some((column.clone(), value.as_text()?)))
+        .collect();
+    if row.is_empty() { Vec::new() } else { vec![row] }
+}
+
+/// Decode an `HSCAN` page (a cursor, then flat field/value pairs) like `decode_hash_fields`
+pub fn decode_hash_scan(reply: &Reply) -> Vec<Row> {
+    match reply {
+        Reply::Array(page) if page.len() == 2 => decode_hash_fields(&page[1]),
+        _ => Vec::new(),
+    }
+}
+
+/// Decode a `ZPOPMIN`/`ZPOPMAX` reply (flat member/score pairs) into (member, score) rows
+pub fn decode_member_scores(reply: &Reply) -> Vec<Row> {
+    let items = match reply {
+        Reply::Array(items) => items,
+        _ => return Vec::new(),
+    };
+
+    items.chunks_exact(2)
+        .map(|pair| vec![
+            ("member".to_string(), pair[0].as_text().unwrap_or_default()),
+            ("score".to_string(), pair[1].as_text().unwrap_or_default()),
+        ])
+        .collect()
+}
+
+/// Decode per-group zset reads (`{key, {member, score, ...}}` pairs) into (key, member, score) rows
+pub fn decode_group_member_scores(reply: &Reply) -> Vec<Row> {
+    let groups = match reply {
+        Reply::Array(groups) => groups,
+        _ => return Vec::new(),
+    };
+
+    groups.iter()
+        .filter_map(|group| match group {
+            Reply::Array(pair) if pair.len() == 2 => Some((pair[0].as_text().unwrap_or_default(), &pair[1])),
+            _ => None,
+        })
+        .flat_map(|(key, members)| {
+            decode_member_scores(members).into_iter().map(move |mut row| {
+                row.insert(0, ("key".to_string(), key.clone()));
+                row
+            })
+        })
+        .collect()
+}
+
+/// Decode scripted rows (an array of flat column/value arrays) into one row each. NULL
+/// (nil) values are left out, so a row whose columns are all NULL is kept with no columns
+pub fn decode_rows(reply: &Reply) -> Vec<Row> {
+    let rows = match reply {
+        Reply::Array(rows) => rows,
+        _ => return Vec::new(),
+    };
+
+    rows.iter()
+        .filter_map(|row| match row {
+            Reply::Array(items) => Some(items.chunks_exact(2)
+                .filter_map(|pair| Some((pair[0].as_text().unwrap_or_default(), pair[1].as_text()?)))
+                .collect()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Decode a keyspace notification (`pmessage`) into a (db, key, event) row.
+/// Subscription confirmations and other replies produce no rows.
+pub fn decode_keyspace_event(reply: &Reply) -> Vec<Row> {
+    let parts = match reply {
+        Reply::Array(parts) if parts.len() == 4 => parts,
+        _ => return Vec::new(),
+    };
+    if parts[0].as_text().as_deref() != Some("pmessage") {
+        return Vec::new();
+    }
+
+    let channel = parts[2].as_text().unwrap_or_default();
+    let event = parts[3].as_text().unwrap_or_default();
+    // Channel format: __keyspace@<db>__:<key>
+    let (db, key) = match channel.strip_prefix("__keyspace@").and_then(|rest| rest.split_once("__:")) {
+        Some((db, key)) => (db.to_string(), key.to_string()),
+        None => return Vec::new(),
+    };
+
+    vec![vec![
+        ("db".to_string(), db),
+        ("key".to_string(), key),
+        ("event".to_string(), event),
+    ]]
+}
+
+/// One difference between two result sets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowDiff {
+    /// Row `index` of the expected rows has no counterpart
+    Missing { index: usize, row: Row },
+    /// Row `index` of the actual rows has no counterpart
+    Extra { index: usize, row: Row },
+    /// A column of expected row `index` has another value (None: the column is absent)
+    Changed { index: usize, column: String, expected: Option<String>, actual: Option<String> },
+}
+
+impl fmt::Display for RowDiff {
+    /// `-` for missing rows, `+` for extra rows, `~` for changed columns
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let render = |row: &Row| row.iter().map(|(column, value)| format!("{}={}", column, value)).collect::<Vec<_>>().join(", ");
+        let value = |value: &Option<String>| value.as_ref().map_or("(absent)".to_string(), |value| format!("{:?}", value));
+        match self {
+            RowDiff::Missing { index, row } => write!(f, "- row {}: {}", index + 1, render(row)),
+            RowDiff::Extra { index, row } => write!(f, "+ row {}: {}", index + 1, render(row)),
+            RowDiff::Changed { index, column, expected, actual } => {
+                write!(f, "~ row {} {}: {} -> {}", index + 1, column, value(expected), value(actual))
+            }
+        }
+    }
+}
+
+/// Compare two result sets regardless of row order (sets and hashes have none): rows found
+/// in both are ignored, the remaining rows are paired in order and compared column by column,
+/// and unpaired rows are missing or extra
+pub fn diff_rows(expected: &[Row], actual: &[Row]) -> Vec<RowDiff> {
+    let mut unmatched: Vec<usize> = (0..actual.len()).collect();
+    let mut missing = Vec::new();
+    for (index, row) in expected.iter().enumerate() {
+        match unmatched.iter().position(|&i| actual[i] == *row) {
+            Some(at) => {
+                unmatched.remove(at);
+            }
+            None => missing.push(index),
+        }
+    }
+
+    let mut diffs = Vec::new();
+    for (&index, &other) in missing.iter().zip(&unmatched) {
+        let (row, other_row) = (&expected[index], &actual[other]);
+        let lookup = |row: &Row, column: &str| row.iter().find(|(c, _)| c == column).map(|(_, value)| value.clone());
+        let mut columns: Vec<&String> = row.iter().map(|(column, _)| column).collect();
+        columns.extend(other_row.iter().map(|(column, _)| column).filter(|column| lookup(row, column).is_none()));
+        for column in columns {
+            let (expected, actual) = (lookup(row, column), lookup(other_row, column));
+            if expected != actual {
+                diffs.push(RowDiff::Changed { index, column: column.clone(), expected, actual });
+            }
+        }
+    }
+    diffs.extend(missing.iter().skip(unmatched.len()).map(|&index| RowDiff::Missing { index, row: expected[index].clone() }));
+    diffs.extend(unmatched.iter().skip(missing.len()).map(|&index| RowDiff::Extra { index, row: actual[index].clone() }));
+    diffs
+}
@@ -0,0 +1,47 @@
+// rewrite.rs - Rewrite suggestions for statements that map to anti-patterns
+// Rules that read a whole key name a bounded alternative; the statement is rewritten
+// into SQL that the paginated rule (SSCAN, HSCAN, LRANGE, ZRANGEBYSCORE ... LIMIT) handles
+
+use std::fmt;
+
+use sqlparser::ast::{Expr, Statement, Value};
+
+/// Page size suggested for statements that read a whole key
+pub const PAGE_SIZE: u64 = 100;
+
+/// How a rule's statement is rewritten into its preferred alternative
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rewrite {
+    /// Add `LIMIT n`, reading one page instead of the whole key
+    Limit(u64),
+}
+
+impl Rewrite {
+    /// The rewritten statement, or None if it doesn't apply (e.g. already LIMIT-ed)
+    pub fn apply(&self, stmt: &Statement) -> Option<Statement> {
+        let mut stmt = stmt.clone();
+        match self {
+            Rewrite::Limit(n) => {
+                let Statement::Query(query) = &mut stmt else { return None };
+                if query.limit.is_some() {
+                    return None;
+                }
+                query.limit = Some(Expr::value(Value::Number(n.to_string(), false)));
+            }
+        }
+        Some(stmt)
+    }
+}
+
+/// A rewritten statement and the commands it translates to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub sql: String,
+    pub command: String,
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "help: rewrite as {}\n  => {}", self.sql, self.command.replace('\n', "\n     "))
+    }
+}
@@ -0,0 +1,52 @@
+// rules/admin.rs - Server diagnostics rules (admin profile only)
+// System tables prefixed with "__" map to introspection commands
+
+use crate::ast::Clause;
+use crate::pattern::matchers::select;
+use crate::context;
+use crate::options::Profile;
+use crate::reply;
+use crate::rule;
+use crate::rules::{Rule, RuleKind};
+
+/// Create all rules for server diagnostics, gated behind the admin profile
+pub fn create_admin_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // <server-info> ::= SELECT * FROM __server_info => INFO
+        rule! {
+            template: "admin_info",
+            matcher: select::is_server_info,
+            context: context::SystemTableContextBuilder,
+            kind: RuleKind::Select,
+            sql: "SELECT * FROM __server_info",
+            redis: "INFO",
+            profile: Profile::Admin,
+            decoder: reply::decode_info,
+        },
+        
+        // <client-list> ::= SELECT * FROM __clients => CLIENT LIST
+        rule! {
+            template: "admin_client_list",
+            matcher: select::is_client_list,
+            context: context::SystemTableContextBuilder,
+            kind: RuleKind::Select,
+            sql: "SELECT * FROM __clients",
+            redis: "CLIENT LIST",
+            profile: Profile::Admin,
+            decoder: reply::decode_client_list,
+        },
+        
+        // <slowlog-get> ::= SELECT * FROM __slowlog [LIMIT n] => SLOWLOG GET [n]
+        rule! {
+            template: "admin_slowlog_get",
+            matcher: select::is_slowlog_get,
+            context: context::SystemTableContextBuilder,
+            kind: RuleKind::Select,
+            sql: "SELECT * FROM __slowlog LIMIT n",
+            redis: "SLOWLOG GET n",
+            consumes: Clause::Limit,
+            profile: Profile::Admin,
+            decoder: reply::decode_slowlog,
+        },
+    ]
+}
@@ -3,7 +3,11 @@
 use crate::pattern::matchers::delete;
 use crate::context;
 use crate::rules::Rule;
-use crate::rules::GenericRule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::pattern::matchers::common::RedisDataType;
+use crate::warning::WarningKind;
+use crate::ast::{Clause, PredicateKind};
+use crate::reply;
 
 /// Create all rules for DELETE statement transformations with rich metadata
 pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
@@ -19,6 +23,8 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "del"
         )
         .with_matcher_name("is_string_delete")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("DELETE FROM table WHERE key = 'key'")
         .with_redis_pattern("DEL key")),
         
@@ -33,18 +39,24 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "hash_delete"
         )
         .with_matcher_name("is_hash_delete")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("DELETE FROM table__hash WHERE key = 'key'")
         .with_redis_pattern("DEL key")),
         
-        // <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" "=" <field>
+        // <hash-delete-field> ::= "DELETE" "FROM" <table> "__hash" "WHERE" "key" "=" <key> "AND" "field" ("=" <field> | "IN" "(" <field1> ["," <field2>]... ")")
         Box::new(GenericRule::new(
             delete::is_hash_delete_field,
             Box::new(context::HashDeleteFieldContextBuilder), 
             "hash_delete_field"
         )
         .with_matcher_name("is_hash_delete_field")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("DELETE FROM table__hash WHERE key = 'key' AND field = 'field'")
-        .with_redis_pattern("HDEL key field")),
+        .with_redis_pattern("HDEL key field [field ...]")
+        .with_consumed_predicate("field", PredicateKind::Equals)
+        .with_consumed_predicate("field", PredicateKind::In)),
         
         // --------------------------------
         // List operations
@@ -57,6 +69,8 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "list_delete"
         )
         .with_matcher_name("is_list_delete")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("DELETE FROM table__list WHERE key = 'key'")
         .with_redis_pattern("DEL key")),
         
@@ -67,8 +81,25 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "list_delete_value"
         )
         .with_matcher_name("is_list_delete_value")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("DELETE FROM table__list WHERE key = 'key' AND value = 'value'")
-        .with_redis_pattern("LREM key 0 value")),
+        .with_redis_pattern("LREM key 0 value")
+        .with_warning(WarningKind::FullScan, "LREM scans the whole list")
+        .with_consumed_predicate("value", PredicateKind::Equals)),
+        
+        // <list-trim> ::= "DELETE" "FROM" <table> "__list" "WHERE" "key" "=" <key> "AND" "index" (">" | ">=" | "<" | "<=") <n>
+        Box::new(GenericRule::new(
+            delete::is_list_trim,
+            Box::new(context::ListTrimContextBuilder),
+            "list_trim"
+        )
+        .with_matcher_name("is_list_trim")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::List)
+        .with_sql_pattern("DELETE FROM table__list WHERE key = 'key' AND index > n")
+        .with_redis_pattern("LTRIM key 0 n")
+        .with_consumed_predicate("index", PredicateKind::Range)),
         
         // --------------------------------
         // Set operations
@@ -82,8 +113,11 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "set_delete_multi_member"
         )
         .with_matcher_name("is_set_delete_multi_member")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("DELETE FROM table__set WHERE key = 'key' AND member IN ('m1', 'm2')")
-        .with_redis_pattern("SREM key m1 m2")),
+        .with_redis_pattern("SREM key m1 m2")
+        .with_consumed_predicate("member", PredicateKind::In)),
         
         // <set-delete-member> ::= "DELETE" "FROM" <table> "__set" "WHERE" "key" "=" <key> "AND" "member" "=" <member>
         Box::new(GenericRule::new(
@@ -92,8 +126,11 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "set_delete_member"
         )
         .with_matcher_name("is_set_delete_member")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("DELETE FROM table__set WHERE key = 'key' AND member = 'member'")
-        .with_redis_pattern("SREM key member")),
+        .with_redis_pattern("SREM key member")
+        .with_consumed_predicate("member", PredicateKind::Equals)),
         
         // <set-delete> ::= "DELETE" "FROM" <table> "__set" "WHERE" "key" "=" <key>
         Box::new(GenericRule::new(
@@ -102,6 +139,8 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "set_delete"
         )
         .with_matcher_name("is_set_delete")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("DELETE FROM table__set WHERE key = 'key'")
         .with_redis_pattern("DEL key")),
         
@@ -116,17 +155,68 @@ pub fn create_delete_rules() -> Vec<Box<dyn Rule>> {
             "zset_delete"
         )
         .with_matcher_name("is_zset_delete")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("DELETE FROM table__zset WHERE key = 'key'")
         .with_redis_pattern("DEL key")),
         
-        // <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" "=" <member>
+        // <zset-delete-member> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "member" ("=" <member> | "IN" "(" <member1> ["," <member2>]... ")")
         Box::new(GenericRule::new(
             delete::is_zset_delete_member,
             Box::new(context::ZSetDeleteMemberContextBuilder), 
             "zset_delete_member"
         )
         .with_matcher_name("is_zset_delete_member")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("DELETE FROM table__zset WHERE key = 'key' AND member = 'member'")
-        .with_redis_pattern("ZREM key member")),
+        .with_redis_pattern("ZREM key member [member ...]")
+        .with_consumed_predicate("member", PredicateKind::Equals)
+        .with_consumed_predicate("member", PredicateKind::In)),
+        
+        // <zset-delete-score-range> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "AND" "score" (<comparison> <n> | "BETWEEN" <min> "AND" <max>)
+        Box::new(GenericRule::new(
+            delete::is_zset_delete_score_range,
+            Box::new(context::ZSetDeleteScoreRangeContextBuilder),
+            "zset_delete_score_range"
+        )
+        .with_matcher_name("is_zset_delete_score_range")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("DELETE FROM table__zset WHERE key = 'key' AND score < NOW() - 86400")
+        .with_redis_pattern("ZREMRANGEBYSCORE key -inf (<now-86400>")
+        .with_consumed_predicate("score", PredicateKind::Range)),
+        
+        // <zset-pop-min> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "ORDER" "BY" "score" ["ASC"] "LIMIT" <count> ["RETURNING" <columns>]
+        Box::new(GenericRule::new(
+            delete::is_zset_pop_min,
+            Box::new(context::ZSetPopContextBuilder),
+            "zset_pop_min"
+        )
+        .with_matcher_name("is_zset_pop_min")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("DELETE FROM table__zset WHERE key = 'key' ORDER BY score ASC LIMIT n RETURNING member, score")
+        .with_redis_pattern("ZPOPMIN key [n]")
+        .with_result_decoder(reply::decode_member_scores)
+        .with_consumes(Clause::OrderBy)
+        .with_consumes(Clause::Limit)
+        .with_consumes(Clause::Returning)),
+        
+        // <zset-pop-max> ::= "DELETE" "FROM" <table> "__zset" "WHERE" "key" "=" <key> "ORDER" "BY" "score" "DESC" "LIMIT" <count> ["RETURNING" <columns>]
+        Box::new(GenericRule::new(
+            delete::is_zset_pop_max,
+            Box::new(context::ZSetPopContextBuilder),
+            "zset_pop_max"
+        )
+        .with_matcher_name("is_zset_pop_max")
+        .with_kind(RuleKind::Delete)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("DELETE FROM table__zset WHERE key = 'key' ORDER BY score DESC LIMIT n RETURNING member, score")
+        .with_redis_pattern("ZPOPMAX key [n]")
+        .with_result_decoder(reply::decode_member_scores)
+        .with_consumes(Clause::OrderBy)
+        .with_consumes(Clause::Limit)
+        .with_consumes(Clause::Returning)),
     ]
 }
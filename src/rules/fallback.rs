@@ -0,0 +1,67 @@
+// rules/fallback.rs - Lua fallback for SELECTs the other rules can't filter
+// Opt-in (TransformOptions::with_lua_fallback): reads a key whole and filters its rows in a script
+
+use sqlparser::ast::Statement;
+use crate::pattern::matchers::common::get_redis_data_type;
+use crate::pattern::matchers::select;
+use crate::context;
+use crate::rules::Rule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::warning::WarningKind;
+use crate::ast;
+use crate::expr;
+use crate::lua;
+use crate::reply;
+
+/// Helper: build the EVAL command reading the key and filtering its rows
+fn build_lua_fallback(stmt: &Statement) -> Option<String> {
+    build_lua_filter(stmt, false)
+}
+
+/// Helper: like `build_lua_fallback`, also sorting the kept rows by the ORDER BY columns
+/// and keeping the first LIMIT of them
+pub(crate) fn build_ordered_lua_filter(stmt: &Statement) -> Option<String> {
+    build_lua_filter(stmt, true)
+}
+
+fn build_lua_filter(stmt: &Statement, ordered: bool) -> Option<String> {
+    let query = ast::sel_get_query(stmt)?;
+    let select = ast::sel_get_select(query)?;
+    let key = ast::sel_get_key_value(&select.selection)?;
+    let table = ast::sel_get_table_name(select)?;
+    let filter = ast::sel_get_row_filter(select)?;
+    let projections = match select::is_wildcard_select(stmt) {
+        true => None,
+        false => Some(expr::projections(&select.projection)?.into_iter()
+            .map(|(projection, name)| (projection.to_case(), name))
+            .collect::<Vec<_>>()),
+    };
+    let data_type = get_redis_data_type(&table);
+    if !ordered {
+        return Some(lua::filtered_select(data_type, &key, filter.as_ref(), projections.as_deref()));
+    }
+    let order = ast::sel_get_order_columns(query)?;
+    Some(lua::filtered_select_ordered(data_type, &key, filter.as_ref(), projections.as_deref(), &order, ast::sel_get_limit(query)))
+}
+
+/// Create the last-resort rule compiling key lookups with arbitrary extra predicates to Lua
+pub fn create_lua_fallback_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // <lua-fallback> ::= "SELECT" ("*" | <projection> ["," ...]) "FROM" <table> "WHERE" "key" "=" <value> ["AND" <predicate>]...
+        Box::new(GenericRule::new(
+            select::is_lua_filter_select,
+            Box::new(context::CountContextBuilder),
+            "lua_fallback"
+        )
+        .with_matcher_name("is_lua_filter_select")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT * FROM table__hash WHERE key = 'value' AND age > 30")
+        .with_redis_pattern("EVAL '<lua: HGETALL + filter>' 1 value age 30")
+        .with_consumed_predicates()
+        .with_last_resort()
+        .with_warning(WarningKind::FullScan, "Lua script reads the whole key to filter its rows")
+        .with_result_decoder(reply::decode_rows)
+        .with_projected_rows()
+        .with_direct_command(build_lua_fallback)),
+    ]
+}
@@ -0,0 +1,83 @@
+// rules/function.rs - Table-function transformation rules
+// `SELECT * FROM redis_hgetall('user:1')` reads a key by command, whatever its table would be called
+
+use crate::pattern::matchers::common::RedisDataType;
+use crate::pattern::matchers::function;
+use crate::context::TableFunctionContextBuilder;
+use crate::reply;
+use crate::rule;
+use crate::rules::{Rule, RuleKind};
+use crate::warning::WarningKind;
+
+/// Create all rules for table-function reads
+pub fn create_table_function_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // <redis-get> ::= SELECT * FROM redis_get('k') => GET k
+        rule! {
+            template: "string_get",
+            matcher: function::is_redis_get,
+            context: TableFunctionContextBuilder(&["key"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::String,
+            sql: "SELECT * FROM redis_get('value')",
+            redis: "GET value",
+        },
+        
+        // <redis-hgetall> ::= SELECT * FROM redis_hgetall('k') => HGETALL k
+        rule! {
+            template: "hash_getall",
+            matcher: function::is_redis_hgetall,
+            context: TableFunctionContextBuilder(&["key"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::Hash,
+            sql: "SELECT * FROM redis_hgetall('value')",
+            redis: "HGETALL value",
+            warning: (WarningKind::FullScan, "HGETALL reads every field of the hash"),
+            decoder: reply::decode_hash_fields,
+        },
+        
+        // <redis-hget> ::= SELECT * FROM redis_hget('k', 'f') => HGET k f
+        rule! {
+            template: "hash_get",
+            matcher: function::is_redis_hget,
+            context: TableFunctionContextBuilder(&["key", "field"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::Hash,
+            sql: "SELECT * FROM redis_hget('value', 'field')",
+            redis: "HGET value field",
+        },
+        
+        // <redis-lrange> ::= SELECT * FROM redis_lrange('k', 0, -1) => LRANGE k 0 -1
+        rule! {
+            template: "list_get_range",
+            matcher: function::is_redis_lrange,
+            context: TableFunctionContextBuilder(&["key", "start", "stop"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::List,
+            sql: "SELECT * FROM redis_lrange('value', 0, n)",
+            redis: "LRANGE value 0 n",
+        },
+        
+        // <redis-smembers> ::= SELECT * FROM redis_smembers('k') => SMEMBERS k
+        rule! {
+            template: "set_getall",
+            matcher: function::is_redis_smembers,
+            context: TableFunctionContextBuilder(&["key"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::Set,
+            sql: "SELECT * FROM redis_smembers('value')",
+            redis: "SMEMBERS value",
+        },
+        
+        // <redis-zrangebyscore> ::= SELECT * FROM redis_zrangebyscore('k', min, max) => ZRANGEBYSCORE k min max
+        rule! {
+            template: "zset_get_score_range",
+            matcher: function::is_redis_zrangebyscore,
+            context: TableFunctionContextBuilder(&["key", "min", "max"]),
+            kind: RuleKind::Select,
+            data_type: RedisDataType::SortedSet,
+            sql: "SELECT * FROM redis_zrangebyscore('value', n, m)",
+            redis: "ZRANGEBYSCORE value n m",
+        },
+    ]
+}
@@ -1,9 +1,27 @@
 // Update rules/insert.rs with enhanced metadata
 
-use crate::pattern::matchers::insert::{is_string_set, is_hash_set, is_list_push, is_set_add, is_zset_add};
+use sqlparser::ast::Statement;
+use crate::pattern::matchers::insert::{is_string_set, is_hash_set, is_list_push, is_set_add, is_set_store, is_type_copy, is_zset_add, is_zset_range_store};
+use crate::ast;
 use crate::context;
+use crate::lua;
 use crate::rules::Rule;
-use crate::rules::GenericRule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::pattern::matchers::common::{get_redis_data_type, RedisDataType};
+use crate::warning::WarningKind;
+
+/// Helper: build the EVAL command copying one collection key into another type
+fn build_type_copy(stmt: &Statement) -> Option<String> {
+    let select = ast::ins_get_source_query(stmt).and_then(ast::sel_get_select)?;
+    let source_table = ast::sel_get_table_name(select)?;
+    let target_table = ast::ins_get_table_name(stmt)?;
+    let source_type = get_redis_data_type(&source_table);
+    let target_type = get_redis_data_type(&target_table);
+    let columns = ast::ins_get_copied_columns(stmt, &select.projection, source_type)?;
+    let source = ast::sel_get_key_value(&select.selection)?;
+    let destination = context::insert_select_destination(stmt, select, &source)?;
+    lua::type_copy(source_type, target_type, &destination, &source, &columns)
+}
 
 /// Create all rules for INSERT statement transformations with rich metadata
 pub fn create_insert_rules() -> Vec<Box<dyn Rule>> {
@@ -19,6 +37,8 @@ pub fn create_insert_rules() -> Vec<Box<dyn Rule>> {
             "string_set"
         )
         .with_matcher_name("is_string_set")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("INSERT INTO table (key, value) VALUES ('key', 'value')")
         .with_redis_pattern("SET key value")),
         
@@ -33,6 +53,8 @@ pub fn create_insert_rules() -> Vec<Box<dyn Rule>> {
             "hash_set"
         )
         .with_matcher_name("is_hash_set")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("INSERT INTO table__hash (key, field1, field2) VALUES ('key', 'value1', 'value2')")
         .with_redis_pattern("HSET key field1 value1 field2 value2")),
         
@@ -47,6 +69,8 @@ pub fn create_insert_rules() -> Vec<Box<dyn Rule>> {
             "list_push"
         )
         .with_matcher_name("is_list_push")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("INSERT INTO table__list (key, value) VALUES ('key', 'value')")
         .with_redis_pattern("RPUSH key value")),
         
@@ -54,28 +78,78 @@ pub fn create_insert_rules() -> Vec<Box<dyn Rule>> {
         // Set operations
         // --------------------------------
         
-        // <set-add> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "VALUES" "(" <key> "," <member> ")"
+        // <set-add> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "VALUES" "(" <key> "," <member> ")" ["," "(" <key> "," <member> ")"]...
         Box::new(GenericRule::new(
             is_set_add,
             Box::new(context::SetAddContextBuilder), 
             "set_add"
         )
         .with_matcher_name("is_set_add")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("INSERT INTO table__set (key, member) VALUES ('key', 'member')")
-        .with_redis_pattern("SADD key member")),
+        .with_redis_pattern("SADD key member [member ...]")),
+        
+        // <set-store> ::= "INSERT" "INTO" <table> "__set" "(key, member)" "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>
+        //                 [("UNION" | "INTERSECT" | "EXCEPT") "SELECT" ... "FROM" <table> "__set" "WHERE" "key" "=" <key>]...
+        Box::new(GenericRule::new(
+            is_set_store,
+            Box::new(context::SetStoreContextBuilder),
+            "set_store"
+        )
+        .with_matcher_name("is_set_store")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::Set)
+        .with_sql_pattern("INSERT INTO dest__set (key, member) SELECT member FROM a__set WHERE key = 'a' INTERSECT SELECT member FROM b__set WHERE key = 'b'")
+        .with_redis_pattern("SINTERSTORE dest:a a b (SUNIONSTORE for UNION, SDIFFSTORE for EXCEPT)")),
         
         // --------------------------------
         // Sorted Set operations
         // --------------------------------
         
-        // <zset-add> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "VALUES" "(" <key> "," <member> "," <score> ")"
+        // <zset-add> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "VALUES" "(" <key> "," <member> "," <score> ")" ["," "(" <key> "," <member> "," <score> ")"]...
         Box::new(GenericRule::new(
             is_zset_add,
             Box::new(context::ZSetAddContextBuilder), 
             "zset_add"
         )
         .with_matcher_name("is_zset_add")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("INSERT INTO table__zset (key, member, score) VALUES ('key', 'member', 'score')")
-        .with_redis_pattern("ZADD key score member")),
+        .with_redis_pattern("ZADD key score member [score member ...]")
+        .with_warning(WarningKind::TypeCoercion, "score is converted to a double-precision float")),
+        
+        // <zset-range-store> ::= "INSERT" "INTO" <table> "__zset" "(key, member, score)" "SELECT" ... "FROM" <table> "__zset"
+        //                        "WHERE" "key" "=" <key> ["AND" <score-condition>] ["ORDER BY" "score" ["DESC"]] ["LIMIT" <n>]
+        Box::new(GenericRule::new(
+            is_zset_range_store,
+            Box::new(context::ZSetRangeStoreContextBuilder),
+            "zset_range_store"
+        )
+        .with_matcher_name("is_zset_range_store")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("INSERT INTO top__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb' AND score > 1000")
+        .with_redis_pattern("ZRANGESTORE top:lb lb (1000 +inf BYSCORE")),
+        
+        // --------------------------------
+        // Copies between collection types
+        // --------------------------------
+        
+        // <type-copy> ::= "INSERT" "INTO" <table> ("__list" | "__set" | "__zset") "(key, " <column>... ")"
+        //                 "SELECT" (<column> | <literal>)... "FROM" <table> ("__list" | "__set" | "__zset") "WHERE" "key" "=" <key>
+        Box::new(GenericRule::new(
+            is_type_copy,
+            Box::new(context::CountContextBuilder),
+            "type_copy"
+        )
+        .with_matcher_name("is_type_copy")
+        .with_kind(RuleKind::Insert)
+        .with_data_type(RedisDataType::List)
+        .with_sql_pattern("INSERT INTO archive__list (key, value) SELECT member FROM old__set WHERE key = 'old:1'")
+        .with_redis_pattern("EVAL '<lua: SMEMBERS + RPUSH>' 2 archive:old:1 old:1 member")
+        .with_warning(WarningKind::FullScan, "Lua script reads every element of the source key")
+        .with_direct_command(build_type_copy)),
     ]
-}
\ No newline at end of file
+}
@@ -0,0 +1,58 @@
+// rules/json.rs - Field reads on string tables holding JSON documents
+// Built per transformer from the tables declared in TransformOptions
+
+use sqlparser::ast::Statement;
+use crate::pattern::matchers::select;
+use crate::context;
+use crate::options::Profile;
+use crate::rules::Rule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::ast;
+use crate::lua;
+
+/// Helper: build the cjson EVAL command reading the projected fields
+fn build_json_fields(stmt: &Statement) -> Option<String> {
+    let select = ast::sel_get_query(stmt).and_then(ast::sel_get_select)?;
+    let key = ast::sel_get_key_value(&select.selection)?;
+    let fields = ast::sel_get_field_names(&select.projection);
+    if fields.is_empty() {
+        return None;
+    }
+    Some(lua::json_fields(&key, &fields))
+}
+
+/// Create the rules for projecting fields out of JSON string tables.
+/// JSON.GET is used under the redisjson profile; otherwise a cjson Lua script.
+pub fn create_json_rules(json_tables: &[String]) -> Vec<Box<dyn Rule>> {
+    if json_tables.is_empty() {
+        return Vec::new();
+    }
+    let redisjson_tables = json_tables.to_vec();
+    let lua_tables = json_tables.to_vec();
+
+    vec![
+        // <json-get-fields> ::= SELECT f1, f2 FROM json_table WHERE key = value => JSON.GET value $.f1 $.f2
+        Box::new(GenericRule::new(
+            move |s| select::is_json_get_fields(s, &redisjson_tables),
+            Box::new(context::JsonGetContextBuilder),
+            "json_get"
+        )
+        .with_matcher_name("is_json_get_fields")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT field1, field2 FROM json_table WHERE key = 'value'")
+        .with_redis_pattern("JSON.GET value $.field1 $.field2")
+        .with_profile(Profile::RedisJson)),
+
+        // <json-get-fields> ::= SELECT f1, f2 FROM json_table WHERE key = value (Lua cjson)
+        Box::new(GenericRule::new(
+            move |s| select::is_json_get_fields(s, &lua_tables),
+            Box::new(context::JsonGetContextBuilder),
+            "json_get_lua"
+        )
+        .with_matcher_name("is_json_get_fields")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT field1, field2 FROM json_table WHERE key = 'value'")
+        .with_redis_pattern("EVAL <cjson.decode script> 1 value field1 field2")
+        .with_direct_command(build_json_fields)),
+    ]
+}
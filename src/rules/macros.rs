@@ -0,0 +1,92 @@
+// rules/macros.rs - Declarative rule definitions
+// `rule!` expands a concise spec into a boxed GenericRule with its metadata
+
+/// Declare a rule as a `Box<dyn Rule>` from a concise spec.
+///
+/// `template`, `matcher`, and either `context` (a `ContextBuilder` value) or
+/// `build` (a `|stmt| -> Option<TemplateContext>` closure) come first, in that order.
+/// They may be followed by any of `sql`, `redis`, `profile`, `decoder`, `direct`,
+/// `since`, `deprecated`, `replacement`, `consumes`, `consumes_predicate` (a
+/// `(&str, PredicateKind)` pair), `rewrite`, and `warning` (a `(WarningKind, &str)` pair).
+/// When `matcher` is a function path its last segment becomes the matcher name.
+///
+/// ```
+/// use sql_redis::rule;
+/// use sql_redis::pattern::matchers::select;
+/// use sql_redis::context;
+/// use sql_redis::rules::Rule;
+///
+/// let rule = rule! {
+///     template: "string_get",
+///     matcher: select::is_string_get,
+///     context: context::StringGetContextBuilder,
+///     sql: "SELECT * FROM table WHERE key = value",
+///     redis: "GET value",
+/// };
+/// assert_eq!(rule.get_matcher_name(), Some("is_string_get"));
+/// ```
+#[macro_export]
+macro_rules! rule {
+    (
+        template: $template:expr,
+        matcher: $matcher:expr,
+        build: $build:expr
+        $(, $option:ident : $value:expr)* $(,)?
+    ) => {
+        $crate::rule! {
+            template: $template,
+            matcher: $matcher,
+            context: $crate::context::from_fn($build)
+            $(, $option: $value)*
+        }
+    };
+    (
+        template: $template:expr,
+        matcher: $matcher:expr,
+        context: $context:expr
+        $(, $option:ident : $value:expr)* $(,)?
+    ) => {{
+        let rule = $crate::rules::GenericRule::new(
+            $matcher,
+            ::std::boxed::Box::new($context),
+            $template,
+        );
+        let rule = match $crate::rules::matcher_name_of(stringify!($matcher)) {
+            ::std::option::Option::Some(name) => rule.with_matcher_name(name),
+            ::std::option::Option::None => rule,
+        };
+        $( let rule = $crate::rule!(@option rule, $option, $value); )*
+        ::std::boxed::Box::new(rule) as ::std::boxed::Box<dyn $crate::rules::Rule>
+    }};
+    (@option $rule:ident, sql, $value:expr) => { $rule.with_sql_pattern($value) };
+    (@option $rule:ident, redis, $value:expr) => { $rule.with_redis_pattern($value) };
+    (@option $rule:ident, kind, $value:expr) => { $rule.with_kind($value) };
+    (@option $rule:ident, data_type, $value:expr) => { $rule.with_data_type($value) };
+    (@option $rule:ident, profile, $value:expr) => { $rule.with_profile($value) };
+    (@option $rule:ident, decoder, $value:expr) => { $rule.with_result_decoder($value) };
+    (@option $rule:ident, direct, $value:expr) => { $rule.with_direct_command($value) };
+    (@option $rule:ident, since, $value:expr) => { $rule.with_since($value) };
+    (@option $rule:ident, deprecated, $value:expr) => { $rule.with_deprecated($value) };
+    (@option $rule:ident, replacement, $value:expr) => { $rule.with_replacement($value) };
+    (@option $rule:ident, consumes, $value:expr) => { $rule.with_consumes($value) };
+    (@option $rule:ident, rewrite, $value:expr) => { $rule.with_rewrite($value) };
+    (@option $rule:ident, warning, $value:expr) => {{
+        let (kind, message) = $value;
+        $rule.with_warning(kind, message)
+    }};
+    (@option $rule:ident, consumes_predicate, $value:expr) => {{
+        let (column, kind) = $value;
+        $rule.with_consumed_predicate(column, kind)
+    }};
+}
+
+/// Derive a matcher name from the stringified matcher expression.
+/// Paths yield their last segment; closures and other expressions yield None.
+#[doc(hidden)]
+pub fn matcher_name_of(expr: &str) -> Option<&str> {
+    let name = expr.rsplit("::").next()?.trim();
+    let is_ident = !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit());
+    if is_ident { Some(name) } else { None }
+}
@@ -2,23 +2,86 @@
 // Each rule connects a pattern matcher with a context builder
 
 use sqlparser::ast::Statement;
+use crate::ast::{Clause, PredicateKind};
 use crate::context::{ContextBuilder, TemplateContext};
+use crate::options::Profile;
+use crate::pattern::matchers::common::RedisDataType;
+use crate::reply::ResultDecoder;
+use crate::rewrite::Rewrite;
+use crate::warning::{Warning, WarningKind};
 
 mod select;
 mod insert;
 mod update;
 mod delete;
+mod admin;
+mod trigger;
+mod session;
+mod function;
+mod json;
+mod fallback;
+mod macros;
 
 pub use select::create_select_rules;
 pub use insert::create_insert_rules;
 pub use update::create_update_rules;
 pub use delete::create_delete_rules;
+pub use admin::create_admin_rules;
+pub use trigger::create_trigger_rules;
+pub use session::create_session_rules;
+pub use function::create_table_function_rules;
+pub use json::create_json_rules;
+pub use fallback::create_lua_fallback_rules;
+#[doc(hidden)]
+pub use macros::matcher_name_of;
 
 
+/// The kind of statement a rule translates, used to group and filter pattern listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    /// `CREATE TRIGGER` (keyspace notifications)
+    Trigger,
+    /// Session statements such as `USE`
+    Session,
+}
+
+impl RuleKind {
+    /// Every kind, in listing order
+    pub const ALL: [RuleKind; 6] = [
+        RuleKind::Select,
+        RuleKind::Insert,
+        RuleKind::Update,
+        RuleKind::Delete,
+        RuleKind::Trigger,
+        RuleKind::Session,
+    ];
+
+    /// Lowercase name used by the CLI and pattern listings
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleKind::Select => "select",
+            RuleKind::Insert => "insert",
+            RuleKind::Update => "update",
+            RuleKind::Delete => "delete",
+            RuleKind::Trigger => "trigger",
+            RuleKind::Session => "session",
+        }
+    }
+
+    /// Look a kind up by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+}
+
 // Update the Rule trait in src/rules/mod.rs to include a description method
 
 /// A Rule defines how a SQL pattern maps to a Redis command via a template
-pub trait Rule {
+pub trait Rule: Send + Sync {
     fn matches(&self, stmt: &Statement) -> bool;
     fn get_context(&self, stmt: &Statement) -> Option<TemplateContext>;
     fn get_template_name(&self) -> &str;
@@ -43,8 +106,89 @@ pub trait Rule {
     fn get_redis_pattern(&self) -> Option<&str> {
         None // Default implementation returns None
     }
+    
+    /// Returns the kind of statement this rule translates
+    fn get_kind(&self) -> Option<RuleKind> {
+        None
+    }
+    
+    /// Returns the Redis data type the rule operates on; None for type-agnostic rules
+    fn get_data_type(&self) -> Option<RedisDataType> {
+        None
+    }
+    
+    /// Returns the profile that must be enabled for this rule to apply
+    fn get_profile(&self) -> Option<Profile> {
+        None // Default rules are always active
+    }
+    
+    /// Returns the decoder mapping the command's reply onto result rows
+    fn get_result_decoder(&self) -> Option<ResultDecoder> {
+        None
+    }
+    
+    /// Whether decoded rows already hold the SELECT's projected columns, so they
+    /// aren't narrowed to the projection again
+    fn decodes_projection(&self) -> bool {
+        false
+    }
+    
+    /// Whether the reply holds the values of the SELECT's projected fields in projection
+    /// order (HGET, HMGET), decoded into one row named by the projected columns
+    fn decodes_values(&self) -> bool {
+        false
+    }
+    
+    /// Returns the crate version that introduced this rule
+    fn get_since(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Returns the crate version that deprecated this rule, if deprecated
+    fn get_deprecated(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Returns the template name of the rule that supersedes this one
+    fn get_replacement(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Returns warnings that always apply to this rule's command (cost, coercions)
+    fn get_warnings(&self) -> &[Warning] {
+        &[]
+    }
+    
+    /// Returns how to rewrite a matched statement into a preferred, bounded alternative
+    fn get_rewrite(&self) -> Option<Rewrite> {
+        None
+    }
+    
+    /// Whether this is a last-resort rule: tried after every other rule, and preferred over a
+    /// match that would drop WHERE predicates
+    fn is_last_resort(&self) -> bool {
+        false
+    }
+    
+    /// Whether the generated command honors the given clause (used by strict mode)
+    fn consumes(&self, clause: &Clause) -> bool {
+        consumed_by_default(clause)
+    }
+}
+
+/// Clauses every rule honors: the `key = ...` or `key IN (...)` predicate and the
+/// projection its matcher checks
+pub fn consumed_by_default(clause: &Clause) -> bool {
+    match clause {
+        Clause::Predicate(column, PredicateKind::Equals | PredicateKind::In) => column == "key",
+        Clause::Projection => true,
+        _ => false,
+    }
 }
 
+/// Builds a rule's command directly from the statement, bypassing its template
+type DirectCommandBuilder = Box<dyn Fn(&Statement) -> Option<String> + Send + Sync>;
+
 // Update GenericRule to include more metadata
 pub struct GenericRule<F> {
     matcher: F,
@@ -53,8 +197,22 @@ pub struct GenericRule<F> {
     matcher_name: Option<String>,
     sql_pattern: Option<String>,
     redis_pattern: Option<String>,
+    kind: Option<RuleKind>,
+    data_type: Option<RedisDataType>,
     /// Optional direct command builder (for Lua EVAL commands)
-    direct_command_builder: Option<Box<dyn Fn(&Statement) -> Option<String>>>,
+    direct_command_builder: Option<DirectCommandBuilder>,
+    profile: Option<Profile>,
+    result_decoder: Option<ResultDecoder>,
+    decodes_projection: bool,
+    decodes_values: bool,
+    since: Option<String>,
+    deprecated: Option<String>,
+    replacement: Option<String>,
+    consumes: Vec<Clause>,
+    consumes_predicates: bool,
+    last_resort: bool,
+    warnings: Vec<Warning>,
+    rewrite: Option<Rewrite>,
 }
 
 impl<F> GenericRule<F> 
@@ -69,7 +227,21 @@ where
             matcher_name: None,
             sql_pattern: None,
             redis_pattern: None,
+            kind: None,
+            data_type: None,
             direct_command_builder: None,
+            profile: None,
+            result_decoder: None,
+            decodes_projection: false,
+            decodes_values: false,
+            since: None,
+            deprecated: None,
+            replacement: None,
+            consumes: Vec::new(),
+            consumes_predicates: false,
+            last_resort: false,
+            warnings: Vec::new(),
+            rewrite: None,
         }
     }
     
@@ -88,19 +260,110 @@ where
         self
     }
     
+    /// Record the kind of statement this rule translates
+    pub fn with_kind(mut self, kind: RuleKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+    
+    /// Record the Redis data type this rule operates on
+    pub fn with_data_type(mut self, data_type: RedisDataType) -> Self {
+        self.data_type = Some(data_type);
+        self
+    }
+    
     /// Attach a direct command builder for Lua EVAL commands
     pub fn with_direct_command<D>(mut self, builder: D) -> Self
     where
-        D: Fn(&Statement) -> Option<String> + 'static,
+        D: Fn(&Statement) -> Option<String> + Send + Sync + 'static,
     {
         self.direct_command_builder = Some(Box::new(builder));
         self
     }
+    
+    /// Only apply this rule when the given profile is enabled
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+    
+    /// Attach a decoder that maps the command's reply onto result rows
+    pub fn with_result_decoder(mut self, decoder: ResultDecoder) -> Self {
+        self.result_decoder = Some(decoder);
+        self
+    }
+    
+    /// Declare that the decoder's rows are already projected (the command computes the projection)
+    pub fn with_projected_rows(mut self) -> Self {
+        self.decodes_projection = true;
+        self
+    }
+    
+    /// Declare that the reply holds the projected fields' values in order, so rows are named
+    /// by the projected columns (aliases included)
+    pub fn with_value_rows(mut self) -> Self {
+        self.decodes_values = true;
+        self
+    }
+    
+    /// Record the crate version that introduced this rule
+    pub fn with_since(mut self, version: &str) -> Self {
+        self.since = Some(version.to_string());
+        self
+    }
+    
+    /// Mark this rule deprecated as of the given crate version
+    pub fn with_deprecated(mut self, version: &str) -> Self {
+        self.deprecated = Some(version.to_string());
+        self
+    }
+    
+    /// Declare a clause the generated command honors beyond the defaults
+    pub fn with_consumes(mut self, clause: Clause) -> Self {
+        self.consumes.push(clause);
+        self
+    }
+    
+    /// Declare a kind of WHERE predicate on a column the generated command honors besides
+    /// `key = ...` and `key IN (...)`; other predicates on the column are still dropped
+    pub fn with_consumed_predicate(self, column: &str, kind: PredicateKind) -> Self {
+        self.with_consumes(Clause::Predicate(column.to_string(), kind))
+    }
+    
+    /// Declare that the generated command honors every WHERE predicate, whatever its column
+    pub fn with_consumed_predicates(mut self) -> Self {
+        self.consumes_predicates = true;
+        self
+    }
+    
+    /// Mark this rule a last resort (see `Rule::is_last_resort`)
+    pub fn with_last_resort(mut self) -> Self {
+        self.last_resort = true;
+        self
+    }
+    
+    /// Attach a warning reported whenever this rule is used
+    pub fn with_warning(mut self, kind: WarningKind, message: &str) -> Self {
+        self.warnings.push(Warning::new(kind, message));
+        self
+    }
+    
+    /// Name the rule (by template name) that supersedes this one
+    pub fn with_replacement(mut self, template_name: &str) -> Self {
+        self.replacement = Some(template_name.to_string());
+        self
+    }
+    
+    /// Suggest rewriting matched statements (e.g. adding a LIMIT) for a bounded alternative
+    pub fn with_rewrite(mut self, rewrite: Rewrite) -> Self {
+        self.rewrite = Some(rewrite);
+        self
+    }
 }
 
 impl<F> Rule for GenericRule<F> 
 where
-    F: Fn(&Statement) -> bool,
+    F: Fn(&Statement) -> bool + Send + Sync,
 {
     fn matches(&self, stmt: &Statement) -> bool {
         (self.matcher)(stmt)
@@ -129,6 +392,60 @@ where
     fn get_redis_pattern(&self) -> Option<&str> {
         self.redis_pattern.as_deref()
     }
+    
+    fn get_kind(&self) -> Option<RuleKind> {
+        self.kind
+    }
+    
+    fn get_data_type(&self) -> Option<RedisDataType> {
+        self.data_type
+    }
+    
+    fn get_profile(&self) -> Option<Profile> {
+        self.profile
+    }
+    
+    fn get_result_decoder(&self) -> Option<ResultDecoder> {
+        self.result_decoder
+    }
+    
+    fn decodes_projection(&self) -> bool {
+        self.decodes_projection
+    }
+    
+    fn decodes_values(&self) -> bool {
+        self.decodes_values
+    }
+    
+    fn get_since(&self) -> Option<&str> {
+        self.since.as_deref()
+    }
+    
+    fn get_deprecated(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+    
+    fn get_replacement(&self) -> Option<&str> {
+        self.replacement.as_deref()
+    }
+    
+    fn get_warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+    
+    fn get_rewrite(&self) -> Option<Rewrite> {
+        self.rewrite
+    }
+    
+    fn is_last_resort(&self) -> bool {
+        self.last_resort
+    }
+    
+    fn consumes(&self, clause: &Clause) -> bool {
+        consumed_by_default(clause)
+            || self.consumes.contains(clause)
+            || (self.consumes_predicates && matches!(clause, Clause::Predicate(..)))
+    }
 }
 
 
@@ -136,6 +453,9 @@ where
 pub fn create_rules() -> Vec<Box<dyn Rule>> {
     let mut rules = Vec::new();
     
+    // Add table-function rules (SELECT * FROM redis_get('k'))
+    rules.extend(create_table_function_rules());
+    
     // Add SELECT rules
     rules.extend(create_select_rules());
     
@@ -150,5 +470,17 @@ pub fn create_rules() -> Vec<Box<dyn Rule>> {
 
     rules.extend(create_delete_rules());
 
+    // Add CREATE TRIGGER rules (keyspace notifications)
+
+    rules.extend(create_trigger_rules());
+
+    // Add session rules (USE => SELECT)
+
+    rules.extend(create_session_rules());
+
+    // Add admin rules (only active under the admin profile)
+
+    rules.extend(create_admin_rules());
+
     rules
 }
\ No newline at end of file
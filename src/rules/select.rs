@@ -5,9 +5,27 @@ use sqlparser::ast::Statement;
 use crate::pattern::matchers::select;
 use crate::context;
 use crate::rules::Rule;
-use crate::rules::GenericRule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::pattern::matchers::common::RedisDataType;
+use crate::warning::WarningKind;
 use crate::ast;
+use crate::ast::{Clause, PredicateKind};
+use crate::expr;
 use crate::lua;
+use crate::reply;
+use crate::rewrite::{Rewrite, PAGE_SIZE};
+use crate::pattern::extractors::extract_zset_hash_join;
+use crate::rules::fallback::build_ordered_lua_filter;
+
+/// Helper: build the EVAL command computing a hash SELECT's projected expressions
+fn build_hash_computed_select(stmt: &Statement) -> Option<String> {
+    let select = ast::sel_get_query(stmt).and_then(ast::sel_get_select)?;
+    let key = ast::sel_get_key_value(&select.selection)?;
+    let projections: Vec<_> = expr::projections(&select.projection)?.into_iter()
+        .map(|(projection, name)| (projection.to_case(), name))
+        .collect();
+    Some(lua::hash_computed_select(&key, &projections))
+}
 
 /// Helper: build a zset aggregate EVAL command from a statement
 fn build_zset_aggregate(stmt: &Statement, script: &str) -> Option<String> {
@@ -19,14 +37,12 @@ fn build_zset_aggregate(stmt: &Statement, script: &str) -> Option<String> {
     let (min, max) = ast::sel_get_query(stmt)
         .and_then(ast::sel_get_select)
         .and_then(|s| ast::sel_get_score_range(&s.selection))
-        .or_else(|| {
-            ast::sel_get_query(stmt)
-                .and_then(ast::sel_get_select)
-                .and_then(|s| ast::sel_get_score_between(&s.selection))
-        })
         .unwrap_or_else(|| ("-inf".to_string(), "+inf".to_string()));
     
-    Some(lua::zset_aggregate(script, &key, &min, &max))
+    let having = ast::sel_get_query(stmt)
+        .and_then(ast::sel_get_select)
+        .and_then(ast::sel_get_having)?;
+    Some(lua::zset_aggregate(script, &key, &min, &max, &having))
 }
 
 /// Helper: build a hash field aggregate EVAL command
@@ -35,20 +51,113 @@ fn build_hash_field_aggregate(stmt: &Statement, script: &str) -> Option<String>
     let key = ast::sel_get_key_value(&select.selection)?;
     let agg = ast::sel_get_aggregate(&select)?;
     let field = agg.field?;
-    Some(lua::hash_aggregate(script, &key, &[field]))
+    let having = ast::sel_get_having(select)?;
+    Some(lua::hash_aggregate(script, &key, &[field], &having))
 }
 
 /// Helper: build a list aggregate EVAL command
 fn build_list_aggregate(stmt: &Statement, script: &str) -> Option<String> {
-    let key = ast::sel_get_query(stmt)
-        .and_then(ast::sel_get_select)
-        .and_then(|s| ast::sel_get_key_value(&s.selection))?;
-    Some(lua::list_aggregate(script, &key))
+    let select = ast::sel_get_query(stmt).and_then(ast::sel_get_select)?;
+    let key = ast::sel_get_key_value(&select.selection)?;
+    let having = ast::sel_get_having(select)?;
+    Some(lua::list_aggregate(script, &key, &having))
+}
+
+/// Helper: build the top-K-per-group EVAL command
+fn build_zset_top_per_group(stmt: &Statement) -> Option<String> {
+    let query = ast::sel_get_query(stmt)?;
+    let (_, groups) = ast::sel_get_key_in_set(&ast::sel_get_select(query)?.selection)?;
+    let descending = ast::sel_get_score_order(query)?;
+    let limit = select::query_has_limit(query)?;
+    Some(lua::zset_top_per_group(&groups, descending, limit))
+}
+
+/// Helper: build the EVAL command joining a sorted set's members to their hashes
+fn build_zset_hash_join(stmt: &Statement) -> Option<String> {
+    let join = extract_zset_hash_join(stmt)?;
+    Some(lua::zset_hash_join(&join.key, &join.key_prefix, join.left, join.descending, join.offset, join.limit, &join.columns))
+}
+
+/// Helper: build the EVAL command counting the keys a `__keys` WHERE clause selects
+fn build_keys_count(stmt: &Statement) -> Option<String> {
+    let select = ast::sel_get_query(stmt).and_then(ast::sel_get_select)?;
+    Some(lua::keys_count(&ast::sel_get_key_pattern(&select.selection)?))
 }
 
 /// Create all rules for SELECT statement transformations with rich metadata
 pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
     vec![
+        // ================================
+        // Key metadata pseudo-columns
+        // (table-type agnostic, so they must precede field projections)
+        // ================================
+        
+        // <object-idletime> ::= SELECT __idletime FROM table WHERE key = value => OBJECT IDLETIME value
+        Box::new(GenericRule::new(
+            select::is_object_idletime,
+            Box::new(context::KeyMetadataContextBuilder),
+            "object_idletime"
+        )
+        .with_matcher_name("is_object_idletime")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT __idletime FROM table WHERE key = 'value'")
+        .with_redis_pattern("OBJECT IDLETIME value")),
+        
+        // <object-freq> ::= SELECT __freq FROM table WHERE key = value => OBJECT FREQ value
+        Box::new(GenericRule::new(
+            select::is_object_freq,
+            Box::new(context::KeyMetadataContextBuilder),
+            "object_freq"
+        )
+        .with_matcher_name("is_object_freq")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT __freq FROM table WHERE key = 'value'")
+        .with_redis_pattern("OBJECT FREQ value")),
+        
+        // ================================
+        // Keyspace browsing
+        // ================================
+        
+        // <keys-browse> ::= SELECT * FROM __keys [WHERE key LIKE pattern] [LIMIT n] => SCAN 0 MATCH glob [COUNT n]
+        Box::new(GenericRule::new(
+            select::is_keys_browse,
+            Box::new(context::KeysBrowseContextBuilder),
+            "keys_scan"
+        )
+        .with_matcher_name("is_keys_browse")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT * FROM __keys WHERE key LIKE 'prefix:%' LIMIT n")
+        .with_redis_pattern("SCAN 0 MATCH prefix:* COUNT n")
+        .with_consumed_predicate("key", PredicateKind::Like)
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::FullScan, "SCAN walks the whole keyspace; executing follows the cursor until LIMIT keys match, then reads each key's TYPE and value")
+        .with_result_decoder(reply::decode_browsed_keys)),
+        
+        // <keys-count> ::= SELECT COUNT(*) FROM __keys => DBSIZE
+        Box::new(GenericRule::new(
+            select::is_keys_count,
+            Box::new(context::SystemTableContextBuilder),
+            "keys_count"
+        )
+        .with_matcher_name("is_keys_count")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT COUNT(*) FROM __keys")
+        .with_redis_pattern("DBSIZE")),
+        
+        // <keys-count-pattern> ::= SELECT COUNT(*) FROM __keys WHERE key LIKE pattern => EVAL (SCAN count)
+        Box::new(GenericRule::new(
+            select::is_keys_count_pattern,
+            Box::new(context::KeysBrowseContextBuilder),
+            "keys_count_pattern"
+        )
+        .with_matcher_name("is_keys_count_pattern")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT COUNT(*) FROM __keys WHERE key LIKE 'prefix:%'")
+        .with_redis_pattern("EVAL '<lua: SCAN count>' 0 prefix:*")
+        .with_consumed_predicate("key", PredicateKind::Like)
+        .with_warning(WarningKind::FullScan, "The script SCANs the whole keyspace in one call, blocking the server until it returns; executing streams the SCAN from the client instead")
+        .with_direct_command(build_keys_count)),
+        
         // ================================
         // String operations
         // ================================
@@ -60,6 +169,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "string_mget"
         )
         .with_matcher_name("is_string_get_multi")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("SELECT * FROM table WHERE key IN ('value1', 'value2')")
         .with_redis_pattern("MGET value1 value2")),
         
@@ -70,6 +181,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "string_get"
         )
         .with_matcher_name("is_string_get")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("SELECT * FROM table WHERE key = 'value'")
         .with_redis_pattern("GET value")),
 
@@ -79,6 +192,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "string_get"
         )
         .with_matcher_name("is_string_get_value")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("SELECT value FROM table WHERE key = 'value'")
         .with_redis_pattern("GET value")),        
 
@@ -86,6 +201,21 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
         // Hash operations
         // ================================
         
+        // <hash-scan> ::= SELECT * FROM table__hash WHERE key = value LIMIT n => HSCAN value 0 COUNT n
+        Box::new(GenericRule::new(
+            select::is_hash_scan,
+            Box::new(context::PageContextBuilder), 
+            "hash_scan"
+        )
+        .with_matcher_name("is_hash_scan")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
+        .with_sql_pattern("SELECT * FROM table__hash WHERE key = 'value' LIMIT n")
+        .with_redis_pattern("HSCAN value 0 COUNT n")
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::ClientSideEval, "HSCAN COUNT is a hint: a page may hold more or fewer fields, and the client continues from the returned cursor")
+        .with_result_decoder(reply::decode_hash_scan)),
+        
         // <hash-getall> ::= SELECT * FROM table__hash WHERE key = value => HGETALL value
         Box::new(GenericRule::new(
             select::is_hash_getall,
@@ -93,8 +223,43 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_getall"
         )
         .with_matcher_name("is_hash_getall")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT * FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("HGETALL value")),
+        .with_redis_pattern("HGETALL value")
+        .with_warning(WarningKind::FullScan, "HGETALL reads every field of the hash")
+        .with_rewrite(Rewrite::Limit(PAGE_SIZE))
+        .with_result_decoder(reply::decode_hash_fields)),
+        
+        // <hash-coalesce> ::= SELECT COALESCE(f1, f2) FROM table__hash WHERE key = value => EVAL (HMGET + fallback)
+        Box::new(GenericRule::new(
+            select::is_hash_coalesce,
+            Box::new(context::CountContextBuilder),
+            "hash_coalesce"
+        )
+        .with_matcher_name("is_hash_coalesce")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
+        .with_sql_pattern("SELECT COALESCE(nickname, name) AS display FROM table__hash WHERE key = 'value'")
+        .with_redis_pattern("EVAL '<lua: HMGET + fallback>' 1 value display nickname name nickname name")
+        .with_direct_command(build_hash_computed_select)
+        .with_result_decoder(reply::decode_hash_fields)
+        .with_projected_rows()),
+        
+        // <hash-getall-projected> ::= SELECT f1, CASE ... END AS a2, UPPER(f3) FROM table__hash WHERE key = value => HGETALL value
+        Box::new(GenericRule::new(
+            select::is_hash_getall_projected,
+            Box::new(context::HashGetAllContextBuilder), 
+            "hash_getall"
+        )
+        .with_matcher_name("is_hash_getall_projected")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
+        .with_sql_pattern("SELECT field1, CASE WHEN field2 = 'x' THEN 'y' END AS alias2 FROM table__hash WHERE key = 'value'")
+        .with_redis_pattern("HGETALL value")
+        .with_warning(WarningKind::FullScan, "HGETALL reads every field of the hash")
+        .with_warning(WarningKind::ClientSideEval, "Computed projections (CASE, UPPER, LOWER, TRIM, REPLACE) are evaluated over the HGETALL reply by the client")
+        .with_result_decoder(reply::decode_hash_fields)),
         
         // <hash-get> ::= SELECT field FROM table__hash WHERE key = value => HGET value field
         Box::new(GenericRule::new(
@@ -103,8 +268,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_get"
         )
         .with_matcher_name("is_hash_get")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT field FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("HGET value field")),
+        .with_redis_pattern("HGET value field")
+        .with_value_rows()),
         
         // <hash-hmget> ::= SELECT f1, f2 FROM table__hash WHERE key = value => HMGET value f1 f2
         Box::new(GenericRule::new(
@@ -113,8 +281,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_hmget"
         )
         .with_matcher_name("is_hash_hmget")
-        .with_sql_pattern("SELECT field1, field2 FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("HMGET value field1 field2...")),
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
+        .with_sql_pattern("SELECT field1, field2 AS alias2 FROM table__hash WHERE key = 'value'")
+        .with_redis_pattern("HMGET value field1 field2...")
+        .with_value_rows()),
         
         // <hash-count> ::= SELECT COUNT(*) FROM table__hash WHERE key = value => HLEN value
         Box::new(GenericRule::new(
@@ -123,6 +294,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_count"
         )
         .with_matcher_name("is_hash_count")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT COUNT(*) FROM table__hash WHERE key = 'value'")
         .with_redis_pattern("HLEN value")),
         
@@ -133,8 +306,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_avg"
         )
         .with_matcher_name("is_hash_avg")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT AVG(field) FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key field")
+        .with_redis_pattern("EVAL '<lua>' 1 value field")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_hash_field_aggregate(s, lua::HASH_FIELD_AVG_SCRIPT))),
         
         // <hash-sum> ::= SELECT SUM(field) FROM table__hash WHERE key = value (Lua)
@@ -144,8 +320,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_sum"
         )
         .with_matcher_name("is_hash_sum")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT SUM(field) FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key field")
+        .with_redis_pattern("EVAL '<lua>' 1 value field")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_hash_field_aggregate(s, lua::HASH_FIELD_SUM_SCRIPT))),
         
         // <hash-min> ::= SELECT MIN(field) FROM table__hash WHERE key = value (Lua)
@@ -155,8 +334,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_min"
         )
         .with_matcher_name("is_hash_min")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT MIN(field) FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key field")
+        .with_redis_pattern("EVAL '<lua>' 1 value field")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_hash_field_aggregate(s, lua::HASH_FIELD_MIN_SCRIPT))),
         
         // <hash-max> ::= SELECT MAX(field) FROM table__hash WHERE key = value (Lua)
@@ -166,8 +348,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "hash_max"
         )
         .with_matcher_name("is_hash_max")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("SELECT MAX(field) FROM table__hash WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key field")
+        .with_redis_pattern("EVAL '<lua>' 1 value field")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_hash_field_aggregate(s, lua::HASH_FIELD_MAX_SCRIPT))),
 
         // ================================
@@ -182,8 +367,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_avg"
         )
         .with_matcher_name("is_list_avg")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT AVG(value) FROM table__list WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key")
+        .with_redis_pattern("EVAL '<lua>' 1 value")
+        .with_warning(WarningKind::FullScan, "Lua script reads every element of the key")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_list_aggregate(s, lua::LIST_AVG_SCRIPT))),
         
         // <list-sum> ::= SELECT SUM(value) FROM table__list WHERE key = value (Lua)
@@ -193,8 +382,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_sum"
         )
         .with_matcher_name("is_list_sum")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT SUM(value) FROM table__list WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key")
+        .with_redis_pattern("EVAL '<lua>' 1 value")
+        .with_warning(WarningKind::FullScan, "Lua script reads every element of the key")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_list_aggregate(s, lua::LIST_SUM_SCRIPT))),
         
         // <list-min> ::= SELECT MIN(value) FROM table__list WHERE key = value (Lua)
@@ -204,8 +397,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_min"
         )
         .with_matcher_name("is_list_min")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT MIN(value) FROM table__list WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key")
+        .with_redis_pattern("EVAL '<lua>' 1 value")
+        .with_warning(WarningKind::FullScan, "Lua script reads every element of the key")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_list_aggregate(s, lua::LIST_MIN_SCRIPT))),
         
         // <list-max> ::= SELECT MAX(value) FROM table__list WHERE key = value (Lua)
@@ -215,8 +412,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_max"
         )
         .with_matcher_name("is_list_max")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT MAX(value) FROM table__list WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key")
+        .with_redis_pattern("EVAL '<lua>' 1 value")
+        .with_warning(WarningKind::FullScan, "Lua script reads every element of the key")
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_list_aggregate(s, lua::LIST_MAX_SCRIPT))),
         
         // <list-get-index> ::= SELECT * FROM table__list WHERE key = value AND index = n => LINDEX value n
@@ -226,8 +427,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_get_index"
         )
         .with_matcher_name("is_list_get_index")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT * FROM table__list WHERE key = 'value' AND index = n")
-        .with_redis_pattern("LINDEX value n")),
+        .with_redis_pattern("LINDEX value n")
+        .with_consumed_predicate("index", PredicateKind::Equals)),
         
         // <list-get-index-range> ::= SELECT * FROM table__list WHERE key = value AND index < n => LRANGE value 0 n-1
         Box::new(GenericRule::new(
@@ -236,8 +440,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_get_index_range"
         )
         .with_matcher_name("is_list_get_index_range")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT * FROM table__list WHERE key = 'value' AND index < n")
-        .with_redis_pattern("LRANGE value 0 n-1")),
+        .with_redis_pattern("LRANGE value 0 n-1")
+        .with_consumed_predicate("index", PredicateKind::Range)),
         
         // <list-get-range> ::= SELECT * FROM table__list WHERE key = value LIMIT n => LRANGE value 0 n-1
         Box::new(GenericRule::new(
@@ -246,8 +453,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_get_range"
         )
         .with_matcher_name("is_list_get_range")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT * FROM table__list WHERE key = 'value' LIMIT n")
-        .with_redis_pattern("LRANGE value 0 n-1")),
+        .with_redis_pattern("LRANGE value 0 n-1")
+        .with_consumes(Clause::Limit)),
         
         // <list-getall> ::= SELECT * FROM table__list WHERE key = value => LRANGE value 0 -1
         Box::new(GenericRule::new(
@@ -256,8 +466,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_getall"
         )
         .with_matcher_name("is_list_getall")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT * FROM table__list WHERE key = 'value'")
-        .with_redis_pattern("LRANGE value 0 -1")),
+        .with_redis_pattern("LRANGE value 0 -1")
+        .with_warning(WarningKind::FullScan, "LRANGE 0 -1 reads the whole list")
+        .with_rewrite(Rewrite::Limit(PAGE_SIZE))),
         
         // <list-count> ::= SELECT COUNT(*) FROM table__list WHERE key = value => LLEN value
         Box::new(GenericRule::new(
@@ -266,6 +480,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "list_count"
         )
         .with_matcher_name("is_list_count")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("SELECT COUNT(*) FROM table__list WHERE key = 'value'")
         .with_redis_pattern("LLEN value")),
 
@@ -273,6 +489,41 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
         // Set operations
         // ================================
         
+        // <member-like-filter> ::= SELECT * FROM table__set WHERE key = value AND member LIKE p AND ... [ORDER BY c] [LIMIT n] => EVAL '<lua>' 1 value member ^p.*$ ...
+        // (sets and sorted sets: a member pattern ANDed with predicates SCAN MATCH can't express;
+        // the script sorts and cuts the matching rows)
+        Box::new(GenericRule::new(
+            select::is_member_like_filter,
+            Box::new(context::CountContextBuilder),
+            "member_like_filter"
+        )
+        .with_matcher_name("is_member_like_filter")
+        .with_kind(RuleKind::Select)
+        .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' AND member LIKE 'p%' AND score > n")
+        .with_redis_pattern("EVAL '<lua: ZRANGE WITHSCORES + filter>' 1 value member ^p.*$ score n")
+        .with_consumed_predicates()
+        .with_consumes(Clause::OrderBy)
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::FullScan, "Lua script reads the whole key to match its members")
+        .with_result_decoder(reply::decode_rows)
+        .with_projected_rows()
+        .with_direct_command(build_ordered_lua_filter)),
+        
+        // <set-scan-match> ::= SELECT * FROM table__set WHERE key = value AND member LIKE p [LIMIT n] => SSCAN value 0 MATCH glob [COUNT n]
+        Box::new(GenericRule::new(
+            select::is_set_scan_match,
+            Box::new(context::MemberPatternContextBuilder),
+            "set_scan_match"
+        )
+        .with_matcher_name("is_set_scan_match")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Set)
+        .with_sql_pattern("SELECT * FROM table__set WHERE key = 'value' AND member LIKE 'prefix:%' LIMIT n")
+        .with_redis_pattern("SSCAN value 0 MATCH prefix:* COUNT n")
+        .with_consumed_predicate("member", PredicateKind::Like)
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::ClientSideEval, "SSCAN returns one page of matches; the client continues from the returned cursor")),
+        
         // <set-ismember> ::= SELECT * FROM table__set WHERE key = value AND member = m => SISMEMBER value m
         Box::new(GenericRule::new(
             select::is_set_ismember,
@@ -280,8 +531,25 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "set_ismember"
         )
         .with_matcher_name("is_set_ismember")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("SELECT * FROM table__set WHERE key = 'value' AND member = 'member'")
-        .with_redis_pattern("SISMEMBER value member")),
+        .with_redis_pattern("SISMEMBER value member")
+        .with_consumed_predicate("member", PredicateKind::Equals)),
+        
+        // <set-scan> ::= SELECT * FROM table__set WHERE key = value LIMIT n => SSCAN value 0 COUNT n
+        Box::new(GenericRule::new(
+            select::is_set_scan,
+            Box::new(context::PageContextBuilder), 
+            "set_scan"
+        )
+        .with_matcher_name("is_set_scan")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Set)
+        .with_sql_pattern("SELECT * FROM table__set WHERE key = 'value' LIMIT n")
+        .with_redis_pattern("SSCAN value 0 COUNT n")
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::ClientSideEval, "SSCAN COUNT is a hint: a page may hold more or fewer members, and the client continues from the returned cursor")),
         
         // <set-getall> ::= SELECT * FROM table__set WHERE key = value => SMEMBERS value
         Box::new(GenericRule::new(
@@ -290,8 +558,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "set_getall"
         )
         .with_matcher_name("is_set_getall")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("SELECT * FROM table__set WHERE key = 'value'")
-        .with_redis_pattern("SMEMBERS value")),
+        .with_redis_pattern("SMEMBERS value")
+        .with_warning(WarningKind::FullScan, "SMEMBERS reads the whole set")
+        .with_rewrite(Rewrite::Limit(PAGE_SIZE))),
         
         // <set-count> ::= SELECT COUNT(*) FROM table__set WHERE key = value => SCARD value
         Box::new(GenericRule::new(
@@ -300,6 +572,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "set_count"
         )
         .with_matcher_name("is_set_count")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::Set)
         .with_sql_pattern("SELECT COUNT(*) FROM table__set WHERE key = 'value'")
         .with_redis_pattern("SCARD value")),
 
@@ -315,8 +589,13 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_avg"
         )
         .with_matcher_name("is_zset_avg")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT AVG(score) FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key -inf +inf")
+        .with_redis_pattern("EVAL '<lua>' 1 value -inf +inf")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member in the score range")
+        .with_consumed_predicate("score", PredicateKind::Range)
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_zset_aggregate(s, lua::ZSET_AVG_SCRIPT))),
         
         // <zset-sum> ::= SELECT SUM(score) FROM table__zset WHERE key = value
@@ -326,8 +605,13 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_sum"
         )
         .with_matcher_name("is_zset_sum")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT SUM(score) FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key -inf +inf")
+        .with_redis_pattern("EVAL '<lua>' 1 value -inf +inf")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member in the score range")
+        .with_consumed_predicate("score", PredicateKind::Range)
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_zset_aggregate(s, lua::ZSET_SUM_SCRIPT))),
         
         // <zset-min> ::= SELECT MIN(score) FROM table__zset WHERE key = value
@@ -337,8 +621,13 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_min"
         )
         .with_matcher_name("is_zset_min")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT MIN(score) FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key -inf +inf")
+        .with_redis_pattern("EVAL '<lua>' 1 value -inf +inf")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member in the score range")
+        .with_consumed_predicate("score", PredicateKind::Range)
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_zset_aggregate(s, lua::ZSET_MIN_SCRIPT))),
         
         // <zset-max> ::= SELECT MAX(score) FROM table__zset WHERE key = value
@@ -348,8 +637,13 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_max"
         )
         .with_matcher_name("is_zset_max")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT MAX(score) FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key -inf +inf")
+        .with_redis_pattern("EVAL '<lua>' 1 value -inf +inf")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member in the score range")
+        .with_consumed_predicate("score", PredicateKind::Range)
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_zset_aggregate(s, lua::ZSET_MAX_SCRIPT))),
         
         // <zset-stddev-pop> ::= SELECT STDDEV_POP(score) FROM table__zset WHERE key = value
@@ -359,10 +653,30 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_stddev_pop"
         )
         .with_matcher_name("is_zset_stddev_pop")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT STDDEV_POP(score) FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("EVAL '<lua>' 1 key -inf +inf")
+        .with_redis_pattern("EVAL '<lua>' 1 value -inf +inf")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member in the score range")
+        .with_consumed_predicate("score", PredicateKind::Range)
+        .with_consumes(Clause::Having)
         .with_direct_command(move |s| build_zset_aggregate(s, lua::ZSET_STDDEV_POP_SCRIPT))),
         
+        // <zset-scan-match> ::= SELECT * FROM table__zset WHERE key = value AND member LIKE p [LIMIT n] => ZSCAN value 0 MATCH glob [COUNT n]
+        Box::new(GenericRule::new(
+            select::is_zset_scan_match,
+            Box::new(context::MemberPatternContextBuilder),
+            "zset_scan_match"
+        )
+        .with_matcher_name("is_zset_scan_match")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' AND member LIKE 'prefix:%' LIMIT n")
+        .with_redis_pattern("ZSCAN value 0 MATCH prefix:* COUNT n")
+        .with_consumed_predicate("member", PredicateKind::Like)
+        .with_consumes(Clause::Limit)
+        .with_warning(WarningKind::ClientSideEval, "ZSCAN returns one page of matches in hash order, not by score; the client continues from the returned cursor")),
+        
         // <zset-get-score-between> ::= SELECT * FROM table__zset WHERE key = value AND score BETWEEN n AND m
         Box::new(GenericRule::new(
             select::is_zset_get_score_between,
@@ -370,8 +684,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_get_score_between"
         )
         .with_matcher_name("is_zset_get_score_between")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' AND score BETWEEN n AND m")
-        .with_redis_pattern("ZRANGEBYSCORE value n m")),
+        .with_redis_pattern("ZRANGEBYSCORE value n m")
+        .with_consumed_predicate("score", PredicateKind::Range)),
         
         // <zset-get-score-range> ::= SELECT * FROM table__zset WHERE key = value AND score > n
         Box::new(GenericRule::new(
@@ -380,8 +697,52 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_get_score_range"
         )
         .with_matcher_name("is_zset_get_score_range")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' AND score > n")
-        .with_redis_pattern("ZRANGEBYSCORE value (n +inf")),
+        .with_redis_pattern("ZRANGEBYSCORE value (n +inf")
+        .with_consumed_predicate("score", PredicateKind::Range)),
+        
+        // <zset-top-per-group> ::= SELECT * FROM table__zset WHERE key IN (SELECT member FROM table__set
+        //     WHERE key = value) ORDER BY score DESC LIMIT n (Lua: top n of each zset the set names)
+        Box::new(GenericRule::new(
+            select::is_zset_top_per_group,
+            Box::new(context::CountContextBuilder),
+            "zset_top_per_group"
+        )
+        .with_matcher_name("is_zset_top_per_group")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("SELECT * FROM table__zset WHERE key IN (SELECT member FROM table__set WHERE key = 'value') ORDER BY score DESC LIMIT n")
+        .with_redis_pattern("EVAL '<lua>' 1 value ZREVRANGEBYSCORE n +inf -inf *")
+        .with_warning(WarningKind::FullScan, "Lua script reads every member of the group set; the zsets it names aren't declared as KEYS, so under Redis Cluster they must share the set's slot")
+        .with_consumes(Clause::SubqueryPredicate("key".to_string()))
+        .with_consumes(Clause::OrderBy)
+        .with_consumes(Clause::Limit)
+        .with_direct_command(build_zset_top_per_group)
+        .with_result_decoder(reply::decode_group_member_scores)),
+        
+        // <zset-hash-join> ::= SELECT l.member, u.field FROM table__zset l JOIN table__hash u
+        //     ON u.key = l.member WHERE l.key = value [ORDER BY l.score] [LIMIT n] (Lua: HMGET per member)
+        Box::new(GenericRule::new(
+            select::is_zset_hash_join,
+            Box::new(context::CountContextBuilder),
+            "zset_hash_join"
+        )
+        .with_matcher_name("is_zset_hash_join")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("SELECT l.member, l.score, u.field FROM table__zset l JOIN table__hash u ON u.key = l.member WHERE l.key = 'value' ORDER BY l.score DESC LIMIT n")
+        .with_redis_pattern("EVAL '<lua>' 1 value ZREVRANGE 0 n inner * 10000 3 member @member score @score field field")
+        .with_warning(WarningKind::FullScan, "Lua script walks the sorted set and reads each member's hash; the hashes aren't declared as KEYS, so under Redis Cluster they must share the sorted set's slot")
+        .with_consumed_predicates()
+        .with_consumes(Clause::Projection)
+        .with_consumes(Clause::OrderBy)
+        .with_consumes(Clause::Limit)
+        .with_consumes(Clause::Offset)
+        .with_direct_command(build_zset_hash_join)
+        .with_result_decoder(reply::decode_rows)
+        .with_projected_rows()),
         
         // <zset-get-reversed> ::= SELECT * FROM table__zset WHERE key = value ORDER BY score DESC
         Box::new(GenericRule::new(
@@ -390,8 +751,11 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_get_reversed"
         )
         .with_matcher_name("is_zset_get_reversed")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' ORDER BY score DESC")
-        .with_redis_pattern("ZREVRANGEBYSCORE value +inf -inf")),
+        .with_redis_pattern("ZREVRANGEBYSCORE value +inf -inf")
+        .with_consumes(Clause::OrderBy)),
         
         // <zset-count-score-range> ::= SELECT COUNT(*) FROM table__zset WHERE key = value AND score BETWEEN n AND m
         Box::new(GenericRule::new(
@@ -400,8 +764,24 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_count_score_range"
         )
         .with_matcher_name("is_zset_count_score_range")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT COUNT(*) FROM table__zset WHERE key = 'value' AND score BETWEEN n AND m")
-        .with_redis_pattern("ZCOUNT value n m")),
+        .with_redis_pattern("ZCOUNT value n m")
+        .with_consumed_predicate("score", PredicateKind::Range)),
+        
+        // <zset-get-limit> ::= SELECT * FROM table__zset WHERE key = value LIMIT n => ZRANGEBYSCORE value -inf +inf LIMIT 0 n
+        Box::new(GenericRule::new(
+            select::is_zset_get_limit,
+            Box::new(context::PageContextBuilder), 
+            "zset_get_limit"
+        )
+        .with_matcher_name("is_zset_get_limit")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
+        .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value' LIMIT n")
+        .with_redis_pattern("ZRANGEBYSCORE value -inf +inf LIMIT 0 n")
+        .with_consumes(Clause::Limit)),
         
         // <zset-getall> ::= SELECT * FROM table__zset WHERE key = value => ZRANGEBYSCORE value -inf +inf
         Box::new(GenericRule::new(
@@ -410,8 +790,12 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_getall"
         )
         .with_matcher_name("is_zset_getall")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT * FROM table__zset WHERE key = 'value'")
-        .with_redis_pattern("ZRANGEBYSCORE value -inf +inf")),
+        .with_redis_pattern("ZRANGEBYSCORE value -inf +inf")
+        .with_warning(WarningKind::FullScan, "ZRANGEBYSCORE -inf +inf reads the whole sorted set")
+        .with_rewrite(Rewrite::Limit(PAGE_SIZE))),
         
         // <zset-count> ::= SELECT COUNT(*) FROM table__zset WHERE key = value => ZCARD value
         Box::new(GenericRule::new(
@@ -420,6 +804,8 @@ pub fn create_select_rules() -> Vec<Box<dyn Rule>> {
             "zset_count"
         )
         .with_matcher_name("is_zset_count")
+        .with_kind(RuleKind::Select)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("SELECT COUNT(*) FROM table__zset WHERE key = 'value'")
         .with_redis_pattern("ZCARD value")),
     ]
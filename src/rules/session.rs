@@ -0,0 +1,22 @@
+// rules/session.rs - Session statement transformation rules
+// USE dbN becomes SELECT N for the rest of the connection
+
+use crate::pattern::matchers::session;
+use crate::context;
+use crate::rule;
+use crate::rules::{Rule, RuleKind};
+
+/// Create all rules for session statements
+pub fn create_session_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // <use-database> ::= USE db3 => SELECT 3
+        rule! {
+            template: "select_db",
+            matcher: session::is_use_database,
+            context: context::UseDatabaseContextBuilder,
+            kind: RuleKind::Session,
+            sql: "USE db3",
+            redis: "SELECT 3",
+        },
+    ]
+}
@@ -0,0 +1,26 @@
+// rules/trigger.rs - CREATE TRIGGER transformation rules
+// Triggers become keyspace notification subscriptions (CONFIG SET + PSUBSCRIBE)
+
+use crate::pattern::matchers::trigger;
+use crate::context;
+use crate::reply;
+use crate::rule;
+use crate::rules::{Rule, RuleKind};
+use crate::warning::WarningKind;
+
+/// Create all rules for CREATE TRIGGER statement transformations
+pub fn create_trigger_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        // <keyspace-subscribe> ::= CREATE TRIGGER name AFTER events ON table [WHEN (key LIKE pattern)] ...
+        rule! {
+            template: "keyspace_subscribe",
+            matcher: trigger::is_keyspace_subscription,
+            context: context::KeyspaceSubscriptionContextBuilder,
+            kind: RuleKind::Trigger,
+            sql: "CREATE TRIGGER name AFTER INSERT ON table__hash FOR EACH ROW WHEN (key LIKE 'prefix:%') EXECUTE FUNCTION f()",
+            redis: "CONFIG SET notify-keyspace-events Kh; PSUBSCRIBE __keyspace@0__:prefix:*",
+            decoder: reply::decode_keyspace_event,
+            warning: (WarningKind::ClientSideEval, "the trigger function is not run; subscribers must handle notifications"),
+        },
+    ]
+}
@@ -1,9 +1,22 @@
 // Update rules/update.rs with enhanced metadata
 
-use crate::pattern::matchers::update::{is_string_update, is_hash_update, is_list_update, is_zset_update};
+use sqlparser::ast::Statement;
+use crate::pattern::matchers::update::{is_string_update, is_hash_case_update, is_hash_update, is_list_update, is_zset_update};
+use crate::ast;
+use crate::ast::PredicateKind;
 use crate::context;
+use crate::lua;
 use crate::rules::Rule;
-use crate::rules::GenericRule;
+use crate::rules::{GenericRule, RuleKind};
+use crate::pattern::matchers::common::RedisDataType;
+use crate::warning::WarningKind;
+
+/// Helper: build the EVAL command evaluating CASE assignments against the hash
+fn build_hash_case_update(stmt: &Statement) -> Option<String> {
+    let key = ast::upd_get_key_value(stmt)?;
+    let assignments = ast::upd_get_case_assignments(stmt)?;
+    Some(lua::hash_case_update(&key, &assignments))
+}
 
 /// Create all rules for UPDATE statement transformations with rich metadata
 pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
@@ -19,6 +32,8 @@ pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
             "string_update"
         )
         .with_matcher_name("is_string_update")
+        .with_kind(RuleKind::Update)
+        .with_data_type(RedisDataType::String)
         .with_sql_pattern("UPDATE table SET value = 'new-value' WHERE key = 'key'")
         .with_redis_pattern("SET key new-value")),
         
@@ -26,6 +41,19 @@ pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
         // Hash operations
         // --------------------------------
         
+        // <hash-case-update> ::= "UPDATE" <table> "__hash" "SET" <field> "=" "CASE" "WHEN" <condition> "THEN" <value> ... "END" "WHERE" "key" "=" <key>
+        Box::new(GenericRule::new(
+            is_hash_case_update,
+            Box::new(context::CountContextBuilder),
+            "hash_case_update"
+        )
+        .with_matcher_name("is_hash_case_update")
+        .with_kind(RuleKind::Update)
+        .with_data_type(RedisDataType::Hash)
+        .with_sql_pattern("UPDATE table__hash SET field1 = CASE WHEN field2 = 'a' THEN 'x' ELSE 'y' END WHERE key = 'key'")
+        .with_redis_pattern("EVAL '<lua: HGET + if/elseif + HSET>' 1 key field1 field2 a x y")
+        .with_direct_command(build_hash_case_update)),
+        
         // <hash-update> ::= "UPDATE" <table> "__hash" "SET" <field> "=" <value> ["," <field2> "=" <value2>]... "WHERE" "key" "=" <key>
         Box::new(GenericRule::new(
             is_hash_update,
@@ -33,6 +61,8 @@ pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
             "hash_update"
         )
         .with_matcher_name("is_hash_update")
+        .with_kind(RuleKind::Update)
+        .with_data_type(RedisDataType::Hash)
         .with_sql_pattern("UPDATE table__hash SET field1 = 'value1', field2 = 'value2' WHERE key = 'key'")
         .with_redis_pattern("HSET key field1 value1 field2 value2")),
         
@@ -47,8 +77,11 @@ pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
             "list_update"
         )
         .with_matcher_name("is_list_update")
+        .with_kind(RuleKind::Update)
+        .with_data_type(RedisDataType::List)
         .with_sql_pattern("UPDATE table__list SET value = 'new-value' WHERE key = 'key' AND index = 0")
-        .with_redis_pattern("LSET key 0 new-value")),
+        .with_redis_pattern("LSET key 0 new-value")
+        .with_consumed_predicate("index", PredicateKind::Equals)),
         
         // --------------------------------
         // Sorted Set operations
@@ -61,7 +94,11 @@ pub fn create_update_rules() -> Vec<Box<dyn Rule>> {
             "zset_update"
         )
         .with_matcher_name("is_zset_update")
+        .with_kind(RuleKind::Update)
+        .with_data_type(RedisDataType::SortedSet)
         .with_sql_pattern("UPDATE table__zset SET score = 'new-score' WHERE key = 'key' AND member = 'member'")
-        .with_redis_pattern("ZADD key new-score member")),
+        .with_redis_pattern("ZADD key new-score member")
+        .with_warning(WarningKind::TypeCoercion, "score is converted to a double-precision float")
+        .with_consumed_predicate("member", PredicateKind::Equals)),
     ]
 }
\ No newline at end of file
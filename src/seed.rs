@@ -0,0 +1,99 @@
+// seed.rs - Synthetic data from SQL INSERT templates
+// Placeholders are expanded once per row before transformation, producing bulk datasets
+// for load testing key designs
+
+use thiserror::Error;
+
+use crate::templates::FakeData;
+
+/// A placeholder the template could not interpret
+#[derive(Debug, Error)]
+#[error("Invalid seed template: {0}")]
+pub struct SeedError(pub String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    /// `{{ i }}`: the row number, from 1
+    Index,
+    /// `{{ faker_name() }}` (or `{{ random_name() }}`): "First Last"
+    Name,
+    /// `{{ uuid() }}`: a random version 4 UUID
+    Uuid,
+    /// `{{ now() }}`: Unix time in seconds
+    Now,
+    /// `{{ rand_int(a, b) }}`: an integer from `a` to `b` inclusive
+    RandInt(i64, i64),
+}
+
+/// A SQL template whose `{{ i }}` and fake-data placeholders (`{{ uuid() }}`, `{{ now() }}`,
+/// `{{ rand_int(a, b) }}`, `{{ faker_name() }}`) are filled per row; a template may hold
+/// several statements, one per line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedTemplate {
+    segments: Vec<Segment>,
+}
+
+impl SeedTemplate {
+    pub fn parse(source: &str) -> Result<Self, SeedError> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..].find("}}")
+                .ok_or_else(|| SeedError(format!("unclosed placeholder: {}", &rest[start..])))?;
+            let name = rest[start + 2..start + end].trim();
+            segments.push(parse_placeholder(name)
+                .ok_or_else(|| SeedError(format!("unknown placeholder {{{{ {} }}}}", name)))?);
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// The template text for row `i`
+    pub fn render(&self, i: u64, fake: &mut FakeData) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Index => out.push_str(&i.to_string()),
+                Segment::Name => out.push_str(&fake.faker_name()),
+                Segment::Uuid => out.push_str(&fake.uuid()),
+                Segment::Now => out.push_str(&fake.now().to_string()),
+                Segment::RandInt(a, b) => out.push_str(&fake.rand_int(*a, *b).to_string()),
+            }
+        }
+        out
+    }
+
+    /// The statements of rows 1 through `count`: each row's non-empty, non-comment lines
+    pub fn expand<'a>(&'a self, count: u64, fake: &'a mut FakeData) -> impl Iterator<Item = String> + 'a {
+        (1..=count).flat_map(move |i| {
+            self.render(i, fake)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with("--"))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+/// The segment for a placeholder's (trimmed) contents
+fn parse_placeholder(name: &str) -> Option<Segment> {
+    match name {
+        "i" => return Some(Segment::Index),
+        "faker_name()" | "random_name()" => return Some(Segment::Name),
+        "uuid()" => return Some(Segment::Uuid),
+        "now()" => return Some(Segment::Now),
+        _ => {}
+    }
+    let args = name.strip_prefix("rand_int")?.trim_start().strip_prefix('(')?.strip_suffix(')')?;
+    let (a, b) = args.split_once(',')?;
+    Some(Segment::RandInt(a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
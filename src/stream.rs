@@ -0,0 +1,47 @@
+// stream.rs - Lazy translation of statement streams
+// Statements are pulled from the input one at a time as plans are consumed, so arbitrarily
+// large dumps translate in constant memory and a slow consumer slows the reader
+
+use crate::plan::Plan;
+use crate::{SqlRedisError, SqlToRedisTransformer};
+
+/// The plans of a stream of statements, one statement per line; blank and `--` comment
+/// lines yield nothing. Nothing is read ahead: each `next` reads lines until it finds a
+/// statement, then plans it, so the consumer sets the pace.
+pub struct PlanStream<'a, I> {
+    transformer: &'a SqlToRedisTransformer,
+    lines: I,
+    line: usize,
+}
+
+impl<'a, I> PlanStream<'a, I>
+where
+    I: Iterator<Item = String>,
+{
+    pub fn new(transformer: &'a SqlToRedisTransformer, lines: I) -> Self {
+        Self { transformer, lines, line: 0 }
+    }
+
+    /// 1-based line number of the statement the last plan (or error) is for; 0 before any
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<I> Iterator for PlanStream<'_, I>
+where
+    I: Iterator<Item = String>,
+{
+    type Item = Result<Plan, SqlRedisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            self.line += 1;
+            let sql = line.trim();
+            if !sql.is_empty() && !sql.starts_with("--") {
+                return Some(self.transformer.plan(sql));
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// templates/fake.rs - Deterministic fake data for template functions
+// Backs `uuid()`, `now()`, `rand_int(a, b)`, and `faker_name()` in Tera templates and seed
+// templates; a fixed seed (and pinned time) reproduces the generated command stream
+
+#[cfg(feature = "templates")]
+use std::collections::HashMap;
+#[cfg(feature = "templates")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "templates")]
+use tera::{Tera, Value};
+
+const FIRST_NAMES: &[&str] = &[
+    "Ann", "Bob", "Carla", "Dev", "Elena", "Farid", "Grace", "Hiro", "Ines", "Jon",
+    "Kemal", "Lena", "Mateo", "Nora", "Omar", "Priya", "Quinn", "Rosa", "Sven", "Tara",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Lee", "Smith", "Garcia", "Chen", "Novak", "Okafor", "Silva", "Tanaka", "Weber", "Khan",
+    "Rossi", "Dubois", "Larsen", "Moreau", "Patel", "Kim", "Costa", "Meyer", "Nowak", "Ali",
+];
+
+/// Seedable fake-data source (SplitMix64): the same seed and time give the same values
+#[derive(Debug, Clone)]
+pub struct FakeData {
+    state: u64,
+    now: i64,
+}
+
+impl FakeData {
+    /// Values drawn from `seed`; `now()` reads the system clock once, at creation
+    pub fn new(seed: u64) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        Self { state: seed, now }
+    }
+
+    /// Values seeded from the system clock, differing between runs
+    pub fn unseeded() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::new(seed)
+    }
+
+    /// Pin the time `now()` returns (Unix seconds)
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// `now()`: Unix time in seconds
+    pub fn now(&self) -> i64 {
+        self.now
+    }
+
+    /// `rand_int(a, b)`: an integer between `a` and `b`, both inclusive (in either order)
+    pub fn rand_int(&mut self, a: i64, b: i64) -> i64 {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        let span = high.abs_diff(low).wrapping_add(1);
+        let offset = if span == 0 { self.next_u64() } else { self.next_u64() % span };
+        low.wrapping_add_unsigned(offset)
+    }
+
+    /// `faker_name()`: "First Last"
+    pub fn faker_name(&mut self) -> String {
+        let first = FIRST_NAMES[(self.next_u64() % FIRST_NAMES.len() as u64) as usize];
+        let last = LAST_NAMES[(self.next_u64() % LAST_NAMES.len() as u64) as usize];
+        format!("{} {}", first, last)
+    }
+
+    /// `uuid()`: a version 4 (random) UUID in its hyphenated form
+    pub fn uuid(&mut self) -> String {
+        let high = (self.next_u64() & 0xffff_ffff_ffff_0fff) | 0x4000;
+        let low = (self.next_u64() & 0x3fff_ffff_ffff_ffff) | 0x8000_0000_0000_0000;
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32, (high >> 16) & 0xffff, high & 0xffff, low >> 48, low & 0xffff_ffff_ffff,
+        )
+    }
+
+    /// Register the functions with Tera, sharing one source so calls across renders
+    /// continue a single reproducible stream. Tera takes named arguments: `rand_int(a=1, b=6)`.
+    #[cfg(feature = "templates")]
+    pub(crate) fn register(self, tera: &mut Tera) {
+        let shared = Arc::new(Mutex::new(self));
+        let fake = Arc::clone(&shared);
+        tera.register_function("uuid", move |_: &HashMap<String, Value>| {
+            Ok(Value::from(lock(&fake).uuid()))
+        });
+        let fake = Arc::clone(&shared);
+        tera.register_function("now", move |_: &HashMap<String, Value>| {
+            Ok(Value::from(lock(&fake).now()))
+        });
+        let fake = Arc::clone(&shared);
+        tera.register_function("rand_int", move |args: &HashMap<String, Value>| {
+            let bound = |name: &str| {
+                args.get(name)
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| tera::Error::msg(format!("rand_int requires an integer `{}` argument", name)))
+            };
+            Ok(Value::from(lock(&fake).rand_int(bound("a")?, bound("b")?)))
+        });
+        tera.register_function("faker_name", move |_: &HashMap<String, Value>| {
+            Ok(Value::from(lock(&shared).faker_name()))
+        });
+    }
+}
+
+/// A panicked render can't leave the generator half-updated, so a poisoned lock is still usable
+#[cfg(feature = "templates")]
+fn lock(fake: &Mutex<FakeData>) -> std::sync::MutexGuard<'_, FakeData> {
+    fake.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
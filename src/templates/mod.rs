@@ -1,119 +1,250 @@
 // templates/mod.rs - Template engine for Redis command generation
-// Loads and renders Redis command templates based on SQL patterns
+// Loads and renders Redis command templates based on SQL patterns.
+// Templates in the static subset ({{ var }}, {% if var %}) use the built-in renderer;
+// anything richer needs the Tera engine (`templates` feature).
 
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "templates")]
 use tera::{Context, Tera};
+use crate::commands::RedisCommand;
 use crate::context::TemplateContext;
 use std::io;
-use std::fmt;
-use std::error::Error;
+use thiserror::Error;
 
-#[derive(Debug)]
-pub enum TemplateError {
-    IoError(io::Error),
-    TeraError(tera::Error),
-    InvalidPath(String),
-}
-
-impl fmt::Display for TemplateError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            TemplateError::IoError(e) => write!(f, "IO error: {}", e),
-            TemplateError::TeraError(e) => write!(f, "Template error: {}", e),
-            TemplateError::InvalidPath(p) => write!(f, "Invalid path: {}", p),
-        }
-    }
-}
-
-impl Error for TemplateError {}
+mod fake;
+mod simple;
 
-impl From<io::Error> for TemplateError {
-    fn from(error: io::Error) -> Self {
-        TemplateError::IoError(error)
-    }
-}
+pub use fake::FakeData;
+pub use simple::SimpleTemplate;
 
-impl From<tera::Error> for TemplateError {
-    fn from(error: tera::Error) -> Self {
-        TemplateError::TeraError(error)
-    }
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    #[cfg(feature = "templates")]
+    #[error("Template error: {0}")]
+    TeraError(#[from] tera::Error),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Unknown template: {0}")]
+    UnknownTemplate(String),
+    #[error("Missing template variable: {0}")]
+    MissingVariable(String),
+    /// The template uses syntax beyond the static subset and Tera is not enabled
+    #[error("Template {0} requires the `templates` feature")]
+    Unsupported(String),
+    /// A user template would replace a built-in one
+    #[error("Template {0} conflicts with a built-in template")]
+    Conflict(String),
 }
 
 pub struct TemplateEngine {
+    simple: HashMap<String, SimpleTemplate>,
+    /// Names registered by `register_all_templates`, which user templates can't replace
+    builtins: HashSet<String>,
+    #[cfg(feature = "templates")]
     tera: Tera,
 }
 
 impl TemplateEngine {
     pub fn new() -> Result<Self, TemplateError> {
-        let mut tera = Tera::default();
+        Self::with_fake_data(FakeData::unseeded())
+    }
+    
+    /// Create an engine whose Tera templates draw `uuid()`, `now()`, `rand_int(a, b)`,
+    /// and `faker_name()` from `fake`
+    #[cfg_attr(not(feature = "templates"), allow(unused_variables))]
+    pub fn with_fake_data(fake: FakeData) -> Result<Self, TemplateError> {
+        let mut engine = Self {
+            simple: HashMap::new(),
+            builtins: HashSet::new(),
+            #[cfg(feature = "templates")]
+            tera: Tera::default(),
+        };
+        #[cfg(feature = "templates")]
+        fake.register(&mut engine.tera);
         
         // Register all command templates
-        Self::register_all_templates(&mut tera)?;
+        engine.register_all_templates()?;
+        engine.builtins = engine.simple.keys().cloned().collect();
+        #[cfg(feature = "templates")]
+        engine.builtins.extend(engine.tera.get_template_names().map(str::to_string));
         
-        Ok(Self { tera })
+        Ok(engine)
+    }
+    
+    /// Register a template (e.g. contributed by a plugin). Static templates use the
+    /// built-in renderer; others are compiled with Tera when the `templates` feature is on.
+    pub fn add_template(&mut self, name: &str, source: &str) -> Result<(), TemplateError> {
+        if let Some(template) = SimpleTemplate::parse(source) {
+            self.simple.insert(name.to_string(), template);
+            return Ok(());
+        }
+        
+        #[cfg(feature = "templates")]
+        {
+            self.simple.remove(name);
+            self.tera.add_raw_template(name, source)?;
+            Ok(())
+        }
+        
+        #[cfg(not(feature = "templates"))]
+        Err(TemplateError::Unsupported(name.to_string()))
+    }
+    
+    /// Register a user template, e.g. a new mapping rolled out to a running service. Unlike
+    /// `add_template` it refuses built-in names; an earlier user template is replaced.
+    pub fn add_user_template(&mut self, name: &str, source: &str) -> Result<(), TemplateError> {
+        if self.is_builtin(name) {
+            return Err(TemplateError::Conflict(name.to_string()));
+        }
+        self.add_template(name, source)
+    }
+    
+    /// Whether `name` is one of the crate's own templates
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.builtins.contains(name)
+    }
+    
+    /// Whether a template named `name` is registered
+    pub fn has_template(&self, name: &str) -> bool {
+        if self.simple.contains_key(name) {
+            return true;
+        }
+        #[cfg(feature = "templates")]
+        if self.tera.get_template_names().any(|template| template == name) {
+            return true;
+        }
+        false
     }
     
     /// Register all command templates as raw strings
-    fn register_all_templates(tera: &mut Tera) -> Result<(), TemplateError> {
+    fn register_all_templates(&mut self) -> Result<(), TemplateError> {
         // Common templates
-        tera.add_raw_template("del", "DEL {{ key }}")?;
+        self.add_template("del", "DEL {{ key }}")?;
+        
+        // Key metadata pseudo-columns
+        self.add_template("object_idletime", "OBJECT IDLETIME {{ key }}")?;
+        self.add_template("object_freq", "OBJECT FREQ {{ key }}")?;
+        
+        // Admin diagnostics
+        self.add_template("admin_info", "INFO")?;
+        self.add_template("admin_client_list", "CLIENT LIST")?;
+        self.add_template("admin_slowlog_get", "SLOWLOG GET{% if count %} {{ count }}{% endif %}")?;
+        
+        // Keyspace browsing
+        self.add_template("keys_count", "DBSIZE")?;
+        self.add_template("keys_scan", "SCAN 0 MATCH {{ pattern }}{% if count %} COUNT {{ count }}{% endif %}")?;
         
         // String operations
-        tera.add_raw_template("string_get", "GET {{ key }}")?;
-        tera.add_raw_template("string_mget", "MGET {{ keys }}")?;
-        tera.add_raw_template("string_set", "SET {{ key }} {{ value }}")?;
-        tera.add_raw_template("string_update", "SET {{ key }} {{ value }}")?;
+        self.add_template("string_get", "GET {{ key }}")?;
+        self.add_template("string_mget", "MGET {{ keys }}")?;
+        self.add_template("string_set", "SET {{ key }} {{ value }}")?;
+        self.add_template("string_update", "SET {{ key }} {{ value }}")?;
+        self.add_template("json_get", "JSON.GET {{ key }} {{ paths }}")?;
         
         // Hash operations
-        tera.add_raw_template("hash_getall", "HGETALL {{ key }}")?;
-        tera.add_raw_template("hash_get", "HGET {{ key }} {{ field }}")?;
-        tera.add_raw_template("hash_hmget", "HMGET {{ key }} {{ fields }}")?;
-        tera.add_raw_template("hash_set", "HSET {{ key }} {{ field_values }}")?;
-        tera.add_raw_template("hash_update", "HSET {{ key }} {{ field_values }}")?;
-        tera.add_raw_template("hash_delete", "DEL {{ key }}")?;
-        tera.add_raw_template("hash_delete_field", "HDEL {{ key }} {{ field }}")?;
-        tera.add_raw_template("hash_count", "HLEN {{ key }}")?;
+        self.add_template("hash_getall", "HGETALL {{ key }}")?;
+        self.add_template("hash_scan", "HSCAN {{ key }} 0 COUNT {{ count }}")?;
+        self.add_template("hash_get", "HGET {{ key }} {{ field }}")?;
+        self.add_template("hash_hmget", "HMGET {{ key }} {{ fields }}")?;
+        self.add_template("hash_set", "HSET {{ key }} {{ field_values }}")?;
+        self.add_template("hash_update", "HSET {{ key }} {{ field_values }}")?;
+        self.add_template("hash_delete", "DEL {{ key }}")?;
+        self.add_template("hash_delete_field", "HDEL {{ key }} {{ fields }}")?;
+        self.add_template("hash_count", "HLEN {{ key }}")?;
         
         // List operations
-        tera.add_raw_template("list_getall", "LRANGE {{ key }} 0 -1")?;
-        tera.add_raw_template("list_get_index", "LINDEX {{ key }} {{ index }}")?;
-        tera.add_raw_template("list_get_index_range", "LRANGE {{ key }} 0 {{ stop }}")?;
-        tera.add_raw_template("list_get_range", "LRANGE {{ key }} {{ start }} {{ stop }}")?;
-        tera.add_raw_template("list_push", "RPUSH {{ key }} {{ value }}")?;
-        tera.add_raw_template("list_update", "LSET {{ key }} {{ index }} {{ value }}")?;
-        tera.add_raw_template("list_delete", "DEL {{ key }}")?;
-        tera.add_raw_template("list_delete_value", "LREM {{ key }} 0 {{ value }}")?;
-        tera.add_raw_template("list_count", "LLEN {{ key }}")?;
+        self.add_template("list_getall", "LRANGE {{ key }} 0 -1")?;
+        self.add_template("list_get_index", "LINDEX {{ key }} {{ index }}")?;
+        self.add_template("list_get_index_range", "LRANGE {{ key }} 0 {{ stop }}")?;
+        self.add_template("list_get_range", "LRANGE {{ key }} {{ start }} {{ stop }}")?;
+        self.add_template("list_push", "RPUSH {{ key }} {{ value }}")?;
+        self.add_template("list_update", "LSET {{ key }} {{ index }} {{ value }}")?;
+        self.add_template("list_delete", "DEL {{ key }}")?;
+        self.add_template("list_delete_value", "LREM {{ key }} 0 {{ value }}")?;
+        self.add_template("list_trim", "LTRIM {{ key }} {{ start }} {{ stop }}")?;
+        self.add_template("list_count", "LLEN {{ key }}")?;
         
         // Set operations
-        tera.add_raw_template("set_getall", "SMEMBERS {{ key }}")?;
-        tera.add_raw_template("set_ismember", "SISMEMBER {{ key }} {{ member }}")?;
-        tera.add_raw_template("set_add", "SADD {{ key }} {{ members }}")?;
-        tera.add_raw_template("set_delete", "DEL {{ key }}")?;
-        tera.add_raw_template("set_delete_member", "SREM {{ key }} {{ member }}")?;
-        tera.add_raw_template("set_delete_multi_member", "SREM {{ key }} {{ members }}")?;
-        tera.add_raw_template("set_count", "SCARD {{ key }}")?;
+        self.add_template("set_getall", "SMEMBERS {{ key }}")?;
+        self.add_template("set_scan", "SSCAN {{ key }} 0 COUNT {{ count }}")?;
+        self.add_template("set_scan_match", "SSCAN {{ key }} 0 MATCH {{ pattern }}{% if count %} COUNT {{ count }}{% endif %}")?;
+        self.add_template("set_ismember", "SISMEMBER {{ key }} {{ member }}")?;
+        self.add_template("set_add", "SADD {{ key }} {{ members }}")?;
+        self.add_template("set_store", "{{ command }} {{ destination }} {{ sources }}")?;
+        self.add_template("set_delete", "DEL {{ key }}")?;
+        self.add_template("set_delete_member", "SREM {{ key }} {{ member }}")?;
+        self.add_template("set_delete_multi_member", "SREM {{ key }} {{ members }}")?;
+        self.add_template("set_count", "SCARD {{ key }}")?;
         
         // Sorted Set operations
-        tera.add_raw_template("zset_getall", "ZRANGEBYSCORE {{ key }} -inf +inf")?;
-        tera.add_raw_template("zset_get_score_range", "ZRANGEBYSCORE {{ key }} {{ min }} {{ max }}")?;
-        tera.add_raw_template("zset_get_score_between", "ZRANGEBYSCORE {{ key }} {{ min }} {{ max }}")?;
-        tera.add_raw_template("zset_get_reversed", "ZREVRANGEBYSCORE {{ key }} {{ max }} {{ min }}")?;
-        tera.add_raw_template("zset_add", "ZADD {{ key }} {{ score }} {{ member }}")?;
-        tera.add_raw_template("zset_update", "ZADD {{ key }} {{ score }} {{ member }}")?;
-        tera.add_raw_template("zset_delete", "DEL {{ key }}")?;
-        tera.add_raw_template("zset_delete_member", "ZREM {{ key }} {{ member }}")?;
-        tera.add_raw_template("zset_count", "ZCARD {{ key }}")?;
-        tera.add_raw_template("zset_count_score_range", "ZCOUNT {{ key }} {{ min }} {{ max }}")?;
+        self.add_template("zset_getall", "ZRANGEBYSCORE {{ key }} -inf +inf")?;
+        self.add_template("zset_scan_match", "ZSCAN {{ key }} 0 MATCH {{ pattern }}{% if count %} COUNT {{ count }}{% endif %}")?;
+        self.add_template("zset_get_limit", "ZRANGEBYSCORE {{ key }} -inf +inf LIMIT 0 {{ count }}")?;
+        self.add_template("zset_get_score_range", "ZRANGEBYSCORE {{ key }} {{ min }} {{ max }}")?;
+        self.add_template("zset_get_score_between", "ZRANGEBYSCORE {{ key }} {{ min }} {{ max }}")?;
+        self.add_template("zset_get_reversed", "ZREVRANGEBYSCORE {{ key }} {{ max }} {{ min }}")?;
+        self.add_template("zset_add", "ZADD {{ key }} {{ score_members }}")?;
+        self.add_template(
+            "zset_range_store",
+            "ZRANGESTORE {{ destination }} {{ source }} {{ start }} {{ stop }}{% if byscore %} BYSCORE{% endif %}{% if rev %} REV{% endif %}{% if count %} LIMIT 0 {{ count }}{% endif %}",
+        )?;
+        self.add_template("zset_update", "ZADD {{ key }} {{ score }} {{ member }}")?;
+        self.add_template("zset_delete", "DEL {{ key }}")?;
+        self.add_template("zset_delete_member", "ZREM {{ key }} {{ members }}")?;
+        self.add_template("zset_delete_score_range", "ZREMRANGEBYSCORE {{ key }} {{ min }} {{ max }}")?;
+        self.add_template("zset_pop_min", "ZPOPMIN {{ key }}{% if count %} {{ count }}{% endif %}")?;
+        self.add_template("zset_pop_max", "ZPOPMAX {{ key }}{% if count %} {{ count }}{% endif %}")?;
+        self.add_template("zset_count", "ZCARD {{ key }}")?;
+        self.add_template("zset_count_score_range", "ZCOUNT {{ key }} {{ min }} {{ max }}")?;
+        
+        // Keyspace notifications (one command per line)
+        self.add_template(
+            "keyspace_subscribe",
+            "CONFIG SET notify-keyspace-events {{ events }}\nPSUBSCRIBE __keyspace@{{ db }}__:{{ pattern }}",
+        )?;
+        
+        // Session statements
+        self.add_template("select_db", "SELECT {{ db }}")?;
         
         Ok(())
     }
     
     /// Render a template with the given context
-    pub fn render(&self, template_name: &str, context: &TemplateContext) -> Result<String, tera::Error> {
+    /// Render a template to command text
+    pub fn render(&self, template_name: &str, context: &TemplateContext) -> Result<String, TemplateError> {
+        if let Some(template) = self.simple.get(template_name) {
+            return template.render(context);
+        }
+        self.render_tera(template_name, context)
+    }
+    
+    /// Render a template to structured commands (one per line), keeping substituted
+    /// values as single arguments
+    pub fn render_commands(&self, template_name: &str, context: &TemplateContext) -> Result<Vec<RedisCommand>, TemplateError> {
+        if let Some(template) = self.simple.get(template_name) {
+            return template.render_commands(context);
+        }
+        // Tera output is text; split it back into commands
+        let text = self.render_tera(template_name, context)?;
+        Ok(RedisCommand::parse_all(&text))
+    }
+    
+    #[cfg(feature = "templates")]
+    fn render_tera(&self, template_name: &str, context: &TemplateContext) -> Result<String, TemplateError> {
+        if !self.tera.get_template_names().any(|name| name == template_name) {
+            return Err(TemplateError::UnknownTemplate(template_name.to_string()));
+        }
         let mut tera_context = Context::new();
         for (key, value) in context {
-            tera_context.insert(key, value);
+            tera_context.insert(key, &value.replace(crate::context::LIST_SEPARATOR, " "));
         }
-        self.tera.render(template_name, &tera_context)
+        Ok(self.tera.render(template_name, &tera_context)?)
     }
-}
\ No newline at end of file
+    
+    #[cfg(not(feature = "templates"))]
+    fn render_tera(&self, template_name: &str, _context: &TemplateContext) -> Result<String, TemplateError> {
+        Err(TemplateError::UnknownTemplate(template_name.to_string()))
+    }
+}
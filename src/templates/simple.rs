@@ -0,0 +1,187 @@
+// templates/simple.rs - Built-in renderer for the static command templates
+// Supports `{{ name }}` substitution and `{% if name %}...{% endif %}`; no filters or loops
+
+use crate::commands::RedisCommand;
+use crate::context::{TemplateContext, LIST_SEPARATOR};
+use super::TemplateError;
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Text(String),
+    Var(String),
+    If(String, Vec<Segment>),
+}
+
+/// A parsed template in the static subset of Tera syntax
+#[derive(Debug, Clone)]
+pub struct SimpleTemplate {
+    segments: Vec<Segment>,
+}
+
+impl SimpleTemplate {
+    /// Parse a template; returns None if it uses syntax outside the static subset
+    pub fn parse(source: &str) -> Option<Self> {
+        let (segments, rest) = parse_segments(source, false)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(Self { segments })
+    }
+
+    /// Render to a command string (list values are space-separated)
+    pub fn render(&self, context: &TemplateContext) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        render_segments(&self.segments, context, &mut |piece| match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Value(value) => out.push_str(&value.replace(LIST_SEPARATOR, " ")),
+        })?;
+        Ok(out)
+    }
+
+    /// Render to structured commands, one per line. Whitespace in the template separates
+    /// arguments; substituted values stay whole, except list values which become one argument each.
+    pub fn render_commands(&self, context: &TemplateContext) -> Result<Vec<RedisCommand>, TemplateError> {
+        let mut builder = CommandBuilder::default();
+        render_segments(&self.segments, context, &mut |piece| builder.push(piece))?;
+        Ok(builder.finish())
+    }
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Value(&'a str),
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Parse segments up to the end of input, or up to `{% endif %}` when `in_if`
+fn parse_segments(mut source: &str, in_if: bool) -> Option<(Vec<Segment>, &str)> {
+    let mut segments = Vec::new();
+    loop {
+        let next = match (source.find("{{"), source.find("{%"), source.find("{#")) {
+            (_, _, Some(_)) => return None, // comments are not supported
+            (Some(v), Some(t), _) => v.min(t),
+            (Some(v), None, _) => v,
+            (None, Some(t), _) => t,
+            (None, None, _) => {
+                if in_if {
+                    return None; // missing endif
+                }
+                if !source.is_empty() {
+                    segments.push(Segment::Text(source.to_string()));
+                }
+                return Some((segments, ""));
+            }
+        };
+        if next > 0 {
+            segments.push(Segment::Text(source[..next].to_string()));
+        }
+        source = &source[next..];
+
+        if let Some(rest) = source.strip_prefix("{{") {
+            let end = rest.find("}}")?;
+            let name = rest[..end].trim();
+            if !is_identifier(name) {
+                return None;
+            }
+            segments.push(Segment::Var(name.to_string()));
+            source = &rest[end + 2..];
+        } else {
+            let rest = &source[2..];
+            let end = rest.find("%}")?;
+            let tag: Vec<&str> = rest[..end].split_whitespace().collect();
+            source = &rest[end + 2..];
+            match tag.as_slice() {
+                ["if", name] if is_identifier(name) => {
+                    let (body, rest) = parse_segments(source, true)?;
+                    segments.push(Segment::If(name.to_string(), body));
+                    source = rest;
+                }
+                ["endif"] if in_if => return Some((segments, source)),
+                _ => return None,
+            }
+        }
+    }
+}
+
+fn render_segments<'a>(
+    segments: &'a [Segment],
+    context: &'a TemplateContext,
+    emit: &mut dyn FnMut(Piece<'a>),
+) -> Result<(), TemplateError> {
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => emit(Piece::Text(text)),
+            Segment::Var(name) => {
+                let value = context.get(name)
+                    .ok_or_else(|| TemplateError::MissingVariable(name.clone()))?;
+                emit(Piece::Value(value));
+            }
+            // Like Tera: missing and empty values are falsy
+            Segment::If(name, body) => {
+                if context.get(name).is_some_and(|value| !value.is_empty()) {
+                    render_segments(body, context, emit)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Accumulates rendered pieces into commands and arguments
+#[derive(Default)]
+struct CommandBuilder {
+    commands: Vec<RedisCommand>,
+    words: Vec<String>,
+    current: Option<String>,
+}
+
+impl CommandBuilder {
+    fn push(&mut self, piece: Piece<'_>) {
+        match piece {
+            Piece::Text(text) => {
+                for c in text.chars() {
+                    match c {
+                        '\n' => {
+                            self.end_word();
+                            self.end_command();
+                        }
+                        c if c.is_whitespace() => self.end_word(),
+                        c => self.current.get_or_insert_with(String::new).push(c),
+                    }
+                }
+            }
+            Piece::Value(value) => {
+                for (i, item) in value.split(LIST_SEPARATOR).enumerate() {
+                    if i > 0 {
+                        self.end_word();
+                    }
+                    self.current.get_or_insert_with(String::new).push_str(item);
+                }
+            }
+        }
+    }
+
+    fn end_word(&mut self) {
+        if let Some(word) = self.current.take() {
+            self.words.push(word);
+        }
+    }
+
+    fn end_command(&mut self) {
+        let mut words = std::mem::take(&mut self.words).into_iter();
+        if let Some(name) = words.next() {
+            self.commands.push(RedisCommand::new(name, words.collect()));
+        }
+    }
+
+    fn finish(mut self) -> Vec<RedisCommand> {
+        self.end_word();
+        self.end_command();
+        self.commands
+    }
+}
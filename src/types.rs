@@ -0,0 +1,104 @@
+// types.rs - Redis data types and the table-suffix convention that selects them
+// `users__hash` is a hash, `feed__list` a list, and an unsuffixed table holds strings; every
+// check of a table's type goes through `redis_type_of`, so a new type is added here alone
+
+/// Determine the Redis data type from a table name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedisDataType {
+    String,
+    Hash,
+    List,
+    Set,
+    SortedSet,
+}
+
+impl RedisDataType {
+    /// Every data type, in listing order
+    pub const ALL: [RedisDataType; 5] = [
+        RedisDataType::String,
+        RedisDataType::Hash,
+        RedisDataType::List,
+        RedisDataType::Set,
+        RedisDataType::SortedSet,
+    ];
+
+    /// Name used by the CLI and pattern listings, matching the table suffix (`zset`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            RedisDataType::String => "string",
+            RedisDataType::Hash => "hash",
+            RedisDataType::List => "list",
+            RedisDataType::Set => "set",
+            RedisDataType::SortedSet => "zset",
+        }
+    }
+
+    /// Look a data type up by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|data_type| data_type.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The table suffix selecting this type by default (`__zset`); strings have none
+    pub fn suffix(&self) -> Option<&'static str> {
+        match self {
+            RedisDataType::String => None,
+            RedisDataType::Hash => Some("__hash"),
+            RedisDataType::List => Some("__list"),
+            RedisDataType::Set => Some("__set"),
+            RedisDataType::SortedSet => Some("__zset"),
+        }
+    }
+
+    /// Columns a collection type stores besides `key`, in the order its write command takes them
+    pub fn columns(&self) -> Option<&'static [&'static str]> {
+        match self {
+            RedisDataType::List => Some(&["value"]),
+            RedisDataType::Set => Some(&["member"]),
+            RedisDataType::SortedSet => Some(&["member", "score"]),
+            RedisDataType::String | RedisDataType::Hash => None,
+        }
+    }
+}
+
+/// Which table suffixes select which data types: each type's own `suffix`, plus any added
+/// with `with_suffix` (checked first, so they can also override a default)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeMapping {
+    suffixes: Vec<(String, RedisDataType)>,
+}
+
+/// The default mapping, for callers without one of their own
+static STANDARD: TypeMapping = TypeMapping::new();
+
+impl TypeMapping {
+    /// The default suffixes only
+    pub const fn new() -> Self {
+        Self { suffixes: Vec::new() }
+    }
+
+    /// The default mapping, shared
+    pub fn standard() -> &'static TypeMapping {
+        &STANDARD
+    }
+
+    /// Map tables ending in `suffix` (e.g. `__h`) to `data_type`
+    pub fn with_suffix(mut self, suffix: &str, data_type: RedisDataType) -> Self {
+        self.suffixes.retain(|(existing, _)| existing != suffix);
+        self.suffixes.push((suffix.to_string(), data_type));
+        self
+    }
+
+    /// The data type `table`'s suffix selects; String when no suffix matches
+    pub fn data_type_of(&self, table: &str) -> RedisDataType {
+        let custom = self.suffixes.iter().map(|(suffix, data_type)| (suffix.as_str(), *data_type));
+        let defaults = RedisDataType::ALL.into_iter().filter_map(|data_type| Some((data_type.suffix()?, data_type)));
+        custom.chain(defaults)
+            .find(|(suffix, _)| table.ends_with(suffix))
+            .map_or(RedisDataType::String, |(_, data_type)| data_type)
+    }
+}
+
+/// The Redis data type a table's name selects under `mapping`
+pub fn redis_type_of(table_name: &str, mapping: &TypeMapping) -> RedisDataType {
+    mapping.data_type_of(table_name)
+}
@@ -0,0 +1,111 @@
+// variables.rs - Session variables for the REPL and batch input
+// `SET @name = 'value'` defines a variable that later statements reference as `@name`, inside
+// string literals or as a value; references are expanded before the statement is parsed
+
+use std::collections::BTreeMap;
+
+use crate::error::SqlRedisError;
+
+/// A variable's value: a string, or a number (or other bare token) from an unquoted assignment
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    Bare(String),
+}
+
+/// Variables set so far in a session
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    values: BTreeMap<String, Value>,
+}
+
+impl Variables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value of `name` (without the `@`)
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|value| match value {
+            Value::Text(text) | Value::Bare(text) => text.as_str(),
+        })
+    }
+
+    /// Define `name` (without the `@`) as a string value
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_string(), Value::Text(value.to_string()));
+    }
+
+    /// Names of the defined variables, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Record `statement` if it is a `SET @name = value` assignment, returning whether it was.
+    /// The value is a quoted string (`''` escapes a quote) or a bare token such as a number,
+    /// and may itself reference variables set earlier.
+    pub fn assign(&mut self, statement: &str) -> Result<bool, SqlRedisError> {
+        let statement = statement.trim().trim_end_matches(';').trim_end();
+        let rest = match statement.get(..3) {
+            Some(keyword) if keyword.eq_ignore_ascii_case("SET") => statement[3..].trim_start(),
+            _ => return Ok(false),
+        };
+        let Some(rest) = rest.strip_prefix('@') else {
+            return Ok(false);
+        };
+        let invalid = || SqlRedisError::SqlParseError(format!("Expected SET @name = value, got: {}", statement));
+        let (name, value) = rest.split_once('=').ok_or_else(invalid)?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return Err(invalid());
+        }
+        let value = self.expand(value.trim());
+        let value = if let Some(quoted) = value.strip_prefix('\'') {
+            let text = quoted.strip_suffix('\'').filter(|text| !text.replace("''", "").contains('\'')).ok_or_else(invalid)?;
+            Value::Text(text.replace("''", "'"))
+        } else if !value.is_empty() && !value.contains(char::is_whitespace) {
+            Value::Bare(value)
+        } else {
+            return Err(invalid());
+        };
+        self.values.insert(name.to_string(), value);
+        Ok(true)
+    }
+
+    /// Replace references to defined variables: inside a string literal with the raw value
+    /// (`'@prefix:1001'` -> `'tenant42:1001'`), elsewhere with the value as a literal
+    /// (`key = @k` -> `key = 'user:1'`). Undefined names, such as the domain in an e-mail
+    /// address, are left alone.
+    pub fn expand(&self, sql: &str) -> String {
+        if self.values.is_empty() {
+            return sql.to_string();
+        }
+        let mut out = String::with_capacity(sql.len());
+        let mut in_literal = false;
+        let mut rest = sql;
+        while let Some(c) = rest.chars().next() {
+            if c == '\'' {
+                in_literal = !in_literal;
+            }
+            if c == '@' {
+                let name_len = rest[1..].find(|c: char| !is_name_char(c)).unwrap_or(rest.len() - 1);
+                if let Some(value) = self.values.get(&rest[1..1 + name_len]) {
+                    match (value, in_literal) {
+                        (Value::Text(text) | Value::Bare(text), true) => out.push_str(&text.replace('\'', "''")),
+                        (Value::Text(text), false) => out.push_str(&format!("'{}'", text.replace('\'', "''"))),
+                        (Value::Bare(text), false) => out.push_str(text),
+                    }
+                    rest = &rest[1 + name_len..];
+                    continue;
+                }
+            }
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
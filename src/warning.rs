@@ -0,0 +1,62 @@
+// warning.rs - Non-fatal diagnostics reported alongside a translation
+// Surfaces semantics the generated command changes or costs it incurs
+
+use sqlparser::tokenizer::Span;
+use std::fmt;
+
+/// The category of a translation warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// A SQL clause the command does not honor (rejected instead in strict mode)
+    DroppedClause,
+    /// Part of the statement must be evaluated by the client
+    ClientSideEval,
+    /// The command reads a whole key or keyspace, O(N) in its size
+    FullScan,
+    /// A SQL value is implicitly converted (e.g. a quoted score to a double)
+    TypeCoercion,
+    /// An oversized command was split into several, which are not applied atomically
+    SplitCommand,
+}
+
+impl WarningKind {
+    /// Kebab-case name used in CLI output
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningKind::DroppedClause => "dropped-clause",
+            WarningKind::ClientSideEval => "client-side-eval",
+            WarningKind::FullScan => "full-scan",
+            WarningKind::TypeCoercion => "type-coercion",
+            WarningKind::SplitCommand => "split-command",
+        }
+    }
+}
+
+/// A warning about a translation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    /// Location of the offending SQL, if the warning concerns a specific clause
+    pub span: Option<Span>,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: &str) -> Self {
+        Self { kind, message: message.to_string(), span: None }
+    }
+
+    /// Point the warning at a source span (empty spans are ignored)
+    pub fn with_span(mut self, span: Span) -> Self {
+        if span != Span::empty() {
+            self.span = Some(span);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning[{}]: {}", self.kind.name(), self.message)
+    }
+}
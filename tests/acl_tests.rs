@@ -0,0 +1,89 @@
+// tests/acl_tests.rs
+use sql_redis::acl::is_read_only;
+use sql_redis::commands::RedisCommand;
+use sql_redis::reply::Reply;
+use sql_redis::{Acl, SqlToRedisTransformer};
+
+#[test]
+fn test_acl_validation() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let acl = Acl::parse("on >secret ~app:* %R~admin:* +@all -@scripting -@dangerous").unwrap();
+
+    // Test permitted commands on matching keys pass
+    assert!(transformer.validate_against_acl("SELECT * FROM users WHERE key = 'app:1'", &acl).unwrap().is_empty());
+    assert!(transformer.validate_against_acl("SELECT * FROM users WHERE key = 'admin:1'", &acl).unwrap().is_empty());
+
+    // Test writes to read-only keys and keys outside every pattern are flagged
+    let violations = transformer.validate_against_acl("INSERT INTO users (key, value) VALUES ('admin:1', 'x')", &acl).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].command.command, "SET");
+    assert!(violations[0].reason.contains("'admin:1'"));
+    assert!(!transformer.validate_against_acl("SELECT * FROM users WHERE key = 'other:1'", &acl).unwrap().is_empty());
+
+    // Test denied categories: EVAL is @scripting, INFO is @dangerous
+    let violations = transformer.validate_against_acl("SELECT AVG(score) FROM game__zset WHERE key = 'app:g'", &acl).unwrap();
+    assert_eq!(violations[0].to_string(), "EVAL is not permitted: the user can't run 'eval'");
+    assert!(!acl.check(&RedisCommand::new("INFO", Vec::<String>::new())).unwrap().reason.is_empty());
+
+    // Test later rules override earlier ones, including subcommands
+    let acl = Acl::parse("on allkeys -@all +get +config -config|set").unwrap();
+    assert!(acl.check(&RedisCommand::new("GET", vec!["k"])).is_none());
+    assert!(acl.check(&RedisCommand::new("SET", vec!["k", "v"])).is_some());
+    assert!(acl.check(&RedisCommand::new("CONFIG", vec!["GET", "maxmemory"])).is_none());
+    assert!(acl.check(&RedisCommand::new("CONFIG", vec!["SET", "notify-keyspace-events", "Kh"])).is_some());
+
+    // Test disabled users and unknown rules
+    let acl = Acl::parse("off allkeys allcommands").unwrap();
+    assert!(acl.check(&RedisCommand::new("GET", vec!["k"])).is_some());
+    assert!(Acl::parse("alice").is_err());
+}
+
+#[test]
+fn test_acl_from_getuser() {
+    let bulk = |s: &str| Reply::bulk(s);
+    // Redis 7 layout: commands, keys, and channels as rule strings
+    let reply = Reply::Array(vec![
+        bulk("flags"), Reply::Array(vec![bulk("on")]),
+        bulk("passwords"), Reply::Array(vec![]),
+        bulk("commands"), bulk("+@read -keys"),
+        bulk("keys"), bulk("~cache:*"),
+        bulk("channels"), bulk("&__keyspace@0__:cache:*"),
+    ]);
+    let acl = Acl::from_getuser(&reply).unwrap();
+    assert!(acl.check(&RedisCommand::new("HGETALL", vec!["cache:1"])).is_none());
+    assert!(acl.check(&RedisCommand::new("HSET", vec!["cache:1", "f", "v"])).is_some());
+    assert!(acl.check(&RedisCommand::new("GET", vec!["user:1"])).is_some());
+    assert!(acl.check(&RedisCommand::new("KEYS", vec!["*"])).is_some());
+
+    // Test unknown users
+    assert!(Acl::from_getuser(&Reply::Nil).is_err());
+}
+
+#[test]
+fn test_read_only_classification() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let read_only = |sql: &str| transformer.transform_to_commands(sql).unwrap().iter().all(is_read_only);
+
+    assert!(read_only("SELECT * FROM users__hash WHERE key = 'u:1'"));
+    assert!(read_only("SELECT AVG(score) FROM lb__zset WHERE key = 'lb'"));
+    assert!(!read_only("INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann')"));
+    assert!(!read_only("DELETE FROM users__hash WHERE key = 'u:1'"));
+
+    // Test script command names are read from literals or resolved from ARGV
+    let eval = |script: &str, args: &[&str]| {
+        let mut all = vec![script, "1", "k"];
+        all.extend(args);
+        is_read_only(&RedisCommand::new("EVAL", all))
+    };
+    assert!(eval("return redis.call('HGET', KEYS[1], ARGV[1])", &["f"]));
+    assert!(eval("return redis.call(ARGV[1], KEYS[1], 0, -1)", &["ZRANGE"]));
+    assert!(!eval("return redis.call(ARGV[1], KEYS[1], 0, -1)", &["ZREMRANGEBYRANK"]));
+    assert!(!eval("redis.call('GET', KEYS[1]) return redis.pcall(\"DEL\", KEYS[1])", &[]));
+    assert!(!eval("local cmd = 'GET' return redis.call(cmd, KEYS[1])", &[]));
+
+    // Test scripts that can't be inspected, and unknown commands, count as writes
+    assert!(!is_read_only(&RedisCommand::new("EVALSHA", vec!["abc", "0"])));
+    assert!(!is_read_only(&RedisCommand::new("FLUSHALL", Vec::<String>::new())));
+    assert!(is_read_only(&RedisCommand::new("CONFIG", vec!["GET", "maxmemory"])));
+    assert!(!is_read_only(&RedisCommand::new("CONFIG", vec!["SET", "maxmemory", "1gb"])));
+}
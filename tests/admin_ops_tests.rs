@@ -0,0 +1,75 @@
+// tests/admin_ops_tests.rs
+use sql_redis::reply::Reply;
+use sql_redis::{Profile, SqlToRedisTransformer, TransformOptions};
+
+fn admin_transformer() -> SqlToRedisTransformer {
+    SqlToRedisTransformer::with_options(TransformOptions::default().with_profile(Profile::Admin)).unwrap()
+}
+
+#[test]
+fn test_admin_operations() {
+    let transformer = admin_transformer();
+    
+    // Test INFO
+    let result = transformer.transform("SELECT * FROM __server_info").unwrap();
+    assert_eq!(result, "INFO");
+    
+    // Test CLIENT LIST
+    let result = transformer.transform("SELECT * FROM __clients").unwrap();
+    assert_eq!(result, "CLIENT LIST");
+    
+    // Test SLOWLOG GET with and without LIMIT
+    let result = transformer.transform("SELECT * FROM __slowlog LIMIT 10").unwrap();
+    assert_eq!(result, "SLOWLOG GET 10");
+    
+    let result = transformer.transform("SELECT * FROM __slowlog").unwrap();
+    assert_eq!(result, "SLOWLOG GET");
+}
+
+#[test]
+fn test_admin_rules_require_profile() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    assert!(transformer.transform("SELECT * FROM __server_info").is_err());
+    assert!(transformer.transform("SELECT * FROM __clients").is_err());
+    assert!(transformer.transform("SELECT * FROM __slowlog LIMIT 10").is_err());
+}
+
+#[test]
+fn test_admin_result_decoders() {
+    let transformer = admin_transformer();
+    
+    // INFO decodes into (section, field, value) rows
+    let info = Reply::bulk("# Server\r\nredis_version:7.2.4\r\n\r\n# Clients\r\nconnected_clients:3\r\n");
+    let rows = transformer.decode_reply("SELECT * FROM __server_info", &info).unwrap().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1], vec![
+        ("section".to_string(), "clients".to_string()),
+        ("field".to_string(), "connected_clients".to_string()),
+        ("value".to_string(), "3".to_string()),
+    ]);
+    
+    // CLIENT LIST decodes one row per client
+    let clients = Reply::bulk("id=3 addr=127.0.0.1:52555 name= db=0\nid=4 addr=127.0.0.1:52556 name=worker db=1\n");
+    let rows = transformer.decode_reply("SELECT * FROM __clients", &clients).unwrap().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1][2], ("name".to_string(), "worker".to_string()));
+    
+    // SLOWLOG GET decodes nested entries
+    let slowlog = Reply::Array(vec![Reply::Array(vec![
+        Reply::Integer(14),
+        Reply::Integer(1309448221),
+        Reply::Integer(15),
+        Reply::Array(vec![Reply::bulk("KEYS"), Reply::bulk("*")]),
+        Reply::bulk("127.0.0.1:58217"),
+        Reply::bulk("worker"),
+    ])]);
+    let rows = transformer.decode_reply("SELECT * FROM __slowlog LIMIT 1", &slowlog).unwrap().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0][0], ("id".to_string(), "14".to_string()));
+    assert_eq!(rows[0][3], ("command".to_string(), "KEYS *".to_string()));
+    
+    // Plain data rules have no decoder
+    let result = transformer.decode_reply("SELECT * FROM users WHERE key = 'user:1001'", &Reply::Nil).unwrap();
+    assert!(result.is_none());
+}
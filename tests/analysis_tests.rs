@@ -0,0 +1,125 @@
+// tests/analysis_tests.rs
+use sqlparser::ast::Statement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sql_redis::analysis::{check_equivalence, check_idempotency, CapacityReport};
+use sql_redis::commands::RedisCommand;
+use sql_redis::{ErrorCode, SqlToRedisTransformer, TransformOptions};
+
+fn parse(sql: &str) -> Statement {
+    Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0)
+}
+
+#[test]
+fn test_idempotency_analysis() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test appends are flagged with a suggestion
+    let issues = transformer.check_idempotency("INSERT INTO logs__list (key, value) VALUES ('app:logs', 'msg')").unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].command.command, "RPUSH");
+    assert!(issues[0].suggestion.contains("LPOS"));
+    
+    // Test pops and head trims are flagged
+    let issues = transformer.check_idempotency("DELETE FROM jobs__zset WHERE key = 'q' ORDER BY score LIMIT 1 RETURNING member").unwrap();
+    assert_eq!(issues[0].command.command, "ZPOPMIN");
+    let issues = transformer.check_idempotency("DELETE FROM logs__list WHERE key = 'app:logs' AND index < 10").unwrap();
+    assert_eq!(issues[0].command.command, "LTRIM");
+    
+    // Test converging writes pass
+    for sql in [
+        "INSERT INTO users (key, value) VALUES ('user:1', 'Ann')",
+        "INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann')",
+        "INSERT INTO tags__set (key, member) VALUES ('post:1', 'x')",
+        "DELETE FROM logs__list WHERE key = 'app:logs' AND index > 999",
+        "DELETE FROM cache WHERE key = 'temp'",
+    ] {
+        assert!(transformer.check_idempotency(sql).unwrap().is_empty(), "Flagged: {}", sql);
+    }
+    
+    // Test commands the translator doesn't emit today
+    assert!(check_idempotency(&RedisCommand::new("HINCRBY", vec!["k", "n", "1"])).is_some());
+    assert!(check_idempotency(&RedisCommand::new("ZADD", vec!["k", "INCR", "1", "m"])).is_some());
+    assert!(check_idempotency(&RedisCommand::new("ZADD", vec!["k", "1", "m"])).is_none());
+}
+
+#[test]
+fn test_capacity_report() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut report = CapacityReport::default();
+    for sql in [
+        "INSERT INTO users__hash (key, name, email) VALUES ('user:1', 'Ann', 'ann@example.com')",
+        "INSERT INTO users__hash (key, name, email) VALUES ('user:2', 'Bob', 'bob@example.com')",
+        "INSERT INTO tags__set (key, member) VALUES ('tags:1', 'a'), ('tags:1', 'b'), ('tags:1', 'a')",
+        "INSERT INTO cache (key, value) VALUES ('greeting', 'hello')",
+        "INSERT INTO cache (key, value) VALUES ('greeting', 'hello world')",
+        "SELECT * FROM cache WHERE key = 'greeting'",
+    ] {
+        transformer.record_capacity(sql, &mut report).unwrap();
+    }
+    
+    // Test rows come from the INSERT values, keys and values from the writes they generate
+    let tables = report.tables();
+    let [cache, tags, users] = tables.as_slice() else { panic!("Expected 3 tables: {:?}", tables) };
+    assert_eq!((cache.rows, cache.keys, cache.values, cache.value_bytes), (2, 1, 1, 11));
+    assert_eq!(cache.kinds, vec!["string"]);
+    assert_eq!((tags.rows, tags.keys, tags.values), (3, 1, 2));
+    assert_eq!((users.rows, users.keys, users.values), (2, 2, 4));
+    assert_eq!(users.avg_value_bytes(), 9.0);
+    assert_eq!(report.keys(), 4);
+    
+    // Test a small hash is listpack-encoded, a large one a hashtable
+    let mut small = CapacityReport::default();
+    small.record("t", &RedisCommand::new("HSET", vec!["h", "f", "v"]));
+    assert_eq!(small.memory(), 48 + 5 + 7 + 3 + 3);
+    let mut large = CapacityReport::default();
+    large.record("t", &RedisCommand::new("HSET", vec!["h", "f", &"v".repeat(100)]));
+    assert_eq!(large.memory(), 48 + 5 + 56 + 32 + 5 + 104);
+    
+    // Test deletes and emptied collections free their keys
+    report.record("tags__set", &RedisCommand::new("SREM", vec!["tags:1", "a", "b"]));
+    report.record("cache", &RedisCommand::new("DEL", vec!["greeting"]));
+    assert_eq!(report.keys(), 2);
+    assert!(report.to_string().starts_with("cache: 2 rows"));
+}
+
+#[test]
+fn test_equivalence_check() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    
+    // Test the rules' own commands agree with their statements, strict mode included
+    for sql in [
+        "SELECT * FROM users WHERE key = 'user:1'",
+        "SELECT name, email FROM users__hash WHERE key = 'user:1'",
+        "INSERT INTO users (key, value) VALUES ('user:2', 'Jane Smith')",
+        "INSERT INTO users__hash (key, name, age) VALUES ('user:3', 'Alice Jones', '29')",
+        "UPDATE users__hash SET status = 'active', last_login = '2023-06-15' WHERE key = 'user:1'",
+        "UPDATE settings SET value = 'dark' WHERE key = 'site:theme'",
+        "DELETE FROM users__hash WHERE key = 'user:1' AND field = 'token'",
+        "SELECT * FROM scores__zset WHERE key = 'game' AND score > 100",
+        "SELECT AVG(score) FROM scores__zset WHERE key = 'game'",
+        "DELETE FROM logs__list WHERE key = 'app:logs' AND index < 10",
+    ] {
+        let commands = transformer.transform_to_commands(sql).unwrap();
+        assert_eq!(check_equivalence(&parse(sql), &commands), vec![], "{}", sql);
+        assert!(strict.transform(sql).is_ok(), "{}", sql);
+    }
+    
+    // Test drifted commands are caught: swapped arguments, another key, wrong access
+    let check = |sql: &str, command: &str| check_equivalence(&parse(sql), &[RedisCommand::parse(command).unwrap()]);
+    let insert = "INSERT INTO users__hash (key, name) VALUES ('user:1', 'Ann')";
+    assert_eq!(check(insert, "HSET user:1 Ann name")[0].reason, "doesn't write the field name");
+    assert_eq!(check(insert, "HSET user:1 name Bob")[0].reason, "writes name = 'Bob', not 'Ann'");
+    assert_eq!(check(insert, "HSET user:1 name Ann"), vec![]);
+    let issues = check("SELECT name FROM users__hash WHERE key = 'user:1'", "HGET name user:1");
+    assert_eq!(issues[0].to_string(), "the commands address name, not the key 'user:1'");
+    assert_eq!(check("SELECT email FROM users__hash WHERE key = 'user:1'", "HGET user:1 name")[0].reason, "doesn't read email");
+    assert_eq!(check("UPDATE settings SET value = 'dark' WHERE key = 'theme'", "SET theme light")[0].reason, "writes 'light', not 'dark'");
+    assert_eq!(check("DELETE FROM cache WHERE key = 'k'", "GET k")[0].reason, "the statement writes, but every command only reads");
+    assert_eq!(check("SELECT * FROM cache WHERE key = 'k'", "DEL k")[0].to_string(), "DEL k: a SELECT runs a write");
+    
+    // Test statements that aren't reads or writes of rows pass unchecked
+    assert_eq!(check("CREATE TABLE t (id INT)", "DEL t"), vec![]);
+    assert_eq!(ErrorCode::InconsistentTranslation.as_str(), "E0013");
+}
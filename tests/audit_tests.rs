@@ -0,0 +1,69 @@
+// tests/audit_tests.rs
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use sql_redis::annotations::{annotate, trailing_comment};
+use sql_redis::audit::{AuditOutcome, AuditRecord, AuditSink};
+use sql_redis::lua::script_sha1;
+use sql_redis::SqlToRedisTransformer;
+
+#[test]
+fn test_audit_records() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "INSERT INTO users (key, value) VALUES ('u:1', 'say \"hi\"')";
+
+    // Test the matched rule is reported, with "fallback" for the built-in generator
+    assert_eq!(transformer.matched_rule(sql).unwrap(), "string_set");
+    assert!(transformer.matched_rule("SELECT * FROM users WHERE name = 'Ann'").is_err());
+
+    // Test records serialize as one JSON line, hashing the SQL
+    let record = AuditRecord::new(sql, "string_set", "SET u:1 \"say \\\"hi\\\"\"", AuditOutcome::Failed("NOPERM".to_string()));
+    let json = record.to_json();
+    assert!(json.starts_with(&format!("{{\"timestamp\":{},", record.timestamp)));
+    assert!(json.contains(&format!("\"sql_hash\":\"{}\"", script_sha1(sql))));
+    assert!(json.ends_with(r#""rule":"string_set","command":"SET u:1 \"say \\\"hi\\\"\"","outcome":"error","error":"NOPERM"}"#));
+    assert!(!json.contains('\n'));
+
+    // Test callback sinks receive each record
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let seen = seen.clone();
+        AuditSink::callback(move |record| seen.lock().unwrap().push(record.outcome.clone()))
+    };
+    sink.record(&AuditRecord::new(sql, "string_set", "SET u:1 x", AuditOutcome::Succeeded)).unwrap();
+    assert_eq!(*seen.lock().unwrap(), [AuditOutcome::Succeeded]);
+
+    // Test file sinks append, never truncate
+    let path = std::env::temp_dir().join(format!("sql_redis_audit_{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    for _ in 0..2 {
+        let sink = AuditSink::jsonl(&path).unwrap();
+        sink.record(&AuditRecord::new(sql, "string_set", "SET u:1 x", AuditOutcome::Translated)).unwrap();
+    }
+    let text = fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.lines().all(|line| line.ends_with("\"outcome\":\"translated\"}")));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_sql_annotations() {
+    let input = "-- owner: billing-team\n--ticket: BILL-42\nINSERT INTO t (key, value) VALUES ('a', 'x -- y') -- backfill\n\n-- detached\n\nDELETE FROM t WHERE key = 'a'\n-- trailing";
+    let statements = annotate(input.lines());
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0].line, 3);
+    assert_eq!(statements[0].notes, ["owner: billing-team", "ticket: BILL-42", "backfill"]);
+    assert_eq!(statements[0].comment_lines(), "# owner: billing-team\n# ticket: BILL-42\n# backfill\n");
+    assert_eq!(statements[1].sql, "DELETE FROM t WHERE key = 'a'");
+    assert!(statements[1].notes.is_empty());
+    
+    // Test comment markers inside literals are not comments
+    assert_eq!(trailing_comment("SELECT * FROM t WHERE key = '--x'"), None);
+    assert_eq!(trailing_comment("SELECT 1 --  why "), Some("why"));
+    
+    // Test annotations appear in JSON records only when present
+    let record = AuditRecord::new("DELETE FROM t", "fallback", "DEL t", AuditOutcome::Translated);
+    assert!(!record.to_json().contains("annotations"));
+    let record = record.with_annotations(&statements[0].notes);
+    assert!(record.to_json().ends_with(r#""outcome":"translated","annotations":["owner: billing-team","ticket: BILL-42","backfill"]}"#));
+}
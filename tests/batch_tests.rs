@@ -0,0 +1,34 @@
+// tests/batch_tests.rs
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Translate `input` as a piped batch, returning stderr (warnings and the summary)
+fn run_batch(input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sql_redis"))
+        .env("XDG_CONFIG_HOME", std::env::temp_dir().join("sql_redis_batch_tests"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn test_batch_summary_counts_commands() {
+    // Test a multi-line EVAL script counts once, as EVAL
+    let stderr = run_batch(concat!(
+        "SELECT AVG(score) FROM lb__zset WHERE key = 'lb'\n",
+        "SELECT * FROM users WHERE key = 'a'\n",
+        "INSERT INTO users (key, value) VALUES ('a', 'b')\n",
+        "SELECT * FROM users WHERE key = 'b'\n",
+    ));
+    let summary = stderr.lines().find(|line| line.starts_with("Commands: ")).unwrap();
+    assert_eq!(summary, "Commands: EVAL 1, GET 2, SET 1");
+    assert!(stderr.contains("Processed 4 statements (0 failed)"));
+}
@@ -0,0 +1,48 @@
+// tests/cache_tests.rs
+use std::time::Duration;
+
+use sql_redis::commands::RedisCommand;
+use sql_redis::reply::Reply;
+use sql_redis::{CacheMiss, ErrorCode, SqlToRedisTransformer};
+
+#[test]
+fn test_cache_plan() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let ttl = Duration::from_secs(300);
+
+    // Test string reads populate with SET ... EX
+    let plan = transformer.cache_plan("SELECT * FROM users WHERE key = 'user:1'", ttl).unwrap();
+    assert_eq!(plan.read, RedisCommand::new("GET", vec!["user:1"]));
+    assert_eq!(plan.miss, CacheMiss::Nil);
+    let row = vec![("value".to_string(), "Ann Lee".to_string())];
+    assert_eq!(plan.fill(&row), vec![RedisCommand::new("SET", vec!["user:1", "Ann Lee", "EX", "300"])]);
+
+    // Test projected hash reads populate the projected fields, then expire
+    let plan = transformer.cache_plan("SELECT name, email FROM users__hash WHERE key = 'user:1'", ttl).unwrap();
+    assert_eq!(plan.miss, CacheMiss::AllNil);
+    assert_eq!(plan.populate[0], RedisCommand::new("HSET", vec!["user:1", "name", "{name}", "email", "{email}"]));
+    let row = vec![("name".to_string(), "Ann".to_string())];
+    assert_eq!(plan.fill(&row), vec![
+        RedisCommand::new("HSET", vec!["user:1", "name", "Ann"]),
+        RedisCommand::new("EXPIRE", vec!["user:1", "300"]),
+    ]);
+
+    // Test SELECT * on a hash writes every column of the row
+    let plan = transformer.cache_plan("SELECT * FROM users__hash WHERE key = 'user:1'", Duration::from_millis(1500)).unwrap();
+    assert_eq!(plan.miss, CacheMiss::EmptyHash);
+    let row = vec![("name".to_string(), "Ann".to_string()), ("age".to_string(), "30".to_string())];
+    assert_eq!(plan.fill(&row), vec![
+        RedisCommand::new("HSET", vec!["user:1", "name", "Ann", "age", "30"]),
+        RedisCommand::new("EXPIRE", vec!["user:1", "2"]),
+    ]);
+
+    // Test miss detection
+    assert!(CacheMiss::Nil.is_miss(&Reply::Nil));
+    assert!(CacheMiss::EmptyHash.is_miss(&Reply::Array(vec![])));
+    assert!(CacheMiss::AllNil.is_miss(&Reply::Array(vec![Reply::Nil, Reply::Nil])));
+    assert!(!CacheMiss::AllNil.is_miss(&Reply::Array(vec![Reply::bulk("Ann"), Reply::Nil])));
+
+    // Test reads that aren't single-key hash or string reads are rejected
+    let error = transformer.cache_plan("SELECT * FROM users WHERE key IN ('a', 'b')", ttl).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::NotCacheable);
+}
@@ -0,0 +1,45 @@
+// tests/chain_tests.rs
+use sql_redis::options::Profile;
+use sql_redis::{SqlRedisError, SqlToRedisTransformer, TransformOptions, TransformerChain};
+
+fn transformer(options: TransformOptions) -> SqlToRedisTransformer {
+    SqlToRedisTransformer::with_options(options).unwrap()
+}
+
+#[test]
+fn test_transformer_chain() {
+    let chain = TransformerChain::new()
+        .with_layer("strict", transformer(TransformOptions::default().strict()))
+        .with_layer("admin", transformer(TransformOptions::default().strict().with_profile(Profile::Admin)))
+        .with_layer("lenient", transformer(TransformOptions::default()));
+    assert_eq!(chain.layers().collect::<Vec<_>>(), vec!["strict", "admin", "lenient"]);
+    
+    // Test faithful translations come from the first layer
+    let result = chain.transform("SELECT name FROM users__hash WHERE key = 'user:1'").unwrap();
+    assert_eq!((result.layer, result.name.as_str(), result.output.as_str()), (0, "strict", "HGET user:1 name"));
+    
+    // Test a rule missing from earlier layers is found in a later one
+    let result = chain.transform("SELECT * FROM __server_info").unwrap();
+    assert_eq!((result.name.as_str(), result.output.as_str()), ("admin", "INFO"));
+    
+    // Test strict-mode rejections fall through to the lenient layer, with its warnings
+    let result = chain.transform_with_report("SELECT * FROM users__hash WHERE key = 'user:1' AND age > 30").unwrap();
+    assert_eq!(result.name, "lenient");
+    assert_eq!(result.output.0, "HGETALL user:1");
+    assert!(!result.output.1.is_empty());
+    let result = chain.transform_to_commands("SELECT * FROM tasks__list WHERE key = 'q' LIMIT 3 OFFSET 2").unwrap();
+    assert_eq!((result.layer, result.output.len()), (2, 1));
+    
+    // Test parse errors stop the chain instead of falling through
+    assert!(matches!(chain.transform("SELEC nothing"), Err(SqlRedisError::SqlParseError(_))));
+    
+    // Test when no layer translates, the last layer's error is returned
+    let strict_only = TransformerChain::new().with_layer("strict", transformer(TransformOptions::default().strict()));
+    assert!(matches!(
+        strict_only.transform("SELECT * FROM users__hash WHERE key = 'user:1' AND age > 30"),
+        Err(SqlRedisError::UnsupportedClause(_))
+    ));
+    assert!(matches!(TransformerChain::new().transform("SELECT * FROM t WHERE key = 'k'"), Err(SqlRedisError::NoMatchingPattern(_))));
+    assert!(chain.layer("admin").is_some());
+    assert!(chain.layer("missing").is_none());
+}
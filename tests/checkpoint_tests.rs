@@ -0,0 +1,113 @@
+// tests/checkpoint_tests.rs
+use std::fs;
+
+use sql_redis::checkpoint::Checkpoint;
+
+#[test]
+fn test_checkpoint_roundtrip() {
+    let dir = std::env::temp_dir().join(format!("sql_redis_checkpoint_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let sidecar = Checkpoint::sidecar_path(&dir.join("migration.sql"));
+    assert_eq!(sidecar, dir.join("migration.sql.checkpoint"));
+    
+    // Test a missing sidecar means nothing was applied
+    assert_eq!(Checkpoint::load(&sidecar).unwrap(), None);
+    
+    let statements = [
+        "INSERT INTO users (key, value) VALUES ('u:1', 'Ann')",
+        "INSERT INTO users (key, value) VALUES ('u:2', 'Bob')",
+        "INSERT INTO users (key, value) VALUES ('u:3', 'Cy')",
+    ];
+    let checkpoint = Checkpoint::new(2, statements[1]);
+    checkpoint.save(&sidecar).unwrap();
+    let loaded = Checkpoint::load(&sidecar).unwrap().unwrap();
+    assert_eq!(loaded, checkpoint);
+    assert!(loaded.matches(&statements));
+    
+    // Test an edited or truncated input no longer matches
+    let edited = [statements[0], "INSERT INTO users (key, value) VALUES ('u:2', 'Bo')", statements[2]];
+    assert!(!loaded.matches(&edited));
+    assert!(!loaded.matches(&statements[..1]));
+    
+    // Test malformed sidecars are rejected
+    fs::write(&sidecar, "not a checkpoint").unwrap();
+    assert!(Checkpoint::load(&sidecar).is_err());
+    
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Batch runs through the command-line binary
+#[cfg(feature = "cli")]
+mod batch {
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::process::{Command, Output, Stdio};
+    use std::thread;
+    
+    use sql_redis::checkpoint::Checkpoint;
+    
+    /// Accept one connection and answer `replies` commands with +OK, then hang up;
+    /// returns the raw commands received
+    fn serve_then_drop(listener: TcpListener, replies: usize) -> String {
+        let (mut socket, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        for _ in 0..replies {
+            let mut buf = [0u8; 256];
+            let n = socket.read(&mut buf).unwrap();
+            received.push_str(&String::from_utf8_lossy(&buf[..n]));
+            socket.write_all(b"+OK\r\n").unwrap();
+        }
+        received
+    }
+
+    fn run_file(dir: &Path, file: &Path, url: &str, extra: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_sql_redis"))
+            .args(["--execute", "--url", url, "--file"])
+            .arg(file)
+            .args(extra)
+            .env("XDG_CONFIG_HOME", dir)
+            .env_remove("SQLNOSQL_URL")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_stops_at_failed_execution() {
+        let dir = std::env::temp_dir().join(format!("sql_redis_checkpoint_fail_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("migration.sql");
+        let statements = [
+            "INSERT INTO users (key, value) VALUES ('u:1', 'Ann')",
+            "INSERT INTO users (key, value) VALUES ('u:2', 'Bob')",
+            "INSERT INTO users (key, value) VALUES ('u:3', 'Cy')",
+        ];
+        fs::write(&file, statements.join("\n")).unwrap();
+        let sidecar = Checkpoint::sidecar_path(&file);
+
+        // Test the server going away after the first statement stops the batch there
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || serve_then_drop(listener, 1));
+        let output = run_file(&dir, &file, &url, &[]);
+        assert!(!output.status.success());
+        assert!(server.join().unwrap().contains("u:1"));
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("u:3"));
+        assert_eq!(Checkpoint::load(&sidecar).unwrap(), Some(Checkpoint::new(1, statements[0])));
+
+        // Test resuming retries the failed statement and skips the applied one
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || serve_then_drop(listener, 2));
+        let output = run_file(&dir, &file, &url, &["--resume"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let received = server.join().unwrap();
+        assert!(!received.contains("u:1"));
+        assert!(received.contains("u:2") && received.contains("u:3"));
+        assert_eq!(Checkpoint::load(&sidecar).unwrap(), Some(Checkpoint::new(3, statements[2])));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+// tests/cluster_tests.rs
+use sql_redis::cluster::{key_slot, SlotReport};
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_key_slots() {
+    assert_eq!(key_slot("123456789"), 12739);
+    assert_eq!(key_slot("foo"), 12182);
+    assert_eq!(key_slot("hello"), 866);
+
+    // Only the hash tag is hashed; an empty tag hashes the whole key
+    assert_eq!(key_slot("{user:1001}:posts"), key_slot("user:1001"));
+    assert_eq!(key_slot("{user:1001}:posts"), key_slot("{user:1001}:followers"));
+    assert_ne!(key_slot("{}foo"), key_slot("foo"));
+}
+
+#[test]
+fn test_slot_report() {
+    let workload = [
+        "SELECT * FROM users__hash WHERE key = 'user:1001'",
+        "SELECT * FROM posts__list WHERE key = 'user:1001:posts'",
+        "INSERT INTO followers__set (key, member) VALUES ('user:1001:followers', 'user:7')",
+        "SELECT * FROM cache WHERE key IN ('foo', 'hello')",
+    ];
+    let report = |options: TransformOptions| {
+        let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+        let mut report = SlotReport::default();
+        for sql in workload {
+            transformer.transform_to_commands(sql).unwrap().iter().for_each(|command| report.record(command));
+        }
+        report
+    };
+
+    let spread = report(TransformOptions::default());
+    assert_eq!(spread.references(), 5);
+    assert_eq!(spread.keys(), 5);
+    assert!(spread.hot_slots(30.0).is_empty());
+
+    // Hash tags put the entity's keys on one slot, which is then hot
+    let tagged = report(TransformOptions::default().with_hash_tags(2));
+    assert_eq!(tagged.slots().count(), 3);
+    let hot = tagged.hot_slots(30.0);
+    assert_eq!(hot.len(), 1);
+    assert_eq!((hot[0].0, hot[0].1), (key_slot("user:1001"), 3));
+
+    // Nodes own equal contiguous ranges covering every slot
+    let nodes = tagged.nodes(2);
+    assert_eq!(nodes.iter().map(|(first, last, _, _)| (*first, *last)).collect::<Vec<_>>(), vec![(0, 8191), (8192, 16383)]);
+    assert_eq!(nodes.iter().map(|(_, _, references, _)| references).sum::<usize>(), 5);
+    let text = tagged.display(2, 30.0).to_string();
+    assert!(text.starts_with("5 distinct keys, 5 references across 3 of 16384 slots\n"), "{}", text);
+    assert!(text.contains("{user:1001}, {user:1001}:followers, {user:1001}:posts"), "{}", text);
+}
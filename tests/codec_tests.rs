@@ -0,0 +1,188 @@
+// tests/codec_tests.rs
+use std::sync::Arc;
+
+use sql_redis::codec::{codec_by_name, BinaryEncoding, JsonCodec, MessagePackCodec, PlainCodec, Scalar};
+use sql_redis::reply::Reply;
+use sql_redis::{SqlToRedisTransformer, TransformOptions, ValueCodec};
+
+fn row(columns: &[(&str, Scalar)]) -> Vec<(String, Scalar)> {
+    columns.iter().map(|(name, value)| (name.to_string(), value.clone())).collect()
+}
+
+#[test]
+fn test_codec_encoding() {
+    let columns = row(&[
+        ("name", Scalar::Text("Ann \"A\"".to_string())),
+        ("age", Scalar::Number("31".to_string())),
+        ("ratio", Scalar::Number(".5".to_string())),
+        ("vip", Scalar::Boolean(true)),
+        ("note", Scalar::Null),
+    ]);
+
+    // Test JSON keeps column order and literal types, normalizing numbers JSON can't spell
+    let json = JsonCodec.encode_row(&columns).unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), r#"{"name":"Ann \"A\"","age":31,"ratio":0.5,"vip":true,"note":null}"#);
+    assert_eq!(JsonCodec.encode_value(&Scalar::Text("x".to_string())), b"\"x\"");
+
+    // Test MessagePack maps use the smallest integer and string encodings
+    let msgpack = MessagePackCodec.encode_row(&row(&[("a", Scalar::Number("1".to_string())), ("b", Scalar::Number("-200".to_string()))])).unwrap();
+    assert_eq!(msgpack, [0x82, 0xa1, b'a', 0x01, 0xa1, b'b', 0xd1, 0xff, 0x38]);
+    assert_eq!(MessagePackCodec.encode_value(&Scalar::Number("300".to_string())), [0xcd, 0x01, 0x2c]);
+    assert_eq!(MessagePackCodec.encode_value(&Scalar::Number("1.5".to_string())), [0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(MessagePackCodec.encode_value(&Scalar::Text("x".repeat(40))).len(), 42);
+    assert_eq!(MessagePackCodec.encode_value(&Scalar::Null), [0xc0]);
+
+    // Test plain text holds a single column only
+    assert_eq!(PlainCodec.encode_row(&row(&[("value", Scalar::Number("7".to_string()))])).unwrap(), b"7");
+    assert!(PlainCodec.encode_row(&columns).is_none());
+
+    // Test built-in codecs are found by name
+    assert_eq!(codec_by_name("MessagePack").unwrap().name(), "msgpack");
+    assert!(codec_by_name("xml").is_none());
+}
+
+#[test]
+fn test_encoded_inserts() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test a multi-column string insert has no translation until the table declares a codec
+    let sql = "INSERT INTO users (key, name, age) VALUES ('user:1', 'Ann', 31)";
+    assert!(transformer.transform(sql).is_err());
+    transformer.catalog().define_codec("Users", Arc::new(JsonCodec));
+    assert_eq!(transformer.transform(sql).unwrap(), r#"SET user:1 {"name":"Ann","age":31}"#);
+
+    // Test several rows go in one MSET, and a lone value column is still stored as written
+    let command = transformer.transform_to_command("INSERT INTO users (key, name) VALUES ('user:1', 'Ann'), ('user:2', 'Bob Smith')").unwrap();
+    assert_eq!(command.command, "MSET");
+    assert_eq!(command.args, ["user:1", r#"{"name":"Ann"}"#, "user:2", r#"{"name":"Bob Smith"}"#]);
+    assert_eq!(transformer.transform("INSERT INTO users (key, value) VALUES ('user:1', '{}')").unwrap(), "SET user:1 {}");
+
+    // Test hash field values are encoded one by one, and binary values render as hex
+    transformer.catalog().define_codec("users__hash", Arc::new(MessagePackCodec));
+    let command = transformer.transform_to_command("INSERT INTO users__hash (key, name, age) VALUES ('user:1', 'Ann', 31)").unwrap();
+    assert_eq!(command.args, ["user:1", "name", "a3416e6e", "age", "1f"]);
+
+    // Test tables declared as JSON documents write JSON too
+    let json_tables = SqlToRedisTransformer::with_options(TransformOptions::default().with_json_table("profiles")).unwrap();
+    assert_eq!(json_tables.transform("INSERT INTO profiles (key, city) VALUES ('p:1', 'Oslo')").unwrap(), r#"SET p:1 {"city":"Oslo"}"#);
+}
+
+#[test]
+fn test_custom_codec() {
+    // Test a codec of one's own plugs in through the catalog
+    #[derive(Debug)]
+    struct Csv;
+    impl ValueCodec for Csv {
+        fn name(&self) -> &str {
+            "csv"
+        }
+        fn encode_row(&self, columns: &[(String, Scalar)]) -> Option<Vec<u8>> {
+            Some(columns.iter().map(|(_, value)| value.to_text()).collect::<Vec<_>>().join(",").into_bytes())
+        }
+        fn encode_value(&self, value: &Scalar) -> Vec<u8> {
+            value.to_text().into_bytes()
+        }
+    }
+
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    transformer.catalog().define_codec("events", Arc::new(Csv));
+    assert_eq!(transformer.catalog().codec("EVENTS").unwrap().name(), "csv");
+    let result = transformer.transform("INSERT INTO events (key, kind, at) VALUES ('e:1', 'login', -5)").unwrap();
+    assert_eq!(result, "SET e:1 login,-5");
+}
+
+#[test]
+fn test_binary_values() {
+    // Test binary encodings round-trip every tail length, and reject text in another encoding
+    for bytes in [&b""[..], b"\x82", b"\x82\xa4", b"\x82\xa4n", b"\xff\x00\x10\x7f"] {
+        for encoding in [BinaryEncoding::Hex, BinaryEncoding::Base64] {
+            assert_eq!(encoding.decode(&encoding.encode(bytes)).unwrap(), bytes);
+        }
+    }
+    assert_eq!(BinaryEncoding::Base64.encode(b"\x82\xa4n"), "gqRu");
+    assert_eq!(BinaryEncoding::Base64.encode(b"\x82"), "gg==");
+    assert!(BinaryEncoding::Hex.decode("gqRu").is_none());
+    assert!(BinaryEncoding::Base64.decode("g=g=").is_none());
+    assert_eq!(BinaryEncoding::from_name("BASE64"), Some(BinaryEncoding::Base64));
+
+    // Test MessagePack rows and values decode back to what was encoded
+    let columns = row(&[
+        ("name", Scalar::Text("Ann".to_string())),
+        ("age", Scalar::Number("-200".to_string())),
+        ("big", Scalar::Number("5000000000".to_string())),
+        ("ratio", Scalar::Number("1.5".to_string())),
+        ("vip", Scalar::Boolean(false)),
+        ("note", Scalar::Null),
+    ]);
+    let encoded = MessagePackCodec.encode_row(&columns).unwrap();
+    assert_eq!(MessagePackCodec.decode_row(&encoded).unwrap(), columns);
+    assert!(MessagePackCodec.decode_row(&encoded[..encoded.len() - 1]).is_none());
+    assert!(MessagePackCodec.decode_row(&[encoded.as_slice(), &[0xc0]].concat()).is_none());
+    assert_eq!(MessagePackCodec.decode_value(&[0xd0, 0x80]), Some(Scalar::Number("-128".to_string())));
+    assert!(JsonCodec.decode_row(br#"{"a":1}"#).is_none());
+}
+
+#[test]
+fn test_binary_output() {
+    let options = TransformOptions::default().with_binary_encoding(BinaryEncoding::Base64);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    transformer.catalog().define_codec("users", Arc::new(MessagePackCodec));
+    let sql = "INSERT INTO users (key, name) VALUES ('u:1', 'Ann'), ('u:2', 'Bo')";
+
+    // Test text output spells binary values in the configured encoding and marks them
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.args, ["u:1", "gaRuYW1lo0Fubg==", "u:2", "gaRuYW1lokJv"]);
+    assert_eq!(command.binary, [1, 3]);
+
+    // Test RESP output writes them as raw bytes
+    let resp = transformer.transform_to_resp(sql).unwrap();
+    let expected = [&b"*5\r\n$4\r\nMSET\r\n$3\r\nu:1\r\n$10\r\n\x81\xa4name\xa3Ann\r\n"[..], b"$3\r\nu:2\r\n$9\r\n\x81\xa4name\xa2Bo\r\n"].concat();
+    assert_eq!(resp, expected);
+
+    // Test split commands keep the marks of the values they carry
+    let options = TransformOptions::default().with_max_command_args(2);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    transformer.catalog().define_codec("users", Arc::new(MessagePackCodec));
+    let commands = transformer.transform_to_commands(sql).unwrap();
+    assert_eq!(commands.iter().map(|command| command.binary.clone()).collect::<Vec<_>>(), [[1], [1]]);
+    assert_eq!(commands[1].args, ["u:2", "81a46e616d65a2426f"]);
+}
+
+#[test]
+fn test_decoded_replies() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    transformer.catalog().define_codec("users", Arc::new(MessagePackCodec));
+    transformer.catalog().define_codec("users__hash", Arc::new(MessagePackCodec));
+    let stored = "82a46e616d65a3416e6ea36167651f";
+    let text = |columns: &[(&str, &str)]| columns.iter().map(|(c, v)| (c.to_string(), v.to_string())).collect::<Vec<_>>();
+
+    // Test GET replies decode into the row's columns, whether read back in text or as raw bytes
+    let rows = transformer.decode_reply("SELECT * FROM users WHERE key = 'u:1'", &Reply::bulk(stored)).unwrap().unwrap();
+    assert_eq!(rows, [text(&[("name", "Ann"), ("age", "31")])]);
+    let raw = BinaryEncoding::Hex.decode(stored).unwrap();
+    assert!(std::str::from_utf8(&raw).is_err());
+    let rows = transformer.decode_reply("SELECT * FROM users WHERE key = 'u:1'", &Reply::Bulk(raw)).unwrap().unwrap();
+    assert_eq!(rows, [text(&[("name", "Ann"), ("age", "31")])]);
+    let raw = Reply::Array(vec![Reply::bulk("name"), Reply::Bulk(vec![0xa3, b'A', b'n', b'n']), Reply::bulk("age"), Reply::Bulk(vec![0x1f])]);
+    let rows = transformer.decode_reply("SELECT * FROM users__hash WHERE key = 'u:1'", &raw).unwrap().unwrap();
+    assert_eq!(rows, [text(&[("name", "Ann"), ("age", "31")])]);
+
+    // Test MGET skips missing keys, and hash field values decode one by one
+    let reply = Reply::Array(vec![Reply::bulk(stored), Reply::Nil]);
+    let rows = transformer.decode_reply("SELECT * FROM users WHERE key IN ('u:1', 'u:2')", &reply).unwrap().unwrap();
+    assert_eq!(rows.len(), 1);
+    let reply = Reply::Array(vec![Reply::bulk("a3416e6e"), Reply::bulk("1f")]);
+    let rows = transformer.decode_reply("SELECT name, age FROM users__hash WHERE key = 'u:1'", &reply).unwrap().unwrap();
+    assert_eq!(rows, [text(&[("name", "Ann"), ("age", "31")])]);
+
+    // Test raw rows decode whatever spelling the text output uses
+    let options = TransformOptions::default().with_binary_encoding(BinaryEncoding::Base64);
+    let base64 = SqlToRedisTransformer::with_options(options).unwrap();
+    base64.catalog().define_codec("users", Arc::new(MessagePackCodec));
+    let raw = Reply::Bulk(BinaryEncoding::Hex.decode(stored).unwrap());
+    let rows = base64.decode_reply("SELECT * FROM users WHERE key = 'u:1'", &raw).unwrap().unwrap();
+    assert_eq!(rows, [text(&[("name", "Ann"), ("age", "31")])]);
+    
+    // Test values the codec can't read back are left as stored
+    assert!(transformer.decode_reply("SELECT * FROM users WHERE key = 'u:1'", &Reply::bulk("plain")).unwrap().is_none());
+}
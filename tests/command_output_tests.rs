@@ -0,0 +1,195 @@
+// tests/command_output_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::functions::FunctionLibrary;
+use sql_redis::lua::{script_sha1, ScriptBundle};
+use sql_redis::context::TemplateContext;
+use sql_redis::templates::SimpleTemplate;
+use sql_redis::{ErrorCode, SqlToRedisTransformer};
+
+#[test]
+fn test_structured_command_output() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test values containing spaces stay a single argument
+    let command = transformer.transform_to_command("INSERT INTO posts__list (key, value) VALUES ('user:1001:posts', 'First post')").unwrap();
+    assert_eq!(command, RedisCommand::new("RPUSH", vec!["user:1001:posts", "First post"]));
+
+    // Test hash field/value pairs
+    let command = transformer.transform_to_command("INSERT INTO users__hash (key, name) VALUES ('user:1001', 'John Doe')").unwrap();
+    assert_eq!(command, RedisCommand::new("HSET", vec!["user:1001", "name", "John Doe"]));
+
+    // Test list values expand to one argument per member
+    let command = transformer.transform_to_command("INSERT INTO followers__set (key, member) VALUES ('user:1001:followers', 'user:1002'), ('user:1001:followers', 'user 1003')").unwrap();
+    assert_eq!(command.command, "SADD");
+    assert_eq!(command.args, vec!["user:1001:followers", "user:1002", "user 1003"]);
+
+    // Test fallback-generated commands
+    let command = transformer.transform_to_command("SELECT * FROM users WHERE key = 'user:1001'").unwrap();
+    assert_eq!(command, RedisCommand::new("GET", vec!["user:1001"]));
+
+    // Test multi-command translations
+    let sql = "CREATE TRIGGER audit AFTER INSERT ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()";
+    let commands = transformer.transform_to_commands(sql).unwrap();
+    assert_eq!(commands, vec![
+        RedisCommand::new("CONFIG", vec!["SET", "notify-keyspace-events", "Kh"]),
+        RedisCommand::new("PSUBSCRIBE", vec!["__keyspace@0__:user:*"]),
+    ]);
+    let error = transformer.transform_to_command(sql).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::MultipleCommands);
+}
+
+#[test]
+fn test_simple_template() {
+    let mut context = TemplateContext::new();
+    context.insert("key".to_string(), "user:1".to_string());
+    context.insert("value".to_string(), "a b".to_string());
+
+    // Test substitution and conditionals
+    let template = SimpleTemplate::parse("SET {{ key }} {{ value }}{% if ttl %} EX {{ ttl }}{% endif %}").unwrap();
+    assert_eq!(template.render(&context).unwrap(), "SET user:1 a b");
+    assert_eq!(template.render_commands(&context).unwrap(), vec![RedisCommand::new("SET", vec!["user:1", "a b"])]);
+
+    context.insert("ttl".to_string(), "60".to_string());
+    assert_eq!(template.render(&context).unwrap(), "SET user:1 a b EX 60");
+
+    // Test missing variables are reported
+    let template = SimpleTemplate::parse("GET {{ missing }}").unwrap();
+    assert!(template.render(&context).is_err());
+
+    // Test syntax outside the static subset is rejected
+    assert!(SimpleTemplate::parse("{% for k in keys %}{{ k }}{% endfor %}").is_none());
+    assert!(SimpleTemplate::parse("GET {{ key | upper }}").is_none());
+}
+
+#[test]
+fn test_script_bundle() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    assert_eq!(script_sha1(""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(script_sha1(&"a".repeat(100)), "7f9000257a4918d7072655ea468540cdcbd42e0c");
+
+    // Test multi-line EVAL scripts stay one command
+    let command = transformer.transform_to_command("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert_eq!(command.args[1..], ["1", "g:1", "-inf", "+inf"]);
+    let script = command.args[0].clone();
+
+    // Test identical scripts are loaded once and invoked by SHA
+    let mut bundle = ScriptBundle::new();
+    for sql in [
+        "SELECT AVG(score) FROM game__zset WHERE key = 'g:1'",
+        "INSERT INTO users (key, value) VALUES ('u:1', 'Ann Lee')",
+        "SELECT AVG(score) FROM game__zset WHERE key = 'g:2'",
+    ] {
+        bundle.extend(transformer.transform_to_commands(sql).unwrap());
+    }
+    assert_eq!(bundle.preamble(), vec![RedisCommand::new("SCRIPT", vec!["LOAD", script.as_str()])]);
+    let sha = script_sha1(&script);
+    assert_eq!(bundle.commands(), [
+        RedisCommand::new("EVALSHA", vec![sha.as_str(), "1", "g:1", "-inf", "+inf"]),
+        RedisCommand::new("SET", vec!["u:1", "Ann Lee"]),
+        RedisCommand::new("EVALSHA", vec![sha.as_str(), "1", "g:2", "-inf", "+inf"]),
+    ]);
+
+    // Test quoted output parses back to the same commands
+    for command in bundle.into_commands() {
+        assert_eq!(RedisCommand::parse(&command.to_quoted_string()), Some(command));
+    }
+}
+
+#[test]
+fn test_function_library() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let library = FunctionLibrary::new();
+
+    // Test the library source registers crate scripts and template scripts
+    let source = library.source();
+    assert!(source.starts_with("#!lua name=sql_redis\n"));
+    assert!(source.contains("redis.register_function('zset_avg', function(KEYS, ARGV)"));
+    assert!(source.contains("redis.register_function('hash_hmget', function(KEYS, ARGV)"));
+    assert_eq!(source.matches("redis.register_function(").count(), library.function_names().count());
+
+    let load = library.load_command(true);
+    assert_eq!(load.command, "FUNCTION");
+    assert_eq!(load.args[..2], ["LOAD", "REPLACE"]);
+    assert_eq!(RedisCommand::parse(&load.to_quoted_string()), Some(load));
+
+    // Test EVAL of a library script becomes FCALL; other commands pass through
+    let command = transformer.transform_to_command("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap();
+    assert_eq!(library.to_fcall(command), RedisCommand::new("FCALL", vec!["zset_avg", "1", "g:1", "-inf", "+inf"]));
+    let command = transformer.transform_to_command("SELECT * FROM users WHERE key = 'u:1'").unwrap();
+    assert_eq!(library.to_fcall(command.clone()), command);
+    let unknown = RedisCommand::new("EVAL", vec!["return 1", "0"]);
+    assert_eq!(library.to_fcall(unknown.clone()), unknown);
+}
+
+#[test]
+fn test_template_context() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test the matched rule and its extracted variables, without rendering
+    let (rule, context) = transformer.build_context("SELECT name FROM users__hash WHERE key = 'user:1'").unwrap().unwrap();
+    assert_eq!(rule, "hash_get");
+    assert_eq!(context, TemplateContext::from([
+        ("key".to_string(), "user:1".to_string()),
+        ("field".to_string(), "name".to_string()),
+    ]));
+    let (rule, context) = transformer.build_context("SELECT * FROM __keys WHERE key LIKE 'user:%' LIMIT 10").unwrap().unwrap();
+    assert_eq!((rule, context["pattern"].as_str(), context["count"].as_str()), ("keys_scan", "user:*", "10"));
+
+    // Test list values keep their separator, so tooling can split them like rendering does
+    let (_, context) = transformer.build_context("SELECT name, email FROM users__hash WHERE key = 'user:1'").unwrap().unwrap();
+    assert_eq!(context["fields"].split(sql_redis::context::LIST_SEPARATOR).collect::<Vec<_>>(), ["name", "email"]);
+
+    // Test scripts have no template context, and bad SQL is an error
+    assert!(transformer.build_context("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap().is_none());
+    assert!(transformer.build_context("NOT SQL").is_err());
+}
+
+#[test]
+fn test_content_hashes() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // A command hashes as the SHA-1 of its RESP encoding, whatever the name's case
+    let command = RedisCommand::new("GET", vec!["user:1"]);
+    assert_eq!(command.content_hash(), script_sha1("*2\r\n$3\r\nGET\r\n$6\r\nuser:1\r\n"));
+    assert_eq!(RedisCommand::new("get", vec!["user:1"]).content_hash(), command.content_hash());
+    // Argument boundaries count: "a b" is not "a", "b"
+    assert_ne!(RedisCommand::new("DEL", vec!["a b"]).content_hash(), RedisCommand::new("DEL", vec!["a", "b"]).content_hash());
+    
+    // Repeated statements dedupe by hash
+    let sqls = ["SELECT * FROM users WHERE key = 'user:1'", "SELECT value FROM users WHERE key = 'user:1'", "SELECT * FROM users WHERE key = 'user:2'"];
+    let mut hashes: Vec<String> = sqls.iter().map(|sql| transformer.transform_to_command(sql).unwrap().content_hash()).collect();
+    hashes.dedup();
+    assert_eq!(hashes.len(), 2);
+    
+    // Plans hash their steps: equal translations agree, and the hash is stable across builds
+    let plan = transformer.plan("UPDATE greetings SET value = value || '!' WHERE key = 'hi'").unwrap();
+    assert_eq!(plan.content_hash(), transformer.plan("UPDATE greetings SET value = value || '!' WHERE key = 'hi'").unwrap().content_hash());
+    assert_ne!(plan.content_hash(), transformer.plan("UPDATE greetings SET value = value || '?' WHERE key = 'hi'").unwrap().content_hash());
+    let plan = transformer.plan("SELECT * FROM users WHERE key IN ('a', 'b')").unwrap();
+    assert_eq!(plan.content_hash(), script_sha1("*3\r\n$4\r\nMGET\r\n$1\r\na\r\n$1\r\nb\r\n"));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_generate_command_agrees_with_transformer_on_fallback() {
+    use sql_redis::commands::generate_command;
+    use sqlparser::dialect::GenericDialect;
+    use sqlparser::parser::Parser;
+
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    for sql in [
+        "SELECT * FROM users WHERE key = 'user:1001'",
+        "SELECT member FROM lb__zset WHERE key = 'lb' AND score > 6",
+    ] {
+        let stmt = Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0);
+        assert_eq!(generate_command(&stmt), Some(transformer.transform_to_command(sql).unwrap()), "{}", sql);
+    }
+}
+
+#[test]
+fn test_transformer_applies_options_the_generator_skips() {
+    let transformer = SqlToRedisTransformer::with_options(sql_redis::TransformOptions::default().with_key_prefix("t:")).unwrap();
+    let command = transformer.transform_to_command("SELECT member FROM lb__zset WHERE key = 'lb' AND score > 6").unwrap();
+    assert_eq!(command, RedisCommand::new("ZRANGEBYSCORE", vec!["t:lb", "(6", "+inf"]));
+}
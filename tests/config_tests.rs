@@ -0,0 +1,64 @@
+// tests/config_tests.rs
+use std::fs;
+use std::path::PathBuf;
+
+use sql_redis::config::{Config, OutputFormat};
+
+#[test]
+fn test_config_file() {
+    let config = Config::parse(r#"
+        # Defaults for the production replica
+        url = "redis+unix:///var/run/redis.sock?db=2"
+        output = "Bundle"   # case-insensitive
+        key_prefix = 'tenant42:'
+        template_dir = "/etc/sqlnosql/templates"
+    "#).unwrap();
+    assert_eq!(config, Config {
+        url: Some("redis+unix:///var/run/redis.sock?db=2".to_string()),
+        output: Some(OutputFormat::Bundle),
+        key_prefix: Some("tenant42:".to_string()),
+        template_dir: Some(PathBuf::from("/etc/sqlnosql/templates")),
+    });
+    assert_eq!(Config::parse(r#"key_prefix = "a\"b\\c""#).unwrap().key_prefix.as_deref(), Some("a\"b\\c"));
+    assert_eq!(Config::parse("").unwrap(), Config::default());
+
+    // Test typos, bare values and unterminated strings are reported with their line
+    assert_eq!(Config::parse("\nurl_ = \"x\"").unwrap_err(), "line 2: unknown key \"url_\"");
+    assert!(Config::parse("url = redis://x").is_err());
+    assert!(Config::parse("url = \"redis://x").is_err());
+    assert!(Config::parse("url = \"redis://x\" trailing").is_err());
+    assert!(Config::parse("output = \"yaml\"").is_err());
+    assert!(Config::parse("[server]").is_err());
+
+    // Test a missing file is not an error, and a malformed one names the file
+    let dir = std::env::temp_dir().join(format!("sql_redis_config_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    assert_eq!(Config::load(&path).unwrap(), None);
+    fs::write(&path, "output = \"functions\"\n").unwrap();
+    assert_eq!(Config::load(&path).unwrap().unwrap().output, Some(OutputFormat::Functions));
+    fs::write(&path, "output = functions\n").unwrap();
+    assert!(Config::load(&path).unwrap_err().to_string().contains("config.toml: line 1"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(Config::default_path().is_none_or(|path| path.ends_with("sqlnosql/config.toml")));
+}
+
+#[cfg(all(feature = "cli", not(feature = "plugins")))]
+#[test]
+fn test_template_dir_needs_plugins() {
+    use std::process::Command;
+
+    // Test a template_dir the binary can't load is an error, not silently skipped
+    let dir = std::env::temp_dir().join(format!("sql_redis_template_dir_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    fs::write(&path, "template_dir = \"/etc/sqlnosql/plugins\"\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_sql_redis"))
+        .args(["--config", path.to_str().unwrap(), "--query", "SELECT * FROM users WHERE key = 'u:1'"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs the `plugins` feature"));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,57 @@
+// tests/corpus_tests.rs
+use sql_redis::corpus::{check_corpus, parse_corpus};
+use sql_redis::SqlToRedisTransformer;
+
+const CORPUS: &str = "\
+-- user reads
+SELECT * FROM users__hash WHERE key = 'user:1'
+-- expect: HGETALL user:1
+
+SELECT * FROM cache WHERE key = 'my key'
+-- a note between expectations is ignored
+-- expect: get \"my key\"
+INSERT INTO cache (key, value) VALUES ('a', 'b')
+SELECT * FROM users__hash WHERE key = 'user:2'
+-- expect: HGET user:2 name
+SELECT nonsense
+-- expect: GET x
+";
+
+#[test]
+fn test_parse_corpus() {
+    let cases = parse_corpus(CORPUS);
+
+    // Test statements without expectations aren't cases
+    assert_eq!(cases.len(), 4);
+    assert_eq!(cases[0].line, 2);
+    assert_eq!(cases[0].expected, ["HGETALL user:1"]);
+    assert_eq!(cases[1].sql, "SELECT * FROM cache WHERE key = 'my key'");
+    assert_eq!(cases[1].expected, ["get \"my key\""]);
+    assert_eq!(cases[2].line, 9);
+}
+
+#[test]
+fn test_check_corpus() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let report = check_corpus(&transformer, CORPUS);
+
+    // Test commands compare parsed, ignoring quoting and command name case
+    assert_eq!(report.passed, 2);
+    assert!(!report.is_success());
+
+    // Test mismatches list the expected and produced commands
+    assert_eq!(report.failures.len(), 2);
+    assert_eq!(report.failures[0].actual, Ok(vec!["HGETALL user:2".to_string()]));
+    assert_eq!(
+        report.failures[0].to_string(),
+        "line 9: SELECT * FROM users__hash WHERE key = 'user:2'\n  - HGET user:2 name\n  + HGETALL user:2"
+    );
+
+    // Test translation errors are failures carrying the error code
+    let error = report.failures[1].actual.as_ref().unwrap_err();
+    assert!(error.starts_with("[E0002]"), "{}", error);
+
+    // Test multi-command statements expect one line per command, in order
+    let corpus = "SELECT * FROM users__hash WHERE key = 'user:1'\n-- expect: HGETALL user:1\n-- expect: HGETALL user:1\n";
+    assert_eq!(check_corpus(&transformer, corpus).failures.len(), 1);
+}
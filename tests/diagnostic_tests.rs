@@ -0,0 +1,36 @@
+// tests/diagnostic_tests.rs
+use sql_redis::diagnostic::render_snippet;
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_span_reporting() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "SELECT * FROM users__hash WHERE key = 'user:1' AND age > 30";
+    
+    // Dropped-clause warnings point at the offending predicate
+    let (_, warnings) = transformer.transform_with_report(sql).unwrap();
+    let span = warnings[0].span.unwrap();
+    assert_eq!((span.start.line, span.start.column, span.end.column), (1, 52, 60));
+    assert_eq!(render_snippet(sql, span, "ignored"), concat!(
+        " --> 1:52\n",
+        "  |\n",
+        "1 | SELECT * FROM users__hash WHERE key = 'user:1' AND age > 30\n",
+        "  |                                                    ^^^^^^^^ ignored\n",
+    ));
+    
+    // Rule warnings have no span
+    assert_eq!(warnings[1].span, None);
+    
+    // Strict-mode errors carry the spans of every dropped clause, across lines
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    let sql = "SELECT * FROM tasks__list WHERE key = 'q'\nLIMIT 3 OFFSET 2";
+    let labels = strict.transform(sql).unwrap_err().labels();
+    assert_eq!(labels.len(), 1);
+    assert_eq!((labels[0].0.start.line, labels[0].0.start.column), (2, 16));
+    
+    // Parse errors point at the unexpected token
+    let labels = transformer.transform("SELECT * FROM t WHERE key = = 3").unwrap_err().labels();
+    assert_eq!(labels.len(), 1);
+    assert_eq!((labels[0].0.start.line, labels[0].0.start.column), (1, 29));
+    assert_eq!(labels[0].1, "syntax error");
+}
@@ -0,0 +1,47 @@
+// tests/emulator_tests.rs
+#![cfg(feature = "emulator")]
+
+use sql_redis::commands::RedisCommand;
+use sql_redis::executor::{Emulator, Executor};
+use sql_redis::reply::Reply;
+use sql_redis::SqlToRedisTransformer;
+
+fn run(transformer: &SqlToRedisTransformer, executor: &mut Executor<Emulator>, sql: &str) -> Reply {
+    let mut replies = transformer.transform_to_commands(sql).unwrap().iter()
+        .map(|command| executor.execute(command).unwrap())
+        .collect::<Vec<_>>();
+    replies.pop().unwrap()
+}
+
+#[test]
+fn test_emulated_execution() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut executor = Executor::new(Emulator::new());
+    
+    // Test writes and reads round-trip without a server
+    run(&transformer, &mut executor, "INSERT INTO users__hash (key, name, city) VALUES ('user:1', 'Ann', 'Oslo')");
+    let reply = run(&transformer, &mut executor, "SELECT city FROM users__hash WHERE key = 'user:1'");
+    assert_eq!(reply, Reply::bulk("Oslo"));
+    run(&transformer, &mut executor, "INSERT INTO lb__zset (key, member, score) VALUES ('lb', 'ann', '10'), ('lb', 'bob', '5')");
+    let reply = run(&transformer, &mut executor, "SELECT member FROM lb__zset WHERE key = 'lb' AND score > 6");
+    assert_eq!(reply, Reply::Array(vec![Reply::bulk("ann")]));
+    run(&transformer, &mut executor, "DELETE FROM lb__zset WHERE key = 'lb' AND member = 'ann'");
+    let reply = run(&transformer, &mut executor, "SELECT COUNT(*) FROM lb__zset WHERE key = 'lb'");
+    assert_eq!(reply, Reply::Integer(1));
+    
+    // Test logical databases are separate keyspaces
+    assert_eq!(executor.execute(&RedisCommand::new("DBSIZE", Vec::<String>::new())).unwrap(), Reply::Integer(2));
+    executor.execute(&RedisCommand::new("SELECT", vec!["3"])).unwrap();
+    assert_eq!(executor.execute(&RedisCommand::new("DBSIZE", Vec::<String>::new())).unwrap(), Reply::Integer(0));
+    
+    // Test Lua-backed translations run in the embedded interpreter
+    let reply = run(&transformer, &mut executor, "SELECT SUM(score) FROM lb__zset WHERE key = 'lb'");
+    assert_eq!(reply, Reply::Integer(0));
+    executor.execute(&RedisCommand::new("SELECT", vec!["0"])).unwrap();
+    let reply = run(&transformer, &mut executor, "SELECT SUM(score) FROM lb__zset WHERE key = 'lb'");
+    assert_eq!(reply, Reply::Integer(5));
+    
+    // Test unemulated commands fail like server errors
+    assert!(executor.execute(&RedisCommand::new("FCALL", vec!["zset_sum", "1", "lb"])).is_err());
+    assert!(executor.execute(&RedisCommand::new("JSON.GET", vec!["doc", "$.a"])).is_err());
+}
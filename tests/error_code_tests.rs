@@ -0,0 +1,51 @@
+// tests/error_code_tests.rs
+use sql_redis::{ErrorCode, SqlRedisError, SqlToRedisTransformer, TransformOptions};
+
+fn code(transformer: &SqlToRedisTransformer, sql: &str) -> ErrorCode {
+    transformer.transform(sql).unwrap_err().code()
+}
+
+#[test]
+fn test_error_codes() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    
+    assert_eq!(code(&transformer, "SELECT * FROM users__hash WHERE key LIKE 'user:%'"), ErrorCode::UnsupportedPattern);
+    assert_eq!(code(&transformer, "SELECT * FROM users__hash WHERE name = 'John'"), ErrorCode::MissingKeyPredicate);
+    assert_eq!(code(&transformer, "DELETE FROM cache"), ErrorCode::MissingKeyPredicate);
+    assert_eq!(code(&transformer, "NOT A VALID SQL"), ErrorCode::SqlParse);
+    assert_eq!(code(&strict, "SELECT * FROM users__hash WHERE key = 'u' AND age > 30"), ErrorCode::UnsupportedClause);
+    
+    // Codes are stable strings for docs and logs
+    assert_eq!(ErrorCode::UnsupportedPattern.as_str(), "E0001");
+    assert_eq!(ErrorCode::MissingKeyPredicate.to_string(), "E0002");
+    assert_eq!(SqlRedisError::TemplateError("x".to_string()).code().as_str(), "E0005");
+    assert_eq!(SqlRedisError::InitializationError("x".to_string()).code().as_str(), "E0006");
+    
+    // Display is unchanged by the codes
+    let err = transformer.transform("SELECT * FROM users__hash WHERE name = 'John'").unwrap_err();
+    assert_eq!(err.to_string(), "Missing key predicate (WHERE key = ..., or WHEN (key LIKE ...) on a trigger) in: SELECT * FROM users__hash WHERE name = 'John'");
+}
+
+#[test]
+fn test_negative_key_predicates() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    for sql in [
+        "SELECT * FROM users__hash WHERE key != 'user:1'",
+        "SELECT * FROM users WHERE key <> 'a' AND age > 3",
+        "DELETE FROM cache WHERE key NOT IN ('a', 'b')",
+        "UPDATE users__hash SET name = 'x' WHERE NOT (key = 'user:1')",
+    ] {
+        assert_eq!(code(&transformer, sql), ErrorCode::UnsupportedPredicate, "{}", sql);
+    }
+    
+    // The error names the predicate and points at it
+    let err = transformer.transform("DELETE FROM cache WHERE key NOT IN ('a', 'b')").unwrap_err();
+    assert_eq!(err.code().as_str(), "E0012");
+    assert!(err.to_string().starts_with("WHERE key NOT IN ('a', 'b') can't map to a key lookup"));
+    assert_eq!(err.labels().len(), 1);
+    
+    // Negative predicates on other columns keep their existing errors
+    assert_eq!(code(&transformer, "SELECT * FROM users__hash WHERE name != 'John'"), ErrorCode::MissingKeyPredicate);
+}
@@ -0,0 +1,632 @@
+// tests/executor_tests.rs
+use std::cell::RefCell;
+use std::io::{self, Cursor, Read, Write};
+use std::net::TcpListener;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use sql_redis::commands::RedisCommand;
+use sql_redis::executor::{self, destroyed_keys, diff_command, BatchProgress, CancellationToken, Change, ClusterConnection, Connection, Divergence, ExecutionError, ExecutionMode, ExecutionOptions, Executor, FanOutConnection, RespConnection, ScriptCache};
+use sql_redis::reply::{decode_browsed_keys, diff_rows, Reply, Row, RowDiff};
+
+/// In-memory stream: reads canned server replies, records what the client wrote
+struct MockStream {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl MockStream {
+    fn new(replies: impl AsRef<[u8]>) -> Self {
+        Self { input: Cursor::new(replies.as_ref().to_vec()), output: Vec::new() }
+    }
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_command_parsing() {
+    let command = RedisCommand::parse("SET user:1001 John").unwrap();
+    assert_eq!(command.command, "SET");
+    assert_eq!(command.args, vec!["user:1001", "John"]);
+    
+    // Single quotes use SQL-style '' escaping (as in rendered EVAL scripts)
+    let command = RedisCommand::parse("EVAL 'return ''ok''' 0").unwrap();
+    assert_eq!(command.args, vec!["return 'ok'", "0"]);
+    
+    // Double quotes use backslash escapes
+    let command = RedisCommand::parse("SET k \"a b\\\"c\"").unwrap();
+    assert_eq!(command.args, vec!["k", "a b\"c"]);
+    
+    assert!(RedisCommand::parse("   ").is_none());
+    assert!(RedisCommand::parse("SET k 'unterminated").is_none());
+}
+
+#[test]
+fn test_resp_replies() {
+    let replies = "+OK\r\n$5\r\nhello\r\n$-1\r\n:42\r\n*2\r\n$1\r\na\r\n:1\r\n-ERR wrong type\r\n";
+    let mut executor = Executor::new(RespConnection::from_stream(MockStream::new(replies)));
+    
+    let results = executor.execute_text("SET k v\nGET k\nGET missing\nINCR n\nLRANGE l 0 -1").unwrap();
+    assert_eq!(results, vec![
+        Reply::Status("OK".to_string()),
+        Reply::bulk("hello"),
+        Reply::Nil,
+        Reply::Integer(42),
+        Reply::Array(vec![Reply::bulk("a"), Reply::Integer(1)]),
+    ]);
+    
+    // Error replies surface as ExecutionError::Server
+    let result = executor.execute(&RedisCommand::new("HGET", vec!["k", "f"]));
+    assert!(matches!(result, Err(ExecutionError::Server(msg)) if msg == "ERR wrong type"));
+    
+    // Binary bulk strings keep their bytes
+    let stored = b"\x82\xa4name\xa3Ann\xa3age\x1f";
+    let replies = [&b"$15\r\n"[..], stored, b"\r\n"].concat();
+    let mut executor = Executor::new(RespConnection::from_stream(MockStream::new(replies)));
+    assert_eq!(executor.execute_text("GET u:1").unwrap(), vec![Reply::Bulk(stored.to_vec())]);
+}
+
+#[test]
+fn test_resp_encoding_and_messages() {
+    let replies = "*3\r\n$10\r\npsubscribe\r\n$1\r\n*\r\n:1\r\n\
+                   *4\r\n$8\r\npmessage\r\n$1\r\n*\r\n$18\r\n__keyspace@0__:k:1\r\n$3\r\nset\r\n";
+    let mut stream = MockStream::new(replies);
+    {
+        let mut executor = Executor::new(RespConnection::from_stream(&mut stream));
+        executor.execute(&RedisCommand::new("PSUBSCRIBE", vec!["*"])).unwrap();
+        
+        let messages: Vec<Reply> = executor.messages().collect();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0], Reply::Array(vec![
+            Reply::bulk("pmessage"),
+            Reply::bulk("*"),
+            Reply::bulk("__keyspace@0__:k:1"),
+            Reply::bulk("set"),
+        ]));
+    }
+    assert_eq!(stream.output, b"*2\r\n$10\r\nPSUBSCRIBE\r\n$1\r\n*\r\n");
+}
+
+/// Connection that answers every command with its name (sequential default pipeline)
+struct EchoConnection;
+
+impl Connection for EchoConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        Ok(Reply::Status(args[0].clone()))
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_batch_execution() {
+    let commands: Vec<RedisCommand> = (0..7)
+        .map(|i| RedisCommand::new("SET", vec![format!("k:{}", i), i.to_string()]))
+        .collect();
+    let options = ExecutionOptions { max_in_flight: 2, chunk_size: 5, delay_between_chunks: Duration::from_millis(1) };
+    let mut executor = Executor::with_options(EchoConnection, options);
+    
+    let mut progress = Vec::new();
+    let replies = executor.execute_batch(&commands, |p| progress.push(p)).unwrap();
+    assert_eq!(replies.len(), 7);
+    assert_eq!(progress.last(), Some(&BatchProgress { completed: 7, total: 7 }));
+    // Chunks of 5 and 2, each pipelined at most 2 deep
+    assert_eq!(progress.iter().map(|p| p.completed).collect::<Vec<_>>(), vec![2, 4, 5, 7]);
+    
+    // RESP connections write the whole window before reading replies; errors stay in place
+    let mut stream = MockStream::new("+OK\r\n-ERR wrong type\r\n");
+    {
+        let mut executor = Executor::new(RespConnection::from_stream(&mut stream));
+        let replies = executor.execute_batch(&commands[..2], |_| ()).unwrap();
+        assert_eq!(replies, vec![Reply::Status("OK".to_string()), Reply::Error("ERR wrong type".to_string())]);
+    }
+    assert_eq!(stream.output, b"*3\r\n$3\r\nSET\r\n$3\r\nk:0\r\n$1\r\n0\r\n*3\r\n$3\r\nSET\r\n$3\r\nk:1\r\n$1\r\n1\r\n");
+}
+
+/// Connection that records each pipelined window and answers GET with a value, EXEC with
+/// the queued replies, and anything else with its name
+#[derive(Default)]
+struct WindowConnection {
+    windows: Rc<RefCell<Vec<Vec<String>>>>,
+}
+
+impl Connection for WindowConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        Ok(match args[0].as_str() {
+            "GET" => Reply::bulk("hello"),
+            "EXEC" => Reply::Array(vec![Reply::Status("OK".to_string())]),
+            name => Reply::Status(name.to_string()),
+        })
+    }
+
+    fn send_pipeline(&mut self, commands: &[Vec<String>]) -> Result<Vec<Reply>, ExecutionError> {
+        self.windows.borrow_mut().push(commands.iter().map(|args| args.join(" ")).collect());
+        commands.iter().map(|args| self.send(args)).collect()
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_plan_execution() {
+    let transformer = sql_redis::SqlToRedisTransformer::new().unwrap();
+    let plan = transformer.update_plan("UPDATE greetings SET value = value || '!' WHERE key = 'hi'").unwrap();
+    let connection = WindowConnection::default();
+    let windows = connection.windows.clone();
+    let mut executor = Executor::new(connection);
+    
+    // Steps are pipelined up to the barrier, then MULTI up to the computation, whose
+    // writes (computed from the GET reply) are pipelined with the rest
+    let replies = executor.execute_plan(&plan).unwrap();
+    assert_eq!(*windows.borrow(), vec![
+        vec!["WATCH hi".to_string(), "GET hi".to_string()],
+        vec!["MULTI".to_string()],
+        vec!["SET hi hello!".to_string()],
+        vec!["EXEC".to_string()],
+    ]);
+    assert_eq!(replies.len(), 5);
+    assert_eq!(replies[4], Reply::Array(vec![Reply::Status("OK".to_string())]));
+}
+
+/// Loads scripts on EVAL and answers EVALSHA of an unknown script with NOSCRIPT
+#[derive(Default)]
+struct ScriptConnection {
+    loaded: Vec<String>,
+    sent: Rc<RefCell<Vec<String>>>,
+}
+
+impl Connection for ScriptConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        self.sent.borrow_mut().push(args[0].clone());
+        match args[0].as_str() {
+            "EVAL" => self.loaded.push(sql_redis::lua::script_sha1(&args[1])),
+            "EVALSHA" if !self.loaded.contains(&args[1]) => {
+                return Ok(Reply::Error("NOSCRIPT No matching script. Please use EVAL.".to_string()));
+            }
+            "SCRIPT" => self.loaded.clear(),
+            _ => {}
+        }
+        Ok(Reply::Integer(args.len() as i64))
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_script_cache() {
+    let eval = |key: &str| RedisCommand::new("EVAL", vec!["return #KEYS", "1", key]);
+    let connection = ScriptConnection::default();
+    let sent = connection.sent.clone();
+    let mut executor = Executor::new(connection).with_script_cache();
+    
+    // Test a script is sent once, then by SHA; replies are unchanged
+    assert_eq!(executor.execute(&eval("a")).unwrap(), Reply::Integer(4));
+    assert_eq!(executor.execute(&eval("b")).unwrap(), Reply::Integer(4));
+    assert_eq!(*sent.borrow(), ["EVAL", "EVALSHA"]);
+    assert!(executor.script_cache().unwrap().contains("return #KEYS"));
+    
+    // Test NOSCRIPT (after SCRIPT FLUSH) falls back to EVAL, for each pipelined EVALSHA
+    executor.execute(&RedisCommand::new("SCRIPT", vec!["FLUSH"])).unwrap();
+    let replies = executor.execute_batch(&[eval("c"), eval("d")], |_| ()).unwrap();
+    assert_eq!(replies, [Reply::Integer(4), Reply::Integer(4)]);
+    assert_eq!(sent.borrow()[2..], ["SCRIPT", "EVALSHA", "EVALSHA", "EVAL", "EVAL"]);
+    
+    let cache: &ScriptCache = executor.script_cache().unwrap();
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 3, 1));
+    assert_eq!(cache.hit_rate(), 0.25);
+    
+    // Test without the cache every EVAL carries its script
+    let mut executor = Executor::new(ScriptConnection::default());
+    executor.execute(&eval("a")).unwrap();
+    executor.execute(&eval("a")).unwrap();
+    assert!(executor.script_cache().is_none());
+}
+
+#[test]
+fn test_timeout_and_cancellation() {
+    // A server that answers a command only after the client has given up on it
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+        serve(&mut socket, &["+OK\r\n"]);
+        let _ = socket.read(&mut [0u8; 256]).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        let _ = socket.write_all(b"+SLOW\r\n");
+        // The client reconnects rather than reading on past the late reply
+        serve(listener.accept().unwrap().0, &["+OK\r\n", "+PONG\r\n"])
+    });
+
+    let connection = RespConnection::connect(&url).unwrap().with_timeout(Duration::from_millis(50)).unwrap();
+    let mut executor = Executor::new(connection);
+    executor.execute(&RedisCommand::new("SELECT", vec!["3"])).unwrap();
+    let slow = executor.execute(&RedisCommand::new("DEBUG", vec!["SLEEP", "1"]));
+    assert!(matches!(slow, Err(ExecutionError::Timeout(t)) if t == Duration::from_millis(50)));
+    thread::sleep(Duration::from_millis(300));
+    let reply = executor.execute(&RedisCommand::new("PING", Vec::<String>::new())).unwrap();
+    assert_eq!(reply, Reply::Status("PONG".to_string()));
+    // The new connection is back in the database the old one had selected
+    let received = server.join().unwrap();
+    assert!(received.starts_with("*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n"), "{:?}", received);
+
+    // A stream that can't be reopened fails the commands after a broken reply
+    let mut connection = RespConnection::from_stream(MockStream::new("+OK\r\n$5\r\nab"));
+    assert!(connection.send(&["PING".to_string()]).is_ok());
+    assert!(connection.send(&["GET".to_string(), "k".to_string()]).is_err());
+    assert!(matches!(connection.send(&["PING".to_string()]), Err(ExecutionError::Protocol(_))));
+
+    // Cancellation stops single commands and batches before anything is sent
+    let token = CancellationToken::new();
+    let mut executor = Executor::new(EchoConnection).with_cancellation(token.clone());
+    let command = RedisCommand::new("GET", vec!["k"]);
+    assert!(executor.execute(&command).is_ok());
+    token.cancel();
+    assert!(matches!(executor.execute(&command), Err(ExecutionError::Cancelled)));
+    let mut progress = Vec::new();
+    let batch = executor.execute_batch(std::slice::from_ref(&command), |p| progress.push(p));
+    assert!(matches!(batch, Err(ExecutionError::Cancelled)));
+    assert!(progress.is_empty());
+}
+
+#[test]
+fn test_destructive_confirmation() {
+    let del = RedisCommand::new("DEL", vec!["u:1", "u:2"]);
+    assert_eq!(destroyed_keys(&del), Some(vec!["u:1".to_string(), "u:2".to_string()]));
+    assert_eq!(destroyed_keys(&RedisCommand::new("flushdb", Vec::<String>::new())), Some(vec!["*".to_string()]));
+    // Field and member removals leave the key in place
+    assert_eq!(destroyed_keys(&RedisCommand::new("HDEL", vec!["u:1", "name"])), None);
+
+    let asked = Rc::new(RefCell::new(Vec::new()));
+    let log = asked.clone();
+    let mut executor = Executor::new(EchoConnection).with_confirmation(move |command, keys| {
+        log.borrow_mut().push(format!("{} {}", command.command, keys.join(" ")));
+        keys.len() == 1
+    });
+    assert!(executor.execute(&RedisCommand::new("HDEL", vec!["u:1", "name"])).is_ok());
+    assert!(executor.execute(&RedisCommand::new("UNLINK", vec!["u:3"])).is_ok());
+    assert!(matches!(executor.execute(&del), Err(ExecutionError::Declined(name)) if name == "DEL"));
+
+    // A declined command stops a batch before its window is sent
+    let batch = [RedisCommand::new("SET", vec!["k", "v"]), del.clone()];
+    let mut progress = Vec::new();
+    assert!(matches!(executor.execute_batch(&batch, |p| progress.push(p)), Err(ExecutionError::Declined(_))));
+    assert!(progress.is_empty());
+    assert_eq!(*asked.borrow(), vec!["UNLINK u:3", "DEL u:1 u:2", "DEL u:1 u:2"]);
+}
+
+#[test]
+fn test_read_only_mode() {
+    let mut executor = Executor::new(EchoConnection).with_mode(ExecutionMode::ReadOnly);
+    assert!(executor.execute(&RedisCommand::new("HGETALL", vec!["u:1"])).is_ok());
+    assert!(executor.execute(&RedisCommand::new("SELECT", vec!["2"])).is_ok());
+    assert!(matches!(executor.execute(&RedisCommand::new("HSET", vec!["u:1", "name", "Ann"])), Err(ExecutionError::ReadOnly(name)) if name == "HSET"));
+
+    // A batch holding a write is refused before anything is sent
+    let batch = [RedisCommand::new("GET", vec!["k"]), RedisCommand::new("SET", vec!["k", "v"])];
+    assert!(matches!(executor.execute_batch(&batch, |_| ()), Err(ExecutionError::ReadOnly(_))));
+
+    // Read-only mode is checked before confirmation, so writes are never offered for it
+    let mut executor = Executor::new(EchoConnection)
+        .with_mode(ExecutionMode::ReadOnly)
+        .with_confirmation(|_, _| panic!("read-only executors don't confirm"));
+    assert!(matches!(executor.execute(&RedisCommand::new("DEL", vec!["k"])), Err(ExecutionError::ReadOnly(_))));
+}
+
+/// Connection that logs each command's name and answers writes with a fixed integer
+struct CountingConnection {
+    log: Rc<RefCell<Vec<String>>>,
+    written: i64,
+}
+
+impl Connection for CountingConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        self.log.borrow_mut().push(args[0].clone());
+        match args[0].as_str() {
+            "HGETALL" | "SELECT" => Ok(Reply::Status("OK".to_string())),
+            _ => Ok(Reply::Integer(self.written)),
+        }
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_fan_out_writes() {
+    let connection = |written| {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        (log.clone(), Box::new(CountingConnection { log, written }) as Box<dyn Connection>)
+    };
+    let (primary_log, primary) = connection(1);
+    let (old_log, old) = connection(1);
+    let (new_log, new) = connection(0);
+    let divergences = Rc::new(RefCell::new(Vec::new()));
+    let report = divergences.clone();
+    let fan_out = FanOutConnection::new(primary)
+        .with_target("old", old)
+        .with_target("new", new)
+        .on_divergence(move |divergence: &Divergence| report.borrow_mut().push(divergence.clone()));
+    assert_eq!(fan_out.targets().collect::<Vec<_>>(), vec!["old", "new"]);
+    let mut executor = Executor::new(fan_out);
+
+    // Reads go to the primary only; SELECT and writes go everywhere
+    executor.execute(&RedisCommand::new("HGETALL", vec!["u:1"])).unwrap();
+    executor.execute(&RedisCommand::new("SELECT", vec!["2"])).unwrap();
+    let reply = executor.execute(&RedisCommand::new("HSET", vec!["u:1", "name", "Ann"])).unwrap();
+    assert_eq!(reply, Reply::Integer(1));
+    let batch = [RedisCommand::new("GET", vec!["k"]), RedisCommand::new("SET", vec!["k", "v"])];
+    executor.execute_batch(&batch, |_| ()).unwrap();
+    assert_eq!(*primary_log.borrow(), vec!["HGETALL", "SELECT", "HSET", "GET", "SET"]);
+    assert_eq!(*old_log.borrow(), vec!["SELECT", "HSET", "SET"]);
+    assert_eq!(*new_log.borrow(), vec!["SELECT", "HSET", "SET"]);
+
+    // Only the target whose replies differ is reported
+    let divergences = divergences.borrow();
+    assert_eq!(divergences.len(), 2);
+    assert_eq!(divergences[0], Divergence {
+        command: "HSET u:1 name Ann".to_string(),
+        target: "new".to_string(),
+        expected: Reply::Integer(1),
+        actual: Ok(Reply::Integer(0)),
+    });
+    assert_eq!(divergences[1].to_string(), "SET k v: primary replied (integer) 1, new replied (integer) 0");
+}
+
+#[test]
+fn test_shadow_reads() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let shadow = CountingConnection { log: log.clone(), written: 0 };
+    let mut executor = Executor::new(EchoConnection).with_shadow(Box::new(shadow));
+
+    // Reads and SELECTs are repeated on the shadow; only the read's reply is kept
+    executor.execute(&RedisCommand::new("SELECT", vec!["1"])).unwrap();
+    assert!(executor.take_shadow_reply().is_none());
+    executor.execute(&RedisCommand::new("HGETALL", vec!["u:1"])).unwrap();
+    assert_eq!(executor.take_shadow_reply().unwrap().unwrap(), Reply::Status("OK".to_string()));
+    assert!(executor.take_shadow_reply().is_none());
+    executor.execute(&RedisCommand::new("HSET", vec!["u:1", "name", "Ann"])).unwrap();
+    assert!(executor.take_shadow_reply().is_none());
+    assert_eq!(*log.borrow(), vec!["SELECT", "HGETALL"]);
+
+    // Rows are compared regardless of order; leftovers pair up column by column
+    let row = |pairs: &[(&str, &str)]| -> Row { pairs.iter().map(|(c, v)| (c.to_string(), v.to_string())).collect() };
+    let expected = [row(&[("member", "a")]), row(&[("member", "b"), ("score", "2")]), row(&[("member", "c")])];
+    assert!(diff_rows(&expected, &[expected[2].clone(), expected[0].clone(), expected[1].clone()]).is_empty());
+    let actual = [row(&[("member", "c")]), row(&[("member", "b"), ("score", "3")])];
+    let diffs = diff_rows(&expected, &actual);
+    assert_eq!(diffs, vec![
+        RowDiff::Changed { index: 0, column: "member".to_string(), expected: Some("a".to_string()), actual: Some("b".to_string()) },
+        RowDiff::Changed { index: 0, column: "score".to_string(), expected: None, actual: Some("3".to_string()) },
+        RowDiff::Missing { index: 1, row: expected[1].clone() },
+    ]);
+    assert_eq!(diffs.iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+        "~ row 1 member: \"a\" -> \"b\"",
+        "~ row 1 score: (absent) -> \"3\"",
+        "- row 2: member=b, score=2",
+    ]);
+    assert_eq!(diff_rows(&[], &actual[..1]), vec![RowDiff::Extra { index: 0, row: actual[0].clone() }]);
+}
+
+/// Accept one connection and answer each command it reads with the next canned reply,
+/// returning the raw commands received
+fn serve<S: Read + Write>(mut socket: S, replies: &[&str]) -> String {
+    let mut received = String::new();
+    for reply in replies {
+        let mut buf = [0u8; 256];
+        let n = socket.read(&mut buf).unwrap();
+        received.push_str(&String::from_utf8_lossy(&buf[..n]));
+        socket.write_all(reply.as_bytes()).unwrap();
+    }
+    received
+}
+
+#[test]
+fn test_connection_urls() {
+    // Unix sockets take the database and password as query parameters
+    #[cfg(unix)]
+    {
+        use std::os::unix::net::UnixListener;
+        let path = std::env::temp_dir().join(format!("sql_redis_test_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = thread::spawn(move || serve(listener.accept().unwrap().0, &["+OK\r\n", "+OK\r\n", "$2\r\nv1\r\n"]));
+        let mut executor = Executor::new(executor::connect(&format!("redis+unix://{}?db=2&password=secret", path.display()), None).unwrap());
+        let reply = executor.execute(&RedisCommand::new("GET", vec!["k"])).unwrap();
+        assert_eq!(reply, Reply::bulk("v1"));
+        let received = server.join().unwrap();
+        assert!(received.starts_with("*2\r\n$4\r\nAUTH\r\n$6\r\nsecret\r\n*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    // Cluster connections skip unreachable seeds and follow MOVED to the owning node
+    let first = TcpListener::bind("127.0.0.1:0").unwrap();
+    let second = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("redis-cluster://127.0.0.1:1,{}", first.local_addr().unwrap());
+    let second_port = second.local_addr().unwrap().port();
+    let moved = format!("-MOVED 12539 :{}\r\n", second_port);
+    let first_server = thread::spawn(move || serve(first.accept().unwrap().0, &[&moved]));
+    let second_server = thread::spawn(move || serve(second.accept().unwrap().0, &["+OK\r\n", "$1\r\n1\r\n"]));
+    let mut cluster = ClusterConnection::connect(&url).unwrap();
+    assert_eq!(cluster.send(&["SET".to_string(), "k".to_string(), "1".to_string()]).unwrap(), Reply::Status("OK".to_string()));
+    assert_eq!(cluster.current_node(), ("127.0.0.1", second_port));
+    assert_eq!(cluster.send(&["GET".to_string(), "k".to_string()]).unwrap(), Reply::bulk("1"));
+    assert!(first_server.join().unwrap().contains("SET"));
+    assert_eq!(second_server.join().unwrap().matches("*").count(), 2);
+
+    for url in ["redis+unix://relative.sock", "redis-cluster://", "redis://host:port", "http://host"] {
+        assert!(matches!(executor::connect(url, None), Err(ExecutionError::Protocol(_))), "{}", url);
+    }
+    #[cfg(not(feature = "tls"))]
+    assert!(matches!(executor::connect("rediss://localhost", None), Err(ExecutionError::Protocol(_))));
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn test_tls_connections() {
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use rustls::pki_types::CertificateDer;
+    use rustls::server::WebPkiClientVerifier;
+    use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+    use sql_redis::executor::TlsOptions;
+
+    let fixture = |name: &str| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name);
+    let certs = |name: &str| -> Vec<CertificateDer<'static>> {
+        rustls_pemfile::certs(&mut BufReader::new(File::open(fixture(name)).unwrap())).collect::<Result<_, _>>().unwrap()
+    };
+    // A server that only accepts clients with a certificate from the test CA
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(certs("ca.pem"));
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build().unwrap();
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(fixture("server.key")).unwrap())).unwrap().unwrap();
+    let config = Arc::new(ServerConfig::builder().with_client_cert_verifier(verifier).with_single_cert(certs("server.pem"), key).unwrap());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("rediss://localhost:{}/1", listener.local_addr().unwrap().port());
+    let server_config = config.clone();
+    let server = thread::spawn(move || {
+        let (socket, _) = listener.accept().unwrap();
+        serve(StreamOwned::new(ServerConnection::new(server_config).unwrap(), socket), &["+OK\r\n", "+PONG\r\n"])
+    });
+    let options = TlsOptions { ca_file: Some(fixture("ca.pem")), cert_file: Some(fixture("client.pem")), key_file: Some(fixture("client.key")) };
+    let mut executor = Executor::new(executor::connect_tls(&url, Some(Duration::from_secs(5)), &options).unwrap());
+    let reply = executor.execute(&RedisCommand::new("PING", Vec::<String>::new())).unwrap();
+    assert_eq!(reply, Reply::Status("PONG".to_string()));
+    assert!(server.join().unwrap().starts_with("*2\r\n$6\r\nSELECT\r\n$1\r\n1\r\n"));
+
+    // Without the test CA the server's certificate isn't trusted, and the handshake fails
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("rediss://localhost:{}", listener.local_addr().unwrap().port());
+    let server = thread::spawn(move || {
+        let (socket, _) = listener.accept().unwrap();
+        let mut stream = StreamOwned::new(ServerConnection::new(config).unwrap(), socket);
+        let _ = stream.read(&mut [0u8; 16]);
+    });
+    assert!(matches!(executor::connect(&url, None), Err(ExecutionError::Io(_))));
+    server.join().unwrap();
+
+    // A client certificate needs its key, and rediss:// options don't apply to other URLs
+    let options = TlsOptions { key_file: None, ..options };
+    assert!(matches!(executor::connect_tls(&url, None, &options), Err(ExecutionError::Protocol(_))));
+    assert!(matches!(executor::connect_tls("redis://localhost", None, &TlsOptions::default()), Err(ExecutionError::Protocol(_))));
+}
+
+#[test]
+fn test_diff_commands() {
+    // EXISTS u:1, HGET u:1 name, HGET u:1 email, then LRANGE l 0 -1, then GET s
+    let replies = ":1\r\n$3\r\nAnn\r\n$-1\r\n*3\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n$-1\r\n";
+    let mut stream = MockStream::new(replies);
+    {
+        let mut executor = Executor::new(RespConnection::from_stream(&mut stream));
+        
+        let changes = diff_command(&mut executor, &RedisCommand::new("HSET", vec!["u:1", "name", "Bob", "email", "b@example.com"])).unwrap();
+        assert_eq!(changes, vec![
+            Change::SetField { key: "u:1".to_string(), field: "name".to_string(), old: Some("Ann".to_string()), new: "Bob".to_string() },
+            Change::SetField { key: "u:1".to_string(), field: "email".to_string(), old: None, new: "b@example.com".to_string() },
+        ]);
+        assert_eq!(changes[0].to_string(), "~ u:1.name: \"Ann\" -> \"Bob\"");
+        
+        // LTRIM l 0 0 keeps only the first element
+        let changes = diff_command(&mut executor, &RedisCommand::new("LTRIM", vec!["l", "0", "0"])).unwrap();
+        assert_eq!(changes, vec![
+            Change::RemoveMember { key: "l".to_string(), member: "b".to_string() },
+            Change::RemoveMember { key: "l".to_string(), member: "c".to_string() },
+        ]);
+        
+        let changes = diff_command(&mut executor, &RedisCommand::new("SET", vec!["s", "v"])).unwrap();
+        assert_eq!(changes, vec![Change::NewKey { key: "s".to_string() }]);
+        
+        // Reads change nothing and send nothing; scripts can't be previewed
+        assert!(diff_command(&mut executor, &RedisCommand::new("GET", vec!["s"])).unwrap().is_empty());
+        let changes = diff_command(&mut executor, &RedisCommand::new("EVAL", vec!["return 1", "0"])).unwrap();
+        assert!(matches!(changes.as_slice(), [Change::Unknown { .. }]));
+    }
+    // Only read commands were sent
+    let sent = String::from_utf8(stream.output).unwrap();
+    assert!(!sent.contains("HSET") && !sent.contains("LTRIM") && sent.contains("LRANGE"));
+}
+
+#[test]
+fn test_browse_keys() {
+    // Two SCAN pages (user:2 returned twice), then TYPE of the first 3 keys, then their reads
+    let replies = concat!(
+        "*2\r\n$2\r\n17\r\n*2\r\n$6\r\nuser:1\r\n$6\r\nuser:2\r\n",
+        "*2\r\n$1\r\n0\r\n*3\r\n$6\r\nuser:2\r\n$6\r\nuser:3\r\n$6\r\nuser:9\r\n",
+        "+hash\r\n+string\r\n+none\r\n",
+        "*4\r\n$4\r\nname\r\n$3\r\nAnn\r\n$3\r\nage\r\n$2\r\n30\r\n$1\r\nx\r\n",
+    );
+    let mut stream = MockStream::new(replies);
+    let scan = RedisCommand::parse("SCAN 0 MATCH user:* COUNT 3").unwrap();
+    assert!(executor::is_browse_command(&scan));
+    let reply = {
+        let mut executor = Executor::new(RespConnection::from_stream(&mut stream));
+        executor::browse_keys(&mut executor, &scan).unwrap()
+    };
+    
+    // Test LIMIT-many distinct keys are kept, and a key deleted since the SCAN is left out
+    let rows = decode_browsed_keys(&reply);
+    let row = |key: &str, kind: &str, value: &str| vec![
+        ("key".to_string(), key.to_string()),
+        ("type".to_string(), kind.to_string()),
+        ("value".to_string(), value.to_string()),
+    ];
+    assert_eq!(rows, vec![row("user:1", "hash", r#"{"name":"Ann","age":"30"}"#), row("user:2", "string", "x")]);
+    let sent = String::from_utf8(stream.output).unwrap();
+    assert!(sent.contains("$4\r\nSCAN\r\n$2\r\n17\r\n"));
+    assert!(!sent.contains("user:9") && sent.contains("HGETALL") && !sent.contains("$4\r\nTYPE\r\n$6\r\nuser:9"));
+    
+    // Test other value types render as JSON, and a plain SCAN page decodes into key rows
+    let browsed = Reply::Array(vec![
+        Reply::Array(vec![Reply::bulk("l"), Reply::bulk("list"), Reply::Array(vec![Reply::bulk("a \"b\"")])]),
+        Reply::Array(vec![Reply::bulk("z"), Reply::bulk("zset"), Reply::Array(vec![Reply::bulk("bob"), Reply::bulk("5")])]),
+        Reply::Array(vec![Reply::bulk("s"), Reply::bulk("stream"), Reply::Nil]),
+    ]);
+    assert_eq!(decode_browsed_keys(&browsed), vec![row("l", "list", r#"["a \"b\""]"#), row("z", "zset", r#"{"bob":"5"}"#), row("s", "stream", "")]);
+    let page = Reply::Array(vec![Reply::bulk("0"), Reply::Array(vec![Reply::bulk("k")])]);
+    assert_eq!(decode_browsed_keys(&page), vec![vec![("key".to_string(), "k".to_string())]]);
+}
+
+#[test]
+fn test_streamed_key_count() {
+    // Two SCAN pages, user:2 returned by both
+    let replies = concat!(
+        "*2\r\n$2\r\n42\r\n*2\r\n$6\r\nuser:1\r\n$6\r\nuser:2\r\n",
+        "*2\r\n$1\r\n0\r\n*2\r\n$6\r\nuser:2\r\n$6\r\nuser:3\r\n",
+    );
+    let mut stream = MockStream::new(replies);
+    let count = RedisCommand::parse_all(&sql_redis::lua::keys_count("user:*")).remove(0);
+    assert!(executor::is_count_command(&count) && !executor::is_browse_command(&count));
+    {
+        let mut executor = Executor::new(RespConnection::from_stream(&mut stream));
+        assert_eq!(executor::count_keys(&mut executor, &count).unwrap(), Reply::Integer(3));
+    }
+    
+    // Test the script itself never runs: the pages are SCANned from the client
+    let sent = String::from_utf8(stream.output).unwrap();
+    assert!(!sent.contains("EVAL") && sent.contains("$6\r\nuser:*\r\n$5\r\nCOUNT\r\n$4\r\n1000\r\n"));
+    assert!(sent.contains("$4\r\nSCAN\r\n$2\r\n42\r\n"));
+}
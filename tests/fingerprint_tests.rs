@@ -0,0 +1,34 @@
+// tests/fingerprint_tests.rs
+use sql_redis::fingerprint::{fingerprint, fingerprint_id};
+
+#[test]
+fn test_fingerprint() {
+    // Test literals, whitespace, comments, and case don't change the shape
+    let shape = "SELECT * FROM users__hash WHERE KEY = ?";
+    assert_eq!(fingerprint("SELECT * FROM users__hash WHERE key = 'user:1'"), shape);
+    assert_eq!(fingerprint("select *\n  from Users__hash where key='user:2'; -- hot path"), shape);
+    assert_eq!(fingerprint_id("SELECT * FROM users__hash WHERE key = 'user:1'"), fingerprint_id("select * from users__hash where key = 'x'"));
+    assert_eq!(fingerprint_id(shape).len(), 16);
+
+    // Test signed numbers are literals, but binary operators stay
+    assert_eq!(
+        fingerprint("SELECT * FROM lb__zset WHERE key = 'lb' AND score BETWEEN -5 AND 10"),
+        "SELECT * FROM lb__zset WHERE KEY = ? AND score BETWEEN ? AND ?"
+    );
+    assert_eq!(fingerprint("UPDATE t__hash SET n = n - 1 WHERE key = $1"), "UPDATE t__hash SET n = n - ? WHERE KEY = ?");
+
+    // Test lists of literals and rows collapse regardless of length
+    assert_eq!(
+        fingerprint("DELETE FROM s__set WHERE key = 'a' AND member IN ('x', 'y', 'z')"),
+        fingerprint("DELETE FROM s__set WHERE key = 'b' AND member IN ('x')")
+    );
+    assert_eq!(
+        fingerprint("INSERT INTO cache (key, value) VALUES ('a', 'b'), ('c', 'd')"),
+        "INSERT INTO CACHE (KEY, VALUE) VALUES (?)"
+    );
+    assert_eq!(fingerprint("SELECT COUNT(*) FROM t__set WHERE key = 's'"), "SELECT COUNT(*) FROM t__set WHERE KEY = ?");
+
+    // Test different shapes stay apart, and quoted identifiers keep their case
+    assert_ne!(fingerprint("SELECT name FROM u__hash WHERE key = 'a'"), fingerprint("SELECT age FROM u__hash WHERE key = 'a'"));
+    assert_eq!(fingerprint("SELECT * FROM \"Users\" WHERE key = 'x'"), "SELECT * FROM \"Users\" WHERE KEY = ?");
+}
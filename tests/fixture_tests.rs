@@ -0,0 +1,109 @@
+// tests/fixture_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::fixtures::{check_fixtures, concretize_sql, fixtures, matches_pattern};
+use sql_redis::matrix::{MatrixFormat, CONSTRUCTS};
+use sql_redis::options::Profile;
+use sql_redis::{RedisDataType, RuleKind, SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_rule_metadata_fixtures() {
+    let options = TransformOptions::default().with_profile(Profile::Admin).with_lua_fallback();
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    
+    // Every registered rule gets a fixture, and each translates as its metadata documents
+    assert_eq!(fixtures(&transformer).len(), transformer.get_pattern_details().len());
+    let failures: Vec<String> = check_fixtures(&transformer).iter().map(ToString::to_string).collect();
+    assert!(failures.is_empty(), "rule metadata drifted from behavior:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_fixture_synthesis() {
+    // Test placeholders are filled in outside string literals only
+    assert_eq!(concretize_sql("SELECT * FROM table WHERE key = 'n' LIMIT n"), "SELECT * FROM items WHERE key = 'n' LIMIT 10");
+    assert_eq!(concretize_sql("SELECT * FROM t__zset WHERE key = 'k' AND score BETWEEN n AND m"), "SELECT * FROM t__zset WHERE key = 'k' AND score BETWEEN 10 AND 20");
+    
+    // Test Redis patterns: placeholders, optional parts, repetition, wildcards, and notes
+    let command = |line: &str| vec![RedisCommand::parse(line).unwrap()];
+    assert!(matches_pattern("LRANGE value 0 n-1", &command("LRANGE value 0 9")));
+    assert!(matches_pattern("ZRANGEBYSCORE value (n +inf", &command("ZRANGEBYSCORE value (10 +inf")));
+    assert!(matches_pattern("ZPOPMIN key [n]", &command("ZPOPMIN key")));
+    assert!(matches_pattern("ZPOPMIN key [n]", &command("ZPOPMIN key 10")));
+    assert!(matches_pattern("SADD key member [member ...]", &command("SADD key member")));
+    assert!(matches_pattern("HMGET value field1 field2...", &command("HMGET value field1 field2")));
+    assert!(matches_pattern("EVAL '<lua: HGET + HSET>' 1 key", &command("EVAL \"return 1\" 1 key")));
+    assert!(matches_pattern("ZREMRANGEBYSCORE key -inf (<now>", &command("ZREMRANGEBYSCORE key -inf (1700000000")));
+    assert!(matches_pattern("SINTERSTORE d a b (SUNIONSTORE for UNION)", &command("SINTERSTORE d a b")));
+    assert!(matches_pattern("CONFIG SET x y; PSUBSCRIBE p", &[RedisCommand::new("CONFIG", vec!["SET", "x", "y"]), RedisCommand::new("PSUBSCRIBE", vec!["p"])]));
+    
+    // Test drift is caught: other arguments, extra arguments, other commands
+    assert!(!matches_pattern("EVAL '<lua>' 1 key", &command("EVAL \"return 1\" 1 value")));
+    assert!(!matches_pattern("HGET value field", &command("HGET value field extra")));
+    assert!(!matches_pattern("ZPOPMIN key [n]", &command("ZPOPMAX key")));
+    assert!(!matches_pattern("SET k v; GET k", &command("SET k v")));
+}
+
+#[test]
+fn test_rule_kind_metadata() {
+    let options = TransformOptions::default().with_profile(Profile::Admin);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    let details = transformer.get_pattern_details();
+    
+    // Every built-in rule records the kind of statement its SQL pattern is
+    for pattern in &details {
+        let kind = pattern.kind.unwrap_or_else(|| panic!("{} has no kind", pattern.name));
+        let keyword = match kind {
+            RuleKind::Trigger => "CREATE TRIGGER",
+            RuleKind::Session => "USE",
+            kind => kind.name(),
+        };
+        assert!(pattern.sql_pattern.to_uppercase().starts_with(&keyword.to_uppercase()), "{} is not a {:?} pattern", pattern.name, kind);
+    }
+    
+    // Test data types agree with the table suffix the pattern writes to or reads from
+    let data_type = |name: &str| details.iter().find(|p| p.name == name).and_then(|p| p.data_type);
+    assert_eq!(data_type("string_set"), Some(RedisDataType::String));
+    assert_eq!(data_type("hash_delete_field"), Some(RedisDataType::Hash));
+    assert_eq!(data_type("type_copy"), Some(RedisDataType::List));
+    assert_eq!(data_type("zset_top_per_group"), Some(RedisDataType::SortedSet));
+    assert_eq!(data_type("object_freq"), None);
+    assert_eq!(data_type("admin_info"), None);
+    
+    // Test filtering by kind and type, as `list-patterns --kind select --type zset` does
+    let zset_selects: Vec<&str> = details.iter()
+        .filter(|p| p.kind == Some(RuleKind::Select) && p.data_type == Some(RedisDataType::SortedSet))
+        .map(|p| p.name.as_str())
+        .collect();
+    assert!(zset_selects.contains(&"zset_getall"));
+    assert!(zset_selects.iter().all(|name| name.starts_with("zset_")));
+    
+    // Test CLI names round-trip
+    assert_eq!(RuleKind::from_name("SELECT"), Some(RuleKind::Select));
+    assert_eq!(RedisDataType::from_name("zset"), Some(RedisDataType::SortedSet));
+    assert_eq!(RedisDataType::from_name("sortedset"), None);
+}
+
+#[test]
+fn test_compatibility_matrix() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let matrix = transformer.compatibility_matrix();
+    
+    // Test cells follow the registered rules
+    assert!(matrix.is_supported("ORDER BY", RedisDataType::SortedSet));
+    assert!(!matrix.is_supported("ORDER BY", RedisDataType::Hash));
+    assert_eq!(matrix.rules("DELETE ... RETURNING", RedisDataType::SortedSet), ["zset_pop_min", "zset_pop_max"]);
+    assert!(matrix.rules("UPDATE ... SET CASE", RedisDataType::Hash).contains(&"hash_case_update".to_string()));
+    assert!(matrix.rules("no such construct", RedisDataType::Hash).is_empty());
+    
+    // Test markdown: a header, a separator, and one row per construct
+    let markdown = matrix.render(MatrixFormat::Markdown);
+    assert!(markdown.starts_with("| Construct | Kind | string | hash | list | set | zset |\n|---|"));
+    assert_eq!(markdown.lines().count(), 2 + CONSTRUCTS.len());
+    assert!(markdown.contains("| ORDER BY | select | no | no | no | no | yes |"));
+    
+    // Test CSV: one line per construct and data type
+    let csv = matrix.render(MatrixFormat::Csv);
+    assert_eq!(csv.lines().count(), 1 + CONSTRUCTS.len() * 5);
+    assert!(csv.contains("\"DELETE ... RETURNING\",delete,zset,true,zset_pop_min;zset_pop_max\n"));
+    assert!(csv.contains("\"ORDER BY\",select,hash,false,\n"));
+    assert_eq!(MatrixFormat::from_name("CSV"), Some(MatrixFormat::Csv));
+}
@@ -1,13 +1,7 @@
 // tests/hash_ops_tests.rs
+use sql_redis::reply::Reply;
 use sql_redis::SqlToRedisTransformer;
 
-fn normalize_hset(s: &str) -> String {
-    let mut parts: Vec<&str> = s.split_whitespace().collect();
-    parts.sort(); // Sort lexicographically
-    parts.join(" ")
-}
-
-
 #[test]
 fn test_hash_operations() {
     let transformer = SqlToRedisTransformer::new().unwrap();
@@ -26,15 +20,18 @@ fn test_hash_operations() {
     
     // Test hash HSET
     let result = transformer.transform("INSERT INTO users__hash (key, name, email) VALUES ('user:1001', 'John Doe', 'john@example.com')").unwrap();
-    assert_eq!(
-        normalize_hset(&result),
-        normalize_hset(&"HSET user:1001 name John Doe email john@example.com")
-    );
+    assert_eq!(result, "HSET user:1001 name John Doe email john@example.com");
     
     // Test hash update
     let result = transformer.transform("UPDATE users__hash SET name = 'Jane Doe' WHERE key = 'user:1001'").unwrap();
     assert_eq!(result, "HSET user:1001 name Jane Doe");
     
+    // Test field order follows the SQL, whatever the column order
+    let result = transformer.transform("INSERT INTO users__hash (email, key, name) VALUES ('j@example.com', 'user:1002', 'Jo')").unwrap();
+    assert_eq!(result, "HSET user:1002 email j@example.com name Jo");
+    let result = transformer.transform("UPDATE users__hash SET email = 'x@example.com', age = '31', name = 'X' WHERE key = 'user:1002'").unwrap();
+    assert_eq!(result, "HSET user:1002 email x@example.com age 31 name X");
+    
     // Test hash delete
     let result = transformer.transform("DELETE FROM users__hash WHERE key = 'user:1001'").unwrap();
     assert_eq!(result, "DEL user:1001");
@@ -42,6 +39,195 @@ fn test_hash_operations() {
     // Test hash field delete
     let result = transformer.transform("DELETE FROM users__hash WHERE key = 'user:1001' AND field = 'temporary_token'").unwrap();
     assert_eq!(result, "HDEL user:1001 temporary_token");
+    
+    // Test multi-field delete in a single HDEL
+    let result = transformer.transform("DELETE FROM users__hash WHERE key = 'user:1001' AND field IN ('a', 'b', 'c')").unwrap();
+    assert_eq!(result, "HDEL user:1001 a b c");
+
+}
+
+#[test]
+fn test_hash_getall_projection() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let reply = Reply::Array(vec![
+        Reply::bulk("name"), Reply::bulk("Ann"),
+        Reply::bulk("email"), Reply::bulk("a@example.com"),
+        Reply::bulk("age"), Reply::bulk("29"),
+    ]);
+    
+    // Test wildcard HGETALL rows keep every field
+    let rows = transformer.decode_reply("SELECT * FROM users__hash WHERE key = 'u:1'", &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("name".to_string(), "Ann".to_string()),
+        ("email".to_string(), "a@example.com".to_string()),
+        ("age".to_string(), "29".to_string()),
+    ]]);
+    
+    // Test missing keys decode to no rows
+    let sql = "SELECT * FROM users__hash WHERE key = 'u:1'";
+    let rows = transformer.decode_reply(sql, &Reply::Array(vec![])).unwrap().unwrap();
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn test_aliased_fields() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test aliased fields are read with HMGET/HGET and the rows named by their aliases
+    let sql = "SELECT age, name AS n, email AS e FROM users__hash WHERE key = 'u:1'";
+    assert_eq!(transformer.transform(sql).unwrap(), "HMGET u:1 age name email");
+    let reply = Reply::Array(vec![Reply::bulk("29"), Reply::bulk("Ann"), Reply::Nil]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("age".to_string(), "29".to_string()),
+        ("n".to_string(), "Ann".to_string()),
+    ]]);
+    let sql = "SELECT u.name AS n FROM users__hash u WHERE u.key = 'u:1'";
+    assert_eq!(transformer.transform(sql).unwrap(), "HGET u:1 name");
+    let rows = transformer.decode_reply(sql, &Reply::bulk("Ann")).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("n".to_string(), "Ann".to_string())]]);
+    
+    // Test unaliased fields keep their names, and missing keys decode to no rows
+    let sql = "SELECT name, email FROM users__hash WHERE key = 'u:1'";
+    let rows = transformer.decode_reply(sql, &Reply::Array(vec![Reply::Nil, Reply::bulk("a@x")])).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("email".to_string(), "a@x".to_string())]]);
+    let rows = transformer.decode_reply(sql, &Reply::Array(vec![Reply::Nil, Reply::Nil])).unwrap().unwrap();
+    assert!(rows.is_empty());
+}
 
+#[test]
+fn test_hash_scan_page() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "SELECT * FROM users__hash WHERE key = 'u:1' LIMIT 2";
+    assert_eq!(transformer.transform(sql).unwrap(), "HSCAN u:1 0 COUNT 2");
+    
+    // Test the page's field/value pairs decode like HGETALL, past the cursor
+    let reply = Reply::Array(vec![
+        Reply::bulk("17"),
+        Reply::Array(vec![
+            Reply::bulk("name"), Reply::bulk("Ann"),
+            Reply::bulk("age"), Reply::bulk("29"),
+        ]),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("name".to_string(), "Ann".to_string()),
+        ("age".to_string(), "29".to_string()),
+    ]]);
 }
 
+#[test]
+fn test_case_expressions() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test a CASE assignment compiles into a Lua conditional; names and literals go in ARGV
+    let command = transformer.transform_to_command("UPDATE flags__hash SET status = CASE WHEN tier = 'gold' AND score >= 100 THEN 'vip' ELSE 'std' END, seen = '1' WHERE key = 'u:1'").unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert_eq!(command.args[1..], ["1", "u:1", "status", "seen", "tier", "gold", "score", "100", "vip", "std", "1"]);
+    let script = &command.args[0];
+    assert!(script.contains("if (compare(field(ARGV[3]), '=', ARGV[4]) and compare(field(ARGV[5]), '>=', ARGV[6])) then\n    values[1] = ARGV[7]\nelse\n    values[1] = ARGV[8]\nend\nvalues[2] = ARGV[9]\n"));
+    assert!(script.ends_with("for i = 1, 2 do\n    if values[i] == nil then\n        redis.call('HDEL', KEYS[1], ARGV[i])\n    else\n        redis.call('HSET', KEYS[1], ARGV[i], values[i])\n    end\nend\nreturn 2\n"));
+    
+    // Test simple CASE compares the operand; a missing ELSE leaves the result NULL
+    let command = transformer.transform_to_command("UPDATE flags__hash f SET f.label = CASE f.tier WHEN 'gold' THEN 'vip' END WHERE f.key = 'u:1'").unwrap();
+    assert_eq!(command.args[1..], ["1", "u:1", "label", "tier", "gold", "vip"]);
+    assert!(command.args[0].contains("if compare(field(ARGV[2]), '=', ARGV[3]) then\n    values[1] = ARGV[4]\nend\n"));
+    
+    // Test plain updates keep HSET and unsupported CASE branches aren't translated
+    assert_eq!(transformer.transform("UPDATE flags__hash SET status = 'x' WHERE key = 'u:1'").unwrap(), "HSET u:1 status x");
+    assert!(transformer.transform("UPDATE flags__hash SET status = CASE WHEN tier ILIKE 'g%' THEN 'vip' END WHERE key = 'u:1'").is_err());
+    
+    // Test LIKE conditions compile to an anchored Lua pattern of the LIKE glob
+    let command = transformer.transform_to_command("UPDATE flags__hash SET status = CASE WHEN tier LIKE 'g_ld%' THEN 'vip' END WHERE key = 'u:1'").unwrap();
+    assert_eq!(command.args[1..], ["1", "u:1", "status", "tier", "^g.ld.*$", "vip"]);
+    assert!(command.args[0].contains("if like(field(ARGV[2]), ARGV[3], false) then\n"));
+    
+    // Test CASE projections read the hash and are evaluated over the reply
+    let sql = "SELECT name, CASE WHEN age >= 30 THEN 'senior' WHEN age IS NULL THEN 'unknown' ELSE 'junior' END AS band, CASE name WHEN 'Bob' THEN 'b' END, CASE WHEN name NOT LIKE 'A_n%' THEN 'not-ann' END AS other FROM users__hash WHERE key = 'u:1'";
+    assert_eq!(transformer.transform(sql).unwrap(), "HGETALL u:1");
+    let reply = Reply::Array(vec![
+        Reply::bulk("name"), Reply::bulk("Ann"),
+        Reply::bulk("age"), Reply::bulk("9"),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("name".to_string(), "Ann".to_string()),
+        ("band".to_string(), "junior".to_string()),
+    ]]);
+    let reply = Reply::Array(vec![Reply::bulk("name"), Reply::bulk("Bob")]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("name".to_string(), "Bob".to_string()),
+        ("band".to_string(), "unknown".to_string()),
+        ("case".to_string(), "b".to_string()),
+        ("other".to_string(), "not-ann".to_string()),
+    ]]);
+}
+
+#[test]
+fn test_string_functions() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test string functions of literals fold before matching, in values and keys alike
+    assert_eq!(transformer.transform("INSERT INTO users__hash (key, name) VALUES (LOWER('U:1'), UPPER(TRIM('  ann ')))").unwrap(), "HSET u:1 name ANN");
+    assert_eq!(transformer.transform("UPDATE users__hash SET slug = REPLACE('a-b-c', '-', '_') WHERE key = 'u:1'").unwrap(), "HSET u:1 slug a_b_c");
+    assert_eq!(transformer.transform("SELECT * FROM users__hash WHERE key = REPLACE('u-1', '-', ':')").unwrap(), "HGETALL u:1");
+    
+    // Test functions of stored fields compile into Lua string calls
+    let command = transformer.transform_to_command("UPDATE users__hash SET name = UPPER(name), slug = REPLACE(LOWER(name), '.', '_') WHERE key = 'u:1'").unwrap();
+    assert_eq!(command.args[1..], ["1", "u:1", "name", "slug", "name", "name", ".", "_"]);
+    assert!(command.args[0].contains("values[1] = call(string.upper, field(ARGV[3]))\nvalues[2] = call(replace, call(string.lower, field(ARGV[4])), ARGV[5], ARGV[6])\n"));
+    
+    // Test CASE conditions may apply functions too
+    let command = transformer.transform_to_command("UPDATE users__hash SET vip = CASE WHEN TRIM(tier) = 'gold' THEN '1' ELSE '0' END WHERE key = 'u:1'").unwrap();
+    assert!(command.args[0].contains("if compare(call(trim, field(ARGV[2])), '=', ARGV[3]) then"));
+    
+    // Test projected functions are evaluated over the fetched hash; NULL arguments give NULL
+    let sql = "SELECT UPPER(name), REPLACE(email, '@', '.at.') AS e, LOWER(nickname) FROM users__hash WHERE key = 'u:1'";
+    assert_eq!(transformer.transform(sql).unwrap(), "HGETALL u:1");
+    let reply = Reply::Array(vec![
+        Reply::bulk("name"), Reply::bulk(" Ann "),
+        Reply::bulk("email"), Reply::bulk("a@example.com"),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("upper".to_string(), " ANN ".to_string()),
+        ("e".to_string(), "a.at.example.com".to_string()),
+    ]]);
+    
+    // Test other functions and arities aren't translated
+    assert!(transformer.transform("UPDATE users__hash SET name = SUBSTR(name, 1, 2) WHERE key = 'u:1'").is_err());
+    assert!(transformer.transform("SELECT REPLACE(name, 'a') FROM users__hash WHERE key = 'u:1'").is_err());
+}
+
+#[test]
+fn test_coalesce() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test COALESCE of literals folds to the first non-NULL one
+    assert_eq!(transformer.transform("INSERT INTO users__hash (key, name) VALUES ('u:1', COALESCE(NULL, 'anon', 'x'))").unwrap(), "HSET u:1 name anon");
+    
+    // Test projected COALESCE reads the fields once with HMGET and falls back in the script
+    let sql = "SELECT name, COALESCE(nickname, UPPER(name), 'anon') AS display FROM users__hash WHERE key = 'u:1'";
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.args[1..], ["1", "u:1", "name", "display", "name", "nickname", "name", "nickname", "name", "anon"]);
+    let script = &command.args[0];
+    assert!(script.contains("local read = redis.call('HMGET', KEYS[1], unpack(ARGV, 3, 4))\n"));
+    assert!(script.contains("values[1] = field(ARGV[5])\nvalues[2] = coalesce(field(ARGV[6]), call(string.upper, field(ARGV[7])), ARGV[8])\n"));
+    assert!(script.ends_with("return result\n"));
+    
+    // Test the script's name/value pairs decode as the projected row
+    let reply = Reply::Array(vec![
+        Reply::bulk("name"), Reply::bulk("Ann"),
+        Reply::bulk("display"), Reply::bulk("ANN"),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("name".to_string(), "Ann".to_string()),
+        ("display".to_string(), "ANN".to_string()),
+    ]]);
+    
+    // Test COALESCE in SET falls back to stored values
+    let command = transformer.transform_to_command("UPDATE users__hash SET nickname = COALESCE(nickname, name) WHERE key = 'u:1'").unwrap();
+    assert!(command.args[0].contains("values[1] = coalesce(field(ARGV[2]), field(ARGV[3]))\n"));
+}
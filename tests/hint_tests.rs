@@ -0,0 +1,50 @@
+// tests/hint_tests.rs
+use sql_redis::{ErrorCode, Hints, SqlToRedisTransformer};
+
+#[test]
+fn test_hint_parsing() {
+    let hints = Hints::parse("SELECT /*+ use_scan, RULE(zset_get_reversed) */ * FROM t /*+ TTL(60) */").unwrap();
+    assert!(hints.use_scan);
+    assert_eq!(hints.rule.as_deref(), Some("zset_get_reversed"));
+    assert_eq!(hints.ttl, Some(60));
+    assert!(Hints::parse("SELECT * FROM t /* not a hint */").unwrap().is_empty());
+    
+    for sql in ["/*+ FOO */ SELECT 1", "/*+ TTL(0) */ SELECT 1", "/*+ TTL(soon) */ SELECT 1", "/*+ RULE */ SELECT 1", "/*+ USE_SCAN"] {
+        assert_eq!(Hints::parse(sql).unwrap_err().code(), ErrorCode::InvalidHint, "{}", sql);
+    }
+}
+
+#[test]
+fn test_rule_hints() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Both zset rules match; the hint picks the reversed read over the score range
+    let sql = "SELECT * FROM lb__zset WHERE key = 'lb' AND score > 10 ORDER BY score DESC";
+    assert_eq!(transformer.transform(sql).unwrap(), "ZRANGEBYSCORE lb (10 +inf");
+    let hinted = "SELECT /*+ RULE(zset_get_reversed) */ * FROM lb__zset WHERE key = 'lb' AND score > 10 ORDER BY score DESC";
+    assert_eq!(transformer.transform(hinted).unwrap(), "ZREVRANGEBYSCORE lb +inf -inf");
+    assert_eq!(transformer.matched_rule(hinted).unwrap(), "zset_get_reversed");
+    
+    // USE_SCAN turns whole-key reads into their paginated form
+    assert_eq!(transformer.transform("SELECT /*+ USE_SCAN */ * FROM tags__set WHERE key = 'post:1'").unwrap(), "SSCAN post:1 0 COUNT 100");
+    assert_eq!(transformer.transform("SELECT /*+ USE_SCAN */ * FROM users__hash WHERE key = 'user:1'").unwrap(), "HSCAN user:1 0 COUNT 100");
+    
+    // A forced rule that is unknown or doesn't match is an error, not a fallback
+    let err = transformer.transform("/*+ RULE(nope) */ SELECT * FROM cache WHERE key = 'a'").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid hint: RULE(nope): no such rule");
+    let err = transformer.transform("/*+ RULE(zset_get_reversed) */ SELECT * FROM cache WHERE key = 'a'").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidHint);
+    assert_eq!(err.code().as_str(), "E0010");
+}
+
+#[test]
+fn test_ttl_hints() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    let commands = transformer.transform_to_commands("/*+ TTL(3600) */ INSERT INTO users__hash (key, name, age) VALUES ('user:1', 'A', 3)").unwrap();
+    assert_eq!(commands.last().unwrap().to_string(), "EXPIRE user:1 3600");
+    assert_eq!(transformer.transform("INSERT /*+ TTL(60) */ INTO cache (key, value) VALUES ('a', '1')").unwrap(), "SET a 1\nEXPIRE a 60");
+    
+    let err = transformer.transform("/*+ TTL(60) */ SELECT * FROM cache WHERE key = 'a'").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidHint);
+}
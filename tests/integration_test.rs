@@ -1,23 +1,13 @@
 // tests/integration_test.rs
 use sql_redis::SqlToRedisTransformer;
 
-
-fn normalize_hset(s: &str) -> String {
-    let mut parts: Vec<&str> = s.split_whitespace().collect();
-    parts.sort(); // Sort lexicographically
-    parts.join(" ")
-}
-
 #[test]
 fn test_full_sql_redis_workflow() {
     let transformer = SqlToRedisTransformer::new().unwrap();
     
     // Create a new user
     let result = transformer.transform("INSERT INTO users__hash (key, name, email, age) VALUES ('user:1001', 'John Doe', 'john@example.com', '30')").unwrap();
-    assert_eq!(
-        normalize_hset(&result),
-        normalize_hset(&"HSET user:1001 name John Doe email john@example.com age 30")
-    );
+    assert_eq!(result, "HSET user:1001 name John Doe email john@example.com age 30");
     
     // Get user information
     let result = transformer.transform("SELECT * FROM users__hash WHERE key = 'user:1001'").unwrap();
@@ -54,4 +44,20 @@ fn test_full_sql_redis_workflow() {
     // Delete user
     let result = transformer.transform("DELETE FROM users__hash WHERE key = 'user:1001'").unwrap();
     assert_eq!(result, "DEL user:1001");
-}
\ No newline at end of file
+}
+#[test]
+fn test_table_aliases() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Columns qualified by the alias or the table name resolve to the table's columns
+    assert_eq!(transformer.transform("SELECT * FROM users__hash u WHERE u.key = 'user:1'").unwrap(), "HGETALL user:1");
+    assert_eq!(transformer.transform("SELECT u.* FROM users__hash AS u WHERE u.key = 'user:1'").unwrap(), "HGETALL user:1");
+    assert_eq!(transformer.transform("SELECT u.name FROM users__hash u WHERE u.key = 'user:1'").unwrap(), "HGET user:1 name");
+    assert_eq!(transformer.transform("SELECT * FROM lb__zset z WHERE z.key = 'lb' AND z.score > 10").unwrap(), "ZRANGEBYSCORE lb (10 +inf");
+    assert_eq!(transformer.transform("UPDATE users__hash u SET u.name = 'x' WHERE u.key = 'user:1'").unwrap(), "HSET user:1 name x");
+    assert_eq!(transformer.transform("DELETE FROM users__hash u WHERE u.key = 'user:1'").unwrap(), "DEL user:1");
+    assert_eq!(transformer.transform("DELETE FROM cache WHERE cache.key = 'a'").unwrap(), "DEL a");
+    
+    // A qualifier naming another table is not this table's column
+    assert!(transformer.transform("SELECT * FROM users__hash u WHERE x.key = 'user:1'").is_err());
+}
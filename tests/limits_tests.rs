@@ -0,0 +1,56 @@
+// tests/limits_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::limits::CommandLimits;
+use sql_redis::{ErrorCode, SqlToRedisTransformer, TransformOptions, WarningKind};
+
+fn limited(options: TransformOptions) -> SqlToRedisTransformer {
+    SqlToRedisTransformer::with_options(options).unwrap()
+}
+
+#[test]
+fn test_multi_row_insert_splits_by_argument_count() {
+    let transformer = limited(TransformOptions::default().with_max_command_args(4));
+    let sql = "INSERT INTO tags__set (key, member) VALUES ('post:1', 'a'), ('post:1', 'b'), ('post:1', 'c'), ('post:1', 'd'), ('post:1', 'e')";
+    
+    let commands = transformer.transform_to_commands(sql).unwrap();
+    let lines: Vec<String> = commands.iter().map(RedisCommand::to_string).collect();
+    assert_eq!(lines, vec!["SADD post:1 a b c", "SADD post:1 d e"]);
+    
+    let (output, warnings) = transformer.transform_with_report(sql).unwrap();
+    assert_eq!(output, "SADD post:1 a b c\nSADD post:1 d e");
+    assert_eq!(warnings.iter().map(|w| w.kind).collect::<Vec<_>>(), vec![WarningKind::SplitCommand]);
+    
+    // Commands within the limits are untouched
+    let (output, warnings) = transformer.transform_with_report("INSERT INTO tags__set (key, member) VALUES ('post:1', 'a')").unwrap();
+    assert_eq!(output, "SADD post:1 a");
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_split_keeps_field_value_pairs_together() {
+    let transformer = limited(TransformOptions::default().with_max_command_bytes(20));
+    let commands = transformer.transform_to_commands("INSERT INTO users__hash (key, name, city) VALUES ('u:1', 'Ann', 'Oslo')").unwrap();
+    assert_eq!(commands, vec![
+        RedisCommand::new("HSET", vec!["u:1", "name", "Ann"]),
+        RedisCommand::new("HSET", vec!["u:1", "city", "Oslo"]),
+    ]);
+    
+    let limits = CommandLimits { max_bytes: None, max_args: Some(4) };
+    let zadd = RedisCommand::new("ZADD", vec!["lb", "NX", "1", "a", "2", "b"]);
+    assert_eq!(limits.split(&zadd).unwrap(), vec![
+        RedisCommand::new("ZADD", vec!["lb", "NX", "1", "a"]),
+        RedisCommand::new("ZADD", vec!["lb", "NX", "2", "b"]),
+    ]);
+}
+
+#[test]
+fn test_unsplittable_commands_are_rejected() {
+    let transformer = limited(TransformOptions::default().with_max_command_bytes(10));
+    let error = transformer.transform("SELECT * FROM users WHERE key = 'a-very-long-key'").unwrap_err();
+    assert_eq!(error.code(), ErrorCode::CommandTooLarge);
+    assert!(error.to_string().contains("over the limit of 10 bytes"), "{}", error);
+    
+    // A single item too large for the limit can't be split either
+    let error = transformer.transform("INSERT INTO tags__set (key, member) VALUES ('k', 'a-very-long-member')").unwrap_err();
+    assert_eq!(error.code(), ErrorCode::CommandTooLarge);
+}
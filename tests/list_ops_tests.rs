@@ -34,3 +34,36 @@ fn test_list_operations() {
     assert_eq!(result, "LREM user:1001:posts 0 spam");
 }
 
+
+#[test]
+fn test_list_trim_operations() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test removing a tail keeps the first N elements
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index > 999").unwrap();
+    assert_eq!(result, "LTRIM app:logs 0 999");
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index >= 1000").unwrap();
+    assert_eq!(result, "LTRIM app:logs 0 999");
+    
+    // Test removing a head keeps the rest
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index < 10").unwrap();
+    assert_eq!(result, "LTRIM app:logs 10 -1");
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index <= 9").unwrap();
+    assert_eq!(result, "LTRIM app:logs 10 -1");
+    
+    // Test the literal may be on the left
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND 999 < index").unwrap();
+    assert_eq!(result, "LTRIM app:logs 0 999");
+    
+    // Test removing every index empties the list
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index >= 0").unwrap();
+    assert_eq!(result, "LTRIM app:logs 1 0");
+    
+    // A single index cannot be removed with LTRIM, and must not delete the whole list
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index = 3");
+    assert!(result.is_err());
+    
+    // A middle range would need two trims; trimming by the first bound alone drops index 10 onward
+    let result = transformer.transform("DELETE FROM logs__list WHERE key = 'app:logs' AND index > 5 AND index < 10");
+    assert!(result.is_err());
+}
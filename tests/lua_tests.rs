@@ -0,0 +1,320 @@
+// tests/lua_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::executor::Executor;
+use sql_redis::functions::FunctionLibrary;
+use sql_redis::lua::{format_script, having_script, minify_script, LuaFormat, MockRedis, VIEW_REFRESH_SCRIPT, ZSET_AVG_SCRIPT, ZSET_SUM_SCRIPT};
+use sql_redis::reply::Reply;
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+fn bulks(items: &[&str]) -> Reply {
+    Reply::Array(items.iter().map(|item| Reply::bulk(*item)).collect())
+}
+
+#[test]
+fn test_mock_redis_calls() {
+    let mut redis = MockRedis::new();
+    redis.call(&["HSET", "user:1", "name", "Ann", "age", "31"]);
+    redis.call(&["HSET", "user:2", "name", "Bob"]);
+    redis.call(&["ZADD", "scores", "10", "ann", "7.5", "bob", "20", "cy"]);
+    redis.call(&["RPUSH", "log", "a", "b", "c"]);
+    redis.clear_calls();
+    
+    // Test the reads the crate's scripts issue answer like Redis
+    assert_eq!(redis.call(&["KEYS", "user:*"]), bulks(&["user:1", "user:2"]));
+    assert_eq!(redis.call(&["HGETALL", "user:1"]), bulks(&["age", "31", "name", "Ann"]));
+    assert_eq!(redis.call(&["HMGET", "user:2", "name", "age"]), Reply::Array(vec![Reply::bulk("Bob"), Reply::Nil]));
+    assert_eq!(redis.call(&["ZRANGEBYSCORE", "scores", "(7.5", "+inf", "WITHSCORES"]), bulks(&["ann", "10", "cy", "20"]));
+    assert_eq!(redis.call(&["ZRANGEBYSCORE", "scores", "-inf", "+inf", "LIMIT", "0", "1"]), bulks(&["bob"]));
+    assert_eq!(redis.call(&["LRANGE", "log", "-2", "-1"]), bulks(&["b", "c"]));
+    assert_eq!(redis.call(&["GET", "missing"]), Reply::Nil);
+    assert_eq!(redis.calls().len(), 7);
+    
+    // Test member scans honor MATCH
+    assert_eq!(redis.call(&["ZSCAN", "scores", "0", "MATCH", "?o*"]), Reply::Array(vec![Reply::bulk("0"), bulks(&["bob", "7.5"])]));
+    redis.call(&["SADD", "tags", "user:1", "user:2", "bot:1"]);
+    assert_eq!(redis.call(&["SSCAN", "tags", "0", "MATCH", "user:*"]), Reply::Array(vec![Reply::bulk("0"), bulks(&["user:1", "user:2"])]));
+    assert_eq!(redis.calls()[0], vec!["KEYS", "user:*"]);
+    
+    // Test errors are the ones redis.call would raise
+    assert!(matches!(redis.call(&["GET", "log"]), Reply::Error(e) if e.starts_with("WRONGTYPE")));
+    assert!(matches!(redis.call(&["HSET", "user:1", "name"]), Reply::Error(e) if e.contains("wrong number")));
+    assert!(matches!(redis.call(&["XADD", "s", "*", "f", "v"]), Reply::Error(e) if e.contains("unknown command")));
+}
+
+#[test]
+fn test_mock_redis_executor() {
+    // Test generated commands can seed the mock through an Executor
+    let mut executor = Executor::new(MockRedis::new());
+    executor.execute(&RedisCommand::new("SADD", vec!["tags", "x", "y"])).unwrap();
+    let reply = executor.execute(&RedisCommand::new("SISMEMBER", vec!["tags", "y"])).unwrap();
+    assert_eq!(reply, Reply::Integer(1));
+}
+
+#[test]
+fn test_mock_redis_writes() {
+    let mut redis = MockRedis::new();
+    redis.call(&["RPUSH", "q", "a", "b", "a", "c", "a"]);
+    assert_eq!(redis.call(&["LREM", "q", "-2", "a"]), Reply::Integer(2));
+    assert_eq!(redis.call(&["LRANGE", "q", "0", "-1"]), bulks(&["a", "b", "c"]));
+    assert_eq!(redis.call(&["LSET", "q", "-1", "z"]), Reply::Status("OK".to_string()));
+    assert_eq!(redis.call(&["LINDEX", "q", "2"]), Reply::bulk("z"));
+    
+    redis.call(&["ZADD", "lb", "1", "a", "2", "b", "3", "c"]);
+    assert_eq!(redis.call(&["ZPOPMAX", "lb"]), bulks(&["c", "3"]));
+    assert_eq!(redis.call(&["ZINCRBY", "lb", "2.5", "a"]), Reply::bulk("3.5"));
+    assert_eq!(redis.call(&["ZREVRANGEBYSCORE", "lb", "+inf", "-inf"]), bulks(&["a", "b"]));
+    assert_eq!(redis.call(&["HINCRBY", "h", "n", "5"]), Reply::Integer(5));
+    
+    // Test emptied collections are deleted, as in Redis
+    redis.call(&["SADD", "s", "x"]);
+    assert_eq!(redis.call(&["SREM", "s", "x"]), Reply::Integer(1));
+    assert_eq!(redis.call(&["EXISTS", "s"]), Reply::Integer(0));
+}
+
+#[test]
+fn test_lua_format() {
+    let messy = "\n  local t = {}  \nfor i = 1, #ARGV do\n        if ARGV[i] ~= '' then\n  t[#t + 1] = ARGV[i] -- keep\n   elseif x then\nreturn 'end  do'\n      else\n\n\n t[#t + 1] = [[a\n  b]]\n end\nend\nreturn cjson.encode(t)\n\n";
+    assert_eq!(format_script(messy), "local t = {}\nfor i = 1, #ARGV do\n    if ARGV[i] ~= '' then\n        t[#t + 1] = ARGV[i] -- keep\n    elseif x then\n        return 'end  do'\n    else\n\n        t[#t + 1] = [[a\n  b]]\n    end\nend\nreturn cjson.encode(t)");
+    assert_eq!(minify_script(messy), "local t = {}\nfor i = 1, #ARGV do\nif ARGV[i] ~= '' then\nt[#t + 1] = ARGV[i]\nelseif x then\nreturn 'end  do'\nelse\nt[#t + 1] = [[a\n  b]]\nend\nend\nreturn cjson.encode(t)");
+    
+    // Test a callback opening two blocks on one line indents once
+    assert_eq!(format_script("pcall(function()\nreturn 1\nend)"), "pcall(function()\n    return 1\nend)");
+    
+    // Test the built-in scripts keep their meaning: formatting is stable and minifies the same
+    for script in [ZSET_AVG_SCRIPT, VIEW_REFRESH_SCRIPT] {
+        let formatted = format_script(script);
+        assert_eq!(format_script(&formatted), formatted);
+        assert_eq!(minify_script(&formatted), minify_script(script));
+        assert!(minify_script(script).len() < script.len());
+    }
+}
+
+#[test]
+fn test_lua_format_option() {
+    let sql = "SELECT AVG(score) FROM lb__zset WHERE key = 'lb'";
+    let options = TransformOptions::default().with_lua_format(LuaFormat::Minify);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.args[0], minify_script(ZSET_AVG_SCRIPT));
+    assert!(transformer.transform(sql).unwrap().starts_with("EVAL 'local members"));
+    
+    // Test minified scripts still map to their library function
+    assert_eq!(FunctionLibrary::new().to_fcall(command).args[0], "zset_avg");
+}
+
+#[test]
+fn test_having_filters() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // The comparison moves into the script; its numbers follow the script's own arguments
+    let command = transformer.transform_to_command("SELECT SUM(score) FROM lb__zset WHERE key = 'g' HAVING SUM(score) > 1000").unwrap();
+    assert_eq!(command.args[1..], ["1", "g", "-inf", "+inf", "1000"]);
+    assert!(command.args[0].starts_with("\nlocal function aggregate()\nlocal members"));
+    assert!(command.args[0].contains("if n == nil or not (n > tonumber(ARGV[#ARGV])) then"));
+    
+    // Reversed operands flip the operator; conjunctions use one argument each
+    let command = transformer.transform_to_command("SELECT AVG(age) FROM users__hash WHERE key = 'u' HAVING 10 <= AVG(age) AND AVG(age) < 65").unwrap();
+    assert_eq!(command.args[1..], ["1", "u", "age", "10", "65"]);
+    assert!(command.args[0].contains("(n >= tonumber(ARGV[#ARGV - 1]) and n < tonumber(ARGV[#ARGV]))"));
+    
+    // Without HAVING the script is unchanged
+    assert_eq!(having_script(ZSET_SUM_SCRIPT, &[]), ZSET_SUM_SCRIPT);
+    
+    // HAVING on anything but the selected aggregate can't be evaluated in the script
+    assert!(transformer.transform("SELECT MAX(value) FROM t__list WHERE key = 'k' HAVING COUNT(*) > 3").is_err());
+    
+    // Strict mode accepts HAVING once the script honours it
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    assert!(strict.transform("SELECT MAX(value) FROM t__list WHERE key = 'k' HAVING MAX(value) >= 0").is_ok());
+}
+
+#[test]
+fn test_lua_fallback() {
+    let default = SqlToRedisTransformer::new().unwrap();
+    let fallback = SqlToRedisTransformer::with_options(TransformOptions::default().with_lua_fallback()).unwrap();
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().with_lua_fallback().strict()).unwrap();
+    
+    // Test predicates no rule honors are dropped by default, but filtered in Lua when enabled
+    let sql = "SELECT name FROM users__hash WHERE key = 'user:1' AND age > 30";
+    assert_eq!(default.matched_rule(sql).unwrap(), "hash_get");
+    assert_eq!(fallback.matched_rule(sql).unwrap(), "lua_fallback");
+    let command = fallback.transform_to_command(sql).unwrap();
+    assert!(command.args[0].contains("redis.call('HGETALL', KEYS[1])"));
+    assert!(command.args[0].contains("if compare(field(ARGV[2]), '>', ARGV[3]) then"));
+    assert_eq!(&command.args[1..], ["1", "user:1", "name", "age", "30", "name"]);
+    assert!(strict.transform(sql).is_ok());
+    
+    // Test every data type is read whole, with BETWEEN, IN, and OR predicates
+    let script = |sql: &str| fallback.transform_to_command(sql).unwrap().args[0].clone();
+    assert!(script("SELECT * FROM tasks__list WHERE key = 'q' AND (value <> 'done' OR index IN (1, 2))").contains("'LRANGE', KEYS[1], 0, -1"));
+    assert!(script("SELECT * FROM tags__set WHERE key = 't' AND member BETWEEN 'a' AND 'm'").contains("'SMEMBERS', KEYS[1]"));
+    assert!(script("SELECT member FROM lb__zset WHERE key = 'g' AND member <> 'bot' AND score > 10").contains("'WITHSCORES'"));
+    assert!(script("SELECT * FROM cache WHERE key = 'page' AND value <> ''").contains("'GET', KEYS[1]"));
+    
+    // Test predicates a rule honors keep that rule, as do clauses beyond the predicates
+    assert_eq!(fallback.matched_rule("SELECT * FROM lb__zset WHERE key = 'g' AND score > 10").unwrap(), "zset_get_score_range");
+    assert_eq!(fallback.matched_rule("SELECT * FROM users__hash WHERE key = 'user:1'").unwrap(), "hash_getall");
+    
+    // Test predicates that can't be compiled are left to the other rules, while LIKE compiles
+    assert_eq!(fallback.matched_rule("SELECT * FROM users__hash WHERE key = 'user:1' AND age * 2 > 60").unwrap(), "hash_getall");
+    assert_eq!(fallback.matched_rule("SELECT * FROM users__hash WHERE key = 'user:1' AND name LIKE 'A%'").unwrap(), "lua_fallback");
+    
+    // Test replies decode into rows already projected
+    let reply = Reply::Array(vec![bulks(&["member", "ann", "score", "12"]), bulks(&["member", "cy", "score", "20"])]);
+    let rows = fallback.decode_reply("SELECT * FROM lb__zset WHERE key = 'g' AND member <> 'bot'", &reply).unwrap().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1], vec![("member".to_string(), "cy".to_string()), ("score".to_string(), "20".to_string())]);
+    let rows = fallback.decode_reply(sql, &Reply::Array(vec![bulks(&["name", "Ann"])])).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("name".to_string(), "Ann".to_string())]]);
+}
+
+#[test]
+fn test_eval_keys_and_args() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let eval = |sql: &str| transformer.transform_to_command(sql).unwrap();
+    
+    // Test the key goes to KEYS[] and the script is the same for every key
+    let spaced = eval("SELECT AVG(score) FROM g__zset WHERE key = 'my board'");
+    let plain = eval("SELECT AVG(score) FROM g__zset WHERE key = 'board'");
+    assert_eq!(spaced.args[0], plain.args[0]);
+    assert!(!spaced.args[0].contains("board"));
+    assert_eq!(&spaced.args[1..], ["1", "my board", "-inf", "+inf"]);
+    
+    // Test values with spaces or quotes stay one ARGV entry each
+    let command = eval("UPDATE users__hash SET status = CASE WHEN age > 30 THEN 'very old' ELSE 'it''s \"new\"' END WHERE key = 'user 1'");
+    assert_eq!(&command.args[1..4], ["1", "user 1", "status"]);
+    assert_eq!(command.args.last().unwrap(), "it's \"new\"");
+    assert!(!command.args[0].contains("very old"));
+    
+    // Test the command round-trips through its text form
+    assert_eq!(RedisCommand::parse(&command.to_quoted_string()).unwrap(), command);
+}
+
+/// Run a translated statement's EVAL in the mock's embedded interpreter
+#[cfg(feature = "lua-test")]
+fn eval(transformer: &SqlToRedisTransformer, redis: &mut MockRedis, sql: &str) -> Reply {
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.command, "EVAL");
+    redis.call(&std::iter::once(command.command).chain(command.args).collect::<Vec<_>>())
+}
+
+#[cfg(feature = "lua-test")]
+#[test]
+fn test_scripts_in_interpreter() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut redis = MockRedis::new();
+    redis.call(&["ZADD", "lb", "10", "ann", "20", "bob", "5", "cy"]);
+    
+    // Test aggregates compute over the range, numbers replying as integers
+    assert_eq!(eval(&transformer, &mut redis, "SELECT SUM(score) FROM lb__zset WHERE key = 'lb'"), Reply::Integer(35));
+    assert_eq!(eval(&transformer, &mut redis, "SELECT SUM(score) FROM lb__zset WHERE key = 'lb' AND score > 5"), Reply::Integer(30));
+    assert_eq!(eval(&transformer, &mut redis, "SELECT MAX(score) FROM lb__zset WHERE key = 'lb'"), Reply::bulk("20"));
+    assert_eq!(eval(&transformer, &mut redis, "SELECT SUM(score) FROM lb__zset WHERE key = 'lb' HAVING SUM(score) > 1000"), Reply::Nil);
+    assert_eq!(eval(&transformer, &mut redis, "SELECT SUM(score) FROM lb__zset WHERE key = 'missing'"), Reply::Integer(0));
+    
+    // Test the script's calls reach the keyspace
+    redis.clear_calls();
+    eval(&transformer, &mut redis, "SELECT AVG(score) FROM lb__zset WHERE key = 'lb'");
+    assert_eq!(redis.calls()[1], vec!["ZRANGEBYSCORE", "lb", "-inf", "+inf", "WITHSCORES"]);
+    
+    // Test a failing redis.call fails the script with the command's error
+    redis.call(&["SET", "plain", "x"]);
+    let reply = eval(&transformer, &mut redis, "SELECT SUM(score) FROM lb__zset WHERE key = 'plain'");
+    assert!(matches!(&reply, Reply::Error(e) if e.starts_with("WRONGTYPE")), "{:?}", reply);
+}
+
+#[cfg(feature = "lua-test")]
+#[test]
+fn test_join_script_in_interpreter() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut redis = MockRedis::new();
+    redis.call(&["ZADD", "game:1", "30", "user:1", "20", "user:2", "10", "user:3"]);
+    redis.call(&["HSET", "user:1", "name", "Ann"]);
+    redis.call(&["HSET", "user:2", "name", "Bob", "age", "40"]);
+    
+    // Test an inner join keeps members with a hash, in score order
+    let sql = "SELECT l.member, l.score, u.name FROM lb__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'game:1' ORDER BY l.score DESC LIMIT 10";
+    let reply = eval(&transformer, &mut redis, sql);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    let cell = |row: usize, column: &str| rows[row].iter().find(|(name, _)| name == column).map(|(_, value)| value.as_str());
+    assert_eq!(rows.len(), 2);
+    assert_eq!((cell(0, "member"), cell(0, "score"), cell(0, "name")), (Some("user:1"), Some("30"), Some("Ann")));
+    assert_eq!((cell(1, "member"), cell(1, "name")), (Some("user:2"), Some("Bob")));
+    
+    // Test a left join pads the missing hash, and OFFSET/LIMIT count joined rows
+    let sql = "SELECT l.member, u.name FROM lb__zset l LEFT JOIN users__hash u ON u.key = l.member WHERE l.key = 'game:1' ORDER BY l.score LIMIT 2";
+    let rows = transformer.decode_reply(sql, &eval(&transformer, &mut redis, sql)).unwrap().unwrap();
+    assert_eq!(rows[0], vec![("member".to_string(), "user:3".to_string())]);
+    assert_eq!(rows.len(), 2);
+    
+    // Test a key prefix keeps the join inside the tenant's hashes
+    let tenant = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("t1:")).unwrap();
+    redis.call(&["ZADD", "t1:game:1", "5", "user:1"]);
+    redis.call(&["HSET", "t1:user:1", "name", "Tenant Ann"]);
+    let sql = "SELECT l.member, u.name FROM lb__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'game:1' LIMIT 10";
+    let rows = tenant.decode_reply(sql, &eval(&tenant, &mut redis, sql)).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("member".to_string(), "user:1".to_string()), ("name".to_string(), "Tenant Ann".to_string())]]);
+}
+
+#[cfg(feature = "lua-test")]
+#[test]
+fn test_json_and_evalsha_in_interpreter() {
+    let options = TransformOptions::default().with_json_table("docs");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    let mut redis = MockRedis::new();
+    redis.call(&["SET", "doc:1", r#"{"title": "Hi \"there\"", "tags": ["a", "b"], "n": 3, "gone": null}"#]);
+    
+    // Test cjson decodes the document and re-encodes nested values
+    let reply = eval(&transformer, &mut redis, "SELECT title, tags, n, gone, missing FROM docs WHERE key = 'doc:1'");
+    assert_eq!(reply, Reply::Array(vec![
+        Reply::bulk("Hi \"there\""),
+        Reply::bulk(r#"["a","b"]"#),
+        Reply::bulk("3"),
+        Reply::Nil,
+        Reply::Nil,
+    ]));
+    
+    // Test a script cache's EVALSHA finds the script EVAL ran
+    let mut executor = Executor::new(redis).with_script_cache();
+    let command = transformer.transform_to_command("SELECT title FROM docs WHERE key = 'doc:1'").unwrap();
+    for _ in 0..2 {
+        assert_eq!(executor.execute(&command).unwrap(), bulks(&["Hi \"there\""]));
+    }
+    assert_eq!(executor.script_cache().unwrap().hits(), 1);
+}
+
+#[cfg(feature = "lua-test")]
+#[test]
+fn test_top_per_group_in_interpreter() {
+    let tenant = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("t1:")).unwrap();
+    let mut redis = MockRedis::new();
+    redis.call(&["SADD", "t1:g", "team:a"]);
+    redis.call(&["ZADD", "t1:team:a", "90", "ann", "70", "bob"]);
+    redis.call(&["ZADD", "team:a", "99", "other-tenant"]);
+    
+    // Test the zsets the set names are read under the tenant's prefix
+    let sql = "SELECT * FROM scores__zset WHERE key IN (SELECT member FROM groups__set WHERE key = 'g') ORDER BY score DESC LIMIT 1";
+    let reply = eval(&tenant, &mut redis, sql);
+    assert_eq!(reply, Reply::Array(vec![Reply::Array(vec![Reply::bulk("team:a"), bulks(&["ann", "90"])])]));
+}
+
+#[cfg(feature = "lua-test")]
+#[test]
+fn test_member_like_filter_in_interpreter() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut redis = MockRedis::new();
+    redis.call(&["ZADD", "lb", "10", "user:a", "30", "user:b", "20", "user:c", "40", "bot:d", "30", "user:e"]);
+    
+    // Test matching members are sorted by the ORDER BY columns and cut at LIMIT
+    let sql = "SELECT member FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:%' AND score > 10 ORDER BY score DESC, member DESC LIMIT 2";
+    let rows = transformer.decode_reply(sql, &eval(&transformer, &mut redis, sql)).unwrap().unwrap();
+    let members: Vec<_> = rows.iter().map(|row| row[0].1.as_str()).collect();
+    assert_eq!(members, ["user:e", "user:b"]);
+    
+    // Test set members sort as text, and LIMIT past the matches keeps them all
+    redis.call(&["SADD", "f", "user:9", "user:10", "bot:1"]);
+    let sql = "SELECT * FROM f__set WHERE key = 'f' AND member LIKE 'user:%' AND member <> 'x' ORDER BY member LIMIT 5";
+    let rows = transformer.decode_reply(sql, &eval(&transformer, &mut redis, sql)).unwrap().unwrap();
+    let members: Vec<_> = rows.iter().map(|row| row[0].1.as_str()).collect();
+    assert_eq!(members, ["user:10", "user:9"]);
+}
@@ -0,0 +1,75 @@
+// tests/matcher_alloc_tests.rs
+//! The common matchers borrow from the AST: matching a parsed statement allocates nothing
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use sql_redis::pattern::combinators::Pattern;
+use sql_redis::pattern::matchers::{common, delete, select, update};
+use sqlparser::ast::{SetExpr, Statement};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made on this thread while running `f`
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    std::hint::black_box(f());
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn parse(sql: &str) -> Statement {
+    Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0)
+}
+
+#[test]
+fn test_select_matchers_do_not_allocate() {
+    let stmt = parse("SELECT * FROM users__hash WHERE key = 'user:1001'");
+    let Statement::Query(query) = &stmt else { unreachable!() };
+    let SetExpr::Select(sel) = &*query.body else { unreachable!() };
+    let selection = sel.selection.as_ref().unwrap();
+
+    assert_eq!(allocations(|| common::wildcard_select().match_pattern(&&stmt).is_ok()), 0);
+    assert_eq!(allocations(|| common::key_equals().match_pattern(&selection)), 0);
+    assert_eq!(allocations(|| common::hash_table().match_pattern(&&sel.from[0].relation)), 0);
+    assert_eq!(allocations(|| select::is_hash_getall(&stmt)), 0);
+    assert_eq!(allocations(|| select::has_key_equals(&stmt)), 0);
+}
+
+#[test]
+fn test_borrowed_key_matches_owned_extraction() {
+    let stmt = parse("SELECT * FROM users__hash WHERE key = 'user:1001'");
+    let Statement::Query(query) = &stmt else { unreachable!() };
+    let SetExpr::Select(sel) = &*query.body else { unreachable!() };
+
+    assert_eq!(common::key_equals().match_pattern(&sel.selection.as_ref().unwrap()), Ok("user:1001"));
+    assert_eq!(common::hash_getall().match_pattern(&stmt), Ok("user:1001".to_string()));
+}
+
+#[test]
+fn test_write_matchers_do_not_allocate() {
+    let update_stmt = parse("UPDATE users__hash SET name = 'Jane' WHERE key = 'user:1001'");
+    let delete_stmt = parse("DELETE FROM users__hash WHERE key = 'user:1001'");
+
+    assert_eq!(allocations(|| update::is_hash_table(&update_stmt) && update::has_key_equals(&update_stmt)), 0);
+    assert_eq!(allocations(|| delete::is_hash_table(&delete_stmt) && delete::has_key_equals(&delete_stmt)), 0);
+}
@@ -0,0 +1,69 @@
+// tests/matcher_consistency_tests.rs
+//! The pattern combinators wrap the `ast` extraction functions, so both agree on every input
+
+use sql_redis::pattern::combinators::Pattern;
+use sql_redis::pattern::matchers::{common, select};
+use sqlparser::ast::{Expr, SetExpr, Statement};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+mod test_utils;
+
+fn parse(sql: &str) -> Statement {
+    Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0)
+}
+
+fn selection(stmt: &Statement) -> &Expr {
+    let Statement::Query(query) = stmt else { panic!("not a query") };
+    let SetExpr::Select(select) = &*query.body else { panic!("not a select") };
+    select.selection.as_ref().unwrap()
+}
+
+#[test]
+fn test_key_equals_agrees_with_has_key_equals() {
+    let cases = [
+        "SELECT * FROM users__hash WHERE key = 'user:1'",
+        "SELECT * FROM users__hash WHERE name = 'Ann' AND key = 'user:1'",
+        "SELECT * FROM users__hash WHERE KEY = 'user:1'",
+        "SELECT * FROM users__hash WHERE key > 'user:1'",
+        "SELECT * FROM users__hash WHERE key IN ('user:1', 'user:2')",
+        "SELECT * FROM users__hash WHERE name = 'Ann'",
+    ];
+    for sql in cases {
+        let stmt = parse(sql);
+        assert_eq!(
+            common::key_equals().match_pattern(&selection(&stmt)).is_ok(),
+            select::has_key_equals(&stmt),
+            "{}", sql
+        );
+    }
+}
+
+#[test]
+fn test_key_equals_finds_key_inside_and() {
+    let stmt = parse("SELECT * FROM posts__list WHERE index = 0 AND key = 'posts'");
+    assert_eq!(common::key_equals().match_pattern(&selection(&stmt)), Ok("posts"));
+    assert_eq!(common::field_equals("index").match_pattern(&selection(&stmt)), Ok("0"));
+}
+
+#[test]
+fn test_key_in_combinator() {
+    let stmt = parse("SELECT * FROM users WHERE key IN ('a', 'b')");
+    assert_eq!(common::key_in().match_pattern(selection(&stmt)), Ok(vec!["a".to_string(), "b".to_string()]));
+    assert!(common::key_equals().match_pattern(&selection(&stmt)).is_err());
+}
+
+#[test]
+fn test_score_range_combines_bounds() {
+    let stmt = parse("SELECT * FROM lb__zset WHERE key = 'k' AND score > 1 AND score <= 5");
+    assert_eq!(common::score_range().match_pattern(selection(&stmt)), Ok(("(1".to_string(), "5".to_string())));
+    test_utils::assert_sql_to_redis(
+        "SELECT * FROM lb__zset WHERE key = 'k' AND score > 1 AND score <= 5",
+        "ZRANGEBYSCORE k (1 5",
+    );
+}
+
+#[test]
+fn test_score_range_single_bound() {
+    let stmt = parse("SELECT * FROM lb__zset WHERE key = 'k' AND score >= 10");
+    assert_eq!(common::score_range().match_pattern(selection(&stmt)), Ok(("10".to_string(), "+inf".to_string())));
+}
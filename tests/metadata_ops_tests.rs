@@ -0,0 +1,59 @@
+// tests/metadata_ops_tests.rs
+use sql_redis::lua::{self, LuaFormat};
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_metadata_operations() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test OBJECT IDLETIME on a string table
+    let result = transformer.transform("SELECT __idletime FROM cache WHERE key = 'page:home'").unwrap();
+    assert_eq!(result, "OBJECT IDLETIME page:home");
+    
+    // Test OBJECT FREQ on a string table
+    let result = transformer.transform("SELECT __freq FROM cache WHERE key = 'page:home'").unwrap();
+    assert_eq!(result, "OBJECT FREQ page:home");
+    
+    // Pseudo-columns must not be mistaken for hash fields
+    let result = transformer.transform("SELECT __idletime FROM users__hash WHERE key = 'user:1001'").unwrap();
+    assert_eq!(result, "OBJECT IDLETIME user:1001");
+    
+    let result = transformer.transform("SELECT __freq FROM leaderboard__zset WHERE key = 'game:global'").unwrap();
+    assert_eq!(result, "OBJECT FREQ game:global");
+}
+
+#[test]
+fn test_keys_browse() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test LIKE patterns become SCAN globs, with LIMIT as the count
+    let result = transformer.transform("SELECT * FROM __keys WHERE key LIKE 'user:%' LIMIT 100").unwrap();
+    assert_eq!(result, "SCAN 0 MATCH user:* COUNT 100");
+    let result = transformer.transform("SELECT * FROM __keys WHERE key LIKE 'user:_\\%'").unwrap();
+    assert_eq!(result, "SCAN 0 MATCH user:?%");
+    let result = transformer.transform("SELECT * FROM __keys").unwrap();
+    assert_eq!(result, "SCAN 0 MATCH *");
+    
+    // Test an exact key matches only itself
+    let result = transformer.transform("SELECT * FROM __keys WHERE key = 'a*b'").unwrap();
+    assert_eq!(result, "SCAN 0 MATCH a\\*b");
+    
+    // Test other conditions aren't silently dropped
+    assert!(transformer.transform("SELECT * FROM __keys WHERE type = 'hash'").is_err());
+}
+
+#[test]
+fn test_keys_count() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    assert_eq!(transformer.transform("SELECT COUNT(*) FROM __keys").unwrap(), "DBSIZE");
+    
+    // Test a pattern counts with the SCAN script, recognizable in any Lua layout
+    let command = transformer.transform_to_command("SELECT COUNT(*) FROM __keys WHERE key LIKE 'user:%'").unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert_eq!(command.args[1..], ["0", "user:*"]);
+    assert!(lua::is_keys_count_script(&command.args[0]));
+    let minified = SqlToRedisTransformer::with_options(TransformOptions::default().with_lua_format(LuaFormat::Minify)).unwrap();
+    let command = minified.transform_to_command("SELECT COUNT(*) FROM __keys WHERE key LIKE 'user:%'").unwrap();
+    assert!(lua::is_keys_count_script(&command.args[0]));
+    assert!(!lua::is_keys_count_script(lua::VIEW_REFRESH_SCRIPT));
+}
@@ -0,0 +1,99 @@
+// tests/metrics_tests.rs
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sql_redis::commands::RedisCommand;
+use sql_redis::executor::{Connection, ExecutionError, Executor};
+use sql_redis::metrics::Metrics;
+use sql_redis::reply::Reply;
+use sql_redis::SqlToRedisTransformer;
+
+/// Records every callback as a line of text
+#[derive(Default)]
+struct RecordingMetrics {
+    events: Mutex<Vec<String>>,
+}
+
+impl Metrics for RecordingMetrics {
+    fn on_parse(&self, _elapsed: Duration, success: bool) {
+        self.events.lock().unwrap().push(format!("parse {}", success));
+    }
+
+    fn on_rule_match(&self, rule: Option<&str>, _elapsed: Duration) {
+        self.events.lock().unwrap().push(format!("match {}", rule.unwrap_or("none")));
+    }
+
+    fn on_render(&self, template: &str, _elapsed: Duration, success: bool) {
+        self.events.lock().unwrap().push(format!("render {} {}", template, success));
+    }
+
+    fn on_execute(&self, command: &str, _elapsed: Duration, success: bool) {
+        self.events.lock().unwrap().push(format!("execute {} {}", command, success));
+    }
+}
+
+/// Connection that rejects DEL and answers everything else with OK
+struct StubConnection;
+
+impl Connection for StubConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        match args[0].as_str() {
+            "DEL" => Ok(Reply::Error("NOPERM".to_string())),
+            _ => Ok(Reply::Status("OK".to_string())),
+        }
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_metrics_hooks() {
+    let metrics = Arc::new(RecordingMetrics::default());
+    let transformer = SqlToRedisTransformer::new().unwrap().with_metrics(metrics.clone());
+
+    transformer.transform("SELECT * FROM users WHERE key = 'u:1'").unwrap();
+    assert!(transformer.transform("SELECT * FROM users WHERE name = 'Ann'").is_err());
+    assert!(transformer.transform("SELEC oops").is_err());
+    assert_eq!(*metrics.events.lock().unwrap(), [
+        "parse true", "match string_get", "render string_get true",
+        "parse true", "match none",
+        "parse false",
+    ]);
+
+    // Test executed commands report success, error replies report failure
+    metrics.events.lock().unwrap().clear();
+    let mut executor = Executor::new(StubConnection).with_metrics(metrics.clone());
+    executor.execute(&RedisCommand::new("SET", vec!["k", "v"])).unwrap();
+    let commands = [RedisCommand::new("DEL", vec!["k"]), RedisCommand::new("GET", vec!["k"])];
+    executor.execute_batch(&commands, |_| ()).unwrap();
+    assert_eq!(*metrics.events.lock().unwrap(), ["execute SET true", "execute DEL false", "execute GET true"]);
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn test_prometheus_exporter() {
+    use sql_redis::metrics::PrometheusMetrics;
+
+    let metrics = Arc::new(PrometheusMetrics::new());
+    let transformer = SqlToRedisTransformer::new().unwrap().with_metrics(metrics.clone());
+    transformer.transform("SELECT * FROM users WHERE key = 'u:1'").unwrap();
+    transformer.transform("SELECT * FROM users WHERE key = 'u:2'").unwrap();
+    assert!(transformer.transform("SELECT * FROM users WHERE name = 'Ann'").is_err());
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE sql_redis_rule_matches_total counter\n"));
+    assert!(text.contains("sql_redis_rule_matches_total{rule=\"string_get\"} 2\n"));
+    assert!(text.contains("sql_redis_no_matching_pattern_total 1\n"));
+    assert!(text.contains("sql_redis_parse_total{result=\"ok\"} 3\n"));
+    assert!(text.contains("sql_redis_render_duration_seconds_count{template=\"string_get\"} 2\n"));
+
+    // Test script cache lookups are counted by result
+    metrics.on_script_cache(true);
+    metrics.on_script_cache(false);
+    metrics.on_script_cache(true);
+    let text = metrics.render();
+    assert!(text.contains("sql_redis_script_cache_total{result=\"hit\"} 2\n"));
+    assert!(text.contains("sql_redis_script_cache_total{result=\"miss\"} 1\n"));
+}
@@ -0,0 +1,119 @@
+// tests/migrate_tests.rs
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use sql_redis::executor::{Connection, ExecutionError, Executor};
+use sql_redis::migrate::{Migration, MigrationError, Migrator, MIGRATIONS_KEY};
+use sql_redis::reply::Reply;
+use sql_redis::SqlToRedisTransformer;
+
+/// Keeps the migrations hash in memory and logs every other command
+#[derive(Default)]
+struct MigrationConnection {
+    versions: BTreeMap<String, String>,
+    log: Rc<RefCell<Vec<String>>>,
+}
+
+impl Connection for MigrationConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        match args {
+            [command, key] if command == "HGETALL" && key == MIGRATIONS_KEY => Ok(Reply::Array(
+                self.versions.iter()
+                    .flat_map(|(version, at)| [Reply::bulk(version.as_str()), Reply::bulk(at.as_str())])
+                    .collect(),
+            )),
+            [command, key, version, at] if command == "HSET" && key == MIGRATIONS_KEY => {
+                self.versions.insert(version.clone(), at.clone());
+                Ok(Reply::Integer(1))
+            }
+            [command, key, version] if command == "HDEL" && key == MIGRATIONS_KEY => {
+                Ok(Reply::Integer(self.versions.remove(version).is_some() as i64))
+            }
+            _ => {
+                self.log.borrow_mut().push(args.join(" "));
+                Ok(Reply::Status("OK".to_string()))
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+fn migrations() -> Vec<Migration> {
+    vec![
+        Migration::parse("001", "users", "
+            -- 001: seed users
+            -- up
+            INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann')
+            INSERT INTO users__hash (key, name) VALUES ('u:2', 'Bob')
+            -- down
+            DELETE FROM users__hash WHERE key = 'u:1'
+            DELETE FROM users__hash WHERE key = 'u:2'
+        ").unwrap(),
+        Migration::parse("002", "scores", "
+            -- up
+            INSERT INTO scores__zset (key, member, score) VALUES ('game', 'u:1', '10')
+            -- down
+            DELETE FROM scores__zset WHERE key = 'game'
+        ").unwrap(),
+    ]
+}
+
+#[test]
+fn test_migration_parsing() {
+    let migration = &migrations()[0];
+    assert_eq!(migration.id(), "001_users");
+    assert_eq!(migration.up.len(), 2);
+    assert_eq!(migration.down, vec!["DELETE FROM users__hash WHERE key = 'u:1'", "DELETE FROM users__hash WHERE key = 'u:2'"]);
+
+    // Test statements outside a section are rejected
+    let error = Migration::parse("003", "bad", "SET x").unwrap_err();
+    assert!(matches!(error, MigrationError::Invalid(..)));
+}
+
+#[test]
+fn test_migrate_up_and_down() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let migrations = migrations();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut executor = Executor::new(MigrationConnection { log: log.clone(), ..Default::default() });
+    let mut migrator = Migrator::new(&transformer, &mut executor);
+
+    // Test up applies pending migrations in order, once
+    let applied = migrator.up(&migrations).unwrap();
+    assert_eq!(applied.iter().map(|m| m.id()).collect::<Vec<_>>(), vec!["001_users", "002_scores"]);
+    assert!(migrator.up(&migrations).unwrap().is_empty());
+    assert_eq!(*log.borrow(), vec!["HSET u:1 name Ann", "HSET u:2 name Bob", "ZADD game 10 u:1"]);
+    assert!(migrator.status(&migrations).unwrap().iter().all(|(_, applied)| *applied));
+
+    // Test down reverts the latest migration only
+    let reverted = migrator.down(&migrations, 1).unwrap();
+    assert_eq!(reverted.iter().map(|m| m.id()).collect::<Vec<_>>(), vec!["002_scores"]);
+    let status: Vec<bool> = migrator.status(&migrations).unwrap().iter().map(|(_, applied)| *applied).collect();
+    assert_eq!(status, vec![true, false]);
+    assert_eq!(log.borrow().last().unwrap(), "DEL game");
+
+    // Test down fails for applied versions without a file
+    assert!(matches!(migrator.down(&migrations[1..], 1), Err(MigrationError::Missing(version)) if version == "001"));
+}
+
+#[test]
+fn test_migration_translation_failure_applies_nothing() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let migrations = vec![Migration::parse("001", "bad", "
+        -- up
+        INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann')
+        SELECT * FROM users
+        -- down
+    ").unwrap()];
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut executor = Executor::new(MigrationConnection { log: log.clone(), ..Default::default() });
+    let mut migrator = Migrator::new(&transformer, &mut executor);
+
+    assert!(matches!(migrator.up(&migrations), Err(MigrationError::Transform(id, _)) if id == "001_bad"));
+    assert!(!migrator.status(&migrations).unwrap()[0].1);
+    assert!(log.borrow().is_empty());
+}
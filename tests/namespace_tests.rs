@@ -0,0 +1,111 @@
+// tests/namespace_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::{ErrorCode, SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_key_prefixing() {
+    let options = TransformOptions::default()
+        .with_key_prefix("tenant42:")
+        .with_table_prefix("config", "shared:");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+
+    // Test single-key and multi-key commands
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap(), "GET tenant42:user:1");
+    assert_eq!(
+        transformer.transform_to_command("SELECT * FROM users WHERE key IN ('a', 'b')").unwrap(),
+        RedisCommand::new("MGET", vec!["tenant42:a", "tenant42:b"]),
+    );
+    assert_eq!(
+        transformer.transform_to_command("INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann Lee')").unwrap(),
+        RedisCommand::new("HSET", vec!["tenant42:u:1", "name", "Ann Lee"]),
+    );
+
+    // Test script KEYS are prefixed, the script and its ARGV are not
+    let command = transformer.transform_to_command("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap();
+    assert_eq!(command.args[1..], ["1", "tenant42:g:1", "-inf", "+inf"]);
+    let text = transformer.transform("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap();
+    assert_eq!(RedisCommand::parse_all(&text), vec![command]);
+    // The script keeps the template's single-quoted, multi-line rendering; only the key changes
+    let plain = SqlToRedisTransformer::new().unwrap().transform("SELECT AVG(score) FROM game__zset WHERE key = 'g:1'").unwrap();
+    assert_eq!(text, plain.replace(" 1 g:1 ", " 1 tenant42:g:1 "));
+
+    // Test join scripts read the member hashes under the prefix too
+    let command = transformer.transform_to_command("SELECT l.member, u.name FROM lb__zset l JOIN users__hash u ON u.key = 'user:' || l.member WHERE l.key = 'g:1' LIMIT 5").unwrap();
+    assert_eq!(command.args[2..8], ["tenant42:g:1", "ZRANGE", "0", "5", "inner", "tenant42:user:*"]);
+
+    // Test keyspace notification channels; without WHEN, the trigger watches the prefix
+    let commands = transformer.transform_to_commands(
+        "CREATE TRIGGER t AFTER INSERT ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()",
+    ).unwrap();
+    assert_eq!(commands[1].args[0], "__keyspace@0__:tenant42:user:*");
+    let commands = transformer.transform_to_commands(
+        "CREATE TRIGGER t AFTER INSERT ON users__hash FOR EACH ROW EXECUTE FUNCTION notify()",
+    ).unwrap();
+    assert_eq!(commands[1].args[0], "__keyspace@0__:tenant42:*");
+
+    // Test keyspace counts only count the prefixed keys
+    let command = transformer.transform_to_command("SELECT COUNT(*) FROM __keys WHERE key LIKE 'user:%'").unwrap();
+    assert_eq!(command.args[1..], ["0", "tenant42:user:*"]);
+    let command = transformer.transform_to_command("SELECT COUNT(*) FROM __keys").unwrap();
+    assert_eq!((command.command.as_str(), &command.args[1..]), ("EVAL", &["0".to_string(), "tenant42:*".to_string()][..]));
+
+    // Test table prefixes override the global prefix
+    assert_eq!(transformer.transform("SELECT * FROM config WHERE key = 'flags'").unwrap(), "GET shared:flags");
+
+    // Test transformers without prefixes are unchanged
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap(), "GET user:1");
+}
+
+#[test]
+fn test_cluster_hash_tags() {
+    let options = TransformOptions::default()
+        .with_hash_tags(2)
+        .with_table_hash_tags("sessions", 1)
+        .with_table_prefix("audit", "tenant42:");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+
+    // Test an entity's keys share the tag of its first two segments
+    assert_eq!(transformer.transform("SELECT * FROM posts__list WHERE key = 'user:1001:posts'").unwrap(), "LRANGE {user:1001}:posts 0 -1");
+    assert_eq!(transformer.transform("SELECT * FROM users__hash WHERE key = 'user:1001'").unwrap(), "HGETALL {user:1001}");
+    assert_eq!(
+        transformer.transform_to_command("SELECT * FROM users WHERE key IN ('user:1:a', 'user:1:b')").unwrap(),
+        RedisCommand::new("MGET", vec!["{user:1}:a", "{user:1}:b"]),
+    );
+
+    // Test script KEYS are tagged, so the script runs on one slot
+    let command = transformer.transform_to_command("SELECT AVG(score) FROM game__zset WHERE key = 'game:1:scores'").unwrap();
+    assert_eq!(command.args[1..], ["1", "{game:1}:scores", "-inf", "+inf"]);
+
+    // Test per-table segment counts, existing tags, and prefixes outside the tag
+    assert_eq!(transformer.transform("SELECT * FROM sessions WHERE key = 'sess:abc:data'").unwrap(), "GET {sess}:abc:data");
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = '{acct:7}:profile'").unwrap(), "GET {acct:7}:profile");
+    assert_eq!(transformer.transform("SELECT * FROM audit WHERE key = 'log:1:day'").unwrap(), "GET tenant42:{log:1}:day");
+}
+
+#[test]
+fn test_schema_qualified_tables() {
+    let options = TransformOptions::default()
+        .with_schema("app")
+        .with_schema_namespace("billing")
+        .with_schema_namespace("db.ops")
+        .with_key_prefix("tenant42:");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+
+    // A stripped schema translates like the bare table
+    assert_eq!(transformer.transform("SELECT * FROM app.users__hash WHERE key = 'user:1'").unwrap(), "HGETALL tenant42:user:1");
+    assert_eq!(transformer.transform("UPDATE app.users__hash SET name = 'x' WHERE key = 'user:1'").unwrap(), "HSET tenant42:user:1 name x");
+    assert_eq!(transformer.transform("SELECT * FROM APP.users__hash u WHERE u.key = 'user:1'").unwrap(), "HGETALL tenant42:user:1");
+
+    // A namespaced schema prefixes the keys inside the key prefix; multi-part schemas join with ':'
+    assert_eq!(
+        transformer.transform_to_command("INSERT INTO billing.invoices__hash (key, total) VALUES ('inv:1', '10')").unwrap(),
+        RedisCommand::new("HSET", vec!["tenant42:billing:inv:1", "total", "10"]),
+    );
+    assert_eq!(transformer.transform("DELETE FROM db.ops.jobs WHERE key = 'job:1'").unwrap(), "DEL tenant42:db:ops:job:1");
+
+    // An undeclared schema is an error rather than a silent mismatch
+    let err = transformer.transform("SELECT * FROM other.users__hash WHERE key = 'user:1'").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownSchema);
+    assert_eq!(err.code().as_str(), "E0011");
+}
@@ -0,0 +1,79 @@
+// tests/plugin_tests.rs
+#![cfg(feature = "plugins")]
+
+use std::fs;
+use sql_redis::context::TemplateContext;
+use sql_redis::plugins::{self, PluginDeclaration, PluginError, PluginRegistry, CRATE_VERSION, PLUGIN_ABI_VERSION};
+use sql_redis::{rule, SqlToRedisTransformer, TransformOptions};
+use sqlparser::ast::Statement;
+
+fn register(registry: &mut PluginRegistry) {
+    registry.register_template("acme_touch", "TOUCH {{ key }}");
+    registry.register_rule(rule! {
+        template: "acme_touch",
+        matcher: |stmt: &Statement| stmt.to_string().starts_with("SELECT * FROM users"),
+        build: |_| Some(TemplateContext::from([("key".to_string(), "user:1".to_string())])),
+    });
+}
+
+static ACME: PluginDeclaration = PluginDeclaration {
+    abi_version: PLUGIN_ABI_VERSION,
+    crate_version: CRATE_VERSION,
+    name: "acme",
+    register,
+};
+
+#[test]
+fn test_plugin_registration() {
+    let mut transformer = SqlToRedisTransformer::new().unwrap();
+    transformer.register_plugin(&ACME).unwrap();
+    
+    // Plugin rules take precedence over built-in rules
+    let result = transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap();
+    assert_eq!(result, "TOUCH user:1");
+    
+    // Other statements still use the built-in rules
+    let result = transformer.transform("SELECT * FROM cache WHERE key = 'page:1'").unwrap();
+    assert_eq!(result, "GET page:1");
+}
+
+#[test]
+fn test_plugin_template_added_at_runtime() {
+    fn register_late(registry: &mut PluginRegistry) {
+        registry.register_rule(rule! {
+            template: "acme_late",
+            matcher: |stmt: &Statement| stmt.to_string().starts_with("SELECT * FROM users"),
+            build: |_| Some(TemplateContext::from([("key".to_string(), "user:1".to_string())])),
+        });
+    }
+    let declaration = PluginDeclaration { name: "late", register: register_late, ..ACME };
+    let mut transformer = SqlToRedisTransformer::new().unwrap();
+    transformer.register_plugin(&declaration).unwrap();
+    
+    // A rule whose template is rolled out later fails to render until it is added
+    assert!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").is_err());
+    transformer.add_template("acme_late", "TOUCH {{ key }}").unwrap();
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap(), "TOUCH user:1");
+}
+
+#[test]
+fn test_plugin_discovery() {
+    let dir = std::env::temp_dir().join(format!("sql_redis_plugins_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    
+    // Non-library files are ignored
+    fs::write(dir.join("README.txt"), "not a plugin").unwrap();
+    assert!(plugins::load_dir(&dir).unwrap().is_empty());
+    let transformer = SqlToRedisTransformer::with_options(TransformOptions::default().with_plugin_dir(&dir)).unwrap();
+    assert_eq!(transformer.plugins().count(), 0);
+    
+    // A file with the library extension that isn't a loadable library fails
+    let bogus = dir.join(format!("bogus.{}", std::env::consts::DLL_EXTENSION));
+    fs::write(&bogus, "not a library").unwrap();
+    assert!(matches!(plugins::load_dir(&dir), Err(PluginError::Load(path, _)) if path == bogus));
+    assert!(SqlToRedisTransformer::with_options(TransformOptions::default().with_plugin_dir(&dir)).is_err());
+    
+    // Missing directories are reported
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(matches!(plugins::load_dir(&dir), Err(PluginError::Io(..))));
+}
@@ -0,0 +1,45 @@
+// tests/recording_tests.rs
+use std::fs;
+
+use sql_redis::recording::{self, RecordedCommand, SessionRecorder};
+use sql_redis::reply::Reply;
+
+#[test]
+fn test_session_recording() {
+    let records = vec![
+        RecordedCommand::new("INSERT INTO users__hash (key, name) VALUES ('u:1', 'Ann \"A\"')", "HSET u:1 name \"Ann \\\"A\\\"\"", Ok(Reply::Integer(1))),
+        RecordedCommand::new("SELECT * FROM users__hash WHERE key = 'u:1'", "HGETALL u:1", Ok(Reply::Array(vec![
+            Reply::bulk("name"),
+            Reply::bulk("Ann \"A\"\n"),
+        ]))),
+        RecordedCommand::new("SELECT * FROM t WHERE key = 'k'", "GET k", Ok(Reply::Nil)),
+        RecordedCommand::new("UPDATE t SET value = 'v' WHERE key = 'k'", "SET k v", Ok(Reply::Status("OK".to_string()))),
+        RecordedCommand::new("SELECT * FROM t WHERE key = 'k'", "EVAL \"return redis.error_reply('x')\" 0", Ok(Reply::Array(vec![Reply::Error("ERR x".to_string())]))),
+        RecordedCommand::new("DELETE FROM t WHERE key = 'k'", "DEL k", Err("Server error: WRONGTYPE".to_string())),
+        RecordedCommand::new("SELECT * FROM users WHERE key = 'u:1'", "GET u:1", Ok(Reply::Bulk(vec![0x81, 0xa1, b'a', 0x01]))),
+    ];
+
+    // Test each record survives a round trip through its JSON line
+    for record in &records {
+        let line = record.to_json();
+        assert!(!line.contains('\n'));
+        assert_eq!(RecordedCommand::from_json(&line).as_ref(), Some(record), "{}", line);
+    }
+    assert_eq!(
+        records[3].to_json(),
+        r#"{"sql":"UPDATE t SET value = 'v' WHERE key = 'k'","command":"SET k v","reply":{"status":"OK"}}"#
+    );
+    assert!(records[6].to_json().ends_with(r#""reply":{"bytes":"81a16101"}}"#));
+    assert!(RecordedCommand::from_json(r#"{"sql":"x","command":"GET k"}"#).is_none());
+    assert!(RecordedCommand::from_json(r#"{"sql":"x","command":"GET k","reply":{"other":"y"}}"#).is_none());
+    assert!(RecordedCommand::from_json(r#"{"sql":"x","command":"GET k","reply":null} trailing"#).is_none());
+
+    // Test a recorded session loads back in order, and a malformed line names the file and line
+    let path = std::env::temp_dir().join(format!("sql_redis_session_{}.jsonl", std::process::id()));
+    let recorder = SessionRecorder::create(&path).unwrap();
+    records.iter().for_each(|record| recorder.record(record).unwrap());
+    assert_eq!(recording::load(&path).unwrap(), records);
+    fs::write(&path, format!("{}\n\nnot json\n", records[0].to_json())).unwrap();
+    assert!(recording::load(&path).unwrap_err().to_string().contains("line 3"));
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,52 @@
+// tests/rule_macro_tests.rs
+use sqlparser::ast::Statement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sql_redis::prelude::{sel_get_key_value, sel_get_query, sel_get_select};
+use sql_redis::context::{self, TemplateContext};
+use sql_redis::pattern::matchers::select;
+use sql_redis::rule;
+use sql_redis::Profile;
+
+fn parse(sql: &str) -> Statement {
+    Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0)
+}
+
+#[test]
+fn test_rule_macro() {
+    // Function paths and builder values, with metadata
+    let rule = rule! {
+        template: "string_get",
+        matcher: select::is_string_get,
+        context: context::StringGetContextBuilder,
+        sql: "SELECT * FROM table WHERE key = value",
+        redis: "GET value",
+        profile: Profile::Admin,
+    };
+    let stmt = parse("SELECT * FROM users WHERE key = 'user:1'");
+    assert!(rule.matches(&stmt));
+    assert_eq!(rule.get_template_name(), "string_get");
+    assert_eq!(rule.get_matcher_name(), Some("is_string_get"));
+    assert_eq!(rule.get_sql_pattern(), Some("SELECT * FROM table WHERE key = value"));
+    assert_eq!(rule.get_redis_pattern(), Some("GET value"));
+    assert_eq!(rule.get_profile(), Some(Profile::Admin));
+    assert_eq!(rule.get_context(&stmt).unwrap().get("key"), Some(&"user:1".to_string()));
+    
+    // Inline closures generate the matcher and context builder; no matcher name is derived
+    let rule = rule! {
+        template: "string_exists",
+        matcher: |stmt: &Statement| select::is_string_get(stmt),
+        build: |stmt| {
+            let key = sel_get_query(stmt)
+                .and_then(sel_get_select)
+                .and_then(|s| sel_get_key_value(&s.selection))?;
+            Some(TemplateContext::from([("key".to_string(), key)]))
+        },
+        direct: |_| Some("EXISTS user:1".to_string()),
+    };
+    assert!(rule.matches(&stmt));
+    assert_eq!(rule.get_matcher_name(), None);
+    assert_eq!(rule.get_profile(), None);
+    assert_eq!(rule.get_context(&stmt).unwrap().get("key"), Some(&"user:1".to_string()));
+    assert_eq!(rule.get_direct_command(&stmt), Some("EXISTS user:1".to_string()));
+}
@@ -0,0 +1,103 @@
+// tests/seed_tests.rs
+use sql_redis::seed::SeedTemplate;
+use sql_redis::templates::FakeData;
+use sql_redis::SqlToRedisTransformer;
+
+#[test]
+fn test_seed_expands_index_per_row() {
+    let template = SeedTemplate::parse("INSERT INTO users__hash (key, visits) VALUES ('user:{{ i }}', '{{i}}')").unwrap();
+    let rows: Vec<String> = template.expand(3, &mut FakeData::new(7)).collect();
+    assert_eq!(rows, vec![
+        "INSERT INTO users__hash (key, visits) VALUES ('user:1', '1')",
+        "INSERT INTO users__hash (key, visits) VALUES ('user:2', '2')",
+        "INSERT INTO users__hash (key, visits) VALUES ('user:3', '3')",
+    ]);
+}
+
+#[test]
+fn test_seed_is_reproducible_from_seed() {
+    let template = SeedTemplate::parse("INSERT INTO users__hash (id, name) VALUES ('{{ uuid() }}', '{{ random_name() }}')").unwrap();
+    let first: Vec<String> = template.expand(5, &mut FakeData::new(42)).collect();
+    let second: Vec<String> = template.expand(5, &mut FakeData::new(42)).collect();
+    let other: Vec<String> = template.expand(5, &mut FakeData::new(43)).collect();
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn test_seed_uuid_is_version_4() {
+    let template = SeedTemplate::parse("{{ uuid() }}").unwrap();
+    let uuid = template.render(1, &mut FakeData::new(1));
+    let groups: Vec<&str> = uuid.split('-').collect();
+    assert_eq!(groups.iter().map(|group| group.len()).collect::<Vec<_>>(), vec![8, 4, 4, 4, 12]);
+    assert!(groups[2].starts_with('4'));
+    assert!(matches!(groups[3].chars().next(), Some('8' | '9' | 'a' | 'b')));
+}
+
+#[test]
+fn test_seed_multiline_template_skips_comments() {
+    let template = SeedTemplate::parse(
+        "-- one user and its leaderboard entry\n\
+         INSERT INTO users__hash (key, name) VALUES ('user:{{ i }}', '{{ random_name() }}')\n\
+         \n\
+         INSERT INTO leaderboard__zset (key, member, score) VALUES ('game:global', 'user:{{ i }}', '{{ i }}')\n",
+    ).unwrap();
+    let rows: Vec<String> = template.expand(2, &mut FakeData::new(1)).collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[2].starts_with("INSERT INTO users__hash (key, name) VALUES ('user:2', '"));
+
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    for row in &rows {
+        assert!(transformer.transform(row).is_ok(), "failed to transform {}", row);
+    }
+}
+
+#[test]
+fn test_seed_rejects_unknown_placeholders() {
+    let err = SeedTemplate::parse("INSERT INTO t (id) VALUES ('{{ random_email() }}')").unwrap_err();
+    assert!(err.to_string().contains("random_email()"));
+    assert!(SeedTemplate::parse("INSERT INTO t (id) VALUES ('{{ i')").is_err());
+}
+
+#[test]
+fn test_seed_fake_data_functions() {
+    let template = SeedTemplate::parse("{{ now() }} {{ rand_int(5, 7) }} {{ faker_name() }}").unwrap();
+    let mut fake = FakeData::new(9).with_now(1_700_000_000);
+    for i in 1..=20 {
+        let row = template.render(i, &mut fake);
+        let mut words = row.split(' ');
+        assert_eq!(words.next(), Some("1700000000"));
+        let n: i64 = words.next().unwrap().parse().unwrap();
+        assert!((5..=7).contains(&n), "{} out of range", n);
+        assert_eq!(words.count(), 2);
+    }
+    assert!(SeedTemplate::parse("{{ rand_int(1) }}").is_err());
+}
+
+#[test]
+fn test_fake_data_rand_int_bounds() {
+    let mut fake = FakeData::new(3);
+    assert_eq!(fake.rand_int(4, 4), 4);
+    assert!((-3..=3).contains(&fake.rand_int(3, -3)));
+    fake.rand_int(i64::MIN, i64::MAX);
+}
+
+#[cfg(feature = "templates")]
+#[test]
+fn test_tera_fake_data_functions_are_reproducible() {
+    use sql_redis::context::TemplateContext;
+    use sql_redis::templates::TemplateEngine;
+
+    let render = || {
+        let mut engine = TemplateEngine::with_fake_data(FakeData::new(42).with_now(1_700_000_000)).unwrap();
+        engine.add_template(
+            "fake_user",
+            "HSET user:{{ uuid() }} name \"{{ faker_name() }}\" age {{ rand_int(a=18, b=90) }} created {{ now() }}",
+        ).unwrap();
+        (0..3).map(|_| engine.render("fake_user", &TemplateContext::new()).unwrap()).collect::<Vec<_>>()
+    };
+    let first = render();
+    assert_eq!(first, render());
+    assert_ne!(first[0], first[1]);
+    assert!(first[0].ends_with("created 1700000000"));
+}
@@ -0,0 +1,78 @@
+// tests/session_tests.rs
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sql_redis::commands::RedisCommand;
+use sql_redis::executor::{Connection, ExecutionError, Executor};
+use sql_redis::reply::Reply;
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_use_database() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test USE with and without the db prefix
+    assert_eq!(transformer.transform("USE db3").unwrap(), "SELECT 3");
+    assert_eq!(transformer.transform("USE \"5\"").unwrap(), "SELECT 5");
+
+    // Test statements are unchanged without table databases
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap(), "GET user:1");
+}
+
+#[test]
+fn test_table_databases() {
+    let options = TransformOptions::default()
+        .with_table_database("sessions", 3);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+
+    // Test the table's commands select its database first
+    assert_eq!(transformer.transform("SELECT * FROM sessions WHERE key = 's:1'").unwrap(), "SELECT 3\nGET s:1");
+    assert_eq!(
+        transformer.transform_to_commands("SELECT * FROM sessions WHERE key = 's:1'").unwrap(),
+        vec![RedisCommand::new("SELECT", vec!["3"]), RedisCommand::new("GET", vec!["s:1"])],
+    );
+
+    // Test other tables switch back to the default database
+    assert_eq!(transformer.transform("SELECT * FROM users WHERE key = 'user:1'").unwrap(), "SELECT 0\nGET user:1");
+
+    // Test keyspace channels name the table's database instead of selecting it
+    let commands = transformer.transform_to_commands(
+        "CREATE TRIGGER t AFTER INSERT ON sessions FOR EACH ROW WHEN (key LIKE 's:%') EXECUTE FUNCTION notify()",
+    ).unwrap();
+    assert!(commands.iter().all(|command| command.command != "SELECT"));
+    assert!(commands[1].args[0].starts_with("__keyspace@3__:"));
+}
+
+/// Records every command sent and replies OK
+struct RecordingConnection(Rc<RefCell<Vec<String>>>);
+
+impl Connection for RecordingConnection {
+    fn send(&mut self, args: &[String]) -> Result<Reply, ExecutionError> {
+        self.0.borrow_mut().push(args.join(" "));
+        Ok(Reply::Status("OK".to_string()))
+    }
+
+    fn receive(&mut self) -> Result<Reply, ExecutionError> {
+        Err(ExecutionError::Protocol("no messages".to_string()))
+    }
+}
+
+#[test]
+fn test_executor_database_tracking() {
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let mut executor = Executor::new(RecordingConnection(sent.clone()));
+    assert_eq!(executor.database(), None);
+
+    // Test repeated SELECTs of the current database are answered without a round trip
+    let replies = executor.execute_text("SELECT 3\nGET s:1\nSELECT 3\nGET s:2").unwrap();
+    assert_eq!(replies.len(), 4);
+    assert_eq!(executor.database(), Some(3));
+    assert_eq!(*sent.borrow(), vec!["SELECT 3", "GET s:1", "GET s:2"]);
+
+    // Test batches track the database too
+    let commands = RedisCommand::parse_all("SELECT 0\nGET user:1");
+    executor.execute_batch(&commands, |_| ()).unwrap();
+    assert_eq!(executor.database(), Some(0));
+    executor.execute_text("SELECT 0").unwrap();
+    assert_eq!(sent.borrow().len(), 5);
+}
@@ -9,6 +9,10 @@ fn test_set_operations() {
     let result = transformer.transform("SELECT * FROM followers__set WHERE key = 'user:1001:followers'").unwrap();
     assert_eq!(result, "SMEMBERS user:1001:followers");
     
+    // Test set SSCAN for a LIMIT-ed read
+    let result = transformer.transform("SELECT * FROM followers__set WHERE key = 'user:1001:followers' LIMIT 50").unwrap();
+    assert_eq!(result, "SSCAN user:1001:followers 0 COUNT 50");
+    
     // Test set SISMEMBER
     let result = transformer.transform("SELECT * FROM followers__set WHERE key = 'user:1001:followers' AND member = 'user:1002'").unwrap();
     assert_eq!(result, "SISMEMBER user:1001:followers user:1002");
@@ -17,6 +21,14 @@ fn test_set_operations() {
     let result = transformer.transform("INSERT INTO followers__set (key, member) VALUES ('user:1001:followers', 'user:1002')").unwrap();
     assert_eq!(result, "SADD user:1001:followers user:1002");
     
+    // Test multi-row insert in a single SADD
+    let result = transformer.transform("INSERT INTO followers__set (key, member) VALUES ('user:1001:followers', 'user:1002'), ('user:1001:followers', 'user:1003')").unwrap();
+    assert_eq!(result, "SADD user:1001:followers user:1002 user:1003");
+    
+    // Test multi-member delete in a single SREM
+    let result = transformer.transform("DELETE FROM followers__set WHERE key = 'user:1001:followers' AND member IN ('user:1002', 'user:1003')").unwrap();
+    assert_eq!(result, "SREM user:1001:followers user:1002 user:1003");
+    
     // Test set delete
     let result = transformer.transform("DELETE FROM followers__set WHERE key = 'user:1001:followers'").unwrap();
     assert_eq!(result, "DEL user:1001:followers");
@@ -24,4 +36,80 @@ fn test_set_operations() {
     // Test set member delete
     let result = transformer.transform("DELETE FROM followers__set WHERE key = 'user:1001:followers' AND member = 'user:1002'").unwrap();
     assert_eq!(result, "SREM user:1001:followers user:1002");
-}
\ No newline at end of file
+}
+#[test]
+fn test_set_store_operations() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test INTERSECT stores into {table}:{first source key}
+    let result = transformer.transform("INSERT INTO common__set (key, member) SELECT member FROM followers__set WHERE key = 'u:1' INTERSECT SELECT member FROM followers__set WHERE key = 'u:2'").unwrap();
+    assert_eq!(result, "SINTERSTORE common:u:1 u:1 u:2");
+    
+    // Test EXCEPT with the destination key projected as a literal
+    let result = transformer.transform("INSERT INTO only__set (key, member) SELECT 'only:u1', member FROM followers__set WHERE key = 'u:1' EXCEPT SELECT 'only:u1', member FROM followers__set WHERE key = 'u:2'").unwrap();
+    assert_eq!(result, "SDIFFSTORE only:u1 u:1 u:2");
+    
+    // Test chained UNIONs and plain copies
+    let result = transformer.transform("INSERT INTO all__set (key, member) SELECT member FROM a__set WHERE key = 'a' UNION SELECT member FROM b__set WHERE key = 'b' UNION SELECT member FROM c__set WHERE key = 'c'").unwrap();
+    assert_eq!(result, "SUNIONSTORE all:a a b c");
+    let result = transformer.transform("INSERT INTO copy__set (key, member) SELECT 'copy', member FROM a__set WHERE key = 'a'").unwrap();
+    assert_eq!(result, "SUNIONSTORE copy a");
+    
+    // Test mixed operators and extra predicates aren't translated
+    assert!(transformer.transform("INSERT INTO x__set (key, member) SELECT member FROM a__set WHERE key = 'a' UNION SELECT member FROM b__set WHERE key = 'b' EXCEPT SELECT member FROM c__set WHERE key = 'c'").is_err());
+    assert!(transformer.transform("INSERT INTO x__set (key, member) SELECT member FROM a__set WHERE key = 'a' AND member = 'm'").is_err());
+}
+#[test]
+fn test_type_copy() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test set -> list copies members in sorted order into {table}:{source key}
+    let result = transformer.transform("INSERT INTO archive__list (key, value) SELECT member FROM old__set WHERE key = 'old:1'").unwrap();
+    assert!(result.starts_with("EVAL "));
+    assert!(result.contains("SMEMBERS") && result.contains("RPUSH"));
+    assert!(result.ends_with(" 2 archive:old:1 old:1 member"));
+    
+    // Test list -> zset with a projected destination key and a constant score
+    let result = transformer.transform("INSERT INTO ranked__zset (key, member, score) SELECT 'ranked', value, 0 FROM queue__list WHERE key = 'q'").unwrap();
+    assert!(result.contains("LRANGE") && result.contains("ZADD"));
+    assert!(result.ends_with(" 2 ranked q value =0"));
+    
+    // Test zset -> set drops the scores
+    let result = transformer.transform("INSERT INTO members__set (key, member) SELECT member FROM scores__zset WHERE key = 'board'").unwrap();
+    assert!(result.contains("ZRANGE") && result.contains("SADD"));
+    assert!(result.ends_with(" 2 members:board board member"));
+    
+    // Test same-type copies keep their native commands
+    let result = transformer.transform("INSERT INTO copy__set (key, member) SELECT 'copy', member FROM a__set WHERE key = 'a'").unwrap();
+    assert_eq!(result, "SUNIONSTORE copy a");
+    
+    // Test unknown source columns and hash sources aren't translated
+    assert!(transformer.transform("INSERT INTO archive__list (key, value) SELECT score FROM old__set WHERE key = 'old:1'").is_err());
+    assert!(transformer.transform("INSERT INTO archive__list (key, value) SELECT name FROM users__hash WHERE key = 'u:1'").is_err());
+}
+
+#[test]
+fn test_set_member_pattern() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test member LIKE scans the set with the LIKE pattern as a glob
+    let result = transformer.transform("SELECT * FROM followers__set WHERE key = 'f' AND member LIKE 'user:10%'").unwrap();
+    assert_eq!(result, "SSCAN f 0 MATCH user:10*");
+    let result = transformer.transform("SELECT * FROM followers__set WHERE member LIKE 'user:1_' AND key = 'f' LIMIT 20").unwrap();
+    assert_eq!(result, "SSCAN f 0 MATCH user:1? COUNT 20");
+    
+    // Test glob metacharacters and escaped LIKE wildcards stay literal
+    let result = transformer.transform("SELECT * FROM followers__set WHERE key = 'f' AND member LIKE 'a*[b]\\%%'").unwrap();
+    assert_eq!(result, "SSCAN f 0 MATCH a\\*\\[b\\]%*");
+    
+    // Test the member pattern is honored rather than dropped
+    let (_, warnings) = transformer.transform_with_report("SELECT * FROM followers__set WHERE key = 'f' AND member LIKE 'user:%'").unwrap();
+    assert!(warnings.iter().all(|warning| warning.kind != sql_redis::WarningKind::DroppedClause));
+    
+    // Test NOT LIKE, or LIKE beside other predicates, filters the members in Lua
+    let command = transformer.transform_to_command("SELECT * FROM followers__set WHERE key = 'f' AND member NOT LIKE 'bot:%'").unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert!(command.args[0].contains("redis.call('SMEMBERS', KEYS[1])"));
+    assert!(command.args[0].contains("if like(field(ARGV[1]), ARGV[2], true) then"));
+    assert_eq!(&command.args[1..], ["1", "f", "member", "^bot:.*$"]);
+}
@@ -0,0 +1,33 @@
+// tests/stream_tests.rs
+use std::cell::Cell;
+
+use sql_redis::{ErrorCode, SqlToRedisTransformer, Step};
+
+#[test]
+fn test_transform_stream() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let input = "SELECT * FROM cache WHERE key = 'a'\n\n-- setup\nSELECT nonsense\nINSERT INTO tags__set (key, member) VALUES ('t', 'x')";
+    let mut plans = transformer.transform_stream(input.lines().map(str::to_string));
+
+    // Test blank and comment lines are skipped, and line numbers follow the input
+    let plan = plans.next().unwrap().unwrap();
+    assert_eq!(plans.line(), 1);
+    assert!(matches!(&plan.steps[..], [Step::Command(command)] if command.to_string() == "GET a"));
+    let error = plans.next().unwrap().unwrap_err();
+    assert_eq!((plans.line(), error.code()), (4, ErrorCode::MissingKeyPredicate));
+    assert!(plans.next().unwrap().is_ok());
+    assert_eq!(plans.line(), 5);
+    assert!(plans.next().is_none());
+}
+
+#[test]
+fn test_transform_stream_is_lazy() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let read = Cell::new(0);
+
+    // Test an endless input is read only as far as plans are taken
+    let lines = std::iter::repeat("SELECT * FROM cache WHERE key = 'a'".to_string()).inspect(|_| read.set(read.get() + 1));
+    let plans: Vec<_> = transformer.transform_stream(lines).take(3).collect();
+    assert_eq!(plans.len(), 3);
+    assert_eq!(read.get(), 3);
+}
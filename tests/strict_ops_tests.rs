@@ -0,0 +1,63 @@
+// tests/strict_ops_tests.rs
+use sql_redis::{SqlRedisError, SqlToRedisTransformer, TransformOptions};
+
+fn dropped(transformer: &SqlToRedisTransformer, sql: &str) -> Vec<String> {
+    match transformer.transform(sql) {
+        Err(SqlRedisError::UnsupportedClause(clauses)) => clauses.into_iter().map(|c| c.sql).collect(),
+        other => panic!("expected UnsupportedClause for {}, got {:?}", sql, other),
+    }
+}
+
+#[test]
+fn test_strict_mode() {
+    let lenient = SqlToRedisTransformer::new().unwrap();
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    
+    // Extra predicates are silently ignored by default...
+    let sql = "SELECT * FROM users__hash WHERE key = 'user:1001' AND age > 30";
+    assert_eq!(lenient.transform(sql).unwrap(), "HGETALL user:1001");
+    // ...and rejected in strict mode
+    assert_eq!(dropped(&strict, sql), vec!["WHERE age > 30"]);
+    
+    // ORDER BY / LIMIT / OFFSET / DISTINCT the command cannot honor
+    assert_eq!(
+        dropped(&strict, "SELECT * FROM leaderboard__zset WHERE key = 'game:global' ORDER BY score ASC LIMIT 5"),
+        vec!["ORDER BY score ASC", "LIMIT 5"]
+    );
+    assert_eq!(dropped(&strict, "SELECT * FROM tasks__list WHERE key = 'q' LIMIT 3 OFFSET 2"), vec!["OFFSET 2"]);
+    assert_eq!(dropped(&strict, "SELECT DISTINCT * FROM tasks__list WHERE key = 'q'"), vec!["DISTINCT"]);
+    
+    // UPDATE and DELETE predicates, including the fallback generator
+    assert_eq!(dropped(&strict, "UPDATE users__hash SET name = 'x' WHERE key = 'user:1' AND age = '3'"), vec!["WHERE age = '3'"]);
+    assert_eq!(dropped(&strict, "DELETE FROM cache WHERE key = 'page:1' AND ttl > 3"), vec!["WHERE ttl > 3"]);
+    
+    // Faithful translations still succeed
+    assert_eq!(strict.transform("SELECT name, age FROM users__hash WHERE key = 'user:1'").unwrap(), "HMGET user:1 name age");
+    assert_eq!(strict.transform("SELECT * FROM tasks__list WHERE key = 'q' LIMIT 3").unwrap(), "LRANGE q 0 2");
+    assert_eq!(strict.transform("SELECT * FROM leaderboard__zset WHERE key = 'g' AND score BETWEEN 1 AND 5").unwrap(), "ZRANGEBYSCORE g 1 5");
+    assert_eq!(strict.transform("SELECT * FROM leaderboard__zset WHERE key = 'g' ORDER BY score DESC").unwrap(), "ZREVRANGEBYSCORE g +inf -inf");
+    assert_eq!(strict.transform("DELETE FROM tags__set WHERE key = 'k' AND member IN ('a', 'b')").unwrap(), "SREM k a b");
+    assert_eq!(strict.transform("INSERT INTO cache (key, value) VALUES ('a', 'b')").unwrap(), "SET a b");
+}
+
+#[test]
+fn test_strict_mode_second_predicate_on_consumed_column() {
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    
+    // A rule honoring a score range doesn't honor other kinds of score predicates
+    assert_eq!(dropped(&strict, "DELETE FROM z__zset WHERE key = 'k' AND score >= 1 AND score IN (3, 4)"), vec!["WHERE score IN (3, 4)"]);
+    assert_eq!(dropped(&strict, "DELETE FROM z__zset WHERE key = 'k' AND score <> 3"), vec!["WHERE score <> 3"]);
+    assert_eq!(dropped(&strict, "SELECT * FROM lb__zset WHERE key = 'g' AND score BETWEEN 1 AND 5 AND score IN (2, 3)"), vec!["WHERE score IN (2, 3)"]);
+    
+    // Nor does one honoring `member = ...` honor `member <> ...`
+    assert_eq!(dropped(&strict, "SELECT * FROM tags__set WHERE key = 'k' AND member = 'a' AND member <> 'b'"), vec!["WHERE member <> 'b'"]);
+    assert_eq!(dropped(&strict, "DELETE FROM tags__set WHERE key = 'k' AND member <> 'b'"), vec!["WHERE member <> 'b'"]);
+    
+    // LIKE next to an equality on the same column
+    assert_eq!(dropped(&strict, "SELECT * FROM cache WHERE key = 'a' AND key LIKE 'b%'"), vec!["WHERE key LIKE 'b%'"]);
+    assert_eq!(dropped(&strict, "DELETE FROM users__hash WHERE key = 'u:1' AND field = 'a' AND field LIKE 'b%'"), vec!["WHERE field LIKE 'b%'"]);
+    
+    // Bounds of one range are all honored
+    assert_eq!(strict.transform("SELECT * FROM lb__zset WHERE key = 'g' AND score > 1 AND 5 > score").unwrap(), "ZRANGEBYSCORE g (1 (5");
+    assert_eq!(strict.transform("SELECT * FROM __keys WHERE key LIKE 'user:%' LIMIT 10").unwrap(), "SCAN 0 MATCH user:* COUNT 10");
+}
@@ -1,5 +1,5 @@
 // tests/string_ops_tests.rs
-use sql_redis::SqlToRedisTransformer;
+use sql_redis::{Profile, SqlToRedisTransformer, TransformOptions};
 
 #[test]
 fn test_string_operations() {
@@ -26,3 +26,29 @@ fn test_string_operations() {
     assert_eq!(result, "DEL user:1001");
 }
 
+#[test]
+fn test_json_string_operations() {
+    let options = TransformOptions::default().with_json_table("users");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    
+    // Test projected fields read through cjson
+    let result = transformer.transform("SELECT name, email FROM users WHERE key = 'u:1'").unwrap();
+    assert!(result.starts_with("EVAL '"), "Expected EVAL, got: {}", result);
+    assert!(result.contains("cjson.decode"));
+    assert!(result.ends_with(" 1 u:1 name email"), "Unexpected arguments: {}", result);
+    
+    // Test whole-value reads and undeclared tables are unchanged
+    let result = transformer.transform("SELECT * FROM users WHERE key = 'u:1'").unwrap();
+    assert_eq!(result, "GET u:1");
+    let result = transformer.transform("SELECT value FROM users WHERE key = 'u:1'").unwrap();
+    assert_eq!(result, "GET u:1");
+    assert!(transformer.transform("SELECT name FROM accounts WHERE key = 'a:1'").is_err());
+    
+    // Test JSON.GET under the redisjson profile
+    let options = TransformOptions::default().with_json_table("users").with_profile(Profile::RedisJson);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    let result = transformer.transform("SELECT name FROM users WHERE key = 'u:1'").unwrap();
+    assert_eq!(result, "JSON.GET u:1 $.name");
+    let commands = transformer.transform_to_commands("SELECT name, email FROM users WHERE key = 'u:1'").unwrap();
+    assert_eq!(commands[0].args, vec!["u:1", "$.name", "$.email"]);
+}
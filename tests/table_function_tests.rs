@@ -0,0 +1,33 @@
+// tests/table_function_tests.rs
+use sql_redis::reply::Reply;
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_table_functions() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test each function reads its key with the command it names
+    assert_eq!(transformer.transform("SELECT * FROM redis_get('user:1')").unwrap(), "GET user:1");
+    assert_eq!(transformer.transform("SELECT * FROM redis_hgetall('user:1')").unwrap(), "HGETALL user:1");
+    assert_eq!(transformer.transform("SELECT * FROM REDIS_HGET('user:1', 'name')").unwrap(), "HGET user:1 name");
+    assert_eq!(transformer.transform("SELECT * FROM redis_lrange('queue', 0, -1)").unwrap(), "LRANGE queue 0 -1");
+    assert_eq!(transformer.transform("SELECT * FROM redis_smembers('tags')").unwrap(), "SMEMBERS tags");
+    assert_eq!(transformer.transform("SELECT * FROM redis_zrangebyscore('lb', 10, 20)").unwrap(), "ZRANGEBYSCORE lb 10 20");
+    assert_eq!(transformer.matched_rule("SELECT * FROM redis_get('user:1')").unwrap(), "string_get");
+
+    // Test keys with spaces stay one argument, and key prefixes apply as to tables
+    let command = transformer.transform_to_command("SELECT * FROM redis_get('my key')").unwrap();
+    assert_eq!(command.args, ["my key"]);
+    let prefixed = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("app:")).unwrap();
+    assert_eq!(prefixed.transform("SELECT * FROM redis_hgetall('user:1')").unwrap(), "HGETALL app:user:1");
+
+    // Test hash reads decode like their table forms
+    let reply = Reply::Array(vec![Reply::bulk("name"), Reply::bulk("Ann")]);
+    let rows = transformer.decode_reply("SELECT * FROM redis_hgetall('user:1')", &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("name".to_string(), "Ann".to_string())]]);
+
+    // Test wrong arities, projections, and unknown functions aren't table-function reads
+    assert!(transformer.transform("SELECT * FROM redis_get('a', 'b')").is_err());
+    assert!(transformer.transform("SELECT name FROM redis_hgetall('user:1')").is_err());
+    assert!(transformer.transform("SELECT * FROM redis_unknown('user:1')").is_err());
+}
@@ -0,0 +1,54 @@
+// tests/template_registration_tests.rs
+use std::sync::Arc;
+use std::thread;
+
+use sql_redis::templates::{TemplateEngine, TemplateError};
+use sql_redis::{ErrorCode, SqlToRedisTransformer};
+
+#[test]
+fn test_add_template() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    assert!(transformer.has_template("hash_getall"));
+    assert!(!transformer.has_template("acme_touch"));
+
+    // Test user templates are added through a shared reference and can be replaced
+    transformer.add_template("acme_touch", "TOUCH {{ key }}").unwrap();
+    assert!(transformer.has_template("acme_touch"));
+    transformer.add_template("acme_touch", "TOUCH {{ key }} {{ other }}").unwrap();
+
+    // Test built-in templates can't be replaced, and translations keep using them
+    let error = transformer.add_template("hash_getall", "HSCAN {{ key }} 0").unwrap_err();
+    assert_eq!(error.code(), ErrorCode::Template);
+    assert!(error.to_string().contains("hash_getall conflicts with a built-in template"), "{}", error);
+    assert_eq!(transformer.transform("SELECT * FROM users__hash WHERE key = 'user:1'").unwrap(), "HGETALL user:1");
+
+    // Test the engine-level check
+    let mut engine = TemplateEngine::new().unwrap();
+    assert!(engine.is_builtin("string_get"));
+    assert!(matches!(engine.add_user_template("string_get", "GET x"), Err(TemplateError::Conflict(name)) if name == "string_get"));
+    assert!(!engine.is_builtin("acme_touch"));
+}
+
+#[test]
+fn test_add_template_concurrently() {
+    let transformer = Arc::new(SqlToRedisTransformer::new().unwrap());
+
+    // Test translations on other threads proceed while templates are registered
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let transformer = Arc::clone(&transformer);
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    assert_eq!(transformer.transform("SELECT * FROM cache WHERE key = 'page:1'").unwrap(), "GET page:1");
+                }
+            })
+        })
+        .collect();
+    for i in 0..50 {
+        transformer.add_template(&format!("acme_{}", i), "TOUCH {{ key }}").unwrap();
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert!((0..50).all(|i| transformer.has_template(&format!("acme_{}", i))));
+}
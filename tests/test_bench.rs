@@ -83,4 +83,31 @@ fn bench_all_operations() {
     
     println!("All operations ({} queries) - {} iterations: {:?} ({:?} per full set)", 
         test_cases.len(), ITERATIONS, duration, duration / ITERATIONS as u32);
-}
\ No newline at end of file
+}
+#[test]
+#[ignore] // Only run these benchmarks when explicitly requested
+fn bench_matchers() {
+    use sql_redis::pattern::combinators::Pattern;
+    use sql_redis::pattern::matchers::{common, select};
+    use sqlparser::ast::Statement;
+    use sqlparser::dialect::GenericDialect;
+    use sqlparser::parser::Parser;
+
+    let stmt = Parser::parse_sql(&GenericDialect {}, "SELECT * FROM users__hash WHERE key = 'user:1001'")
+        .unwrap()
+        .remove(0);
+    let Statement::Query(query) = &stmt else { unreachable!() };
+    let sqlparser::ast::SetExpr::Select(sel) = &*query.body else { unreachable!() };
+    let selection = sel.selection.as_ref().unwrap();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS * 100 {
+        assert!(common::wildcard_select().match_pattern(&&stmt).is_ok());
+        assert!(common::key_equals().match_pattern(&selection).is_ok());
+        assert!(select::is_hash_getall(&stmt));
+    }
+    let duration = start.elapsed();
+
+    println!("Matchers (wildcard_select, key_equals, is_hash_getall) - {} iterations: {:?} ({:?} per set)",
+        ITERATIONS * 100, duration, duration / (ITERATIONS * 100) as u32);
+}
@@ -0,0 +1,65 @@
+// tests/trigger_ops_tests.rs
+use sql_redis::reply::Reply;
+use sql_redis::{ErrorCode, SqlToRedisTransformer, TransformOptions};
+
+#[test]
+fn test_trigger_operations() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test hash writes filtered by key prefix
+    let result = transformer.transform("CREATE TRIGGER audit AFTER INSERT OR UPDATE ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(result, "CONFIG SET notify-keyspace-events Kh\nPSUBSCRIBE __keyspace@0__:user:*");
+    
+    // Test DELETE also subscribes to generic (DEL) events
+    let result = transformer.transform("CREATE TRIGGER purge AFTER DELETE ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(result, "CONFIG SET notify-keyspace-events Kgh\nPSUBSCRIBE __keyspace@0__:user:*");
+    
+    // Test a single watched key on a sorted set
+    let result = transformer.transform("CREATE TRIGGER lb AFTER UPDATE ON leaderboard__zset FOR EACH ROW WHEN (key = 'game:global') EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(result, "CONFIG SET notify-keyspace-events Kz\nPSUBSCRIBE __keyspace@0__:game:global");
+    
+    // Test string tables use the '$' class and LIKE '_' becomes '?'
+    let result = transformer.transform("CREATE TRIGGER s AFTER INSERT ON cache FOR EACH ROW WHEN (key LIKE 'page:_') EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(result, "CONFIG SET notify-keyspace-events K$\nPSUBSCRIBE __keyspace@0__:page:?");
+    
+    // Test a trigger without WHEN is refused rather than watching every key
+    let result = transformer.transform("CREATE TRIGGER purge AFTER DELETE ON users__hash FOR EACH ROW EXECUTE FUNCTION notify()");
+    assert_eq!(result.unwrap_err().code(), ErrorCode::MissingKeyPredicate);
+    
+    // Test a key prefix scopes a trigger without WHEN
+    let prefixed = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("tenant42:")).unwrap();
+    let result = prefixed.transform("CREATE TRIGGER purge AFTER DELETE ON users__hash FOR EACH ROW EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(result, "CONFIG SET notify-keyspace-events Kgh\nPSUBSCRIBE __keyspace@0__:tenant42:*");
+    
+    // BEFORE triggers cannot be honored by notifications
+    let result = transformer.transform("CREATE TRIGGER b BEFORE INSERT ON users__hash FOR EACH ROW EXECUTE FUNCTION notify()");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_keyspace_event_decoding() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "CREATE TRIGGER audit AFTER INSERT ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()";
+    
+    let message = Reply::Array(vec![
+        Reply::bulk("pmessage"),
+        Reply::bulk("__keyspace@0__:user:*"),
+        Reply::bulk("__keyspace@0__:user:1001"),
+        Reply::bulk("hset"),
+    ]);
+    let rows = transformer.decode_reply(sql, &message).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![
+        ("db".to_string(), "0".to_string()),
+        ("key".to_string(), "user:1001".to_string()),
+        ("event".to_string(), "hset".to_string()),
+    ]]);
+    
+    // Subscription confirmations produce no rows
+    let confirmation = Reply::Array(vec![
+        Reply::bulk("psubscribe"),
+        Reply::bulk("__keyspace@0__:user:*"),
+        Reply::Integer(1),
+    ]);
+    let rows = transformer.decode_reply(sql, &confirmation).unwrap().unwrap();
+    assert!(rows.is_empty());
+}
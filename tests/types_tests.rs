@@ -0,0 +1,41 @@
+// tests/types_tests.rs
+//! Table suffixes select Redis data types through one mapping
+
+use sql_redis::{redis_type_of, RedisDataType, TypeMapping};
+
+#[test]
+fn test_standard_suffixes() {
+    let mapping = TypeMapping::standard();
+    assert_eq!(redis_type_of("users__hash", mapping), RedisDataType::Hash);
+    assert_eq!(redis_type_of("feed__list", mapping), RedisDataType::List);
+    assert_eq!(redis_type_of("tags__set", mapping), RedisDataType::Set);
+    assert_eq!(redis_type_of("scores__zset", mapping), RedisDataType::SortedSet);
+    assert_eq!(redis_type_of("users", mapping), RedisDataType::String);
+    assert_eq!(redis_type_of("hash", mapping), RedisDataType::String);
+}
+
+#[test]
+fn test_suffix_round_trips() {
+    for data_type in RedisDataType::ALL {
+        let table = format!("t{}", data_type.suffix().unwrap_or(""));
+        assert_eq!(redis_type_of(&table, TypeMapping::standard()), data_type);
+    }
+}
+
+#[test]
+fn test_custom_suffix() {
+    let mapping = TypeMapping::new()
+        .with_suffix("_h", RedisDataType::Hash)
+        .with_suffix("__set", RedisDataType::List);
+    assert_eq!(redis_type_of("users_h", &mapping), RedisDataType::Hash);
+    assert_eq!(redis_type_of("queue__set", &mapping), RedisDataType::List);
+    assert_eq!(redis_type_of("scores__zset", &mapping), RedisDataType::SortedSet);
+    assert_eq!(redis_type_of("users_h", TypeMapping::standard()), RedisDataType::String);
+}
+
+#[test]
+fn test_collection_columns() {
+    assert_eq!(RedisDataType::SortedSet.columns(), Some(&["member", "score"][..]));
+    assert_eq!(RedisDataType::List.columns(), Some(&["value"][..]));
+    assert_eq!(RedisDataType::Hash.columns(), None);
+}
@@ -1,5 +1,7 @@
 // tests/update_ops_tests.rs
-use sql_redis::SqlToRedisTransformer;
+use sql_redis::commands::RedisCommand;
+use sql_redis::reply::Reply;
+use sql_redis::{ErrorCode, Plan, SqlToRedisTransformer, Step, TransformOptions};
 
 #[test]
 fn test_update_operations() {
@@ -24,4 +26,79 @@ fn test_update_operations() {
     // Test sorted set update
     let result = transformer.transform("UPDATE leaderboard__zset SET score = '3000' WHERE key = 'game:global' AND member = 'user:1001'").unwrap();
     assert_eq!(result, "ZADD game:global 3000 user:1001");
-}
\ No newline at end of file
+}
+#[test]
+fn test_update_plan() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let bulk = |s: &str| Reply::bulk(s);
+    let command = |name: &str, args: &[&str]| Step::Command(RedisCommand::new(name, args.to_vec()));
+    
+    // A string update computed from the value: watch, read, then SET inside MULTI/EXEC
+    let plan = transformer.update_plan("UPDATE greetings SET value = '>' || value WHERE key = 'hi'").unwrap();
+    assert_eq!(plan.steps[..4], [command("WATCH", &["hi"]), command("GET", &["hi"]), Step::Barrier, command("MULTI", &[])]);
+    assert_eq!(plan.steps[5], command("EXEC", &[]));
+    let Step::ClientCompute(compute) = &plan.steps[4] else { panic!("expected a client-side computation") };
+    assert_eq!(compute.inputs, [1]);
+    assert_eq!(compute.assignments, [("value".to_string(), "'>' || value".to_string())]);
+    let read = RedisCommand::new("GET", vec!["hi"]);
+    assert_eq!(compute.commands(&[(read.clone(), bulk("hello"))]), [RedisCommand::new("SET", vec!["hi", ">hello"])]);
+    // NULL || '>' is NULL: a missing value is deleted, not written
+    assert_eq!(compute.commands(&[(read, Reply::Nil)]), [RedisCommand::new("DEL", vec!["hi"])]);
+    assert_eq!(plan.to_string(), "0. WATCH hi\n1. GET hi\n2. barrier\n3. MULTI\n4. compute from 1: value = '>' || value\n   SET hi {value}\n5. EXEC");
+    
+    // Hash updates across keys read the columns the assignments use, once per key
+    let plan = transformer.update_plan("UPDATE users__hash SET name = UPPER(name), tag = nick || '!', age = '30' WHERE key IN ('user:1', 'user:2')").unwrap();
+    assert_eq!(plan.steps[0], command("WATCH", &["user:1", "user:2"]));
+    assert_eq!(plan.steps[2], command("HMGET", &["user:2", "name", "nick"]));
+    let Step::ClientCompute(compute) = &plan.steps[5] else { panic!("expected a client-side computation") };
+    let reads = plan.commands();
+    assert_eq!(compute.commands(&[
+        (reads[1].clone(), Reply::Array(vec![bulk("ann"), bulk("a")])),
+        (reads[2].clone(), Reply::Array(vec![bulk("bob"), Reply::Nil])),
+    ]), [
+        RedisCommand::new("HSET", vec!["user:1", "name", "ANN", "tag", "a!", "age", "30"]),
+        RedisCommand::new("HSET", vec!["user:2", "name", "BOB", "age", "30"]),
+        RedisCommand::new("HDEL", vec!["user:2", "tag"]),
+    ]);
+    
+    // Plans address keys as configured for the table; a database SELECT comes first
+    let prefixed = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("app:").with_table_database("users__hash", 2)).unwrap();
+    let plan = prefixed.update_plan("UPDATE users__hash SET name = TRIM(name) WHERE key = 'user:1'").unwrap();
+    assert_eq!(plan.steps[..3], [command("SELECT", &["2"]), command("WATCH", &["app:user:1"]), command("HMGET", &["app:user:1", "name"])]);
+    let Step::ClientCompute(compute) = &plan.steps[5] else { panic!("expected a client-side computation") };
+    assert_eq!(compute.inputs, [2]);
+    
+    // Updates that don't read what they write, or address keys otherwise, have no plan
+    for sql in [
+        "UPDATE users__hash SET name = 'Ann' WHERE key = 'user:1'",
+        "UPDATE greetings SET value = UPPER(other) WHERE key = 'hi'",
+        "UPDATE users__hash SET name = UPPER(name) WHERE key = 'user:1' AND age > 3",
+        "UPDATE lb__zset SET score = score WHERE key = 'g' AND member = 'm'",
+    ] {
+        assert_eq!(transformer.update_plan(sql).unwrap_err().code(), ErrorCode::NotPlannable, "{}", sql);
+    }
+    assert_eq!(ErrorCode::NotPlannable.as_str(), "E0015");
+}
+
+#[test]
+fn test_plans() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Translations become command steps, scripts eval steps
+    let plan = transformer.plan("SELECT * FROM users WHERE key IN ('a', 'b')").unwrap();
+    assert_eq!(plan, Plan::new(vec![Step::Command(RedisCommand::new("MGET", vec!["a", "b"]))]));
+    let plan = transformer.plan("UPDATE users__hash SET name = UPPER(name) WHERE key = 'user:1'").unwrap();
+    let Step::Eval { keys, args, .. } = &plan.steps[0] else { panic!("expected an eval step") };
+    assert_eq!((keys.as_slice(), args.as_slice()), (["user:1".to_string()].as_slice(), ["name".to_string(), "name".to_string()].as_slice()));
+    assert_eq!(plan.steps[0].to_command(), Some(transformer.transform_to_command("UPDATE users__hash SET name = UPPER(name) WHERE key = 'user:1'").unwrap()));
+    
+    // Updates no rule translates fall back to their WATCH/MULTI plan
+    let plan = transformer.plan("UPDATE greetings SET value = value || '!' WHERE key = 'hi'").unwrap();
+    assert_eq!(plan.steps.len(), 6);
+    assert_eq!(plan.to_json(), concat!(
+        r#"{"steps":[{"type":"command","command":"WATCH","args":["hi"]},{"type":"command","command":"GET","args":["hi"]},"#,
+        r#"{"type":"barrier"},{"type":"command","command":"MULTI","args":[]},"#,
+        r#"{"type":"client_compute","inputs":[1],"assignments":{"value":"value || '!'"}},{"type":"command","command":"EXEC","args":[]}]}"#,
+    ));
+    assert_eq!(transformer.plan("UPDATE greetings SET value = 'x' WHERE owner = 'me'").unwrap_err().code(), ErrorCode::MissingKeyPredicate);
+}
@@ -0,0 +1,42 @@
+// tests/variables_tests.rs
+use sql_redis::variables::Variables;
+use sql_redis::SqlToRedisTransformer;
+
+#[test]
+fn test_session_variables() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let mut variables = Variables::new();
+
+    assert!(variables.assign("SET @prefix = 'tenant42';").unwrap());
+    assert!(variables.assign("set @limit = 10").unwrap());
+    // Values may reference earlier variables
+    assert!(variables.assign("SET @user = '@prefix:user:1001'").unwrap());
+    assert_eq!(variables.get("user"), Some("tenant42:user:1001"));
+    assert_eq!(variables.names().collect::<Vec<_>>(), vec!["limit", "prefix", "user"]);
+
+    // Test references inside literals take the raw value, bare ones a literal
+    let sql = variables.expand("SELECT * FROM users__hash WHERE key = @user");
+    assert_eq!(sql, "SELECT * FROM users__hash WHERE key = 'tenant42:user:1001'");
+    assert_eq!(transformer.transform(&sql).unwrap(), "HGETALL tenant42:user:1001");
+    assert_eq!(
+        variables.expand("SELECT * FROM lb__zset WHERE key = '@prefix:lb' LIMIT @limit"),
+        "SELECT * FROM lb__zset WHERE key = 'tenant42:lb' LIMIT 10"
+    );
+
+    // Test undefined names and longer names are left alone
+    assert_eq!(variables.expand("WHERE email = 'ann@example.com' AND key = @prefix2"), "WHERE email = 'ann@example.com' AND key = @prefix2");
+
+    // Test quotes in values are escaped where they are substituted
+    variables.set("name", "O'Brien");
+    assert_eq!(variables.expand("VALUES (@name, 'Mr @name')"), "VALUES ('O''Brien', 'Mr O''Brien')");
+    assert!(variables.assign("SET @quoted = 'it''s'").unwrap());
+    assert_eq!(variables.get("quoted"), Some("it's"));
+
+    // Test other statements aren't assignments, and malformed assignments are errors
+    assert!(!variables.assign("SELECT * FROM users__hash WHERE key = 'u:1'").unwrap());
+    assert!(!variables.assign("SET search_path = app").unwrap());
+    assert!(variables.assign("SET @x").is_err());
+    assert!(variables.assign("SET @x = 'unterminated").is_err());
+    assert!(variables.assign("SET @x = two words").is_err());
+    assert!(variables.assign("SET @bad-name = 1").is_err());
+}
@@ -0,0 +1,49 @@
+// tests/view_tests.rs
+use sql_redis::commands::RedisCommand;
+use sql_redis::lua::VIEW_REFRESH_SCRIPT;
+use sql_redis::{SqlToRedisTransformer, TransformOptions};
+
+const TOP10: &str = "CREATE VIEW top10 AS SELECT * FROM scores__zset WHERE key = 'game:1' ORDER BY score DESC LIMIT 10";
+
+#[test]
+fn test_view_materialized_as_list() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+
+    // Test views are unknown until defined
+    assert!(transformer.transform("SELECT * FROM top10").is_err());
+
+    // Test CREATE VIEW refreshes the derived list with a Lua script
+    let refresh = transformer.transform_to_command(TOP10).unwrap();
+    assert_eq!(
+        refresh,
+        RedisCommand::new("EVAL", vec![VIEW_REFRESH_SCRIPT, "2", "view:top10", "game:1", "ZREVRANGE", "9"]),
+    );
+    assert_eq!(transformer.refresh_view("TOP10"), Some(refresh));
+    assert_eq!(transformer.catalog().views().len(), 1);
+
+    // Test SELECT from the view reads the derived key
+    assert_eq!(transformer.transform("SELECT * FROM top10").unwrap(), "LRANGE view:top10 0 -1");
+
+    // Test ascending views read from the bottom of the sorted set
+    transformer.transform(
+        "CREATE VIEW bottom3 AS SELECT * FROM scores__zset WHERE key = 'game:1' ORDER BY score LIMIT 3",
+    ).unwrap();
+    assert_eq!(transformer.refresh_view("bottom3").unwrap().args[4..], ["ZRANGE", "2"]);
+
+    // Test views need a keyed, score-ordered, limited sorted set read
+    assert!(transformer.transform("CREATE VIEW v AS SELECT * FROM scores__zset WHERE key = 'game:1' ORDER BY score").is_err());
+    assert!(transformer.transform("CREATE VIEW v AS SELECT * FROM users__hash WHERE key = 'u:1' ORDER BY score LIMIT 1").is_err());
+    assert!(transformer.catalog().view("v").is_none());
+}
+
+#[test]
+fn test_view_materialized_with_zrangestore() {
+    let options = TransformOptions::default()
+        .with_redis_version(6, 2)
+        .with_key_prefix("tenant42:");
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+
+    // Test Redis 6.2+ stores the view with ZRANGESTORE, prefixing both keys
+    assert_eq!(transformer.transform(TOP10).unwrap(), "ZRANGESTORE tenant42:view:top10 tenant42:game:1 0 9 REV");
+    assert_eq!(transformer.transform("SELECT * FROM top10").unwrap(), "ZREVRANGE tenant42:view:top10 0 -1");
+}
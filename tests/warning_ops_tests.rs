@@ -0,0 +1,61 @@
+// tests/warning_ops_tests.rs
+use sql_redis::{SqlToRedisTransformer, Warning, WarningKind};
+
+fn kinds(warnings: &[Warning]) -> Vec<WarningKind> {
+    warnings.iter().map(|w| w.kind).collect()
+}
+
+#[test]
+fn test_transform_warnings() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Dropped clauses come first, then the rule's own warnings
+    let (command, warnings) = transformer.transform_with_report("SELECT * FROM users__hash WHERE key = 'user:1001' AND age > 30").unwrap();
+    assert_eq!(command, "HGETALL user:1001");
+    assert_eq!(kinds(&warnings), vec![WarningKind::DroppedClause, WarningKind::FullScan]);
+    assert_eq!(warnings[0].to_string(), "warning[dropped-clause]: WHERE age > 30 is ignored");
+    
+    // Exact translations have no warnings
+    let (command, warnings) = transformer.transform_with_report("SELECT * FROM cache WHERE key = 'page:1'").unwrap();
+    assert_eq!(command, "GET page:1");
+    assert!(warnings.is_empty());
+    
+    // Full scans, including Lua aggregates
+    let (_, warnings) = transformer.transform_with_report("SELECT AVG(score) FROM leaderboard__zset WHERE key = 'game:global'").unwrap();
+    assert_eq!(kinds(&warnings), vec![WarningKind::FullScan]);
+    
+    // Implicit coercions
+    let (_, warnings) = transformer.transform_with_report("INSERT INTO leaderboard__zset (key, member, score) VALUES ('game:global', 'player1', '100')").unwrap();
+    assert_eq!(kinds(&warnings), vec![WarningKind::TypeCoercion]);
+    
+    // Client-side evaluation requirements
+    let (_, warnings) = transformer.transform_with_report("CREATE TRIGGER t AFTER INSERT ON users__hash FOR EACH ROW WHEN (key LIKE 'user:%') EXECUTE FUNCTION notify()").unwrap();
+    assert_eq!(kinds(&warnings), vec![WarningKind::ClientSideEval]);
+    
+    // Fallback-generated commands still report dropped clauses
+    let (command, warnings) = transformer.transform_with_report("DELETE FROM cache WHERE key = 'page:1' AND ttl > 3").unwrap();
+    assert_eq!(command, "DEL page:1");
+    assert_eq!(kinds(&warnings), vec![WarningKind::DroppedClause]);
+}
+
+#[test]
+fn test_rewrite_suggestions() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    let suggestion = transformer.suggest_rewrite("SELECT * FROM followers__set WHERE key = 'u:1:followers'").unwrap().unwrap();
+    assert_eq!(suggestion.sql, "SELECT * FROM followers__set WHERE key = 'u:1:followers' LIMIT 100");
+    assert_eq!(suggestion.command, "SSCAN u:1:followers 0 COUNT 100");
+    
+    let suggestion = transformer.suggest_rewrite("SELECT * FROM users__hash WHERE key = 'user:1001'").unwrap().unwrap();
+    assert_eq!(suggestion.command, "HSCAN user:1001 0 COUNT 100");
+    
+    let suggestion = transformer.suggest_rewrite("SELECT * FROM posts__list WHERE key = 'u:1:posts'").unwrap().unwrap();
+    assert_eq!(suggestion.command, "LRANGE u:1:posts 0 99");
+    
+    let suggestion = transformer.suggest_rewrite("SELECT * FROM leaderboard__zset WHERE key = 'game:global'").unwrap().unwrap();
+    assert_eq!(suggestion.to_string(), "help: rewrite as SELECT * FROM leaderboard__zset WHERE key = 'game:global' LIMIT 100\n  => ZRANGEBYSCORE game:global -inf +inf LIMIT 0 100");
+    
+    // Bounded reads and already paginated statements have nothing to suggest
+    assert!(transformer.suggest_rewrite("SELECT name FROM users__hash WHERE key = 'user:1001'").unwrap().is_none());
+    assert!(transformer.suggest_rewrite("SELECT * FROM followers__set WHERE key = 'u:1:followers' LIMIT 10").unwrap().is_none());
+}
@@ -1,5 +1,6 @@
 // tests/zset_ops_tests.rs
-use sql_redis::SqlToRedisTransformer;
+use sql_redis::reply::Reply;
+use sql_redis::{SqlToRedisTransformer, TransformOptions, WarningKind};
 
 #[test]
 fn test_zset_operations() {
@@ -9,6 +10,10 @@ fn test_zset_operations() {
     let result = transformer.transform("SELECT * FROM leaderboard__zset WHERE key = 'game:global'").unwrap();
     assert_eq!(result, "ZRANGEBYSCORE game:global -inf +inf");
     
+    // Test a LIMIT-ed read returns the lowest-scored page
+    let result = transformer.transform("SELECT * FROM leaderboard__zset WHERE key = 'game:global' LIMIT 10").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE game:global -inf +inf LIMIT 0 10");
+    
     // Test zset ZRANGEBYSCORE with score range
     let result = transformer.transform("SELECT * FROM leaderboard__zset WHERE key = 'game:global' AND score > 1000").unwrap();
     assert_eq!(result, "ZRANGEBYSCORE game:global (1000 +inf");
@@ -32,5 +37,378 @@ fn test_zset_operations() {
     // Test zset member delete
     let result = transformer.transform("DELETE FROM leaderboard__zset WHERE key = 'game:global' AND member = 'user:1001'").unwrap();
     assert_eq!(result, "ZREM game:global user:1001");
+    
+    // Test multi-member delete in a single ZREM
+    let result = transformer.transform("DELETE FROM leaderboard__zset WHERE key = 'game:global' AND member IN ('user:1001', 'user:1002')").unwrap();
+    assert_eq!(result, "ZREM game:global user:1001 user:1002");
+    
+    // Test multi-row insert in a single ZADD
+    let result = transformer.transform("INSERT INTO leaderboard__zset (key, member, score) VALUES ('game:global', 'user:1001', '2500'), ('game:global', 'user:1002', '1800')").unwrap();
+    assert_eq!(result, "ZADD game:global 2500 user:1001 1800 user:1002");
+    
+    // Rows for different keys cannot share one ZADD
+    let result = transformer.transform("INSERT INTO leaderboard__zset (key, member, score) VALUES ('game:a', 'user:1001', '1'), ('game:b', 'user:1002', '2')");
+    assert!(result.is_err());
+}
+
+
+#[test]
+fn test_zset_pop_operations() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test lowest-score pop (LIMIT 1 is the command default)
+    let result = transformer.transform("DELETE FROM jobs__zset WHERE key = 'queue' ORDER BY score ASC LIMIT 1 RETURNING member, score").unwrap();
+    assert_eq!(result, "ZPOPMIN queue");
+    
+    // Test highest-score pop with a count
+    let result = transformer.transform("DELETE FROM jobs__zset WHERE key = 'queue' ORDER BY score DESC LIMIT 3 RETURNING member, score").unwrap();
+    assert_eq!(result, "ZPOPMAX queue 3");
+    
+    // Test RETURNING in the position the parser expects, and without RETURNING
+    let result = transformer.transform("DELETE FROM jobs__zset WHERE key = 'queue' RETURNING member ORDER BY score LIMIT 2").unwrap();
+    assert_eq!(result, "ZPOPMIN queue 2");
+    let result = transformer.transform("DELETE FROM jobs__zset WHERE key = 'queue' ORDER BY score DESC LIMIT 1").unwrap();
+    assert_eq!(result, "ZPOPMAX queue");
+    
+    // Test other predicates aren't dropped by popping the lowest or highest scores
+    let result = transformer.transform("DELETE FROM z__zset WHERE key = 'k' AND score > 50 ORDER BY score LIMIT 2 RETURNING member");
+    assert!(!result.is_ok_and(|command| command.starts_with("ZPOP")));
+    let result = transformer.transform("DELETE FROM z__zset WHERE key = 'k' AND member LIKE 'job:%' ORDER BY score DESC LIMIT 1");
+    assert!(!result.is_ok_and(|command| command.starts_with("ZPOP")));
+    
+    // A LIMIT without a score ordering must not delete the whole key
+    let result = transformer.transform("DELETE FROM jobs__zset WHERE key = 'queue' LIMIT 2");
+    assert!(result.is_err());
+    
+    // Test popped pairs decode into (member, score) rows
+    let reply = Reply::Array(vec![
+        Reply::bulk("job:1"),
+        Reply::bulk("10"),
+        Reply::bulk("job:2"),
+        Reply::bulk("20"),
+    ]);
+    let rows = transformer.decode_reply("DELETE FROM jobs__zset WHERE key = 'queue' ORDER BY score LIMIT 2 RETURNING member, score", &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![
+        vec![("member".to_string(), "job:1".to_string()), ("score".to_string(), "10".to_string())],
+        vec![("member".to_string(), "job:2".to_string()), ("score".to_string(), "20".to_string())],
+    ]);
+}
+
+#[test]
+fn test_zset_retention_operations() {
+    let options = TransformOptions::default().with_now(1_700_000_000);
+    let transformer = SqlToRedisTransformer::with_options(options).unwrap();
+    
+    // Test NOW() folding into a score range delete
+    let result = transformer.transform("DELETE FROM events__zset WHERE key = 'events' AND score < NOW() - 86400").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE events -inf (1699913600");
+    
+    // Test constant arithmetic and the other NOW() spellings
+    let result = transformer.transform("DELETE FROM events__zset WHERE key = 'events' AND score <= (UNIX_TIMESTAMP() - 3600) * 1000").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE events -inf 1699996400000");
+    let result = transformer.transform("DELETE FROM events__zset WHERE key = 'events' AND score < CURRENT_TIMESTAMP").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE events -inf (1700000000");
+    
+    // Test plain score ranges
+    let result = transformer.transform("DELETE FROM events__zset WHERE key = 'events' AND score BETWEEN 10 AND 20").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE events 10 20");
+    let result = transformer.transform("DELETE FROM events__zset WHERE key = 'events' AND score >= 100").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE events 100 +inf");
+    
+    // Test folding also applies to reads
+    let result = transformer.transform("SELECT * FROM events__zset WHERE key = 'events' AND score > NOW() - 60").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE events (1699999940 +inf");
+}
+
+#[test]
+fn test_zset_range_store() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test score ranges store BYSCORE into {table}:{source key}
+    let result = transformer.transform("INSERT INTO top__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb:1' AND score > 1000").unwrap();
+    assert_eq!(result, "ZRANGESTORE top:lb:1 lb:1 (1000 +inf BYSCORE");
+    
+    // Test top-N by rank with the destination key projected as a literal
+    let result = transformer.transform("INSERT INTO top__zset (key, member, score) SELECT 'top10', member, score FROM lb__zset WHERE key = 'lb:1' ORDER BY score DESC LIMIT 10").unwrap();
+    assert_eq!(result, "ZRANGESTORE top10 lb:1 0 9 REV");
+    
+    // Test reversed score ranges swap their bounds and page with LIMIT
+    let result = transformer.transform("INSERT INTO top__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb:1' AND score BETWEEN 5 AND 10 ORDER BY score DESC LIMIT 3").unwrap();
+    assert_eq!(result, "ZRANGESTORE top:lb:1 lb:1 10 5 BYSCORE REV LIMIT 0 3");
+    
+    // Test whole-set copies
+    let result = transformer.transform("INSERT INTO backup__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb:1'").unwrap();
+    assert_eq!(result, "ZRANGESTORE backup:lb:1 lb:1 0 -1");
+    
+    // Test predicates ZRANGESTORE can't honor aren't translated
+    assert!(transformer.transform("INSERT INTO top__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb:1' AND member = 'm'").is_err());
+    assert!(transformer.transform("INSERT INTO top__zset (key, member, score) SELECT member, score FROM lb__zset WHERE key = 'lb:1' ORDER BY member").is_err());
 }
 
+#[test]
+fn test_zset_top_per_group() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "SELECT * FROM scores__zset WHERE key IN (SELECT member FROM groups__set WHERE key = 'g') ORDER BY score DESC LIMIT 3";
+    
+    // One EVAL reads the group set and the top members of each zset it names
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert!(command.args[0].contains("redis.call('SMEMBERS', KEYS[1])"));
+    assert_eq!(command.args[1..], ["1", "g", "ZREVRANGEBYSCORE", "3", "+inf", "-inf", "*"]);
+    assert_eq!(transformer.matched_rule(sql).unwrap(), "zset_top_per_group");
+    
+    // The subquery predicate is honored: no dropped-clause warning, and strict mode accepts it
+    let (_, warnings) = transformer.transform_with_report(sql).unwrap();
+    assert!(warnings.iter().all(|warning| warning.kind != WarningKind::DroppedClause), "{:?}", warnings);
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    assert!(strict.transform(sql).is_ok());
+    
+    // A key prefix reaches the zsets the set names, not just the set
+    let tenant = SqlToRedisTransformer::with_options(TransformOptions::default().with_key_prefix("t1:")).unwrap();
+    let command = tenant.transform_to_command(sql).unwrap();
+    assert_eq!(command.args[1..], ["1", "t1:g", "ZREVRANGEBYSCORE", "3", "+inf", "-inf", "t1:*"]);
+    
+    // Ascending order reads the lowest scores
+    let ascending = transformer.transform_to_command(&sql.replace("DESC", "ASC")).unwrap();
+    assert_eq!(ascending.args[1..], ["1", "g", "ZRANGEBYSCORE", "3", "-inf", "+inf", "*"]);
+    
+    // Replies decode to one row per member, tagged with its zset
+    let bulk = |s: &str| Reply::bulk(s);
+    let reply = Reply::Array(vec![
+        Reply::Array(vec![bulk("team:a"), Reply::Array(vec![bulk("ann"), bulk("90"), bulk("bob"), bulk("70")])]),
+        Reply::Array(vec![bulk("team:b"), Reply::Array(vec![bulk("cy"), bulk("85")])]),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2], vec![
+        ("key".to_string(), "team:b".to_string()),
+        ("member".to_string(), "cy".to_string()),
+        ("score".to_string(), "85".to_string()),
+    ]);
+    
+    // Without a LIMIT there is no top-K to take per group
+    assert!(transformer.transform(&sql.replace(" LIMIT 3", "")).is_err());
+}
+
+#[test]
+fn test_zset_hash_join() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let sql = "SELECT l.member, l.score, u.name FROM leaderboard__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'game:1' ORDER BY l.score DESC LIMIT 10";
+    
+    // One EVAL walks the sorted set and reads the projected fields of each member's hash
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert_eq!(command.command, "EVAL");
+    assert!(command.args[0].contains("redis.call('HMGET', key, unpack(fields))"));
+    assert_eq!(command.args[1..], ["1", "game:1", "ZREVRANGE", "0", "10", "inner", "*", "10000", "3", "member", "@member", "score", "@score", "name", "name"]);
+    assert_eq!(transformer.matched_rule(sql).unwrap(), "zset_hash_join");
+    let (_, warnings) = transformer.transform_with_report(sql).unwrap();
+    assert!(warnings.iter().all(|warning| warning.kind != sql_redis::WarningKind::DroppedClause));
+    
+    // LEFT JOIN keeps members without a hash; aliases name the columns, and the joined id
+    // column is the member itself
+    let command = transformer.transform_to_command(
+        "SELECT l.member AS player, u.id, u.email FROM lb__zset l LEFT OUTER JOIN users__hash u ON l.member = u.id WHERE l.key = 'g' LIMIT 5 OFFSET 5"
+    ).unwrap();
+    assert_eq!(command.args[1..], ["1", "g", "ZRANGE", "5", "5", "left", "*", "10000", "3", "player", "@member", "id", "#member", "email", "email"]);
+    
+    // A key prefix concatenated to the member names the hash
+    let command = transformer.transform_to_command(
+        "SELECT member, name, u.key FROM lb__zset l JOIN users__hash u ON u.key = 'user:' || l.member WHERE l.key = 'g'"
+    ).unwrap();
+    assert_eq!(command.args[2..10], ["g", "ZRANGE", "0", "-1", "inner", "user:*", "10000", "3"]);
+    assert_eq!(command.args[12..], ["name", "name", "key", "#key"]);
+    
+    // Every row carries every column; NULLs (nil padding for a missing hash) decode as
+    // absent columns
+    let bulk = |s: &str| Reply::bulk(s);
+    let reply = Reply::Array(vec![
+        Reply::Array(vec![bulk("member"), bulk("ann"), bulk("score"), bulk("90"), bulk("name"), bulk("Ann")]),
+        Reply::Array(vec![bulk("member"), bulk("bob"), bulk("score"), bulk("70"), bulk("name"), Reply::Nil]),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][2], ("name".to_string(), "Ann".to_string()));
+    assert_eq!(rows[1], vec![("member".to_string(), "bob".to_string()), ("score".to_string(), "70".to_string())]);
+    let script = &command.args[0];
+    assert!(script.contains("value = values[f] or false") && script.contains("value = exists and member"));
+    
+    // Without a LIMIT the script walks at most the member cap: a warning, or an error in
+    // strict mode; the cap is configurable
+    let unlimited = "SELECT member, name FROM lb__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'g'";
+    let (_, warnings) = transformer.transform_with_report(unlimited).unwrap();
+    assert!(warnings.iter().any(|warning| warning.message.contains("no LIMIT: it walks up to 10000 sorted set members")));
+    let (_, warnings) = transformer.transform_with_report(sql).unwrap();
+    assert!(!warnings.iter().any(|warning| warning.message.contains("no LIMIT")));
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    assert_eq!(strict.transform(unlimited).unwrap_err().code(), sql_redis::ErrorCode::UnsupportedJoin);
+    assert!(strict.transform(sql).is_ok());
+    let capped = SqlToRedisTransformer::with_options(TransformOptions::default().with_max_join_members(500)).unwrap();
+    assert_eq!(capped.transform_to_command(sql).unwrap().args[8], "500");
+    assert!(capped.transform(sql).unwrap().contains(" inner * 500 3 member "));
+    assert!(script.contains("if walked == cap then"));
+    
+    // Wildcards: l.* is the member and score, u.* (#*) every field of the hash as its own
+    // column, and * both
+    let wildcards = |projection: &str| transformer.transform_to_command(&format!(
+        "SELECT {} FROM lb__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'g' LIMIT 5", projection
+    )).unwrap().args[9..].to_vec();
+    assert_eq!(wildcards("u.*, l.score"), ["2", "*", "#*", "score", "@score"]);
+    assert_eq!(wildcards("l.*, u.name"), ["3", "member", "@member", "score", "@score", "name", "name"]);
+    assert_eq!(wildcards("*"), ["3", "member", "@member", "score", "@score", "*", "#*"]);
+    assert!(script.contains("local all = exists and redis.call('HGETALL', key) or {}"));
+    let sql = "SELECT l.member, u.* FROM lb__zset l JOIN users__hash u ON u.key = l.member WHERE l.key = 'g' LIMIT 5";
+    let reply = Reply::Array(vec![
+        Reply::Array(vec![bulk("member"), bulk("ann"), bulk("name"), bulk("Ann"), bulk("age"), bulk("31")]),
+    ]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows[0][1..], [("name".to_string(), "Ann".to_string()), ("age".to_string(), "31".to_string())]);
+    
+    // Other join types, predicates, orderings, and shapes fail with the reason
+    let join = "SELECT l.member, u.name FROM lb__zset l JOIN users__hash u ON u.key = l.member";
+    for (sql, reason) in [
+        (format!("{} WHERE l.key = 'g' AND u.age > 3", join), "a join's WHERE clause must be exactly l.key = 'value'"),
+        (format!("{} WHERE l.key = 'g' ORDER BY u.name", join), "a join can only be ordered by l.score"),
+        (join.replace("l.member, u.name", "UPPER(u.name)") + " WHERE l.key = 'g'", "UPPER(u.name) can't be projected from a join; name l.member, l.score, l.*, or hash fields"),
+        (join.replace("l.member, u.name", "x.*") + " WHERE l.key = 'g'", "x.* can't be projected from a join; name l.member, l.score, l.*, or hash fields"),
+        (join.replace("= l.member", "= l.score") + " WHERE l.key = 'g'", "the ON condition must equate a hash column with l.member (or 'prefix' || l.member)"),
+        (join.replace(" JOIN", " RIGHT JOIN") + " WHERE l.key = 'g'", "RIGHT JOIN isn't supported; only [INNER] JOIN and LEFT [OUTER] JOIN are"),
+        (join.replace(" JOIN", " FULL OUTER JOIN") + " WHERE l.key = 'g'", "FULL OUTER JOIN isn't supported; only [INNER] JOIN and LEFT [OUTER] JOIN are"),
+        ("SELECT * FROM lb__zset l CROSS JOIN users__hash u WHERE l.key = 'g'".to_string(), "CROSS JOIN isn't supported; only [INNER] JOIN and LEFT [OUTER] JOIN are"),
+        ("SELECT u.name FROM users__hash u JOIN lb__zset l ON u.key = l.member WHERE l.key = 'g'".to_string(), "users__hash JOIN lb__zset: only a sorted set (__zset) joined to hashes (__hash) is supported"),
+        ("SELECT * FROM lb__zset l, users__hash u WHERE l.key = 'g'".to_string(), "comma-separated tables aren't supported; join them with JOIN ... ON"),
+    ] {
+        let err = transformer.transform(&sql).unwrap_err();
+        assert_eq!(err.code(), sql_redis::ErrorCode::UnsupportedJoin, "{}", sql);
+        assert_eq!(err.to_string(), format!("Unsupported join: {}", reason));
+    }
+}
+
+#[test]
+fn test_zset_score_range_both_bounds() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let lowers = [(">", "(100"), (">=", "100")];
+    let uppers = [("<", "(200"), ("<=", "200")];
+
+    // Every pairing of a lower and an upper bound, in either order and with the key first,
+    // between, or last
+    for (lower_op, min) in lowers {
+        for (upper_op, max) in uppers {
+            let lower = format!("score {} 100", lower_op);
+            let upper = format!("score {} 200", upper_op);
+            let key = "key = 'g'".to_string();
+            let orders = [
+                [&key, &lower, &upper], [&key, &upper, &lower],
+                [&lower, &key, &upper], [&upper, &key, &lower],
+                [&lower, &upper, &key], [&upper, &lower, &key],
+            ];
+            for conjuncts in orders {
+                let condition = conjuncts.map(String::as_str).join(" AND ");
+                let expected = format!("g {} {}", min, max);
+                assert_eq!(
+                    transformer.transform(&format!("SELECT * FROM lb__zset WHERE {}", condition)).unwrap(),
+                    format!("ZRANGEBYSCORE {}", expected), "{}", condition
+                );
+                assert_eq!(
+                    transformer.transform(&format!("SELECT member FROM lb__zset WHERE {}", condition)).unwrap(),
+                    format!("ZRANGEBYSCORE {}", expected), "{}", condition
+                );
+                assert_eq!(
+                    transformer.transform(&format!("SELECT COUNT(*) FROM lb__zset WHERE {}", condition)).unwrap(),
+                    format!("ZCOUNT {}", expected), "{}", condition
+                );
+                assert_eq!(
+                    transformer.transform(&format!("DELETE FROM lb__zset WHERE {}", condition)).unwrap(),
+                    format!("ZREMRANGEBYSCORE {}", expected), "{}", condition
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_zset_score_range_tighter_bound_wins() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND score > 1 AND score >= 5 AND score <= 9 AND score < 20").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g 5 9");
+
+    // On a tie the strict bound is tighter
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND score >= 5 AND score > 5").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g (5 +inf");
+
+    // Test a BETWEEN intersects with the other bounds
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND score BETWEEN 1 AND 10 AND score < 5").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g 1 (5");
+    let result = transformer.transform("SELECT COUNT(*) FROM lb__zset WHERE key = 'g' AND score > 3 AND score BETWEEN 1 AND 10").unwrap();
+    assert_eq!(result, "ZCOUNT g (3 10");
+    
+    // A single comparison still counts with ZCOUNT
+    let result = transformer.transform("SELECT COUNT(*) FROM lb__zset WHERE key = 'g' AND score <= 9").unwrap();
+    assert_eq!(result, "ZCOUNT g -inf 9");
+}
+
+#[test]
+fn test_zset_member_pattern() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    let strict = SqlToRedisTransformer::with_options(TransformOptions::default().strict()).unwrap();
+    
+    // A lone member pattern scans the sorted set with ZSCAN MATCH
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:10%'").unwrap();
+    assert_eq!(result, "ZSCAN lb 0 MATCH user:10*");
+    let result = strict.transform("SELECT * FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:%' LIMIT 50").unwrap();
+    assert_eq!(result, "ZSCAN lb 0 MATCH user:* COUNT 50");
+    
+    // Beside a score range, the pattern and the range are both filtered in Lua
+    let sql = "SELECT * FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:%' AND score > 5";
+    assert_eq!(transformer.matched_rule(sql).unwrap(), "member_like_filter");
+    assert!(strict.transform(sql).is_ok());
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert!(command.args[0].contains("redis.call('ZRANGE', KEYS[1], 0, -1, 'WITHSCORES')"));
+    assert!(command.args[0].contains("if (like(field(ARGV[1]), ARGV[2], false) and compare(field(ARGV[3]), '>', ARGV[4])) then"));
+    assert_eq!(&command.args[1..], ["1", "lb", "member", "^user:.*$", "score", "5"]);
+    
+    // ORDER BY and LIMIT are applied in the script rather than dropped
+    let sql = "SELECT * FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:%' AND score > 5 ORDER BY score DESC LIMIT 3";
+    let (_, warnings) = transformer.transform_with_report(sql).unwrap();
+    assert!(warnings.iter().all(|warning| warning.kind != WarningKind::DroppedClause));
+    assert!(strict.transform(sql).is_ok());
+    let command = transformer.transform_to_command(sql).unwrap();
+    assert!(command.args[0].contains("table.sort(order, function(i, j)"));
+    assert_eq!(&command.args[1..], ["1", "lb", "member", "^user:.*$", "score", "5", "score", "3"]);
+    // An ORDER BY expression can't be sorted on, so no rule honors it
+    assert!(strict.transform("SELECT * FROM lb__zset WHERE key = 'lb' AND member LIKE 'user:%' AND score > 5 ORDER BY score + 1").is_err());
+    
+    // Lua pattern magic characters in the LIKE pattern are escaped
+    let command = transformer.transform_to_command("SELECT member FROM lb__zset WHERE key = 'lb' AND member LIKE 'a.b-%'").unwrap();
+    assert_eq!(&command.args[1..], ["1", "lb", "member", "member", "^a%.b%-.*$", "member"]);
+    
+    // Filtered rows decode like the Lua fallback's
+    let reply = Reply::Array(vec![Reply::Array(vec![
+        Reply::bulk("member"), Reply::bulk("user:7"),
+        Reply::bulk("score"), Reply::bulk("9"),
+    ])]);
+    let rows = transformer.decode_reply(sql, &reply).unwrap().unwrap();
+    assert_eq!(rows, vec![vec![("member".to_string(), "user:7".to_string()), ("score".to_string(), "9".to_string())]]);
+}
+
+#[test]
+fn test_zset_score_range_reversed_and_nested() {
+    let transformer = SqlToRedisTransformer::new().unwrap();
+    
+    // Test the value may come first: `100 <= score` is `score >= 100`
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND 100 <= score AND score < 200").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g 100 (200");
+    let result = transformer.transform("SELECT COUNT(*) FROM lb__zset WHERE key = 'g' AND 200 > score AND 100 < score").unwrap();
+    assert_eq!(result, "ZCOUNT g (100 (200");
+    
+    // Test parenthesized conditions
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND (score >= 100 AND score < 200)").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g 100 (200");
+    let result = transformer.transform("SELECT * FROM lb__zset WHERE key = 'g' AND (score > 5)").unwrap();
+    assert_eq!(result, "ZRANGEBYSCORE g (5 +inf");
+    
+    // Test DELETE keeps both bounds, whichever side the value is on
+    let result = transformer.transform("DELETE FROM z__zset WHERE key = 'k' AND 100 <= score AND score < 200").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE k 100 (200");
+    let result = transformer.transform("DELETE FROM z__zset WHERE key = 'k' AND (score BETWEEN 100 AND 300) AND 200 > score").unwrap();
+    assert_eq!(result, "ZREMRANGEBYSCORE k 100 (200");
+}